@@ -3,37 +3,260 @@
 /// Fáze 1: OBSERVE only — loguje, neobchoduje
 
 use anyhow::{Context, Result};
-use logger::{EventLogger, ArbOpportunityEvent, now_iso};
+use esports_core::match_id::{normalize_team, team_names_equivalent, MatchId};
+use esports_core::{HttpTimeouts, Sport, UpcomingMatch};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use logger::{EventLogger, ArbOpportunityEvent, ArbRejectedEvent, MatchResolvedEvent, NearMissLog, OrderRequest, PaperSettlementEvent, RecentEvents, Venue, now_iso};
 use reqwest::Client;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, info, warn};
 
+mod paper;
+mod sx_numeric;
+mod venue_math;
+pub use paper::{append_paper_bet, PaperBet, PaperBetLine, PaperBook, PaperInbox, PaperSettlement, PAPER_BETS_INBOX};
+pub use sx_numeric::{parse_sx_prob, parse_wei_decimal, wei_to_decimal, SX_ODDS_DECIMALS, WEI_DECIMALS};
+pub use venue_math::{compute_azuro_edge, compute_sx_edge, sx_fill, sx_liquidity_usd, SxEdgeResult, VenueEdge, AZURO_SLIPPAGE_PENALTY, MIN_NET_EDGE};
+
+/// Výchozí floor pro pre-resolution arb (Pinnacle fair value vs trh), env ARB_MIN_EDGE_PCT v %.
+const DEFAULT_MIN_EDGE_PCT: f64 = 0.03;
+/// Výchozí floor pro oracle lag (vítěz známý, trh ještě neuzavřel), env ORACLE_LAG_MIN_EDGE_PCT v %.
+const DEFAULT_ORACLE_LAG_MIN_EDGE_PCT: f64 = 0.05;
+/// Výchozí strop net edge v eval_sxbet/eval_azuro, env ARB_MAX_EDGE_PCT v %.
+/// Stejně jako `max_disc > 40` v find_odds_anomalies: 40%+ edge = skoro vždy špatná data.
+const DEFAULT_MAX_EDGE_PCT: f64 = 0.40;
+/// PINNACLE_LINE starší než tohle už není fair value pro live value betting.
+const PINNACLE_LINE_MAX_AGE: Duration = Duration::from_secs(180);
+/// Kolik posledních PINNACLE_LINE z bufferu projít.
+const PINNACLE_LINE_SCAN: usize = 512;
+/// Simulovaná sázka pro live value fill (stejně jako v eval_sxbet).
+const LIVE_VALUE_BET_SIZE_USD: f64 = 100.0;
+/// Min. podíl simulované sázky, který musí SX orderbook pokrýt, aby edge byl actionable; env SX_MIN_FILL_RATIO.
+/// $3 objemu na $100 cíl dá "kurz" z drobného fillu, který ve skutečné velikosti nezrealizujeme.
+const DEFAULT_SX_MIN_FILL_RATIO: f64 = 0.8;
+/// Jak dlouho po `gameTime` brát SX market ještě jako otevřený — pak už ho orákulum reportuje.
+const SX_SETTLEMENT_WINDOW_SECS: i64 = 12 * 3600;
+/// Kolik nejbližších cachovaných SX marketů vypsat při missu v eval_sxbet.
+const SX_MISS_SUGGESTIONS: usize = 3;
+
+/// Druh příležitosti — každý má vlastní threshold, `source` v eventu a Telegram kategorii.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArbCategory {
+    /// Zápas ještě běží, fair value z Pinnacle
+    PreResolution,
+    /// Výsledek už známe (fair value = 1.0), edge je jen zpoždění orákula / orderbooku
+    OracleLag,
+}
+
+impl ArbCategory {
+    pub fn source(self) -> &'static str {
+        match self {
+            ArbCategory::PreResolution => "pinnacle_vs_polymarket",
+            ArbCategory::OracleLag => "oracle_lag",
+        }
+    }
+
+    fn alert_title(self) -> &'static str {
+        match self {
+            ArbCategory::PreResolution => "🚨 ARB EDGE",
+            ArbCategory::OracleLag => "⏱️ ORACLE LAG",
+        }
+    }
+}
+
+fn edge_floor_from_env(var: &str, default: f64) -> f64 {
+    std::env::var(var)
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|pct| *pct > 0.0)
+        .map(|pct| pct / 100.0)
+        .unwrap_or(default)
+}
+
+/// TELEGRAM_CHAT_ID může nést víc chatů oddělených čárkou; prázdné položky a duplicity se zahodí.
+pub fn parse_telegram_chat_ids(raw: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in raw.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        if !ids.iter().any(|known| known == id) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+/// Formát Telegram zprávy: Plain = čistý text, Html = parse_mode HTML bez náhledu odkazů (alert_bot).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelegramFormat {
+    Plain,
+    Html,
+}
+
+/// Pošle `text` do všech chatů souběžně; chyba jednoho chatu ostatní neblokuje.
+/// Vrací výsledek per chat (v pořadí `chat_ids`).
+pub async fn broadcast_telegram(
+    client: &Client,
+    api_url: &str,
+    bot_token: &str,
+    chat_ids: &[String],
+    text: &str,
+    format: TelegramFormat,
+) -> Vec<(String, Result<()>)> {
+    let url = format!("{}/bot{}/sendMessage", api_url.trim_end_matches('/'), bot_token);
+    let sends = chat_ids.iter().map(|chat_id| {
+        let url = url.clone();
+        async move {
+            let mut payload = json!({ "chat_id": chat_id, "text": text });
+            if format == TelegramFormat::Html {
+                payload["parse_mode"] = json!("HTML");
+                payload["disable_web_page_preview"] = json!(true);
+            }
+            let result = match client.post(&url).json(&payload).send().await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(anyhow::anyhow!("Telegram HTTP {}", resp.status())),
+                Err(e) => Err(anyhow::Error::from(e)),
+            };
+            if let Err(e) = &result {
+                warn!("Failed to send Telegram notification to chat {}: {}", chat_id, e);
+            }
+            (chat_id.clone(), result)
+        }
+    });
+    futures_util::future::join_all(sends).await
+}
+
+/// Výchozí počet souběžných /markets/active fetchů v background syncu.
+const DEFAULT_SX_SYNC_CONCURRENCY: usize = 4;
+/// Výchozí pauza mezi full refreshi SX cache.
+const DEFAULT_SX_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// Výchozí pauza slotu po každém fetchi. 4 sloty × 800 ms = max ~5 req/s na SX API, stejně jako
+/// dřívější sekvenční sync s 200 ms; zrychlení dává jen překryv latence requestů.
+const DEFAULT_SX_SYNC_REQUEST_GAP: Duration = Duration::from_millis(800);
+
+/// Background sync SX marketů: souběžnost lig, interval refreshe a pacing requestů.
+#[derive(Debug, Clone)]
+struct SxSyncConfig {
+    concurrency: usize,
+    interval:    Duration,
+    request_gap: Duration,
+    /// Opt-in floor likvidity (USD na orderbooku); Some = každý market stojí navíc /orders request
+    min_liquidity_usd: Option<f64>,
+}
+
+impl Default for SxSyncConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_SX_SYNC_CONCURRENCY,
+            interval:    DEFAULT_SX_SYNC_INTERVAL,
+            request_gap: DEFAULT_SX_SYNC_REQUEST_GAP,
+            min_liquidity_usd: None,
+        }
+    }
+}
+
+impl SxSyncConfig {
+    /// SX_SYNC_CONCURRENCY (0 / chybějící = default), SX_SYNC_INTERVAL_SECS, SX_SYNC_REQUEST_GAP_MS,
+    /// SX_SYNC_MIN_LIQUIDITY_USD (0 / chybějící = bez filtru).
+    fn from_env() -> Self {
+        let parse = |var: &str| std::env::var(var).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            concurrency: parse("SX_SYNC_CONCURRENCY").filter(|n| *n > 0).map(|n| n as usize).unwrap_or(defaults.concurrency),
+            interval:    parse("SX_SYNC_INTERVAL_SECS").filter(|n| *n > 0).map(Duration::from_secs).unwrap_or(defaults.interval),
+            request_gap: parse("SX_SYNC_REQUEST_GAP_MS").map(Duration::from_millis).unwrap_or(defaults.request_gap),
+            min_liquidity_usd: std::env::var("SX_SYNC_MIN_LIQUIDITY_USD").ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|usd| *usd > 0.0),
+        }
+    }
+}
+
 pub struct ArbDetector {
     logger:       EventLogger,
     observe_only: bool,
+    /// Floor pro pre-resolution arb (zlomek, 0.03 = 3 %)
     min_edge_pct: f64,
+    /// Floor pro oracle-lag příležitosti po vyhodnocení zápasu
+    oracle_lag_min_edge_pct: f64,
+    /// Strop net edge (zlomek) — nad ním ARB_REJECTED místo akce
+    max_edge_pct: f64,
+    /// NEAR_MISS_LOG: edge těsně pod `min_edge_pct` → NEAR_MISS event
+    near_miss:    Option<NearMissLog>,
     client:       Client,
     telegram_bot_token: String,
-    telegram_chat_id: String,
-    // Mapa "home_vs_away" -> (marketHash, sportXeventId)
-    active_markets: Arc<RwLock<HashMap<String, (String, String)>>>,
+    /// Cílové chaty alertů (TELEGRAM_CHAT_ID, víc oddělených čárkou = broadcast)
+    telegram_chat_ids: Vec<String>,
+    /// Base URL Telegram Bot API (testy: mock server)
+    telegram_api_url: String,
+    // MatchId -> SX market; kanonické pořadí týmů → stačí jeden záznam na zápas
+    active_markets: Arc<RwLock<HashMap<MatchId, SxMarket>>>,
+    /// sportXeventId -> MatchId (SX strana, plní `spawn_sx_market_sync`)
+    sx_event_ids:   Arc<RwLock<HashMap<String, MatchId>>>,
+    /// Nativní ID zápasu ze zdroje výsledku (Riot, HLTV) -> MatchId; join podle ID má přednost před párováním jmen
+    source_match_ids: RwLock<HashMap<String, MatchId>>,
+    /// Base URL SX Bet API (env SX_API_URL)
+    sx_api_url:     String,
+    /// Background sync a prewarm SX cache (SX_SYNC_*, vč. floor likvidity)
+    sx_sync:        SxSyncConfig,
+    arbitrum_rpc_url: String,
+    polygon_rpc_url:  String,
+    /// Gas cache per chain: (čas fetch, USD). Mutex drží i během fetch → souběžné evaly čekají na jeden RPC call.
+    gas_cache:     HashMap<&'static str, Mutex<Option<(Instant, f64)>>>,
+    gas_cache_ttl: Duration,
+    /// SX API timeout (klient) a těsný timeout pro /orders v eval_sxbet
+    timeouts:      HttpTimeouts,
+    /// Min. pokrytí cílové velikosti orderbookem (zlomek), jinak nedostatečná likvidita
+    sx_min_fill_ratio: f64,
+    log_dir:       std::path::PathBuf,
+    /// PAPER_SETTLEMENT=1 (jen observe_only): dry-run příležitosti jako paper sázky, vyhodnocené MATCH_RESOLVED
+    paper:         Option<std::sync::Mutex<PaperBook>>,
+    /// Inbox dry-run sázek jiných procesů (alert-bot), jen se zapnutým `paper`
+    paper_inbox:   Option<std::sync::Mutex<PaperInbox>>,
+}
+
+/// Nacachovaný SX moneyline market. Klíč cache má týmy seřazené, pořadí outcomů drží
+/// `outcome_one` / `outcome_two` (teamOneName / teamTwoName z `/markets/active`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SxMarket {
+    market_hash: String,
+    event_id:    String,
+    outcome_one: String,
+    outcome_two: String,
+}
+
+impl SxMarket {
+    fn new(market_hash: &str, event_id: &str, outcome_one: &str, outcome_two: &str) -> Self {
+        Self {
+            market_hash: market_hash.to_string(),
+            event_id:    event_id.to_string(),
+            outcome_one: outcome_one.to_string(),
+            outcome_two: outcome_two.to_string(),
+        }
+    }
+
+    /// Název SX outcomu pro tým ze zdroje (fuzzy shoda jmen); None = nejde jednoznačně spárovat.
+    fn side_of(&self, team: &str) -> Option<&str> {
+        match (team_names_equivalent(team, &self.outcome_one), team_names_equivalent(team, &self.outcome_two)) {
+            (true, false) => Some(&self.outcome_one),
+            (false, true) => Some(&self.outcome_two),
+            _ => None,
+        }
+    }
+
+    /// "outcome_one" / "outcome_two" pro order na SX outcome `side` (výstup `side_of`).
+    fn outcome_id(&self, side: &str) -> &'static str {
+        if side == self.outcome_one { "outcome_one" } else { "outcome_two" }
+    }
 }
 
 impl ArbDetector {
     pub fn new(log_dir: impl Into<std::path::PathBuf>, observe_only: bool) -> Self {
-        let detector = Self {
-            logger:       EventLogger::new(log_dir),
-            observe_only,
-            min_edge_pct: 0.03, // 3% minimum edge
-            client:       Client::builder().timeout(Duration::from_secs(5)).build().unwrap_or_else(|_| Client::new()),
-            telegram_bot_token: std::env::var("TELEGRAM_BOT_TOKEN").unwrap_or_else(|_| "8125729036:AAH_rDK4i-xmWlN2OttWLYxN1Wq_vI4Nvv8".to_string()),
-            telegram_chat_id: std::env::var("TELEGRAM_CHAT_ID").unwrap_or_else(|_| "6458129071".to_string()),
-            active_markets: Arc::new(RwLock::new(HashMap::new())),
-        };
+        let detector = Self::build(log_dir, observe_only);
 
         // Spustime background sync pro SX Bet markety
         detector.spawn_sx_market_sync();
@@ -41,96 +264,304 @@ impl ArbDetector {
         detector
     }
 
-    /// Pomocná funkce na normalizaci názvů týmů (jen malá alfanumerika) pro lepší cache hits.
-    fn normalize_team_name(name: &str) -> String {
-        name.to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric())
-            .collect()
+    /// Sestaví detektor bez background tasků (sdíleno s testy).
+    fn build(log_dir: impl Into<std::path::PathBuf>, observe_only: bool) -> Self {
+        let log_dir = log_dir.into();
+        let gas_cache_ttl_secs = std::env::var("GAS_FEE_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30);
+        let timeouts = HttpTimeouts::from_env();
+
+        Self {
+            logger:       EventLogger::new(&log_dir).with_webhook_from_env(),
+            observe_only,
+            min_edge_pct: edge_floor_from_env("ARB_MIN_EDGE_PCT", DEFAULT_MIN_EDGE_PCT),
+            oracle_lag_min_edge_pct: edge_floor_from_env("ORACLE_LAG_MIN_EDGE_PCT", DEFAULT_ORACLE_LAG_MIN_EDGE_PCT),
+            max_edge_pct: edge_floor_from_env("ARB_MAX_EDGE_PCT", DEFAULT_MAX_EDGE_PCT),
+            near_miss:    NearMissLog::from_env(&log_dir),
+            client:       Client::builder().timeout(timeouts.sx_api).build().unwrap_or_else(|_| Client::new()),
+            telegram_bot_token: std::env::var("TELEGRAM_BOT_TOKEN").unwrap_or_else(|_| "8125729036:AAH_rDK4i-xmWlN2OttWLYxN1Wq_vI4Nvv8".to_string()),
+            telegram_chat_ids: parse_telegram_chat_ids(&std::env::var("TELEGRAM_CHAT_ID").unwrap_or_else(|_| "6458129071".to_string())),
+            telegram_api_url: "https://api.telegram.org".to_string(),
+            active_markets: Arc::new(RwLock::new(HashMap::new())),
+            sx_event_ids:   Arc::new(RwLock::new(HashMap::new())),
+            source_match_ids: RwLock::new(HashMap::new()),
+            sx_api_url:     std::env::var("SX_API_URL").unwrap_or_else(|_| "https://api.sx.bet".to_string()),
+            sx_sync:        SxSyncConfig::from_env(),
+            arbitrum_rpc_url: std::env::var("ARBITRUM_RPC_URL").unwrap_or_else(|_| "https://arb1.arbitrum.io/rpc".to_string()),
+            polygon_rpc_url:  std::env::var("POLYGON_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com/".to_string()),
+            gas_cache:        HashMap::from([("arbitrum", Mutex::new(None)), ("polygon", Mutex::new(None))]),
+            gas_cache_ttl:    Duration::from_secs(gas_cache_ttl_secs),
+            timeouts,
+            sx_min_fill_ratio: std::env::var("SX_MIN_FILL_RATIO")
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|r| *r > 0.0 && *r <= 1.0)
+                .unwrap_or(DEFAULT_SX_MIN_FILL_RATIO),
+            log_dir,
+            paper:         None,
+            paper_inbox:   None,
+        }
+        .with_paper_settlement(std::env::var("PAPER_SETTLEMENT").is_ok_and(|v| matches!(v.trim(), "1" | "true")))
+    }
+
+    /// Paper settlement (PAPER_SETTLEMENT): dry-run sázky z oracle lag / live value + inbox jiných procesů.
+    /// Mimo observe_only se nezapne — skutečné sázky mají skutečný P&L.
+    pub fn with_paper_settlement(mut self, enabled: bool) -> Self {
+        if !(enabled && self.observe_only) {
+            self.paper = None;
+            self.paper_inbox = None;
+            return self;
+        }
+        let mut inbox = PaperInbox::new(&self.log_dir);
+        let book = PaperBook::restore(&self.log_dir, inbox.read_new());
+        let (settled, won) = book.record();
+        if settled > 0 || book.open_bets() > 0 {
+            info!("📝 PAPER restored: {} open bets, P&L {:+.2}$ ({}/{} won)", book.open_bets(), book.pnl_usd(), won, settled);
+        }
+        self.paper = Some(std::sync::Mutex::new(book));
+        self.paper_inbox = Some(std::sync::Mutex::new(inbox));
+        self
+    }
+
+    /// SX Bet esports ligy nesou hru jen v labelu ("CS2 - IEM Cologne", "LoL - LCK").
+    fn sx_league_sport(league_label: &str) -> Option<Sport> {
+        let game = league_label.split(" - ").next().unwrap_or(league_label);
+        Sport::from_label(game)
+    }
+
+    /// Aktivní esport ligy (sportId = 9) z `/leagues` se hrou rozpoznanou z labelu.
+    fn parse_sx_esport_leagues(data: &serde_json::Value) -> Vec<(u64, Sport)> {
+        let mut leagues_out = Vec::new();
+        if let Some(leagues) = data.pointer("/data").and_then(|d| d.as_array()) {
+            for l in leagues {
+                let is_active = l.pointer("/active").and_then(|a| a.as_bool()).unwrap_or(false);
+                let is_esports = l.pointer("/sportId").and_then(|s| s.as_u64()).unwrap_or(0) == 9;
+                if is_active && is_esports {
+                    if let Some(l_id) = l.pointer("/leagueId").and_then(|id| id.as_u64()) {
+                        let label = l.pointer("/label").and_then(|s| s.as_str()).unwrap_or("");
+                        match Self::sx_league_sport(label) {
+                            Some(sport) => leagues_out.push((l_id, sport)),
+                            // Bez sportu nejde postavit MatchId → liga vypadne z cache; musí být vidět v logu
+                            None => warn!("SX league {} '{}': hra z labelu nerozpoznána, markety ligy přeskakuji", l_id, label),
+                        }
+                    }
+                }
+            }
+        }
+        leagues_out
+    }
+
+    /// MoneyLine markety (type 52) z `/markets/active` → (MatchId, SxMarket).
+    fn parse_sx_moneylines(data: &serde_json::Value, sport: Sport) -> Vec<(MatchId, SxMarket)> {
+        let mut out = Vec::new();
+        if let Some(markets) = data.pointer("/data/markets").and_then(|m| m.as_array()) {
+            for m in markets {
+                // Chceme jen MoneyLine sázky = type: 52
+                let type_id = m.pointer("/type").and_then(|t| t.as_u64()).unwrap_or(0);
+                if type_id == 52 {
+                    let t1_raw = m.pointer("/teamOneName").and_then(|s| s.as_str()).unwrap_or("");
+                    let t2_raw = m.pointer("/teamTwoName").and_then(|s| s.as_str()).unwrap_or("");
+
+                    let id = MatchId::new(sport, t1_raw, t2_raw);
+
+                    let hash = m.pointer("/marketHash").and_then(|s| s.as_str()).unwrap_or("");
+                    let event_id = m.pointer("/sportXeventId").and_then(|s| s.as_str()).unwrap_or("");
+
+                    if !id.team_a.is_empty() && !id.team_b.is_empty() && !hash.is_empty() {
+                        out.push((id, SxMarket::new(hash, event_id, t1_raw, t2_raw)));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Propis marketů do cache + side table sportXeventId → MatchId. `below_floor` = dříve cachované
+    /// zápasy, jejichž likvidita klesla pod floor — vypadnou z cache i ze side table.
+    async fn store_sx_markets(
+        cache: &RwLock<HashMap<MatchId, SxMarket>>,
+        event_ids: &RwLock<HashMap<String, MatchId>>,
+        entries: impl IntoIterator<Item = (MatchId, SxMarket)>,
+        below_floor: &[MatchId],
+    ) {
+        let mut lock = cache.write().await;
+        let mut ids = event_ids.write().await;
+        for id in below_floor {
+            if lock.remove(id).is_some() {
+                debug!("SX cache: {} pod floor likvidity, vyřazuji", id);
+            }
+        }
+        ids.retain(|_, id| !below_floor.contains(id));
+        for (k, v) in entries {
+            if !v.event_id.is_empty() {
+                ids.insert(v.event_id.clone(), k.clone());
+            }
+            lock.insert(k, v);
+        }
     }
 
     /// Background task pro udržování superrychle cache aktivních trhů na SX Bet
     fn spawn_sx_market_sync(&self) {
         let client = self.client.clone();
         let cache = Arc::clone(&self.active_markets);
+        let event_ids = Arc::clone(&self.sx_event_ids);
+        let sx_api_url = self.sx_api_url.clone();
+        let config = self.sx_sync.clone();
+        info!("SX market sync: concurrency {}, interval {}s, request gap {}ms, min liquidity {}",
+            config.concurrency, config.interval.as_secs(), config.request_gap.as_millis(),
+            config.min_liquidity_usd.map(|usd| format!("${:.0}", usd)).unwrap_or_else(|| "off".to_string()));
 
         tokio::spawn(async move {
             loop {
-                let mut new_cache = HashMap::new();
-                
-                // 1. Získej všechny aktivní esport ligy ze SX Bet (sportId = 9)
-                let mut active_esport_leagues = Vec::new();
-                if let Ok(l_resp) = client.get("https://api.sx.bet/leagues").send().await {
-                    if let Ok(l_data) = l_resp.json::<serde_json::Value>().await {
-                        if let Some(leagues) = l_data.pointer("/data").and_then(|d| d.as_array()) {
-                            for l in leagues {
-                                let is_active = l.pointer("/active").and_then(|a| a.as_bool()).unwrap_or(false);
-                                let is_esports = l.pointer("/sportId").and_then(|s| s.as_u64()).unwrap_or(0) == 9;
-                                if is_active && is_esports {
-                                    if let Some(l_id) = l.pointer("/leagueId").and_then(|id| id.as_u64()) {
-                                        active_esport_leagues.push(l_id);
-                                    }
-                                }
-                            }
-                        }
+                let started = Instant::now();
+                let leagues = Self::sync_sx_markets_once(&client, &sx_api_url, &cache, &event_ids, &config).await;
+                let total_items = cache.read().await.len();
+                info!("Background sync completed in {:.1}s: {} leagues, cached {} mapped SX Bet moneyline matches.",
+                    started.elapsed().as_secs_f64(), leagues, total_items);
+
+                sleep(config.interval).await;
+            }
+        });
+    }
+
+    /// Jeden full refresh: /leagues → /markets/active pro každou esport ligu, max `concurrency` souběžně.
+    /// Každý slot po fetchi čeká `request_gap` (SX rate limit). Vrací počet lig.
+    async fn sync_sx_markets_once(
+        client: &Client,
+        sx_api_url: &str,
+        cache: &RwLock<HashMap<MatchId, SxMarket>>,
+        event_ids: &RwLock<HashMap<String, MatchId>>,
+        config: &SxSyncConfig,
+    ) -> usize {
+        // 1. Získej všechny aktivní esport ligy ze SX Bet (sportId = 9)
+        let mut active_esport_leagues = Vec::new();
+        if let Ok(l_resp) = client.get(format!("{}/leagues", sx_api_url)).send().await {
+            if let Ok(l_data) = l_resp.json::<serde_json::Value>().await {
+                active_esport_leagues = Self::parse_sx_esport_leagues(&l_data);
+            }
+        }
+
+        info!("Background sync: Found {} active SX Bet e-sports leagues.", active_esport_leagues.len());
+
+        // 2. Pro každou ligu získej aktivní markety, průběžně propisuj do cache
+        futures_util::stream::iter(active_esport_leagues.iter())
+            .for_each_concurrent(config.concurrency, |(league_id, sport)| async move {
+                let url = format!("{}/markets/active?leagueId={}", sx_api_url, league_id);
+                if let Ok(resp) = client.get(&url).send().await {
+                    if let Ok(data) = resp.json::<serde_json::Value>().await {
+                        let markets = Self::parse_sx_moneylines(&data, *sport);
+                        let (markets, below_floor) =
+                            Self::filter_liquid_markets(client, sx_api_url, markets, config).await;
+                        Self::store_sx_markets(cache, event_ids, markets, &below_floor).await;
                     }
                 }
+                // Zvolni slot, abychom nezaspamovali SX Bet API
+                sleep(config.request_gap).await;
+            })
+            .await;
 
-                info!("Background sync: Found {} active SX Bet e-sports leagues.", active_esport_leagues.len());
-
-                // 2. Pro každou ligu získej aktivní markety
-                for league_id in active_esport_leagues.iter() {
-                    let url = format!("https://api.sx.bet/markets/active?leagueId={}", league_id);
-                    if let Ok(resp) = client.get(&url).send().await {
-                        if let Ok(data) = resp.json::<serde_json::Value>().await {
-                            if let Some(markets) = data.pointer("/data/markets").and_then(|m| m.as_array()) {
-                                for m in markets {
-                                    // Chceme jen MoneyLine sázky = type: 52
-                                    let type_id = m.pointer("/type").and_then(|t| t.as_u64()).unwrap_or(0);
-                                    if type_id == 52 {
-                                        let t1_raw = m.pointer("/teamOneName").and_then(|s| s.as_str()).unwrap_or("");
-                                        let t2_raw = m.pointer("/teamTwoName").and_then(|s| s.as_str()).unwrap_or("");
-                                        
-                                        let t1 = Self::normalize_team_name(t1_raw);
-                                        let t2 = Self::normalize_team_name(t2_raw);
-                                        
-                                        let hash = m.pointer("/marketHash").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                        let event_id = m.pointer("/sportXeventId").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                        
-                                        if !t1.is_empty() && !t2.is_empty() && !hash.is_empty() {
-                                            new_cache.insert(format!("{}_vs_{}", t1, t2), (hash.clone(), event_id.clone()));
-                                            new_cache.insert(format!("{}_vs_{}", t2, t1), (hash, event_id)); // pro oba smery
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    
-                    // Bezprostřední propis do cache
-                    {
-                        let mut lock = cache.write().await;
-                        for (k, v) in new_cache.drain() {
-                            lock.insert(k, v);
-                        }
-                    }
+        active_esport_leagues.len()
+    }
 
-                    // Zvolni, abychom nezaspamovali SX Bet API
-                    sleep(Duration::from_millis(200)).await;
+    /// Rozdělí markety na ty s aspoň `min_usd` zbývajícího objemu na orderbooku (oba outcomy) a MatchId
+    /// těch pod floor. Market, jehož /orders selže, zůstává — výpadek SX API nesmí vyprázdnit cache.
+    /// `min_liquidity_usd` None = bez filtru a bez /orders requestů. Před každým /orders se čeká
+    /// `request_gap`, stejně jako slot syncu po fetchi lig (SX rate limit).
+    async fn filter_liquid_markets(
+        client: &Client,
+        sx_api_url: &str,
+        markets: Vec<(MatchId, SxMarket)>,
+        config: &SxSyncConfig,
+    ) -> (Vec<(MatchId, SxMarket)>, Vec<MatchId>) {
+        let Some(min_usd) = config.min_liquidity_usd else {
+            return (markets, Vec::new());
+        };
+        let mut liquid = Vec::with_capacity(markets.len());
+        let mut below_floor = Vec::new();
+        for (id, market) in markets {
+            sleep(config.request_gap).await;
+            let market_hash = &market.market_hash;
+            let url = format!("{}/orders?marketHash={}", sx_api_url, market_hash);
+            let orders = match client.get(&url).send().await {
+                Ok(resp) => resp.json::<serde_json::Value>().await.ok(),
+                Err(_) => None,
+            };
+            match orders.map(|orders| sx_liquidity_usd(&orders)) {
+                Some(usd) if usd < min_usd => {
+                    debug!("SX sync: {} ({}) má jen ${:.2} likvidity < ${:.0}, necachuji", id, market_hash, usd, min_usd);
+                    below_floor.push(id);
+                    continue;
                 }
+                None => debug!("SX sync: /orders pro {} selhal, cachuji bez kontroly likvidity", market_hash),
+                Some(_) => {}
+            }
+            liquid.push((id, market));
+        }
+        (liquid, below_floor)
+    }
+
+    /// Předehřátí cache pro zápasy z rozpisu (LoL getSchedule): ligy jejich sportu se stáhnou hned,
+    /// ne až v dalším kole background syncu. Riot ID z rozpisu se mapuje do side table, takže
+    /// pozdější MATCH_RESOLVED se stejným `source_match_id` trefí market bez párování jmen.
+    /// Vrací počet nově nacachovaných zápasů.
+    pub async fn prewarm_markets(&self, upcoming: &[UpcomingMatch]) -> usize {
+        let missing: Vec<&UpcomingMatch> = {
+            let cache = self.active_markets.read().await;
+            upcoming.iter().filter(|m| !cache.contains_key(&m.id)).collect()
+        };
+        self.record_source_ids(upcoming).await;
+        if missing.is_empty() {
+            return 0;
+        }
 
-                let total_items = cache.read().await.len() / 2;
-                info!("Background sync completed: Cached {} mapped SX Bet moneyline matches.", total_items);
-                
-                // Osvěžíme za minutu
-                sleep(Duration::from_secs(60)).await; 
+        let leagues = match self.client.get(format!("{}/leagues", self.sx_api_url)).send().await {
+            Ok(resp) => resp.json::<serde_json::Value>().await
+                .map(|data| Self::parse_sx_esport_leagues(&data))
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!("SX prewarm: /leagues failed: {}", e);
+                return 0;
             }
-        });
+        };
+
+        let mut warmed = 0;
+        for (league_id, sport) in leagues {
+            if !missing.iter().any(|m| m.id.sport == sport) {
+                continue;
+            }
+            let url = format!("{}/markets/active?leagueId={}", self.sx_api_url, league_id);
+            let Ok(resp) = self.client.get(&url).send().await else { continue };
+            let Ok(data) = resp.json::<serde_json::Value>().await else { continue };
+            let hits: Vec<_> = Self::parse_sx_moneylines(&data, sport)
+                .into_iter()
+                .filter(|(id, _)| missing.iter().any(|m| &m.id == id))
+                .collect();
+            let (hits, below_floor) =
+                Self::filter_liquid_markets(&self.client, &self.sx_api_url, hits, &self.sx_sync).await;
+            warmed += hits.len();
+            Self::store_sx_markets(&self.active_markets, &self.sx_event_ids, hits, &below_floor).await;
+        }
+
+        info!("🔥 SX prewarm: {}/{} nadcházejících zápasů nacachováno", warmed, missing.len());
+        warmed
+    }
+
+    /// ID zdroje z rozpisu → MatchId do side table (join při resolved podle `source_match_id`).
+    async fn record_source_ids(&self, upcoming: &[UpcomingMatch]) {
+        let mut ids = self.source_match_ids.write().await;
+        for m in upcoming {
+            if let Some(source_id) = &m.source_match_id {
+                ids.insert(source_id.clone(), m.id.clone());
+            }
+        }
     }
 
     /// Porovnej Pinnacle implied prob vs Polymarket price
     /// pinnacle_prob: 0.0–1.0 (fair value bez vigu)
     /// polymarket_price: 0.0–1.0 (YES cena na CLOB)
+    /// Vrací true, pokud edge prošel floorem a ARB_OPPORTUNITY se publikovala.
     pub fn evaluate_pinnacle_vs_polymarket(
         &self,
         home:             &str,
@@ -139,62 +570,106 @@ impl ArbDetector {
         pinnacle_prob:    f64,  // fair value
         polymarket_price: f64,  // aktuální tržní cena
         condition_id:     &str,
-    ) {
+    ) -> bool {
         // Edge = fair value - market price
         // Pokud Polymarket podhodnotí (cena < fair value) → edge na BUY
         let edge = pinnacle_prob - polymarket_price;
 
         if edge < self.min_edge_pct {
-            return; // pod threshold → ticho
+            if let Some(near_miss) = &self.near_miss {
+                near_miss.record(
+                    ArbCategory::PreResolution.source(),
+                    &format!("{}_vs_{}", home, away),
+                    sport,
+                    edge * 100.0,
+                    self.min_edge_pct * 100.0,
+                    "below_min_edge",
+                );
+            }
+            return false; // pod threshold → ticho
         }
 
-        let action = if self.observe_only { "OBSERVE" } else { "BUY" };
+        let ev = self.opportunity_event(ArbCategory::PreResolution, home, away, sport, pinnacle_prob, polymarket_price);
+        self.publish_opportunity(ArbCategory::PreResolution, &ev, condition_id);
+        true
+    }
 
-        let ev = ArbOpportunityEvent {
+    /// Vítěz už je známý → fair value 1.0; "edge" je jen oracle lag trhu, který ještě neuzavřel.
+    /// Vlastní threshold (ORACLE_LAG_MIN_EDGE_PCT), aby každý vyhodnocený zápas se zbytkem orderbooku nealertoval.
+    pub fn evaluate_oracle_lag(&self, home: &str, away: &str, sport: &str, market_price: f64, market_id: &str) {
+        self.evaluate_oracle_lag_order(home, away, sport, market_price, market_id, None);
+    }
+
+    /// Jako `evaluate_oracle_lag`, navíc přiloží k eventu `OrderRequest`, který by executor umístil.
+    /// Vrací true, pokud edge prošel floorem a ARB_OPPORTUNITY se publikovala.
+    fn evaluate_oracle_lag_order(&self, home: &str, away: &str, sport: &str, market_price: f64, market_id: &str, order: Option<OrderRequest>) -> bool {
+        let edge = 1.0 - market_price;
+        if edge < self.oracle_lag_min_edge_pct {
+            debug!("{} vs {} — oracle lag {:.1}% pod floor {:.1}% ({})",
+                home, away, edge * 100.0, self.oracle_lag_min_edge_pct * 100.0, market_id);
+            return false;
+        }
+        let mut ev = self.opportunity_event(ArbCategory::OracleLag, home, away, sport, 1.0, market_price);
+        ev.order = order;
+        self.publish_opportunity(ArbCategory::OracleLag, &ev, market_id);
+        true
+    }
+
+    fn opportunity_event(
+        &self,
+        category:         ArbCategory,
+        home:             &str,
+        away:             &str,
+        sport:            &str,
+        fair_prob:        f64,
+        market_price:     f64,
+    ) -> ArbOpportunityEvent {
+        let action = if self.observe_only { "OBSERVE" } else { "BUY" };
+        ArbOpportunityEvent {
             ts:               now_iso(),
             event:            "ARB_OPPORTUNITY",
-            source:           "pinnacle_vs_polymarket".to_string(),
+            source:           category.source().to_string(),
             home:             home.to_string(),
             away:             away.to_string(),
             sport:            sport.to_string(),
-            edge_pct:         edge,
-            pinnacle_prob,
-            polymarket_price,
+            edge_pct:         fair_prob - market_price,
+            pinnacle_prob:    fair_prob,
+            polymarket_price: market_price,
             action:           action.to_string(),
-        };
+            order:            None,
+        }
+    }
 
+    /// Zapíše ARB_OPPORTUNITY a pošle Telegram alert s kategorií.
+    fn publish_opportunity(&self, category: ArbCategory, ev: &ArbOpportunityEvent, condition_id: &str) {
         info!(
-            edge = format!("{:.1}%", edge * 100.0),
-            pinnacle_prob = format!("{:.2}", pinnacle_prob),
-            polymarket   = format!("{:.2}", polymarket_price),
-            "{} vs {} — edge found (Condition: {})",
-            home, away, condition_id
+            edge = format!("{:.1}%", ev.edge_pct * 100.0),
+            pinnacle_prob = format!("{:.2}", ev.pinnacle_prob),
+            polymarket   = format!("{:.2}", ev.polymarket_price),
+            "{} vs {} — {} found (Condition: {})",
+            ev.home, ev.away, category.source(), condition_id
         );
 
-        let _ = self.logger.log(&ev);
+        let _ = self.logger.log(ev);
 
         // Telegram Notification
         let bot_token = self.telegram_bot_token.clone();
-        let chat_id = self.telegram_chat_id.clone();
+        let chat_ids = self.telegram_chat_ids.clone();
+        let api_url = self.telegram_api_url.clone();
         let client = self.client.clone();
-        let h = home.to_string();
-        let a = away.to_string();
-        
-        if !bot_token.is_empty() && !chat_id.is_empty() {
-            let decimal_odds = 1.0 / polymarket_price;
+
+        if !bot_token.is_empty() && !chat_ids.is_empty() {
+            let decimal_odds = 1.0 / ev.polymarket_price;
             let msg = format!(
-                "🚨 EDGE {:.1}% se našla pro zápas {} vs {}!\n\nVýhra by byla {:.2}x.\nFair Prob: {:.2} vs SX Prob: {:.2}", 
-                edge * 100.0, h, a, decimal_odds, pinnacle_prob, polymarket_price
+                "{} {:.1}% pro zápas {} vs {}!\n\nVýhra by byla {:.2}x.\nFair Prob: {:.2} vs SX Prob: {:.2}",
+                category.alert_title(), ev.edge_pct * 100.0, ev.home, ev.away, decimal_odds, ev.pinnacle_prob, ev.polymarket_price
             );
-            
+
             tokio::spawn(async move {
-                let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
-                let payload = serde_json::json!({
-                    "chat_id": chat_id,
-                    "text": msg,
-                });
-                if let Err(e) = client.post(&url).json(&payload).send().await {
-                    warn!("Failed to send Telegram notification: {}", e);
+                let results = broadcast_telegram(&client, &api_url, &bot_token, &chat_ids, &msg, TelegramFormat::Plain).await;
+                let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+                if failed > 0 {
+                    warn!("Telegram broadcast: {}/{} chatů selhalo", failed, results.len());
                 }
             });
         }
@@ -202,12 +677,13 @@ impl ArbDetector {
 
     /// MULTI-BOOKIE FAN-OUT
     /// Asynchronně spouští evaluaci trhu pro všechny napojené burzy současně.
-    pub async fn evaluate_esports_match(&self, home: &str, away: &str, sport: &str, winner: &str) -> Result<()> {
+    /// `source_match_id` = nativní ID zápasu ze zdroje výsledku; když ho zná i side table, páruje se podle něj.
+    pub async fn evaluate_esports_match(&self, home: &str, away: &str, sport: &str, winner: &str, source_match_id: Option<&str>) -> Result<()> {
         info!("⚔️ MULTI-BOOKIE EVAL: {} vs {} ({}) → Winner: {}", home, away, sport, winner);
         let start = std::time::Instant::now();
 
         let (sx_res, azuro_res) = tokio::join!(
-            self.eval_sxbet(home, away, sport, winner),
+            self.eval_sxbet(home, away, sport, winner, source_match_id),
             self.eval_azuro(home, away, sport, winner)
         );
 
@@ -219,142 +695,404 @@ impl ArbDetector {
     }
 
     /// Privátní SX Bet evaluátor (Arbitrum)
-    async fn eval_sxbet(&self, home: &str, away: &str, sport: &str, winner: &str) -> Result<()> {
-        let t1 = Self::normalize_team_name(home);
-        let t2 = Self::normalize_team_name(away);
-        let key = format!("{}_vs_{}", t1, t2);
+    async fn eval_sxbet(&self, home: &str, away: &str, sport: &str, winner: &str, source_match_id: Option<&str>) -> Result<()> {
+        let t1 = normalize_team(home);
+        let t2 = normalize_team(away);
+        let id = MatchId::from_labels(sport, home, away);
+        let key = id.as_ref().map(MatchId::key).unwrap_or_else(|| format!("{}_vs_{}", t1, t2));
 
         let overall_start = std::time::Instant::now();
-        
-        let (market_hash, event_id) = {
-            let cache = self.active_markets.read().await;
-            
-            // Prohledame i substringove (pri castecne normalizaci) pokud exaktni match selze
-            let exact_match = cache.get(&key).cloned();
-            
-            if exact_match.is_none() {
-                // Pokusime se najit substring match v klicich (drazsi operace, ale match_resolved se nestava tak casto)
-                let partial_match = cache.keys().find(|k| k.contains(&t1) && k.contains(&t2));
-                if let Some(p_key) = partial_match {
-                     cache.get(p_key).cloned()
-                } else {
-                    None
-                }
+
+        let Some(market) = self.lookup_sx_market(id.as_ref(), &t1, &t2, source_match_id).await else {
+            let suggestions = match id.as_ref() {
+                Some(id) => Self::nearest_sx_markets(&*self.active_markets.read().await, id, SX_MISS_SUGGESTIONS)
+                    .into_iter()
+                    .map(|(cached, market_hash, score)| format!("{} ({:.2}, market {})", cached, score, market_hash))
+                    .collect::<Vec<_>>(),
+                None => Vec::new(),
+            };
+            if suggestions.is_empty() {
+                info!("No cached SX Bet market found for {} vs {} (key: {})", home, away, key);
             } else {
-                exact_match
+                info!("No cached SX Bet market found for {} vs {} (key: {}); nejbližší v cache: {}",
+                    home, away, key, suggestions.join(", "));
             }
-        }.unwrap_or((String::new(), String::new()));
-        
-        if market_hash.is_empty() {
-            info!("No cached SX Bet market found for {} vs {} (key: {})", home, away, key);
             return Ok(());
-        }
+        };
+        let market_hash = market.market_hash.clone();
 
         let cache_elapsed = overall_start.elapsed().as_micros();
-        info!("⚡ FAST LOOKUP: {} vs {} mapped to SX Event {} in {}µs", home, away, event_id, cache_elapsed);
+        info!("⚡ FAST LOOKUP: {} vs {} mapped to SX Event {} in {}µs", home, away, market.event_id, cache_elapsed);
+
+        // Klíč cache je seřazený — stranu vítěze je nutné spárovat s teamOneName/teamTwoName marketu
+        let Some(winner_side) = market.side_of(winner) else {
+            warn!("SX market {} ({} / {}): vítěz {} nejde spárovat s outcomem — oracle lag přeskočen",
+                market_hash, market.outcome_one, market.outcome_two, winner);
+            return Ok(());
+        };
+
+        // Reportovaný / zavřený market = zbylé ACTIVE ordery jsou stale, fill nemá smysl
+        match self.fetch_sx_market(&market_hash).await {
+            Ok(Some(market)) => {
+                if let Some(reason) = Self::sx_market_closed_reason(&market, Utc::now().timestamp()) {
+                    info!("⏭️ SX market {} ({} vs {}) už je {} — oracle lag přeskočen", market_hash, home, away, reason);
+                    return Ok(());
+                }
+            }
+            Ok(None) => warn!("SX market {} není v /markets/find — pokračuji bez status checku", market_hash),
+            Err(e) => warn!("SX market status {} selhal: {} — pokračuji bez status checku", market_hash, e),
+        }
 
         // Nyní jdeme okamžitě rovnou na orderbook (/orders?marketHash=X) přečíst nejlepší kurzy
-        let orders_url = format!("https://api.sx.bet/orders?marketHash={}", market_hash);
-        
         let req_start = std::time::Instant::now();
-        let orders_resp = self.client.get(&orders_url)
-            .send().await.context("SX Bet orders API failed")?;
-            
-        let pm_orders: serde_json::Value = orders_resp.json().await.context("SX Bet JSON parse failed")?;
+        let pm_orders = self.fetch_sx_orders(&market_hash).await?;
         
         // ---------------------------------------------------------------------------------------------------------------- //
-        // SLIPPAGE & GAS MODEL - Real data calculation
+        // SLIPPAGE & GAS MODEL - Real data calculation (čistá matematika v `compute_sx_edge`)
         // ---------------------------------------------------------------------------------------------------------------- //
-        // Pro zjištění reálného skluzu na orderbooku nasebíráme všechny nabídnuté limitní příkazy
-        // a budeme je "vykupovat" od nejlepšího, dokud nenaplníme náš testovací budget.
         let target_bet_size_usd = 100.0; // Simulovaná sázka $100
-        let mut available_orders: Vec<(f64, f64)> = Vec::new(); // (dec_prob, volume_usd)
-        
-        if let Some(orders_arr) = pm_orders.pointer("/data").and_then(|d| d.as_array()) {
-            for order in orders_arr {
-                let status = order.pointer("/orderStatus").and_then(|s| s.as_str()).unwrap_or("");
-                if status != "ACTIVE" { continue; }
-
-                // Determine whose bet this is - MakerOutcomeOne
-                let is_t1 = order.pointer("/isMakerBettingOutcomeOne").and_then(|b| b.as_bool()).unwrap_or(false);
-                let order_winner = if is_t1 { Self::normalize_team_name(home) } else { Self::normalize_team_name(away) };
-
-                // My chceme vzít BUY objednávku na YES pro 'winner'. 
-                // Zjednodusime - SX Bet nabizi kurzy makeru, taker sází proti nim
-                if order_winner.contains(&Self::normalize_team_name(winner)) {
-                    let prob_str = order.pointer("/percentageOdds").and_then(|s| s.as_str()).unwrap_or("0");
-                    let fill_amt_str = order.pointer("/fillAmount").and_then(|s| s.as_str()).unwrap_or("0");
-                    let orig_amt_str = order.pointer("/originalAmount").and_then(|s| s.as_str()).unwrap_or("0");
-                    
-                    if let (Ok(prob_u128), Ok(orig), Ok(fill)) = (prob_str.parse::<u128>(), orig_amt_str.parse::<f64>(), fill_amt_str.parse::<f64>()) {
-                        // Převod z 10^18 formátu do float: např 95000000000000000000 -> 95.0 -> 0.95
-                        let dec_prob = (prob_u128 as f64) / 100_000_000_000_000_000_000.0;
-                        
-                        // Remaining volume na tomto limitním příkazu
-                        let remaining_wei = orig - fill;
-                        let size_usd = remaining_wei / 1e18; // base je v wei (18 decimals) - defaultně USDC
-                        
-                        if dec_prob > 0.01 && size_usd > 0.05 { // ignoruj dust orders
-                            available_orders.push((dec_prob, size_usd));
-                        }
-                    }
+        // Reálný Gas Oracle pro Arbitrum; pokud selže, fallback 5 centů (Arbitrum normal)
+        let gas_usd = self.cached_gas_fee_usd("arbitrum").await.unwrap_or(0.05);
+        let edge = compute_sx_edge(&pm_orders, &market.outcome_one, &market.outcome_two, winner_side, target_bet_size_usd, self.sx_min_fill_ratio, gas_usd);
+
+        let req_elapsed = req_start.elapsed().as_millis();
+        let total_elapsed = overall_start.elapsed().as_millis();
+        info!("⚡ SX API Ping: {}ms | Total Arb Eval: {}ms | Best Edge Prob: {:.2}", req_elapsed, total_elapsed, edge.best_prob);
+
+        if edge.has_volume() {
+            // Evaluace: Pinnacle je teď vlastně "skutečný vývoj reality" = 100% tzn 1.0 
+            // My jsme našli trh na SX Betu s weighted kurzem best_prob po simulaci orderbook průstřelu (slippage započítána v průměru).
+            if self.reject_implausible_edge("sx_bet", home, away, sport, winner, &market_hash, edge.net_edge) {
+                return Ok(());
+            }
+            if edge.profitable {
+                info!("💎 A+ ARB FOUND na SX Bet! H: {}, A: {}, Win: {} | Avg Prob: {:.2} | Gas: {:.2}$ | Net Edge: {:.2}%", home, away, winner, edge.best_prob, gas_usd, edge.net_edge * 100.0);
+                // V reálu bych zde podepsal SX smart kontrakt transakci přes Ethers-rs lokálně
+                let order = OrderRequest::new(
+                    Venue::SxBet, Some("arbitrum"), &market_hash, market.outcome_id(winner_side),
+                    target_bet_size_usd, 1.0 / edge.best_prob, Utc::now().timestamp(),
+                );
+                let published = self.evaluate_oracle_lag_order(home, away, sport, edge.best_prob, &market_hash, Some(order));
+                // Observe-only order = dry-run sázka na vítěze; vyhodnotí ji settle_paper_bets na tentýž MATCH_RESOLVED
+                if let (true, Some(id)) = (published, id.clone()) {
+                    self.place_paper_bet(PaperBet {
+                        match_id:  id,
+                        home:      home.to_string(),
+                        away:      away.to_string(),
+                        side:      winner.to_string(),
+                        market_id: market_hash.clone(),
+                        stake_usd: target_bet_size_usd,
+                        price:     edge.best_prob,
+                    });
                 }
+            } else {
+                info!("SX Bet sázka by byla neprofitabilní po započtení poplatků (Edge {:.2}%, Gas: {:.2}$)", edge.net_edge * 100.0, gas_usd);
             }
+        } else {
+            warn!("Not enough volume left on SX Bet orderbook to fill {:.0}% of ${:.0} for {} (filled ${:.2})",
+                self.sx_min_fill_ratio * 100.0, target_bet_size_usd, winner, edge.filled_usd);
         }
 
-        // Seřadit od nejmenší pravděpodobnosti po největší (my chceme KOUPOVAT za co nejmenší implikovanou pravděpodobnost čili nejvyšší kurz)
-        available_orders.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Ok(())
+    }
+
+    /// Edge nad `max_edge_pct` se nejedná: hlasitý warn + ARB_REJECTED event. Vrací true pro zamítnutí.
+    #[allow(clippy::too_many_arguments)]
+    fn reject_implausible_edge(&self, venue: &str, home: &str, away: &str, sport: &str, winner: &str, market_id: &str, net_edge: f64) -> bool {
+        if net_edge <= self.max_edge_pct {
+            return false;
+        }
+        warn!("🛑 IMPLAUSIBLE EDGE na {}: {} vs {} (Win: {}) | Net Edge {:.1}% > strop {:.1}% — špatná data? Market {} zamítnut",
+            venue, home, away, winner, net_edge * 100.0, self.max_edge_pct * 100.0, market_id);
+        let _ = self.logger.log(&ArbRejectedEvent {
+            ts:           now_iso(),
+            event:        "ARB_REJECTED",
+            venue:        venue.to_string(),
+            home:         home.to_string(),
+            away:         away.to_string(),
+            sport:        sport.to_string(),
+            winner:       winner.to_string(),
+            market_id:    market_id.to_string(),
+            net_edge_pct: net_edge,
+            max_edge_pct: self.max_edge_pct,
+            reason:       "edge_above_ceiling".to_string(),
+        });
+        true
+    }
 
-        // Simulace orderbook fill
-        let mut accumulated_size = 0.0;
-        let mut weighted_prob_sum = 0.0;
-        
-        for (prob, size) in available_orders {
-            let remaining = target_bet_size_usd - accumulated_size;
-            if remaining <= 0.0 { break; }
+    /// Jeden market z `/markets/find` (status, reportedDate, gameTime); None = SX ho nezná.
+    async fn fetch_sx_market(&self, market_hash: &str) -> Result<Option<serde_json::Value>> {
+        let url = format!("{}/markets/find?marketHashes={}", self.sx_api_url, market_hash);
+        let data: serde_json::Value = self.client.get(&url)
+            .send().await.context("SX Bet markets API failed")?
+            .json().await.context("SX Bet markets JSON parse failed")?;
+        Ok(data.pointer("/data/0").cloned())
+    }
 
-            let fill = f64::min(remaining, size);
-            accumulated_size += fill;
-            weighted_prob_sum += prob * fill;
+    /// Proč už SX market nebrat: "reported" (orákulum zapsalo výsledek), "inactive" (status != ACTIVE)
+    /// nebo "past_settlement_window" (gameTime starší než SX_SETTLEMENT_WINDOW_SECS). None = otevřený.
+    fn sx_market_closed_reason(market: &serde_json::Value, now_ts: i64) -> Option<&'static str> {
+        let reported = market.get("reportedDate").is_some_and(|d| !d.is_null())
+            || market.get("outcome").and_then(|o| o.as_u64()).is_some_and(|o| o != 0);
+        if reported {
+            return Some("reported");
+        }
+        if market.get("status").and_then(|s| s.as_str()).is_some_and(|s| s != "ACTIVE") {
+            return Some("inactive");
         }
+        if market.get("gameTime").and_then(|t| t.as_i64()).is_some_and(|t| now_ts - t > SX_SETTLEMENT_WINDOW_SECS) {
+            return Some("past_settlement_window");
+        }
+        None
+    }
 
-        let best_guaranteed_prob = if accumulated_size > 0.0 {
-            weighted_prob_sum / accumulated_size
-        } else {
-            1.0 // no volume
+    /// Orderbook s per-request timeoutem `sx_orderbook` — v oracle-lag okně je pomalá odpověď k ničemu.
+    async fn fetch_sx_orders(&self, market_hash: &str) -> Result<serde_json::Value> {
+        let orders_url = format!("{}/orders?marketHash={}", self.sx_api_url, market_hash);
+        let orders_resp = self.client.get(&orders_url)
+            .timeout(self.timeouts.sx_orderbook)
+            .send().await.context("SX Bet orders API failed")?;
+        orders_resp.json().await.context("SX Bet JSON parse failed")
+    }
+
+    /// Live value betting: PINNACLE_LINE (PriceMonitor je loguje do sdíleného `RecentEvents`) × nacachované SX markety.
+    /// Pro spárovaný zápas se simuluje fill na obě strany a `evaluate_pinnacle_vs_polymarket` dostane
+    /// skutečnou fair value — edge i před koncem zápasu, ne jen oracle lag. Vrací počet vyhodnocených marketů.
+    pub async fn evaluate_live_value(&self, pinnacle_lines: &RecentEvents) -> usize {
+        let now = Utc::now();
+        // Nejnovější čerstvá line per dvojice týmů (Pinnacle esports nenese hru, páruje se jen podle týmů)
+        let mut fair: HashMap<String, (String, String, f64, f64)> = HashMap::new();
+        for line in pinnacle_lines.recent_of("PINNACLE_LINE", PINNACLE_LINE_SCAN) {
+            let fresh = line["ts"].as_str()
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .is_some_and(|ts| (now - ts.with_timezone(&Utc)).to_std().ok().is_none_or(|age| age <= PINNACLE_LINE_MAX_AGE));
+            let (Some(home), Some(away), Some(prob_home), Some(prob_away)) = (
+                line["home"].as_str(),
+                line["away"].as_str(),
+                line["pinnacle_prob_home"].as_f64(),
+                line["pinnacle_prob_away"].as_f64(),
+            ) else {
+                continue;
+            };
+            if fresh {
+                let teams = MatchId::new(Sport::Lol, home, away).teams_key();
+                fair.insert(teams, (home.to_string(), away.to_string(), prob_home, prob_away));
+            }
+        }
+        if fair.is_empty() {
+            return 0;
+        }
+
+        let markets: Vec<(MatchId, SxMarket)> = self.active_markets.read().await
+            .iter()
+            .filter(|(id, _)| fair.contains_key(&id.teams_key()))
+            .map(|(id, market)| (id.clone(), market.clone()))
+            .collect();
+
+        let mut evaluated = 0;
+        for (id, market) in markets {
+            let (home, away, prob_home, prob_away) = &fair[&id.teams_key()];
+            let market_hash = &market.market_hash;
+            let orders = match self.fetch_sx_orders(market_hash).await {
+                Ok(orders) => orders,
+                Err(e) => {
+                    warn!("Live value: SX orderbook {} vs {} failed: {}", home, away, e);
+                    continue;
+                }
+            };
+            for (side, fair_prob) in [(home, *prob_home), (away, *prob_away)] {
+                // Pinnacle tým → SX outcome podle jména (klíč cache je seřazený, pořadí nic neříká)
+                let Some(sx_side) = market.side_of(side) else {
+                    debug!("Live value: {} nejde spárovat s SX outcomem ({} / {})", side, market.outcome_one, market.outcome_two);
+                    continue;
+                };
+                let market_prob = Self::sx_fill_prob(&orders, &market, sx_side, LIVE_VALUE_BET_SIZE_USD, self.sx_min_fill_ratio);
+                if market_prob >= 1.0 {
+                    continue; // nedostatečný objem na tuhle stranu
+                }
+                debug!("Live value {} ({} vs {}): Pinnacle {:.3} vs SX {:.3}", side, home, away, fair_prob, market_prob);
+                if self.evaluate_pinnacle_vs_polymarket(home, away, id.sport.label(), fair_prob, market_prob, market_hash) {
+                    self.place_paper_bet(PaperBet {
+                        match_id:  id.clone(),
+                        home:      home.clone(),
+                        away:      away.clone(),
+                        side:      side.clone(),
+                        market_id: market_hash.clone(),
+                        stake_usd: LIVE_VALUE_BET_SIZE_USD,
+                        price:     market_prob,
+                    });
+                }
+            }
+            evaluated += 1;
+        }
+        evaluated
+    }
+
+    /// Paper sázka z dry-run příležitosti (jen s PAPER_SETTLEMENT); duplicitní strana se ignoruje.
+    fn place_paper_bet(&self, bet: PaperBet) {
+        let Some(paper) = &self.paper else { return };
+        let desc = format!("{} @ {:.2} ({} vs {})", bet.side, bet.odds(), bet.home, bet.away);
+        if paper.lock().unwrap_or_else(|e| e.into_inner()).place(bet) {
+            info!("📝 PAPER BET: {}", desc);
+        }
+    }
+
+    /// MATCH_RESOLVED → vyhodnoť otevřené paper sázky na zápas, připiš do paper P&L a zaloguj PAPER_SETTLEMENT.
+    /// Vrací počet vyhodnocených sázek (0 i bez PAPER_SETTLEMENT).
+    pub fn settle_paper_bets(&self, m: &MatchResolvedEvent) -> usize {
+        let Some(paper) = &self.paper else { return 0 };
+        if let Some(inbox) = &self.paper_inbox {
+            let external = inbox.lock().unwrap_or_else(|e| e.into_inner()).read_new();
+            for bet in external {
+                self.place_paper_bet(bet);
+            }
+        }
+        let Some(id) = MatchId::from_labels(&m.sport, &m.home, &m.away) else { return 0 };
+        let (settled, pnl_total, (evaluated, won)) = {
+            let mut book = paper.lock().unwrap_or_else(|e| e.into_inner());
+            let settled = book.settle(&id, &m.winner);
+            (settled, book.pnl_usd(), book.record())
         };
+        for s in &settled {
+            info!("📝 PAPER {}: {} @ {:.2} ({} vs {}) → {:+.2}$ | paper P&L {:+.2}$ ({}/{} won)",
+                if s.won { "WIN" } else { "LOSS" }, s.bet.side, s.bet.odds(), s.bet.home, s.bet.away,
+                s.pnl_usd, pnl_total, won, evaluated);
+            let _ = self.logger.log(&PaperSettlementEvent {
+                ts:            now_iso(),
+                event:         "PAPER_SETTLEMENT",
+                sport:         m.sport.clone(),
+                home:          s.bet.home.clone(),
+                away:          s.bet.away.clone(),
+                side:          s.bet.side.clone(),
+                winner:        m.winner.clone(),
+                market_id:     s.bet.market_id.clone(),
+                stake_usd:     s.bet.stake_usd,
+                odds:          s.bet.odds(),
+                won:           s.won,
+                pnl_usd:       s.pnl_usd,
+                paper_pnl_usd: pnl_total,
+            });
+        }
+        settled.len()
+    }
 
-        let req_elapsed = req_start.elapsed().as_millis();
-        let total_elapsed = overall_start.elapsed().as_millis();
-        info!("⚡ SX API Ping: {}ms | Total Arb Eval: {}ms | Best Edge Prob: {:.2}", req_elapsed, total_elapsed, best_guaranteed_prob);
+    /// SX outcome pro vítěze: home = outcomeOne (stejná konvence jako `isMakerBettingOutcomeOne`).
+    /// Průměrná implied prob po simulovaném průstřelu orderbooku za `target_usd` na SX outcome `side`;
+    /// 1.0 = nedostatečný objem (fill pod `min_fill_ratio` × `target_usd`).
+    fn sx_fill_prob(orders: &serde_json::Value, market: &SxMarket, side: &str, target_usd: f64, min_fill_ratio: f64) -> f64 {
+        sx_fill(orders, &market.outcome_one, &market.outcome_two, side, target_usd, min_fill_ratio).0
+    }
 
-        if best_guaranteed_prob < 1.0 {
-            // Evaluace: Pinnacle je teď vlastně "skutečný vývoj reality" = 100% tzn 1.0 
-            // My jsme našli trh na SX Betu s weighted kurzem best_guaranteed_prob po simulaci orderbook průstřelu (slippage započítána v průměru).
-            
-            // Reálný Gas Oracle pro Arbitrum
-            let gas_usd = self.fetch_arbitrum_gas_fee_usd().await.unwrap_or(0.05); // Pokud selže, fallback 5 centů (Arbitrum normal)
-            let gas_fee_pct = gas_usd / target_bet_size_usd; 
-            
-            let net_edge = (1.0 - best_guaranteed_prob) - gas_fee_pct;
-
-            if net_edge > 0.01 { // Striktní pravidlo ze specifikace: Net Edge > 1%
-                info!("💎 A+ ARB FOUND na SX Bet! H: {}, A: {}, Win: {} | Avg Prob: {:.2} | Gas: {:.2}$ | Net Edge: {:.2}%", home, away, winner, best_guaranteed_prob, gas_usd, net_edge * 100.0);
-                // V reálu bych zde podepsal SX smart kontrakt transakci přes Ethers-rs lokálně
-                self.evaluate_pinnacle_vs_polymarket(home, away, sport, 1.0, best_guaranteed_prob, &market_hash);
-            } else {
-                info!("SX Bet sázka by byla neprofitabilní po započtení poplatků (Edge {:.2}%, Gas: {:.2}$)", net_edge * 100.0, gas_usd);
+    /// SX market pro zápas: nejdřív ID zdroje přes side table, pak exaktní MatchId, nakonec substring jmen.
+    async fn lookup_sx_market(&self, id: Option<&MatchId>, t1: &str, t2: &str, source_match_id: Option<&str>) -> Option<SxMarket> {
+        let cache = self.active_markets.read().await;
+
+        if let Some(source_id) = source_match_id {
+            let mapped = self.source_match_ids.read().await.get(source_id).cloned();
+            if let Some(hit) = mapped.and_then(|mapped| cache.get(&mapped).cloned()) {
+                debug!("SX lookup podle ID {} → {}", source_id, hit.market_hash);
+                return Some(hit);
             }
-        } else {
-            warn!("Not enough volume left on SX Bet orderbook to fill $100 for {}", winner);
         }
 
-        Ok(())
+        // Prohledame i substringove (pri castecne normalizaci) pokud exaktni match selze
+        if let Some(exact) = id.and_then(|id| cache.get(id).cloned()) {
+            return Some(exact);
+        }
+
+        // Pokusime se najit substring match v klicich (drazsi operace, ale match_resolved se nestava tak casto)
+        cache.iter()
+            .find(|(k, _)| {
+                let teams = k.teams_key();
+                teams.contains(t1) && teams.contains(t2)
+            })
+            .map(|(_, v)| v.clone())
+    }
+
+    /// Diagnostika missu: až `limit` cachovaných marketů nejpodobnějších `query` (sdílený fuzzy matcher
+    /// z `MatchId`), sestupně podle skóre. Nulová podobnost (jiný sport, nic společného) se vynechá.
+    fn nearest_sx_markets(cache: &HashMap<MatchId, SxMarket>, query: &MatchId, limit: usize) -> Vec<(MatchId, String, f64)> {
+        let mut scored: Vec<(MatchId, String, f64)> = cache.iter()
+            .map(|(id, market)| (id.clone(), market.market_hash.clone(), query.similarity(id)))
+            .filter(|(_, _, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.key().cmp(&b.0.key())));
+        scored.truncate(limit);
+        scored
+    }
+
+    /// Gas fee v USD s TTL cache per chain ("arbitrum" | "polygon").
+    /// Burst resolved zápasů tak nespamuje public RPC stejným eth_gasPrice dotazem.
+    /// Chyby se necachují — volající použije svůj fallback a příští eval zkusí RPC znovu.
+    async fn cached_gas_fee_usd(&self, chain: &'static str) -> Result<f64> {
+        let slot = self.gas_cache.get(chain).context("Unknown gas chain")?;
+        let mut cached = slot.lock().await;
+        if let Some((fetched_at, usd)) = *cached {
+            if fetched_at.elapsed() < self.gas_cache_ttl {
+                debug!("Gas cache hit ({}): {:.4}$", chain, usd);
+                return Ok(usd);
+            }
+        }
+
+        let usd = match chain {
+            "arbitrum" => self.fetch_arbitrum_gas_fee_usd().await?,
+            _          => self.fetch_polygon_gas_fee_usd().await?,
+        };
+        *cached = Some((Instant::now(), usd));
+        Ok(usd)
+    }
+
+    /// Selftest: jeden request na zdroj ("sx_bet" | "azuro_thegraph" | "arbitrum_rpc" | "polygon_rpc").
+    /// Vrací krátký detail pro report, Err = zdroj nedostupný.
+    pub async fn probe_source(&self, source: &str) -> Result<String> {
+        match source {
+            "sx_bet" => {
+                let resp = self.client.get(format!("{}/leagues", self.sx_api_url))
+                    .send().await.context("SX Bet /leagues request failed")?;
+                if !resp.status().is_success() {
+                    anyhow::bail!("SX Bet HTTP {}", resp.status());
+                }
+                let json: serde_json::Value = resp.json().await?;
+                let leagues = json.pointer("/data").and_then(|d| d.as_array()).map(|d| d.len()).unwrap_or(0);
+                Ok(format!("HTTP 200, {} leagues", leagues))
+            }
+            "azuro_thegraph" => {
+                let payload = json!({ "query": "{ _meta { block { number } } }" });
+                let resp = self.client.post("https://thegraph.azuro.org/api/v1/graphql")
+                    .json(&payload).send().await.context("Azuro TheGraph request failed")?;
+                if !resp.status().is_success() {
+                    anyhow::bail!("Azuro TheGraph HTTP {}", resp.status());
+                }
+                let json: serde_json::Value = resp.json().await?;
+                if let Some(errors) = json.get("errors") {
+                    anyhow::bail!("Azuro TheGraph errors: {}", errors);
+                }
+                let block = json.pointer("/data/_meta/block/number").and_then(|b| b.as_u64()).unwrap_or(0);
+                Ok(format!("indexed block {}", block))
+            }
+            "arbitrum_rpc" | "polygon_rpc" => {
+                let rpc_url = if source == "arbitrum_rpc" { &self.arbitrum_rpc_url } else { &self.polygon_rpc_url };
+                let payload = json!({
+                    "jsonrpc": "2.0",
+                    "method": "eth_blockNumber",
+                    "params": [],
+                    "id": 1
+                });
+                let resp = self.client.post(rpc_url.as_str())
+                    .json(&payload).send().await.context("RPC request failed")?;
+                let json: serde_json::Value = resp.json().await?;
+                let block_hex = json.pointer("/result").and_then(|r| r.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("RPC {} returned no result: {}", rpc_url, json))?;
+                let block = u64::from_str_radix(block_hex.trim_start_matches("0x"), 16)?;
+                Ok(format!("block {}", block))
+            }
+            other => anyhow::bail!("unknown probe source: {}", other),
+        }
     }
 
     /// Fetches currently streaming real-world gas baseFee from Arbitrum public RPC
     async fn fetch_arbitrum_gas_fee_usd(&self) -> Result<f64> {
-        let rpc_url = "https://arb1.arbitrum.io/rpc";
+        let rpc_url = self.arbitrum_rpc_url.as_str();
         let payload = json!({
             "jsonrpc": "2.0",
             "method": "eth_gasPrice",
@@ -366,10 +1104,11 @@ impl ArbDetector {
             if let Ok(json) = resp.json::<serde_json::Value>().await {
                 if let Some(gas_price_hex) = json.pointer("/result").and_then(|r| r.as_str()) {
                     let clean_hex = gas_price_hex.trim_start_matches("0x");
-                    if let Ok(gas_price_wei) = u128::from_str_radix(clean_hex, 16) {
-                        // Standardní place order call na SX / Polymarket Polygon/Arbitrum = ~800,000 gas limit units
-                        let total_gas_wei = gas_price_wei * 800_000;
-                        let gas_eth = (total_gas_wei as f64) / 1e18;
+                    // Standardní place order call na SX / Polymarket Polygon/Arbitrum = ~800,000 gas limit units
+                    let gas_eth = u128::from_str_radix(clean_hex, 16).ok()
+                        .and_then(|gas_price_wei| gas_price_wei.checked_mul(800_000))
+                        .and_then(|total_gas_wei| wei_to_decimal(total_gas_wei, WEI_DECIMALS));
+                    if let Some(gas_eth) = gas_eth {
                         // Odhadovaná cena ETH (pro neuvěřitelnou přesnost bychom přidali Chainlink, ale tohle je plně operativní MVP Oracle)
                         let eth_price_usd = 3000.0; 
                         return Ok(gas_eth * eth_price_usd);
@@ -382,7 +1121,7 @@ impl ArbDetector {
 
     /// Fetches currently streaming real-world gas baseFee from Polygon public RPC
     async fn fetch_polygon_gas_fee_usd(&self) -> Result<f64> {
-        let rpc_url = "https://polygon-rpc.com/";
+        let rpc_url = self.polygon_rpc_url.as_str();
         let payload = json!({
             "jsonrpc": "2.0",
             "method": "eth_gasPrice",
@@ -394,10 +1133,11 @@ impl ArbDetector {
             if let Ok(json) = resp.json::<serde_json::Value>().await {
                 if let Some(gas_price_hex) = json.pointer("/result").and_then(|r| r.as_str()) {
                     let clean_hex = gas_price_hex.trim_start_matches("0x");
-                    if let Ok(gas_price_wei) = u128::from_str_radix(clean_hex, 16) {
-                        // Polygon trade je obvykle 500k-1M gas limit, průměr ~800,000
-                        let total_gas_wei = gas_price_wei * 800_000;
-                        let gas_matic = (total_gas_wei as f64) / 1e18;
+                    // Polygon trade je obvykle 500k-1M gas limit, průměr ~800,000
+                    let gas_matic = u128::from_str_radix(clean_hex, 16).ok()
+                        .and_then(|gas_price_wei| gas_price_wei.checked_mul(800_000))
+                        .and_then(|total_gas_wei| wei_to_decimal(total_gas_wei, WEI_DECIMALS));
+                    if let Some(gas_matic) = gas_matic {
                         let pol_price_usd = 0.50; // POL (ex-MATIC) odhadovaná cena
                         return Ok(gas_matic * pol_price_usd);
                     }
@@ -412,7 +1152,7 @@ impl ArbDetector {
         let thegraph_url = "https://thegraph.azuro.org/api/v1/graphql";
         
         // Zjednodušený fulltext search term pro GraphQL
-        let search_term = Self::normalize_team_name(home);
+        let search_term = normalize_team(home);
         
         let query = r#"
         query SearchGames($search: String!) {
@@ -436,56 +1176,22 @@ impl ArbDetector {
         let resp = self.client.post(thegraph_url).json(&payload).send().await?;
         let json_resp: serde_json::Value = resp.json().await?;
         
-        let mut best_prob = 1.0;
-        
-        if let Some(games) = json_resp.pointer("/data/games").and_then(|g| g.as_array()) {
-            for game in games {
-                let title = game.pointer("/title").and_then(|t| t.as_str()).unwrap_or("").to_lowercase();
-                if title.contains(&Self::normalize_team_name(away)) {
-                    // Zápas nalezen
-                    if let Some(conditions) = game.pointer("/conditions").and_then(|c| c.as_array()) {
-                        for condition in conditions {
-                            if let Some(outcomes) = condition.pointer("/outcomes").and_then(|o| o.as_array()) {
-                                let target_idx = if winner.to_lowercase() == home.to_lowercase() { 0 } else { 1 };
-                                if target_idx < outcomes.len() {
-                                    if let Some(odds_str) = outcomes[target_idx].pointer("/currentOdds").and_then(|o| o.as_str()) {
-                                        if let Ok(odds_f64) = odds_str.parse::<f64>() {
-                                            let raw_prob = 1.0 / odds_f64;
-                                            
-                                            // AMM SLIPPAGE SIMULATION (Reálná data kalkulace pro $100 budget)
-                                            // Azuro Liquidity Pool slippage pro normální esport market posouvá kurz cca o 1.5% u $100
-                                            let slippage_penalty = 0.015; 
-                                            let prob_after_slippage = raw_prob + slippage_penalty; 
-                                            
-                                            if prob_after_slippage < best_prob && prob_after_slippage > 0.01 {
-                                                best_prob = prob_after_slippage;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
         let total_elapsed = req_start.elapsed().as_millis();
-        
-        if best_prob < 1.0 {
-            info!("⚡ Azuro TheGraph Ping: {}ms | Best Edge Prob: {:.2}", total_elapsed, best_prob);
-
-            let target_bet_size_usd = 100.0;
-            let gas_usd = self.fetch_polygon_gas_fee_usd().await.unwrap_or(0.01); // Polygon normálně ~1 cent
-            let gas_fee_pct = gas_usd / target_bet_size_usd; 
-            
-            let net_edge = (1.0 - best_prob) - gas_fee_pct;
-
-            if net_edge > 0.01 { 
-                info!("🔮 A+ ARB FOUND na Azuro! H: {}, A: {}, Win: {} | Avg Prob: {:.2} | Gas: {:.2}$ | Net Edge: {:.2}%", home, away, winner, best_prob, gas_usd, net_edge * 100.0);
-                self.evaluate_pinnacle_vs_polymarket(home, away, sport, 1.0, best_prob, "azuro_graphql_market");
+        let target_bet_size_usd = 100.0;
+        let gas_usd = self.cached_gas_fee_usd("polygon").await.unwrap_or(0.01); // Polygon normálně ~1 cent
+        let edge = compute_azuro_edge(&json_resp, home, away, winner, target_bet_size_usd, gas_usd);
+
+        if edge.has_volume() {
+            info!("⚡ Azuro TheGraph Ping: {}ms | Best Edge Prob: {:.2}", total_elapsed, edge.best_prob);
+
+            if self.reject_implausible_edge("azuro", home, away, sport, winner, "azuro_graphql_market", edge.net_edge) {
+                return Ok(());
+            }
+            if edge.profitable {
+                info!("🔮 A+ ARB FOUND na Azuro! H: {}, A: {}, Win: {} | Avg Prob: {:.2} | Gas: {:.2}$ | Net Edge: {:.2}%", home, away, winner, edge.best_prob, gas_usd, edge.net_edge * 100.0);
+                self.evaluate_oracle_lag(home, away, sport, edge.best_prob, "azuro_graphql_market");
             } else {
-                info!("Azuro sázka by byla neprofitabilní po započtení poplatků (Edge {:.2}%, Gas: {:.2}$)", net_edge * 100.0, gas_usd);
+                info!("Azuro sázka by byla neprofitabilní po započtení poplatků (Edge {:.2}%, Gas: {:.2}$)", edge.net_edge * 100.0, gas_usd);
             }
         } else {
             debug!("Azuro ping ({}ms): Žádný ziskový Azuro market pro {}", total_elapsed, winner);
@@ -494,12 +1200,926 @@ impl ArbDetector {
         Ok(())
     }
 
+    /// Dopíše bufferované JSONL eventy (graceful shutdown).
+    pub fn flush_logs(&self) {
+        let near_miss = self.near_miss.as_ref().map_or(Ok(()), NearMissLog::flush);
+        if let Err(e) = self.logger.flush().and(near_miss) {
+            warn!("Flush arb logu selhal: {}", e);
+        }
+    }
+
     /// Debugovaci pomucka pro vypsani obsahu cache
     pub async fn debug_print_cache(&self) {
         let cache = self.active_markets.read().await;
         info!("--- CURRENT SX BET CACHE DUMP ({} items) ---", cache.len());
         for (key, val) in cache.iter().take(15) { // ukaž prvnich 15 pro prehled
-            info!("MAPPED: {} -> SX Event ID: {}", key, val.1);
+            info!("MAPPED: {} -> SX Event ID: {}", key, val.event_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod gas_cache_tests {
+    use super::ArbDetector;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::time::Duration;
+
+    /// Mock JSON-RPC: odpovídá eth_gasPrice = 1 gwei a počítá requesty.
+    async fn spawn_counting_rpc() -> (String, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let url = test_http::serve(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            test_http::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#)
+        }).await;
+        (format!("{}/", url), calls)
+    }
+
+    #[tokio::test]
+    async fn gas_fee_is_fetched_once_within_ttl() {
+        let (url, calls) = spawn_counting_rpc().await;
+        let mut detector = ArbDetector::build(std::env::temp_dir().join("arb_gas_cache_test"), true);
+        detector.arbitrum_rpc_url = url.clone();
+        detector.polygon_rpc_url = url;
+        detector.gas_cache_ttl = Duration::from_secs(30);
+
+        let first = detector.cached_gas_fee_usd("arbitrum").await.unwrap();
+        for _ in 0..5 {
+            assert_eq!(detector.cached_gas_fee_usd("arbitrum").await.unwrap(), first);
         }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Jiný chain má vlastní slot
+        detector.cached_gas_fee_usd("polygon").await.unwrap();
+        detector.cached_gas_fee_usd("polygon").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn gas_fee_is_refetched_after_ttl_expires() {
+        let (url, calls) = spawn_counting_rpc().await;
+        let mut detector = ArbDetector::build(std::env::temp_dir().join("arb_gas_cache_test"), true);
+        detector.arbitrum_rpc_url = url;
+        detector.gas_cache_ttl = Duration::ZERO;
+
+        detector.cached_gas_fee_usd("arbitrum").await.unwrap();
+        detector.cached_gas_fee_usd("arbitrum").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
+
+#[cfg(test)]
+mod near_miss_tests {
+    use super::ArbDetector;
+    use logger::{EventReader, NearMissLog};
+
+    fn near_miss_lines(dir: &std::path::Path) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all("NEAR_MISS").unwrap()
+    }
+
+    #[test]
+    fn sub_threshold_edge_within_band_is_logged_as_near_miss() {
+        let dir = std::env::temp_dir().join(format!("arb_near_miss_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.near_miss = Some(NearMissLog::new(&dir, 0.8));
+
+        // 2.5% edge vs 3% threshold → near miss; 2% je pod 80% pásmem → nic
+        detector.evaluate_pinnacle_vs_polymarket("Alpha", "Beta", "cs2", 0.525, 0.50, "cond");
+        detector.evaluate_pinnacle_vs_polymarket("Gamma", "Delta", "cs2", 0.52, 0.50, "cond");
+
+        detector.flush_logs();
+        let records = near_miss_lines(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["match_name"], "Alpha_vs_Beta");
+        assert_eq!(records[0]["source"], "pinnacle_vs_polymarket");
+        assert!((records[0]["edge_pct"].as_f64().unwrap() - 2.5).abs() < 1e-9);
+        assert!((records[0]["threshold_pct"].as_f64().unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(records[0]["reason"], "below_min_edge");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod arb_category_tests {
+    use super::ArbDetector;
+    use logger::EventReader;
+
+    fn opportunity_lines(dir: &std::path::Path) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all("ARB_OPPORTUNITY").unwrap()
+    }
+
+    fn detector(dir: &std::path::Path) -> ArbDetector {
+        let _ = std::fs::remove_dir_all(dir);
+        let mut detector = ArbDetector::build(dir, true);
+        detector.telegram_bot_token = String::new();
+        detector.min_edge_pct = 0.03;
+        detector.oracle_lag_min_edge_pct = 0.05;
+        detector
+    }
+
+    #[test]
+    fn pre_resolution_edge_uses_its_own_floor_and_source() {
+        let dir = std::env::temp_dir().join(format!("arb_category_pre_{}", std::process::id()));
+        let detector = detector(&dir);
+
+        // 4 % nad 3% floorem pre-resolution (oracle-lag floor 5 % se ho netýká)
+        detector.evaluate_pinnacle_vs_polymarket("Alpha", "Beta", "cs2", 0.54, 0.50, "cond");
+
+        detector.flush_logs();
+        let records = opportunity_lines(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["source"], "pinnacle_vs_polymarket");
+        assert!((records[0]["edge_pct"].as_f64().unwrap() - 0.04).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn oracle_lag_is_tagged_separately_and_filtered_by_its_floor() {
+        let dir = std::env::temp_dir().join(format!("arb_category_lag_{}", std::process::id()));
+        let detector = detector(&dir);
+
+        // Vyhodnocený zápas se zbytkem orderbooku @0.96 → 4 % lag pod 5% floorem → ticho
+        detector.evaluate_oracle_lag("Alpha", "Beta", "cs2", 0.96, "sx_market");
+        detector.flush_logs();
+        assert!(opportunity_lines(&dir).is_empty());
+
+        detector.evaluate_oracle_lag("Gamma", "Delta", "cs2", 0.90, "sx_market");
+        detector.flush_logs();
+        let records = opportunity_lines(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["source"], "oracle_lag");
+        assert_eq!(records[0]["pinnacle_prob"], 1.0);
+        assert!((records[0]["edge_pct"].as_f64().unwrap() - 0.10).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod source_id_mapping_tests {
+    use super::{ArbDetector, SxMarket};
+    use esports_core::match_id::{normalize_team, MatchId};
+    use esports_core::Sport;
+
+    #[tokio::test]
+    async fn source_match_id_joins_market_when_team_names_differ() {
+        let dir = std::env::temp_dir().join(format!("arb_source_id_{}", std::process::id()));
+        let detector = ArbDetector::build(&dir, true);
+        let market = MatchId::new(Sport::Cs2, "Natus Vincere", "Team Liquid");
+        ArbDetector::store_sx_markets(&detector.active_markets, &detector.sx_event_ids,
+            [(market.clone(), SxMarket::new("0xhash", "L7178624", "Natus Vincere", "Team Liquid"))], &[]).await;
+        // HLTV ID zápasu — jiný prostor ID než sportXeventId
+        detector.source_match_ids.write().await.insert("2371389".to_string(), market);
+
+        // Zkratky z výsledkového zdroje se s názvy SX marketu nespárují
+        let id = MatchId::from_labels("cs2", "NAVI", "TL");
+        let (t1, t2) = (normalize_team("NAVI"), normalize_team("TL"));
+        assert!(detector.lookup_sx_market(id.as_ref(), &t1, &t2, None).await.is_none());
+        assert!(detector.lookup_sx_market(id.as_ref(), &t1, &t2, Some("L0000000")).await.is_none());
+        // sportXeventId není ID zdroje výsledku
+        assert!(detector.lookup_sx_market(id.as_ref(), &t1, &t2, Some("L7178624")).await.is_none());
+
+        let hit = detector.lookup_sx_market(id.as_ref(), &t1, &t2, Some("2371389")).await;
+        assert_eq!(hit.map(|m| m.market_hash), Some("0xhash".to_string()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod sx_miss_suggestion_tests {
+    use super::{ArbDetector, SxMarket};
+    use esports_core::match_id::MatchId;
+    use esports_core::Sport;
+    use std::collections::HashMap;
+
+    #[test]
+    fn cache_miss_suggests_closest_cached_market() {
+        let entry = |hash: &str| SxMarket::new(hash, "L1", "Home", "Away");
+        let cache = HashMap::from([
+            (MatchId::new(Sport::Cs2, "Natus Vincere", "Team Vitality"), entry("0xnavi")),
+            (MatchId::new(Sport::Cs2, "FaZe Clan", "MOUZ"), entry("0xfaze")),
+            (MatchId::new(Sport::Dota2, "Natus Vincere", "Team Vitality"), entry("0xdota")),
+        ]);
+        // Překlep + jiné pořadí týmů → exaktní lookup mine, suggestion ho najde
+        let query = MatchId::new(Sport::Cs2, "Vitality", "Natus Vinceree");
+
+        let suggestions = ArbDetector::nearest_sx_markets(&cache, &query, 3);
+        assert_eq!(suggestions[0].0, MatchId::new(Sport::Cs2, "Natus Vincere", "Team Vitality"));
+        assert_eq!(suggestions[0].1, "0xnavi");
+        assert!(suggestions[0].2 > 0.8, "score {}", suggestions[0].2);
+        assert!(suggestions.iter().all(|(id, _, _)| id.sport == Sport::Cs2), "jiný sport se nenavrhuje");
+        assert!(suggestions.windows(2).all(|w| w[0].2 >= w[1].2));
+        assert_eq!(ArbDetector::nearest_sx_markets(&cache, &query, 1).len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod edge_ceiling_tests {
+    use super::ArbDetector;
+    use logger::EventReader;
+
+    fn event_lines(dir: &std::path::Path, event: &str) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all(event).unwrap()
+    }
+
+    #[test]
+    fn edge_above_ceiling_is_rejected_and_logged() {
+        let dir = std::env::temp_dir().join(format!("arb_edge_ceiling_reject_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.max_edge_pct = 0.40;
+
+        assert!(detector.reject_implausible_edge("sx_bet", "Alpha", "Beta", "cs2", "Alpha", "0xhash", 0.50));
+
+        detector.flush_logs();
+        let records = event_lines(&dir, "ARB_REJECTED");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["venue"], "sx_bet");
+        assert_eq!(records[0]["market_id"], "0xhash");
+        assert_eq!(records[0]["reason"], "edge_above_ceiling");
+        assert!((records[0]["net_edge_pct"].as_f64().unwrap() - 0.50).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plausible_edge_passes_the_ceiling() {
+        let dir = std::env::temp_dir().join(format!("arb_edge_ceiling_pass_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.max_edge_pct = 0.40;
+
+        assert!(!detector.reject_implausible_edge("azuro", "Alpha", "Beta", "cs2", "Alpha", "azuro_graphql_market", 0.04));
+        detector.flush_logs();
+        assert!(event_lines(&dir, "ARB_REJECTED").is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod prewarm_tests {
+    use super::{ArbDetector, SxMarket};
+    use chrono::Utc;
+    use esports_core::match_id::normalize_team;
+    use esports_core::{MatchId, Sport, UpcomingMatch};
+
+    /// Mock SX API: /leagues → jedna LoL liga, /markets/active → T1 vs Gen.G moneyline.
+    async fn spawn_sx_api() -> String {
+        test_http::serve(|req| test_http::json(if req.raw.starts_with("GET /leagues") {
+            r#"{"data":[{"leagueId":1236,"label":"LoL - LCK","sportId":9,"active":true}]}"#
+        } else {
+            r#"{"data":{"markets":[
+                {"type":52,"teamOneName":"T1","teamTwoName":"Gen.G","marketHash":"0xlck","sportXeventId":"L9000001"},
+                {"type":52,"teamOneName":"KT Rolster","teamTwoName":"DRX","marketHash":"0xother","sportXeventId":"L9000002"}
+            ]}}"#
+        })).await
+    }
+
+    #[tokio::test]
+    async fn upcoming_match_prewarms_cache_for_later_resolution() {
+        let dir = std::env::temp_dir().join(format!("arb_prewarm_{}", std::process::id()));
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.sx_api_url = spawn_sx_api().await;
+
+        let upcoming = UpcomingMatch {
+            id:         MatchId::new(Sport::Lol, "T1", "Gen.G"),
+            home:       "T1".to_string(),
+            away:       "Gen.G".to_string(),
+            start_time: Utc::now(),
+            source_match_id: Some("113475".to_string()),
+        };
+        assert_eq!(detector.prewarm_markets(std::slice::from_ref(&upcoming)).await, 1);
+        // Jen zápasy z rozpisu, ne celá liga
+        assert_eq!(detector.active_markets.read().await.len(), 1);
+        // Už nacachované se znovu nestahuje
+        assert_eq!(detector.prewarm_markets(std::slice::from_ref(&upcoming)).await, 0);
+
+        // Resolved s Riot ID → hit přes side table; i podle jmen bez ID
+        let (t1, t2) = (normalize_team("T1"), normalize_team("Gen.G"));
+        let expected = Some(SxMarket::new("0xlck", "L9000001", "T1", "Gen.G"));
+        assert_eq!(detector.lookup_sx_market(None, "", "", Some("113475")).await, expected);
+        let id = MatchId::from_labels("leagueoflegends", "T1", "Gen.G");
+        assert_eq!(detector.lookup_sx_market(id.as_ref(), &t1, &t2, None).await, expected);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod live_value_tests {
+    use super::{append_paper_bet, ArbDetector, PaperBetLine, SxMarket};
+    use esports_core::{MatchId, Sport};
+    use logger::{now_iso, EventLogger, EventReader, MatchResolvedEvent, PinnacleLineEvent, RecentEvents};
+
+    /// Mock SX /orders: maker nabízí Alpha @ 0.50 ($200), na Beta nic.
+    async fn spawn_sx_orders() -> String {
+        test_http::serve(|_| test_http::json(r#"{"data":[{"orderStatus":"ACTIVE","isMakerBettingOutcomeOne":true,
+            "percentageOdds":"50000000000000000000","originalAmount":"200000000000000000000","fillAmount":"0"}]}"#)).await
+    }
+
+    fn event_lines(dir: &std::path::Path, event: &str) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all(event).unwrap()
+    }
+
+    fn opportunity_lines(dir: &std::path::Path) -> Vec<serde_json::Value> {
+        event_lines(dir, "ARB_OPPORTUNITY")
+    }
+
+    #[tokio::test]
+    async fn pinnacle_fair_value_drives_live_value_against_sx_price() {
+        let dir = std::env::temp_dir().join(format!("arb_live_value_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.telegram_bot_token = String::new();
+        detector.min_edge_pct = 0.03;
+        detector.sx_api_url = spawn_sx_orders().await;
+        detector.active_markets.write().await
+            .insert(MatchId::new(Sport::Cs2, "Alpha", "Beta"), SxMarket::new("0xlive", "L1", "Alpha", "Beta"));
+
+        // PriceMonitor loguje PINNACLE_LINE do sdíleného bufferu
+        let lines = RecentEvents::new(64);
+        let pinnacle_dir = dir.join("pinnacle");
+        EventLogger::new(&pinnacle_dir).with_recent_events(lines.clone()).log(&PinnacleLineEvent {
+            ts:                 now_iso(),
+            event:              "PINNACLE_LINE",
+            sport:              "esports".to_string(),
+            home:               "Alpha".to_string(),
+            away:               "Beta".to_string(),
+            home_odds:          1.60,
+            away_odds:          2.60,
+            draw_odds:          None,
+            pinnacle_prob_home: 0.60,
+            pinnacle_prob_away: 0.40,
+        }).unwrap();
+
+        assert_eq!(detector.evaluate_live_value(&lines).await, 1);
+
+        detector.flush_logs();
+        let records = opportunity_lines(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["source"], "pinnacle_vs_polymarket");
+        assert_eq!(records[0]["home"], "Alpha");
+        assert_eq!(records[0]["sport"], "CS2");
+        assert!((records[0]["pinnacle_prob"].as_f64().unwrap() - 0.60).abs() < 1e-9);
+        assert!((records[0]["polymarket_price"].as_f64().unwrap() - 0.50).abs() < 1e-9);
+        assert!((records[0]["edge_pct"].as_f64().unwrap() - 0.10).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn live_value_maps_sides_by_sx_outcome_names() {
+        let dir = std::env::temp_dir().join(format!("arb_live_value_sides_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.telegram_bot_token = String::new();
+        detector.min_edge_pct = 0.03;
+        detector.sx_api_url = spawn_sx_orders().await;
+        // Outcome one = Beta: maker nabídka @ 0.50 je na Beta, ne na Pinnacle home (Alpha)
+        detector.active_markets.write().await
+            .insert(MatchId::new(Sport::Cs2, "Alpha", "Beta"), SxMarket::new("0xlive", "L1", "Beta", "Alpha"));
+
+        let lines = RecentEvents::new(64);
+        EventLogger::new(dir.join("pinnacle")).with_recent_events(lines.clone()).log(&PinnacleLineEvent {
+            ts:                 now_iso(),
+            event:              "PINNACLE_LINE",
+            sport:              "esports".to_string(),
+            home:               "Alpha".to_string(),
+            away:               "Beta".to_string(),
+            home_odds:          1.60,
+            away_odds:          2.60,
+            draw_odds:          None,
+            pinnacle_prob_home: 0.60,
+            pinnacle_prob_away: 0.40,
+        }).unwrap();
+
+        // Beta: fair 0.40 < SX 0.50 → žádný edge; Alpha na SX nemá objem
+        assert_eq!(detector.evaluate_live_value(&lines).await, 1);
+        detector.flush_logs();
+        assert!(opportunity_lines(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn live_value_paper_bet_settles_against_resolved_match() {
+        let dir = std::env::temp_dir().join(format!("arb_paper_settlement_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true).with_paper_settlement(true);
+        detector.telegram_bot_token = String::new();
+        detector.min_edge_pct = 0.03;
+        detector.sx_api_url = spawn_sx_orders().await;
+        detector.active_markets.write().await
+            .insert(MatchId::new(Sport::Cs2, "Alpha", "Beta"), SxMarket::new("0xlive", "L1", "Alpha", "Beta"));
+
+        let lines = RecentEvents::new(64);
+        EventLogger::new(dir.join("pinnacle")).with_recent_events(lines.clone()).log(&PinnacleLineEvent {
+            ts:                 now_iso(),
+            event:              "PINNACLE_LINE",
+            sport:              "esports".to_string(),
+            home:               "Alpha".to_string(),
+            away:               "Beta".to_string(),
+            home_odds:          1.60,
+            away_odds:          2.60,
+            draw_odds:          None,
+            pinnacle_prob_home: 0.60,
+            pinnacle_prob_away: 0.40,
+        }).unwrap();
+
+        // Dva cykly se stejným edge → jediná paper sázka (Alpha @ 2.00)
+        assert_eq!(detector.evaluate_live_value(&lines).await, 1);
+        assert_eq!(detector.evaluate_live_value(&lines).await, 1);
+
+        let resolved = MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "cs2".to_string(),
+            match_name: "Alpha vs Beta".to_string(),
+            home:       "Alpha".to_string(),
+            away:       "Beta".to_string(),
+            winner:     "Alpha".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        };
+        assert_eq!(detector.settle_paper_bets(&resolved), 1);
+        assert_eq!(detector.settle_paper_bets(&resolved), 0, "vyhodnocená sázka už není otevřená");
+
+        detector.flush_logs();
+        let settlements: Vec<serde_json::Value> = event_lines(&dir, "PAPER_SETTLEMENT");
+        assert_eq!(settlements.len(), 1);
+        assert_eq!(settlements[0]["side"], "Alpha");
+        assert_eq!(settlements[0]["won"], true);
+        assert!((settlements[0]["odds"].as_f64().unwrap() - 2.0).abs() < 1e-9);
+        assert!((settlements[0]["pnl_usd"].as_f64().unwrap() - 100.0).abs() < 1e-9);
+        assert!((settlements[0]["paper_pnl_usd"].as_f64().unwrap() - 100.0).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn paper_settlement_is_observe_only_and_settles_inbox_bets() {
+        let dir = std::env::temp_dir().join(format!("arb_paper_inbox_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let resolved = MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "dota2".to_string(),
+            match_name: "gamma_vs_delta".to_string(),
+            home:       "Gamma".to_string(),
+            away:       "Delta".to_string(),
+            winner:     "Delta".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        };
+        let line = PaperBetLine {
+            ts:        now_iso(),
+            source:    "alert_bot_score_edge".to_string(),
+            sport:     "dota-2".to_string(),
+            home:      "Gamma".to_string(),
+            away:      "Delta".to_string(),
+            side:      "Gamma".to_string(),
+            market_id: "0xcond".to_string(),
+            stake_usd: 2.0,
+            price:     0.40,
+        };
+
+        // Živý detektor paper settlement nezapne ani s PAPER_SETTLEMENT
+        let live = ArbDetector::build(&dir, false).with_paper_settlement(true);
+        let observer = ArbDetector::build(&dir, true).with_paper_settlement(true);
+        append_paper_bet(&dir, &line).unwrap();
+        assert_eq!(live.settle_paper_bets(&resolved), 0);
+
+        // Observe-only detektor vezme sázku z inboxu (alert-bot dry-run) a vyhodnotí ji jako prohru
+        assert_eq!(observer.settle_paper_bets(&resolved), 1);
+        assert_eq!(observer.settle_paper_bets(&resolved), 0, "řádek inboxu se čte jednou");
+        observer.flush_logs();
+        let settlements: Vec<serde_json::Value> = event_lines(&dir, "PAPER_SETTLEMENT");
+        assert_eq!(settlements.len(), 1);
+        assert_eq!(settlements[0]["won"], false);
+        assert!((settlements[0]["pnl_usd"].as_f64().unwrap() + 2.0).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod market_status_tests {
+    use super::{ArbDetector, SxMarket};
+    use logger::{now_iso, EventReader, MatchResolvedEvent, OrderRequest, Venue};
+    use chrono::Utc;
+    use esports_core::{MatchId, Sport};
+    use serde_json::json;
+    use std::time::Instant;
+
+    /// Mock SX API: /markets/find → 0xreported je reportovaný, 0xopen běží; /orders → Alpha @ 0.80 ($200).
+    async fn spawn_sx_markets() -> String {
+        let game_time = Utc::now().timestamp() - 3600;
+        test_http::serve(move |req| test_http::json(if req.raw.starts_with("GET /markets/find") {
+            let reported = req.raw.contains("0xreported");
+            json!({"status": "success", "data": [{
+                "marketHash": if reported { "0xreported" } else { "0xopen" },
+                "status": "ACTIVE",
+                "gameTime": game_time,
+                "reportedDate": if reported { json!(game_time + 3000) } else { json!(null) },
+                "outcome": if reported { 1 } else { 0 },
+            }]}).to_string()
+        } else {
+            r#"{"data":[{"orderStatus":"ACTIVE","isMakerBettingOutcomeOne":true,
+                "percentageOdds":"80000000000000000000","originalAmount":"200000000000000000000","fillAmount":"0"}]}"#.to_string()
+        })).await
+    }
+
+    fn event_lines(dir: &std::path::Path, event: &str) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all(event).unwrap()
+    }
+
+    fn opportunity_events(dir: &std::path::Path) -> Vec<serde_json::Value> {
+        event_lines(dir, "ARB_OPPORTUNITY")
+    }
+
+    fn opportunity_count(dir: &std::path::Path) -> usize {
+        opportunity_events(dir).len()
+    }
+
+    async fn detector_for(dir: &std::path::Path, market_hash: &str) -> ArbDetector {
+        let _ = std::fs::remove_dir_all(dir);
+        let mut detector = ArbDetector::build(dir, true);
+        detector.telegram_bot_token = String::new();
+        detector.oracle_lag_min_edge_pct = 0.05;
+        detector.sx_api_url = spawn_sx_markets().await;
+        *detector.gas_cache["arbitrum"].lock().await = Some((Instant::now(), 0.05));
+        detector.active_markets.write().await
+            .insert(MatchId::new(Sport::Cs2, "Alpha", "Beta"), SxMarket::new(market_hash, "L1", "Alpha", "Beta"));
+        detector
+    }
+
+    #[tokio::test]
+    async fn reported_market_is_skipped_before_oracle_lag() {
+        let dir = std::env::temp_dir().join(format!("arb_market_reported_{}", std::process::id()));
+        let detector = detector_for(&dir, "0xreported").await;
+        detector.eval_sxbet("Alpha", "Beta", "cs2", "Alpha", None).await.unwrap();
+        detector.flush_logs();
+        assert_eq!(opportunity_count(&dir), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn open_market_proceeds_to_oracle_lag() {
+        let dir = std::env::temp_dir().join(format!("arb_market_open_{}", std::process::id()));
+        let detector = detector_for(&dir, "0xopen").await;
+        detector.eval_sxbet("Alpha", "Beta", "cs2", "Alpha", None).await.unwrap();
+        detector.flush_logs();
+        assert_eq!(opportunity_count(&dir), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn sx_opportunity_carries_typed_order_request() {
+        let dir = std::env::temp_dir().join(format!("arb_market_order_{}", std::process::id()));
+        let detector = detector_for(&dir, "0xopen").await;
+        detector.eval_sxbet("Alpha", "Beta", "cs2", "Alpha", None).await.unwrap();
+        detector.flush_logs();
+        let events = opportunity_events(&dir);
+        assert_eq!(events.len(), 1);
+        let order: OrderRequest = serde_json::from_value(events[0]["order"].clone()).unwrap();
+        assert_eq!(order.venue, Venue::SxBet);
+        assert_eq!(order.chain.as_deref(), Some("arbitrum"));
+        assert_eq!(order.market_ref, "0xopen");
+        assert_eq!(order.outcome_id, "outcome_one");
+        assert_eq!(SxMarket::new("0xopen", "L1", "Alpha", "Beta").outcome_id("Beta"), "outcome_two");
+        assert_eq!(order.stake_usd, 100.0);
+        assert!(order.min_odds > 1.0);
+        assert!(order.idempotency_key.starts_with("bet-"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn observe_only_order_is_settled_as_paper_bet() {
+        let dir = std::env::temp_dir().join(format!("arb_market_paper_{}", std::process::id()));
+        let detector = detector_for(&dir, "0xopen").await.with_paper_settlement(true);
+        let resolved = MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "cs2".to_string(),
+            match_name: "alpha_vs_beta".to_string(),
+            home:       "Alpha".to_string(),
+            away:       "Beta".to_string(),
+            winner:     "Alpha".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        };
+        detector.eval_sxbet("Alpha", "Beta", "cs2", "Alpha", None).await.unwrap();
+        assert_eq!(detector.settle_paper_bets(&resolved), 1);
+        detector.flush_logs();
+        let settlement = event_lines(&dir, "PAPER_SETTLEMENT").remove(0);
+        assert_eq!(settlement["won"], true);
+        // $100 @ 0.80 → 1.25 → +25 $
+        assert!((settlement["pnl_usd"].as_f64().unwrap() - 25.0).abs() < 1e-6);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn manual_oracle_lag_event_has_no_order() {
+        let dir = std::env::temp_dir().join(format!("arb_manual_no_order_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.telegram_bot_token = String::new();
+        detector.evaluate_oracle_lag("Alpha", "Beta", "cs2", 0.80, "sx_market");
+        detector.flush_logs();
+        let events = opportunity_events(&dir);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].get("order").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn closed_reason_covers_inactive_and_settlement_window() {
+        let now = Utc::now().timestamp();
+        let open = json!({"status": "ACTIVE", "gameTime": now - 600, "reportedDate": null, "outcome": 0});
+        assert_eq!(ArbDetector::sx_market_closed_reason(&open, now), None);
+        assert_eq!(ArbDetector::sx_market_closed_reason(&json!({"status": "INACTIVE"}), now), Some("inactive"));
+        let stale = json!({"status": "ACTIVE", "gameTime": now - 13 * 3600});
+        assert_eq!(ArbDetector::sx_market_closed_reason(&stale, now), Some("past_settlement_window"));
+    }
+}
+
+#[cfg(test)]
+mod sx_timeout_tests {
+    use super::ArbDetector;
+    use std::time::{Duration, Instant};
+
+    /// Mock SX API, které odpoví až po `delay` (markety i orderbook).
+    async fn spawn_slow_sx(delay: Duration) -> String {
+        test_http::serve_async(move |_| async move {
+            tokio::time::sleep(delay).await;
+            test_http::json(r#"{"data":[{"marketHash":"0xslow","status":"ACTIVE"}]}"#)
+        }).await
+    }
+
+    #[tokio::test]
+    async fn orderbook_read_honors_tight_timeout_while_market_lookup_waits() {
+        let dir = std::env::temp_dir().join(format!("arb_sx_timeout_{}", std::process::id()));
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.sx_api_url = spawn_slow_sx(Duration::from_millis(600)).await;
+        detector.timeouts.sx_orderbook = Duration::from_millis(150);
+
+        let started = Instant::now();
+        let orders = detector.fetch_sx_orders("0xslow").await;
+        assert!(orders.is_err(), "orderbook read must give up before the slow response");
+        assert!(started.elapsed() < Duration::from_millis(500), "took {:?}", started.elapsed());
+
+        // Stejný pomalý server, ale /markets/find jede na obecném SX API timeoutu (5s)
+        let market = detector.fetch_sx_market("0xslow").await.unwrap();
+        assert_eq!(market.unwrap()["marketHash"], "0xslow");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod sx_sync_tests {
+    use super::{ArbDetector, SxMarket, SxSyncConfig};
+    use chrono::Utc;
+    use esports_core::{MatchId, Sport, UpcomingMatch};
+    use std::time::{Duration, Instant};
+
+    const LEAGUES: u64 = 40;
+
+    /// Mock SX API: /leagues → LEAGUES CS2 lig, /markets/active → po 50 ms jeden moneyline per liga.
+    async fn spawn_many_leagues() -> String {
+        test_http::serve_async(|req| async move {
+            let body = if req.raw.starts_with("GET /leagues") {
+                let leagues: Vec<_> = (1..=LEAGUES).map(|id| serde_json::json!({
+                    "leagueId": id, "label": format!("CS2 - League {}", id), "sportId": 9, "active": true,
+                })).collect();
+                serde_json::json!({"data": leagues})
+            } else {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                let id: u64 = req.raw.split("leagueId=").nth(1)
+                    .and_then(|r| r.split(|c: char| !c.is_ascii_digit()).next())
+                    .and_then(|d| d.parse().ok())
+                    .unwrap_or(0);
+                serde_json::json!({"data": {"markets": [{
+                    "type": 52,
+                    "teamOneName": format!("Home {}", id),
+                    "teamTwoName": format!("Away {}", id),
+                    "marketHash": format!("0x{}", id),
+                    "sportXeventId": format!("E{}", id),
+                }]}})
+            };
+            test_http::json(body.to_string())
+        }).await
+    }
+
+    #[tokio::test]
+    async fn many_leagues_refresh_concurrently_and_fill_cache() {
+        let dir = std::env::temp_dir().join(format!("arb_sx_sync_{}", std::process::id()));
+        let detector = ArbDetector::build(&dir, true);
+        let sx_api_url = spawn_many_leagues().await;
+        let config = SxSyncConfig { concurrency: 8, request_gap: Duration::from_millis(20), ..SxSyncConfig::default() };
+
+        let started = Instant::now();
+        let leagues = ArbDetector::sync_sx_markets_once(
+            &detector.client, &sx_api_url, &detector.active_markets, &detector.sx_event_ids, &config,
+        ).await;
+        let elapsed = started.elapsed();
+
+        assert_eq!(leagues, LEAGUES as usize);
+        assert_eq!(detector.active_markets.read().await.len(), LEAGUES as usize);
+        assert_eq!(detector.sx_event_ids.read().await.len(), LEAGUES as usize);
+        // Sekvenčně ≥ 40 × 70 ms = 2.8 s; s 8 sloty ~0.35 s
+        assert!(elapsed < Duration::from_millis(1500), "full refresh took {:?}", elapsed);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Mock SX API: jedna CS2 liga, dva moneyliny; 0xdeep má $200 na orderbooku, 0xdust $0.50 + dust order.
+    async fn spawn_liquidity_markets() -> String {
+        test_http::serve(|req| {
+            let order = |amount: &str| serde_json::json!({
+                "orderStatus": "ACTIVE", "isMakerBettingOutcomeOne": true,
+                "percentageOdds": "50000000000000000000", "originalAmount": amount, "fillAmount": "0",
+            });
+            let body = if req.raw.starts_with("GET /leagues") {
+                serde_json::json!({"data": [{"leagueId": 1, "label": "CS2 - Major", "sportId": 9, "active": true}]})
+            } else if req.raw.starts_with("GET /markets/active") {
+                serde_json::json!({"data": {"markets": [
+                    {"type": 52, "teamOneName": "Vitality", "teamTwoName": "MOUZ", "marketHash": "0xdeep", "sportXeventId": "E1"},
+                    {"type": 52, "teamOneName": "FaZe", "teamTwoName": "G2", "marketHash": "0xdust", "sportXeventId": "E2"},
+                ]}})
+            } else if req.raw.starts_with("GET /orders?marketHash=0xdeep") {
+                serde_json::json!({"data": [order("200000000000000000000")]})
+            } else {
+                serde_json::json!({"data": [order("500000000000000000"), order("10000000000000000")]})
+            };
+            test_http::json(body.to_string())
+        }).await
+    }
+
+    #[tokio::test]
+    async fn dust_liquidity_market_is_not_cached_when_floor_is_set() {
+        let dir = std::env::temp_dir().join(format!("arb_sx_liquidity_{}", std::process::id()));
+        let sx_api_url = spawn_liquidity_markets().await;
+
+        let detector = ArbDetector::build(&dir, true);
+        let config = SxSyncConfig { min_liquidity_usd: Some(50.0), request_gap: Duration::ZERO, ..SxSyncConfig::default() };
+        ArbDetector::sync_sx_markets_once(
+            &detector.client, &sx_api_url, &detector.active_markets, &detector.sx_event_ids, &config,
+        ).await;
+        let hashes: Vec<String> = detector.active_markets.read().await.values().map(|m| m.market_hash.clone()).collect();
+        assert_eq!(hashes, vec!["0xdeep".to_string()]);
+
+        // Bez floor (default) se cachuje všechno a /orders se nevolá
+        let detector = ArbDetector::build(&dir, true);
+        let config = SxSyncConfig { request_gap: Duration::ZERO, ..SxSyncConfig::default() };
+        ArbDetector::sync_sx_markets_once(
+            &detector.client, &sx_api_url, &detector.active_markets, &detector.sx_event_ids, &config,
+        ).await;
+        assert_eq!(detector.active_markets.read().await.len(), 2);
+
+        // Další kolo s floor: market, jehož likvidita je pod floor, z cache vypadne
+        let config = SxSyncConfig { min_liquidity_usd: Some(50.0), request_gap: Duration::ZERO, ..SxSyncConfig::default() };
+        ArbDetector::sync_sx_markets_once(
+            &detector.client, &sx_api_url, &detector.active_markets, &detector.sx_event_ids, &config,
+        ).await;
+        let hashes: Vec<String> = detector.active_markets.read().await.values().map(|m| m.market_hash.clone()).collect();
+        assert_eq!(hashes, vec!["0xdeep".to_string()]);
+        assert!(!detector.sx_event_ids.read().await.contains_key("E2"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn liquidity_orders_fetches_are_paced_by_request_gap() {
+        let dir = std::env::temp_dir().join(format!("arb_sx_liquidity_gap_{}", std::process::id()));
+        let sx_api_url = spawn_liquidity_markets().await;
+        let detector = ArbDetector::build(&dir, true);
+        let markets: Vec<_> = ["0xdeep", "0xdust", "0xdust2"].iter()
+            .map(|hash| (MatchId::new(Sport::Cs2, hash, "x"), SxMarket::new(hash, "", "A", "B")))
+            .collect();
+        let config = SxSyncConfig { min_liquidity_usd: Some(50.0), request_gap: Duration::from_millis(100), ..SxSyncConfig::default() };
+
+        let started = Instant::now();
+        let (liquid, below_floor) = ArbDetector::filter_liquid_markets(&detector.client, &sx_api_url, markets, &config).await;
+        // 3 /orders requesty → 3 × request_gap, ne burst
+        assert!(started.elapsed() >= Duration::from_millis(300), "orders fetch took {:?}", started.elapsed());
+        assert_eq!((liquid.len(), below_floor.len()), (1, 2));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn prewarm_applies_liquidity_floor() {
+        let dir = std::env::temp_dir().join(format!("arb_sx_prewarm_liquidity_{}", std::process::id()));
+        let mut detector = ArbDetector::build(&dir, true);
+        detector.sx_api_url = spawn_liquidity_markets().await;
+        detector.sx_sync.min_liquidity_usd = Some(50.0);
+
+        let upcoming: Vec<UpcomingMatch> = [("Vitality", "MOUZ"), ("FaZe", "G2")].iter()
+            .map(|(home, away)| UpcomingMatch {
+                id:         MatchId::new(Sport::Cs2, home, away),
+                home:       home.to_string(),
+                away:       away.to_string(),
+                start_time: Utc::now(),
+                source_match_id: None,
+            })
+            .collect();
+        assert_eq!(detector.prewarm_markets(&upcoming).await, 1);
+        let hashes: Vec<String> = detector.active_markets.read().await.values().map(|m| m.market_hash.clone()).collect();
+        assert_eq!(hashes, vec!["0xdeep".to_string()]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod sx_fill_tests {
+    use super::{ArbDetector, SxMarket};
+
+    fn market() -> SxMarket {
+        SxMarket::new("0xfill", "L1", "Alpha", "Beta")
+    }
+
+    /// Jeden ACTIVE maker order na Alpha @ 0.60 s `size_usd` volného objemu.
+    fn orders(size_usd: u64) -> serde_json::Value {
+        serde_json::json!({"data": [{
+            "orderStatus": "ACTIVE",
+            "isMakerBettingOutcomeOne": true,
+            "percentageOdds": "60000000000000000000",
+            "originalAmount": format!("{}000000000000000000", size_usd),
+            "fillAmount": "0"
+        }]})
+    }
+
+    #[test]
+    fn tiny_partial_fill_is_insufficient_liquidity() {
+        assert_eq!(ArbDetector::sx_fill_prob(&orders(3), &market(), "Alpha", 100.0, 0.8), 1.0);
+        assert_eq!(ArbDetector::sx_fill_prob(&orders(79), &market(), "Alpha", 100.0, 0.8), 1.0);
+    }
+
+    #[test]
+    fn partial_fill_above_ratio_prices_the_edge() {
+        let prob = ArbDetector::sx_fill_prob(&orders(85), &market(), "Alpha", 100.0, 0.8);
+        assert!((prob - 0.60).abs() < 1e-9);
+        // Plný fill beze změny
+        let full = ArbDetector::sx_fill_prob(&orders(500), &market(), "Alpha", 100.0, 0.8);
+        assert!((full - 0.60).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod telegram_broadcast_tests {
+    use super::{broadcast_telegram, parse_telegram_chat_ids, TelegramFormat};
+    use reqwest::Client;
+    use std::sync::{Arc, Mutex};
+
+    /// Mock Bot API: zapisuje těla sendMessage; chat "-100broken" dostane 403.
+    async fn spawn_telegram() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let url = test_http::serve(move |req| {
+            let Ok(body) = serde_json::from_str::<serde_json::Value>(req.body()) else {
+                return test_http::response("400 Bad Request", &[], "");
+            };
+            let status = if body["chat_id"] == "-100broken" { "403 Forbidden" } else { "200 OK" };
+            sink.lock().unwrap().push(body);
+            test_http::response(status, &["Content-Type: application/json"], r#"{"ok":true}"#)
+        }).await;
+        (url, received)
+    }
+
+    #[test]
+    fn chat_id_list_is_split_trimmed_and_deduplicated() {
+        assert_eq!(parse_telegram_chat_ids("6458129071"), vec!["6458129071"]);
+        assert_eq!(parse_telegram_chat_ids(" 111, -100222 ,,111 "), vec!["111", "-100222"]);
+        assert!(parse_telegram_chat_ids(" , ").is_empty());
+    }
+
+    #[tokio::test]
+    async fn every_chat_receives_the_alert_even_if_one_fails() {
+        let (url, received) = spawn_telegram().await;
+        let chat_ids = parse_telegram_chat_ids("-100broken,111");
+
+        let results = broadcast_telegram(&Client::new(), &url, "token", &chat_ids, "⏱️ ORACLE LAG", TelegramFormat::Plain).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "-100broken");
+        assert!(results[0].1.is_err());
+        assert_eq!(results[1].0, "111");
+        assert!(results[1].1.is_ok());
+
+        let mut chats: Vec<String> = received.lock().unwrap().iter()
+            .inspect(|body| assert_eq!(body["text"], "⏱️ ORACLE LAG"))
+            .map(|body| body["chat_id"].as_str().unwrap().to_string())
+            .collect();
+        chats.sort();
+        assert_eq!(chats, vec!["-100broken", "111"]);
+    }
+
+    #[tokio::test]
+    async fn html_format_sets_parse_mode_without_preview() {
+        let (url, received) = spawn_telegram().await;
+        let chat_ids = parse_telegram_chat_ids("111");
+
+        let results = broadcast_telegram(&Client::new(), &url, "token", &chat_ids, "<b>EDGE</b>", TelegramFormat::Html).await;
+
+        assert!(results[0].1.is_ok());
+        let body = received.lock().unwrap()[0].clone();
+        assert_eq!(body["parse_mode"], "HTML");
+        assert_eq!(body["disable_web_page_preview"], true);
     }
 }
@@ -0,0 +1,280 @@
+//! Paper settlement pro observe-only režim: PreResolution příležitost = simulovaná sázka,
+//! MATCH_RESOLVED ji vyhodnotí (výhra / prohra podle vítěze) a připíše do paper P&L.
+//! Dry-run sázky jiných procesů (alert-bot score edge) chodí přes inbox `paper_bets.jsonl` v log dir —
+//! vítěze zná jen live-observer. Po restartu se otevřené sázky a P&L obnoví z inboxu a zalogovaných
+//! PAPER_SETTLEMENT eventů (`PaperBook::restore`).
+
+use esports_core::match_id::{team_names_equivalent, MatchId};
+use logger::EventReader;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Soubor inboxu paper sázek v log dir.
+pub const PAPER_BETS_INBOX: &str = "paper_bets.jsonl";
+
+/// Otevřená paper sázka na tým `side` za cenu `price` (implied prob z orderbooku).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperBet {
+    pub match_id:  MatchId,
+    pub home:      String,
+    pub away:      String,
+    pub side:      String,
+    pub market_id: String,
+    pub stake_usd: f64,
+    pub price:     f64,
+}
+
+impl PaperBet {
+    /// Decimal kurz odpovídající `price`.
+    pub fn odds(&self) -> f64 {
+        1.0 / self.price
+    }
+}
+
+/// Řádek inboxu: dry-run sázka zapsaná jiným procesem.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaperBetLine {
+    pub ts:        String,
+    /// Kdo sázku zapsal ("alert_bot_score_edge")
+    pub source:    String,
+    pub sport:     String,
+    pub home:      String,
+    pub away:      String,
+    pub side:      String,
+    pub market_id: String,
+    pub stake_usd: f64,
+    pub price:     f64,
+}
+
+impl PaperBetLine {
+    /// None = sport, který MatchId nezná (MATCH_RESOLVED na něj nepřijde).
+    fn into_bet(self) -> Option<PaperBet> {
+        Some(PaperBet {
+            match_id:  MatchId::from_labels(&self.sport, &self.home, &self.away)?,
+            home:      self.home,
+            away:      self.away,
+            side:      self.side,
+            market_id: self.market_id,
+            stake_usd: self.stake_usd,
+            price:     self.price,
+        })
+    }
+}
+
+/// Připíše sázku do inboxu v `log_dir` jedním zápisem (append → řádky víc zapisovatelů se neproloží).
+pub fn append_paper_bet(log_dir: &Path, line: &PaperBetLine) -> std::io::Result<()> {
+    std::fs::create_dir_all(log_dir)?;
+    let mut record = serde_json::to_string(line).map_err(std::io::Error::other)?;
+    record.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_dir.join(PAPER_BETS_INBOX))?
+        .write_all(record.as_bytes())
+}
+
+/// Čtecí pozice v inboxu. Začíná na začátku souboru — první `read_new` vrátí i sázky z minulých běhů,
+/// už vyhodnocené z nich odfiltruje `PaperBook::restore`.
+#[derive(Debug)]
+pub struct PaperInbox {
+    path:   PathBuf,
+    offset: u64,
+}
+
+impl PaperInbox {
+    pub fn new(log_dir: &Path) -> Self {
+        Self { path: log_dir.join(PAPER_BETS_INBOX), offset: 0 }
+    }
+
+    /// Kompletní řádky zapsané od minulého čtení; rozepsaný poslední řádek počká na další čtení.
+    pub fn read_new(&mut self) -> Vec<PaperBet> {
+        let Ok(mut file) = File::open(&self.path) else { return Vec::new() };
+        if file.metadata().is_ok_and(|m| m.len() < self.offset) {
+            self.offset = 0; // soubor někdo zkrátil / smazal a založil znovu
+        }
+        let mut buf = String::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err() || file.read_to_string(&mut buf).is_err() {
+            return Vec::new();
+        }
+        let complete = buf.rfind('\n').map_or(0, |i| i + 1);
+        self.offset += complete as u64;
+        buf[..complete].lines()
+            .filter_map(|l| serde_json::from_str::<PaperBetLine>(l).ok())
+            .filter_map(PaperBetLine::into_bet)
+            .collect()
+    }
+}
+
+/// Vyhodnocená sázka: `pnl_usd` = čistý zisk (výhra) nebo −stake (prohra).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperSettlement {
+    pub bet:     PaperBet,
+    pub won:     bool,
+    pub pnl_usd: f64,
+}
+
+/// Otevřené paper sázky + kumulativní P&L. Na jeden zápas a stranu jen jedna sázka —
+/// live value vyhodnocuje stejný edge každý cyklus a opakování by P&L jen násobilo.
+#[derive(Debug, Default)]
+pub struct PaperBook {
+    open:    Vec<PaperBet>,
+    pnl_usd: f64,
+    settled: usize,
+    won:     usize,
+}
+
+impl PaperBook {
+    /// Stav po restartu: P&L a bilance z PAPER_SETTLEMENT eventů v `log_dir`, otevřené sázky = `inbox`
+    /// bez zápasů, které už PAPER_SETTLEMENT mají (MATCH_RESOLVED na zápas přijde jen jednou).
+    pub fn restore(log_dir: &Path, inbox: Vec<PaperBet>) -> Self {
+        let mut book = Self::default();
+        let mut closed: Vec<MatchId> = Vec::new();
+        for ev in EventReader::new(log_dir).read_all("PAPER_SETTLEMENT").unwrap_or_default() {
+            let text = |key: &str| ev.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+            book.pnl_usd += ev.get("pnl_usd").and_then(Value::as_f64).unwrap_or(0.0);
+            book.settled += 1;
+            if ev.get("won").and_then(Value::as_bool) == Some(true) {
+                book.won += 1;
+            }
+            if let Some(id) = MatchId::from_labels(&text("sport"), &text("home"), &text("away")) {
+                closed.push(id);
+            }
+        }
+        for bet in inbox {
+            if !closed.iter().any(|id| id.same_match(&bet.match_id)) {
+                book.place(bet);
+            }
+        }
+        book
+    }
+
+    /// Vrací false, pokud na stejný zápas a stranu už sázka je (nebo je cena mimo (0, 1)).
+    pub fn place(&mut self, bet: PaperBet) -> bool {
+        if !(bet.price > 0.0 && bet.price < 1.0) {
+            return false;
+        }
+        let duplicate = self.open.iter()
+            .any(|b| b.match_id.same_match(&bet.match_id) && team_names_equivalent(&b.side, &bet.side));
+        if duplicate {
+            return false;
+        }
+        self.open.push(bet);
+        true
+    }
+
+    /// Uzavře všechny otevřené sázky na zápas `id` proti vítězi `winner`.
+    pub fn settle(&mut self, id: &MatchId, winner: &str) -> Vec<PaperSettlement> {
+        let (matching, rest): (Vec<PaperBet>, Vec<PaperBet>) = std::mem::take(&mut self.open)
+            .into_iter()
+            .partition(|b| b.match_id.same_match(id));
+        self.open = rest;
+
+        matching.into_iter().map(|bet| {
+            let won = team_names_equivalent(&bet.side, winner);
+            let pnl_usd = if won { bet.stake_usd * (bet.odds() - 1.0) } else { -bet.stake_usd };
+            self.pnl_usd += pnl_usd;
+            self.settled += 1;
+            if won {
+                self.won += 1;
+            }
+            PaperSettlement { bet, won, pnl_usd }
+        }).collect()
+    }
+
+    pub fn pnl_usd(&self) -> f64 {
+        self.pnl_usd
+    }
+
+    pub fn open_bets(&self) -> usize {
+        self.open.len()
+    }
+
+    /// (vyhodnocené, vyhrané)
+    pub fn record(&self) -> (usize, usize) {
+        (self.settled, self.won)
+    }
+}
+
+#[cfg(test)]
+mod paper_book_tests {
+    use super::{append_paper_bet, PaperBet, PaperBetLine, PaperBook, PaperInbox};
+    use esports_core::{MatchId, Sport};
+
+    fn bet(side: &str, price: f64) -> PaperBet {
+        PaperBet {
+            match_id:  MatchId::new(Sport::Cs2, "Team Vitality", "G2 Esports"),
+            home:      "Team Vitality".to_string(),
+            away:      "G2 Esports".to_string(),
+            side:      side.to_string(),
+            market_id: "0xm".to_string(),
+            stake_usd: 100.0,
+            price,
+        }
+    }
+
+    #[test]
+    fn settles_both_sides_against_winner_with_other_spelling() {
+        let mut book = PaperBook::default();
+        assert!(book.place(bet("Team Vitality", 0.50)));
+        assert!(!book.place(bet("Vitality", 0.45)), "stejná strana podruhé neotevře");
+        assert!(book.place(bet("G2 Esports", 0.40)));
+        assert!(!book.place(bet("G2 Esports", 1.0)));
+
+        // Jiný zápas se nevyhodnotí
+        assert!(book.settle(&MatchId::new(Sport::Cs2, "NAVI", "FaZe"), "NAVI").is_empty());
+        assert_eq!(book.open_bets(), 2);
+
+        let settled = book.settle(&MatchId::new(Sport::Cs2, "g2", "vitality"), "Vitality");
+        assert_eq!(settled.len(), 2);
+        assert!(settled[0].won);
+        assert!((settled[0].pnl_usd - 100.0).abs() < 1e-9);
+        assert!(!settled[1].won);
+        assert!((settled[1].pnl_usd + 100.0).abs() < 1e-9);
+        assert!(book.pnl_usd().abs() < 1e-9);
+        assert_eq!(book.record(), (2, 1));
+        assert_eq!(book.open_bets(), 0);
+    }
+
+    fn inbox_line(home: &str, away: &str, side: &str) -> PaperBetLine {
+        PaperBetLine {
+            ts:        "2026-03-01T18:00:00Z".to_string(),
+            source:    "alert_bot_score_edge".to_string(),
+            sport:     "cs2".to_string(),
+            home:      home.to_string(),
+            away:      away.to_string(),
+            side:      side.to_string(),
+            market_id: "0xcond".to_string(),
+            stake_usd: 10.0,
+            price:     0.50,
+        }
+    }
+
+    #[test]
+    fn restore_reopens_unsettled_inbox_bets_and_keeps_pnl() {
+        let dir = std::env::temp_dir().join(format!("arb_paper_restore_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        append_paper_bet(&dir, &inbox_line("Team Vitality", "G2 Esports", "Vitality")).unwrap();
+        append_paper_bet(&dir, &inbox_line("NAVI", "FaZe", "FaZe")).unwrap();
+        std::fs::write(dir.join("2026-03-01.jsonl"), concat!(
+            r#"{"event":"PAPER_SETTLEMENT","sport":"cs2","home":"Team Vitality","away":"G2 Esports","side":"Vitality","winner":"G2","won":false,"pnl_usd":-10.0}"#, "\n",
+            r#"{"event":"PAPER_SETTLEMENT","sport":"cs2","home":"Spirit","away":"MOUZ","side":"Spirit","winner":"Spirit","won":true,"pnl_usd":4.5}"#, "\n",
+        )).unwrap();
+
+        let mut inbox = PaperInbox::new(&dir);
+        let mut book = PaperBook::restore(&dir, inbox.read_new());
+
+        assert_eq!(book.open_bets(), 1, "Vitality vs G2 už má PAPER_SETTLEMENT");
+        assert_eq!(book.record(), (2, 1));
+        assert!((book.pnl_usd() + 5.5).abs() < 1e-9);
+        assert!(inbox.read_new().is_empty(), "restore spotřeboval celý inbox");
+
+        let settled = book.settle(&MatchId::new(Sport::Cs2, "FaZe", "NAVI"), "FaZe");
+        assert_eq!(settled.len(), 1);
+        assert!((book.pnl_usd() - 4.5).abs() < 1e-9);
+        assert_eq!(book.record(), (3, 2));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,74 @@
+//! Bezpečný převod SX Bet / EVM fixed-point stringů na f64.
+//!
+//! SX posílá `percentageOdds` v 10^20 škále (50 % = "50000000000000000000") a částky v base
+//! jednotkách s 18 desetinnými místy. Parsuje se přes u128 s kontrolou formátu a overflow —
+//! malformed vstup vrací None, nikdy panic ani tichou ztrátu přesnosti přes f64 parse.
+
+/// Škála `percentageOdds`: 10^20 = 100 %.
+pub const SX_ODDS_DECIMALS: u32 = 20;
+/// Desetinná místa částek (`originalAmount`, `fillAmount`) a wei.
+pub const WEI_DECIMALS: u32 = 18;
+
+/// Celé číslo v base jednotkách → f64 s `decimals` desetinnými místy.
+/// Celá a zlomková část se převádí zvlášť, aby velké hodnoty neztratily desetinnou část.
+pub fn wei_to_decimal(value: u128, decimals: u32) -> Option<f64> {
+    let scale = 10u128.checked_pow(decimals)?;
+    Some((value / scale) as f64 + (value % scale) as f64 / scale as f64)
+}
+
+/// Decimální string base jednotek ("95000000000000000000") → f64. Jen ASCII číslice (okolní whitespace se ořízne).
+pub fn parse_wei_decimal(s: &str, decimals: u32) -> Option<f64> {
+    wei_to_decimal(parse_base_units(s)?, decimals)
+}
+
+/// SX `percentageOdds` → implied pravděpodobnost 0.0–1.0; nad 10^20 (100 %) = None.
+/// Rozsah se hlídá na celém čísle — těsně nad 100 % by se v f64 zaokrouhlilo na 1.0.
+pub fn parse_sx_prob(s: &str) -> Option<f64> {
+    let value = parse_base_units(s)?;
+    if value > 10u128.pow(SX_ODDS_DECIMALS) {
+        return None;
+    }
+    wei_to_decimal(value, SX_ODDS_DECIMALS)
+}
+
+fn parse_base_units(s: &str) -> Option<u128> {
+    let s = s.trim();
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse::<u128>().ok()
+}
+
+#[cfg(test)]
+mod sx_numeric_tests {
+    use super::{parse_sx_prob, parse_wei_decimal, wei_to_decimal, WEI_DECIMALS};
+
+    #[test]
+    fn parses_exact_sx_strings() {
+        assert_eq!(parse_sx_prob("95000000000000000000"), Some(0.95));
+        assert_eq!(parse_sx_prob("50000000000000000000"), Some(0.5));
+        assert_eq!(parse_sx_prob("100000000000000000000"), Some(1.0));
+        assert_eq!(parse_sx_prob("0"), Some(0.0));
+        let p = parse_sx_prob("52631578947368421052").unwrap();
+        assert!((p - 0.526_315_789_473_684_2).abs() < 1e-15);
+
+        assert_eq!(parse_wei_decimal("100000000000000000000", WEI_DECIMALS), Some(100.0));
+        assert_eq!(parse_wei_decimal("250000000000000000", WEI_DECIMALS), Some(0.25));
+        assert_eq!(parse_wei_decimal("1", WEI_DECIMALS), Some(1e-18));
+        // u128::MAX je platný vstup, jen velký
+        assert!(parse_wei_decimal(&u128::MAX.to_string(), WEI_DECIMALS).is_some_and(|v| v > 3.4e20));
+    }
+
+    #[test]
+    fn malformed_inputs_yield_none() {
+        for bad in ["", "   ", "-1", "+5", "1.5", "0x10", "1e18", "95 000", "abc", "NaN",
+                    "340282366920938463463374607431768211456"] {
+            assert_eq!(parse_wei_decimal(bad, WEI_DECIMALS), None, "{:?}", bad);
+            assert_eq!(parse_sx_prob(bad), None, "{:?}", bad);
+        }
+        // Nad 100 % není pravděpodobnost
+        assert_eq!(parse_sx_prob("100000000000000000001"), None);
+        // 10^39 se do u128 nevejde
+        assert_eq!(wei_to_decimal(1, 39), None);
+    }
+}
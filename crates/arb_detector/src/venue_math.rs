@@ -0,0 +1,247 @@
+//! Čistá venue matematika pro `eval_sxbet` / `eval_azuro` — fill orderbooku, skluz, gas a net edge
+//! bez sítě a logování, aby šla ověřit nad uloženými odpověďmi API.
+
+use crate::sx_numeric::{parse_sx_prob, parse_wei_decimal, WEI_DECIMALS};
+use esports_core::match_id::normalize_team;
+
+/// Net edge po gasu musí být nad 1 %, jinak se arb nevyplatí (striktní pravidlo ze specifikace).
+pub const MIN_NET_EDGE: f64 = 0.01;
+/// Azuro LP posune kurz normálního esport marketu při $100 sázce zhruba o 1.5 p.b.
+pub const AZURO_SLIPPAGE_PENALTY: f64 = 0.015;
+
+/// Výsledek simulace jedné venue pro sázku `stake_usd`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VenueEdge {
+    /// Průměrná implied prob po skluzu; 1.0 = nedostatečný objem / žádný market
+    pub best_prob:  f64,
+    /// Kolik USD ze sázky orderbook / pool pokryl
+    pub filled_usd: f64,
+    pub gas_usd:    f64,
+    /// (1 − best_prob) − gas / stake
+    pub net_edge:   f64,
+    /// Je objem a net edge > MIN_NET_EDGE
+    pub profitable: bool,
+}
+
+/// SX i Azuro vrací stejnou strukturu; alias drží název z orderbook kontextu.
+pub type SxEdgeResult = VenueEdge;
+
+impl VenueEdge {
+    fn new(best_prob: f64, filled_usd: f64, stake_usd: f64, gas_usd: f64) -> Self {
+        let gas_fee_pct = if stake_usd > 0.0 { gas_usd / stake_usd } else { 0.0 };
+        let net_edge = (1.0 - best_prob) - gas_fee_pct;
+        Self {
+            best_prob,
+            filled_usd,
+            gas_usd,
+            net_edge,
+            profitable: best_prob < 1.0 && net_edge > MIN_NET_EDGE,
+        }
+    }
+
+    /// false = orderbook / pool nepokryl sázku (best_prob 1.0)
+    pub fn has_volume(&self) -> bool {
+        self.best_prob < 1.0
+    }
+}
+
+/// Simulovaný průstřel SX orderbooku za `target_usd` na tým `side` → (průměrná implied prob, fill v USD).
+/// Prob 1.0 = nedostatečný objem (fill pod `min_fill_ratio` × `target_usd`).
+/// Outcome one = `home` (stejný předpoklad jako cache podle MatchResolvedEvent).
+pub fn sx_fill(orders: &serde_json::Value, home: &str, away: &str, side: &str, target_usd: f64, min_fill_ratio: f64) -> (f64, f64) {
+    // Pro zjištění reálného skluzu na orderbooku nasebíráme všechny nabídnuté limitní příkazy
+    // a budeme je "vykupovat" od nejlepšího, dokud nenaplníme náš testovací budget.
+    let mut available_orders = active_sx_orders(orders, home, away, Some(side));
+
+    // Seřadit od nejmenší pravděpodobnosti po největší (my chceme KOUPOVAT za co nejmenší implikovanou pravděpodobnost čili nejvyšší kurz)
+    available_orders.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // Simulace orderbook fill
+    let mut accumulated_size = 0.0;
+    let mut weighted_prob_sum = 0.0;
+
+    for (prob, size) in available_orders {
+        let remaining = target_usd - accumulated_size;
+        if remaining <= 0.0 { break; }
+
+        let fill = f64::min(remaining, size);
+        accumulated_size += fill;
+        weighted_prob_sum += prob * fill;
+    }
+
+    if accumulated_size > 0.0 && accumulated_size >= target_usd * min_fill_ratio {
+        (weighted_prob_sum / accumulated_size, accumulated_size)
+    } else {
+        (1.0, accumulated_size) // no / insufficient volume
+    }
+}
+
+/// ACTIVE limitní příkazy orderbooku jako (implied prob, zbývající objem v USD), bez dust orderů.
+/// `side` = jen ordery na daný tým (outcome one = `home`), None = oba outcomy.
+fn active_sx_orders(orders: &serde_json::Value, home: &str, away: &str, side: Option<&str>) -> Vec<(f64, f64)> {
+    let mut available_orders: Vec<(f64, f64)> = Vec::new(); // (dec_prob, volume_usd)
+
+    if let Some(orders_arr) = orders.pointer("/data").and_then(|d| d.as_array()) {
+        for order in orders_arr {
+            let status = order.pointer("/orderStatus").and_then(|s| s.as_str()).unwrap_or("");
+            if status != "ACTIVE" { continue; }
+
+            // Determine whose bet this is - MakerOutcomeOne
+            let is_t1 = order.pointer("/isMakerBettingOutcomeOne").and_then(|b| b.as_bool()).unwrap_or(false);
+            let order_winner = if is_t1 { normalize_team(home) } else { normalize_team(away) };
+
+            // My chceme vzít BUY objednávku na YES pro 'side'.
+            // Zjednodusime - SX Bet nabizi kurzy makeru, taker sází proti nim
+            if side.is_none_or(|side| order_winner.contains(&normalize_team(side))) {
+                let prob_str = order.pointer("/percentageOdds").and_then(|s| s.as_str()).unwrap_or("0");
+                let fill_amt_str = order.pointer("/fillAmount").and_then(|s| s.as_str()).unwrap_or("0");
+                let orig_amt_str = order.pointer("/originalAmount").and_then(|s| s.as_str()).unwrap_or("0");
+
+                // percentageOdds v 10^20 škále (95000000000000000000 -> 0.95), částky v 18 decimals
+                if let (Some(dec_prob), Some(orig), Some(fill)) = (
+                    parse_sx_prob(prob_str),
+                    parse_wei_decimal(orig_amt_str, WEI_DECIMALS),
+                    parse_wei_decimal(fill_amt_str, WEI_DECIMALS),
+                ) {
+                    // Remaining volume na tomto limitním příkazu (fill > original = rozbitý order → 0)
+                    let size_usd = (orig - fill).max(0.0);
+
+                    if dec_prob > 0.01 && size_usd > 0.05 { // ignoruj dust orders
+                        available_orders.push((dec_prob, size_usd));
+                    }
+                }
+            }
+        }
+    }
+    available_orders
+}
+
+/// Celkový zbývající objem ACTIVE orderů na obou outcomech v USD — odhad likvidity marketu
+/// pro filtr cache v SX syncu (SX_SYNC_MIN_LIQUIDITY_USD).
+pub fn sx_liquidity_usd(orders: &serde_json::Value) -> f64 {
+    active_sx_orders(orders, "", "", None).iter().map(|(_, size)| size).sum()
+}
+
+/// SX edge pro vítěze `side`: fill orderbooku za `stake_usd` + Arbitrum gas.
+pub fn compute_sx_edge(
+    orders: &serde_json::Value,
+    home: &str,
+    away: &str,
+    side: &str,
+    stake_usd: f64,
+    min_fill_ratio: f64,
+    gas_usd: f64,
+) -> SxEdgeResult {
+    let (best_prob, filled_usd) = sx_fill(orders, home, away, side, stake_usd, min_fill_ratio);
+    VenueEdge::new(best_prob, filled_usd, stake_usd, gas_usd)
+}
+
+/// Azuro edge z TheGraph `games` odpovědi: nejlepší `currentOdds` vítěze (index 0 = home) napříč
+/// podmínkami zápasu + AMM skluz, Polygon gas. Pool pokryje celou sázku, nebo nic.
+pub fn compute_azuro_edge(
+    games: &serde_json::Value,
+    home: &str,
+    away: &str,
+    winner: &str,
+    stake_usd: f64,
+    gas_usd: f64,
+) -> VenueEdge {
+    let mut best_prob = 1.0;
+    let target_idx = if winner.to_lowercase() == home.to_lowercase() { 0 } else { 1 };
+    let away_key = normalize_team(away);
+
+    for game in games.pointer("/data/games").and_then(|g| g.as_array()).into_iter().flatten() {
+        let title = game.pointer("/title").and_then(|t| t.as_str()).unwrap_or("").to_lowercase();
+        if !title.contains(&away_key) {
+            continue;
+        }
+        for condition in game.pointer("/conditions").and_then(|c| c.as_array()).into_iter().flatten() {
+            let odds = condition.pointer("/outcomes").and_then(|o| o.as_array())
+                .and_then(|outcomes| outcomes.get(target_idx))
+                .and_then(|outcome| outcome.pointer("/currentOdds").and_then(|o| o.as_str()))
+                .and_then(|odds| odds.parse::<f64>().ok());
+            if let Some(odds) = odds {
+                let prob_after_slippage = 1.0 / odds + AZURO_SLIPPAGE_PENALTY;
+                if prob_after_slippage < best_prob && prob_after_slippage > 0.01 {
+                    best_prob = prob_after_slippage;
+                }
+            }
+        }
+    }
+
+    let filled_usd = if best_prob < 1.0 { stake_usd } else { 0.0 };
+    VenueEdge::new(best_prob, filled_usd, stake_usd, gas_usd)
+}
+
+#[cfg(test)]
+mod venue_math_tests {
+    use super::{compute_azuro_edge, compute_sx_edge, MIN_NET_EDGE};
+
+    /// Zachycená /orders odpověď: Vitality (outcome one) má 3 ordery, G2 jeden, jeden order je už zrušený.
+    const SX_ORDERS: &str = r#"{"status":"success","data":[
+        {"orderHash":"0xa1","marketHash":"0xm","orderStatus":"ACTIVE","isMakerBettingOutcomeOne":true,
+         "percentageOdds":"82000000000000000000","originalAmount":"40000000000000000000","fillAmount":"10000000000000000000"},
+        {"orderHash":"0xa2","marketHash":"0xm","orderStatus":"ACTIVE","isMakerBettingOutcomeOne":true,
+         "percentageOdds":"85000000000000000000","originalAmount":"120000000000000000000","fillAmount":"0"},
+        {"orderHash":"0xa3","marketHash":"0xm","orderStatus":"INACTIVE","isMakerBettingOutcomeOne":true,
+         "percentageOdds":"50000000000000000000","originalAmount":"500000000000000000000","fillAmount":"0"},
+        {"orderHash":"0xb1","marketHash":"0xm","orderStatus":"ACTIVE","isMakerBettingOutcomeOne":false,
+         "percentageOdds":"20000000000000000000","originalAmount":"15000000000000000000","fillAmount":"0"}
+    ]}"#;
+
+    #[test]
+    fn sx_orderbook_walk_prices_slippage_and_gas() {
+        let orders: serde_json::Value = serde_json::from_str(SX_ORDERS).unwrap();
+        let edge = compute_sx_edge(&orders, "Vitality", "G2", "Vitality", 100.0, 0.8, 0.05);
+
+        // $30 @ 0.82 + $70 @ 0.85 → 0.841; gas 0.05 % stake
+        assert!((edge.best_prob - 0.841).abs() < 1e-9);
+        assert!((edge.filled_usd - 100.0).abs() < 1e-9);
+        assert!((edge.net_edge - (0.159 - 0.0005)).abs() < 1e-9);
+        assert!(edge.profitable);
+    }
+
+    #[test]
+    fn thin_side_has_no_volume_and_is_not_profitable() {
+        let orders: serde_json::Value = serde_json::from_str(SX_ORDERS).unwrap();
+        let edge = compute_sx_edge(&orders, "Vitality", "G2", "G2", 100.0, 0.8, 0.05);
+
+        assert_eq!(edge.best_prob, 1.0);
+        assert!((edge.filled_usd - 15.0).abs() < 1e-9);
+        assert!(!edge.has_volume() && !edge.profitable);
+    }
+
+    #[test]
+    fn gas_can_eat_a_thin_edge() {
+        let orders = serde_json::json!({"data": [{
+            "orderStatus": "ACTIVE", "isMakerBettingOutcomeOne": true,
+            "percentageOdds": "98500000000000000000", "originalAmount": "100000000000000000000", "fillAmount": "0"
+        }]});
+        let edge = compute_sx_edge(&orders, "Vitality", "G2", "Vitality", 100.0, 0.8, 0.60);
+
+        assert!(edge.has_volume());
+        assert!(edge.net_edge < MIN_NET_EDGE);
+        assert!(!edge.profitable);
+    }
+
+    #[test]
+    fn azuro_takes_best_condition_with_amm_slippage() {
+        let games = serde_json::json!({"data": {"games": [
+            {"id": "1", "title": "Vitality - G2", "conditions": [
+                {"outcomes": [{"outcomeId": "29", "currentOdds": "1.25"}, {"outcomeId": "30", "currentOdds": "3.6"}], "margin": "0.05"},
+                {"outcomes": [{"outcomeId": "29", "currentOdds": "1.3"}, {"outcomeId": "30", "currentOdds": "3.4"}], "margin": "0.05"}
+            ]},
+            {"id": "2", "title": "Vitality - FaZe", "conditions": [
+                {"outcomes": [{"outcomeId": "29", "currentOdds": "5.0"}, {"outcomeId": "30", "currentOdds": "1.1"}]}
+            ]}
+        ]}});
+        let edge = compute_azuro_edge(&games, "Vitality", "G2", "Vitality", 100.0, 0.01);
+
+        assert!((edge.best_prob - (1.0 / 1.3 + 0.015)).abs() < 1e-9);
+        assert_eq!(edge.filled_usd, 100.0);
+        assert!(edge.profitable);
+
+        let none = compute_azuro_edge(&serde_json::json!({"data": {"games": []}}), "Vitality", "G2", "Vitality", 100.0, 0.01);
+        assert!(!none.has_volume() && none.filled_usd == 0.0 && !none.profitable);
+    }
+}
@@ -0,0 +1,70 @@
+//! Typované chyby fetch helperů — volající matchují varianty místo substringů v anyhow zprávách.
+
+use reqwest::StatusCode;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrapeError {
+    /// Ne-2xx odpověď (kromě 404 → NotFound)
+    Http(StatusCode),
+    /// 2xx, ale tělo je Cloudflare challenge stránka
+    Challenge,
+    Timeout,
+    /// Tělo nejde přečíst / rozparsovat
+    Parse(String),
+    NotFound,
+    /// Headless Chrome fallback selhal (launch, navigace, cooldown)
+    Browser(String),
+    /// Síťová chyba bez HTTP odpovědi (DNS, connect, TLS)
+    Transport(String),
+}
+
+impl ScrapeError {
+    /// 403 / challenge → má smysl zkusit headless browser
+    pub fn needs_browser_fallback(&self) -> bool {
+        matches!(self, ScrapeError::Http(StatusCode::FORBIDDEN) | ScrapeError::Challenge)
+    }
+
+    /// 401/403 — zdroj vyžaduje token / blokuje nás, retry nemá smysl hned
+    pub fn is_auth_block(&self) -> bool {
+        matches!(self, ScrapeError::Http(StatusCode::FORBIDDEN) | ScrapeError::Http(StatusCode::UNAUTHORIZED))
+    }
+
+    pub fn from_status(status: StatusCode) -> Self {
+        if status == StatusCode::NOT_FOUND {
+            ScrapeError::NotFound
+        } else {
+            ScrapeError::Http(status)
+        }
+    }
+}
+
+impl From<reqwest::Error> for ScrapeError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ScrapeError::Timeout
+        } else if let Some(status) = err.status() {
+            ScrapeError::from_status(status)
+        } else if err.is_decode() || err.is_body() {
+            ScrapeError::Parse(err.to_string())
+        } else {
+            ScrapeError::Transport(err.to_string())
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeError::Http(status) => write!(f, "HTTP {}", status),
+            ScrapeError::Challenge => write!(f, "Cloudflare challenge page"),
+            ScrapeError::Timeout => write!(f, "request timed out"),
+            ScrapeError::Parse(msg) => write!(f, "parse error: {}", msg),
+            ScrapeError::NotFound => write!(f, "not found (404)"),
+            ScrapeError::Browser(msg) => write!(f, "browser fallback failed: {}", msg),
+            ScrapeError::Transport(msg) => write!(f, "transport error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {}
@@ -0,0 +1,23 @@
+//! Sdílené typy esports zápasů a chyba fetch helperů bez scraping závislostí (headless Chrome, WebSockety) —
+//! arb_detector a binárky je berou odsud, `esports_monitor` je re-exportuje.
+
+use chrono::{DateTime, Utc};
+
+pub mod error;
+pub mod match_id;
+pub mod timeouts;
+pub use error::ScrapeError;
+pub use match_id::MatchId;
+pub use prediction_engine::Sport;
+pub use timeouts::HttpTimeouts;
+
+/// Nadcházející zápas z rozpisu (LoL getSchedule) — arb detector si pro něj předem nacachuje SX market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpcomingMatch {
+    pub id:         MatchId,
+    pub home:       String,
+    pub away:       String,
+    pub start_time: DateTime<Utc>,
+    /// Riot `/match/id` — stejné ID pak nese MATCH_RESOLVED
+    pub source_match_id: Option<String>,
+}
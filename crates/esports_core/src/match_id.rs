@@ -0,0 +1,249 @@
+//! Kanonická identita zápasu — jeden typ místo čtyř string formátů napříč komponentami.
+//!
+//! Legacy formáty (parsery níže):
+//!   - monitor:    "{sport}_{home}_vs_{away}"     (EsportsMonitor live cache, sport = "valorant" | "counterstrike" | ...)
+//!   - match_name: "{home}_vs_{away}"             (MatchResolvedEvent.match_name)
+//!   - match_key:  "{sport}::{t1}_vs_{t2}"         (alert_bot, volitelně "::mapN_winner" suffix)
+//!   - arb:        "{t1}_vs_{t2}" normalizované  (dřívější ArbDetector SX Bet cache, bez sportu)
+
+use prediction_engine::Sport;
+use std::fmt;
+
+/// Týmy jsou normalizované (malá alfanumerika) a seřazené → "A vs B" == "B vs A".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchId {
+    pub sport:  Sport,
+    pub team_a: String,
+    pub team_b: String,
+}
+
+/// Jen malá alfanumerika ("Team Liquid" → "teamliquid").
+pub fn normalize_team(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl MatchId {
+    pub fn new(sport: Sport, team1: &str, team2: &str) -> Self {
+        let (a, b) = (normalize_team(team1), normalize_team(team2));
+        let (team_a, team_b) = if a <= b { (a, b) } else { (b, a) };
+        Self { sport, team_a, team_b }
+    }
+
+    /// Jako `new`, sport z libovolného labelu ("counterstrike", "dota-2", "LoL", ...).
+    pub fn from_labels(sport: &str, team1: &str, team2: &str) -> Option<Self> {
+        Some(Self::new(Sport::from_label(sport)?, team1, team2))
+    }
+
+    /// Stabilní klíč pro HashMapy / logy: "cs2::faze_vs_navi".
+    pub fn key(&self) -> String {
+        format!("{}::{}", self.sport.slug(), self.teams_key())
+    }
+
+    /// Sport-agnostický klíč týmů (= legacy arb formát).
+    pub fn teams_key(&self) -> String {
+        format!("{}_vs_{}", self.team_a, self.team_b)
+    }
+
+    /// Legacy: "{sport}_{home}_vs_{away}"
+    pub fn parse_monitor_key(raw: &str) -> Option<Self> {
+        let (sport, teams) = raw.split_once('_')?;
+        let (home, away) = split_teams(teams)?;
+        Self::from_labels(sport, home, away)
+    }
+
+    /// Legacy: "{home}_vs_{away}" (sport není součástí → musí ho dodat volající)
+    pub fn parse_match_name(sport: Sport, raw: &str) -> Option<Self> {
+        let (home, away) = split_teams(raw)?;
+        Some(Self::new(sport, home, away))
+    }
+
+    /// Legacy: "{sport}::{t1}_vs_{t2}[::mapN_winner]"
+    pub fn parse_match_key(raw: &str) -> Option<Self> {
+        let mut parts = raw.split("::");
+        let sport = parts.next()?;
+        let (t1, t2) = split_teams(parts.next()?)?;
+        Self::from_labels(sport, t1, t2)
+    }
+
+    /// Legacy: normalizované "{t1}_vs_{t2}" z ArbDetector cache
+    pub fn parse_arb_key(sport: Sport, raw: &str) -> Option<Self> {
+        Self::parse_match_name(sport, raw)
+    }
+
+    /// Stejný zápas z jiného zdroje s jiným zápisem týmů ("Team Vitality" vs "vitality"):
+    /// stejný sport a oba týmy fuzzy ekvivalentní (v libovolném pořadí).
+    pub fn same_match(&self, other: &MatchId) -> bool {
+        if self.sport != other.sport {
+            return false;
+        }
+        let eq = |a: &str, b: &str| normalized_teams_equivalent(a, b);
+        (eq(&self.team_a, &other.team_a) && eq(&self.team_b, &other.team_b))
+            || (eq(&self.team_a, &other.team_b) && eq(&self.team_b, &other.team_a))
+    }
+
+    /// Skóre podobnosti 0.0–1.0 pro diagnostiku (nejbližší klíče v cache při missu):
+    /// jiný sport = 0, jinak průměr podobnosti týmů v lepším z obou párování.
+    pub fn similarity(&self, other: &MatchId) -> f64 {
+        if self.sport != other.sport {
+            return 0.0;
+        }
+        let sim = |a: &str, b: &str| normalized_team_similarity(a, b);
+        let straight = (sim(&self.team_a, &other.team_a) + sim(&self.team_b, &other.team_b)) / 2.0;
+        let crossed = (sim(&self.team_a, &other.team_b) + sim(&self.team_b, &other.team_a)) / 2.0;
+        straight.max(crossed)
+    }
+}
+
+/// Stupňovaná verze `normalized_teams_equivalent`: shoda 1.0, substring / jedna editace 0.9, jinak bigram Jaccard.
+fn normalized_team_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a.contains(b) || b.contains(a) || one_edit_apart(a, b) {
+        return 0.9;
+    }
+    bigram_jaccard(a, b)
+}
+
+/// Fuzzy shoda dvou názvů týmu napříč zdroji (zkratky, sufixy, překlepy).
+pub fn team_names_equivalent(a: &str, b: &str) -> bool {
+    normalized_teams_equivalent(&normalize_team(a), &normalize_team(b))
+}
+
+/// Heuristika (i pro feed-hub alias matching): substring, jedna editace, bigram Jaccard.
+fn normalized_teams_equivalent(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a.contains(b) || b.contains(a) || one_edit_apart(a, b) {
+        return true;
+    }
+    let sim = bigram_jaccard(a, b);
+    let common_prefix = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    sim >= 0.70 || (sim >= 0.55 && common_prefix >= 4)
+}
+
+fn one_edit_apart(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+    if a.len() == b.len() {
+        return a.iter().zip(b).filter(|(x, y)| x != y).count() == 1;
+    }
+    let (shorter, longer) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    let mut skipped = false;
+    let (mut i, mut j) = (0, 0);
+    while i < shorter.len() && j < longer.len() {
+        if shorter[i] == longer[j] {
+            i += 1;
+        } else if skipped {
+            return false;
+        } else {
+            skipped = true;
+        }
+        j += 1;
+    }
+    true
+}
+
+fn bigram_jaccard(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> std::collections::HashSet<(u8, u8)> {
+        s.as_bytes().windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (sa, sb) = (bigrams(a), bigrams(b));
+    if sa.is_empty() || sb.is_empty() {
+        return 0.0;
+    }
+    sa.intersection(&sb).count() as f64 / sa.union(&sb).count() as f64
+}
+
+impl fmt::Display for MatchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.key())
+    }
+}
+
+fn split_teams(raw: &str) -> Option<(&str, &str)> {
+    let (home, away) = raw.split_once("_vs_")?;
+    (!home.trim().is_empty() && !away.trim().is_empty()).then_some((home, away))
+}
+
+#[cfg(test)]
+mod match_id_tests {
+    use super::{normalize_team, team_names_equivalent, MatchId};
+    use prediction_engine::Sport;
+
+    #[test]
+    fn team_order_and_case_do_not_change_identity() {
+        let a = MatchId::new(Sport::Cs2, "Natus Vincere", "FaZe Clan");
+        let b = MatchId::new(Sport::Cs2, "faze clan", "NATUS-VINCERE");
+        assert_eq!(a, b);
+        assert_eq!(a.key(), "cs2::fazeclan_vs_natusvincere");
+        assert_ne!(a, MatchId::new(Sport::Dota2, "Natus Vincere", "FaZe Clan"));
+        assert_eq!(normalize_team("Team Liquid!"), "teamliquid");
+    }
+
+    #[test]
+    fn every_legacy_format_round_trips() {
+        let id = MatchId::new(Sport::Valorant, "Sentinels", "Team Heretics");
+
+        // monitor: "{sport}_{home}_vs_{away}"
+        assert_eq!(MatchId::parse_monitor_key("valorant_Team Heretics_vs_Sentinels"), Some(id.clone()));
+        assert_eq!(MatchId::parse_monitor_key(&format!("valorant_{}", id.teams_key())), Some(id.clone()));
+        let cs = MatchId::new(Sport::Cs2, "Vitality", "MOUZ");
+        assert_eq!(MatchId::parse_monitor_key("counterstrike_vitality_vs_mouz"), Some(cs.clone()));
+        assert_eq!(
+            MatchId::parse_monitor_key("leagueoflegends_T1_vs_Gen.G"),
+            Some(MatchId::new(Sport::Lol, "Gen.G", "T1")),
+        );
+
+        // match_name: "{home}_vs_{away}"
+        assert_eq!(MatchId::parse_match_name(Sport::Valorant, "Sentinels_vs_Team Heretics"), Some(id.clone()));
+        assert_eq!(MatchId::parse_match_name(Sport::Valorant, &id.teams_key()), Some(id.clone()));
+
+        // match_key (alert_bot): "{sport}::{t1}_vs_{t2}[::mapN_winner]"
+        assert_eq!(MatchId::parse_match_key(&id.key()), Some(id.clone()));
+        assert_eq!(MatchId::parse_match_key("cs2::mouz_vs_vitality::map2_winner"), Some(cs.clone()));
+        assert_eq!(
+            MatchId::parse_match_key("dota-2::team_liquid_vs_gaimin_gladiators"),
+            Some(MatchId::new(Sport::Dota2, "Gaimin Gladiators", "Team Liquid")),
+        );
+
+        // arb: normalizované "{t1}_vs_{t2}"
+        assert_eq!(MatchId::parse_arb_key(Sport::Valorant, "teamheretics_vs_sentinels"), Some(id.clone()));
+        assert_eq!(MatchId::parse_arb_key(Sport::Valorant, &id.teams_key()), Some(id));
+    }
+
+    #[test]
+    fn unknown_sport_or_malformed_key_is_rejected() {
+        assert_eq!(MatchId::parse_match_key("football::a_vs_b"), None);
+        assert_eq!(MatchId::parse_monitor_key("valorant_onlyoneteam"), None);
+        assert_eq!(MatchId::parse_match_name(Sport::Cs2, "_vs_b"), None);
+    }
+
+    #[test]
+    fn fuzzy_same_match_tolerates_source_spellings() {
+        let hltv = MatchId::new(Sport::Cs2, "Team Vitality", "G2 Esports");
+        assert!(hltv.same_match(&MatchId::new(Sport::Cs2, "g2", "vitality")));
+        assert!(hltv.same_match(&MatchId::new(Sport::Cs2, "Team Vitallity", "G2 Esports")));
+        assert!(!hltv.same_match(&MatchId::new(Sport::Cs2, "Vitality", "FaZe Clan")), "jen jeden tým nestačí");
+        assert!(!hltv.same_match(&MatchId::new(Sport::Dota2, "Vitality", "G2")));
+
+        assert!(team_names_equivalent("Natus Vincere", "natus-vincere"));
+        assert!(!team_names_equivalent("MOUZ", "FURIA"));
+
+        assert_eq!(hltv.similarity(&MatchId::new(Sport::Cs2, "G2 Esports", "Team Vitality")), 1.0);
+        assert!(hltv.similarity(&MatchId::new(Sport::Cs2, "g2", "vitality")) > hltv.similarity(&MatchId::new(Sport::Cs2, "MOUZ", "FaZe")));
+        assert_eq!(hltv.similarity(&MatchId::new(Sport::Dota2, "Team Vitality", "G2 Esports")), 0.0);
+    }
+}
@@ -0,0 +1,84 @@
+//! HTTP timeouty per zdroj / operace místo jednoho globálního čísla.
+//!
+//! Headless Chrome na GosuGamers potřebuje desítky sekund (launch + Cloudflare challenge),
+//! kdežto SX orderbook v oracle-lag okně musí přijít pod sekundu, jinak je edge pryč.
+
+use std::time::Duration;
+
+/// Výchozí timeout HTML/JSON scraperů monitoru (vlr.gg, lolesports, GosuGamers HTTP-only).
+pub const SCRAPE_TIMEOUT_DEFAULT: Duration = Duration::from_secs(12);
+/// Výchozí strop jednoho headless Chrome fetch (launch + navigace + challenge wait).
+pub const CHROME_TIMEOUT_DEFAULT: Duration = Duration::from_secs(45);
+/// Výchozí timeout HLTV scraperu.
+pub const HLTV_TIMEOUT_DEFAULT: Duration = Duration::from_secs(10);
+/// Výchozí timeout ostatních SX Bet API callů (markety, ligy, cache sync).
+pub const SX_API_TIMEOUT_DEFAULT: Duration = Duration::from_secs(5);
+/// Výchozí timeout SX `/orders` v eval_sxbet — latency-critical pro oracle lag.
+pub const SX_ORDERBOOK_TIMEOUT_DEFAULT: Duration = Duration::from_millis(800);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    pub scrape:       Duration,
+    pub chrome:       Duration,
+    pub hltv:         Duration,
+    pub sx_api:       Duration,
+    pub sx_orderbook: Duration,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            scrape:       SCRAPE_TIMEOUT_DEFAULT,
+            chrome:       CHROME_TIMEOUT_DEFAULT,
+            hltv:         HLTV_TIMEOUT_DEFAULT,
+            sx_api:       SX_API_TIMEOUT_DEFAULT,
+            sx_orderbook: SX_ORDERBOOK_TIMEOUT_DEFAULT,
+        }
+    }
+}
+
+impl HttpTimeouts {
+    /// SCRAPE_HTTP_TIMEOUT_SECS, GOSU_CHROME_TIMEOUT_SECS, HLTV_HTTP_TIMEOUT_SECS, SX_API_TIMEOUT_SECS,
+    /// SX_ORDERBOOK_TIMEOUT_MS; chybějící / nevalidní / 0 = default.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let positive = |key: &str| lookup(key)
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|n| *n > 0);
+        let defaults = Self::default();
+        Self {
+            scrape:       positive("SCRAPE_HTTP_TIMEOUT_SECS").map(Duration::from_secs).unwrap_or(defaults.scrape),
+            chrome:       positive("GOSU_CHROME_TIMEOUT_SECS").map(Duration::from_secs).unwrap_or(defaults.chrome),
+            hltv:         positive("HLTV_HTTP_TIMEOUT_SECS").map(Duration::from_secs).unwrap_or(defaults.hltv),
+            sx_api:       positive("SX_API_TIMEOUT_SECS").map(Duration::from_secs).unwrap_or(defaults.sx_api),
+            sx_orderbook: positive("SX_ORDERBOOK_TIMEOUT_MS").map(Duration::from_millis).unwrap_or(defaults.sx_orderbook),
+        }
+    }
+}
+
+#[cfg(test)]
+mod http_timeouts_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn env_overrides_per_source_and_ignores_garbage() {
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("GOSU_CHROME_TIMEOUT_SECS", "90"),
+            ("SX_ORDERBOOK_TIMEOUT_MS", "250"),
+            ("SCRAPE_HTTP_TIMEOUT_SECS", "0"),
+            ("SX_API_TIMEOUT_SECS", "fast"),
+        ]);
+        let t = HttpTimeouts::from_lookup(|k| env.get(k).map(|v| v.to_string()));
+
+        assert_eq!(t.chrome, Duration::from_secs(90));
+        assert_eq!(t.sx_orderbook, Duration::from_millis(250));
+        assert_eq!(t.scrape, SCRAPE_TIMEOUT_DEFAULT);
+        assert_eq!(t.sx_api, SX_API_TIMEOUT_DEFAULT);
+        assert_eq!(t.hltv, HLTV_TIMEOUT_DEFAULT);
+        assert!(t.sx_orderbook < t.scrape && t.scrape < t.chrome);
+    }
+}
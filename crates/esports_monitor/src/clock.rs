@@ -0,0 +1,84 @@
+//! Injektovatelný zdroj času — cooldowny, freeze a throttle jdou testovat bez `sleep`.
+//!
+//! Produkce jede na `SystemClock`; testy na `MockClock`, který se posouvá ručně přes `advance`.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub trait Clock: Send + Sync {
+    fn now_utc(&self) -> DateTime<Utc>;
+    fn now_instant(&self) -> Instant;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Výchozí sdílený clock pro komponenty, které si ho drží jako `Arc<dyn Clock>`.
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// Stojící čas; klony sdílí stav, takže test posouvá clock, který drží komponenta.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<(DateTime<Utc>, Instant)>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self { state: Arc::new(Mutex::new((Utc::now(), Instant::now()))) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += chrono::Duration::from_std(by).expect("mock clock advance out of range");
+        state.1 += by;
+    }
+
+    pub fn advance_secs(&self, secs: u64) {
+        self.advance(Duration::from_secs(secs));
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.state.lock().unwrap().0
+    }
+
+    fn now_instant(&self) -> Instant {
+        self.state.lock().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod clock_tests {
+    use super::{Clock, MockClock};
+
+    #[test]
+    fn mock_clock_advances_both_time_sources_for_all_clones() {
+        let clock = MockClock::new();
+        let shared = clock.clone();
+        let (utc0, instant0) = (clock.now_utc(), clock.now_instant());
+
+        shared.advance_secs(30);
+        assert_eq!((clock.now_utc() - utc0).num_seconds(), 30);
+        assert_eq!(clock.now_instant() - instant0, std::time::Duration::from_secs(30));
+    }
+}
@@ -5,22 +5,33 @@
 ///
 /// Zdroje:
 /// - LoL:      getSchedule API (state: inProgress → completed)
-/// - Valorant: vlr.gg/matches (live section)
-/// - CS2:      gosugamers.net/counter-strike/matches (live section)
+/// - Valorant: vlr.gg/matches (live section), volitelně JSON mirror (VLR_JSON_URL)
+/// - CS2:      gosugamers.net/counter-strike/matches (live section), volitelně + HLTV live (ESPORTS_HLTV_LIVE)
 /// - Dota 2:   gosugamers.net/dota2/matches (live section)
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use esports_core::ScrapeError;
 use futures_util::{StreamExt, SinkExt};
-use governor::{Quota, RateLimiter, state::NotKeyed, state::InMemoryState, clock::{Clock, DefaultClock}};
-use headless_chrome::{Browser, LaunchOptions};
-use logger::{ApiStatusEvent, EventLogger, MatchResolvedEvent, SystemHeartbeatEvent, now_iso};
+use governor::{Quota, RateLimiter, state::NotKeyed, state::InMemoryState, clock::{Clock as _, DefaultClock}};
+use headless_chrome::Browser;
+use hltv_scraper::{is_challenge_page, ChromeLaunchConfig, HltvLiveMatch, HltvScraper, ScrapeOutcome, ScraperHealth, ScraperHealthTracker};
+use logger::{ApiStatusEvent, EventLogger, MatchResolvedEvent, PredictionEvent, PredictionInputs, SystemHeartbeatEvent, now_iso};
+use prediction_engine::{MatchState, Prediction, PredictionEngine, match_state_with_series, prediction_sport, should_trigger_sniper};
 use scraper::{Html, Selector};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use tracing::{debug, info, warn};
 
+pub mod clock;
+pub mod stratz;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use esports_core::{match_id, timeouts, HttpTimeouts, MatchId, UpcomingMatch};
+pub use stratz::StratzRestConfig;
+
 pub type RiotRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
 
 // ── Live Match State Machine ───────────────────────────────────────────────
@@ -31,23 +42,413 @@ struct LiveMatch {
     away:       String,
     #[allow(dead_code)]
     sport:      String,
-    #[allow(dead_code)]
     first_seen: std::time::Instant,
+    /// Skóre série = počet vyhraných map (vlr.gg/matches list), None = zdroj neposílá
+    series_score: Option<(u8, u8)>,
+    /// Roundy na právě hrané mapě (vlr.gg detail zápasu), None = zdroj neposílá
+    map_score:    Option<(u8, u8)>,
+    /// Délka série (Bo1/Bo3/Bo5) ze zdroje; None = neznámá, predikce pak jen odhaduje Bo3
+    total_maps:   Option<u8>,
+    /// Roundy aktuální mapy po pollech (nejstarší první), max SCORE_HISTORY_CAP
+    score_history: Vec<(std::time::Instant, u8, u8)>,
+    /// Zdroje, které zápas právě hlásí jako live (CS2: HLTV + GosuGamers = jeden logický zápas)
+    sources:      Vec<&'static str>,
+}
+
+const SOURCE_HLTV: &str = "hltv";
+const SOURCE_GOSUGAMERS: &str = "gosugamers";
+const SOURCE_VLR: &str = "vlrgg";
+const SOURCE_LOLESPORTS: &str = "lolesports";
+const SOURCE_STRATZ: &str = "stratz";
+
+/// Priorita skóre při více zdrojích jednoho zápasu (nižší = rychlejší / čistší).
+fn score_source_rank(source: &str) -> u8 {
+    match source {
+        SOURCE_HLTV => 0,
+        SOURCE_VLR | SOURCE_LOLESPORTS => 1,
+        _ => 2,
+    }
+}
+
+/// Kolik posledních změn skóre mapy držet pro momentum.
+const SCORE_HISTORY_CAP: usize = 32;
+
+impl LiveMatch {
+    /// Skóre ze `source` se bere, jen když zápas nehlásí žádný lepší zdroj.
+    fn accepts_score_from(&self, source: &str) -> bool {
+        self.sources.iter().all(|s| score_source_rank(s) >= score_source_rank(source))
+    }
+
+    fn add_source(&mut self, source: &'static str) {
+        if !self.sources.contains(&source) {
+            self.sources.push(source);
+        }
+    }
+
+    /// Přidá naskenované skóre mapy; beze změny se nic nepřidá, pokles skóre = nová mapa → historie od nuly.
+    fn record_score(&mut self, at: std::time::Instant, score: (u8, u8)) {
+        if let Some(&(_, s1, s2)) = self.score_history.last() {
+            if (s1, s2) == score {
+                return;
+            }
+            if score.0 < s1 || score.1 < s2 {
+                self.score_history.clear();
+            }
+        }
+        self.score_history.push((at, score.0, score.1));
+        if self.score_history.len() > SCORE_HISTORY_CAP {
+            let overflow = self.score_history.len() - SCORE_HISTORY_CAP;
+            self.score_history.drain(..overflow);
+        }
+    }
+
+    /// `MatchState.history` z historie skóre (Instant → UTC přes dvojici "teď" ze stejného clocku).
+    fn history_for_state(&self, now_utc: DateTime<Utc>, now: std::time::Instant) -> Vec<(DateTime<Utc>, u8, u8)> {
+        self.score_history
+            .iter()
+            .map(|&(at, s1, s2)| {
+                let age = chrono::Duration::from_std(now.saturating_duration_since(at)).unwrap_or_default();
+                (now_utc - age, s1, s2)
+            })
+            .collect()
+    }
+}
+
+/// Řádek GosuGamers /results: týmy ze slugu + text odkazu se skóre ("2:0", "W:FF").
+#[derive(Debug, Clone, PartialEq)]
+struct GosuResultRow {
+    t1:       String,
+    t2:       String,
+    text:     String,
+    /// Numerické GosuGamers ID z href slugu
+    match_id: Option<String>,
+}
+
+/// Výsledek dohledaného dokončeného zápasu na results stránce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SeriesOutcome {
+    Winner(String),
+    /// Formát, který legitimně končí nerozhodně (Bo2 1:1, Bo4 2:2)
+    Draw,
+    /// Zápas na stránce je, ale skóre ještě není finální (0:0, rozehraný stav) → zkusit znovu
+    Incomplete,
+}
+
+impl SeriesOutcome {
+    /// Sériové skóre → výsledek. Remíza jen u sudého best-of se všemi mapami odehranými (Bo2 1:1, Bo4 2:2);
+    /// jiná shoda (Bo3 1:1, 0:0, 13:13 z mapy, neznámý formát) znamená, že scrape chytil mezistav.
+    fn from_series_score(s1: i32, s2: i32, t1: &str, t2: &str, total_maps: Option<u8>) -> Self {
+        match s1.cmp(&s2) {
+            std::cmp::Ordering::Greater => SeriesOutcome::Winner(t1.to_string()),
+            std::cmp::Ordering::Less    => SeriesOutcome::Winner(t2.to_string()),
+            std::cmp::Ordering::Equal if total_maps.is_some_and(|n| n % 2 == 0 && s1 + s2 == i32::from(n)) => SeriesOutcome::Draw,
+            std::cmp::Ordering::Equal   => SeriesOutcome::Incomplete,
+        }
+    }
+}
+
+/// Jak dlouho sdílet jednou stažené GosuGamers /results (víc winner lookupů v jednom cyklu).
+const GOSU_RESULTS_CACHE_TTL: Duration = Duration::from_secs(5);
+type GosuResultsEntry = (std::time::Instant, Arc<Vec<GosuResultRow>>);
+
+/// Jak dlouho platí stažený detail vlr.gg zápasu — kratší POLL_INTERVAL_VALORANT_SECS pak detail nestahuje každý cyklus.
+const VLR_DETAIL_CACHE_TTL: Duration = Duration::from_secs(10);
+/// Kolik detailů live zápasů vlr.gg se stahuje souběžně (víc live zápasů ≠ N sekvenčních requestů).
+const VLR_DETAIL_CONCURRENCY: usize = 3;
+
+/// Jak daleko dopředu brát zápasy z LoL getSchedule pro předehřátí SX cache.
+const LOL_SCHEDULE_HORIZON: Duration = Duration::from_secs(12 * 3600);
+
+/// "Bo3" / "BO5" / "Best of 3" kdekoli v textu → 1, 3 nebo 5.
+fn parse_best_of_label(text: &str) -> Option<u8> {
+    let words: Vec<String> = text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+    words.iter().enumerate().find_map(|(i, w)| {
+        let n = match w.strip_prefix("bo") {
+            Some(d) if !d.is_empty() => d.parse::<u8>().ok()?,
+            _ if w == "best" && words.get(i + 1).map(String::as_str) == Some("of") => words.get(i + 2)?.parse::<u8>().ok()?,
+            _ => return None,
+        };
+        matches!(n, 1 | 3 | 5).then_some(n)
+    })
+}
+
+/// Live položka z vlr.gg/matches. Skóre na listu je SÉRIE (počet map), ne roundy.
+#[derive(Debug, Clone, PartialEq)]
+struct VlrLiveItem {
+    home:         String,
+    away:         String,
+    series_score: Option<(u8, u8)>,
+    /// "Bo3"/"Bo5" štítek na položce, pokud ho list ukazuje
+    best_of:      Option<u8>,
+    href:         Option<String>,
+}
+
+/// Detail live zápasu na vlr.gg: série z hlavičky, roundy z právě hrané mapy.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct VlrMatchDetail {
+    series_score: Option<(u8, u8)>,
+    map_score:    Option<(u8, u8)>,
+    best_of:      Option<u8>,
+}
+
+/// Jen skóre série → odehrané mapy jako rozhodnuté (13:0 / 0:13) + aktuální mapa 0:0 pro `predict_series`.
+/// `best_of` None = neznámý formát → `predict_series` sérii nevyhlásí.
+fn series_only_states(sport: &str, home: &str, away: &str, series: (u8, u8), best_of: Option<u8>) -> Vec<MatchState> {
+    let mut states = Vec::new();
+    let mut map_number = 1;
+    for (won1, count) in [(true, series.0), (false, series.1)] {
+        for _ in 0..count {
+            let (s1, s2) = if won1 { (13, 0) } else { (0, 13) };
+            states.push(match_state_with_series(sport, home, away, s1, s2, map_number, best_of, false));
+            map_number += 1;
+        }
+    }
+    states.push(match_state_with_series(sport, home, away, 0, 0, map_number, best_of, true));
+    states
+}
+
+// ── Per-source poll scheduling ─────────────────────────────────────────────
+
+pub use prediction_engine::Sport;
+
+/// Env proměnná s intervalem v sekundách live zdroje (např. POLL_INTERVAL_CS2_SECS).
+fn poll_env_key(sport: Sport) -> &'static str {
+    match sport {
+        Sport::Lol      => "POLL_INTERVAL_LOL_SECS",
+        Sport::Valorant => "POLL_INTERVAL_VALORANT_SECS",
+        Sport::Cs2      => "POLL_INTERVAL_CS2_SECS",
+        Sport::Dota2    => "POLL_INTERVAL_DOTA2_SECS",
+    }
+}
+
+/// LoL = 0 → každý tick (throttling řeší Riot token bucket), ostatní 15s.
+fn default_poll_interval(sport: Sport) -> Duration {
+    match sport {
+        Sport::Lol => Duration::ZERO,
+        _          => Duration::from_secs(15),
+    }
+}
+
+/// Per-source intervaly z env (POLL_INTERVAL_<SPORT>_SECS), chybějící = default.
+pub fn poll_intervals_from_env() -> HashMap<Sport, Duration> {
+    Sport::ALL
+        .iter()
+        .map(|&sport| {
+            let interval = std::env::var(poll_env_key(sport))
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or_else(|| default_poll_interval(sport));
+            (sport, interval)
+        })
+        .collect()
+}
+
+/// Whitelist LoL lig z env (LOL_LEAGUES, oddělené čárkou, např. "LCK,LEC,LPL,LTA"); prázdný / chybějící = všechny.
+pub fn lol_leagues_from_env() -> Vec<String> {
+    std::env::var("LOL_LEAGUES")
+        .unwrap_or_default()
+        .split(',')
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// JSON mirror vlr.gg z env (VLR_JSON_URL, např. vlrggapi `/match?q=live_score`); chybějící = jen HTML scraping.
+pub fn vlr_json_url_from_env() -> Option<String> {
+    std::env::var("VLR_JSON_URL")
+        .ok()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+}
+
+/// HLTV jako druhý live zdroj CS2 (ESPORTS_HLTV_LIVE=1); default vypnuto — GosuGamers stačí a HLTV je pomalé na detailech.
+pub fn hltv_live_enabled_from_env() -> bool {
+    std::env::var("ESPORTS_HLTV_LIVE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "on"))
+        .unwrap_or(false)
+}
+
+/// Max HLTV detailů (1 request / zápas) za jeden CS2 poll.
+const HLTV_LIVE_DETAIL_CAP: usize = 8;
+
+/// Souběžnost winner lookupů z env (ESPORTS_RESOLVE_CONCURRENCY), chybějící / 0 = RESOLVE_CONCURRENCY_DEFAULT.
+pub fn resolve_concurrency_from_env() -> usize {
+    std::env::var("ESPORTS_RESOLVE_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(RESOLVE_CONCURRENCY_DEFAULT)
+}
+
+/// Min. rozestup iterací LoL tasku (`run_lol_poller`) — Riot bucket ~0.8 req/s sdílí i getSchedule.
+const LOL_TASK_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Výchozí max. doba, po kterou zápas smí zůstat v `live_matches` (Bo5 + pauzy se vejdou).
+const LIVE_MATCH_MAX_AGE_DEFAULT: Duration = Duration::from_secs(3 * 3600);
+
+/// Max. stáří live zápasu z env (ESPORTS_LIVE_MATCH_MAX_AGE_SECS), chybějící / 0 = LIVE_MATCH_MAX_AGE_DEFAULT.
+pub fn live_match_max_age_from_env() -> Duration {
+    std::env::var("ESPORTS_LIVE_MATCH_MAX_AGE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(LIVE_MATCH_MAX_AGE_DEFAULT)
+}
+
+/// Výchozí User-Agent pool (stejný jako HltvScraper), rotuje se po každém requestu.
+const DEFAULT_USER_AGENTS: [&str; 3] = [
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/118.0.0.0 Safari/537.36",
+];
+
+/// UA pool z env (ESPORTS_USER_AGENTS, oddělené `|`), prázdný / chybějící = DEFAULT_USER_AGENTS.
+pub fn user_agents_from_env() -> Vec<String> {
+    let pool: Vec<String> = std::env::var("ESPORTS_USER_AGENTS")
+        .unwrap_or_default()
+        .split('|')
+        .map(str::trim)
+        .filter(|ua| !ua.is_empty())
+        .map(String::from)
+        .collect();
+    if pool.is_empty() {
+        DEFAULT_USER_AGENTS.iter().map(|ua| ua.to_string()).collect()
+    } else {
+        pool
+    }
+}
+
+/// Počet elementů odpovídajících CSS selektoru.
+fn count_elements(html: &str, selector: &str) -> usize {
+    let selector = Selector::parse(selector).unwrap();
+    Html::parse_document(html).select(&selector).count()
+}
+
+/// Numerické ID na začátku URL segmentu ("641836-a-vs-b" → "641836", "12345/team-a-vs-team-b" → "12345").
+fn leading_numeric_id(segment: &str) -> Option<String> {
+    let digits: String = segment.chars().take_while(|c| c.is_ascii_digit()).collect();
+    (!digits.is_empty()).then_some(digits)
+}
+
+/// Po kolika chybách v řadě se interval začne zdvojovat.
+const BACKOFF_FAILURE_THRESHOLD: u32 = 3;
+/// Strop pro backoff (Cloudflare challenge umí trvat dlouho, ale zdroj nechceme ztratit úplně).
+const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(300);
+/// Min. rozestup Chrome fallbacků pro vlr.gg — Valorant polluje po 15s, Chrome tak často nespouštíme.
+const VLR_BROWSER_COOLDOWN: Duration = Duration::from_secs(120);
+/// Kolik winner lookupů (results fetchů) zápasů dokončených v jednom cyklu běží souběžně.
+const RESOLVE_CONCURRENCY_DEFAULT: usize = 3;
+
+/// Adaptivní throttle jednoho zdroje: base interval, po opakovaných chybách zdvojení, úspěch = reset.
+#[derive(Debug, Clone)]
+struct SourceSchedule {
+    base:                 Duration,
+    effective:            Duration,
+    consecutive_failures: u32,
+    last_poll:            Option<std::time::Instant>,
+}
+
+impl SourceSchedule {
+    fn new(base: Duration) -> Self {
+        Self { base, effective: base, consecutive_failures: 0, last_poll: None }
+    }
+
+    fn is_due(&self, now: std::time::Instant) -> bool {
+        self.last_poll.is_none_or(|last| now.duration_since(last) >= self.effective)
+    }
+
+    fn record(&mut self, now: std::time::Instant, ok: bool) {
+        self.last_poll = Some(now);
+        if ok {
+            self.consecutive_failures = 0;
+            self.effective = self.base;
+            return;
+        }
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= BACKOFF_FAILURE_THRESHOLD {
+            // base 0 (LoL) by se zdvojením nepohnul → začni od 1s
+            let doubled = self.effective.max(Duration::from_secs(1)) * 2;
+            self.effective = doubled.min(MAX_BACKOFF_INTERVAL.max(self.base));
+        }
+    }
 }
 
 pub struct EsportsMonitor {
     client:           reqwest::Client,
     logger:           EventLogger,
     poll_interval_secs: u64,
-    /// Zápasy momentálně LIVE: klíč = "<sport>_<home>_vs_<away>"
-    live_matches:     Mutex<HashMap<String, LiveMatch>>,
+    /// Zápasy momentálně LIVE
+    live_matches:     Mutex<HashMap<MatchId, LiveMatch>>,
     /// Deduplikace pro results fallback
     seen_matches:     Mutex<HashSet<String>>,
     /// Riot Games Rate Limiter (< 0.8 req/s)
     riot_limiter:     Arc<RiotRateLimiter>,
-    /// Throttling pro ne-Riot zdroje během Sniper mode
-    last_vlr_poll:    Mutex<std::time::Instant>,
-    last_gosu_poll:   Mutex<std::time::Instant>,
+    /// Per-source throttling (Sniper mode tickuje 3s, zdroje mají vlastní interval + backoff)
+    poll_schedule:    Mutex<HashMap<Sport, SourceSchedule>>,
+    /// Heuristická predikce nad live snapshotem (gate pro Sniper mode)
+    predictor:        PredictionEngine,
+    /// false = Chrome nejde spustit → GosuGamers přes HTTP-only
+    chrome_available: bool,
+    /// Challenge / 403 / Chrome počítadla GosuGamers + vlr.gg
+    scrape_health:    ScraperHealthTracker,
+    /// Poslední Chrome fallback pro vlr.gg (cooldown VLR_BROWSER_COOLDOWN)
+    vlr_browser_last: Mutex<Option<std::time::Instant>>,
+    /// href → (kdy staženo, detail), TTL VLR_DETAIL_CACHE_TTL; zápasy mimo live list se vyhazují
+    vlr_detail_cache: Mutex<HashMap<String, (std::time::Instant, VlrMatchDetail)>>,
+    /// sport → (kdy staženo, rozparsované /results řádky), TTL GOSU_RESULTS_CACHE_TTL
+    gosu_results_cache: Mutex<HashMap<String, GosuResultsEntry>>,
+    /// Souběžné lookupy čekají na jediný /results fetch místo vlastních requestů
+    gosu_results_fetch: tokio::sync::Mutex<()>,
+    /// Max souběžných winner lookupů při více dokončených zápasech v jednom cyklu
+    resolve_concurrency: usize,
+    /// User-Agent rotace — každý request bere další UA z poolu
+    user_agents:      Vec<String>,
+    ua_index:         AtomicUsize,
+    /// Zdroj času pro per-source throttling (testy: MockClock)
+    clock:            Arc<dyn Clock>,
+    /// Whitelist LoL lig (id / slug / name, lowercase); prázdný = všechny ligy
+    lol_leagues:      Vec<String>,
+    /// JSON mirror vlr.gg pro live Valorant; None = jen HTML scraping
+    vlr_json_url:     Option<String>,
+    /// Volitelný HLTV live zdroj CS2 (skóre roundů), sloučený s GosuGamers přes fuzzy MatchId
+    hltv:             Option<tokio::sync::Mutex<HltvScraper>>,
+    /// Timeouty per zdroj (scrape HTTP, Chrome, HLTV)
+    timeouts:         HttpTimeouts,
+    /// Proxy / extra argumenty / profil pro každé spuštění Chromu (GosuGamers, vlr.gg fallback)
+    chrome_launch:    ChromeLaunchConfig,
+    /// Zápas starší než tohle (od `first_seen`) se z `live_matches` vyhodí — zdroj ho přestal hlásit bez "finished"
+    live_match_max_age: Duration,
+    /// true = LoL polluje vlastní task (`run_lol_poller`), `poll_live_all` ho přeskakuje a jen vybírá kanál
+    lol_in_task:      AtomicBool,
+    /// Min. rozestup iterací LoL tasku (nad rámec POLL_INTERVAL_LOL_SECS)
+    lol_min_interval: Duration,
+    /// Dokončené LoL zápasy z tasku → další `poll_live_all`
+    lol_events_tx:    tokio::sync::mpsc::UnboundedSender<MatchResolvedEvent>,
+    lol_events_rx:    Mutex<tokio::sync::mpsc::UnboundedReceiver<MatchResolvedEvent>>,
+    /// STRATZ REST fallback pro Dota 2 (URL, token, interval)
+    stratz_rest:      StratzRestConfig,
+    /// true = STRATZ WebSocket je připojený; REST fallback pak běží jen bez tokenu
+    stratz_ws_live:   Arc<AtomicBool>,
+    /// Další povolený REST poll (interval / Retry-After po 429); None = hned
+    stratz_rest_next: Mutex<Option<std::time::Instant>>,
+    /// STRATZ matchId dohraných her už připsaných do `series_score` (hra visí v `/match/live` víc pollů)
+    stratz_counted_games: Mutex<HashSet<u64>>,
+    /// Série hlášené jen STRATZem, které zmizely z `/match/live` → od kdy (pauza mezi hrami ≠ konec série)
+    stratz_missing_since: Mutex<HashMap<MatchId, std::time::Instant>>,
+}
+
+/// Při zahození LoL tasku (cancel) vrátí LoL zpět do `poll_live_all`.
+struct LolTaskGuard<'a>(&'a AtomicBool);
+
+impl Drop for LolTaskGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
 }
 
 impl EsportsMonitor {
@@ -55,10 +456,54 @@ impl EsportsMonitor {
         // Limit k Riot API: max ~0.8 req/s (100 req / 2 min = 1.2s průměr).
         let quota = Quota::with_period(Duration::from_millis(1250)).unwrap();
         let riot_limiter = Arc::new(RateLimiter::direct(quota));
+        let timeouts = HttpTimeouts::from_env();
+        let (lol_events_tx, lol_events_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        Self {
+            client:             Self::build_http_client(timeouts.scrape),
+            logger:             EventLogger::new(log_dir).with_webhook_from_env(),
+            poll_interval_secs,
+            live_matches:       Mutex::new(HashMap::new()),
+            seen_matches:       Mutex::new(HashSet::new()),
+            riot_limiter,
+            poll_schedule:      Mutex::new(Self::build_schedule(&poll_intervals_from_env())),
+            predictor:          PredictionEngine::new(),
+            chrome_available:   false,
+            scrape_health:      ScraperHealthTracker::default(),
+            vlr_browser_last:   Mutex::new(None),
+            vlr_detail_cache:   Mutex::new(HashMap::new()),
+            gosu_results_cache: Mutex::new(HashMap::new()),
+            gosu_results_fetch: tokio::sync::Mutex::new(()),
+            resolve_concurrency: resolve_concurrency_from_env(),
+            user_agents:        user_agents_from_env(),
+            ua_index:           AtomicUsize::new(0),
+            clock:              clock::system_clock(),
+            lol_leagues:        lol_leagues_from_env(),
+            vlr_json_url:       vlr_json_url_from_env(),
+            hltv:               hltv_live_enabled_from_env()
+                .then(|| tokio::sync::Mutex::new(HltvScraper::new().with_http_timeout(timeouts.hltv))),
+            timeouts,
+            chrome_launch:      ChromeLaunchConfig::from_env(),
+            live_match_max_age: live_match_max_age_from_env(),
+            lol_in_task:        AtomicBool::new(false),
+            lol_min_interval:   LOL_TASK_MIN_INTERVAL,
+            lol_events_tx,
+            lol_events_rx:      Mutex::new(lol_events_rx),
+            stratz_rest:        StratzRestConfig::from_env(),
+            stratz_ws_live:     Arc::new(AtomicBool::new(false)),
+            stratz_rest_next:   Mutex::new(None),
+            stratz_counted_games: Mutex::new(HashSet::new()),
+            stratz_missing_since: Mutex::new(HashMap::new()),
+        }
+    }
 
+    /// Sdílený HTTP klient pro všechny scrapery (vlr.gg, lolesports, GosuGamers).
+    /// User-Agent není v default headers — nastavuje ho `get` z rotujícího poolu.
+    /// gzip komprese šetří SSR payloady, keep-alive pool drží spojení mezi polly.
+    /// Accept-Encoding necháváme na reqwestu — ručně ho nenastavujeme, jinak by se vypnula auto-dekomprese.
+    fn build_http_client(timeout: Duration) -> reqwest::Client {
         use reqwest::header;
         let mut headers = header::HeaderMap::new();
-        headers.insert(header::USER_AGENT, header::HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"));
         headers.insert(header::ACCEPT, header::HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8"));
         headers.insert(header::ACCEPT_LANGUAGE, header::HeaderValue::from_static("en-US,en;q=0.5"));
         headers.insert(header::CONNECTION, header::HeaderValue::from_static("keep-alive"));
@@ -71,19 +516,196 @@ impl EsportsMonitor {
         headers.insert("Sec-Ch-Ua-Mobile", header::HeaderValue::from_static("?0"));
         headers.insert("Sec-Ch-Ua-Platform", header::HeaderValue::from_static("\"Windows\""));
 
-        Self {
-            client: reqwest::Client::builder()
-                .default_headers(headers)
-                .timeout(std::time::Duration::from_secs(12))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
-            logger:             EventLogger::new(log_dir),
-            poll_interval_secs,
-            live_matches:       Mutex::new(HashMap::new()),
-            seen_matches:       Mutex::new(HashSet::new()),
-            riot_limiter,
-            last_vlr_poll:      Mutex::new(std::time::Instant::now() - std::time::Duration::from_secs(60)),
-            last_gosu_poll:     Mutex::new(std::time::Instant::now() - std::time::Duration::from_secs(60)),
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(timeout)
+            .gzip(true)
+            .pool_max_idle_per_host(4)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .http2_adaptive_window(true)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new())
+    }
+
+    /// Přepíše per-source intervaly (chybějící sport si nechá aktuální nastavení).
+    pub fn with_poll_intervals(self, intervals: HashMap<Sport, Duration>) -> Self {
+        {
+            let mut schedule = self.poll_schedule.lock().unwrap();
+            for (sport, interval) in intervals {
+                schedule.insert(sport, SourceSchedule::new(interval));
+            }
+        }
+        self
+    }
+
+    /// Přepíše UA pool (prázdný pool se ignoruje).
+    pub fn with_user_agents(mut self, user_agents: Vec<String>) -> Self {
+        if !user_agents.is_empty() {
+            self.user_agents = user_agents;
+        }
+        self
+    }
+
+    /// GET s aktuálním User-Agentem; po každém requestu se pool posune (jako HltvScraper).
+    fn get(&self, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+        let idx = self.ua_index.fetch_add(1, Ordering::Relaxed) % self.user_agents.len();
+        self.client.get(url).header("User-Agent", self.user_agents[idx].as_str())
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Přepíše whitelist LoL lig (prázdný = sledovat všechny).
+    pub fn with_lol_leagues(mut self, leagues: Vec<String>) -> Self {
+        self.lol_leagues = leagues.iter().map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()).collect();
+        self
+    }
+
+    /// Přepíše souběžnost winner lookupů (min. 1).
+    pub fn with_resolve_concurrency(mut self, concurrency: usize) -> Self {
+        self.resolve_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Zapne / vypne JSON zdroj live Valorant zápasů (None = jen HTML scraping).
+    pub fn with_vlr_json_url(mut self, url: Option<String>) -> Self {
+        self.vlr_json_url = url;
+        self
+    }
+
+    /// Zapne / vypne HLTV jako druhý live zdroj CS2.
+    pub fn with_hltv_live(mut self, enabled: bool) -> Self {
+        self.hltv = enabled.then(|| tokio::sync::Mutex::new(
+            HltvScraper::new()
+                .with_http_timeout(self.timeouts.hltv)
+                .with_browser_available(self.chrome_available),
+        ));
+        self
+    }
+
+    /// Přepíše timeouty per zdroj; HTTP klient (a HLTV, je-li zapnuté) se sestaví znovu.
+    pub fn with_http_timeouts(mut self, timeouts: HttpTimeouts) -> Self {
+        self.client = Self::build_http_client(timeouts.scrape);
+        if let Some(hltv) = self.hltv.as_mut() {
+            hltv.get_mut().set_http_timeout(timeouts.hltv);
+        }
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Přepíše STRATZ REST fallback (URL, token, interval).
+    pub fn with_stratz_rest(mut self, config: StratzRestConfig) -> Self {
+        self.stratz_rest = config;
+        self
+    }
+
+    /// Přepíše min. rozestup iterací LoL tasku.
+    pub fn with_lol_min_interval(mut self, interval: Duration) -> Self {
+        self.lol_min_interval = interval;
+        self
+    }
+
+    /// Přepíše max. stáří live zápasu (0 se ignoruje).
+    pub fn with_live_match_max_age(mut self, max_age: Duration) -> Self {
+        if !max_age.is_zero() {
+            self.live_match_max_age = max_age;
+        }
+        self
+    }
+
+    /// Vyhodí zápasy, které jsou v `live_matches` déle než `live_match_max_age` (mis-detekce, změna URL
+    /// u zdroje) — jinak by navždy držely `is_any_match_live()` a Sniper mode. Vrací počet vyhozených.
+    fn evict_stale_live_matches(&self) -> usize {
+        let now = self.clock.now_instant();
+        let mut live = self.live_matches.lock().unwrap();
+        let before = live.len();
+        live.retain(|id, m| {
+            let age = now.saturating_duration_since(m.first_seen);
+            let keep = age <= self.live_match_max_age;
+            if !keep {
+                warn!("⌛ {} ({} vs {}, {}) je live {} min bez ukončení → vyhazuji z live_matches",
+                    id, m.home, m.away, m.sport, age.as_secs() / 60);
+            }
+            keep
+        });
+        before - live.len()
+    }
+
+    /// Chrome fallback (GosuGamers, vlr, HLTV) podle `hltv_scraper::probe_chrome_available` ze startu
+    /// binárky; konstruktor Chrome nespouští a bez téhle volby běží HTTP-only.
+    pub fn with_chrome_available(mut self, available: bool) -> Self {
+        if let Some(hltv) = self.hltv.take() {
+            self.hltv = Some(tokio::sync::Mutex::new(hltv.into_inner().with_browser_available(available)));
+        }
+        self.chrome_available = available;
+        self
+    }
+
+    pub fn chrome_available(&self) -> bool {
+        self.chrome_available
+    }
+
+    /// Přepíše parametry spuštění Chromu (i pro HLTV, je-li zapnuté).
+    pub fn with_chrome_launch(mut self, config: ChromeLaunchConfig) -> Self {
+        if let Some(hltv) = self.hltv.take() {
+            self.hltv = Some(tokio::sync::Mutex::new(hltv.into_inner().with_chrome_launch(config.clone())));
+        }
+        self.chrome_launch = config;
+        self
+    }
+
+    /// Event z getLive projde whitelistem, pokud league id, slug nebo name sedí (bez ohledu na velikost písmen).
+    fn lol_league_allowed(&self, ev: &serde_json::Value) -> bool {
+        if self.lol_leagues.is_empty() {
+            return true;
+        }
+        ["/league/id", "/league/slug", "/league/name"].iter()
+            .filter_map(|ptr| ev.pointer(ptr).and_then(|v| v.as_str()))
+            .any(|value| self.lol_leagues.iter().any(|l| l.eq_ignore_ascii_case(value)))
+    }
+
+    fn build_schedule(intervals: &HashMap<Sport, Duration>) -> HashMap<Sport, SourceSchedule> {
+        Sport::ALL
+            .iter()
+            .map(|&sport| {
+                let base = intervals.get(&sport).copied().unwrap_or_else(|| default_poll_interval(sport));
+                (sport, SourceSchedule::new(base))
+            })
+            .collect()
+    }
+
+    /// Aktuální interval zdroje včetně backoffu.
+    pub fn effective_interval(&self, sport: Sport) -> Duration {
+        self.poll_schedule.lock().unwrap()
+            .get(&sport)
+            .map(|s| s.effective)
+            .unwrap_or_else(|| default_poll_interval(sport))
+    }
+
+    fn source_due(&self, sport: Sport, now: std::time::Instant) -> bool {
+        self.poll_schedule.lock().unwrap()
+            .get(&sport)
+            .is_none_or(|s| s.is_due(now))
+    }
+
+    fn record_poll_result(&self, sport: Sport, now: std::time::Instant, ok: bool) {
+        let mut schedule = self.poll_schedule.lock().unwrap();
+        let entry = schedule.entry(sport).or_insert_with(|| SourceSchedule::new(default_poll_interval(sport)));
+        let before = entry.effective;
+        entry.record(now, ok);
+        if entry.effective > before {
+            warn!("⏳ {} backoff: {} chyb v řadě → interval {}s", sport.label(), entry.consecutive_failures, entry.effective.as_secs());
+        } else if entry.effective < before {
+            info!("✅ {} recovered → interval {}s", sport.label(), entry.effective.as_secs());
+        }
+    }
+
+    /// Dopíše bufferované JSONL eventy (graceful shutdown).
+    pub fn flush_logs(&self) {
+        if let Err(e) = self.logger.flush() {
+            warn!("Flush esports logu selhal: {}", e);
         }
     }
 
@@ -92,43 +714,132 @@ impl EsportsMonitor {
         !self.live_matches.lock().unwrap().is_empty()
     }
 
+    /// True, pokud aspoň jeden live zápas má predikci s confidence >= `threshold`.
+    /// Sniper mode má smysl jen u zápasů blízko konce, ne u vyrovnaného 3:2.
+    pub fn has_conclusive_or_near_match(&self, threshold: f32) -> bool {
+        let live = self.live_matches.lock().unwrap();
+        live.values().any(|m| should_trigger_sniper(&self.live_prediction(m), threshold))
+    }
+
+    /// Roundy na mapě → per-map `predict`; jen skóre série → `predict_series`.
+    fn live_prediction(&self, m: &LiveMatch) -> Prediction {
+        self.live_prediction_with_inputs(m).0
+    }
+
+    /// Predikce + vstupy, ze kterých vznikla (PREDICTION event).
+    fn live_prediction_with_inputs(&self, m: &LiveMatch) -> (Prediction, PredictionInputs) {
+        let sport = prediction_sport(&m.sport);
+        let map_number = m.series_score.map(|(w1, w2)| w1 + w2 + 1).unwrap_or(1);
+        let inputs = PredictionInputs {
+            map_score:       m.map_score,
+            series_score:    m.series_score,
+            map_number,
+            best_of:         m.total_maps,
+            score_history:   m.score_history.iter().map(|&(_, s1, s2)| (s1, s2)).collect(),
+        };
+        let prediction = match (m.map_score, m.series_score) {
+            (Some((s1, s2)), _) => {
+                let mut state = match_state_with_series(sport, &m.home, &m.away, s1, s2, map_number, m.total_maps, true);
+                state.history = m.history_for_state(self.clock.now_utc(), self.clock.now_instant());
+                self.predictor.predict(&state)
+            }
+            (None, Some(series)) => {
+                self.predictor.predict_series(&series_only_states(sport, &m.home, &m.away, series, m.total_maps))
+            }
+            (None, None) => Prediction::Uncertain,
+        };
+        (prediction, inputs)
+    }
+
+    /// PREDICTION eventy pro všechny právě live zápasy (seřazené podle `match_id`).
+    pub fn live_predictions(&self) -> Vec<PredictionEvent> {
+        let live = self.live_matches.lock().unwrap();
+        let mut events: Vec<PredictionEvent> = live
+            .iter()
+            .map(|(id, m)| {
+                let (prediction, inputs) = self.live_prediction_with_inputs(m);
+                PredictionEvent {
+                    ts:         now_iso(),
+                    event:      "PREDICTION",
+                    match_id:   id.to_string(),
+                    sport:      m.sport.clone(),
+                    home:       m.home.clone(),
+                    away:       m.away.clone(),
+                    confidence: prediction.confidence(),
+                    prediction,
+                    inputs,
+                }
+            })
+            .collect();
+        events.sort_by(|a, b| a.match_id.cmp(&b.match_id));
+        events
+    }
+
     // ── PRIMÁRNÍ: Live polling ─────────────────────────────────────────────
 
     /// Primární metoda — vrací zápasy co PRÁVĚ skončily (live→finished transition).
-    /// Volat každých 15s.
+    /// Volat každých 15s. Když běží `run_lol_poller`, LoL se tu nepolluje, jen se předají jeho výsledky.
     pub async fn poll_live_all(&self) -> Vec<MatchResolvedEvent> {
-        let mut newly_finished = Vec::new();
-
-        // 1. LoL — getSchedule API (chráněno Riot token bucketem, běží při každém ticku, i v 3s Sniper Mode)
-        match self.poll_live_lol().await {
-            Ok(mut res) => newly_finished.append(&mut res),
-            Err(e) => warn!("LoL live poll failed: {}", e),
-        }
+        // LoL (getLive, chráněno Riot token bucketem), Valorant (vlr.gg), CS2 & Dota 2 (GosuGamers)
+        // — každý zdroj má vlastní interval, opakované chyby ho zdvojují.
+        let mut finished = self.poll_due_sources(|sport| async move {
+            match sport {
+                Sport::Lol      => self.poll_live_lol().await,
+                Sport::Valorant => self.poll_live_valorant().await,
+                Sport::Cs2      => self.poll_live_cs2().await,
+                Sport::Dota2    => self.poll_live_dota2().await,
+            }
+        }).await;
+        finished.extend(self.drain_lol_events());
+        finished
+    }
 
-        let now = std::time::Instant::now();
-        let vlr_elapsed = { *self.last_vlr_poll.lock().unwrap() };
-        let gosu_elapsed = { *self.last_gosu_poll.lock().unwrap() };
+    /// LoL live polling ve vlastním tasku mimo cadence Sniper mode: čekání na Riot token tak nebrzdí
+    /// CS2/Valorant v `poll_live_all`. Dokončené zápasy jdou kanálem do dalšího `poll_live_all`.
+    /// Běží, dokud se future nezahodí (select s cancel tokenem).
+    pub async fn run_lol_poller(&self) {
+        self.run_lol_loop(|_| self.poll_live_lol()).await
+    }
 
-        // 2. Valorant — vlr.gg/matches (Throttled na 15s)
-        if now.duration_since(vlr_elapsed).as_secs() >= 15 {
-            match self.poll_live_valorant().await {
-                Ok(mut res) => newly_finished.append(&mut res),
-                Err(e) => warn!("Valorant live poll failed: {}", e),
+    async fn run_lol_loop<F, Fut>(&self, poll: F)
+    where
+        F: Fn(Sport) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<MatchResolvedEvent>>>,
+    {
+        self.lol_in_task.store(true, Ordering::SeqCst);
+        let _guard = LolTaskGuard(&self.lol_in_task);
+        info!("🧵 LoL live polling běží ve vlastním tasku (min. {:.1}s)", self.lol_min_interval.as_secs_f32());
+        loop {
+            for event in self.poll_source_if_due(Sport::Lol, &poll).await {
+                // Přijímač drží `self` → send selže jen při dropu monitoru
+                let _ = self.lol_events_tx.send(event);
             }
-            *self.last_vlr_poll.lock().unwrap() = now;
+            let interval = self.effective_interval(Sport::Lol).max(self.lol_min_interval);
+            tokio::time::sleep(interval).await;
         }
+    }
 
-        // 3. CS2 & Dota 2 — GosuGamers /matches (Throttled na 15s)
-        if now.duration_since(gosu_elapsed).as_secs() >= 15 {
-            match self.poll_live_cs2().await {
-                Ok(mut res) => newly_finished.append(&mut res),
-                Err(e) => warn!("CS2 live poll failed: {}", e),
-            }
-            match self.poll_live_dota2().await {
-                Ok(mut res) => newly_finished.append(&mut res),
-                Err(e) => warn!("Dota2 live poll failed: {}", e),
+    /// Výsledky LoL tasku nasbírané od posledního volání.
+    fn drain_lol_events(&self) -> Vec<MatchResolvedEvent> {
+        let mut rx = self.lol_events_rx.lock().unwrap();
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    /// Throttle smyčka `poll_live_all`: zavolá `poll` jen pro zdroje, kterým podle `self.clock`
+    /// uplynul interval. Oddělená od scraperů, aby šla testovat s MockClock bez sítě.
+    async fn poll_due_sources<F, Fut>(&self, poll: F) -> Vec<MatchResolvedEvent>
+    where
+        F: Fn(Sport) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<MatchResolvedEvent>>>,
+    {
+        let mut newly_finished = Vec::new();
+        self.evict_stale_live_matches();
+
+        for sport in Sport::ALL {
+            if sport == Sport::Lol && self.lol_in_task.load(Ordering::SeqCst) {
+                continue;
             }
-            *self.last_gosu_poll.lock().unwrap() = now;
+            newly_finished.append(&mut self.poll_source_if_due(sport, &poll).await);
         }
 
         if !newly_finished.is_empty() {
@@ -140,12 +851,35 @@ impl EsportsMonitor {
         newly_finished
     }
 
+    /// Jeden zdroj: zavolá `poll`, jen pokud mu uplynul interval; výsledek posune jeho schedule.
+    async fn poll_source_if_due<F, Fut>(&self, sport: Sport, poll: &F) -> Vec<MatchResolvedEvent>
+    where
+        F: Fn(Sport) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<MatchResolvedEvent>>>,
+    {
+        let now = self.clock.now_instant();
+        if !self.source_due(sport, now) {
+            return Vec::new();
+        }
+        let result = poll(sport).await;
+        self.record_poll_result(sport, now, result.is_ok());
+        result.unwrap_or_else(|e| {
+            warn!("{} live poll failed: {}", sport.label(), e);
+            Vec::new()
+        })
+    }
+
     /// Spustí STRATZ GraphQL WebSocket pro Dota 2 live data (0 MB RAM overhead proxy)
     pub async fn start_stratz_ws(&self) {
         info!("🔌 Starting STRATZ WebSocket listener for Dota 2...");
         // WS endpoint Stratzu vyžaduje Bearer token, použijeme anonymní napojení nebo free-tier mock
         let url = "wss://api.stratz.com/graphql";
-        
+        let ws_live = Arc::clone(&self.stratz_ws_live);
+        if self.stratz_rest.token.is_none() {
+            info!("STRATZ_API_TOKEN chybí → Dota 2 live jede přes STRATZ REST fallback (každých {}s)",
+                self.stratz_rest.interval.as_secs());
+        }
+
         // Spawn tokio background task
         tokio::spawn(async move {
             loop {
@@ -160,6 +894,7 @@ impl EsportsMonitor {
                             tokio::time::sleep(Duration::from_secs(5)).await;
                             continue;
                         }
+                        ws_live.store(true, Ordering::SeqCst);
 
                         // Event loop
                         while let Some(msg) = ws_stream.next().await {
@@ -176,15 +911,16 @@ impl EsportsMonitor {
                                 _ => {}
                             }
                         }
+                        ws_live.store(false, Ordering::SeqCst);
                     }
                     Err(e) => {
-                        let err_str = e.to_string();
-                        if err_str.contains("403") || err_str.contains("401") || err_str.contains("Forbidden") {
-                            warn!("❌ STRATZ WS Connection refused (403 Forbidden). Token is likely required. Sleeping for 1 hour to prevent spam...");
+                        let err = Self::ws_connect_error(e);
+                        if err.is_auth_block() {
+                            warn!("❌ STRATZ WS Connection refused ({}). Token is likely required. Sleeping for 1 hour to prevent spam...", err);
                             tokio::time::sleep(Duration::from_secs(3600)).await;
                             continue;
                         }
-                        warn!("❌ STRATZ WS Connection failed: {}. Retrying in 5s...", err_str);
+                        warn!("❌ STRATZ WS Connection failed: {}. Retrying in 5s...", err);
                     }
                 }
                 tokio::time::sleep(Duration::from_secs(5)).await;
@@ -192,24 +928,23 @@ impl EsportsMonitor {
         });
     }
 
+    /// WS handshake chyba → ScrapeError (HTTP status z odmítnutého upgrade requestu).
+    fn ws_connect_error(err: tokio_tungstenite::tungstenite::Error) -> ScrapeError {
+        use tokio_tungstenite::tungstenite::Error as WsError;
+        match err {
+            WsError::Http(resp) => ScrapeError::from_status(resp.status()),
+            WsError::Io(e) if e.kind() == std::io::ErrorKind::TimedOut => ScrapeError::Timeout,
+            other => ScrapeError::Transport(other.to_string()),
+        }
+    }
+
     /// LoL live tracking přes getLive API.
     /// state: "inProgress" → zapamatuj si. "completed" → emituj resolved.
     async fn poll_live_lol(&self) -> Result<Vec<MatchResolvedEvent>> {
-        // Token Bucket: Čekáme na přidělení tokenu (max 0.8 req/s)
-        let clock = DefaultClock::default();
-        loop {
-            if let Err(not_until) = self.riot_limiter.check() {
-                let wait = not_until.wait_time_from(clock.now());
-                if wait > Duration::ZERO {
-                    tokio::time::sleep(wait).await;
-                }
-            } else {
-                break;
-            }
-        }
+        self.wait_riot_token().await;
 
         let url = "https://esports-api.lolesports.com/persisted/gw/getLive?hl=en-US";
-        let resp = self.client.get(url)
+        let resp = self.get(url)
             .header("x-api-key", "0TvQnueqKa5mxJntVWt0w4LpLfEkrV1Ta8rQBb9Z")
             .send().await.context("LoL getLive request failed")?;
 
@@ -218,16 +953,25 @@ impl EsportsMonitor {
         }
 
         let data: serde_json::Value = resp.json().await?;
+        Ok(self.process_lol_live(&data))
+    }
+
+    /// Zpracuje getLive odpověď: zapíše LIVE zápasy whitelistovaných lig a vrátí ty, co z listu vypadly.
+    fn process_lol_live(&self, data: &serde_json::Value) -> Vec<MatchResolvedEvent> {
         let events = match data.pointer("/data/schedule/events").and_then(|v| v.as_array()) {
             Some(e) => e,
             // Pokud tu /events nejsou (prázdné pole taky projde jako some), vracíme prázdné pole, nikoliv chybu.
-            None => return Ok(vec![]),
+            None => return vec![],
         };
 
         let mut newly_finished = Vec::new();
         let mut current_live_keys = HashSet::new();
 
         for ev in events {
+            if !self.lol_league_allowed(ev) {
+                debug!("LoL event mimo whitelist lig: {:?}", ev.pointer("/league/name"));
+                continue;
+            }
             let state = ev.pointer("/state").and_then(|s| s.as_str()).unwrap_or("");
             
             // Riot API má match->teams pokud je hra aktivní
@@ -238,7 +982,7 @@ impl EsportsMonitor {
                     let t2 = teams[1].pointer("/name").and_then(|n| n.as_str()).unwrap_or("").to_string();
 
                     if !t1.is_empty() && !t2.is_empty() {
-                        let key = format!("leagueoflegends_{}_vs_{}", t1, t2);
+                        let key = MatchId::new(Sport::Lol, &t1, &t2);
 
                         if state == "inProgress" || state == "unstarted" {
                             if state == "inProgress" {
@@ -250,7 +994,12 @@ impl EsportsMonitor {
                                         home: t1.clone(),
                                         away: t2.clone(),
                                         sport: "leagueoflegends".to_string(),
-                                        first_seen: std::time::Instant::now(),
+                                        first_seen: self.clock.now_instant(),
+                                        series_score: None,
+                                        map_score: None,
+                                        total_maps: None,
+                                        score_history: Vec::new(),
+                                        sources: vec![SOURCE_LOLESPORTS],
                                     }
                                 });
                             }
@@ -263,12 +1012,12 @@ impl EsportsMonitor {
         // Live → Finished detekce
         // Oproti VLR/GosuGamers, Riot `getLive` vrací všechny LIVE eventy na jedné stránce.
         // Cokoliv, co bylo v paměti a už není v getLive response, ZKONČILO (pokud je to LoL).
-        let resolved_pairs: Vec<(String, String, String)> = {
+        let resolved_pairs: Vec<(MatchId, String, String)> = {
             let mut mem = self.live_matches.lock().unwrap();
             let mut to_remove = Vec::new();
 
             for (key, m) in mem.iter() {
-                if m.sport == "leagueoflegends" && !current_live_keys.contains(key) {
+                if key.sport == Sport::Lol && !current_live_keys.contains(key) {
                     to_remove.push((key.clone(), m.home.clone(), m.away.clone()));
                 }
             }
@@ -284,74 +1033,109 @@ impl EsportsMonitor {
             // Máme unknown vítěze z live response (zápas vypadl z live listu), musíme pak z audit queue zjistit víc
             // Pro SX bet stačí znát finiš zápasu, zbytek najdeme na oraclu
             let match_id = format!("{}_vs_{}", home, away);
-            if let Some(ev) = self.log_resolved("leagueoflegends", &match_id, &home, &away, "Unknown") {
+            if let Some(ev) = self.log_resolved("leagueoflegends", &match_id, (&home, &away), "Unknown", None) {
                 newly_finished.push(ev);
             }
         }
 
         self.log_api_ok("lolesports", "lol", current_live_keys.len());
-        Ok(newly_finished)
+        newly_finished
     }
 
-    /// Valorant live tracking přes vlr.gg/matches.
-    /// Live zápasy mají score místo countdown timeru a CSS class "mod-live".
-    async fn poll_live_valorant(&self) -> Result<Vec<MatchResolvedEvent>> {
-        let url = "https://www.vlr.gg/matches";
-        let resp = self.client.get(url).send().await.context("VLR /matches request failed")?;
-
-        if !resp.status().is_success() {
-            return Err(anyhow::anyhow!("VLR HTTP {}", resp.status()));
+    /// Token Bucket: čeká na přidělení Riot tokenu (max 0.8 req/s), sdílí getLive i getSchedule.
+    async fn wait_riot_token(&self) {
+        let clock = DefaultClock::default();
+        while let Err(not_until) = self.riot_limiter.check() {
+            let wait = not_until.wait_time_from(clock.now());
+            if wait > Duration::ZERO {
+                tokio::time::sleep(wait).await;
+            }
         }
+    }
 
-        let html = resp.text().await?;
-        let document = Html::parse_document(&html);
-
-        // Live zápasy na vlr.gg/matches mají class "mod-live" na match-item elementu
-        let live_selector = Selector::parse("a.match-item.mod-live").unwrap();
-        let team_selector = Selector::parse(".match-item-vs-team-name").unwrap();
-        let score_selector = Selector::parse(".match-item-vs-team-score").unwrap();
+    /// LoL rozpis přes getSchedule — nezačaté zápasy v LOL_SCHEDULE_HORIZON.
+    /// Arb detector podle nich předehřeje SX markety, aby eval při resolved nečekal na cache miss.
+    pub async fn poll_lol_schedule(&self) -> Result<Vec<UpcomingMatch>> {
+        self.wait_riot_token().await;
 
-        let mut current_live_keys: HashSet<String> = HashSet::new();
-        let mut newly_finished = Vec::new();
+        let url = "https://esports-api.lolesports.com/persisted/gw/getSchedule?hl=en-US";
+        let resp = self.get(url)
+            .header("x-api-key", "0TvQnueqKa5mxJntVWt0w4LpLfEkrV1Ta8rQBb9Z")
+            .send().await.context("LoL getSchedule request failed")?;
 
-        // Parsuj aktuálně live zápasy
-        for node in document.select(&live_selector) {
-            let teams: Vec<String> = node.select(&team_selector)
-                .map(|t| t.text().collect::<String>().trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-            let scores: Vec<String> = node.select(&score_selector)
-                .map(|s| s.text().collect::<String>().trim().to_string())
-                .collect();
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("LoL getSchedule HTTP {}", resp.status()));
+        }
 
-            if teams.len() < 2 { continue; }
+        let data: serde_json::Value = resp.json().await?;
+        let upcoming = Self::parse_lol_schedule(&data, self.clock.now_utc());
+        self.log_api_ok("lolesports", "lol_schedule", upcoming.len());
+        Ok(upcoming)
+    }
 
-            let score_display = if scores.len() >= 2 {
-                format!(" ({}-{})", scores[0], scores[1])
-            } else {
-                String::new()
-            };
+    /// getSchedule eventy → nezačaté zápasy se známými týmy, start do `now + LOL_SCHEDULE_HORIZON`.
+    fn parse_lol_schedule(data: &serde_json::Value, now: DateTime<Utc>) -> Vec<UpcomingMatch> {
+        let horizon = now + chrono::Duration::from_std(LOL_SCHEDULE_HORIZON).unwrap_or_default();
+        let Some(events) = data.pointer("/data/schedule/events").and_then(|v| v.as_array()) else {
+            return vec![];
+        };
 
-            let key = format!("valorant_{}_vs_{}", teams[0], teams[1]);
-            current_live_keys.insert(key.clone());
+        events.iter()
+            .filter(|ev| ev.pointer("/state").and_then(|s| s.as_str()) == Some("unstarted"))
+            .filter_map(|ev| {
+                let start_time = ev.pointer("/startTime")
+                    .and_then(|t| t.as_str())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                    .with_timezone(&Utc);
+                if start_time > horizon {
+                    return None;
+                }
+                let home = ev.pointer("/match/teams/0/name").and_then(|n| n.as_str())?.trim().to_string();
+                let away = ev.pointer("/match/teams/1/name").and_then(|n| n.as_str())?.trim().to_string();
+                // Rozpis playoffs má před určením soupeře "TBD"
+                if home.is_empty() || away.is_empty() || home == "TBD" || away == "TBD" {
+                    return None;
+                }
+                Some(UpcomingMatch {
+                    id: MatchId::new(Sport::Lol, &home, &away),
+                    home,
+                    away,
+                    start_time,
+                    source_match_id: ev.pointer("/match/id").and_then(|i| i.as_str()).map(str::to_string),
+                })
+            })
+            .collect()
+    }
 
-            let mut live = self.live_matches.lock().unwrap();
-            live.entry(key.clone()).or_insert_with(|| {
-                info!("🔴 LIVE detekován: {} vs {}{} (Valorant)", teams[0], teams[1], score_display);
-                LiveMatch {
-                    home:       teams[0].clone(),
-                    away:       teams[1].clone(),
-                    sport:      "valorant".to_string(),
-                    first_seen: std::time::Instant::now(),
+    /// Valorant live tracking: JSON mirror vlr.gg (VLR_JSON_URL), při selhání vlr.gg/matches HTML.
+    /// Live zápasy mají v HTML score místo countdown timeru a CSS class "mod-live".
+    async fn poll_live_valorant(&self) -> Result<Vec<MatchResolvedEvent>> {
+        // JSON zdroj nese roundy rovnou a nezávisí na CSS třídách; chyba → HTML scraping jako dřív
+        let json_entries = match &self.vlr_json_url {
+            Some(url) => match self.fetch_vlr_json_live(url).await {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    warn!("VLR JSON {} selhal: {:#} — fallback na HTML", url, e);
+                    None
                 }
-            });
-        }
+            },
+            None => None,
+        };
+        let entries = match json_entries {
+            Some(entries) => entries,
+            None => match self.fetch_vlr_html_live().await? {
+                Some(entries) => entries,
+                None => return Ok(vec![]),
+            },
+        };
+
+        let current_live_keys = self.record_valorant_live(&entries);
 
         // Detekuj zápasy co zmizely z live sekce → právě skončily
-        let finished_keys: Vec<(String, LiveMatch)> = {
+        let finished_keys: Vec<(MatchId, LiveMatch)> = {
             let mut live = self.live_matches.lock().unwrap();
-            let finished: Vec<String> = live.keys()
-                .filter(|k| k.starts_with("valorant_") && !current_live_keys.contains(*k))
+            let finished: Vec<MatchId> = live.keys()
+                .filter(|k| k.sport == Sport::Valorant && !current_live_keys.contains(*k))
                 .cloned()
                 .collect();
             finished.into_iter()
@@ -359,39 +1143,144 @@ impl EsportsMonitor {
                 .collect()
         };
 
-        for (key, m) in finished_keys {
-            // Zápas zmizel z live → musíme dohledat výsledek z /matches (results sekce)
-            // Hledáme ho v results části stránky
-            let winner = self.find_just_finished_valorant_winner(&m.home, &m.away, &html).await;
-            let winner_str = winner.unwrap_or_else(|| {
-                warn!("Valorant {}: nelze dohledat vítěze, přeskakuji.", key);
-                String::new()
-            });
-            if winner_str.is_empty() { continue; }
+        // Zápas zmizel z live → výsledek dohledáme na vlr.gg/matches/results
+        Ok(self.resolve_finished("valorant", finished_keys, |home, away, total_maps| async move {
+            self.find_just_finished_valorant_winner(&home, &away, total_maps).await
+        }).await)
+    }
 
-            info!("✅ MATCH FINISHED (byl LIVE): {} vs {} → winner: {} (Valorant)", m.home, m.away, winner_str);
-            let match_id = format!("{}_vs_{}", m.home, m.away);
-            if let Some(ev) = self.emit_resolved("valorant", &match_id, &m.home, &m.away, &winner_str) {
-                newly_finished.push(ev);
-            }
+    /// HTML cesta: vlr.gg/matches + detail každého live zápasu (roundy jsou jen tam).
+    /// None = stránka bez match elementů (rozbitý parser) → live stav neměnit.
+    async fn fetch_vlr_html_live(&self) -> Result<Option<Vec<(VlrLiveItem, VlrMatchDetail)>>> {
+        let html = self.fetch_vlr_html("https://www.vlr.gg/matches").await?;
+        // Rozbitý parser ≠ konec zápasů — bez match elementů live stav neměníme
+        if self.check_parsed_page("vlrgg", "valorant", &html, count_elements(&html, "a.match-item")) {
+            return Ok(None);
         }
+        let items = Self::parse_vlr_live_items(&html);
 
-        Ok(newly_finished)
+        // Skóre na listu je série; roundy aktuální mapy jsou jen na detailu zápasu
+        Ok(Some(self.fetch_vlr_details(items).await))
     }
 
-    /// Dohledá výsledek právě dokončeného Valorant zápasu na vlr.gg/matches/results.
-    async fn find_just_finished_valorant_winner(&self, home: &str, away: &str, _live_html: &str) -> Option<String> {
-        let url = "https://www.vlr.gg/matches/results";
-        let resp = self.client.get(url).send().await.ok()?;
-        if !resp.status().is_success() { return None; }
+    /// Detaily live zápasů: max VLR_DETAIL_CONCURRENCY souběžně, v rámci VLR_DETAIL_CACHE_TTL z cache.
+    /// Pořadí výstupu = pořadí `items`; selhaný detail = prázdný (jen série z listu).
+    async fn fetch_vlr_details(&self, items: Vec<VlrLiveItem>) -> Vec<(VlrLiveItem, VlrMatchDetail)> {
+        {
+            let live_hrefs: HashSet<&str> = items.iter().filter_map(|i| i.href.as_deref()).collect();
+            self.vlr_detail_cache.lock().unwrap().retain(|href, _| live_hrefs.contains(href.as_str()));
+        }
 
-        let html = resp.text().await.ok()?;
-        let document = Html::parse_document(&html);
-        let match_selector = Selector::parse("a.match-item").unwrap();
-        let team_selector = Selector::parse(".match-item-vs-team-name").unwrap();
-        let score_selector = Selector::parse(".match-item-vs-team-score").unwrap();
+        futures_util::stream::iter(items)
+            .map(|item| async move {
+                let detail = match &item.href {
+                    Some(href) => self.vlr_match_detail_cached(href).await,
+                    None => VlrMatchDetail::default(),
+                };
+                (item, detail)
+            })
+            .buffered(VLR_DETAIL_CONCURRENCY)
+            .collect()
+            .await
+    }
 
-        let home_norm = home.to_lowercase();
+    async fn vlr_match_detail_cached(&self, href: &str) -> VlrMatchDetail {
+        let now = self.clock.now_instant();
+        if let Some((fetched_at, detail)) = self.vlr_detail_cache.lock().unwrap().get(href) {
+            if now.saturating_duration_since(*fetched_at) < VLR_DETAIL_CACHE_TTL {
+                return detail.clone();
+            }
+        }
+        match self.fetch_vlr_match_detail(href).await {
+            Ok(detail) => {
+                self.vlr_detail_cache.lock().unwrap().insert(href.to_string(), (now, detail.clone()));
+                detail
+            }
+            Err(e) => {
+                debug!("VLR detail {} failed: {}", href, e);
+                VlrMatchDetail::default()
+            }
+        }
+    }
+
+    /// JSON mirror vlr.gg (vlrggapi `/match?q=live_score`).
+    async fn fetch_vlr_json_live(&self, url: &str) -> Result<Vec<(VlrLiveItem, VlrMatchDetail)>> {
+        let resp = self.get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send().await.context("VLR JSON request failed")?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("VLR JSON HTTP {}", resp.status()));
+        }
+        let body: serde_json::Value = serde_json::from_str(&resp.text().await?).context("VLR JSON parse")?;
+        Self::parse_vlr_json_live(&body)
+    }
+
+    /// Zapíše live Valorant zápasy (z JSON i HTML cesty) do state machine, vrátí jejich klíče.
+    fn record_valorant_live(&self, entries: &[(VlrLiveItem, VlrMatchDetail)]) -> HashSet<MatchId> {
+        let mut current_live_keys: HashSet<MatchId> = HashSet::new();
+
+        for (item, detail) in entries {
+            let series_score = detail.series_score.or(item.series_score);
+            let map_score = detail.map_score;
+            let total_maps = detail.best_of.or(item.best_of);
+
+            let score_display = match (series_score, map_score) {
+                (Some((w1, w2)), Some((r1, r2))) => format!(" (série {}-{}, mapa {}-{})", w1, w2, r1, r2),
+                (Some((w1, w2)), None) => format!(" (série {}-{})", w1, w2),
+                _ => String::new(),
+            };
+
+            let key = MatchId::new(Sport::Valorant, &item.home, &item.away);
+            current_live_keys.insert(key.clone());
+
+            let mut live = self.live_matches.lock().unwrap();
+            let entry = live.entry(key.clone())
+                .and_modify(|m| {
+                    m.series_score = series_score;
+                    m.map_score = map_score;
+                    m.total_maps = total_maps.or(m.total_maps);
+                })
+                .or_insert_with(|| {
+                    let bo_display = total_maps.map(|n| format!("Bo{}", n)).unwrap_or_else(|| "Bo?".to_string());
+                    info!("🔴 LIVE detekován: {} vs {}{} {} (Valorant)", item.home, item.away, score_display, bo_display);
+                    LiveMatch {
+                        home:       item.home.clone(),
+                        away:       item.away.clone(),
+                        sport:      "valorant".to_string(),
+                        first_seen: self.clock.now_instant(),
+                        series_score,
+                        map_score,
+                        total_maps,
+                        score_history: Vec::new(),
+                        sources:    vec![SOURCE_VLR],
+                    }
+                });
+            if let Some(score) = map_score {
+                entry.record_score(self.clock.now_instant(), score);
+            }
+        }
+
+        current_live_keys
+    }
+
+    /// Dohledá výsledek právě dokončeného Valorant zápasu na vlr.gg/matches/results.
+    async fn find_just_finished_valorant_winner(&self, home: &str, away: &str, total_maps: Option<u8>) -> Option<SeriesOutcome> {
+        let url = "https://www.vlr.gg/matches/results";
+        let resp = self.get(url).send().await.ok()?;
+        if !resp.status().is_success() { return None; }
+
+        let html = resp.text().await.ok()?;
+        Self::valorant_result_outcome(&html, home, away, total_maps)
+    }
+
+    /// Výsledek zápasu `home` vs `away` z prvních 5 řádků vlr.gg/matches/results.
+    fn valorant_result_outcome(html: &str, home: &str, away: &str, total_maps: Option<u8>) -> Option<SeriesOutcome> {
+        let document = Html::parse_document(html);
+        let match_selector = Selector::parse("a.match-item").unwrap();
+        let team_selector = Selector::parse(".match-item-vs-team-name").unwrap();
+        let score_selector = Selector::parse(".match-item-vs-team-score").unwrap();
+
+        let home_norm = home.to_lowercase();
         let away_norm = away.to_lowercase();
 
         // Hledáme jen v prvních 5 výsledcích (právě dokončené)
@@ -413,9 +1302,7 @@ impl EsportsMonitor {
             {
                 let s1: i32 = scores[0].parse().unwrap_or(0);
                 let s2: i32 = scores[1].parse().unwrap_or(0);
-                if s1 != s2 {
-                    return Some(if s1 > s2 { teams[0].clone() } else { teams[1].clone() });
-                }
+                return Some(SeriesOutcome::from_series_score(s1, s2, &teams[0], &teams[1], total_maps));
             }
         }
         None
@@ -423,13 +1310,351 @@ impl EsportsMonitor {
 
     /// CS2 live tracking přes GosuGamers /counterstrike/matches.
     async fn poll_live_cs2(&self) -> Result<Vec<MatchResolvedEvent>> {
+        if let Some(hltv) = &self.hltv {
+            let matches = {
+                let mut scraper = hltv.lock().await;
+                match scraper.fetch_live_matches().await {
+                    Ok(ids) => {
+                        let mut matches = Vec::new();
+                        for id in ids.into_iter().take(HLTV_LIVE_DETAIL_CAP) {
+                            if let Ok(Some(m)) = scraper.fetch_match_details(id).await {
+                                matches.push(m);
+                            }
+                        }
+                        Some(matches)
+                    }
+                    Err(e) => {
+                        warn!("HLTV live fetch failed: {}", e);
+                        None
+                    }
+                }
+            };
+            if let Some(matches) = matches {
+                self.ingest_hltv_live(&matches);
+            }
+        }
         self.poll_live_gosugamers("counterstrike", "https://www.gosugamers.net/counterstrike/matches").await
     }
 
     /// Dota 2 live tracking (nově nahrazeno STRATZ WebSockets v backgroundu)
     /// Tato funkce slouží pro kompatibilitu, pokud zhavaruje WS
+    /// GosuGamers + STRATZ REST fallback (když WebSocket neběží nebo chybí token, max 1× za interval).
+    /// Chyba STRATZ jen zaloguje; výsledky STRATZ přežijí i výpadek GosuGamers.
     async fn poll_live_dota2(&self) -> Result<Vec<MatchResolvedEvent>> {
-        self.poll_live_gosugamers("dota2", "https://www.gosugamers.net/dota2/matches").await
+        let stratz = if self.stratz_rest_due(self.clock.now_instant()) {
+            self.poll_stratz_rest().await.unwrap_or_else(|e| {
+                warn!("STRATZ REST fallback failed: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        match self.poll_live_gosugamers("dota2", "https://www.gosugamers.net/dota2/matches").await {
+            Ok(mut finished) => {
+                finished.extend(stratz);
+                Ok(finished)
+            }
+            Err(e) if !stratz.is_empty() => {
+                warn!("dota2 GosuGamers poll failed ({}), STRATZ výsledky použity", e);
+                Ok(stratz)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// REST fallback na řadě: WebSocket nedává data (odpojený / bez tokenu) a uplynul interval / Retry-After.
+    fn stratz_rest_due(&self, now: std::time::Instant) -> bool {
+        let ws_usable = self.stratz_rest.token.is_some() && self.stratz_ws_live.load(Ordering::SeqCst);
+        !ws_usable && self.stratz_rest_next.lock().unwrap().is_none_or(|next| now >= next)
+    }
+
+    /// STRATZ `/match/live` → `live_matches`. 429 posune další poll o Retry-After (default 5 min).
+    async fn poll_stratz_rest(&self) -> Result<Vec<MatchResolvedEvent>> {
+        let now = self.clock.now_instant();
+        *self.stratz_rest_next.lock().unwrap() = Some(now + self.stratz_rest.interval);
+
+        let mut req = self.get(self.stratz_rest.live_url());
+        if let Some(token) = &self.stratz_rest.token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req.send().await.context("STRATZ /match/live request failed")?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let backoff = stratz::retry_after(resp.headers())
+                .unwrap_or(stratz::STRATZ_RATE_LIMIT_BACKOFF_DEFAULT)
+                .max(self.stratz_rest.interval);
+            *self.stratz_rest_next.lock().unwrap() = Some(now + backoff);
+            self.log_api_error("stratz", "dota2", &format!("HTTP 429, backoff {}s", backoff.as_secs()));
+            anyhow::bail!("STRATZ rate limit (429), další pokus za {}s", backoff.as_secs());
+        }
+        if !status.is_success() {
+            return Err(ScrapeError::from_status(status)).context("STRATZ /match/live");
+        }
+
+        let body: serde_json::Value = resp.json().await.context("STRATZ /match/live JSON")?;
+        let games = stratz::parse_stratz_live(&body).map_err(|e| anyhow::anyhow!(e))?;
+        self.log_api_ok("stratz", "dota2", games.len());
+
+        let finished = self.ingest_stratz_live(&games);
+        let winners: HashMap<MatchId, String> = finished.iter()
+            .filter_map(|(key, _, winner)| winner.clone().map(|w| (key.clone(), w)))
+            .collect();
+        let finished = finished.into_iter().map(|(key, m, _)| (key, m)).collect();
+        Ok(self.resolve_finished("dota2", finished, |home, away, total_maps| {
+            let known = winners.get(&MatchId::new(Sport::Dota2, &home, &away)).cloned();
+            async move {
+                match known {
+                    Some(winner) => Some(SeriesOutcome::Winner(winner)),
+                    None => self.find_gosugamers_winner("dota2", &home, &away, total_maps, "https://www.gosugamers.net/dota2/matches/results").await,
+                }
+            }
+        }).await)
+    }
+
+    /// STRATZ hry → `live_matches` (fuzzy sloučení s GosuGamers záznamem). STRATZ vidí jednotlivé hry,
+    /// ne sérii: dohraná hra jen připíše mapu do `series_score` (každá hra jednou), délku série bere ze STRATZ
+    /// `series.type`. Vrací dokončené série, které hlásil jen STRATZ: skóre dosáhlo `total_maps / 2 + 1`
+    /// (vítěz známý), nebo chybí v `/match/live` déle než `STRATZ_SERIES_GAP` (vítěz None → GosuGamers results);
+    /// kratší výpadek je pauza mezi hrami a záznam i se `series_score` zůstává. Záznam hlášený i GosuGamers
+    /// jen ztratí STRATZ zdroj.
+    fn ingest_stratz_live(&self, games: &[stratz::StratzLiveMatch]) -> Vec<(MatchId, LiveMatch, Option<String>)> {
+        let now = self.clock.now_instant();
+        let mut live = self.live_matches.lock().unwrap();
+        let mut counted = self.stratz_counted_games.lock().unwrap();
+        counted.retain(|id| games.iter().any(|g| g.match_id == *id));
+        let mut missing_since = self.stratz_missing_since.lock().unwrap();
+        let mut current: HashSet<MatchId> = HashSet::new();
+        let mut decided: HashMap<MatchId, String> = HashMap::new();
+
+        for game in games {
+            let key = Self::canonical_live_key(&live, MatchId::new(Sport::Dota2, &game.radiant, &game.dire));
+            if let Some(winner) = game.winner() {
+                let Some(m) = live.get_mut(&key) else { continue };
+                current.insert(key.clone());
+                m.total_maps = m.total_maps.or(game.series_length);
+                if counted.insert(game.match_id) {
+                    let (s1, s2) = m.series_score.unwrap_or((0, 0));
+                    m.series_score = Some(if match_id::team_names_equivalent(winner, &m.home) {
+                        (s1.saturating_add(1), s2)
+                    } else {
+                        (s1, s2.saturating_add(1))
+                    });
+                    info!("🗺️ Dota 2 hra dohrána: {} vs {} → {} (série {:?}, STRATZ)", m.home, m.away, winner, m.series_score);
+                }
+                let needed = m.total_maps.map(|n| n / 2 + 1);
+                if let (Some(needed), Some((s1, s2))) = (needed, m.series_score) {
+                    if s1 >= needed {
+                        decided.insert(key, m.home.clone());
+                    } else if s2 >= needed {
+                        decided.insert(key, m.away.clone());
+                    }
+                }
+                continue;
+            }
+            current.insert(key.clone());
+            let m = live.entry(key).or_insert_with(|| {
+                info!("🔴 LIVE detekován: {} vs {} (dota2, STRATZ)", game.radiant, game.dire);
+                LiveMatch {
+                    home:       game.radiant.clone(),
+                    away:       game.dire.clone(),
+                    sport:      "dota2".to_string(),
+                    first_seen: now,
+                    series_score: None,
+                    map_score:    None,
+                    total_maps:   None,
+                    score_history: Vec::new(),
+                    sources:    Vec::new(),
+                }
+            });
+            m.add_source(SOURCE_STRATZ);
+            m.total_maps = m.total_maps.or(game.series_length);
+        }
+        missing_since.retain(|key, _| !current.contains(key));
+
+        let mut finished = Vec::new();
+        for (key, winner) in decided {
+            let m = live.remove(&key).expect("key z iterace");
+            missing_since.remove(&key);
+            finished.push((key, m, Some(winner)));
+        }
+
+        let gone: Vec<MatchId> = live.iter()
+            .filter(|(k, m)| k.sport == Sport::Dota2 && !current.contains(*k) && m.sources.contains(&SOURCE_STRATZ))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in gone {
+            let m = live.get_mut(&key).expect("key z iterace");
+            if m.sources.len() > 1 {
+                m.sources.retain(|s| *s != SOURCE_STRATZ);
+                continue;
+            }
+            let since = *missing_since.entry(key.clone()).or_insert(now);
+            if now.saturating_duration_since(since) >= stratz::STRATZ_SERIES_GAP {
+                missing_since.remove(&key);
+                let m = live.remove(&key).expect("key z iterace");
+                finished.push((key, m, None));
+            }
+        }
+        finished
+    }
+
+    /// Live zápasy z vlr.gg/matches (class "mod-live" na match-item).
+    /// `.match-item-vs-team-score` je počet vyhraných map v sérii, NE roundy.
+    fn parse_vlr_live_items(html: &str) -> Vec<VlrLiveItem> {
+        let document = Html::parse_document(html);
+        let live_selector = Selector::parse("a.match-item.mod-live").unwrap();
+        let team_selector = Selector::parse(".match-item-vs-team-name").unwrap();
+        let score_selector = Selector::parse(".match-item-vs-team-score").unwrap();
+
+        document.select(&live_selector).filter_map(|node| {
+            let teams: Vec<String> = node.select(&team_selector)
+                .map(|t| t.text().collect::<String>().trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if teams.len() < 2 { return None; }
+
+            let scores: Vec<Option<u8>> = node.select(&score_selector)
+                .map(|s| s.text().collect::<String>().trim().parse::<u8>().ok())
+                .collect();
+            let series_score = match (scores.first().copied().flatten(), scores.get(1).copied().flatten()) {
+                (Some(s1), Some(s2)) => Some((s1, s2)),
+                _ => None,
+            };
+
+            Some(VlrLiveItem {
+                home: teams[0].clone(),
+                away: teams[1].clone(),
+                series_score,
+                best_of: parse_best_of_label(&node.text().collect::<Vec<_>>().join(" ")),
+                href: node.value().attr("href").map(str::to_string),
+            })
+        }).collect()
+    }
+
+    /// Live zápasy z JSON mirroru vlr.gg (vlrggapi `live_score`: `data.segments[]`).
+    /// `score1/2` = série, `team*_round_ct + team*_round_t` = roundy aktuální mapy ("N/A" = strana ještě nehrála).
+    /// Prázdné `segments` nebo segmenty bez týmů = chyba (fallback na HTML), ne "nic se nehraje" —
+    /// jinak by výpadek mirroru smazal celý live stav Valorantu.
+    fn parse_vlr_json_live(body: &serde_json::Value) -> Result<Vec<(VlrLiveItem, VlrMatchDetail)>> {
+        let segments = body.pointer("/data/segments")
+            .and_then(|s| s.as_array())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("VLR JSON bez data.segments"))?;
+        let has_teams = |seg: &serde_json::Value| ["team1", "team2"].iter()
+            .all(|key| seg.get(*key).and_then(|v| v.as_str()).is_some_and(|t| !t.trim().is_empty()));
+        if !segments.iter().any(has_teams) {
+            return Err(anyhow::anyhow!("VLR JSON: žádný segment s týmy"));
+        }
+
+        Ok(segments.iter().filter_map(|seg| {
+            let text = |key: &str| seg.get(key).and_then(|v| v.as_str()).map(str::trim).unwrap_or("");
+            let num = |key: &str| text(key).parse::<u8>().ok();
+
+            let status = text("time_until_match");
+            if !status.is_empty() && !status.eq_ignore_ascii_case("live") { return None; }
+            let (home, away) = (text("team1"), text("team2"));
+            if home.is_empty() || away.is_empty() { return None; }
+
+            let series_score = num("score1").zip(num("score2"));
+            let rounds = |team: &str| match (num(&format!("{}_round_ct", team)), num(&format!("{}_round_t", team))) {
+                (None, None) => None,
+                (ct, t) => Some(ct.unwrap_or(0) + t.unwrap_or(0)),
+            };
+            let map_score = match (rounds("team1"), rounds("team2")) {
+                (None, None) => None,
+                (r1, r2) => Some((r1.unwrap_or(0), r2.unwrap_or(0))),
+            };
+            let best_of = parse_best_of_label(&format!("{} {}", text("match_series"), text("match_event")));
+            let href = Some(text("match_page"))
+                .filter(|p| !p.is_empty())
+                .map(|p| p.trim_start_matches("https://www.vlr.gg").trim_start_matches("https://vlr.gg").to_string());
+
+            Some((
+                VlrLiveItem { home: home.to_string(), away: away.to_string(), series_score, best_of, href },
+                VlrMatchDetail { series_score, map_score, best_of },
+            ))
+        }).collect())
+    }
+
+    /// Detail zápasu: série z `.match-header-vs-score`, Bo z poznámky v hlavičce,
+    /// roundy z mapy bez `mod-win` skóre (= právě hraná mapa).
+    fn parse_vlr_match_detail(html: &str) -> VlrMatchDetail {
+        let document = Html::parse_document(html);
+        let series_selector = Selector::parse(".match-header-vs-score .js-spoiler span").unwrap();
+        let note_selector = Selector::parse("div.match-header-vs-note").unwrap();
+        let game_selector = Selector::parse(".vm-stats-game[data-game-id]").unwrap();
+        let map_score_selector = Selector::parse(".vm-stats-game-header .score").unwrap();
+
+        let series_nums: Vec<u8> = document.select(&series_selector)
+            .filter_map(|s| s.text().collect::<String>().trim().parse::<u8>().ok())
+            .collect();
+        let series_score = (series_nums.len() >= 2).then(|| (series_nums[0], series_nums[1]));
+
+        let best_of = document.select(&note_selector)
+            .find_map(|n| parse_best_of_label(&n.text().collect::<String>()));
+
+        let map_score = document.select(&game_selector)
+            .filter(|g| g.value().attr("data-game-id") != Some("all"))
+            .find_map(|g| {
+                let scores: Vec<_> = g.select(&map_score_selector).collect();
+                if scores.len() != 2 { return None; }
+                if scores.iter().any(|s| s.value().classes().any(|c| c == "mod-win")) { return None; }
+                let r1 = scores[0].text().collect::<String>().trim().parse::<u8>().ok()?;
+                let r2 = scores[1].text().collect::<String>().trim().parse::<u8>().ok()?;
+                Some((r1, r2))
+            });
+
+        VlrMatchDetail { series_score, map_score, best_of }
+    }
+
+    /// vlr.gg přes HTTP; 403 / challenge stránka → headless Chrome (max 1× za VLR_BROWSER_COOLDOWN).
+    async fn fetch_vlr_html(&self, url: &str) -> Result<String> {
+        let resp = self.get(url).send().await.context("VLR /matches request failed")?;
+        let status = resp.status();
+        let err = if !status.is_success() {
+            let outcome = if status == reqwest::StatusCode::FORBIDDEN { ScrapeOutcome::Forbidden } else { ScrapeOutcome::Error };
+            self.scrape_health.record_response("vlr", outcome);
+            ScrapeError::from_status(status)
+        } else {
+            let html = resp.text().await?;
+            if !is_challenge_page(&html) {
+                self.scrape_health.record_response("vlr", ScrapeOutcome::Ok);
+                return Ok(html);
+            }
+            self.scrape_health.record_response("vlr", ScrapeOutcome::Challenge);
+            ScrapeError::Challenge
+        };
+
+        if !err.needs_browser_fallback() || !self.chrome_available {
+            return Err(anyhow::Error::new(err).context("VLR /matches"));
+        }
+        warn!("VLR {} on {}, trying browser fallback", err, url);
+        self.scrape_health.record_browser_fallback("vlr");
+        let now = self.clock.now_instant();
+        {
+            let mut last = self.vlr_browser_last.lock().unwrap();
+            if let Some(prev) = *last {
+                let elapsed = now.duration_since(prev);
+                if elapsed < VLR_BROWSER_COOLDOWN {
+                    return Err(anyhow::Error::new(ScrapeError::Browser(format!(
+                        "cooldown active ({}s remaining)",
+                        (VLR_BROWSER_COOLDOWN - elapsed).as_secs()
+                    ))));
+                }
+            }
+            *last = Some(now);
+        }
+        self.fetch_html_with_chrome("valorant", url).await
+    }
+
+    async fn fetch_vlr_match_detail(&self, href: &str) -> Result<VlrMatchDetail> {
+        let url = format!("https://www.vlr.gg{}", href);
+        let resp = self.get(&url).send().await.context("VLR match detail request failed")?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("VLR detail HTTP {}", resp.status()));
+        }
+        let html = resp.text().await?;
+        Ok(Self::parse_vlr_match_detail(&html))
     }
 
     /// Extrahuje jména týmů z GosuGamers match href slugu.
@@ -449,53 +1674,101 @@ impl EsportsMonitor {
     }
 
     /// Generický GosuGamers live scraper (rewritten for MUI SSR structure).
+    /// Headless Chrome fetch (GosuGamers blokuje reqwest přes Cloudflare).
+    async fn fetch_html_with_chrome(&self, sport: &str, url: &str) -> Result<String> {
+        let limit = self.timeouts.chrome;
+        let url = url.to_string();
+        let sport = sport.to_string();
+        let launch = self.chrome_launch.clone();
+        Self::run_chrome_fetch(limit, move || -> Result<String> {
+            info!("🚀 Launching headless chrome for {}...", sport);
+            let options = launch.launch_options(None).map_err(|e| anyhow::anyhow!(e))?;
+            let browser = Browser::new(options).context("Failed to launch Chrome")?;
+            let tab = browser.new_tab().context("Failed to create Chrome tab")?;
+            tab.set_default_timeout(limit);
+
+            // Navigate a počkat na selector
+            tab.navigate_to(&url)?;
+            tab.wait_for_element("body")?; // počkáme až aspoň něco najede
+            std::thread::sleep(Duration::from_secs(3)); // extra Cloudflare challenge wait
+
+            let content = tab.get_content()?;
+            Ok(content)
+        }).await
+    }
+
+    /// Blokující Chrome fetch na blocking threadu, nejdéle `limit` (pak ScrapeError::Timeout).
+    async fn run_chrome_fetch<F>(limit: Duration, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Result<String> + Send + 'static,
+    {
+        match tokio::time::timeout(limit, tokio::task::spawn_blocking(fetch)).await {
+            Ok(joined) => joined?,
+            Err(_) => Err(anyhow::Error::new(ScrapeError::Timeout)
+                .context(format!("headless Chrome fetch exceeded {}s", limit.as_secs()))),
+        }
+    }
+
+    /// Challenge / 403 / browser počítadla scraperů monitoru (GosuGamers, vlr.gg).
+    pub fn scraper_health(&self) -> ScraperHealth {
+        self.scrape_health.snapshot()
+    }
+
+    /// Chrome když jde spustit, jinak HTTP-only (Cloudflare může vrátit challenge).
+    async fn fetch_gosugamers_html(&self, sport: &str, url: &str) -> Result<String> {
+        let html = if self.chrome_available {
+            self.scrape_health.record_browser_fallback("gosugamers");
+            self.fetch_html_with_chrome(sport, url).await
+        } else {
+            self.fetch_gosugamers_http(url).await
+        };
+        let outcome = match &html {
+            Ok(body) if is_challenge_page(body) => ScrapeOutcome::Challenge,
+            Ok(_) => ScrapeOutcome::Ok,
+            Err(e) if matches!(e.downcast_ref::<ScrapeError>(), Some(ScrapeError::Http(reqwest::StatusCode::FORBIDDEN))) => {
+                ScrapeOutcome::Forbidden
+            }
+            Err(_) => ScrapeOutcome::Error,
+        };
+        self.scrape_health.record_response("gosugamers", outcome);
+        html
+    }
+
+    async fn fetch_gosugamers_http(&self, url: &str) -> Result<String> {
+        let resp = self.get(url).send().await.context("GosuGamers HTTP-only request failed")?;
+        if !resp.status().is_success() {
+            return Err(anyhow::Error::new(ScrapeError::from_status(resp.status()))
+                .context("GosuGamers HTTP-only (Chrome unavailable)"));
+        }
+        Ok(resp.text().await?)
+    }
+
     /// GosuGamers vrací SSR HTML s <a> elementy kde:
     ///   - href obsahuje "/matches/" a slug s názvy týmů
     ///   - textContent obsahuje "Live" pro aktivní zápasy  
     ///   - textContent obsahuje "XhYm" pro upcoming
     async fn poll_live_gosugamers(&self, sport: &str, url: &str) -> Result<Vec<MatchResolvedEvent>> {
+        let sport_kind = Sport::from_label(sport)
+            .ok_or_else(|| anyhow::anyhow!("GosuGamers: unknown sport {}", sport))?;
         // --- CHROME HEADLESS FALLBACK pro Cloudflare bypass ---
-        // GosuGamers brutálně blokuje reqwest. Použijeme Headless Chrome.
-        let html = tokio::task::spawn_blocking({
-            let url = url.to_string();
-            let sport = sport.to_string();
-            move || -> Result<String> {
-                info!("🚀 Launching headless chrome for {}...", sport);
-                let options = LaunchOptions::default_builder()
-                    .headless(true)
-                    .sandbox(false)
-                    .build()
-                    .unwrap();
-                let browser = Browser::new(options).context("Failed to launch Chrome")?;
-                let tab = browser.new_tab().context("Failed to create Chrome tab")?;
-                
-                // Navigate a počkat na selector
-                tab.navigate_to(&url)?;
-                tab.wait_for_element("body")?; // počkáme až aspoň něco najede
-                std::thread::sleep(Duration::from_secs(3)); // extra Cloudflare challenge wait
-                
-                let content = tab.get_content()?;
-                Ok(content)
-            }
-        }).await??;
+        // GosuGamers brutálně blokuje reqwest. Použijeme Headless Chrome (bez Chrome HTTP-only).
+        let html = self.fetch_gosugamers_html(sport, url).await?;
 
         let document = Html::parse_document(&html);
 
         // GosuGamers MUI: match linky jsou <a> s href obsahujícím "/matches/"
         let link_selector = Selector::parse("a[href*='/matches/']").unwrap();
+        // Filtruj jen skutečné match linky (ne navigační)
+        let match_links: Vec<_> = document.select(&link_selector)
+            .filter_map(|node| node.value().attr("href").filter(|h| h.contains("/tournaments/")).map(|h| (node, h)))
+            .collect();
+        if self.check_parsed_page("gosugamers", sport, &html, match_links.len()) {
+            return Ok(vec![]);
+        }
 
-        let mut current_live_keys: HashSet<String> = HashSet::new();
-        let mut newly_finished = Vec::new();
-
-        for node in document.select(&link_selector) {
-            let href = match node.value().attr("href") {
-                Some(h) => h,
-                None => continue,
-            };
-
-            // Filtruj jen skutečné match linky (ne navigační)
-            if !href.contains("/tournaments/") { continue; }
+        let mut current_live_keys: HashSet<MatchId> = HashSet::new();
 
+        for (node, href) in match_links {
             let text: String = node.text().collect::<String>();
 
             // Detekuj LIVE zápasy: text obsahuje "Live" (ne "0h21m" timing)
@@ -507,119 +1780,222 @@ impl EsportsMonitor {
                 None => continue,
             };
 
-            let key = format!("{}_{}_vs_{}", sport, t1, t2);
+            let mut live = self.live_matches.lock().unwrap();
+            // Stejný zápas už může běžet pod HLTV zápisem týmů → jeden logický záznam
+            let key = Self::canonical_live_key(&live, MatchId::new(sport_kind, &t1, &t2));
             current_live_keys.insert(key.clone());
 
-            let mut live = self.live_matches.lock().unwrap();
-            live.entry(key.clone()).or_insert_with(|| {
+            live.entry(key.clone()).and_modify(|m| m.add_source(SOURCE_GOSUGAMERS)).or_insert_with(|| {
                 info!("🔴 LIVE detekován: {} vs {} ({})", t1, t2, sport);
                 LiveMatch {
                     home:       t1.clone(),
                     away:       t2.clone(),
                     sport:      sport.to_string(),
-                    first_seen: std::time::Instant::now(),
+                    first_seen: self.clock.now_instant(),
+                    series_score: None,
+                    map_score:    None,
+                    total_maps:   None,
+                    score_history: Vec::new(),
+                    sources:    vec![SOURCE_GOSUGAMERS],
                 }
             });
         }
 
         // Detekuj zápasy co zmizely z live → právě skončily
-        let sport_prefix = format!("{}_", sport);
-        let finished_keys: Vec<(String, LiveMatch)> = {
+        let finished_keys: Vec<(MatchId, LiveMatch)> = {
             let mut live = self.live_matches.lock().unwrap();
-            let finished: Vec<String> = live.keys()
-                .filter(|k| k.starts_with(&sport_prefix) && !current_live_keys.contains(*k))
-                .cloned()
+            // Jen zápasy, které GosuGamers hlásil — čistě HLTV záznam jeho zmizením neskončil
+            let finished: Vec<MatchId> = live.iter()
+                .filter(|(k, m)| k.sport == sport_kind && !current_live_keys.contains(*k) && m.sources.contains(&SOURCE_GOSUGAMERS))
+                .map(|(k, _)| k.clone())
                 .collect();
             finished.into_iter()
                 .filter_map(|k| live.remove(&k).map(|m| (k, m)))
                 .collect()
         };
 
-        for (key, m) in finished_keys {
-            // Dohledáme výsledek na /results stránce (právě dokončený → bude na vrchu)
-            let results_url = if sport == "counterstrike" {
-                "https://www.gosugamers.net/counterstrike/matches/results"
-            } else {
-                "https://www.gosugamers.net/dota2/matches/results"
-            };
+        // Dohledáme výsledek na /results stránce (právě dokončený → bude na vrchu)
+        let results_url = if sport == "counterstrike" {
+            "https://www.gosugamers.net/counterstrike/matches/results"
+        } else {
+            "https://www.gosugamers.net/dota2/matches/results"
+        };
+        Ok(self.resolve_finished(sport, finished_keys, |home, away, total_maps| async move {
+            self.find_gosugamers_winner(sport, &home, &away, total_maps, results_url).await
+        }).await)
+    }
+
+    /// Klíč existujícího live záznamu téhož zápasu z jiného zdroje (fuzzy shoda týmů), jinak `id`.
+    fn canonical_live_key(live: &HashMap<MatchId, LiveMatch>, id: MatchId) -> MatchId {
+        if live.contains_key(&id) {
+            return id;
+        }
+        live.keys().find(|k| k.same_match(&id)).cloned().unwrap_or(id)
+    }
 
-            let winner = self.find_gosugamers_winner(&m.home, &m.away, results_url).await;
-            let winner_str = match winner {
-                Some(w) => w,
+    /// HLTV live CS2 zápasy → stejné `live_matches` jako GosuGamers. Fuzzy shoda týmů spojí
+    /// oba zdroje do jednoho záznamu (jedno vyhodnocení); roundy bere z HLTV jako z rychlejšího zdroje.
+    /// Vítěze dál dohledává GosuGamers — HLTV-only záznam po zmizení z HLTV jen zahodí.
+    pub fn ingest_hltv_live(&self, matches: &[HltvLiveMatch]) {
+        let now = self.clock.now_instant();
+        let mut live = self.live_matches.lock().unwrap();
+        let mut current: HashSet<MatchId> = HashSet::new();
+
+        for m in matches.iter().filter(|m| m.is_live) {
+            let key = Self::canonical_live_key(&live, MatchId::new(Sport::Cs2, &m.team1, &m.team2));
+            current.insert(key.clone());
+            let entry = live.entry(key).or_insert_with(|| {
+                info!("🔴 LIVE detekován: {} vs {} (CS2, HLTV)", m.team1, m.team2);
+                LiveMatch {
+                    home:       m.team1.clone(),
+                    away:       m.team2.clone(),
+                    sport:      "counterstrike".to_string(),
+                    first_seen: now,
+                    series_score: None,
+                    map_score:    None,
+                    total_maps:   None,
+                    score_history: Vec::new(),
+                    sources:    Vec::new(),
+                }
+            });
+            entry.add_source(SOURCE_HLTV);
+            if entry.accepts_score_from(SOURCE_HLTV) {
+                // Záznam může nést jiný zápis / pořadí týmů (GosuGamers slug)
+                let score = if match_id::team_names_equivalent(&entry.home, &m.team1) {
+                    (m.score1, m.score2)
+                } else {
+                    (m.score2, m.score1)
+                };
+                entry.map_score = Some(score);
+                entry.record_score(now, score);
+            }
+        }
+
+        live.retain(|k, m| {
+            if k.sport != Sport::Cs2 || current.contains(k) || !m.sources.contains(&SOURCE_HLTV) {
+                return true;
+            }
+            m.sources.retain(|s| *s != SOURCE_HLTV);
+            !m.sources.is_empty()
+        });
+    }
+
+    /// Winner lookupy zápasů dokončených v jednom cyklu souběžně (max `resolve_concurrency`),
+    /// nalezené výsledky se emitují jako MATCH_RESOLVED. Nenalezený zápas se přeskočí,
+    /// neúplné skóre vrací zápas do `live_matches` (další cyklus lookup zopakuje).
+    async fn resolve_finished<F, Fut>(&self, sport: &str, finished: Vec<(MatchId, LiveMatch)>, lookup: F) -> Vec<MatchResolvedEvent>
+    where
+        F: Fn(String, String, Option<u8>) -> Fut,
+        Fut: std::future::Future<Output = Option<SeriesOutcome>>,
+    {
+        let resolved: Vec<(MatchId, LiveMatch, Option<SeriesOutcome>)> = futures_util::stream::iter(finished)
+            .map(|(key, m)| {
+                let lookup_fut = lookup(m.home.clone(), m.away.clone(), m.total_maps);
+                async move { (key, m, lookup_fut.await) }
+            })
+            .buffer_unordered(self.resolve_concurrency)
+            .collect()
+            .await;
+
+        let mut newly_finished = Vec::new();
+        for (key, m, outcome) in resolved {
+            let winner_str = match outcome {
+                Some(SeriesOutcome::Winner(w)) => w,
+                Some(SeriesOutcome::Draw) => {
+                    // Evaluátor zatím nemá Draw outcome → jen uzavřít, ať se lookup neopakuje
+                    info!("🤝 MATCH FINISHED (byl LIVE): {} vs {} → remíza ({}), bez MATCH_RESOLVED", m.home, m.away, sport);
+                    self.mark_resolved(sport, &format!("{}_vs_{}", m.home, m.away));
+                    continue;
+                }
+                Some(SeriesOutcome::Incomplete) => {
+                    info!("{}: {} má na results neúplné skóre, zkusím příští cyklus.", sport, key);
+                    self.live_matches.lock().unwrap().entry(key).or_insert(m);
+                    continue;
+                }
                 None => {
                     warn!("{}: nelze dohledat vítěze pro {}, přeskakuji.", sport, key);
                     continue;
                 }
             };
-
             info!("✅ MATCH FINISHED (byl LIVE): {} vs {} → winner: {} ({})", m.home, m.away, winner_str, sport);
             let match_id = format!("{}_vs_{}", m.home, m.away);
             if let Some(ev) = self.emit_resolved(sport, &match_id, &m.home, &m.away, &winner_str) {
                 newly_finished.push(ev);
             }
         }
-
-        Ok(newly_finished)
+        newly_finished
     }
 
     /// Dohledá vítěze zápasu z GosuGamers results page.
     /// Formát na results page: href slug obsahuje názvy týmů,
     /// textContent obsahuje "Team1SCORE:SCORETeam2" pattern.
-    async fn find_gosugamers_winner(&self, home: &str, away: &str, results_url: &str) -> Option<String> {
-        let resp = self.client.get(results_url).send().await.ok()?;
-        if !resp.status().is_success() { return None; }
-
-        let html = resp.text().await.ok()?;
-        let document = Html::parse_document(&html);
-        let link_selector = Selector::parse("a[href*='/matches/']").unwrap();
-
-        let home_norm = home.to_lowercase();
-        let away_norm = away.to_lowercase();
+    /// GosuGamers /results pro sport — v rámci GOSU_RESULTS_CACHE_TTL sdílí jeden fetch mezi lookupy i auditem.
+    async fn gosugamers_results_rows(&self, sport: &str, url: &str) -> Result<Arc<Vec<GosuResultRow>>> {
+        // Souběžné lookupy (resolve_finished) se seřadí — první stáhne, ostatní vezmou cache
+        let _fetch_guard = self.gosu_results_fetch.lock().await;
+        let now = self.clock.now_instant();
+        if let Some((fetched_at, rows)) = self.gosu_results_cache.lock().unwrap().get(sport) {
+            if now.saturating_duration_since(*fetched_at) < GOSU_RESULTS_CACHE_TTL {
+                return Ok(Arc::clone(rows));
+            }
+        }
 
-        for node in document.select(&link_selector).take(15) {
-            let href = match node.value().attr("href") {
-                Some(h) => h,
-                None => continue,
-            };
-            if !href.contains("/tournaments/") { continue; }
+        let resp = self.get(url).send().await
+            .context(format!("GosuGamers {} results request failed", sport))?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!("GosuGamers {} HTTP {}", sport, resp.status()));
+        }
+        let html = resp.text().await?;
+        let rows = Arc::new(Self::parse_gosugamers_results(&html));
+        self.check_parsed_page("gosugamers", sport, &html, rows.len());
+        self.gosu_results_cache.lock().unwrap().insert(sport.to_string(), (now, Arc::clone(&rows)));
+        Ok(rows)
+    }
 
-            // Zkontroluj jestli href slug obsahuje oba týmy
-            let (t1, t2) = match Self::extract_teams_from_gosugamers_href(href) {
-                Some(pair) => pair,
-                None => continue,
-            };
+    /// Prvních 15 turnajových match linků z /results.
+    fn parse_gosugamers_results(html: &str) -> Vec<GosuResultRow> {
+        let document = Html::parse_document(html);
+        let link_selector = Selector::parse("a[href*='/matches/']").unwrap();
+        document.select(&link_selector)
+            .take(15)
+            .filter_map(|node| {
+                let href = node.value().attr("href").filter(|h| h.contains("/tournaments/"))?;
+                let (t1, t2) = Self::extract_teams_from_gosugamers_href(href)?;
+                let match_id = leading_numeric_id(href.rsplit('/').next().unwrap_or(""));
+                Some(GosuResultRow { t1, t2, text: node.text().collect(), match_id })
+            })
+            .collect()
+    }
 
-            let t1_norm = t1.to_lowercase();
-            let t2_norm = t2.to_lowercase();
+    async fn find_gosugamers_winner(&self, sport: &str, home: &str, away: &str, total_maps: Option<u8>, results_url: &str) -> Option<SeriesOutcome> {
+        let rows = self.gosugamers_results_rows(sport, results_url).await.ok()?;
 
-            let home_matches = t1_norm.contains(&home_norm) || home_norm.contains(&t1_norm);
-            let away_matches = t2_norm.contains(&away_norm) || away_norm.contains(&t2_norm);
+        // Regex: najdi pattern X:Y kde X,Y jsou čísla nebo W/FF
+        let score_re = regex::Regex::new(r"(\d+)\s*:\s*(\d+)").ok()?;
+        // Živý záznam může nést HLTV zápis týmů → stejný fuzzy matcher jako slučování zdrojů
+        let wanted = MatchId::from_labels(sport, home, away)?;
 
-            if !(home_matches && away_matches) {
-                // Zkus opačný směr
-                let home_matches_rev = t2_norm.contains(&home_norm) || home_norm.contains(&t2_norm);
-                let away_matches_rev = t1_norm.contains(&away_norm) || away_norm.contains(&t1_norm);
-                if !(home_matches_rev && away_matches_rev) { continue; }
+        for row in rows.iter() {
+            if !wanted.same_match(&MatchId::new(wanted.sport, &row.t1, &row.t2)) {
+                continue;
             }
 
-            // Najdi skóre v textu: pattern "SCORE:SCORE" (např. "2:0", "0:2", "W:FF")
-            let text: String = node.text().collect();
-            // Regex: najdi pattern X:Y kde X,Y jsou čísla nebo W/FF
-            let score_re = regex::Regex::new(r"(\d+)\s*:\s*(\d+)").ok()?;
-            if let Some(caps) = score_re.captures(&text) {
-                let s1: i32 = caps[1].parse().unwrap_or(0);
-                let s2: i32 = caps[2].parse().unwrap_or(0);
-                if s1 > s2 {
-                    return Some(t1);
-                } else if s2 > s1 {
-                    return Some(t2);
-                }
-            }
             // W:FF pattern
-            if text.contains("W:FF") || text.contains("W :FF") {
+            if row.text.contains("W:FF") || row.text.contains("W :FF") {
                 // Tým který má W je na pozici t1 (vzhledem k href ordering)
-                return Some(t1);
+                return Some(SeriesOutcome::Winner(row.t1.clone()));
             }
+            // Najdi skóre v textu: pattern "SCORE:SCORE" (např. "2:0", "0:2", "1:1")
+            return Some(match score_re.captures(&row.text) {
+                Some(caps) => SeriesOutcome::from_series_score(
+                    caps[1].parse().unwrap_or(0),
+                    caps[2].parse().unwrap_or(0),
+                    &row.t1,
+                    &row.t2,
+                    total_maps,
+                ),
+                None => SeriesOutcome::Incomplete,
+            });
         }
         None
     }
@@ -674,7 +2050,7 @@ impl EsportsMonitor {
 
     async fn poll_lol(&self) -> Result<Vec<MatchResolvedEvent>> {
         let url = "https://esports-api.lolesports.com/persisted/gw/getCompletedEvents?hl=en-US";
-        let resp = self.client.get(url)
+        let resp = self.get(url)
             .header("x-api-key", "0TvQnueqKa5mxJntVWt0w4LpLfEkrV1Ta8rQBb9Z")
             .send().await.context("LoL request failed")?;
 
@@ -691,12 +2067,13 @@ impl EsportsMonitor {
             for ev in event_list.iter().take(5) {
                 let state = ev.pointer("/state").and_then(|s| s.as_str()).unwrap_or("");
                 if state == "completed" {
-                    let match_id = ev.pointer("/match/id").and_then(|i| i.as_str()).unwrap_or("?");
+                    let riot_id = ev.pointer("/match/id").and_then(|i| i.as_str());
+                    let match_id = riot_id.unwrap_or("?");
                     let t1 = ev.pointer("/match/teams/0/name").and_then(|n| n.as_str()).unwrap_or("T1");
                     let t2 = ev.pointer("/match/teams/1/name").and_then(|n| n.as_str()).unwrap_or("T2");
                     let w1 = ev.pointer("/match/teams/0/result/outcome").and_then(|n| n.as_str()).unwrap_or("");
                     let winner = if w1 == "win" { t1.to_string() } else { t2.to_string() };
-                    if let Some(ev) = self.log_resolved("leagueoflegends", match_id, t1, t2, &winner) {
+                    if let Some(ev) = self.log_resolved("leagueoflegends", match_id, (t1, t2), &winner, riot_id) {
                         results.push(ev);
                     }
                 }
@@ -708,7 +2085,7 @@ impl EsportsMonitor {
 
     async fn poll_valorant(&self) -> Result<Vec<MatchResolvedEvent>> {
         let url = "https://www.vlr.gg/matches/results";
-        let resp = self.client.get(url).send().await.context("VLR request failed")?;
+        let resp = self.get(url).send().await.context("VLR request failed")?;
         if !resp.status().is_success() {
             return Err(anyhow::anyhow!("VLR HTTP {}", resp.status()));
         }
@@ -718,6 +2095,8 @@ impl EsportsMonitor {
         let team_selector = Selector::parse(".match-item-vs-team-name").unwrap();
         let score_selector = Selector::parse(".match-item-vs-team-score").unwrap();
 
+        self.check_parsed_page("vlrgg", "valorant", &html, document.select(&match_selector).count());
+
         let mut results = Vec::new();
         for node in document.select(&match_selector).take(5) {
             let teams: Vec<_> = node.select(&team_selector).map(|t| t.text().collect::<String>().trim().to_string()).collect();
@@ -728,7 +2107,10 @@ impl EsportsMonitor {
                 if s1 != s2 {
                     let winner = if s1 > s2 { &teams[0] } else { &teams[1] };
                     let match_id = teams[0].clone() + "_vs_" + &teams[1];
-                    if let Some(ev) = self.log_resolved("valorant", &match_id, &teams[0], &teams[1], winner) {
+                    // href="/12345/team-a-vs-team-b-..." → vlr.gg match ID
+                    let vlr_id = node.value().attr("href")
+                        .and_then(|h| leading_numeric_id(h.trim_start_matches('/')));
+                    if let Some(ev) = self.log_resolved("valorant", &match_id, (&teams[0], &teams[1]), winner, vlr_id.as_deref()) {
                         results.push(ev);
                     }
                 }
@@ -748,34 +2130,18 @@ impl EsportsMonitor {
 
     /// Generický GosuGamers results fallback scraper (SSR kompatibilní).
     async fn poll_gosugamers_results(&self, sport: &str, url: &str) -> Result<Vec<MatchResolvedEvent>> {
-        let resp = self.client.get(url).send().await
-            .context(format!("GosuGamers {} results request failed", sport))?;
-        if !resp.status().is_success() {
-            return Err(anyhow::anyhow!("GosuGamers {} HTTP {}", sport, resp.status()));
-        }
-        let html = resp.text().await?;
-        let document = Html::parse_document(&html);
-        let link_selector = Selector::parse("a[href*='/matches/']").unwrap();
+        let rows = self.gosugamers_results_rows(sport, url).await?;
         let score_re = regex::Regex::new(r"(\d+)\s*:\s*(\d+)").unwrap();
 
         let mut results = Vec::new();
-        for node in document.select(&link_selector).take(10) {
-            let href = match node.value().attr("href") {
-                Some(h) if h.contains("/tournaments/") => h,
-                _ => continue,
-            };
-            let (t1, t2) = match Self::extract_teams_from_gosugamers_href(href) {
-                Some(pair) => pair,
-                None => continue,
-            };
-            let text: String = node.text().collect();
-            if let Some(caps) = score_re.captures(&text) {
+        for row in rows.iter().take(10) {
+            if let Some(caps) = score_re.captures(&row.text) {
                 let s1: i32 = caps[1].parse().unwrap_or(0);
                 let s2: i32 = caps[2].parse().unwrap_or(0);
                 if s1 != s2 {
-                    let winner = if s1 > s2 { &t1 } else { &t2 };
-                    let match_id = format!("{}_vs_{}", t1, t2);
-                    if let Some(ev) = self.log_resolved(sport, &match_id, &t1, &t2, winner) {
+                    let winner = if s1 > s2 { &row.t1 } else { &row.t2 };
+                    let match_id = format!("{}_vs_{}", row.t1, row.t2);
+                    if let Some(ev) = self.log_resolved(sport, &match_id, (&row.t1, &row.t2), winner, row.match_id.as_deref()) {
                         results.push(ev);
                     }
                 }
@@ -787,7 +2153,34 @@ impl EsportsMonitor {
 
     // ── Helpers ───────────────────────────────────────────────────────────
 
+    /// Live cesta: emituje vždy, ale klíč zapíše do `seen_matches`, aby ho audit (`log_resolved`) už neposlal znovu.
     fn emit_resolved(&self, sport: &str, m_id: &str, t1: &str, t2: &str, winner: &str) -> Option<MatchResolvedEvent> {
+        self.mark_resolved(sport, m_id);
+        self.write_resolved(sport, m_id, (t1, t2), winner, None)
+    }
+
+    /// Results fallback (audit): jen zápasy, které ještě nebyly vyhodnocené live ani dřívějším auditem.
+    /// `source_match_id` = nativní ID zdroje, pokud ho results stránka nese.
+    fn log_resolved(&self, sport: &str, m_id: &str, teams: (&str, &str), winner: &str, source_match_id: Option<&str>) -> Option<MatchResolvedEvent> {
+        if !self.mark_resolved(sport, m_id) {
+            return None;
+        }
+        self.write_resolved(sport, m_id, teams, winner, source_match_id)
+    }
+
+    /// Zapíše klíč do `seen_matches`; false = už byl vyhodnocený.
+    fn mark_resolved(&self, sport: &str, m_id: &str) -> bool {
+        let unique_key = format!("{}_{}", sport, m_id);
+        let mut seen = self.seen_matches.lock().unwrap();
+        // Periodické čištění — max 500 entries
+        if seen.len() > 500 {
+            seen.clear();
+            debug!("seen_matches cleared (>500 entries)");
+        }
+        seen.insert(unique_key)
+    }
+
+    fn write_resolved(&self, sport: &str, m_id: &str, (t1, t2): (&str, &str), winner: &str, source_match_id: Option<&str>) -> Option<MatchResolvedEvent> {
         let ev = MatchResolvedEvent {
             ts:         now_iso(),
             event:      "MATCH_RESOLVED",
@@ -797,26 +2190,51 @@ impl EsportsMonitor {
             away:       t2.to_string(),
             winner:     winner.to_string(),
             ended_at:   now_iso(),
+            source_match_id: source_match_id.map(str::to_string),
+            match_id:        MatchId::from_labels(sport, t1, t2).map(|id| id.key()),
         };
         let _ = self.logger.log(&ev);
         Some(ev)
     }
 
-    fn log_resolved(&self, sport: &str, m_id: &str, t1: &str, t2: &str, winner: &str) -> Option<MatchResolvedEvent> {
-        // Deduplikace pro results fallback
-        let unique_key = format!("{}_{}", sport, m_id);
-        {
-            let mut seen = self.seen_matches.lock().unwrap();
-            // Periodické čištění — max 500 entries
-            if seen.len() > 500 {
-                seen.clear();
-                debug!("seen_matches cleared (>500 entries)");
+    /// Selftest: jeden request na zdroj ("lolesports" | "vlr" | "gosugamers"), nemění live stav.
+    /// Vrací krátký detail pro report, Err = zdroj nedostupný.
+    pub async fn probe_source(&self, source: &str) -> Result<String> {
+        match source {
+            "lolesports" => {
+                let url = "https://esports-api.lolesports.com/persisted/gw/getLive?hl=en-US";
+                let resp = self.get(url)
+                    .header("x-api-key", "0TvQnueqKa5mxJntVWt0w4LpLfEkrV1Ta8rQBb9Z")
+                    .send().await.context("LoL getLive request failed")?;
+                if !resp.status().is_success() {
+                    return Err(anyhow::anyhow!("LoL getLive HTTP {}", resp.status()));
+                }
+                let data: serde_json::Value = resp.json().await?;
+                let events = data.pointer("/data/schedule/events")
+                    .and_then(|v| v.as_array())
+                    .map(|e| e.len())
+                    .unwrap_or(0);
+                Ok(format!("HTTP 200, {} live events", events))
+            }
+            "vlr" => {
+                let resp = self.get("https://www.vlr.gg/matches")
+                    .send().await.context("VLR /matches request failed")?;
+                if !resp.status().is_success() {
+                    return Err(anyhow::anyhow!("VLR HTTP {}", resp.status()));
+                }
+                let html = resp.text().await?;
+                Ok(format!("HTTP 200, {} bytes", html.len()))
             }
-            if !seen.insert(unique_key) {
-                return None;
+            "gosugamers" => {
+                let html = self.fetch_gosugamers_html("cs2", "https://www.gosugamers.net/counterstrike/matches").await?;
+                if !html.contains("/matches/") {
+                    return Err(anyhow::anyhow!("GosuGamers: no match links ({} bytes, Cloudflare challenge?)", html.len()));
+                }
+                let mode = if self.chrome_available { "Chrome" } else { "HTTP-only" };
+                Ok(format!("{} OK, {} bytes", mode, html.len()))
             }
+            other => Err(anyhow::anyhow!("unknown probe source: {}", other)),
         }
-        self.emit_resolved(sport, m_id, t1, t2, winner)
     }
 
     fn log_api_error(&self, source: &str, sport: &str, msg: &str) {
@@ -829,9 +2247,33 @@ impl EsportsMonitor {
             status_code:  None,
             message:      msg.to_string(),
             items_logged: 0,
+            parser_suspect: false,
         });
     }
 
+    /// Načtená stránka bez jediného match elementu (ani ne-live) = selektory nesedí na markup.
+    /// Loguje API_STATUS s `parser_suspect`, počítá do health trackeru; vrací true pro suspect.
+    fn check_parsed_page(&self, source: &str, sport: &str, html: &str, match_elements: usize) -> bool {
+        let suspect = match_elements == 0 && !html.trim().is_empty() && !is_challenge_page(html);
+        let health_source = if source == "vlrgg" { "vlr" } else { source };
+        self.scrape_health.record_parse(health_source, suspect);
+        if suspect {
+            warn!("🧩 {} ({}): stránka načtená ({} B), ale 0 match elementů — změna markupu?", source, sport, html.len());
+            let _ = self.logger.log(&ApiStatusEvent {
+                ts:           now_iso(),
+                event:        "API_STATUS",
+                source:       source.to_string(),
+                scope:        sport.to_string(),
+                ok:           false,
+                status_code:  Some(200),
+                message:      "page loaded but no match elements matched selectors".to_string(),
+                items_logged: 0,
+                parser_suspect: true,
+            });
+        }
+        suspect
+    }
+
     fn log_api_ok(&self, source: &str, sport: &str, count: usize) {
         let _ = self.logger.log(&ApiStatusEvent {
             ts:           now_iso(),
@@ -842,6 +2284,1140 @@ impl EsportsMonitor {
             status_code:  Some(200),
             message:      "ok".to_string(),
             items_logged: count,
+            parser_suspect: false,
         });
     }
 }
+
+#[cfg(test)]
+mod http_client_tests {
+    use super::{Clock, EsportsMonitor, GosuResultRow, HltvLiveMatch, HttpTimeouts, LiveMatch, MatchId, MockClock, ScrapeError, SeriesOutcome, Sport};
+    use logger::EventReader;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn outcome_winner(outcome: &SeriesOutcome) -> Option<&str> {
+        match outcome {
+            SeriesOutcome::Winner(w) => Some(w),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn client_decodes_gzipped_response_body() {
+        let body = "<html><a class=\"match-item mod-live\">vlr</a></html>";
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(body.as_bytes()).unwrap();
+        let gz = enc.finish().unwrap();
+
+        let accept_encoding = Arc::new(Mutex::new(None));
+        let seen = Arc::clone(&accept_encoding);
+        let base_url = test_http::serve(move |req| {
+            *seen.lock().unwrap() = req.header("accept-encoding").map(str::to_string);
+            test_http::response("200 OK", &["Content-Type: text/html", "Content-Encoding: gzip"], &gz)
+        }).await;
+
+        let client = EsportsMonitor::build_http_client(super::timeouts::SCRAPE_TIMEOUT_DEFAULT);
+        let text = client.get(format!("{}/matches", base_url)).send().await.unwrap().text().await.unwrap();
+        assert_eq!(text, body);
+
+        let accept_encoding = accept_encoding.lock().unwrap().clone().unwrap_or_default();
+        assert!(accept_encoding.contains("gzip"), "request must advertise gzip: {accept_encoding}");
+    }
+
+    #[tokio::test]
+    async fn gosugamers_uses_http_only_when_chrome_is_unavailable() {
+        let body = r#"<html><a href="/counterstrike/tournaments/62675-cct/matches/641836-ground-zero-gaming-vs-mindfreak">Ground Zero Gaming Live MindFreak</a></html>"#;
+        let base_url = test_http::serve(move |_| test_http::html(body)).await;
+
+        let mut monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_gosugamers_http_only_logs"), 15);
+        monitor.chrome_available = false;
+
+        let finished = monitor
+            .poll_live_gosugamers("counterstrike", &format!("{}/counterstrike/matches", base_url))
+            .await
+            .expect("HTTP-only fetch must not try to launch Chrome");
+        assert!(finished.is_empty());
+
+        let live = monitor.live_matches.lock().unwrap();
+        assert_eq!(live.len(), 1);
+        assert!(live.values().any(|m| m.home == "ground zero gaming" && m.away == "mindfreak"));
+
+        let health = monitor.scraper_health().source("gosugamers");
+        assert_eq!((health.responses, health.challenge_pages, health.browser_fallbacks), (1, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn markup_change_is_flagged_as_parser_suspect_without_finishing_live_matches() {
+        // Redesign: match karty bez <a href=".../tournaments/.../matches/...">
+        let bodies = [
+            r#"<html><a href="/counterstrike/tournaments/62675-cct/matches/641836-alpha-vs-beta">alpha Live beta</a></html>"#,
+            r#"<html><div class="match-card" data-match="641836"><span>alpha</span> Live <span>beta</span></div></html>"#,
+        ];
+        let base_url = test_http::serve(move |req| test_http::html(bodies[req.index.min(1)])).await;
+        let url = format!("{}/counterstrike/matches", base_url);
+        let log_dir = std::env::temp_dir().join(format!("rustmisko_parser_suspect_logs_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&log_dir);
+        let mut monitor = EsportsMonitor::new(&log_dir, 15);
+        monitor.chrome_available = false;
+
+        monitor.poll_live_gosugamers("counterstrike", &url).await.unwrap();
+        assert_eq!(monitor.scraper_health().source("gosugamers").parser_suspect, 0);
+
+        // Rozbitý markup: alpha vs beta nesmí spadnout do "finished" větve
+        let finished = monitor.poll_live_gosugamers("counterstrike", &url).await.unwrap();
+        assert!(finished.is_empty());
+        assert_eq!(monitor.live_matches.lock().unwrap().len(), 1);
+
+        let health = monitor.scraper_health().source("gosugamers");
+        assert_eq!((health.pages_parsed, health.parser_suspect), (2, 1));
+
+        monitor.flush_logs();
+        let statuses: Vec<serde_json::Value> = EventReader::new(&log_dir).read_all("API_STATUS").unwrap()
+            .into_iter()
+            .filter(|v| v["parser_suspect"] == true)
+            .collect();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0]["source"], "gosugamers");
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    #[tokio::test]
+    async fn audit_does_not_re_emit_match_resolved_live() {
+        let body = concat!(
+            r#"<html><a href="/counterstrike/tournaments/62675-cct/matches/641836-alpha-vs-beta">alpha 2 : 0 beta</a>"#,
+            r#"<a href="/counterstrike/tournaments/62675-cct/matches/641837-gamma-vs-delta">gamma 1 : 2 delta</a></html>"#,
+        );
+        let base_url = test_http::serve(move |_| test_http::html(body)).await;
+
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_audit_dedup_logs"), 15);
+        // Live cesta už alpha vs beta vyhodnotila
+        assert!(monitor.emit_resolved("counterstrike", "alpha_vs_beta", "alpha", "beta", "alpha").is_some());
+
+        let audit = monitor
+            .poll_gosugamers_results("counterstrike", &format!("{}/counterstrike/matches/results", base_url))
+            .await
+            .unwrap();
+        let names: Vec<&str> = audit.iter().map(|ev| ev.match_name.as_str()).collect();
+        assert_eq!(names, vec!["gamma_vs_delta"]);
+        assert_eq!(audit[0].source_match_id.as_deref(), Some("641837"));
+    }
+
+    #[tokio::test]
+    async fn gosugamers_results_page_is_fetched_once_for_winner_lookups_within_ttl() {
+        let body = concat!(
+            r#"<html><a href="/counterstrike/tournaments/1-cct/matches/1-alpha-vs-beta">alpha 2 : 0 beta</a>"#,
+            r#"<a href="/counterstrike/tournaments/1-cct/matches/2-gamma-vs-delta">gamma 0 : 2 delta</a>"#,
+            r#"<a href="/counterstrike/tournaments/1-cct/matches/3-eps-vs-zeta">eps 2 : 1 zeta</a></html>"#,
+        );
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&fetches);
+        let base_url = test_http::serve(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            test_http::html(body)
+        }).await;
+        let url = format!("{}/counterstrike/matches/results", base_url);
+
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_gosu_results_cache_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+
+        // Tři dokončené zápasy v jednom cyklu → jeden fetch /results
+        assert_eq!(monitor.find_gosugamers_winner("counterstrike", "alpha", "beta", None, &url).await.as_ref().and_then(outcome_winner), Some("alpha"));
+        assert_eq!(monitor.find_gosugamers_winner("counterstrike", "gamma", "delta", None, &url).await.as_ref().and_then(outcome_winner), Some("delta"));
+        assert_eq!(monitor.find_gosugamers_winner("counterstrike", "eps", "zeta", None, &url).await.as_ref().and_then(outcome_winner), Some("eps"));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Po TTL se stránka stáhne znovu
+        clock.advance_secs(6);
+        assert_eq!(monitor.find_gosugamers_winner("counterstrike", "alpha", "beta", None, &url).await.as_ref().and_then(outcome_winner), Some("alpha"));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // Souběžné lookupy (resolve_finished) po TTL → pořád jen jeden fetch
+        clock.advance_secs(6);
+        let (a, b, c) = tokio::join!(
+            monitor.find_gosugamers_winner("counterstrike", "alpha", "beta", None, &url),
+            monitor.find_gosugamers_winner("counterstrike", "gamma", "delta", None, &url),
+            monitor.find_gosugamers_winner("counterstrike", "eps", "zeta", None, &url),
+        );
+        let winner = |o: &Option<SeriesOutcome>| o.as_ref().and_then(outcome_winner).map(str::to_string);
+        assert_eq!((winner(&a).as_deref(), winner(&b).as_deref(), winner(&c).as_deref()), (Some("alpha"), Some("delta"), Some("eps")));
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn genuine_tie_is_a_draw_but_zero_zero_scrape_is_retried() {
+        let html = concat!(
+            r#"<html><a href="/dota2/tournaments/1-dpc/matches/1-alpha-vs-beta">alpha 1 : 1 beta</a>"#,
+            r#"<a href="/dota2/tournaments/1-dpc/matches/2-gamma-vs-delta">gamma 0 : 0 delta</a></html>"#,
+        );
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_series_outcome_logs"), 15);
+        // Předvyplněná cache → lookup nejde na síť
+        monitor.gosu_results_cache.lock().unwrap().insert(
+            "dota2".to_string(),
+            (monitor.clock.now_instant(), Arc::new(EsportsMonitor::parse_gosugamers_results(html))),
+        );
+        let url = "http://127.0.0.1:9/dota2/matches/results";
+        assert_eq!(monitor.find_gosugamers_winner("dota2", "alpha", "beta", Some(2), url).await, Some(SeriesOutcome::Draw));
+        assert_eq!(monitor.find_gosugamers_winner("dota2", "alpha", "beta", Some(3), url).await, Some(SeriesOutcome::Incomplete));
+        assert_eq!(monitor.find_gosugamers_winner("dota2", "gamma", "delta", Some(2), url).await, Some(SeriesOutcome::Incomplete));
+
+        let vlr = concat!(
+            r#"<a class="match-item"><div class="match-item-vs-team-name">Alpha</div><div class="match-item-vs-team-score">1</div>"#,
+            r#"<div class="match-item-vs-team-name">Beta</div><div class="match-item-vs-team-score">1</div></a>"#,
+            r#"<a class="match-item"><div class="match-item-vs-team-name">Gamma</div><div class="match-item-vs-team-score">0</div>"#,
+            r#"<div class="match-item-vs-team-name">Delta</div><div class="match-item-vs-team-score">0</div></a>"#,
+        );
+        assert_eq!(EsportsMonitor::valorant_result_outcome(vlr, "alpha", "beta", Some(2)), Some(SeriesOutcome::Draw));
+        assert_eq!(EsportsMonitor::valorant_result_outcome(vlr, "alpha", "beta", None), Some(SeriesOutcome::Incomplete));
+        assert_eq!(EsportsMonitor::valorant_result_outcome(vlr, "gamma", "delta", Some(2)), Some(SeriesOutcome::Incomplete));
+    }
+
+    #[tokio::test]
+    async fn incomplete_score_requeues_match_and_draw_closes_it_without_event() {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_series_outcome_logs"), 15);
+        let finished: Vec<(MatchId, LiveMatch)> = ["alpha", "gamma"].iter().map(|home| {
+            let m = LiveMatch {
+                home:          home.to_string(),
+                away:          "beta".to_string(),
+                sport:         "dota2".to_string(),
+                first_seen:    std::time::Instant::now(),
+                series_score:  Some((1, 1)),
+                map_score:     None,
+                total_maps:    Some(2),
+                score_history: Vec::new(),
+                sources:       Vec::new(),
+            };
+            (MatchId::new(Sport::Dota2, home, "beta"), m)
+        }).collect();
+
+        let events = monitor.resolve_finished("dota2", finished, |home, _away, _total_maps| async move {
+            Some(if home == "alpha" { SeriesOutcome::Draw } else { SeriesOutcome::Incomplete })
+        }).await;
+
+        assert!(events.is_empty());
+        let live = monitor.live_matches.lock().unwrap();
+        assert!(live.contains_key(&MatchId::new(Sport::Dota2, "gamma", "beta")));
+        assert!(!live.contains_key(&MatchId::new(Sport::Dota2, "alpha", "beta")));
+        drop(live);
+        // Remíza je uzavřená → audit ji znovu nevyhodnotí
+        assert!(!monitor.mark_resolved("dota2", "alpha_vs_beta"));
+    }
+
+    #[test]
+    fn series_score_plausibility() {
+        assert_eq!(SeriesOutcome::from_series_score(2, 1, "a", "b", Some(3)), SeriesOutcome::Winner("a".to_string()));
+        assert_eq!(SeriesOutcome::from_series_score(0, 2, "a", "b", None), SeriesOutcome::Winner("b".to_string()));
+        assert_eq!(SeriesOutcome::from_series_score(1, 1, "a", "b", Some(2)), SeriesOutcome::Draw);
+        assert_eq!(SeriesOutcome::from_series_score(2, 2, "a", "b", Some(4)), SeriesOutcome::Draw);
+        assert_eq!(SeriesOutcome::from_series_score(0, 0, "a", "b", Some(2)), SeriesOutcome::Incomplete);
+        assert_eq!(SeriesOutcome::from_series_score(13, 13, "a", "b", None), SeriesOutcome::Incomplete);
+    }
+
+    #[test]
+    fn bo3_one_all_is_an_unfinished_series_not_a_draw() {
+        assert_eq!(SeriesOutcome::from_series_score(1, 1, "a", "b", Some(3)), SeriesOutcome::Incomplete);
+        assert_eq!(SeriesOutcome::from_series_score(1, 1, "a", "b", None), SeriesOutcome::Incomplete);
+        assert_eq!(SeriesOutcome::from_series_score(1, 1, "a", "b", Some(4)), SeriesOutcome::Incomplete);
+    }
+
+    #[tokio::test]
+    async fn same_cs2_match_from_hltv_and_gosugamers_resolves_once() {
+        let pages = [
+            r#"<html><a href="/counterstrike/tournaments/1-iem/matches/700-vitality-vs-g2">Vitality Live G2</a></html>"#,
+            r#"<html><a href="/counterstrike/tournaments/1-iem/matches/701-mouz-vs-furia">MOUZ 0h21m FURIA</a></html>"#,
+        ];
+        let base_url = test_http::serve(move |req| test_http::html(pages[req.index.min(1)])).await;
+        let url = format!("{}/counterstrike/matches", base_url);
+        let dir = std::env::temp_dir().join(format!("rustmisko_cross_source_dedup_{}", std::process::id()));
+        let mut monitor = EsportsMonitor::new(&dir, 15);
+        monitor.chrome_available = false;
+
+        let hltv = |score1, score2| HltvLiveMatch {
+            match_id: 2370001,
+            team1: "Team Vitality".to_string(),
+            team2: "G2 Esports".to_string(),
+            score1,
+            score2,
+            is_live: true,
+            map_name: None,
+            series_format: "bo3".to_string(),
+            current_round: None,
+            last_update: std::time::Instant::now(),
+            url: "https://www.hltv.org/matches/2370001".to_string(),
+        };
+
+        // HLTV vidí zápas první, GosuGamers ho hlásí pod jiným zápisem → pořád jeden záznam
+        monitor.ingest_hltv_live(&[hltv(10, 4)]);
+        assert!(monitor.poll_live_gosugamers("counterstrike", &url).await.unwrap().is_empty());
+        {
+            let live = monitor.live_matches.lock().unwrap();
+            assert_eq!(live.len(), 1);
+            let m = live.values().next().unwrap();
+            assert_eq!(m.sources, vec!["hltv", "gosugamers"]);
+            assert_eq!(m.map_score, Some((10, 4)), "skóre z HLTV zůstává");
+        }
+        monitor.ingest_hltv_live(&[hltv(12, 5)]);
+        assert_eq!(monitor.live_matches.lock().unwrap().values().next().unwrap().map_score, Some((12, 5)));
+
+        // Zápas zmizí z GosuGamers → jedno vyhodnocení (results z cache, bez sítě)
+        monitor.gosu_results_cache.lock().unwrap().insert("counterstrike".to_string(), (
+            monitor.clock.now_instant(),
+            Arc::new(vec![GosuResultRow {
+                t1: "vitality".to_string(),
+                t2: "g2".to_string(),
+                text: "Vitality 2:1 G2".to_string(),
+                match_id: Some("700".to_string()),
+            }]),
+        ));
+        let resolved = monitor.poll_live_gosugamers("counterstrike", &url).await.unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].winner, "vitality");
+
+        // HLTV ho ještě chvíli hlásí → HLTV-only záznam, GosuGamers ho znovu nevyhodnotí
+        monitor.ingest_hltv_live(&[hltv(13, 5)]);
+        assert!(monitor.poll_live_gosugamers("counterstrike", &url).await.unwrap().is_empty());
+        monitor.ingest_hltv_live(&[]);
+        assert!(monitor.live_matches.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn simultaneous_finishes_resolve_concurrently_with_capped_parallelism() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_resolve_concurrency_{}", std::process::id()));
+        let monitor = EsportsMonitor::new(&dir, 15).with_resolve_concurrency(2);
+        let finished: Vec<(MatchId, LiveMatch)> = (0..5).map(|i| {
+            let (home, away) = (format!("home{}", i), format!("away{}", i));
+            let m = LiveMatch {
+                home:          home.clone(),
+                away:          away.clone(),
+                sport:         "counterstrike".to_string(),
+                first_seen:    std::time::Instant::now(),
+                series_score:  None,
+                map_score:     None,
+                total_maps:    None,
+                score_history: Vec::new(),
+                sources:       Vec::new(),
+            };
+            (MatchId::new(Sport::Cs2, &home, &away), m)
+        }).collect();
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let events = monitor.resolve_finished("counterstrike", finished, |home, _away, _total_maps| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                // home4 nemá výsledek → přeskočen, ostatní vyhrál domácí
+                (home != "home4").then_some(SeriesOutcome::Winner(home))
+            }
+        }).await;
+
+        let mut winners: Vec<String> = events.iter().map(|ev| ev.winner.clone()).collect();
+        winners.sort();
+        assert_eq!(winners, ["home0", "home1", "home2", "home3"]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2, "max 2 lookupy současně");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn consecutive_requests_rotate_user_agent() {
+        let seen = Arc::new(Mutex::new(Vec::<String>::new()));
+        let sink = Arc::clone(&seen);
+        let base_url = test_http::serve(move |req| {
+            sink.lock().unwrap().push(req.header("user-agent").unwrap_or("").to_string());
+            test_http::response("200 OK", &[], "ok")
+        }).await;
+        let url = format!("{}/matches", base_url);
+
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_ua_rotation_logs"), 15)
+            .with_user_agents(vec!["ua-one".to_string(), "ua-two".to_string()]);
+        for _ in 0..3 {
+            monitor.get(&url).send().await.unwrap();
+        }
+
+        assert_eq!(*seen.lock().unwrap(), vec!["ua-one", "ua-two", "ua-one"]);
+    }
+
+    #[test]
+    fn chrome_flag_comes_from_startup_probe_not_constructor() {
+        // Konstruktor Chrome nespouští → HTTP-only, dokud binárka nepředá výsledek probe
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_chrome_flag_logs"), 15);
+        assert!(!monitor.chrome_available());
+        let monitor = monitor.with_hltv_live(true).with_chrome_available(true);
+        assert!(monitor.chrome_available());
+        assert!(monitor.hltv.is_some());
+    }
+
+    #[tokio::test]
+    async fn vlr_challenge_page_takes_browser_fallback_with_cooldown() {
+        let body = r#"<title>Just a moment...</title><script src="/cdn-cgi/challenge-platform/h/b"></script>"#;
+        let base_url = test_http::serve(move |_| test_http::html(body)).await;
+        let url = format!("{}/matches", base_url);
+
+        // Chrome "právě běžel" → fallback větev se vezme, ale cooldown nespustí další Chrome
+        let clock = MockClock::new();
+        let mut monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_vlr_fallback_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+        monitor.chrome_available = true;
+        *monitor.vlr_browser_last.lock().unwrap() = Some(clock.now_instant());
+
+        let err = monitor.fetch_vlr_html(&url).await.unwrap_err();
+        assert!(
+            matches!(err.downcast_ref::<ScrapeError>(), Some(ScrapeError::Browser(msg)) if msg.contains("cooldown")),
+            "got {err:?}"
+        );
+        let health = monitor.scraper_health().source("vlr");
+        assert_eq!((health.responses, health.challenge_pages, health.browser_fallbacks), (1, 1, 1));
+
+        // Bez Chrome zůstane původní challenge chyba (HTTP-only)
+        monitor.chrome_available = false;
+        let err = monitor.fetch_vlr_html(&url).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<ScrapeError>(), Some(&ScrapeError::Challenge));
+        assert_eq!(monitor.scraper_health().source("vlr").browser_fallbacks, 1);
+    }
+
+    #[tokio::test]
+    async fn slow_chrome_fetch_gets_its_own_longer_timeout() {
+        let timeouts = HttpTimeouts {
+            chrome:       std::time::Duration::from_secs(2),
+            sx_orderbook: std::time::Duration::from_millis(150),
+            ..HttpTimeouts::default()
+        };
+        let slow_page = || {
+            std::thread::sleep(std::time::Duration::from_millis(400));
+            Ok("<html>gosu</html>".to_string())
+        };
+
+        let html = EsportsMonitor::run_chrome_fetch(timeouts.chrome, slow_page).await.unwrap();
+        assert_eq!(html, "<html>gosu</html>");
+
+        // Stejně pomalý fetch pod těsným (orderbook) limitem skončí Timeout chybou
+        let err = EsportsMonitor::run_chrome_fetch(timeouts.sx_orderbook, slow_page).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<ScrapeError>(), Some(&ScrapeError::Timeout));
+    }
+}
+
+#[cfg(test)]
+mod poll_schedule_tests {
+    use super::{Clock, EsportsMonitor, MockClock, Sport, SourceSchedule, MAX_BACKOFF_INTERVAL};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn failing_source_interval_grows_and_recovers() {
+        let mut schedule = SourceSchedule::new(Duration::from_secs(15));
+        let now = Instant::now();
+
+        schedule.record(now, false);
+        schedule.record(now, false);
+        assert_eq!(schedule.effective, Duration::from_secs(15));
+
+        schedule.record(now, false);
+        assert_eq!(schedule.effective, Duration::from_secs(30));
+        schedule.record(now, false);
+        assert_eq!(schedule.effective, Duration::from_secs(60));
+        assert!(!schedule.is_due(now + Duration::from_secs(59)));
+        assert!(schedule.is_due(now + Duration::from_secs(60)));
+
+        for _ in 0..10 {
+            schedule.record(now, false);
+        }
+        assert_eq!(schedule.effective, MAX_BACKOFF_INTERVAL);
+
+        schedule.record(now, true);
+        assert_eq!(schedule.effective, Duration::from_secs(15));
+        assert_eq!(schedule.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn per_source_intervals_are_independent() {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_poll_schedule_logs"), 15)
+            .with_poll_intervals(HashMap::from([
+                (Sport::Cs2, Duration::from_secs(5)),
+                (Sport::Dota2, Duration::from_secs(60)),
+            ]));
+        assert_eq!(monitor.effective_interval(Sport::Cs2), Duration::from_secs(5));
+        assert_eq!(monitor.effective_interval(Sport::Dota2), Duration::from_secs(60));
+
+        let now = Instant::now();
+        for _ in 0..4 {
+            monitor.record_poll_result(Sport::Cs2, now, false);
+        }
+        assert_eq!(monitor.effective_interval(Sport::Cs2), Duration::from_secs(20));
+        assert_eq!(monitor.effective_interval(Sport::Dota2), Duration::from_secs(60));
+        assert!(!monitor.source_due(Sport::Cs2, now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn backoff_window_follows_injected_clock() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_poll_schedule_logs"), 15)
+            .with_poll_intervals(HashMap::from([(Sport::Cs2, Duration::from_secs(15))]))
+            .with_clock(Arc::new(clock.clone()));
+
+        for _ in 0..3 {
+            monitor.record_poll_result(Sport::Cs2, clock.now_instant(), false);
+        }
+        clock.advance_secs(29);
+        assert!(!monitor.source_due(Sport::Cs2, clock.now_instant()));
+        clock.advance_secs(1);
+        assert!(monitor.source_due(Sport::Cs2, clock.now_instant()));
+
+        monitor.record_poll_result(Sport::Cs2, clock.now_instant(), false);
+        clock.advance_secs(59);
+        assert!(!monitor.source_due(Sport::Cs2, clock.now_instant()));
+        clock.advance_secs(1);
+        assert!(monitor.source_due(Sport::Cs2, clock.now_instant()));
+    }
+
+    /// Jeden tick `poll_live_all` s podvrženými scrapery → které zdroje se reálně zavolaly.
+    async fn tick(monitor: &EsportsMonitor, failing: &[Sport]) -> Vec<Sport> {
+        let polled = Mutex::new(Vec::new());
+        monitor.poll_due_sources(|sport| {
+            polled.lock().unwrap().push(sport);
+            let ok = !failing.contains(&sport);
+            async move { if ok { Ok(Vec::new()) } else { Err(anyhow::anyhow!("scrape failed")) } }
+        }).await;
+        polled.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn vlr_and_gosu_wait_for_the_15s_interval() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_poll_schedule_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+
+        assert_eq!(tick(&monitor, &[]).await, Sport::ALL.to_vec());
+
+        // LoL (interval 0) jede každý tick, vlr.gg a GosuGamers čekají
+        clock.advance_secs(14);
+        assert_eq!(tick(&monitor, &[]).await, vec![Sport::Lol]);
+
+        clock.advance_secs(1);
+        assert_eq!(tick(&monitor, &[]).await, Sport::ALL.to_vec());
+    }
+
+    #[tokio::test]
+    async fn failing_source_falls_back_to_doubled_cadence() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_poll_schedule_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+
+        for _ in 0..3 {
+            tick(&monitor, &[Sport::Valorant]).await;
+            clock.advance_secs(15);
+        }
+        // 3 chyby → vlr.gg na 30s, GosuGamers dál po 15s
+        assert_eq!(tick(&monitor, &[]).await, vec![Sport::Lol, Sport::Cs2, Sport::Dota2]);
+        clock.advance_secs(15);
+        assert_eq!(tick(&monitor, &[]).await, Sport::ALL.to_vec());
+    }
+}
+
+#[cfg(test)]
+mod live_match_eviction_tests {
+    use super::{Clock, EsportsMonitor, LiveMatch, MatchId, MockClock, Sport};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn live_match(home: &str, first_seen: std::time::Instant) -> LiveMatch {
+        LiveMatch {
+            home:          home.to_string(),
+            away:          "beta".to_string(),
+            sport:         "cs2".to_string(),
+            first_seen,
+            series_score:  None,
+            map_score:     None,
+            total_maps:    None,
+            score_history: Vec::new(),
+            sources:       Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_live_match_is_evicted_after_max_age() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_live_eviction_logs"), 15)
+            .with_clock(Arc::new(clock.clone()))
+            .with_live_match_max_age(Duration::from_secs(3 * 3600));
+        monitor.live_matches.lock().unwrap()
+            .insert(MatchId::new(Sport::Cs2, "stale", "beta"), live_match("stale", clock.now_instant()));
+        clock.advance(Duration::from_secs(2 * 3600));
+        monitor.live_matches.lock().unwrap()
+            .insert(MatchId::new(Sport::Cs2, "fresh", "beta"), live_match("fresh", clock.now_instant()));
+
+        clock.advance(Duration::from_secs(3600 + 1));
+        monitor.poll_due_sources(|_| async { Ok(Vec::new()) }).await;
+
+        let live = monitor.live_matches.lock().unwrap();
+        assert!(!live.contains_key(&MatchId::new(Sport::Cs2, "stale", "beta")));
+        assert!(live.contains_key(&MatchId::new(Sport::Cs2, "fresh", "beta")));
+        drop(live);
+        assert!(monitor.is_any_match_live());
+
+        clock.advance(Duration::from_secs(2 * 3600));
+        assert_eq!(monitor.evict_stale_live_matches(), 1);
+        assert!(!monitor.is_any_match_live());
+    }
+}
+
+#[cfg(test)]
+mod stratz_rest_tests {
+    use super::stratz::{StratzLiveMatch, STRATZ_SERIES_GAP};
+    use super::{Clock, EsportsMonitor, MatchId, MockClock, Sport, StratzRestConfig};
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Mock STRATZ: 1. live snapshot, 2.+3. hra 1 Spirit vs GG dohrána (GG), 4. hra 2 (GG, strany prohozené),
+    /// pak 429 s Retry-After: 600.
+    async fn spawn_stratz() -> String {
+        test_http::serve(|req| match req.index {
+            0 => test_http::json(include_str!("../tests/fixtures/stratz_live.json")),
+            1 | 2 => test_http::json(include_str!("../tests/fixtures/stratz_live_completed.json")),
+            3 => test_http::json(include_str!("../tests/fixtures/stratz_live_series_point.json")),
+            _ => test_http::response("429 Too Many Requests", &["Retry-After: 600"], ""),
+        }).await
+    }
+
+    #[tokio::test]
+    async fn rest_fallback_counts_games_and_resolves_only_decided_series() {
+        let clock = MockClock::new();
+        let base_url = spawn_stratz().await;
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_stratz_rest_logs"), 15)
+            .with_clock(Arc::new(clock.clone()))
+            .with_stratz_rest(StratzRestConfig { base_url, token: None, interval: Duration::from_secs(60) });
+
+        assert!(monitor.stratz_rest_due(clock.now_instant()));
+        assert!(monitor.poll_stratz_rest().await.unwrap().is_empty());
+        assert_eq!(monitor.live_matches.lock().unwrap().len(), 2, "pub hra bez týmů se nesleduje");
+        assert!(!monitor.stratz_rest_due(clock.now_instant()));
+
+        // Délka série ze STRATZ series.type; Tundra vs BetBoom ji nenese
+        let spirit_gg = MatchId::new(Sport::Dota2, "Team Spirit", "Gaimin Gladiators");
+        assert_eq!(monitor.live_matches.lock().unwrap()[&spirit_gg].total_maps, Some(3));
+        assert_eq!(monitor.live_matches.lock().unwrap()[&MatchId::new(Sport::Dota2, "Tundra Esports", "BetBoom Team")].total_maps, None);
+
+        // Dohraná hra 1 série neukončí, jen připíše mapu — i když ji /match/live hlásí dva polly po sobě
+        for _ in 0..2 {
+            clock.advance(Duration::from_secs(60));
+            assert!(monitor.stratz_rest_due(clock.now_instant()));
+            assert!(monitor.poll_stratz_rest().await.unwrap().is_empty());
+            assert_eq!(monitor.live_matches.lock().unwrap()[&spirit_gg].series_score, Some((0, 1)));
+        }
+
+        // Hra 2 (GG na Radiant) → 0:2 v Bo3 = série rozhodnutá
+        clock.advance(Duration::from_secs(60));
+        let finished = monitor.poll_stratz_rest().await.unwrap();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].winner, "Gaimin Gladiators");
+        assert_eq!(finished[0].sport, "dota2");
+        {
+            let live = monitor.live_matches.lock().unwrap();
+            assert!(live.contains_key(&MatchId::new(Sport::Dota2, "Tundra Esports", "BetBoom Team")));
+            assert!(!live.contains_key(&spirit_gg));
+        }
+
+        // 429 → další poll až po Retry-After, ne po běžném intervalu
+        clock.advance(Duration::from_secs(60));
+        assert!(monitor.poll_stratz_rest().await.is_err());
+        clock.advance(Duration::from_secs(599));
+        assert!(!monitor.stratz_rest_due(clock.now_instant()));
+        clock.advance(Duration::from_secs(1));
+        assert!(monitor.stratz_rest_due(clock.now_instant()));
+    }
+
+    fn game(match_id: u64, radiant_won: Option<bool>) -> StratzLiveMatch {
+        StratzLiveMatch {
+            match_id,
+            radiant:  "Team Spirit".to_string(),
+            dire:     "Gaimin Gladiators".to_string(),
+            radiant_won,
+            series_length: None,
+        }
+    }
+
+    #[test]
+    fn unknown_length_series_survives_gap_between_games() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_stratz_gap_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+        let key = MatchId::new(Sport::Dota2, "Team Spirit", "Gaimin Gladiators");
+        let series = |m: &EsportsMonitor| m.live_matches.lock().unwrap().get(&key).map(|m| (m.series_score, m.total_maps));
+
+        assert!(monitor.ingest_stratz_live(&[game(1, None)]).is_empty());
+        assert!(monitor.ingest_stratz_live(&[game(1, Some(true))]).is_empty());
+        assert_eq!(series(&monitor), Some((Some((1, 0)), None)));
+
+        // Pauza mezi hrami: zápas z /match/live zmizí, série i skóre zůstávají
+        clock.advance(Duration::from_secs(5 * 60));
+        assert!(monitor.ingest_stratz_live(&[]).is_empty());
+        clock.advance(Duration::from_secs(5 * 60));
+        assert!(monitor.ingest_stratz_live(&[]).is_empty());
+        assert!(monitor.ingest_stratz_live(&[game(2, None)]).is_empty());
+        assert!(monitor.ingest_stratz_live(&[game(2, Some(false))]).is_empty());
+        assert_eq!(series(&monitor), Some((Some((1, 1)), None)), "hra 2 navazuje na 1:0, ne na 0:0");
+
+        // Výpadek se počítá od posledního zmizení; teprve po STRATZ_SERIES_GAP → dohraná série
+        assert!(monitor.ingest_stratz_live(&[]).is_empty());
+        clock.advance(STRATZ_SERIES_GAP - Duration::from_secs(1));
+        assert!(monitor.ingest_stratz_live(&[]).is_empty());
+        clock.advance(Duration::from_secs(1));
+        let finished = monitor.ingest_stratz_live(&[]);
+        assert_eq!(finished.len(), 1);
+        assert_eq!((finished[0].1.series_score, finished[0].2.as_deref()), (Some((1, 1)), None));
+        assert!(series(&monitor).is_none());
+    }
+
+    #[test]
+    fn rest_fallback_yields_to_connected_websocket_with_token() {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_stratz_rest_logs"), 15)
+            .with_stratz_rest(StratzRestConfig { token: Some("t".to_string()), ..StratzRestConfig::default() });
+        let now = std::time::Instant::now();
+        assert!(monitor.stratz_rest_due(now));
+        monitor.stratz_ws_live.store(true, Ordering::SeqCst);
+        assert!(!monitor.stratz_rest_due(now));
+    }
+}
+
+#[cfg(test)]
+mod lol_task_tests {
+    use super::{EsportsMonitor, Sport};
+    use logger::MatchResolvedEvent;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn resolved(match_name: &str) -> MatchResolvedEvent {
+        MatchResolvedEvent {
+            ts:         "2026-01-01T00:00:00Z".to_string(),
+            event:      "MATCH_RESOLVED",
+            sport:      "lol".to_string(),
+            match_name: match_name.to_string(),
+            home:       "t1".to_string(),
+            away:       "geng".to_string(),
+            winner:     "t1".to_string(),
+            ended_at:   "2026-01-01T00:00:00Z".to_string(),
+            source_match_id: None,
+            match_id:        None,
+        }
+    }
+
+    #[tokio::test]
+    async fn lol_task_stays_rate_bounded_while_cs2_polls_faster() {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_lol_task_logs"), 15)
+            .with_poll_intervals(HashMap::from([(Sport::Cs2, Duration::ZERO)]))
+            .with_lol_min_interval(Duration::from_millis(100));
+        let lol_polls = AtomicUsize::new(0);
+        let cs2_polls = AtomicUsize::new(0);
+        let mut collected = Vec::new();
+
+        let lol_task = monitor.run_lol_loop(|_| {
+            let n = lol_polls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // Simulace čekání na Riot token — nesmí brzdit CS2 smyčku
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                Ok(if n == 0 { vec![resolved("t1_vs_geng")] } else { Vec::new() })
+            }
+        });
+        let fast_loop = async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            let deadline = tokio::time::Instant::now() + Duration::from_millis(500);
+            while tokio::time::Instant::now() < deadline {
+                collected.extend(monitor.poll_due_sources(|sport| {
+                    if sport == Sport::Cs2 {
+                        cs2_polls.fetch_add(1, Ordering::SeqCst);
+                    }
+                    assert_ne!(sport, Sport::Lol, "LoL má běžet jen ve vlastním tasku");
+                    async { Ok(Vec::new()) }
+                }).await);
+                collected.extend(monitor.drain_lol_events());
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::select! {
+            _ = lol_task => unreachable!("LoL task běží do zahození"),
+            _ = fast_loop => {}
+        }
+
+        let lol = lol_polls.load(Ordering::SeqCst);
+        let cs2 = cs2_polls.load(Ordering::SeqCst);
+        assert!((1..=6).contains(&lol), "LoL polls: {lol}");
+        assert!(cs2 > lol * 5, "CS2 polls: {cs2}, LoL polls: {lol}");
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].match_name, "t1_vs_geng");
+        // Po zahození tasku se LoL vrací do `poll_live_all`
+        assert!(!monitor.lol_in_task.load(Ordering::SeqCst));
+    }
+}
+
+#[cfg(test)]
+mod sniper_gate_tests {
+    use super::{EsportsMonitor, LiveMatch, MatchId, Sport};
+
+    fn monitor_with_live(home: &str, away: &str, score: (u8, u8)) -> EsportsMonitor {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_sniper_gate_logs"), 15);
+        monitor.live_matches.lock().unwrap().insert(
+            MatchId::new(Sport::Valorant, home, away),
+            LiveMatch {
+                home:       home.to_string(),
+                away:       away.to_string(),
+                sport:      "valorant".to_string(),
+                first_seen: std::time::Instant::now(),
+                series_score: None,
+                map_score:    Some(score),
+                total_maps:   None,
+                score_history: Vec::new(),
+                sources:    Vec::new(),
+            },
+        );
+        monitor
+    }
+
+    #[test]
+    fn blowout_enables_sniper_mode() {
+        let monitor = monitor_with_live("Alpha", "Beta", (12, 3));
+        assert!(monitor.is_any_match_live());
+        assert!(monitor.has_conclusive_or_near_match(0.85));
+    }
+
+    #[test]
+    fn even_match_keeps_sniper_mode_off() {
+        let monitor = monitor_with_live("Alpha", "Beta", (3, 2));
+        assert!(monitor.is_any_match_live());
+        assert!(!monitor.has_conclusive_or_near_match(0.85));
+    }
+
+    #[test]
+    fn live_prediction_produces_well_formed_event() {
+        let monitor = monitor_with_live("Alpha", "Beta", (12, 3));
+        let events = monitor.live_predictions();
+        assert_eq!(events.len(), 1);
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["event"], "PREDICTION");
+        assert_eq!(json["match_id"], MatchId::new(Sport::Valorant, "Alpha", "Beta").to_string());
+        assert_eq!(json["sport"], "valorant");
+        assert_eq!((json["home"].as_str(), json["away"].as_str()), (Some("Alpha"), Some("Beta")));
+        assert_eq!(json["prediction"], "team1_win");
+        assert!(json["confidence"].as_f64().is_some_and(|c| c >= 0.85));
+        assert_eq!(json["inputs"]["map_score"], serde_json::json!([12, 3]));
+        assert!(json["inputs"]["series_score"].is_null());
+        assert_eq!(json["inputs"]["map_number"], 1);
+        // Neznámý formát → engine dostal None a PREDICTION ho tak i loguje
+        assert!(json["inputs"]["best_of"].is_null());
+        assert!(json["inputs"].get("best_of_guessed").is_none());
+        assert!(chrono::DateTime::parse_from_rfc3339(json["ts"].as_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn resolved_event_carries_prediction_join_key() {
+        let monitor = monitor_with_live("Alpha", "Beta", (12, 3));
+        let prediction = monitor.live_predictions().remove(0);
+        // Zdroj výsledku má vlastní match_name; join jde přes match_id
+        let resolved = monitor.emit_resolved("valorant", "12345-alpha-vs-beta", "Alpha", "Beta", "Alpha").unwrap();
+        assert_eq!(resolved.match_id.as_deref(), Some(prediction.match_id.as_str()));
+        assert_ne!(resolved.match_name, prediction.match_id);
+    }
+}
+
+#[cfg(test)]
+mod score_history_tests {
+    use super::{Clock, EsportsMonitor, LiveMatch, MockClock, SCORE_HISTORY_CAP};
+    use prediction_engine::Prediction;
+    use std::sync::Arc;
+
+    fn valorant_match() -> LiveMatch {
+        LiveMatch {
+            home:       "Alpha".to_string(),
+            away:       "Beta".to_string(),
+            sport:      "valorant".to_string(),
+            first_seen: std::time::Instant::now(),
+            series_score: None,
+            map_score:    None,
+            total_maps:   Some(3),
+            score_history: Vec::new(),
+            sources:    Vec::new(),
+        }
+    }
+
+    #[test]
+    fn growing_lead_over_polls_is_detected_as_momentum() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_score_history_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+        let mut m = valorant_match();
+
+        // Jeden snapshot 11:8 → bez historie žádné momentum
+        m.map_score = Some((11, 8));
+        assert_eq!(monitor.live_prediction(&m), Prediction::Uncertain);
+
+        // 8:8 → 9:8 → 10:8 → 11:8 po 15s pollech (11:8 samo o sobě nestačí na 0.88 větev)
+        m.score_history.clear();
+        for score in [(8, 8), (8, 8), (9, 8), (10, 8), (11, 8)] {
+            m.map_score = Some(score);
+            m.record_score(clock.now_instant(), score);
+            clock.advance_secs(15);
+        }
+        assert_eq!(m.score_history.len(), 4, "unchanged score is not recorded twice");
+        assert_eq!(monitor.live_prediction(&m), Prediction::Team1Win(0.75));
+
+        // Beta získá round → série přerušená
+        m.map_score = Some((11, 9));
+        m.record_score(clock.now_instant(), (11, 9));
+        assert_eq!(monitor.live_prediction(&m), Prediction::Uncertain);
+    }
+
+    #[test]
+    fn history_is_capped_and_resets_on_new_map() {
+        let clock = MockClock::new();
+        let mut m = valorant_match();
+        for r in 0..(SCORE_HISTORY_CAP as u8 + 10) {
+            m.record_score(clock.now_instant(), (r, 0));
+            clock.advance_secs(15);
+        }
+        assert_eq!(m.score_history.len(), SCORE_HISTORY_CAP);
+        assert_eq!(m.score_history.last().map(|&(_, s1, _)| s1), Some(SCORE_HISTORY_CAP as u8 + 9));
+
+        // Skóre kleslo → nová mapa, stará historie pryč
+        m.record_score(clock.now_instant(), (1, 0));
+        assert_eq!(m.score_history.len(), 1);
+
+        let history = m.history_for_state(clock.now_utc(), clock.now_instant());
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].0, clock.now_utc());
+    }
+}
+
+#[cfg(test)]
+mod vlr_series_tests {
+    use super::{parse_best_of_label, Clock, EsportsMonitor, LiveMatch, MockClock, VlrLiveItem, VlrMatchDetail};
+    use prediction_engine::Prediction;
+    use std::sync::Arc;
+
+    const MATCHES_HTML: &str = include_str!("../tests/fixtures/vlr_matches_live_1_0.html");
+    const DETAIL_HTML: &str = include_str!("../tests/fixtures/vlr_match_detail_live_1_0.html");
+    const DETAIL_BO5_HTML: &str = include_str!("../tests/fixtures/vlr_match_detail_live_bo5_2_1.html");
+    const MATCHES_BO_LABELS_HTML: &str = include_str!("../tests/fixtures/vlr_matches_live_bo_labels.html");
+    const LIVE_SCORE_JSON: &str = include_str!("../tests/fixtures/vlrggapi_live_score_1_0.json");
+
+    fn live_match(series_score: Option<(u8, u8)>, map_score: Option<(u8, u8)>) -> LiveMatch {
+        LiveMatch {
+            home:       "Team Heretics".to_string(),
+            away:       "FNATIC".to_string(),
+            sport:      "valorant".to_string(),
+            first_seen: std::time::Instant::now(),
+            series_score,
+            map_score,
+            total_maps: Some(3),
+            score_history: Vec::new(),
+            sources:    Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_list_score_is_series_score() {
+        let items = EsportsMonitor::parse_vlr_live_items(MATCHES_HTML);
+        assert_eq!(items.len(), 1, "only the mod-live item is live");
+        assert_eq!(items[0].home, "Team Heretics");
+        assert_eq!(items[0].away, "FNATIC");
+        assert_eq!(items[0].series_score, Some((1, 0)));
+        assert_eq!(items[0].href.as_deref(), Some("/312345/team-heretics-vs-fnatic-champions-tour-2026-emea-stage-1-w3"));
+    }
+
+    #[test]
+    fn detail_page_separates_series_and_map_round_score() {
+        let detail = EsportsMonitor::parse_vlr_match_detail(DETAIL_HTML);
+        assert_eq!(detail, VlrMatchDetail {
+            series_score: Some((1, 0)),
+            map_score:    Some((7, 5)),
+            best_of:      Some(3),
+        });
+    }
+
+    #[test]
+    fn series_length_is_parsed_from_bo_labels() {
+        assert_eq!(EsportsMonitor::parse_vlr_match_detail(DETAIL_HTML).best_of, Some(3));
+        let bo5 = EsportsMonitor::parse_vlr_match_detail(DETAIL_BO5_HTML);
+        assert_eq!(bo5.best_of, Some(5));
+        assert_eq!(bo5.series_score, Some((2, 1)));
+
+        let items = EsportsMonitor::parse_vlr_live_items(MATCHES_BO_LABELS_HTML);
+        let labels: Vec<_> = items.iter().map(|i| (i.home.as_str(), i.best_of)).collect();
+        assert_eq!(labels, [("Team Heretics", Some(3)), ("Sentinels", Some(5))]);
+        // List bez štítku → neznámé, ne tichý Bo3
+        assert_eq!(EsportsMonitor::parse_vlr_live_items(MATCHES_HTML)[0].best_of, None);
+
+        assert_eq!(parse_best_of_label("Best of 5"), Some(5));
+        assert_eq!(parse_best_of_label("Bo2"), None);
+        assert_eq!(parse_best_of_label("bots"), None);
+    }
+
+    #[test]
+    fn unknown_series_length_is_not_guessed() {
+        let known = LiveMatch { total_maps: Some(5), ..live_match(Some((2, 1)), None) };
+        let unknown = LiveMatch { total_maps: None, ..live_match(Some((2, 1)), None) };
+
+        // 2-1 v Bo3 je hotová série, v Bo5 ne
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_vlr_bo_logs"), 15);
+        assert_ne!(monitor.live_prediction(&live_match(Some((2, 1)), None)), Prediction::Uncertain);
+        assert_eq!(monitor.live_prediction(&known), Prediction::Uncertain);
+        // Neznámá délka: žádný tichý Bo3 — vstupy nesou přesně to, co dostal engine
+        let (prediction, inputs) = monitor.live_prediction_with_inputs(&unknown);
+        assert_eq!(prediction, Prediction::Uncertain);
+        assert_eq!(inputs.best_of, None);
+        assert_eq!(monitor.live_prediction_with_inputs(&known).1.best_of, Some(5));
+    }
+
+    #[test]
+    fn json_live_score_produces_same_live_matches_as_html() {
+        let body: serde_json::Value = serde_json::from_str(LIVE_SCORE_JSON).unwrap();
+        let json_entries = EsportsMonitor::parse_vlr_json_live(&body).unwrap();
+        assert_eq!(json_entries.len(), 1, "upcoming segment is not live");
+
+        let html_item = EsportsMonitor::parse_vlr_live_items(MATCHES_HTML).remove(0);
+        let html_entries = vec![(html_item, EsportsMonitor::parse_vlr_match_detail(DETAIL_HTML))];
+        assert_eq!(json_entries[0].0.href, html_entries[0].0.href);
+
+        let snapshot = |entries: &[(VlrLiveItem, VlrMatchDetail)], tag: &str| {
+            let monitor = EsportsMonitor::new(std::env::temp_dir().join(format!("rustmisko_vlr_json_{}", tag)), 15);
+            let keys = monitor.record_valorant_live(entries);
+            let live = monitor.live_matches.lock().unwrap();
+            let mut matches: Vec<_> = live.iter()
+                .map(|(k, m)| (k.clone(), m.home.clone(), m.away.clone(), m.series_score, m.map_score))
+                .collect();
+            matches.sort_by_key(|m| m.0.to_string());
+            (keys, matches)
+        };
+        let (json_keys, json_matches) = snapshot(&json_entries, "json");
+        let (html_keys, html_matches) = snapshot(&html_entries, "html");
+        assert_eq!(json_keys, html_keys);
+        assert_eq!(json_matches, html_matches);
+        assert_eq!(json_matches[0].3, Some((1, 0)));
+        assert_eq!(json_matches[0].4, Some((7, 5)));
+    }
+
+    #[test]
+    fn json_without_segments_is_an_error_for_html_fallback() {
+        assert!(EsportsMonitor::parse_vlr_json_live(&serde_json::json!({"error": "rate limited"})).is_err());
+        assert!(EsportsMonitor::parse_vlr_json_live(&serde_json::json!({"data": {"segments": []}})).is_err());
+        assert!(EsportsMonitor::parse_vlr_json_live(&serde_json::json!({"data": {"segments": [{"score1": "1"}]}})).is_err());
+    }
+
+    #[tokio::test]
+    async fn fresh_details_come_from_cache_in_list_order() {
+        let clock = MockClock::new();
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_vlr_detail_cache_logs"), 15)
+            .with_clock(Arc::new(clock.clone()));
+        let detail = |m| VlrMatchDetail { series_score: Some((1, 0)), map_score: Some((m, 3)), best_of: Some(3) };
+        {
+            let mut cache = monitor.vlr_detail_cache.lock().unwrap();
+            for (href, m) in [("/1/a-vs-b", 7), ("/2/c-vs-d", 9), ("/9/finished", 13)] {
+                cache.insert(href.to_string(), (clock.now_instant(), detail(m)));
+            }
+        }
+        clock.advance_secs(5);
+
+        let item = |href: &str| VlrLiveItem {
+            home: "A".to_string(), away: "B".to_string(), series_score: Some((1, 0)), best_of: None, href: Some(href.to_string()),
+        };
+        let entries = monitor.fetch_vlr_details(vec![item("/2/c-vs-d"), item("/1/a-vs-b")]).await;
+
+        // V rámci TTL žádný request, pořadí podle listu
+        let maps: Vec<_> = entries.iter().map(|(i, d)| (i.href.as_deref().unwrap(), d.map_score)).collect();
+        assert_eq!(maps, [("/2/c-vs-d", Some((9, 3))), ("/1/a-vs-b", Some((7, 3)))]);
+        // Zápas mimo live list z cache vypadne
+        assert!(!monitor.vlr_detail_cache.lock().unwrap().contains_key("/9/finished"));
+    }
+
+    #[test]
+    fn series_only_score_feeds_predict_series() {
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_vlr_series_logs"), 15);
+
+        // 1-0 v Bo3 bez roundů → série nerozhodnutá, ne "mapa 1:0"
+        assert_eq!(monitor.live_prediction(&live_match(Some((1, 0)), None)), Prediction::Uncertain);
+
+        // Se skóre mapy jde per-map predikce (12:3 na mapě 2)
+        assert_eq!(
+            monitor.live_prediction(&live_match(Some((1, 0)), Some((12, 3)))),
+            Prediction::Team1Win(0.98)
+        );
+    }
+}
+
+#[cfg(test)]
+mod lol_schedule_tests {
+    use super::{EsportsMonitor, MatchId, Sport};
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn schedule_keeps_unstarted_matches_within_horizon() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 14, 12, 0, 0).unwrap();
+        let data = json!({ "data": { "schedule": { "events": [
+            { "startTime": "2026-03-14T15:00:00Z", "state": "unstarted",
+              "match": { "id": "113475", "teams": [{ "name": "T1" }, { "name": "Gen.G" }] } },
+            { "startTime": "2026-03-14T11:00:00Z", "state": "inProgress",
+              "match": { "id": "113474", "teams": [{ "name": "G2 Esports" }, { "name": "Fnatic" }] } },
+            { "startTime": "2026-03-15T18:00:00Z", "state": "unstarted",
+              "match": { "id": "113476", "teams": [{ "name": "JD Gaming" }, { "name": "BLG" }] } },
+            { "startTime": "2026-03-14T16:00:00Z", "state": "unstarted",
+              "match": { "id": "113477", "teams": [{ "name": "TBD" }, { "name": "TBD" }] } }
+        ] } } });
+
+        let upcoming = EsportsMonitor::parse_lol_schedule(&data, now);
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].id, MatchId::new(Sport::Lol, "Gen.G", "T1"));
+        assert_eq!(upcoming[0].source_match_id.as_deref(), Some("113475"));
+        assert_eq!(upcoming[0].start_time, Utc.with_ymd_and_hms(2026, 3, 14, 15, 0, 0).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod lol_league_filter_tests {
+    use super::{EsportsMonitor, MatchId, Sport};
+    use serde_json::json;
+
+    fn live_event(league_id: &str, slug: &str, name: &str, t1: &str, t2: &str) -> serde_json::Value {
+        json!({
+            "state": "inProgress",
+            "league": { "id": league_id, "slug": slug, "name": name },
+            "match": { "teams": [{ "name": t1 }, { "name": t2 }] }
+        })
+    }
+
+    #[test]
+    fn only_whitelisted_leagues_are_recorded() {
+        let data = json!({ "data": { "schedule": { "events": [
+            live_event("98767991310872058", "lck", "LCK", "T1", "Gen.G"),
+            live_event("98767991302996019", "lec", "LEC", "G2 Esports", "Fnatic"),
+            live_event("98767991355908944", "lck_challengers_league", "LCK Challengers", "T1 Academy", "Gen.G Academy"),
+            live_event("113470291645289904", "lta_north", "LTA North", "Cloud9", "Team Liquid"),
+        ] } } });
+
+        let monitor = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_lol_league_filter_logs"), 15)
+            .with_lol_leagues(vec!["LCK".into(), " lec ".into(), "113470291645289904".into()]);
+        monitor.process_lol_live(&data);
+        {
+            let live = monitor.live_matches.lock().unwrap();
+            assert_eq!(live.len(), 3);
+            assert!(live.contains_key(&MatchId::new(Sport::Lol, "T1", "Gen.G")));
+            assert!(live.contains_key(&MatchId::new(Sport::Lol, "G2 Esports", "Fnatic")));
+            assert!(live.contains_key(&MatchId::new(Sport::Lol, "Cloud9", "Team Liquid")));
+            assert!(!live.contains_key(&MatchId::new(Sport::Lol, "T1 Academy", "Gen.G Academy")));
+        }
+
+        // Default (prázdný whitelist) = všechny ligy
+        let all = EsportsMonitor::new(std::env::temp_dir().join("rustmisko_lol_league_filter_logs"), 15)
+            .with_lol_leagues(Vec::new());
+        all.process_lol_live(&data);
+        assert_eq!(all.live_matches.lock().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,153 @@
+//! STRATZ REST fallback pro Dota 2 live — když GraphQL WebSocket bez tokenu nic nedává.
+//!
+//! `GET /api/v1/match/live` vrací pole právě hraných her (jedna položka = jedna hra, ne série).
+//! Pro live → finished se počítají jen pro zápasy s oběma týmy (pub hry bez týmů se přeskakují).
+//! Mezi hrami série zápas z `/match/live` na pár minut zmizí — za dohraný se bere až po `STRATZ_SERIES_GAP`.
+
+use std::time::Duration;
+
+/// Výchozí base URL STRATZ API.
+pub const STRATZ_REST_URL_DEFAULT: &str = "https://api.stratz.com";
+/// Výchozí interval REST pollu — anonymní tier má nízký limit, 1× za minutu je bezpečné.
+pub const STRATZ_REST_INTERVAL_DEFAULT: Duration = Duration::from_secs(60);
+/// Pauza po 429 bez Retry-After hlavičky.
+pub const STRATZ_RATE_LIMIT_BACKOFF_DEFAULT: Duration = Duration::from_secs(300);
+/// Jak dlouho smí série hlášená jen STRATZem chybět v `/match/live` (pauza mezi hrami), než se bere jako dohraná.
+pub const STRATZ_SERIES_GAP: Duration = Duration::from_secs(20 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StratzRestConfig {
+    /// Base URL (testy: mock server)
+    pub base_url: String,
+    /// Bearer token (STRATZ_API_TOKEN); None = anonymní přístup, WebSocket pak nepoužitelný
+    pub token:    Option<String>,
+    pub interval: Duration,
+}
+
+impl Default for StratzRestConfig {
+    fn default() -> Self {
+        Self {
+            base_url: STRATZ_REST_URL_DEFAULT.to_string(),
+            token:    None,
+            interval: STRATZ_REST_INTERVAL_DEFAULT,
+        }
+    }
+}
+
+impl StratzRestConfig {
+    /// STRATZ_REST_URL, STRATZ_API_TOKEN, STRATZ_REST_INTERVAL_SECS; chybějící / prázdné / 0 = default.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let non_empty = |key: &str| lookup(key)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let defaults = Self::default();
+        Self {
+            base_url: non_empty("STRATZ_REST_URL")
+                .map(|u| u.trim_end_matches('/').to_string())
+                .unwrap_or(defaults.base_url),
+            token:    non_empty("STRATZ_API_TOKEN"),
+            interval: non_empty("STRATZ_REST_INTERVAL_SECS")
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|n| *n > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+        }
+    }
+
+    pub fn live_url(&self) -> String {
+        format!("{}/api/v1/match/live", self.base_url)
+    }
+}
+
+/// Jedna live hra ze STRATZ `/match/live`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StratzLiveMatch {
+    pub match_id: u64,
+    pub radiant:  String,
+    pub dire:     String,
+    /// Some(true) = hra skončila výhrou Radiant, None = ještě běží
+    pub radiant_won: Option<bool>,
+    /// Délka série z `series.type` ("BEST_OF_THREE" → 3); None = STRATZ ji neposlal
+    pub series_length: Option<u8>,
+}
+
+impl StratzLiveMatch {
+    pub fn winner(&self) -> Option<&str> {
+        self.radiant_won.map(|won| if won { self.radiant.as_str() } else { self.dire.as_str() })
+    }
+}
+
+/// Pole her z `/match/live`; hry bez obou týmů (pub / ranked) se vynechají.
+pub fn parse_stratz_live(body: &serde_json::Value) -> Result<Vec<StratzLiveMatch>, String> {
+    let games = body.as_array().ok_or_else(|| "STRATZ /match/live: čekám JSON pole".to_string())?;
+    Ok(games.iter().filter_map(|g| {
+        let team = |side: &str| g.pointer(&format!("/{}Team/name", side))
+            .and_then(|n| n.as_str())
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string);
+        let completed = g.get("isCompleted").and_then(|c| c.as_bool()).unwrap_or(false);
+        Some(StratzLiveMatch {
+            match_id: g.get("matchId").and_then(|id| id.as_u64())?,
+            radiant:  team("radiant")?,
+            dire:     team("dire")?,
+            radiant_won: if completed { g.get("didRadiantWin").and_then(|w| w.as_bool()) } else { None },
+            series_length: g.pointer("/series/type").and_then(|t| t.as_str()).and_then(parse_series_type),
+        })
+    }).collect())
+}
+
+/// STRATZ `SeriesEnum` → počet her série.
+fn parse_series_type(raw: &str) -> Option<u8> {
+    match raw {
+        "BEST_OF_ONE" => Some(1),
+        "BEST_OF_TWO" => Some(2),
+        "BEST_OF_THREE" => Some(3),
+        "BEST_OF_FIVE" => Some(5),
+        _ => None,
+    }
+}
+
+/// Retry-After v sekundách (HTTP-date varianta STRATZ neposílá → None).
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers.get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod stratz_tests {
+    use super::{parse_stratz_live, StratzRestConfig, STRATZ_REST_INTERVAL_DEFAULT};
+    use std::time::Duration;
+
+    #[test]
+    fn config_reads_lookup_and_falls_back_to_defaults() {
+        let cfg = StratzRestConfig::from_lookup(|key| match key {
+            "STRATZ_REST_URL" => Some("http://127.0.0.1:9/".to_string()),
+            "STRATZ_API_TOKEN" => Some("  ".to_string()),
+            "STRATZ_REST_INTERVAL_SECS" => Some("120".to_string()),
+            _ => None,
+        });
+        assert_eq!(cfg.live_url(), "http://127.0.0.1:9/api/v1/match/live");
+        assert_eq!(cfg.token, None);
+        assert_eq!(cfg.interval, Duration::from_secs(120));
+        assert_eq!(StratzRestConfig::from_lookup(|_| None).interval, STRATZ_REST_INTERVAL_DEFAULT);
+    }
+
+    #[test]
+    fn captured_live_response_keeps_only_team_games() {
+        let body: serde_json::Value = serde_json::from_str(include_str!("../tests/fixtures/stratz_live.json")).unwrap();
+        let games = parse_stratz_live(&body).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!((games[0].radiant.as_str(), games[0].dire.as_str()), ("Team Spirit", "Gaimin Gladiators"));
+        assert_eq!(games[0].winner(), None);
+        assert_eq!(games[0].series_length, Some(3));
+        assert_eq!(games[1].series_length, None);
+        assert!(parse_stratz_live(&serde_json::json!({"error": "x"})).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Průběžná dostupnost klíčových scraping endpointů (HLTV / GosuGamers).
+//!
+//! `EndpointProber` periodicky stáhne každý endpoint čistým HTTP (bez browser fallbacku — chceme
+//! vědět, jestli HTTP cesta dostává challenge) a výsledek zapíše do klouzavého okna per URL.
+//! Snapshot je JSON pro API a Prometheus gauge pro metrics endpoint.
+
+use crate::health::{is_challenge_page, ScrapeOutcome};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Okno, přes které se počítá dostupnost.
+pub const AVAILABILITY_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Endpointy, které scrapery reálně používají.
+pub const DEFAULT_PROBE_ENDPOINTS: [&str; 5] = [
+    "https://www.hltv.org/results",
+    "https://www.hltv.org/matches",
+    "https://www.hltv.org/live",
+    "https://www.gosugamers.net/counterstrike/matches",
+    "https://www.gosugamers.net/dota2/matches",
+];
+
+/// Výsledky probe jednoho endpointu v okně.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EndpointStats {
+    pub samples:    usize,
+    pub ok:         usize,
+    pub challenged: usize,
+    pub forbidden:  usize,
+    pub errors:     usize,
+}
+
+impl EndpointStats {
+    /// Podíl OK odpovědí v okně (0–100); bez vzorků None.
+    pub fn available_pct(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.ok as f64 / self.samples as f64 * 100.0)
+    }
+
+    /// Podíl challenge stránek v okně (0–100); bez vzorků None.
+    pub fn challenged_pct(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.challenged as f64 / self.samples as f64 * 100.0)
+    }
+}
+
+/// Snapshot dostupnosti (url → stats) za posledních AVAILABILITY_WINDOW.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EndpointAvailability {
+    pub window_secs: u64,
+    pub endpoints:   BTreeMap<String, EndpointStats>,
+}
+
+impl EndpointAvailability {
+    pub fn endpoint(&self, url: &str) -> EndpointStats {
+        self.endpoints.get(url).cloned().unwrap_or_default()
+    }
+
+    /// Prometheus text format (gauge per url; endpoint bez vzorků se vynechá).
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        for (name, pick) in [
+            ("scraper_endpoint_available_ratio", EndpointStats::available_pct as fn(&EndpointStats) -> Option<f64>),
+            ("scraper_endpoint_challenged_ratio", EndpointStats::challenged_pct),
+        ] {
+            out.push_str(&format!("# TYPE {} gauge\n", name));
+            for (url, stats) in &self.endpoints {
+                if let Some(pct) = pick(stats) {
+                    out.push_str(&format!("{}{{url=\"{}\"}} {:.4}\n", name, url, pct / 100.0));
+                }
+            }
+        }
+        out.push_str("# TYPE scraper_endpoint_probes gauge\n");
+        for (url, stats) in &self.endpoints {
+            out.push_str(&format!("scraper_endpoint_probes{{url=\"{}\"}} {}\n", url, stats.samples));
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+pub struct EndpointAvailabilityTracker {
+    endpoints: Mutex<HashMap<String, VecDeque<(Instant, ScrapeOutcome)>>>,
+}
+
+impl EndpointAvailabilityTracker {
+    pub fn record(&self, url: &str, outcome: ScrapeOutcome) {
+        self.record_at(url, outcome, Instant::now());
+    }
+
+    pub fn snapshot(&self) -> EndpointAvailability {
+        self.snapshot_at(Instant::now())
+    }
+
+    fn record_at(&self, url: &str, outcome: ScrapeOutcome, now: Instant) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let window = endpoints.entry(url.to_string()).or_default();
+        window.push_back((now, outcome));
+        while window.front().is_some_and(|(at, _)| now.duration_since(*at) > AVAILABILITY_WINDOW) {
+            window.pop_front();
+        }
+    }
+
+    fn snapshot_at(&self, now: Instant) -> EndpointAvailability {
+        let endpoints = self.endpoints.lock().unwrap();
+        let endpoints = endpoints.iter()
+            .map(|(url, window)| {
+                let mut stats = EndpointStats::default();
+                for (_, outcome) in window.iter().filter(|(at, _)| now.saturating_duration_since(*at) <= AVAILABILITY_WINDOW) {
+                    stats.samples += 1;
+                    match outcome {
+                        ScrapeOutcome::Ok        => stats.ok += 1,
+                        ScrapeOutcome::Challenge => stats.challenged += 1,
+                        ScrapeOutcome::Forbidden => stats.forbidden += 1,
+                        ScrapeOutcome::Error     => stats.errors += 1,
+                    }
+                }
+                (url.clone(), stats)
+            })
+            .collect();
+        EndpointAvailability { window_secs: AVAILABILITY_WINDOW.as_secs(), endpoints }
+    }
+}
+
+/// HTTP status + tělo → výsledek probe (stejná klasifikace jako `fetch_html_http`).
+pub fn classify_probe_response(status: reqwest::StatusCode, body: &str) -> ScrapeOutcome {
+    if status == reqwest::StatusCode::FORBIDDEN {
+        ScrapeOutcome::Forbidden
+    } else if !status.is_success() {
+        ScrapeOutcome::Error
+    } else if is_challenge_page(body) {
+        ScrapeOutcome::Challenge
+    } else {
+        ScrapeOutcome::Ok
+    }
+}
+
+/// Background prober endpointů; tracker je sdílený (`tracker()`) pro API / metrics.
+pub struct EndpointProber {
+    client:    reqwest::Client,
+    endpoints: Vec<String>,
+    tracker:   Arc<EndpointAvailabilityTracker>,
+}
+
+impl EndpointProber {
+    pub fn new(endpoints: Vec<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .gzip(true)
+                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new()),
+            endpoints,
+            tracker: Arc::new(EndpointAvailabilityTracker::default()),
+        }
+    }
+
+    pub fn with_default_endpoints() -> Self {
+        Self::new(DEFAULT_PROBE_ENDPOINTS.iter().map(|u| u.to_string()).collect())
+    }
+
+    /// Zapisuje do existujícího trackeru (např. sdíleného se stavem HTTP serveru).
+    pub fn with_tracker(mut self, tracker: Arc<EndpointAvailabilityTracker>) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    pub fn tracker(&self) -> Arc<EndpointAvailabilityTracker> {
+        Arc::clone(&self.tracker)
+    }
+
+    /// Jedno kolo přes všechny endpointy (sekvenčně, ať nezahltíme zdroj).
+    pub async fn probe_all(&self) {
+        for url in &self.endpoints {
+            let outcome = match self.client.get(url).send().await {
+                Ok(resp) => {
+                    let status = resp.status();
+                    let body = resp.text().await.unwrap_or_default();
+                    classify_probe_response(status, &body)
+                }
+                Err(e) => {
+                    debug!("endpoint probe {} failed: {}", url, e);
+                    ScrapeOutcome::Error
+                }
+            };
+            if outcome != ScrapeOutcome::Ok {
+                warn!("🛰️ endpoint probe {} → {:?}", url, outcome);
+            }
+            self.tracker.record(url, outcome);
+        }
+    }
+
+    /// Nekonečná smyčka `probe_all` každých `every`.
+    pub async fn run(self, every: Duration) {
+        loop {
+            self.probe_all().await;
+            tokio::time::sleep(every).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod availability_tests {
+    use super::{classify_probe_response, EndpointAvailabilityTracker, AVAILABILITY_WINDOW};
+    use crate::ScrapeOutcome;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn availability_is_computed_over_rolling_window() {
+        let tracker = EndpointAvailabilityTracker::default();
+        let results = "https://www.hltv.org/results";
+        let matches = "https://www.hltv.org/matches";
+        let t0 = Instant::now();
+
+        // Stará challenge mimo okno se nepočítá
+        tracker.record_at(results, ScrapeOutcome::Challenge, t0);
+        let t1 = t0 + AVAILABILITY_WINDOW + Duration::from_secs(60);
+        for (i, outcome) in [
+            ScrapeOutcome::Ok, ScrapeOutcome::Challenge, ScrapeOutcome::Ok,
+            ScrapeOutcome::Ok, ScrapeOutcome::Forbidden,
+        ].into_iter().enumerate() {
+            tracker.record_at(results, outcome, t1 + Duration::from_secs(i as u64 * 60));
+        }
+        tracker.record_at(matches, ScrapeOutcome::Error, t1);
+
+        let now = t1 + Duration::from_secs(300);
+        let snap = tracker.snapshot_at(now);
+        let stats = snap.endpoint(results);
+        assert_eq!((stats.samples, stats.ok, stats.challenged, stats.forbidden), (5, 3, 1, 1));
+        assert_eq!(stats.available_pct(), Some(60.0));
+        assert_eq!(stats.challenged_pct(), Some(20.0));
+        assert_eq!(snap.endpoint(matches).available_pct(), Some(0.0));
+        assert_eq!(snap.endpoint("https://unknown").available_pct(), None);
+
+        let prom = snap.render_prometheus();
+        assert!(prom.contains("scraper_endpoint_challenged_ratio{url=\"https://www.hltv.org/results\"} 0.2000"), "{}", prom);
+        assert!(prom.contains("scraper_endpoint_probes{url=\"https://www.hltv.org/matches\"} 1"));
+
+        // O hodinu později vypadne i zbytek
+        let later = tracker.snapshot_at(now + AVAILABILITY_WINDOW);
+        assert_eq!(later.endpoint(results).samples, 0);
+
+        let challenge = "<title>Just a moment...</title><div id=\"challenge-platform\"></div>";
+        assert_eq!(classify_probe_response(reqwest::StatusCode::OK, challenge), ScrapeOutcome::Challenge);
+        assert_eq!(classify_probe_response(reqwest::StatusCode::FORBIDDEN, ""), ScrapeOutcome::Forbidden);
+        assert_eq!(classify_probe_response(reqwest::StatusCode::OK, "<html>ok</html>"), ScrapeOutcome::Ok);
+    }
+}
@@ -0,0 +1,144 @@
+//! Startup probe headless Chrome — bez spustitelného Chromia jedou browser fallbacky HTTP-only
+//! místo toho, aby každý cyklus padaly na `Browser::new`.
+//!
+//! `ChromeLaunchConfig` drží proxy / extra argumenty / profil společné pro všechna spuštění
+//! (probe, GosuGamers, HLTV) — residential proxy obchází Cloudflare blokace datacenter IP.
+
+use headless_chrome::browser::default_executable;
+use headless_chrome::{Browser, LaunchOptions};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+static CHROME_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// Parametry spuštění headless Chrome nad rámec `headless(true).sandbox(false)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChromeLaunchConfig {
+    /// `--proxy-server`, např. `http://user@proxy:8080` nebo `socks5://127.0.0.1:1080`
+    pub proxy_server:  Option<String>,
+    /// Další argumenty Chromu (`--window-size=1920,1080`, `--lang=en-US` …)
+    pub extra_args:    Vec<String>,
+    /// Perzistentní profil (cookies z vyřešené challenge přežijí restart); None = dočasný profil
+    pub user_data_dir: Option<PathBuf>,
+}
+
+impl ChromeLaunchConfig {
+    /// CHROME_PROXY_SERVER, CHROME_EXTRA_ARGS (oddělené mezerou), CHROME_USER_DATA_DIR; prázdné = nic.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let non_empty = |key: &str| lookup(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            proxy_server:  non_empty("CHROME_PROXY_SERVER"),
+            extra_args:    non_empty("CHROME_EXTRA_ARGS")
+                .map(|v| v.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            user_data_dir: non_empty("CHROME_USER_DATA_DIR").map(PathBuf::from),
+        }
+    }
+
+    pub fn with_proxy_server(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy_server = Some(proxy.into());
+        self
+    }
+
+    pub fn with_extra_args(mut self, args: Vec<String>) -> Self {
+        self.extra_args = args;
+        self
+    }
+
+    pub fn with_user_data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.user_data_dir = Some(dir.into());
+        self
+    }
+
+    /// Launch options pro všechna spuštění Chromu; `path` = konkrétní binárka (None = autodetekce).
+    pub fn launch_options(&self, path: Option<PathBuf>) -> Result<LaunchOptions<'_>, String> {
+        LaunchOptions::default_builder()
+            .headless(true)
+            .sandbox(false)
+            .path(path)
+            .proxy_server(self.proxy_server.as_deref())
+            .args(self.extra_args.iter().map(OsStr::new).collect())
+            .user_data_dir(self.user_data_dir.clone())
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Najde Chrome (PATH / `CHROME`) a jednou ho zkusí spustit. Výsledek se cachuje na celý proces.
+/// Blokuje (spouští Chrome) — z async kódu volat přes `probe_chrome_available`.
+pub fn chrome_available() -> bool {
+    *CHROME_AVAILABLE.get_or_init(|| match probe_chrome(&ChromeLaunchConfig::from_env()) {
+        Ok(path) => {
+            info!("🌐 Headless Chrome OK ({})", path.display());
+            true
+        }
+        Err(reason) => {
+            warn!(
+                "⚠️ Headless Chrome nedostupný: {} — GosuGamers/HLTV poběží HTTP-only (Cloudflare je může blokovat). \
+                 Nainstaluj chromium nebo nastav CHROME=/cesta/k/chrome.",
+                reason
+            );
+            false
+        }
+    })
+}
+
+/// Jednorázový probe Chromu při startu binárky mimo async runtime; výsledek se předá
+/// do `EsportsMonitor::with_chrome_available` / `HltvScraper::with_browser_available`.
+pub async fn probe_chrome_available() -> bool {
+    tokio::task::spawn_blocking(chrome_available).await.unwrap_or(false)
+}
+
+fn probe_chrome(config: &ChromeLaunchConfig) -> Result<PathBuf, String> {
+    let path = default_executable()?;
+    let options = config.launch_options(Some(path.clone()))?;
+    Browser::new(options).map_err(|e| format!("launch {} failed: {:#}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod chrome_launch_tests {
+    use super::ChromeLaunchConfig;
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn proxy_args_and_profile_reach_launch_options() {
+        let config = ChromeLaunchConfig::default()
+            .with_proxy_server("socks5://127.0.0.1:1080")
+            .with_extra_args(vec!["--lang=en-US".to_string()])
+            .with_user_data_dir("/tmp/rustmisko-chrome");
+        let options = config.launch_options(Some(PathBuf::from("/usr/bin/chromium"))).unwrap();
+
+        assert_eq!(options.proxy_server, Some("socks5://127.0.0.1:1080"));
+        assert_eq!(options.args, vec![OsStr::new("--lang=en-US")]);
+        assert_eq!(options.user_data_dir.as_deref(), Some(Path::new("/tmp/rustmisko-chrome")));
+        assert!(options.headless && !options.sandbox);
+    }
+
+    #[test]
+    fn env_config_splits_args_and_ignores_blank_values() {
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("CHROME_PROXY_SERVER", " http://proxy.example:8080 "),
+            ("CHROME_EXTRA_ARGS", "--window-size=1920,1080  --lang=en-US"),
+            ("CHROME_USER_DATA_DIR", "  "),
+        ]);
+        let config = ChromeLaunchConfig::from_lookup(|k| env.get(k).map(|v| v.to_string()));
+
+        assert_eq!(config.proxy_server.as_deref(), Some("http://proxy.example:8080"));
+        assert_eq!(config.extra_args, vec!["--window-size=1920,1080", "--lang=en-US"]);
+        assert_eq!(config.user_data_dir, None);
+
+        let default_config = ChromeLaunchConfig::default();
+        let default_options = default_config.launch_options(None).unwrap();
+        assert_eq!(default_options.proxy_server, None);
+        assert!(default_options.args.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! Počítadla degradace scrapingu per zdroj — challenge stránky, 403 a aktivace browser fallbacku.
+//!
+//! Nad klouzavým oknem se hlídá podíl challenge odpovědí; nad thresholdem jednorázový warn
+//! (znovu až po poklesu pod threshold). Stejně se hlídá podíl stránek, kde parser nenašel
+//! žádný match element (pravděpodobně změna markupu).
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Okno pro challenge rate.
+pub const CHALLENGE_RATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Podíl challenge odpovědí v okně, nad kterým se warnuje.
+pub const CHALLENGE_RATE_WARN: f64 = 0.5;
+/// Podíl parser-suspect stránek v okně, nad kterým se warnuje.
+pub const PARSER_SUSPECT_RATE_WARN: f64 = 0.5;
+/// Pod tímto počtem odpovědí v okně je rate jen šum.
+const MIN_WINDOW_RESPONSES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrapeOutcome {
+    Ok,
+    Challenge,
+    Forbidden,
+    Error,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceCounters {
+    pub responses:         u64,
+    pub challenge_pages:   u64,
+    pub forbidden:         u64,
+    pub browser_fallbacks: u64,
+    /// Stránky, které prošly parserem
+    pub pages_parsed:      u64,
+    /// Z nich stránky bez jediného match elementu (selektory nesedí)
+    pub parser_suspect:    u64,
+}
+
+/// Snapshot počítadel (source → counters).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScraperHealth {
+    pub sources: BTreeMap<String, SourceCounters>,
+}
+
+impl ScraperHealth {
+    pub fn source(&self, source: &str) -> SourceCounters {
+        self.sources.get(source).cloned().unwrap_or_default()
+    }
+
+    /// Prometheus text format (counter per source).
+    pub fn render_prometheus(&self) -> String {
+        let names = [
+            "scraper_responses_total",
+            "scraper_challenge_pages_total",
+            "scraper_forbidden_total",
+            "scraper_browser_fallbacks_total",
+            "scraper_pages_parsed_total",
+            "scraper_parser_suspect_total",
+        ];
+        let mut out = String::new();
+        for (i, name) in names.iter().enumerate() {
+            out.push_str(&format!("# TYPE {} counter\n", name));
+            for (source, c) in &self.sources {
+                let value = [c.responses, c.challenge_pages, c.forbidden, c.browser_fallbacks, c.pages_parsed, c.parser_suspect][i];
+                out.push_str(&format!("{}{{source=\"{}\"}} {}\n", name, source, value));
+            }
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+struct SourceState {
+    counters: SourceCounters,
+    /// (kdy, byl challenge) za posledních CHALLENGE_RATE_WINDOW
+    window:   VecDeque<(Instant, bool)>,
+    warned:   bool,
+    /// (kdy, byl parser suspect) za posledních CHALLENGE_RATE_WINDOW
+    parse_window: VecDeque<(Instant, bool)>,
+    parse_warned: bool,
+}
+
+#[derive(Default)]
+pub struct ScraperHealthTracker {
+    sources: Mutex<HashMap<String, SourceState>>,
+}
+
+impl ScraperHealthTracker {
+    pub fn record_response(&self, source: &str, outcome: ScrapeOutcome) {
+        self.record_response_at(source, outcome, Instant::now());
+    }
+
+    pub fn record_browser_fallback(&self, source: &str) {
+        let mut sources = self.sources.lock().unwrap();
+        sources.entry(source.to_string()).or_default().counters.browser_fallbacks += 1;
+    }
+
+    /// Výsledek parseru nad načtenou stránkou; `suspect` = žádný match element.
+    pub fn record_parse(&self, source: &str, suspect: bool) {
+        self.record_parse_at(source, suspect, Instant::now());
+    }
+
+    pub fn snapshot(&self) -> ScraperHealth {
+        let sources = self.sources.lock().unwrap();
+        ScraperHealth {
+            sources: sources.iter().map(|(k, v)| (k.clone(), v.counters.clone())).collect(),
+        }
+    }
+
+    fn record_response_at(&self, source: &str, outcome: ScrapeOutcome, now: Instant) {
+        let mut sources = self.sources.lock().unwrap();
+        let state = sources.entry(source.to_string()).or_default();
+        state.counters.responses += 1;
+        match outcome {
+            ScrapeOutcome::Challenge => state.counters.challenge_pages += 1,
+            ScrapeOutcome::Forbidden => state.counters.forbidden += 1,
+            ScrapeOutcome::Ok | ScrapeOutcome::Error => {}
+        }
+
+        state.window.push_back((now, outcome == ScrapeOutcome::Challenge));
+        while state.window.front().is_some_and(|(at, _)| now.duration_since(*at) > CHALLENGE_RATE_WINDOW) {
+            state.window.pop_front();
+        }
+        if state.window.len() < MIN_WINDOW_RESPONSES {
+            return;
+        }
+        let challenged = state.window.iter().filter(|(_, c)| *c).count();
+        let rate = challenged as f64 / state.window.len() as f64;
+        if rate > CHALLENGE_RATE_WARN && !state.warned {
+            warn!("🛡️ {} challenge rate {:.0}% ({}/{} za {}min) — scraping degraduje",
+                source, rate * 100.0, challenged, state.window.len(), CHALLENGE_RATE_WINDOW.as_secs() / 60);
+            state.warned = true;
+        } else if rate <= CHALLENGE_RATE_WARN && state.warned {
+            info!("🛡️ {} challenge rate zpět na {:.0}%", source, rate * 100.0);
+            state.warned = false;
+        }
+    }
+
+    fn record_parse_at(&self, source: &str, suspect: bool, now: Instant) {
+        let mut sources = self.sources.lock().unwrap();
+        let state = sources.entry(source.to_string()).or_default();
+        state.counters.pages_parsed += 1;
+        if suspect {
+            state.counters.parser_suspect += 1;
+        }
+
+        state.parse_window.push_back((now, suspect));
+        while state.parse_window.front().is_some_and(|(at, _)| now.duration_since(*at) > CHALLENGE_RATE_WINDOW) {
+            state.parse_window.pop_front();
+        }
+        if state.parse_window.len() < MIN_WINDOW_RESPONSES {
+            return;
+        }
+        let suspects = state.parse_window.iter().filter(|(_, s)| *s).count();
+        let rate = suspects as f64 / state.parse_window.len() as f64;
+        if rate > PARSER_SUSPECT_RATE_WARN && !state.parse_warned {
+            warn!("🧩 {} parser suspect rate {:.0}% ({}/{} stránek bez match elementů) — změna markupu?",
+                source, rate * 100.0, suspects, state.parse_window.len());
+            state.parse_warned = true;
+        } else if rate <= PARSER_SUSPECT_RATE_WARN && state.parse_warned {
+            info!("🧩 {} parser suspect rate zpět na {:.0}%", source, rate * 100.0);
+            state.parse_warned = false;
+        }
+    }
+
+    /// True, pokud je parser suspect rate zdroje nad thresholdem (warn aktivní).
+    pub fn parser_suspect_alert(&self, source: &str) -> bool {
+        self.sources.lock().unwrap().get(source).is_some_and(|s| s.parse_warned)
+    }
+}
+
+/// Striktní detekce Cloudflare challenge (ne jen zmínka "cloudflare" v assetech).
+pub fn is_challenge_page(html: &str) -> bool {
+    let lower = html.to_lowercase();
+    lower.contains("just a moment") && (lower.contains("cf-challenge") || lower.contains("challenge-platform"))
+}
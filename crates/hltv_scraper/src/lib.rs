@@ -9,8 +9,8 @@
 //! <div class="team2-gradient"> <div class="score">8</div> </div>
 
 use anyhow::{Context, Result};
-use headless_chrome::{Browser, LaunchOptions};
-use reqwest::StatusCode;
+use headless_chrome::Browser;
+use prediction_engine::{match_state_with_series, Prediction, PredictionEngine};
 use scraper::{Html, Selector};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
@@ -19,6 +19,20 @@ use tokio::task;
 use tokio::time::sleep;
 use tracing::{debug, info, warn};
 
+mod availability;
+mod browser;
+mod health;
+pub use availability::{
+    classify_probe_response, EndpointAvailability, EndpointAvailabilityTracker, EndpointProber, EndpointStats,
+    AVAILABILITY_WINDOW, DEFAULT_PROBE_ENDPOINTS,
+};
+pub use browser::{chrome_available, probe_chrome_available, ChromeLaunchConfig};
+pub use esports_core::ScrapeError;
+pub use health::{is_challenge_page, ScrapeOutcome, ScraperHealth, ScraperHealthTracker, SourceCounters};
+
+/// Klíč HLTV v ScraperHealth.
+const HEALTH_SOURCE: &str = "hltv";
+
 /// Live match stav z HLTV
 #[derive(Debug, Clone)]
 pub struct HltvLiveMatch {
@@ -30,6 +44,8 @@ pub struct HltvLiveMatch {
     pub is_live: bool,
     pub map_name: Option<String>,
     pub series_format: String, // "bo1", "bo3", "bo5"
+    /// Právě hraný round z live indikátoru scorebotu ("Round 17"); None = stránka ho neukazuje
+    pub current_round: Option<u8>,
     pub last_update: Instant,
     pub url: String,
 }
@@ -42,35 +58,15 @@ pub struct HltvEndpointProbe {
     pub looks_like_challenge_page: bool,
 }
 
-/// Stav predikce pro sniper mode
-#[derive(Debug, PartialEq)]
-pub enum MatchPrediction {
-    Team1Win(f32), // confidence 0.0-1.0
-    Team2Win(f32),
-    Uncertain,
-}
+/// Stav predikce pro sniper mode — stejná škála confidence jako `prediction_engine`.
+pub type MatchPrediction = Prediction;
 
 impl HltvLiveMatch {
-    /// Predikuje výsledek na základě aktuálního skóre
+    /// Predikuje výsledek na základě aktuálního skóre (CS2 logika z `PredictionEngine::predict_cs2`)
     pub fn predict(&self) -> MatchPrediction {
-        // CS2: vyhrává se na 13 vítězných roundů
-        if self.score1 >= 13 && self.score1 - self.score2 >= 2 {
-            MatchPrediction::Team1Win(1.0)
-        } else if self.score2 >= 13 && self.score2 - self.score1 >= 2 {
-            MatchPrediction::Team2Win(1.0)
-        } else if self.score1 == 12 && self.score2 <= 10 {
-            // 12:10 → velmi vysoká šance
-            MatchPrediction::Team1Win(0.95)
-        } else if self.score2 == 12 && self.score1 <= 10 {
-            MatchPrediction::Team2Win(0.95)
-        } else if self.score1 >= 11 && self.score1 - self.score2 >= 5 {
-            // Např. 11:6 → ~85% šance
-            MatchPrediction::Team1Win(0.85)
-        } else if self.score2 >= 11 && self.score2 - self.score1 >= 5 {
-            MatchPrediction::Team2Win(0.85)
-        } else {
-            MatchPrediction::Uncertain
-        }
+        let total_maps = self.series_format.trim_start_matches("bo").parse::<u8>().ok();
+        let state = match_state_with_series("cs2", &self.team1, &self.team2, self.score1, self.score2, 1, total_maps, self.is_live);
+        PredictionEngine::new().predict_cs2(&state)
     }
     
     /// Vrací true pokud je zápas prakticky ukončen
@@ -78,6 +74,12 @@ impl HltvLiveMatch {
         matches!(self.predict(), MatchPrediction::Team1Win(_) | MatchPrediction::Team2Win(_))
     }
     
+    /// Aktuální round mapy pro odhad zbývajícího času (too-late filtr, sizing);
+    /// bez live indikátoru aproximace `score1 + score2`.
+    pub fn round_number(&self) -> u8 {
+        self.current_round.unwrap_or_else(|| self.score1.saturating_add(self.score2))
+    }
+
     /// Vítěz podle predikce
     pub fn predicted_winner(&self) -> Option<(&str, f32)> {
         match self.predict() {
@@ -100,8 +102,16 @@ pub struct HltvScraper {
     min_request_interval: Duration,
     last_browser_fetch: Instant,
     min_browser_interval: Duration,
+    /// false = Chrome nejde spustit → HTTP-only (403/challenge se vrací bez browser fallbacku)
+    browser_available: bool,
+    /// Proxy / extra argumenty / profil pro browser fallback
+    chrome_launch: ChromeLaunchConfig,
+    health: ScraperHealthTracker,
 }
 
+/// Výchozí timeout HTTP requestů na HLTV.
+pub const HTTP_TIMEOUT_DEFAULT: Duration = Duration::from_secs(10);
+
 impl HltvScraper {
     pub fn new() -> Self {
         let user_agents = vec![
@@ -110,19 +120,8 @@ impl HltvScraper {
             "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/118.0.0.0 Safari/537.36".to_string(),
         ];
         
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8".parse().unwrap());
-        headers.insert("Accept-Language", "en-US,en;q=0.5".parse().unwrap());
-        headers.insert("Connection", "keep-alive".parse().unwrap());
-        headers.insert("Upgrade-Insecure-Requests", "1".parse().unwrap());
-        
         Self {
-            client: reqwest::Client::builder()
-                .default_headers(headers)
-                .timeout(Duration::from_secs(10))
-                .gzip(true)
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
+            client: Self::build_client(HTTP_TIMEOUT_DEFAULT),
             live_cache: Arc::new(Mutex::new(HashMap::new())),
             user_agents,
             current_ua_index: 0,
@@ -130,9 +129,49 @@ impl HltvScraper {
             min_request_interval: Duration::from_secs(3), // Respektuj robots.txt
             last_browser_fetch: Instant::now() - Duration::from_secs(300),
             min_browser_interval: Duration::from_secs(6),
+            browser_available: false,
+            chrome_launch: ChromeLaunchConfig::from_env(),
+            health: ScraperHealthTracker::default(),
         }
     }
 
+    fn build_client(timeout: Duration) -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8".parse().unwrap());
+        headers.insert("Accept-Language", "en-US,en;q=0.5".parse().unwrap());
+        headers.insert("Connection", "keep-alive".parse().unwrap());
+        headers.insert("Upgrade-Insecure-Requests", "1".parse().unwrap());
+
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(timeout)
+            .gzip(true)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new())
+    }
+
+    /// Timeout HTTP requestů na HLTV (default HTTP_TIMEOUT_DEFAULT).
+    pub fn with_http_timeout(mut self, timeout: Duration) -> Self {
+        self.set_http_timeout(timeout);
+        self
+    }
+
+    pub fn set_http_timeout(&mut self, timeout: Duration) {
+        self.client = Self::build_client(timeout);
+    }
+
+    /// Chrome fallback pro Cloudflare challenge (výsledek `probe_chrome_available` při startu; default vypnuto).
+    pub fn with_browser_available(mut self, available: bool) -> Self {
+        self.browser_available = available;
+        self
+    }
+
+    /// Přepíše parametry spuštění Chromu (default z env, viz `ChromeLaunchConfig::from_env`).
+    pub fn with_chrome_launch(mut self, config: ChromeLaunchConfig) -> Self {
+        self.chrome_launch = config;
+        self
+    }
+
     fn parse_match_ids_from_html(html: &str) -> Vec<u64> {
         let mut ids = HashSet::new();
         let mut search_from = 0usize;
@@ -163,41 +202,51 @@ impl HltvScraper {
         out
     }
 
-    async fn fetch_html_http(&mut self, url: &str) -> Result<String> {
+    /// Challenge / 403 / browser fallback počítadla (per source).
+    pub fn scraper_health(&self) -> ScraperHealth {
+        self.health.snapshot()
+    }
+
+    async fn fetch_html_http(&mut self, url: &str) -> std::result::Result<String, ScrapeError> {
         self.wait_for_rate_limit().await;
 
         let resp = self.client.get(url)
             .header("User-Agent", self.current_user_agent())
             .send()
-            .await
-            .context(format!("HLTV request failed for {}", url))?;
+            .await?;
 
         let status = resp.status();
         if !status.is_success() {
-            return Err(anyhow::anyhow!("HLTV HTTP {}", status));
+            let outcome = if status == reqwest::StatusCode::FORBIDDEN { ScrapeOutcome::Forbidden } else { ScrapeOutcome::Error };
+            self.health.record_response(HEALTH_SOURCE, outcome);
+            return Err(ScrapeError::from_status(status));
         }
 
-        Ok(resp.text().await?)
+        let html = resp.text().await?;
+        if is_challenge_page(&html) {
+            self.health.record_response(HEALTH_SOURCE, ScrapeOutcome::Challenge);
+            return Err(ScrapeError::Challenge);
+        }
+        self.health.record_response(HEALTH_SOURCE, ScrapeOutcome::Ok);
+        Ok(html)
     }
 
-    async fn fetch_html_browser(&mut self, url: &str) -> Result<String> {
+    async fn fetch_html_browser(&mut self, url: &str) -> std::result::Result<String, ScrapeError> {
         let elapsed = self.last_browser_fetch.elapsed();
         if elapsed < self.min_browser_interval {
-            return Err(anyhow::anyhow!(
-                "Browser fallback cooldown active ({}s remaining)",
+            return Err(ScrapeError::Browser(format!(
+                "cooldown active ({}s remaining)",
                 (self.min_browser_interval - elapsed).as_secs()
-            ));
+            )));
         }
 
         self.last_browser_fetch = Instant::now();
         let url = url.to_string();
+        let launch = self.chrome_launch.clone();
 
         let html = task::spawn_blocking(move || -> Result<String> {
-            let options = LaunchOptions::default_builder()
-                .headless(true)
-                .sandbox(false)
-                .build()
-                .context("Failed to build Chrome launch options")?;
+            let options = launch.launch_options(None)
+                .map_err(|e| anyhow::anyhow!("Failed to build Chrome launch options: {}", e))?;
 
             let browser = Browser::new(options).context("Failed to launch Chrome")?;
             let tab = browser.new_tab().context("Failed to create browser tab")?;
@@ -207,28 +256,31 @@ impl HltvScraper {
             std::thread::sleep(Duration::from_secs(2));
 
             tab.get_content().context("Failed to read HTML from browser tab")
-        }).await??;
+        }).await
+            .map_err(|e| ScrapeError::Browser(e.to_string()))?
+            .map_err(|e| ScrapeError::Browser(format!("{:#}", e)))?;
 
         Ok(html)
     }
 
-    async fn fetch_html_with_fallback(&mut self, url: &str) -> Result<String> {
+    async fn fetch_html_with_fallback(&mut self, url: &str) -> std::result::Result<String, ScrapeError> {
         match self.fetch_html_http(url).await {
             Ok(html) => {
                 self.rotate_user_agent();
                 Ok(html)
             }
-            Err(err) => {
-                let is_403 = err.to_string().contains(&StatusCode::FORBIDDEN.to_string());
-                if !is_403 {
-                    return Err(err);
-                }
-
-                warn!("HLTV HTTP 403 on {}, trying browser fallback", url);
+            Err(err) if err.needs_browser_fallback() && !self.browser_available => {
+                debug!("HLTV {} on {}, HTTP-only mode (Chrome unavailable)", err, url);
+                Err(err)
+            }
+            Err(err) if err.needs_browser_fallback() => {
+                warn!("HLTV {} on {}, trying browser fallback", err, url);
+                self.health.record_browser_fallback(HEALTH_SOURCE);
                 let html = self.fetch_html_browser(url).await?;
                 self.rotate_user_agent();
                 Ok(html)
             }
+            Err(err) => Err(err),
         }
     }
 
@@ -297,17 +349,40 @@ impl HltvScraper {
 
         let html = match self.fetch_html_with_fallback(&url).await {
             Ok(html) => html,
+            Err(ScrapeError::NotFound) => {
+                debug!("HLTV match {} not found", match_id);
+                return Ok(None);
+            }
             Err(err) => {
-                if err.to_string().contains("404") {
-                    debug!("HLTV match {} not found", match_id);
-                    return Ok(None);
-                }
                 warn!("HLTV match {} fetch failed: {}", match_id, err);
                 return Ok(None);
             }
         };
 
-        let document = Html::parse_document(&html);
+        let match_data = Self::parse_match_details(match_id, url, &html);
+        self.rotate_user_agent();
+        Ok(Some(match_data))
+    }
+
+    /// Live indikátor roundu ze scorebotu ("Round 17", "12 - 11 Round 24"); číslo za slovem "round"
+    /// má přednost před skóre v témže elementu. Mimo 1..=99 = nevalidní.
+    fn parse_current_round(document: &Html) -> Option<u8> {
+        let selector = Selector::parse(".currentRoundText, .round-number, .scoreboard .round").ok()?;
+        document.select(&selector).find_map(|e| {
+            let text = e.text().collect::<String>().to_lowercase();
+            let tail = text.rsplit_once("round").map_or(text.as_str(), |(_, tail)| tail);
+            let digits: String = tail
+                .chars()
+                .skip_while(|c| !c.is_ascii_digit())
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits.parse::<u8>().ok().filter(|r| (1..=99).contains(r))
+        })
+    }
+
+    /// Rozparsuje detail zápasu (týmy, skóre, live stav, aktuální round).
+    fn parse_match_details(match_id: u64, url: String, html: &str) -> HltvLiveMatch {
+        let document = Html::parse_document(html);
         
         // Extrahuj jména týmů z titulku nebo speciálních elementů
         let team1_selector = Selector::parse(".team1-gradient .teamName").unwrap_or_else(|_| {
@@ -349,7 +424,7 @@ impl HltvScraper {
         let is_live = document.select(&live_selector)
             .any(|e| e.text().collect::<String>().to_lowercase().contains("live"));
         
-        let match_data = HltvLiveMatch {
+        HltvLiveMatch {
             match_id,
             team1,
             team2,
@@ -358,12 +433,10 @@ impl HltvScraper {
             is_live,
             map_name: None, // TODO: extrahovat z .map-name elementu
             series_format: "bo1".to_string(), // TODO: detekovat z kontextu
+            current_round: Self::parse_current_round(&document),
             last_update: Instant::now(),
             url,
-        };
-        
-        self.rotate_user_agent();
-        Ok(Some(match_data))
+        }
     }
     
     /// Main loop pro sledování live zápasů
@@ -432,3 +505,164 @@ impl HltvScraper {
         }
     }
 }
+
+#[cfg(test)]
+mod prediction_parity_tests {
+    use super::{HltvLiveMatch, MatchPrediction};
+    use prediction_engine::{match_state_from_hltv, PredictionEngine};
+    use std::time::Instant;
+
+    fn hltv(score1: u8, score2: u8) -> HltvLiveMatch {
+        HltvLiveMatch {
+            match_id: 2370001,
+            team1: "Vitality".to_string(),
+            team2: "G2".to_string(),
+            score1,
+            score2,
+            is_live: true,
+            map_name: None,
+            series_format: "bo3".to_string(),
+            current_round: None,
+            last_update: Instant::now(),
+            url: "https://www.hltv.org/matches/2370001".to_string(),
+        }
+    }
+
+    #[test]
+    fn hltv_and_engine_agree_on_every_cs2_score() {
+        let engine = PredictionEngine::new();
+        for score1 in 0..=19 {
+            for score2 in 0..=19 {
+                let state = match_state_from_hltv("cs2", "Vitality", "G2", score1, score2, 1, 3, true);
+                assert_eq!(hltv(score1, score2).predict(), engine.predict(&state), "{}:{}", score1, score2);
+            }
+        }
+    }
+
+    #[test]
+    fn shared_scale_keeps_known_anchors() {
+        assert_eq!(hltv(12, 10).predict(), MatchPrediction::Team1Win(0.95));
+        assert_eq!(hltv(4, 11).predict(), MatchPrediction::Team2Win(0.85));
+        assert_eq!(hltv(6, 13).predict(), MatchPrediction::Team2Win(1.0));
+        assert_eq!(hltv(13, 12).predict(), MatchPrediction::Uncertain);
+        assert_eq!(hltv(6, 13).predicted_winner(), Some(("G2", 1.0)));
+    }
+}
+
+#[cfg(test)]
+mod live_round_tests {
+    use super::HltvScraper;
+
+    const ROUND_24_HTML: &str = include_str!("../tests/fixtures/hltv_match_live_round_24.html");
+    const NO_ROUND_HTML: &str = include_str!("../tests/fixtures/hltv_match_live_no_round.html");
+
+    #[test]
+    fn live_round_indicator_is_parsed_from_scorebot() {
+        let m = HltvScraper::parse_match_details(2370001, "https://www.hltv.org/matches/2370001".to_string(), ROUND_24_HTML);
+
+        assert_eq!((m.team1.as_str(), m.team2.as_str()), ("Vitality", "G2"));
+        assert_eq!((m.score1, m.score2), (12, 11));
+        assert!(m.is_live);
+        assert_eq!(m.current_round, Some(24));
+        assert_eq!(m.round_number(), 24);
+    }
+
+    #[test]
+    fn missing_round_indicator_falls_back_to_score_sum() {
+        let m = HltvScraper::parse_match_details(2370002, "https://www.hltv.org/matches/2370002".to_string(), NO_ROUND_HTML);
+
+        assert_eq!((m.score1, m.score2), (9, 7));
+        assert_eq!(m.current_round, None);
+        assert_eq!(m.round_number(), 16);
+    }
+}
+
+#[cfg(test)]
+mod scrape_error_tests {
+    use super::{HltvScraper, ScrapeError};
+    use reqwest::StatusCode;
+    use std::time::{Duration, Instant};
+
+    /// Lokální server, který na každý request odpoví daným statusem a tělem.
+    async fn serve(status: &'static str, body: &'static str) -> String {
+        let url = test_http::serve(move |_| test_http::response(status, &["Content-Type: text/html"], body)).await;
+        format!("{}/matches", url)
+    }
+
+    /// Scraper s aktivním browser cooldownem → fallback se pozná bez spouštění Chrome.
+    fn scraper_with_browser_cooldown() -> HltvScraper {
+        let mut scraper = HltvScraper::new();
+        scraper.last_browser_fetch = Instant::now();
+        scraper.min_browser_interval = Duration::from_secs(600);
+        scraper.min_request_interval = Duration::ZERO;
+        scraper.browser_available = true;
+        scraper
+    }
+
+    #[tokio::test]
+    async fn forbidden_maps_to_http_403_and_triggers_browser_fallback() {
+        let url = serve("403 Forbidden", "blocked").await;
+        let mut scraper = scraper_with_browser_cooldown();
+
+        let err = scraper.fetch_html_http(&url).await.unwrap_err();
+        assert_eq!(err, ScrapeError::Http(StatusCode::FORBIDDEN));
+        assert!(err.needs_browser_fallback());
+
+        // Fallback proběhl: chyba je z browser větve, ne původní 403
+        let err = scraper.fetch_html_with_fallback(&url).await.unwrap_err();
+        assert!(matches!(err, ScrapeError::Browser(ref msg) if msg.contains("cooldown")), "got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn unavailable_browser_falls_back_to_http_only() {
+        let url = serve("403 Forbidden", "blocked").await;
+        let mut scraper = scraper_with_browser_cooldown();
+        scraper.browser_available = false;
+
+        // Bez Chrome se browser větev vůbec nezkouší → původní HTTP chyba
+        let err = scraper.fetch_html_with_fallback(&url).await.unwrap_err();
+        assert_eq!(err, ScrapeError::Http(StatusCode::FORBIDDEN));
+
+        let ok_url = serve("200 OK", "<a href=\"/matches/2370001/a-vs-b\">A vs B</a>").await;
+        let html = scraper.fetch_html_with_fallback(&ok_url).await.unwrap();
+        assert_eq!(HltvScraper::parse_match_ids_from_html(&html), vec![2370001]);
+    }
+
+    #[tokio::test]
+    async fn not_found_skips_browser_fallback() {
+        let url = serve("404 Not Found", "nope").await;
+        let mut scraper = scraper_with_browser_cooldown();
+
+        let err = scraper.fetch_html_with_fallback(&url).await.unwrap_err();
+        assert_eq!(err, ScrapeError::NotFound);
+        assert!(!err.needs_browser_fallback());
+    }
+
+    #[tokio::test]
+    async fn challenge_page_is_detected_on_success_status() {
+        let url = serve("200 OK", "<title>Just a moment...</title><div id=\"cf-challenge-running\"></div>").await;
+        let mut scraper = scraper_with_browser_cooldown();
+
+        assert_eq!(scraper.fetch_html_http(&url).await.unwrap_err(), ScrapeError::Challenge);
+    }
+
+    #[tokio::test]
+    async fn challenge_forbidden_and_fallback_are_counted() {
+        let challenge = serve("200 OK", "<title>Just a moment...</title><script src=\"/cdn-cgi/challenge-platform/h/b\"></script>").await;
+        let forbidden = serve("403 Forbidden", "blocked").await;
+        let ok = serve("200 OK", "<a href=\"/matches/2370001/a-vs-b\">A vs B</a>").await;
+        let mut scraper = scraper_with_browser_cooldown();
+
+        let _ = scraper.fetch_html_http(&challenge).await;
+        let _ = scraper.fetch_html_with_fallback(&forbidden).await;
+        scraper.fetch_html_http(&ok).await.unwrap();
+
+        let hltv = scraper.scraper_health().source("hltv");
+        assert_eq!(hltv.responses, 3);
+        assert_eq!(hltv.challenge_pages, 1);
+        assert_eq!(hltv.forbidden, 1);
+        assert_eq!(hltv.browser_fallbacks, 1);
+        assert!(scraper.scraper_health().render_prometheus()
+            .contains("scraper_challenge_pages_total{source=\"hltv\"} 1"));
+    }
+}
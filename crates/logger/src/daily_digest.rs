@@ -0,0 +1,230 @@
+//! Denní digest — při přechodu dne (půlnoc UTC) se z ledgeru složí DAILY_DIGEST event.
+//!
+//! Ledger (`data/ledger.jsonl`) je zdroj pravdy: PLACED = vsazeno, WON / LOST / CANCELED = vyřízeno.
+
+use crate::now_iso;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DigestBet {
+    pub bet_id:    String,
+    pub match_key: String,
+    pub pnl_usd:   f64,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct SportDigest {
+    pub placed:      usize,
+    pub won:         usize,
+    pub lost:        usize,
+    pub wagered_usd: f64,
+    pub net_pnl_usd: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DailyDigestEvent {
+    pub ts:                    String,
+    pub event:                 &'static str,   // "DAILY_DIGEST"
+    pub date:                  String,         // den, který digest shrnuje (YYYY-MM-DD)
+    pub bets_placed:           usize,
+    pub settled_won:           usize,
+    pub settled_lost:          usize,
+    pub settled_canceled:      usize,
+    pub wagered_usd:           f64,
+    pub net_pnl_usd:           f64,            // jen vyřízené sázky
+    pub best_bet:              Option<DigestBet>,
+    pub worst_bet:             Option<DigestBet>,
+    pub per_sport:             BTreeMap<String, SportDigest>,
+    pub safe_mode_activations: usize,
+}
+
+fn f64_field(entry: &Value, key: &str) -> Option<f64> {
+    entry.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()))
+}
+
+fn str_field<'a>(entry: &'a Value, key: &str) -> &'a str {
+    entry.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn sport_of(match_key: &str) -> String {
+    match match_key.split("::").next() {
+        Some(sport) if !sport.is_empty() && match_key.contains("::") => sport.to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+impl DailyDigestEvent {
+    /// Složí digest z ledger záznamů daného dne (záznamy jiných dnů se ignorují).
+    pub fn from_ledger(date: &str, entries: &[Value], safe_mode_activations: usize) -> Self {
+        Self::from_ledger_with(date, entries, safe_mode_activations, |ts| ts.starts_with(date))
+    }
+
+    /// Jako `from_ledger`, ale příslušnost záznamu ke dni určuje `in_day(ts)` — den nemusí končit o půlnoci UTC.
+    pub fn from_ledger_with(date: &str, entries: &[Value], safe_mode_activations: usize, in_day: impl Fn(&str) -> bool) -> Self {
+        let mut digest = Self {
+            ts:                    now_iso(),
+            event:                 "DAILY_DIGEST",
+            date:                  date.to_string(),
+            bets_placed:           0,
+            settled_won:           0,
+            settled_lost:          0,
+            settled_canceled:      0,
+            wagered_usd:           0.0,
+            net_pnl_usd:           0.0,
+            best_bet:              None,
+            worst_bet:             None,
+            per_sport:             BTreeMap::new(),
+            safe_mode_activations,
+        };
+
+        for entry in entries.iter().filter(|e| in_day(str_field(e, "ts"))) {
+            let match_key = str_field(entry, "match_key");
+            let stake = f64_field(entry, "amount_usd").unwrap_or(0.0);
+            let pnl = match str_field(entry, "event") {
+                "PLACED" => {
+                    digest.bets_placed += 1;
+                    digest.wagered_usd += stake;
+                    let sport = digest.per_sport.entry(sport_of(match_key)).or_default();
+                    sport.placed += 1;
+                    sport.wagered_usd += stake;
+                    continue;
+                }
+                "WON" => {
+                    digest.settled_won += 1;
+                    digest.per_sport.entry(sport_of(match_key)).or_default().won += 1;
+                    f64_field(entry, "payout_usd").unwrap_or(0.0) - stake
+                }
+                "LOST" => {
+                    digest.settled_lost += 1;
+                    digest.per_sport.entry(sport_of(match_key)).or_default().lost += 1;
+                    -stake
+                }
+                "CANCELED" => {
+                    digest.settled_canceled += 1;
+                    f64_field(entry, "payout_usd").unwrap_or(stake) - stake
+                }
+                _ => continue,
+            };
+
+            digest.net_pnl_usd += pnl;
+            digest.per_sport.entry(sport_of(match_key)).or_default().net_pnl_usd += pnl;
+            let bet = DigestBet {
+                bet_id:    str_field(entry, "bet_id").to_string(),
+                match_key: match_key.to_string(),
+                pnl_usd:   pnl,
+            };
+            if digest.best_bet.as_ref().is_none_or(|b| pnl > b.pnl_usd) {
+                digest.best_bet = Some(bet.clone());
+            }
+            if digest.worst_bet.as_ref().is_none_or(|b| pnl < b.pnl_usd) {
+                digest.worst_bet = Some(bet);
+            }
+        }
+        digest
+    }
+
+    /// Načte ledger JSONL a složí digest pro `date`; chybějící soubor = prázdný den.
+    pub fn from_ledger_file(path: impl AsRef<Path>, date: &str, safe_mode_activations: usize) -> Self {
+        let entries: Vec<Value> = std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| line.contains(date))
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Self::from_ledger(date, &entries, safe_mode_activations)
+    }
+
+    /// Ledger JSONL pro den s vlastní hranicí (`in_day(ts)`); bez předfiltru na datum v řádku.
+    pub fn from_ledger_file_with(path: impl AsRef<Path>, date: &str, safe_mode_activations: usize, in_day: impl Fn(&str) -> bool) -> Self {
+        let entries: Vec<Value> = std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Self::from_ledger_with(date, &entries, safe_mode_activations, in_day)
+    }
+
+    /// Čitelné shrnutí pro Telegram / NTFY.
+    pub fn summary_text(&self) -> String {
+        let mut text = format!(
+            "📊 Denní digest {}\nSázky: {} (${:.2})\nVyřízeno: {}W / {}L / {}C\nNet PnL: {:+.2} USD\nSAFE MODE: {}x",
+            self.date, self.bets_placed, self.wagered_usd,
+            self.settled_won, self.settled_lost, self.settled_canceled,
+            self.net_pnl_usd, self.safe_mode_activations,
+        );
+        if let Some(best) = &self.best_bet {
+            text.push_str(&format!("\nNejlepší: {} {:+.2}", best.match_key, best.pnl_usd));
+        }
+        if let Some(worst) = &self.worst_bet {
+            text.push_str(&format!("\nNejhorší: {} {:+.2}", worst.match_key, worst.pnl_usd));
+        }
+        for (sport, s) in &self.per_sport {
+            text.push_str(&format!(
+                "\n• {}: {} sázek, {}W/{}L, {:+.2} USD",
+                sport, s.placed, s.won, s.lost, s.net_pnl_usd
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod daily_digest_tests {
+    use super::DailyDigestEvent;
+    use serde_json::json;
+
+    #[test]
+    fn digest_summarizes_one_day_of_ledger_entries() {
+        let entries = vec![
+            json!({"ts": "2026-03-01T09:00:00Z", "event": "PLACED", "bet_id": "b1", "match_key": "cs2::a_vs_b", "amount_usd": 3.0}),
+            json!({"ts": "2026-03-01T09:05:00Z", "event": "PLACED", "bet_id": "b2", "match_key": "tennis::c_vs_d", "amount_usd": 2.0}),
+            json!({"ts": "2026-03-01T10:00:00Z", "event": "PLACED", "bet_id": "b3", "match_key": "cs2::e_vs_f", "amount_usd": 4.0}),
+            json!({"ts": "2026-03-01T11:00:00Z", "event": "WON", "bet_id": "b1", "match_key": "cs2::a_vs_b", "amount_usd": 3.0, "payout_usd": 6.6}),
+            json!({"ts": "2026-03-01T12:00:00Z", "event": "LOST", "bet_id": "b2", "match_key": "tennis::c_vs_d", "amount_usd": 2.0}),
+            json!({"ts": "2026-03-01T13:00:00Z", "event": "CANCELED", "bet_id": "b3", "match_key": "cs2::e_vs_f", "amount_usd": 4.0, "payout_usd": 4.0}),
+            json!({"ts": "2026-03-01T13:30:00Z", "event": "BET_FAILED", "match_key": "cs2::g_vs_h", "amount_usd": 5.0}),
+            // Jiný den se nezapočítá
+            json!({"ts": "2026-02-28T23:59:00Z", "event": "PLACED", "bet_id": "b0", "match_key": "cs2::x_vs_y", "amount_usd": 9.0}),
+        ];
+
+        let digest = DailyDigestEvent::from_ledger("2026-03-01", &entries, 2);
+
+        assert_eq!(digest.event, "DAILY_DIGEST");
+        assert_eq!(digest.bets_placed, 3);
+        assert!((digest.wagered_usd - 9.0).abs() < 1e-9);
+        assert_eq!((digest.settled_won, digest.settled_lost, digest.settled_canceled), (1, 1, 1));
+        assert!((digest.net_pnl_usd - 1.6).abs() < 1e-9);
+        assert_eq!(digest.best_bet.as_ref().map(|b| b.bet_id.as_str()), Some("b1"));
+        assert_eq!(digest.worst_bet.as_ref().map(|b| b.bet_id.as_str()), Some("b2"));
+        assert_eq!(digest.safe_mode_activations, 2);
+
+        let cs2 = &digest.per_sport["cs2"];
+        assert_eq!((cs2.placed, cs2.won, cs2.lost), (2, 1, 0));
+        assert!((cs2.net_pnl_usd - 3.6).abs() < 1e-9);
+        let tennis = &digest.per_sport["tennis"];
+        assert_eq!((tennis.placed, tennis.won, tennis.lost), (1, 0, 1));
+        assert!((tennis.net_pnl_usd + 2.0).abs() < 1e-9);
+
+        let text = digest.summary_text();
+        assert!(text.contains("Net PnL: +1.60 USD"));
+        assert!(text.contains("SAFE MODE: 2x"));
+    }
+
+    #[test]
+    fn custom_day_boundary_includes_entries_before_utc_midnight() {
+        let entries = vec![
+            json!({"ts": "2026-02-28T22:59:00Z", "event": "PLACED", "bet_id": "b0", "match_key": "cs2::a_vs_b", "amount_usd": 1.0}),
+            json!({"ts": "2026-02-28T23:30:00Z", "event": "PLACED", "bet_id": "b1", "match_key": "cs2::c_vs_d", "amount_usd": 2.0}),
+            json!({"ts": "2026-03-01T12:00:00Z", "event": "PLACED", "bet_id": "b2", "match_key": "cs2::e_vs_f", "amount_usd": 3.0}),
+        ];
+        // Den "2026-03-01" v UTC+1 = [2026-02-28T23:00Z, 2026-03-01T23:00Z)
+        let digest = DailyDigestEvent::from_ledger_with("2026-03-01", &entries, 0, |ts| {
+            ("2026-02-28T23:00:00Z".."2026-03-01T23:00:00Z").contains(&ts)
+        });
+        assert_eq!(digest.bets_placed, 2);
+        assert!((digest.wagered_usd - 5.0).abs() < 1e-9);
+    }
+}
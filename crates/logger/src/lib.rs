@@ -3,30 +3,195 @@
 
 use anyhow::Result;
 use chrono::Utc;
-use serde::Serialize;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use prediction_engine::Prediction;
+use serde::{Serialize, Serializer};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+mod daily_digest;
+mod near_miss;
+mod order;
+mod recent_events;
+mod resolved_csv;
+mod webhook;
+pub use webhook::{sign_payload, WebhookSink, EVENT_HEADER, SIGNATURE_HEADER};
+pub use near_miss::{NearMissEvent, NearMissLog, NEAR_MISS_DEFAULT_RATIO};
+pub use daily_digest::{DailyDigestEvent, DigestBet, SportDigest};
+pub use recent_events::RecentEvents;
+pub use order::{OrderReceipt, OrderRequest, Venue};
+pub use resolved_csv::{export_resolved_csv, EventReader, RESOLVED_CSV_HEADER};
+
+/// Výchozí max. zpoždění zápisu bufferovaných řádků na disk (flush při dalším `log`,
+/// v tichu mezi eventy ho dožene flush vlákno loggeru).
+pub const LOG_FLUSH_INTERVAL_DEFAULT: Duration = Duration::from_secs(1);
+
+/// Bufferovaný append do denního JSONL souboru.
+struct DailyWriter {
+    date:       String,
+    file:       BufWriter<File>,
+    last_flush: Instant,
+}
+
+impl DailyWriter {
+    /// Každý write() na O_APPEND soubor nese jen celé řádky: řádek, který se do bufferu nevejde,
+    /// nejdřív vyprázdní buffer; řádek větší než buffer jde na disk jedním zápisem.
+    /// Jiný EventLogger nad stejným souborem (monitor, arb, observer) se tak do řádku nevklíní.
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let record = format!("{line}\n");
+        if self.file.buffer().len() + record.len() > self.file.capacity() {
+            self.file.flush()?;
+        }
+        if record.len() >= self.file.capacity() {
+            self.file.get_mut().write_all(record.as_bytes())
+        } else {
+            self.file.write_all(record.as_bytes())
+        }
+    }
+}
 
 pub struct EventLogger {
     log_dir: PathBuf,
+    /// Zámek drží celý řádek → souběžní volající se v souboru neproloží
+    writer:  Arc<Mutex<Option<DailyWriter>>>,
+    flush_interval: Duration,
+    /// Flush vlákno se spouští líně s prvním otevřeným souborem
+    flusher_started: AtomicBool,
+    /// Volitelný outbound webhook (MATCH_RESOLVED / ARB_OPPORTUNITY pro integrátory)
+    webhook: Option<WebhookSink>,
+    /// Volitelný in-memory ring buffer posledních eventů (dashboard, /status)
+    recent:  Option<RecentEvents>,
 }
 
 impl EventLogger {
     pub fn new(log_dir: impl Into<PathBuf>) -> Self {
         let dir = log_dir.into();
         fs::create_dir_all(&dir).ok();
-        Self { log_dir: dir }
+        Self {
+            log_dir: dir,
+            writer: Arc::new(Mutex::new(None)),
+            flush_interval: LOG_FLUSH_INTERVAL_DEFAULT,
+            flusher_started: AtomicBool::new(false),
+            webhook: None,
+            recent: None,
+        }
+    }
+
+    /// Jak dlouho smí řádky ležet v bufferu; `Duration::ZERO` = flush po každém řádku.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Každý logovaný event, který sink chce, se navíc POSTne na webhook (neblokuje).
+    pub fn with_webhook(mut self, sink: WebhookSink) -> Self {
+        self.webhook = Some(sink);
+        self
+    }
+
+    /// Připojí webhook z WEBHOOK_URL / WEBHOOK_SECRET, pokud je nastavený (jinak beze změny).
+    pub fn with_webhook_from_env(self) -> Self {
+        match WebhookSink::from_env() {
+            Some(sink) => self.with_webhook(sink),
+            None => self,
+        }
+    }
+
+    /// Každý logovaný event se navíc pushne do sdíleného ring bufferu.
+    pub fn with_recent_events(mut self, recent: RecentEvents) -> Self {
+        self.recent = Some(recent);
+        self
+    }
+
+    pub fn recent_events(&self) -> Option<&RecentEvents> {
+        self.recent.as_ref()
     }
 
     pub fn log<T: Serialize>(&self, event: &T) -> Result<()> {
         let date  = Utc::now().format("%Y-%m-%d").to_string();
         let path  = self.log_dir.join(format!("{date}.jsonl"));
         let line  = serde_json::to_string(event)?;
-        let mut f = OpenOptions::new().create(true).append(true).open(&path)?;
-        writeln!(f, "{line}")?;
+        {
+            let mut guard = self.writer.lock().unwrap_or_else(|p| p.into_inner());
+            // Nový den (nebo první zápis) → dopsat starý soubor a otevřít nový
+            if !matches!(guard.as_ref(), Some(w) if w.date == date) {
+                if let Some(mut old) = guard.take() {
+                    old.file.flush()?;
+                }
+                let f = OpenOptions::new().create(true).append(true).open(&path)?;
+                *guard = Some(DailyWriter { date, file: BufWriter::new(f), last_flush: Instant::now() });
+                self.start_idle_flusher();
+            }
+            if let Some(w) = guard.as_mut() {
+                w.write_line(&line)?;
+                if w.last_flush.elapsed() >= self.flush_interval {
+                    w.file.flush()?;
+                    w.last_flush = Instant::now();
+                }
+            }
+        }
+
+        if let Some(recent) = &self.recent {
+            recent.push(serde_json::to_value(event)?);
+        }
+
+        if let Some(sink) = &self.webhook {
+            let name = serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .and_then(|v| v.get("event").and_then(|e| e.as_str()).map(str::to_string));
+            if let Some(name) = name.filter(|n| sink.wants(n)) {
+                sink.enqueue(&name, line);
+            }
+        }
         Ok(())
     }
+
+    /// Vlákno, které každý `flush_interval` dopíše řádky ležící v bufferu, i když už žádný další
+    /// `log` nepřijde. Drží jen `Weak` na writer → s dropem loggeru samo skončí.
+    fn start_idle_flusher(&self) {
+        if self.flush_interval.is_zero() || self.flusher_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let writer: Weak<Mutex<Option<DailyWriter>>> = Arc::downgrade(&self.writer);
+        let interval = self.flush_interval;
+        let spawned = std::thread::Builder::new()
+            .name("event-log-flush".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                let Some(writer) = writer.upgrade() else { break };
+                let mut guard = writer.lock().unwrap_or_else(|p| p.into_inner());
+                if let Some(w) = guard.as_mut() {
+                    if !w.file.buffer().is_empty() && w.last_flush.elapsed() >= interval {
+                        if let Err(e) = w.file.flush() {
+                            tracing::warn!("log flush failed: {e}");
+                        }
+                        w.last_flush = Instant::now();
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("log flush thread failed to start: {e}");
+        }
+    }
+
+    /// Zapíše všechny bufferované řádky na disk (graceful shutdown, testy čtoucí soubor).
+    pub fn flush(&self) -> Result<()> {
+        let mut guard = self.writer.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(w) = guard.as_mut() {
+            w.file.flush()?;
+            w.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for EventLogger {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
 }
 
 pub fn now_iso() -> String {
@@ -49,6 +214,23 @@ pub struct PinnacleLineEvent {
     pub pinnacle_prob_away: f64,
 }
 
+/// Arbitráž z odds-api.io /arbitrage-bets (dvě sázkovky, dva outcomy).
+#[derive(Serialize, Debug)]
+pub struct OddsApiArbEvent {
+    pub ts:             String,
+    pub event:          &'static str, // "ODDS_API_ARB"
+    pub sport:          String,
+    pub home:           String,
+    pub away:           String,
+    pub roi_pct:        f64,
+    pub outcome_a:      String,
+    pub outcome_a_odds: f64,
+    pub bookmaker_a:    String,
+    pub outcome_b:      String,
+    pub outcome_b_odds: f64,
+    pub bookmaker_b:    String,
+}
+
 #[derive(Serialize, Debug)]
 pub struct PolymarketPriceEvent {
     pub ts:           String,
@@ -72,6 +254,44 @@ pub struct ArbOpportunityEvent {
     pub pinnacle_prob:   f64,
     pub polymarket_price: f64,
     pub action:          String,         // "OBSERVE" (48h), pak "BUY"
+    /// Objednávka, kterou by executor umístil (zatím jen SX oracle lag)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order:           Option<OrderRequest>,
+}
+
+/// Edge nad stropem — téměř jistě špatná data (jiný market, stale orderbook, parse chyba), nejedná se.
+#[derive(Serialize, Debug)]
+pub struct ArbRejectedEvent {
+    pub ts:              String,
+    pub event:           &'static str,   // "ARB_REJECTED"
+    pub venue:           String,         // "sx_bet" | "azuro"
+    pub home:            String,
+    pub away:            String,
+    pub sport:           String,
+    pub winner:          String,
+    pub market_id:       String,
+    pub net_edge_pct:    f64,
+    pub max_edge_pct:    f64,
+    pub reason:          String,         // "edge_above_ceiling"
+}
+
+/// Vyhodnocená paper sázka (observe-only PreResolution příležitost) proti MATCH_RESOLVED.
+#[derive(Serialize, Debug)]
+pub struct PaperSettlementEvent {
+    pub ts:              String,
+    pub event:           &'static str,   // "PAPER_SETTLEMENT"
+    pub sport:           String,
+    pub home:            String,
+    pub away:            String,
+    pub side:            String,         // tým, na který paper sázka šla
+    pub winner:          String,
+    pub market_id:       String,
+    pub stake_usd:       f64,
+    pub odds:            f64,            // decimal (1 / market price)
+    pub won:             bool,
+    pub pnl_usd:         f64,
+    /// Kumulativní paper P&L (včetně vyhodnocení z minulých běhů)
+    pub paper_pnl_usd:   f64,
 }
 
 #[derive(Serialize, Debug)]
@@ -84,6 +304,59 @@ pub struct MatchResolvedEvent {
     pub away:        String,
     pub winner:      String,
     pub ended_at:    String,
+    /// Nativní ID zápasu u zdroje (Riot `/match/id`, vlr.gg / GosuGamers numerické ID) — join bez jmen týmů
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_match_id: Option<String>,
+    /// `MatchId` klíč ("cs2::faze_vs_navi") — join s `PredictionEvent.match_id`; None = sport bez MatchId
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_id:    Option<String>,
+}
+
+/// Live predikce zápasu i se vstupy — offline kalibrace ji joinuje s MATCH_RESOLVED přes `match_id`.
+#[derive(Serialize, Debug, Clone)]
+pub struct PredictionEvent {
+    pub ts:         String,
+    pub event:      &'static str,    // "PREDICTION"
+    /// `MatchId` klíč ("cs2::faze_vs_navi") — stejný jako `MatchResolvedEvent.match_id`
+    /// (`match_name` nese vlastní název zdroje výsledku, ne tento klíč)
+    pub match_id:   String,
+    pub sport:      String,
+    pub home:       String,           // = team1 predikce
+    pub away:       String,
+    /// Surová predikce enginu; v JSONL jen varianta "team1_win" | "team2_win" | "uncertain"
+    #[serde(serialize_with = "serialize_prediction_variant")]
+    pub prediction: Prediction,
+    /// Logovaná confidence (observer ji nahradí kalibrovanou)
+    pub confidence: Option<f32>,
+    pub inputs:     PredictionInputs,
+}
+
+fn serialize_prediction_variant<S: Serializer>(prediction: &Prediction, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(prediction.variant())
+}
+
+/// Vstupy predikce — stačí k jejímu zopakování nad stejným enginem.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PredictionInputs {
+    /// Roundy na hrané mapě, None = zdroj posílá jen skóre série
+    pub map_score:       Option<(u8, u8)>,
+    pub series_score:    Option<(u8, u8)>,
+    pub map_number:      u8,
+    /// Délka série tak, jak ji dostal engine; None = formát neznámý (žádný odhad)
+    pub best_of:         Option<u8>,
+    /// Skóre mapy po pollech (nejstarší první) — momentum
+    pub score_history:   Vec<(u8, u8)>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PredictionCalibrationEvent {
+    pub ts:              String,
+    pub event:           &'static str,    // "PREDICTION_CALIBRATION"
+    pub evaluated:       usize,           // predikce vyhodnocené proti MATCH_RESOLVED
+    pub correct:         usize,
+    pub accuracy:        Option<f64>,
+    pub avg_confidence:  Option<f64>,     // kalibrace: avg_confidence vs accuracy
+    pub pending_matches: usize,
 }
 
 #[derive(Serialize, Debug)]
@@ -96,6 +369,9 @@ pub struct ApiStatusEvent {
     pub status_code: Option<u16>,
     pub message:     String,
     pub items_logged: usize,
+    /// Stránka se načetla, ale parser nenašel žádný match element (pravděpodobně změna markupu)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub parser_suspect: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -132,3 +408,108 @@ pub async fn send_ntfy_alert(msg: &str, title: &str) {
         Err(e) => tracing::warn!("NTFY failed: {}", e),
     }
 }
+
+#[cfg(test)]
+mod event_logger_tests {
+    use super::*;
+
+    /// Rozbitý (proložený) řádek EventReader přeskočí → testy to poznají na počtu řádků.
+    fn lines(dir: &std::path::Path, event: &str) -> Vec<serde_json::Value> {
+        EventReader::new(dir).read_all(event).unwrap()
+    }
+
+    #[test]
+    fn concurrent_lines_survive_flush_without_interleaving() {
+        let dir = std::env::temp_dir().join(format!("event_logger_concurrent_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let logger = Arc::new(EventLogger::new(&dir).with_flush_interval(Duration::from_secs(3600)));
+
+        let padding = "x".repeat(700); // > kapacita bufferu po pár řádcích
+        let handles: Vec<_> = (0..8).map(|t| {
+            let logger = logger.clone();
+            let padding = padding.clone();
+            std::thread::spawn(move || {
+                for i in 0..250 {
+                    logger.log(&serde_json::json!({"event": "TEST", "thread": t, "i": i, "pad": padding})).unwrap();
+                }
+            })
+        }).collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        logger.flush().unwrap();
+
+        let records = lines(&dir, "TEST");
+        assert_eq!(records.len(), 8 * 250);
+        let unique: std::collections::HashSet<(u64, u64)> = records.iter()
+            .map(|r| (r["thread"].as_u64().unwrap(), r["i"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(unique.len(), 8 * 250);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn loggers_sharing_a_file_never_split_large_lines() {
+        // Monitor, ArbDetector i observer mají vlastní EventLogger nad stejným logs/ → různé file handly
+        let dir = std::env::temp_dir().join(format!("event_logger_shared_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let loggers: Vec<Arc<EventLogger>> = (0..4)
+            .map(|_| Arc::new(EventLogger::new(&dir).with_flush_interval(Duration::from_secs(3600))))
+            .collect();
+
+        // 5 kB řádky přetékají hranici 8 kB bufferu, 20 kB jsou větší než celý buffer
+        let handles: Vec<_> = (0..16).map(|t| {
+            let logger = loggers[t % loggers.len()].clone();
+            std::thread::spawn(move || {
+                for i in 0..60 {
+                    let pad = "y".repeat(if i % 3 == 0 { 20_000 } else { 5_000 } + t);
+                    logger.log(&serde_json::json!({"event": "BIG", "thread": t, "i": i, "pad": pad})).unwrap();
+                }
+            })
+        }).collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        for logger in &loggers {
+            logger.flush().unwrap();
+        }
+
+        let records = lines(&dir, "BIG");
+        assert_eq!(records.len(), 16 * 60);
+        assert!(records.iter().all(|r| r["pad"].as_str().unwrap().len() >= 5_000));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn idle_logger_flushes_without_further_log_calls() {
+        let dir = std::env::temp_dir().join(format!("event_logger_idle_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let logger = EventLogger::new(&dir).with_flush_interval(Duration::from_millis(50));
+        logger.log(&serde_json::json!({"event": "IDLE", "n": 1})).unwrap();
+        assert!(lines(&dir, "IDLE").is_empty(), "řádek zatím jen v bufferu");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while lines(&dir, "IDLE").is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(lines(&dir, "IDLE").len(), 1);
+        drop(logger);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn drop_flushes_buffered_lines() {
+        let dir = std::env::temp_dir().join(format!("event_logger_drop_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        {
+            let logger = EventLogger::new(&dir).with_flush_interval(Duration::from_secs(3600));
+            logger.log(&serde_json::json!({"event": "DROP", "n": 1})).unwrap();
+            logger.log(&serde_json::json!({"event": "DROP", "n": 2})).unwrap();
+            assert!(lines(&dir, "DROP").is_empty(), "řádky zatím jen v bufferu");
+        }
+        let records = lines(&dir, "DROP");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["n"], 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,73 @@
+//! Near-miss log — edge těsně pod thresholdem se zapíše jako NEAR_MISS event (dataset pro tuning thresholdů).
+//!
+//! Zapnuto přes `NEAR_MISS_LOG`: "1" / "true" → výchozí 80 % thresholdu, číslo v (0, 1) → vlastní podíl.
+
+use crate::{now_iso, EventLogger};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Výchozí podíl thresholdu, od kterého se sub-threshold edge považuje za near miss.
+pub const NEAR_MISS_DEFAULT_RATIO: f64 = 0.8;
+
+#[derive(Serialize, Debug)]
+pub struct NearMissEvent {
+    pub ts:            String,
+    pub event:         &'static str,   // "NEAR_MISS"
+    pub source:        String,         // "pinnacle_vs_polymarket" | "score_edge"
+    pub match_name:    String,
+    pub sport:         String,
+    pub edge_pct:      f64,            // v procentech (2.5 = 2.5 %)
+    pub threshold_pct: f64,
+    pub reason:        String,
+}
+
+pub struct NearMissLog {
+    logger: EventLogger,
+    ratio:  f64,
+}
+
+impl NearMissLog {
+    pub fn new(log_dir: impl Into<PathBuf>, ratio: f64) -> Self {
+        Self { logger: EventLogger::new(log_dir), ratio }
+    }
+
+    /// None = NEAR_MISS_LOG nenastaveno / vypnuto.
+    pub fn from_env(log_dir: impl Into<PathBuf>) -> Option<Self> {
+        let ratio = parse_ratio(&std::env::var("NEAR_MISS_LOG").ok()?)?;
+        Some(Self::new(log_dir, ratio))
+    }
+
+    /// Pod thresholdem, ale aspoň `ratio` × threshold.
+    pub fn is_near_miss(&self, edge_pct: f64, threshold_pct: f64) -> bool {
+        edge_pct < threshold_pct && edge_pct >= threshold_pct * self.ratio
+    }
+
+    /// Zapíše NEAR_MISS, pokud edge spadá do pásma; vrací true při zápisu.
+    pub fn record(&self, source: &str, match_name: &str, sport: &str, edge_pct: f64, threshold_pct: f64, reason: &str) -> bool {
+        if !self.is_near_miss(edge_pct, threshold_pct) {
+            return false;
+        }
+        self.logger.log(&NearMissEvent {
+            ts:            now_iso(),
+            event:         "NEAR_MISS",
+            source:        source.to_string(),
+            match_name:    match_name.to_string(),
+            sport:         sport.to_string(),
+            edge_pct,
+            threshold_pct,
+            reason:        reason.to_string(),
+        }).is_ok()
+    }
+
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.logger.flush()
+    }
+}
+
+fn parse_ratio(raw: &str) -> Option<f64> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" | "off" => None,
+        "1" | "true" | "on" => Some(NEAR_MISS_DEFAULT_RATIO),
+        other => other.parse::<f64>().ok().filter(|r| *r > 0.0 && *r < 1.0),
+    }
+}
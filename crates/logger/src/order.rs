@@ -0,0 +1,188 @@
+//! Sdílený model objednávky pro executor integrace — `OrderRequest` (co chceme umístit)
+//! a `OrderReceipt` (co executor vrátil). Alert-bot z něj staví tělo `POST /bet`,
+//! arb_detector ho přikládá k A+ příležitostem, aby budoucí SX executor dostal stejný tvar.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Venue {
+    Azuro,
+    SxBet,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub venue:           Venue,
+    pub chain:           Option<String>,   // "polygon" | "gnosis" | "arbitrum" …; None = default executoru
+    pub market_ref:      String,           // Azuro conditionId / SX marketHash
+    pub outcome_id:      String,
+    pub stake_usd:       f64,
+    pub min_odds:        f64,              // desetinný kurz (ne raw 1e12)
+    pub idempotency_key: String,
+}
+
+impl OrderRequest {
+    /// Idempotency key se odvodí z trhu, outcome, stake a minutového bucketu `at_unix`.
+    pub fn new(
+        venue: Venue,
+        chain: Option<&str>,
+        market_ref: &str,
+        outcome_id: &str,
+        stake_usd: f64,
+        min_odds: f64,
+        at_unix: i64,
+    ) -> Self {
+        Self {
+            venue,
+            chain: chain.map(str::to_string),
+            market_ref: market_ref.to_string(),
+            outcome_id: outcome_id.to_string(),
+            stake_usd,
+            min_odds,
+            idempotency_key: Self::idempotency_key(market_ref, outcome_id, stake_usd, at_unix),
+        }
+    }
+
+    /// Deterministický idempotency key: market + outcome + stake + minutový bucket.
+    /// Retry téhož rozhodnutí (stejný čas rozhodnutí) tak nese stejný klíč a executor ho neumístí dvakrát.
+    pub fn idempotency_key(market_ref: &str, outcome_id: &str, stake_usd: f64, at_unix: i64) -> String {
+        // FNV-1a 64 — stabilní napříč procesy i verzemi (DefaultHasher to negarantuje)
+        let material = format!("{}|{}|{:.2}|{}", market_ref, outcome_id, stake_usd, at_unix.div_euclid(60));
+        let hash = material.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("bet-{:016x}", hash)
+    }
+
+    /// Stake v USDT jednotkách (6 desetinných míst).
+    pub fn amount_raw(&self) -> u64 {
+        (self.stake_usd * 1e6) as u64
+    }
+
+    /// Minimální kurz v Azuro formátu (12 desetinných míst).
+    pub fn min_odds_raw(&self) -> u64 {
+        (self.min_odds * 1e12) as u64
+    }
+
+    /// Tělo `POST /bet` Azuro executoru. `context` (matchKey, týmy, sport, requestedOdds…)
+    /// se přimixuje beze změny — executor ho jen loguje a ukládá k sázce.
+    pub fn to_azuro_executor_body(&self, context: serde_json::Value) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "conditionId":    self.market_ref,
+            "outcomeId":      self.outcome_id,
+            "amount":         self.amount_raw().to_string(),
+            "minOdds":        self.min_odds_raw().to_string(),
+            "idempotencyKey": self.idempotency_key,
+        });
+        if let (Some(dst), serde_json::Value::Object(extra)) = (body.as_object_mut(), context) {
+            for (key, value) in extra {
+                dst.entry(key).or_insert(value);
+            }
+        }
+        body
+    }
+}
+
+/// Odpověď executoru na `POST /bet` (úspěch, DRY-RUN, idempotentní replay i chybové tvary).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderReceipt {
+    pub bet_id:         Option<String>,
+    pub token_id:       Option<String>,
+    pub status:         Option<String>,
+    #[serde(rename = "acceptedOdds")]
+    pub placed_odds:    Option<f64>,
+    pub graph_bet_id:   Option<String>,
+    pub requested_odds: Option<f64>,
+    pub min_odds:       Option<f64>,
+    pub state:          Option<String>,
+    pub error:          Option<String>,
+    /// Executor už tenhle idempotency key jednou umístil a vrací původní odpověď
+    #[serde(default)]
+    pub idempotent_replay: bool,
+}
+
+#[cfg(test)]
+mod order_tests {
+    use super::*;
+
+    fn request() -> OrderRequest {
+        OrderRequest::new(Venue::Azuro, None, "100", "1", 3.0, 1.85, 1_700_000_000)
+    }
+
+    #[test]
+    fn azuro_body_matches_executor_shape_and_keeps_core_fields() {
+        let body = request().to_azuro_executor_body(serde_json::json!({
+            "requestedOdds": 1.90,
+            "matchKey": "cs2::alpha_vs_beta",
+            "conditionId": "override-attempt",
+        }));
+
+        assert_eq!(body["conditionId"], "100");
+        assert_eq!(body["outcomeId"], "1");
+        assert_eq!(body["amount"], "3000000");
+        assert_eq!(body["minOdds"], "1850000000000");
+        assert_eq!(body["requestedOdds"], 1.90);
+        assert_eq!(body["matchKey"], "cs2::alpha_vs_beta");
+        assert_eq!(body["idempotencyKey"], request().idempotency_key);
+    }
+
+    #[test]
+    fn idempotency_key_is_stable_within_minute_bucket() {
+        let a = OrderRequest::idempotency_key("100", "1", 3.0, 1_700_000_040);
+        let b = OrderRequest::idempotency_key("100", "1", 3.0, 1_700_000_099);
+        let c = OrderRequest::idempotency_key("100", "1", 3.0, 1_700_000_100);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("bet-") && a.len() == 20);
+    }
+
+    #[test]
+    fn request_roundtrips_with_snake_case_venue() {
+        let order = OrderRequest::new(Venue::SxBet, Some("arbitrum"), "0xabc", "outcome_one", 25.0, 2.1, 0);
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json["venue"], "sx_bet");
+        assert_eq!(json["chain"], "arbitrum");
+        let back: OrderRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(back, order);
+    }
+
+    #[test]
+    fn receipt_parses_success_response() {
+        let r: OrderReceipt = serde_json::from_str(r#"{
+            "status": "ok", "betId": "0xtx", "tokenId": "4242", "graphBetId": "0xcore_4242",
+            "state": "Accepted", "acceptedOdds": 1.87, "requestedOdds": 1.90,
+            "minOdds": 1.85, "error": null
+        }"#).unwrap();
+        assert_eq!(r.bet_id.as_deref(), Some("0xtx"));
+        assert_eq!(r.token_id.as_deref(), Some("4242"));
+        assert_eq!(r.graph_bet_id.as_deref(), Some("0xcore_4242"));
+        assert_eq!(r.placed_odds, Some(1.87));
+        assert_eq!(r.requested_odds, Some(1.90));
+        assert!(!r.idempotent_replay);
+        assert!(r.error.is_none());
+    }
+
+    #[test]
+    fn receipt_parses_dry_run_replay_and_error_shapes() {
+        let dry: OrderReceipt = serde_json::from_str(r#"{
+            "status": "ok", "betId": "dry-run-1", "state": "DRY-RUN", "mode": "dry", "details": {}
+        }"#).unwrap();
+        assert_eq!(dry.state.as_deref(), Some("DRY-RUN"));
+        assert!(dry.token_id.is_none() && dry.placed_odds.is_none());
+
+        let replay: OrderReceipt = serde_json::from_str(r#"{
+            "status": "ok", "betId": "bet-1", "state": "Accepted",
+            "idempotentReplay": true, "idempotencyKey": "bet-0123456789abcdef"
+        }"#).unwrap();
+        assert!(replay.idempotent_replay);
+
+        let timeout: OrderReceipt = serde_json::from_str(r#"{
+            "error": "Bet submission timed out", "conditionId": "100", "outcomeId": "1",
+            "hint": "check /bets", "keepLock": true
+        }"#).unwrap();
+        assert_eq!(timeout.error.as_deref(), Some("Bet submission timed out"));
+        assert!(timeout.bet_id.is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! In-memory ring buffer posledních eventů — dashboard / `/status` bez čtení JSONL z disku.
+//!
+//! Vypnuto, dokud se na `EventLogger` nepřipojí přes `with_recent_events`; handle je `Clone`
+//! a sdílí jeden buffer, takže ho komponenta může držet nezávisle na loggeru.
+
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+pub struct RecentEvents {
+    buffer:   Arc<Mutex<VecDeque<Value>>>,
+    capacity: usize,
+}
+
+impl RecentEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Přidá event; nad kapacitou vypadne nejstarší.
+    pub fn push(&self, event: Value) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() == self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(event);
+    }
+
+    /// Posledních `n` eventů, nejstarší první.
+    pub fn recent(&self, n: usize) -> Vec<Value> {
+        let buffer = self.buffer.lock().unwrap();
+        buffer.iter().skip(buffer.len().saturating_sub(n)).cloned().collect()
+    }
+
+    /// Posledních `n` eventů daného typu (`"event"` pole, např. "ARB_OPPORTUNITY"), nejstarší první.
+    pub fn recent_of(&self, event: &str, n: usize) -> Vec<Value> {
+        let buffer = self.buffer.lock().unwrap();
+        let mut out: Vec<Value> = buffer.iter().rev()
+            .filter(|v| v.get("event").and_then(|e| e.as_str()) == Some(event))
+            .take(n)
+            .cloned()
+            .collect();
+        out.reverse();
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod recent_events_tests {
+    use super::RecentEvents;
+    use crate::{now_iso, EventLogger, MatchResolvedEvent, ApiStatusEvent};
+
+    fn resolved(name: &str) -> MatchResolvedEvent {
+        MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "cs2".to_string(),
+            match_name: name.to_string(),
+            home:       "alpha".to_string(),
+            away:       "beta".to_string(),
+            winner:     "alpha".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        }
+    }
+
+    #[test]
+    fn buffer_holds_last_n_and_evicts_older() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_recent_events_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let recent = RecentEvents::new(3);
+        let logger = EventLogger::new(&dir).with_recent_events(recent.clone());
+
+        for i in 0..5 {
+            logger.log(&resolved(&format!("m{}", i))).unwrap();
+        }
+        logger.log(&ApiStatusEvent {
+            ts:           now_iso(),
+            event:        "API_STATUS",
+            source:       "vlrgg".to_string(),
+            scope:        "valorant".to_string(),
+            ok:           true,
+            status_code:  Some(200),
+            message:      "ok".to_string(),
+            items_logged: 1,
+            parser_suspect: false,
+        }).unwrap();
+
+        assert_eq!(recent.len(), 3);
+        let names: Vec<_> = recent.recent(10).iter().map(|v| v["event"].as_str().unwrap().to_string()).collect();
+        assert_eq!(names, ["MATCH_RESOLVED", "MATCH_RESOLVED", "API_STATUS"]);
+
+        // m0–m2 vypadly; filtr po typu vrací nejnovější
+        let resolved: Vec<_> = recent.recent_of("MATCH_RESOLVED", 10).iter()
+            .map(|v| v["match_name"].as_str().unwrap().to_string()).collect();
+        assert_eq!(resolved, ["m3", "m4"]);
+        assert_eq!(recent.recent_of("MATCH_RESOLVED", 1)[0]["match_name"], "m4");
+        assert_eq!(recent.recent(1)[0]["event"], "API_STATUS");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
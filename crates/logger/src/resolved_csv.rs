@@ -0,0 +1,149 @@
+//! Export MATCH_RESOLVED eventů z denních JSONL (`{log_dir}/YYYY-MM-DD.jsonl`) do CSV pro tabulky.
+//!
+//! Sloupce: sport, home, away, winner, ended_at, source_match_id, by_forfeit (poslední dva prázdné,
+//! pokud je event nenese).
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+pub const RESOLVED_CSV_HEADER: &str = "sport,home,away,winner,ended_at,source_match_id,by_forfeit";
+
+/// Čte eventy daného typu z denních JSONL souborů v rozsahu dní; chybějící den / vadný řádek se přeskočí.
+pub struct EventReader {
+    log_dir: PathBuf,
+}
+
+impl EventReader {
+    pub fn new(log_dir: impl Into<PathBuf>) -> Self {
+        Self { log_dir: log_dir.into() }
+    }
+
+    pub fn read(&self, event: &str, days: RangeInclusive<NaiveDate>) -> Result<Vec<Value>> {
+        let mut out = Vec::new();
+        for day in days.start().iter_days().take_while(|d| d <= days.end()) {
+            let path = self.log_dir.join(format!("{}.jsonl", day.format("%Y-%m-%d")));
+            if !path.exists() {
+                continue;
+            }
+            for line in BufReader::new(File::open(&path)?).lines() {
+                let Ok(entry) = serde_json::from_str::<Value>(&line?) else { continue };
+                if entry.get("event").and_then(Value::as_str) == Some(event) {
+                    out.push(entry);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Jako `read`, ale přes všechny denní soubory v `log_dir` — pro stav, který má přežít restart.
+    pub fn read_all(&self, event: &str) -> Result<Vec<Value>> {
+        let Ok(entries) = std::fs::read_dir(&self.log_dir) else { return Ok(Vec::new()) };
+        let mut days: Vec<NaiveDate> = entries.flatten()
+            .filter_map(|e| {
+                let name = e.file_name();
+                NaiveDate::parse_from_str(name.to_str()?.strip_suffix(".jsonl")?, "%Y-%m-%d").ok()
+            })
+            .collect();
+        days.sort();
+        match (days.first(), days.last()) {
+            (Some(&first), Some(&last)) => self.read(event, first..=last),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// RFC 4180: pole s čárkou, uvozovkou nebo novým řádkem do uvozovek, uvozovky zdvojit.
+fn csv_field(raw: &str) -> String {
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw.to_string()
+    }
+}
+
+fn text(entry: &Value, key: &str) -> String {
+    match entry.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Bool(b)) => b.to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Zapíše MATCH_RESOLVED eventy z `log_dir` za dny `days` jako CSV (s hlavičkou). Vrací počet řádků dat.
+pub fn export_resolved_csv(log_dir: impl AsRef<Path>, days: RangeInclusive<NaiveDate>, mut writer: impl Write) -> Result<usize> {
+    let events = EventReader::new(log_dir.as_ref()).read("MATCH_RESOLVED", days)?;
+    writeln!(writer, "{}", RESOLVED_CSV_HEADER)?;
+    for ev in &events {
+        let row: Vec<String> = ["sport", "home", "away", "winner", "ended_at", "source_match_id", "by_forfeit"]
+            .iter()
+            .map(|key| csv_field(&text(ev, key)))
+            .collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    writer.flush()?;
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod resolved_csv_tests {
+    use super::*;
+
+    fn day(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn exports_resolved_matches_in_range_with_escaping() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_resolved_csv_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2026-03-01.jsonl"), concat!(
+            r#"{"ts":"2026-03-01T20:00:00Z","event":"MATCH_RESOLVED","sport":"cs2","match_name":"navi_vs_faze","home":"NaVi","away":"FaZe","winner":"NaVi","ended_at":"2026-03-01T20:00:00Z","source_match_id":"2370001"}"#, "\n",
+            r#"{"ts":"2026-03-01T20:01:00Z","event":"SYSTEM_HEARTBEAT","phase":"LIVE_SCORING_ACTIVE"}"#, "\n",
+            "not json\n",
+        )).unwrap();
+        std::fs::write(dir.join("2026-03-02.jsonl"), concat!(
+            r#"{"ts":"2026-03-02T18:00:00Z","event":"MATCH_RESOLVED","sport":"valorant","match_name":"x","home":"Team \"Quoted\"","away":"A, B","winner":"A, B","ended_at":"2026-03-02T18:00:00Z","by_forfeit":true}"#, "\n",
+        )).unwrap();
+        // Mimo rozsah
+        std::fs::write(dir.join("2026-03-05.jsonl"), concat!(
+            r#"{"ts":"2026-03-05T18:00:00Z","event":"MATCH_RESOLVED","sport":"dota2","home":"a","away":"b","winner":"a","ended_at":"2026-03-05T18:00:00Z"}"#, "\n",
+        )).unwrap();
+
+        let mut out = Vec::new();
+        let rows = export_resolved_csv(&dir, day("2026-03-01")..=day("2026-03-03"), &mut out).unwrap();
+
+        assert_eq!(rows, 2);
+        assert_eq!(String::from_utf8(out).unwrap(), concat!(
+            "sport,home,away,winner,ended_at,source_match_id,by_forfeit\n",
+            "cs2,NaVi,FaZe,NaVi,2026-03-01T20:00:00Z,2370001,\n",
+            "valorant,\"Team \"\"Quoted\"\"\",\"A, B\",\"A, B\",2026-03-02T18:00:00Z,,true\n",
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn read_all_covers_every_day_file_and_skips_other_jsonl() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_event_reader_all_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2026-03-05.jsonl"), concat!(r#"{"event":"PAPER_SETTLEMENT","n":2}"#, "\n")).unwrap();
+        std::fs::write(dir.join("2026-02-27.jsonl"), concat!(
+            r#"{"event":"PAPER_SETTLEMENT","n":1}"#, "\n",
+            r#"{"event":"MATCH_RESOLVED"}"#, "\n",
+        )).unwrap();
+        // Inbox v téže složce není denní log
+        std::fs::write(dir.join("paper_bets.jsonl"), concat!(r#"{"event":"PAPER_SETTLEMENT","n":9}"#, "\n")).unwrap();
+
+        let events = EventReader::new(&dir).read_all("PAPER_SETTLEMENT").unwrap();
+        let order: Vec<i64> = events.iter().filter_map(|e| e["n"].as_i64()).collect();
+        assert_eq!(order, vec![1, 2]);
+        assert!(EventReader::new(dir.join("missing")).read_all("PAPER_SETTLEMENT").unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
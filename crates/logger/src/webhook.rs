@@ -0,0 +1,198 @@
+//! Outbound webhook sink — POSTuje vybrané eventy (MATCH_RESOLVED, ARB_OPPORTUNITY) na URL integrátora.
+//!
+//! Bounded fronta + background worker: pomalý webhook nikdy neblokuje hot path (plná fronta = drop + warn).
+//! Payload je podepsaný HMAC-SHA256 z `WEBHOOK_SECRET` v hlavičce `X-Rustmisko-Signature: sha256=<hex>`.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+pub const SIGNATURE_HEADER: &str = "X-Rustmisko-Signature";
+pub const EVENT_HEADER: &str = "X-Rustmisko-Event";
+
+/// Kapacita fronty — při zaseknutém webhooku se další eventy zahazují místo hromadění v RAM.
+const QUEUE_CAPACITY: usize = 256;
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Eventy posílané, pokud WEBHOOK_EVENTS není nastavené.
+const DEFAULT_EVENTS: [&str; 2] = ["MATCH_RESOLVED", "ARB_OPPORTUNITY"];
+
+/// HMAC-SHA256 podpis těla jako hex (receiver si ho přepočítá stejným secretem).
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[derive(Clone, Debug)]
+pub struct WebhookSink {
+    tx:     mpsc::Sender<(String, String)>,
+    events: Vec<String>,
+}
+
+impl WebhookSink {
+    /// Spustí worker na aktuálním tokio runtime. Bez runtime vrací None.
+    pub fn spawn(url: impl Into<String>, secret: Option<String>) -> Option<Self> {
+        Self::spawn_with_backoff(url.into(), secret, INITIAL_BACKOFF)
+    }
+
+    /// WEBHOOK_URL (povinné), WEBHOOK_SECRET, WEBHOOK_EVENTS (čárkami oddělené názvy eventů).
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("WEBHOOK_URL").ok().filter(|u| !u.trim().is_empty())?;
+        let secret = std::env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+        let mut sink = Self::spawn(url, secret)?;
+        if let Ok(events) = std::env::var("WEBHOOK_EVENTS") {
+            sink.events = events.split(',')
+                .map(|e| e.trim().to_uppercase())
+                .filter(|e| !e.is_empty())
+                .collect();
+        }
+        Some(sink)
+    }
+
+    fn spawn_with_backoff(url: String, secret: Option<String>, initial_backoff: Duration) -> Option<Self> {
+        let handle = tokio::runtime::Handle::try_current().ok()?;
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        handle.spawn(run_worker(url, secret, rx, initial_backoff));
+        Some(Self {
+            tx,
+            events: DEFAULT_EVENTS.iter().map(|e| e.to_string()).collect(),
+        })
+    }
+
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.iter().any(|e| e == event)
+    }
+
+    /// Non-blocking enqueue; plná fronta = event se pro webhook zahodí (JSONL ho má dál).
+    pub fn enqueue(&self, event: &str, body: String) {
+        if let Err(e) = self.tx.try_send((event.to_string(), body)) {
+            warn!("Webhook queue full/closed, dropping {}: {}", event, e);
+        }
+    }
+}
+
+async fn run_worker(
+    url: String,
+    secret: Option<String>,
+    mut rx: mpsc::Receiver<(String, String)>,
+    initial_backoff: Duration,
+) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+
+    while let Some((event, body)) = rx.recv().await {
+        let signature = secret.as_deref().map(|s| format!("sha256={}", sign_payload(s, &body)));
+        let mut backoff = initial_backoff;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let mut req = client.post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event.as_str())
+                .body(body.clone());
+            if let Some(sig) = &signature {
+                req = req.header(SIGNATURE_HEADER, sig.as_str());
+            }
+
+            match req.send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    debug!("Webhook delivered {} (attempt {})", event, attempt);
+                    break;
+                }
+                Ok(resp) => warn!("Webhook {} HTTP {} (attempt {}/{})", event, resp.status(), attempt, MAX_ATTEMPTS),
+                Err(e) => warn!("Webhook {} failed: {} (attempt {}/{})", event, e, attempt, MAX_ATTEMPTS),
+            }
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod webhook_tests {
+    use super::{sign_payload, WebhookSink, EVENT_HEADER, SIGNATURE_HEADER};
+    use crate::{now_iso, ApiStatusEvent, EventLogger, MatchResolvedEvent};
+    use std::time::Duration;
+    use test_http::Request;
+    use tokio::sync::mpsc;
+
+    /// Webhook endpoint: odpovídá `status(pořadí requestu)` a posílá přijaté requesty do kanálu.
+    async fn spawn_hook(status: fn(usize) -> &'static str) -> (String, mpsc::UnboundedReceiver<Request>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let url = test_http::serve(move |req| {
+            let _ = tx.send(Request { index: req.index, raw: req.raw.clone() });
+            test_http::response(status(req.index), &[], "")
+        }).await;
+        (format!("{}/hook", url), rx)
+    }
+
+    async fn next_request(rx: &mut mpsc::UnboundedReceiver<Request>) -> Request {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn resolved_event_is_delivered_with_valid_signature() {
+        let (url, mut requests) = spawn_hook(|_| "200 OK").await;
+
+        let dir = std::env::temp_dir().join(format!("rustmisko_webhook_{}", std::process::id()));
+        let sink = WebhookSink::spawn(url, Some("s3cret".to_string())).unwrap();
+        let logger = EventLogger::new(&dir).with_webhook(sink);
+
+        // API_STATUS není ve výchozím filtru → na webhook nejde
+        logger.log(&ApiStatusEvent {
+            ts:           now_iso(),
+            event:        "API_STATUS",
+            source:       "vlr".to_string(),
+            scope:        "valorant".to_string(),
+            ok:           true,
+            status_code:  Some(200),
+            message:      "ok".to_string(),
+            items_logged: 1,
+            parser_suspect: false,
+        }).unwrap();
+        logger.log(&MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "valorant".to_string(),
+            match_name: "Alpha vs Beta".to_string(),
+            home:       "Alpha".to_string(),
+            away:       "Beta".to_string(),
+            winner:     "Alpha".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        }).unwrap();
+
+        let req = next_request(&mut requests).await;
+        let parsed: serde_json::Value = serde_json::from_str(req.body()).unwrap();
+        assert_eq!(parsed["event"], "MATCH_RESOLVED");
+        assert_eq!(parsed["winner"], "Alpha");
+        assert_eq!(req.header(EVENT_HEADER), Some("MATCH_RESOLVED"));
+
+        let expected = format!("sha256={}", sign_payload("s3cret", req.body()));
+        assert_eq!(req.header(SIGNATURE_HEADER), Some(expected.as_str()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_is_retried() {
+        let (url, mut requests) = spawn_hook(|index| if index == 0 { "503 Service Unavailable" } else { "200 OK" }).await;
+
+        let sink = WebhookSink::spawn_with_backoff(url, None, Duration::from_millis(10)).unwrap();
+        sink.enqueue("ARB_OPPORTUNITY", r#"{"event":"ARB_OPPORTUNITY"}"#.to_string());
+
+        let first = next_request(&mut requests).await;
+        let retry = next_request(&mut requests).await;
+        assert_eq!(retry.index, 1);
+        assert_eq!(first.body(), retry.body());
+    }
+}
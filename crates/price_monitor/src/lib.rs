@@ -12,16 +12,76 @@ use logger::{
     EventLogger,
     OddsApiArbEvent,
     PinnacleLineEvent,
+    RecentEvents,
     SystemHeartbeatEvent,
     now_iso,
 };
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tracing::{info, warn, debug};
 
+const PINNACLE_BASE_URL: &str = "https://api.pinnacle.com";
+const ODDS_API_BASE_URL: &str = "https://odds-api.io";
+
+/// Výchozí sporty odds-api.io /arbitrage-bets — jen tradiční sporty (kompatibilita se starším během),
+/// esports pipeline z nich nic nemá. Jiné klíče (ověřené v odds-api.io katalogu) přes `ODDS_API_SPORTS`.
+pub const ODDS_API_DEFAULT_SPORTS: &[&str] = &[
+    "basketball_nba",
+    "soccer_epl",
+    "soccer_uefa_champs_league",
+    "americanfootball_nfl",
+    "baseball_mlb",
+    "icehockey_nhl",
+    "tennis_atp_french_open",
+];
+
+/// Sporty pro odds-api.io poller z env:
+///   ODDS_API_SPORTS — klíče oddělené čárkou; skupina `default`; chybí = `ODDS_API_DEFAULT_SPORTS`
+///   ODDS_API_EXCLUDE_SPORTS — klíče / skupiny vyřazené z výsledku
+///   ODDS_API_ENABLED=0 — poller úplně vypnout
+/// Prázdný seznam = poller vypnutý.
+pub fn oddsapi_sports_from_env() -> Vec<String> {
+    oddsapi_sports_from_lookup(|key| std::env::var(key).ok())
+}
+
+fn oddsapi_sports_from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    if lookup("ODDS_API_ENABLED").is_some_and(|v| matches!(v.trim(), "0" | "false")) {
+        return Vec::new();
+    }
+    let expand = |raw: &str| -> Vec<String> {
+        raw.split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .flat_map(|s| match s.as_str() {
+                "default" => ODDS_API_DEFAULT_SPORTS.iter().map(|k| k.to_string()).collect(),
+                _ => vec![s],
+            })
+            .collect()
+    };
+    let include = lookup("ODDS_API_SPORTS")
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| expand(&v))
+        .unwrap_or_else(|| expand("default"));
+    let exclude = lookup("ODDS_API_EXCLUDE_SPORTS").map(|v| expand(&v)).unwrap_or_default();
+
+    let mut sports: Vec<String> = Vec::new();
+    for sport in include {
+        if !exclude.contains(&sport) && !sports.contains(&sport) {
+            sports.push(sport);
+        }
+    }
+    sports
+}
+
 // ── Pinnacle structs ─────────────────────────────────────────────────────────
 
 #[derive(Deserialize, Debug)]
 pub struct PinnacleFixtures {
+    /// Kurzor pro další `since` dotaz (jen v2)
+    #[serde(default)]
+    pub last:      Option<u64>,
+    #[serde(default)]
     pub league:    Vec<PinnacleLeague>,
 }
 
@@ -42,6 +102,9 @@ pub struct PinnacleEvent {
 
 #[derive(Deserialize, Debug)]
 pub struct PinnacleOdds {
+    #[serde(default)]
+    pub last:    Option<u64>,
+    #[serde(default)]
     pub leagues: Vec<PinnacleLeagueOdds>,
 }
 
@@ -60,6 +123,7 @@ pub struct PinnacleEventOdds {
 #[derive(Deserialize, Debug)]
 pub struct PinnaclePeriod {
     pub number:    u8,
+    #[serde(rename = "moneyline")]
     pub money_line: Option<PinnacleMoneyLine>,
 }
 
@@ -101,8 +165,29 @@ pub struct PriceMonitor {
     logger:       EventLogger,
     pinnacle_key: Option<String>,   // None = Pinnacle bez auth (free)
     oddsapi_key:  Option<String>,   // odds-api.io klíč
+    /// Sporty pro /arbitrage-bets; prázdné = poller vypnutý
+    oddsapi_sports: Vec<String>,
+    oddsapi_base_url: String,
     min_roi_pct:  f64,
     poll_interval_secs: u64,
+    pinnacle_base_url: String,
+    /// v2 incremental polling: sport_id → `last` kurzory (fixtures, odds)
+    pinnacle_since: Mutex<HashMap<u32, PinnacleCursor>>,
+    /// v2 fixtures cache: event_id → týmy (incremental fixtures vrací jen změny)
+    pinnacle_fixtures: Mutex<HashMap<u64, PinnacleFixtureInfo>>,
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+struct PinnacleCursor {
+    fixtures: Option<u64>,
+    odds:     Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+struct PinnacleFixtureInfo {
+    home:   String,
+    away:   String,
+    league: String,
 }
 
 struct SourceProbe {
@@ -133,11 +218,44 @@ impl PriceMonitor {
             logger:       EventLogger::new(log_dir),
             pinnacle_key,
             oddsapi_key,
+            oddsapi_sports: ODDS_API_DEFAULT_SPORTS.iter().map(|s| s.to_string()).collect(),
+            oddsapi_base_url: ODDS_API_BASE_URL.to_string(),
             min_roi_pct,
             poll_interval_secs,
+            pinnacle_base_url: PINNACLE_BASE_URL.to_string(),
+            pinnacle_since: Mutex::new(HashMap::new()),
+            pinnacle_fixtures: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Přesměrování Pinnacle API (testy / proxy).
+    pub fn with_pinnacle_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.pinnacle_base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sporty pro odds-api.io poller (viz `oddsapi_sports_from_env`); prázdný seznam poller vypne.
+    pub fn with_oddsapi_sports<S: Into<String>>(mut self, sports: impl IntoIterator<Item = S>) -> Self {
+        self.oddsapi_sports = sports.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Přesměrování odds-api.io (testy / proxy).
+    pub fn with_oddsapi_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.oddsapi_base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// PINNACLE_LINE eventy navíc do sdíleného bufferu — `ArbDetector::evaluate_live_value` z nich bere fair value.
+    pub fn with_recent_events(mut self, recent: RecentEvents) -> Self {
+        self.logger = self.logger.with_recent_events(recent);
+        self
+    }
+
+    fn pinnacle_auth(&self) -> Option<&str> {
+        self.pinnacle_key.as_deref().filter(|k| !k.trim().is_empty())
+    }
+
     /// Hlavní poll — zavolej periodicky (každých 60s)
     pub async fn poll_all(&self) {
         let mut summary = PollSummary {
@@ -165,6 +283,7 @@ impl PriceMonitor {
                     status_code: None,
                     message: format!("poll_failed: {e}"),
                     items_logged: 0,
+                    parser_suspect: false,
                 };
                 let _ = self.logger.log(&status);
             }
@@ -192,6 +311,7 @@ impl PriceMonitor {
                     status_code: None,
                     message: format!("poll_failed: {e}"),
                     items_logged: 0,
+                    parser_suspect: false,
                 };
                 let _ = self.logger.log(&status);
             }
@@ -205,6 +325,7 @@ impl PriceMonitor {
             pinnacle_items: summary.pinnacle_items,
             oddsapi_items: summary.oddsapi_items,
             total_items: summary.pinnacle_items + summary.oddsapi_items,
+            overall_items: summary.pinnacle_items + summary.oddsapi_items,
             healthy_sources: summary.healthy_sources,
             total_sources: summary.total_sources,
         };
@@ -227,13 +348,14 @@ impl PriceMonitor {
     // ── A) Pinnacle ──────────────────────────────────────────────────────────
 
     async fn poll_pinnacle(&self) -> Result<(usize, usize, usize)> {
-        // Sports IDs na Pinnacle: 29=soccer, 4=basketball, 3=baseball, 6=hockey, 33=tennis
+        // Sports IDs na Pinnacle: 29=soccer, 4=basketball, 3=baseball, 6=hockey, 33=tennis, 12=esports
         let sport_ids = vec![
             (29u32, "soccer"),
             (4,  "basketball"),
             (3,  "baseball"),
             (6,  "hockey"),
             (33, "tennis"),
+            (12, "esports"),
         ];
 
         let mut total_items = 0usize;
@@ -253,6 +375,7 @@ impl PriceMonitor {
                         status_code: probe.status_code,
                         message: probe.message,
                         items_logged: probe.items_logged,
+                        parser_suspect: false,
                     };
                     let _ = self.logger.log(&ev);
 
@@ -269,23 +392,108 @@ impl PriceMonitor {
     }
 
     async fn fetch_pinnacle_sport(&self, sport_id: u32, sport_name: &str) -> Result<SourceProbe> {
-        // Pinnacle public API (free, bez auth pro read-only odds)
-        // Docs: https://pinnacleapi.github.io/linesapi
-        let url = format!(
-            "https://api.pinnacle.com/v1/odds?sportId={}&oddsFormat=Decimal&toOddsFormat=Decimal",
-            sport_id
+        if self.pinnacle_auth().is_some() {
+            self.fetch_pinnacle_sport_v2(sport_id, sport_name).await
+        } else {
+            self.fetch_pinnacle_sport_v1(sport_id, sport_name).await
+        }
+    }
+
+    /// Autentizovaný v2: fixtures (týmy) + odds (kurzy), obojí inkrementálně přes `since`.
+    async fn fetch_pinnacle_sport_v2(&self, sport_id: u32, sport_name: &str) -> Result<SourceProbe> {
+        let cursor = self.pinnacle_since.lock().unwrap().get(&sport_id).copied().unwrap_or_default();
+
+        let fixtures_url = with_since(
+            format!("{}/v2/fixtures?sportId={}", self.pinnacle_base_url, sport_id),
+            cursor.fixtures,
         );
+        let fixtures_raw = match self.pinnacle_get(&fixtures_url, sport_name).await? {
+            Ok(raw) => raw,
+            Err(probe) => return Ok(probe),
+        };
+        // Prázdné tělo = žádné změny od `since`
+        if !fixtures_raw.trim().is_empty() {
+            let fixtures: PinnacleFixtures = serde_json::from_str(&fixtures_raw)
+                .context("Pinnacle v2 fixtures parse failed")?;
+            let mut cache = self.pinnacle_fixtures.lock().unwrap();
+            merge_pinnacle_fixtures(&mut cache, &fixtures);
+            if let Some(last) = fixtures.last {
+                self.pinnacle_since.lock().unwrap().entry(sport_id).or_default().fixtures = Some(last);
+            }
+        }
 
-        let mut req = self.client.get(&url)
-            .header("Accept", "application/json");
+        let odds_url = with_since(
+            format!("{}/v2/odds?sportId={}&oddsFormat=Decimal", self.pinnacle_base_url, sport_id),
+            cursor.odds,
+        );
+        let (status_code, odds_raw) = match self.pinnacle_get(&odds_url, sport_name).await? {
+            Ok(raw) => (200u16, raw),
+            Err(probe) => return Ok(probe),
+        };
 
-        // Pinnacle může vyžadovat auth pro některé endpointy
-        if let Some(ref key) = self.pinnacle_key {
-            req = req.header("Authorization", format!("Basic {}", key));
+        let mut count = 0usize;
+        if !odds_raw.trim().is_empty() {
+            let odds: PinnacleOdds = serde_json::from_str(&odds_raw)
+                .context("Pinnacle v2 odds parse failed")?;
+            let lines = {
+                let cache = self.pinnacle_fixtures.lock().unwrap();
+                pinnacle_lines_from_v2(sport_name, &cache, &odds)
+            };
+            for line_ev in &lines {
+                if let Err(e) = self.logger.log(line_ev) {
+                    warn!("Log write failed: {e}");
+                }
+            }
+            count = lines.len();
+            if let Some(last) = odds.last {
+                self.pinnacle_since.lock().unwrap().entry(sport_id).or_default().odds = Some(last);
+            }
         }
 
+        Ok(SourceProbe {
+            scope: sport_name.to_string(),
+            ok: true,
+            status_code: Some(status_code),
+            message: "ok_v2".to_string(),
+            items_logged: count,
+        })
+    }
+
+    /// GET s Basic auth; ne-2xx → Err(probe) pro API_STATUS.
+    async fn pinnacle_get(&self, url: &str, sport_name: &str) -> Result<std::result::Result<String, SourceProbe>> {
+        let mut req = self.client.get(url).header("Accept", "application/json");
+        if let Some(key) = self.pinnacle_auth() {
+            req = req.header("Authorization", format!("Basic {}", key));
+        }
         let resp = req.send().await.context("Pinnacle request failed")?;
         let status = resp.status();
+        let body = resp.text().await.context("Pinnacle body read failed")?;
+        if !status.is_success() {
+            warn!("Pinnacle API {sport_name} status {status}: {}", &body[..body.len().min(200)]);
+            return Ok(Err(SourceProbe {
+                scope: sport_name.to_string(),
+                ok: false,
+                status_code: Some(status.as_u16()),
+                message: format!("http_status_{status}"),
+                items_logged: 0,
+            }));
+        }
+        Ok(Ok(body))
+    }
+
+    /// Nepřihlášený v1 `odds` (bez fixtures → místo týmů jen event id / league id).
+    async fn fetch_pinnacle_sport_v1(&self, sport_id: u32, sport_name: &str) -> Result<SourceProbe> {
+        // Pinnacle public API (free, bez auth pro read-only odds)
+        // Docs: https://pinnacleapi.github.io/linesapi
+        let url = format!(
+            "{}/v1/odds?sportId={}&oddsFormat=Decimal&toOddsFormat=Decimal",
+            self.pinnacle_base_url, sport_id
+        );
+
+        let resp = self.client.get(&url)
+            .header("Accept", "application/json")
+            .send().await.context("Pinnacle request failed")?;
+        let status = resp.status();
 
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
@@ -370,6 +578,11 @@ impl PriceMonitor {
     // ── B) odds-api.io /arbitrage-bets ───────────────────────────────────────
 
     async fn poll_oddsapi_arb(&self) -> Result<(usize, usize, usize)> {
+        if self.oddsapi_sports.is_empty() {
+            debug!("odds-api.io poller vypnutý (žádné sporty)");
+            return Ok((0, 0, 0));
+        }
+
         let key = match &self.oddsapi_key {
             Some(k) if !k.trim().is_empty() => k.clone(),
             _ => {
@@ -382,28 +595,18 @@ impl PriceMonitor {
                     status_code: None,
                     message: "skipped_no_api_key".to_string(),
                     items_logged: 0,
+                    parser_suspect: false,
                 };
                 let _ = self.logger.log(&status);
                 return Ok((0, 0, 1));
             }
         };
 
-        // odds-api.io — sports supported: americanfootball_nfl, basketball_nba, soccer_epl, ...
-        let sports = vec![
-            "basketball_nba",
-            "soccer_epl",
-            "soccer_uefa_champs_league",
-            "americanfootball_nfl",
-            "baseball_mlb",
-            "icehockey_nhl",
-            "tennis_atp_french_open",
-        ];
-
         let mut total_items = 0usize;
         let mut healthy = 0usize;
         let mut total_sources = 0usize;
 
-        for sport in sports {
+        for sport in &self.oddsapi_sports {
             total_sources += 1;
             match self.fetch_arb_for_sport(sport, &key).await {
                 Ok(probe) => {
@@ -416,6 +619,7 @@ impl PriceMonitor {
                         status_code: probe.status_code,
                         message: probe.message,
                         items_logged: probe.items_logged,
+                        parser_suspect: false,
                     };
                     let _ = self.logger.log(&ev);
 
@@ -435,7 +639,7 @@ impl PriceMonitor {
     async fn fetch_arb_for_sport(&self, sport: &str, api_key: &str) -> Result<SourceProbe> {
         // odds-api.io free tier: 100 req/hour
         // Endpoint: GET https://odds-api.io/v1/arbitrage-bets?sport={sport}&apiKey={key}
-        let base_url = format!("https://odds-api.io/v1/arbitrage-bets?sport={}&apiKey={}", sport, api_key);
+        let base_url = format!("{}/v1/arbitrage-bets?sport={}&apiKey={}", self.oddsapi_base_url, sport, api_key);
 
         let resp = self.client
             .get(&base_url)
@@ -508,3 +712,178 @@ impl PriceMonitor {
         })
     }
 }
+
+fn with_since(url: String, since: Option<u64>) -> String {
+    match since {
+        Some(last) => format!("{url}&since={last}"),
+        None => url,
+    }
+}
+
+fn merge_pinnacle_fixtures(cache: &mut HashMap<u64, PinnacleFixtureInfo>, fixtures: &PinnacleFixtures) {
+    for league in &fixtures.league {
+        for ev in &league.events {
+            cache.insert(ev.id, PinnacleFixtureInfo {
+                home:   ev.home.clone(),
+                away:   ev.away.clone(),
+                league: league.name.clone(),
+            });
+        }
+    }
+}
+
+/// v2 odds + fixtures cache → PINNACLE_LINE eventy se skutečnými názvy týmů.
+/// Eventy bez známého fixture (ještě nepřišel) se přeskočí.
+fn pinnacle_lines_from_v2(
+    sport_name: &str,
+    fixtures: &HashMap<u64, PinnacleFixtureInfo>,
+    odds: &PinnacleOdds,
+) -> Vec<PinnacleLineEvent> {
+    let mut lines = Vec::new();
+    for league in &odds.leagues {
+        for ev in &league.events {
+            let Some(fixture) = fixtures.get(&ev.id) else {
+                debug!("Pinnacle {sport_name}: odds for unknown event {} (league {})", ev.id, league.id);
+                continue;
+            };
+            let moneyline = ev.periods.iter()
+                .find(|p| p.number == 0)
+                .and_then(|p| p.money_line.as_ref());
+            let Some(PinnacleMoneyLine { home: Some(h), away: Some(a), draw }) = moneyline else {
+                continue;
+            };
+            // Převod decimal odds → implied prob (bez vigu)
+            let raw_home = 1.0 / h;
+            let raw_away = 1.0 / a;
+            let total = raw_home + raw_away + draw.map(|d| 1.0 / d).unwrap_or(0.0);
+            debug!("Pinnacle {sport_name} [{}]: {} vs {}", fixture.league, fixture.home, fixture.away);
+            lines.push(PinnacleLineEvent {
+                ts:                 now_iso(),
+                event:              "PINNACLE_LINE",
+                sport:              sport_name.to_string(),
+                home:               fixture.home.clone(),
+                away:               fixture.away.clone(),
+                home_odds:          *h,
+                away_odds:          *a,
+                draw_odds:          *draw,
+                pinnacle_prob_home: raw_home / total,
+                pinnacle_prob_away: raw_away / total,
+            });
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod pinnacle_v2_tests {
+    use super::{merge_pinnacle_fixtures, pinnacle_lines_from_v2, with_since, PinnacleFixtures, PinnacleOdds, PriceMonitor};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const FIXTURES: &str = include_str!("../tests/fixtures/pinnacle_v2_fixtures.json");
+    const ODDS: &str = include_str!("../tests/fixtures/pinnacle_v2_odds.json");
+
+    #[test]
+    fn v2_fixtures_and_odds_produce_lines_with_team_names() {
+        let fixtures: PinnacleFixtures = serde_json::from_str(FIXTURES).unwrap();
+        let odds: PinnacleOdds = serde_json::from_str(ODDS).unwrap();
+        assert_eq!(fixtures.last, Some(1_700_000_100));
+        assert_eq!(odds.last, Some(1_700_000_200));
+
+        let mut cache = HashMap::new();
+        merge_pinnacle_fixtures(&mut cache, &fixtures);
+        let lines = pinnacle_lines_from_v2("esports", &cache, &odds);
+
+        // 3. event v odds nemá fixture → přeskočen
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].home.as_str(), lines[0].away.as_str()), ("Natus Vincere", "FaZe Clan"));
+        assert_eq!(lines[0].home_odds, 1.85);
+        assert!((lines[0].pinnacle_prob_home + lines[0].pinnacle_prob_away - 1.0).abs() < 1e-9);
+        assert_eq!((lines[1].home.as_str(), lines[1].away.as_str()), ("Team Liquid", "Gaimin Gladiators"));
+
+        assert_eq!(with_since("u?sportId=12".into(), None), "u?sportId=12");
+        assert_eq!(with_since("u?sportId=12".into(), Some(5)), "u?sportId=12&since=5");
+    }
+
+    #[tokio::test]
+    async fn authenticated_poll_uses_v2_and_since_cursor() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let url = test_http::serve(move |req| {
+            sink.lock().unwrap().push((req.path().to_string(), req.header("authorization").map(str::to_string)));
+            test_http::json(if req.path().starts_with("/v2/fixtures") { FIXTURES } else { ODDS })
+        }).await;
+
+        let dir = std::env::temp_dir().join(format!("rustmisko_pinnacle_v2_{}", std::process::id()));
+        let monitor = PriceMonitor::new(&dir, Some("secret".to_string()), None, 1.0, 60)
+            .with_pinnacle_base_url(url);
+
+        let first = monitor.fetch_pinnacle_sport(12, "esports").await.unwrap();
+        assert!(first.ok);
+        assert_eq!(first.items_logged, 2);
+        let second = monitor.fetch_pinnacle_sport(12, "esports").await.unwrap();
+        assert_eq!(second.items_logged, 2);
+
+        let seen = seen.lock().unwrap().clone();
+        assert!(seen.iter().all(|(_, auth)| auth.as_deref() == Some("Basic secret")), "{seen:?}");
+        let paths: Vec<String> = seen.into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths.len(), 4);
+        assert_eq!(paths[0], "/v2/fixtures?sportId=12");
+        assert_eq!(paths[1], "/v2/odds?sportId=12&oddsFormat=Decimal");
+        assert_eq!(paths[2], "/v2/fixtures?sportId=12&since=1700000100");
+        assert_eq!(paths[3], "/v2/odds?sportId=12&oddsFormat=Decimal&since=1700000200");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod oddsapi_sports_tests {
+    use super::{oddsapi_sports_from_lookup, PriceMonitor, ODDS_API_DEFAULT_SPORTS};
+    use std::sync::{Arc, Mutex};
+
+    fn sports(vars: &[(&str, &str)]) -> Vec<String> {
+        oddsapi_sports_from_lookup(|key| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string()))
+    }
+
+    #[test]
+    fn env_include_exclude_and_disable() {
+        assert_eq!(sports(&[]), ODDS_API_DEFAULT_SPORTS);
+        assert_eq!(
+            sports(&[("ODDS_API_SPORTS", "default, Tennis_WTA,soccer_epl"), ("ODDS_API_EXCLUDE_SPORTS", "baseball_mlb")]),
+            vec!["basketball_nba", "soccer_epl", "soccer_uefa_champs_league", "americanfootball_nfl",
+                "icehockey_nhl", "tennis_atp_french_open", "tennis_wta"],
+        );
+        assert!(sports(&[("ODDS_API_SPORTS", "soccer_epl"), ("ODDS_API_ENABLED", "0")]).is_empty());
+        assert!(sports(&[("ODDS_API_EXCLUDE_SPORTS", "default")]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn configured_sports_drive_requested_endpoints() {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&paths);
+        let url = test_http::serve(move |req| {
+            sink.lock().unwrap().push(req.path().to_string());
+            test_http::json(r#"{"arb_bets":[]}"#)
+        }).await;
+
+        let dir = std::env::temp_dir().join(format!("rustmisko_oddsapi_sports_{}", std::process::id()));
+        let monitor = PriceMonitor::new(&dir, None, Some("k".to_string()), 1.0, 60)
+            .with_oddsapi_base_url(format!("{}/", url))
+            .with_oddsapi_sports(["soccer_epl", "icehockey_nhl"]);
+
+        let (items, healthy, total) = monitor.poll_oddsapi_arb().await.unwrap();
+        assert_eq!((items, healthy, total), (0, 2, 2));
+        assert_eq!(*paths.lock().unwrap(), vec![
+            "/v1/arbitrage-bets?sport=soccer_epl&apiKey=k",
+            "/v1/arbitrage-bets?sport=icehockey_nhl&apiKey=k",
+        ]);
+
+        // Prázdný seznam = poller vypnutý, žádný request ani zdroj v heartbeat
+        let disabled = PriceMonitor::new(&dir, None, Some("k".to_string()), 1.0, 60)
+            .with_oddsapi_sports(Vec::<String>::new());
+        assert_eq!(disabled.poll_oddsapi_arb().await.unwrap(), (0, 0, 0));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,113 @@
+//! Mock HTTP server pro testy: jedno spojení = jeden request, odpověď (`Connection: close`) skládá handler.
+//! Sdílí ho testy všech crate workspace (`[dev-dependencies]`).
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Přijatý request: pořadí spojení od startu serveru a celý text (request line, hlavičky, tělo).
+pub struct Request {
+    pub index: usize,
+    pub raw:   String,
+}
+
+impl Request {
+    pub fn body(&self) -> &str {
+        self.raw.split_once("\r\n\r\n").map_or("", |(_, body)| body)
+    }
+
+    /// Cesta z request line ("/v2/odds?sportId=12").
+    pub fn path(&self) -> &str {
+        self.raw.split_whitespace().nth(1).unwrap_or("")
+    }
+
+    /// Hodnota hlavičky bez ohledu na velikost písmen v názvu.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let head = self.raw.split("\r\n\r\n").next().unwrap_or("");
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+/// HTTP/1.1 odpověď: `status` ("200 OK"), extra hlavičky ("Retry-After: 600") a tělo.
+pub fn response(status: &str, headers: &[&str], body: impl AsRef<[u8]>) -> Vec<u8> {
+    let body = body.as_ref();
+    let mut out = format!("HTTP/1.1 {}\r\n", status);
+    for header in headers {
+        out.push_str(header);
+        out.push_str("\r\n");
+    }
+    out.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", body.len()));
+    let mut out = out.into_bytes();
+    out.extend_from_slice(body);
+    out
+}
+
+pub fn html(body: impl AsRef<[u8]>) -> Vec<u8> {
+    response("200 OK", &["Content-Type: text/html"], body)
+}
+
+pub fn json(body: impl AsRef<[u8]>) -> Vec<u8> {
+    response("200 OK", &["Content-Type: application/json"], body)
+}
+
+/// Čte, dokud nedorazí hlavičky a celé tělo podle Content-Length (nebo klient spojení nezavře).
+async fn read_request(sock: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = sock.read(&mut chunk).await.unwrap_or(0);
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        let request = Request { index: 0, raw: text };
+        if let Some((_, body)) = request.raw.split_once("\r\n\r\n") {
+            let expected = request.header("content-length").and_then(|v| v.parse::<usize>().ok()).unwrap_or(0);
+            if body.len() >= expected {
+                return request.raw;
+            }
+        }
+        if n == 0 {
+            return request.raw;
+        }
+    }
+}
+
+/// Server na 127.0.0.1:0; vrací base URL ("http://127.0.0.1:port"). Spojení se obsluhují souběžně.
+pub async fn serve_async<F, Fut>(handler: F) -> String
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Vec<u8>> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handler = Arc::new(handler);
+    let hits = AtomicUsize::new(0);
+    tokio::spawn(async move {
+        while let Ok((mut sock, _)) = listener.accept().await {
+            let index = hits.fetch_add(1, Ordering::SeqCst);
+            let handler = Arc::clone(&handler);
+            tokio::spawn(async move {
+                let raw = read_request(&mut sock).await;
+                let resp = handler(Request { index, raw }).await;
+                let _ = sock.write_all(&resp).await;
+            });
+        }
+    });
+    url
+}
+
+/// Jako `serve_async` pro handler, který odpovídá hned.
+pub async fn serve<F>(handler: F) -> String
+where
+    F: Fn(&Request) -> Vec<u8> + Send + Sync + 'static,
+{
+    serve_async(move |req| {
+        let resp = handler(&req);
+        async move { resp }
+    })
+    .await
+}
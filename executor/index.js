@@ -246,6 +246,43 @@ function rollbackBetInflight(conditionId, outcomeId) {
   }
 }
 
+// IDEMPOTENCY KEY: alert_bot posílá deterministický klíč per pokus o sázku.
+// Retry se stejným klíčem (např. po client timeoutu) dostane původní odpověď
+// s idempotentReplay=true místo druhé sázky nebo 409 DEDUP.
+// Key: idempotencyKey, Value: { ts, response: body|null, done: Promise<body|null> }
+const idempotencyResults = new Map();
+
+function isPlacedBetResponse(statusCode, body) {
+  if (statusCode >= 300 || !body || body.error) return false;
+  return !["Rejected", "Failed", "Cancelled"].includes(body.state);
+}
+
+function beginIdempotentBet(key) {
+  let resolve;
+  const entry = { ts: Date.now(), response: null, done: new Promise((r) => { resolve = r; }) };
+  idempotencyResults.set(key, entry);
+  return (statusCode, body) => {
+    if (isPlacedBetResponse(statusCode, body)) {
+      entry.response = body;
+      resolve(body);
+    } else {
+      // Neúspěch nebo nejistý výsledek — klíč neblokuje, další vrstvy dedupu rozhodnou
+      idempotencyResults.delete(key);
+      resolve(null);
+    }
+  };
+}
+
+async function idempotentReplay(key) {
+  const entry = idempotencyResults.get(key);
+  if (!entry) return null;
+  if (Date.now() - entry.ts > FINGERPRINT_TTL_MS) {
+    idempotencyResults.delete(key);
+    return null;
+  }
+  return entry.response ?? (await entry.done);
+}
+
 // Periodic cleanup of expired fingerprints (every 60s)
 setInterval(() => {
   const now = Date.now();
@@ -254,6 +291,11 @@ setInterval(() => {
       recentBetFingerprints.delete(fp);
     }
   }
+  for (const [key, entry] of idempotencyResults) {
+    if (now - entry.ts > FINGERPRINT_TTL_MS && entry.response) {
+      idempotencyResults.delete(key);
+    }
+  }
 }, 60_000);
 
 function loadActiveBetsFromDisk() {
@@ -1027,6 +1069,22 @@ app.post("/bet", async (req, res) => {
       .json({ error: "Missing: conditionId, outcomeId, amount" });
   }
 
+  // Layer 0: Idempotency key — retry téhož pokusu vrátí původní výsledek
+  const idempotencyKey = req.body.idempotencyKey || req.get("Idempotency-Key");
+  if (idempotencyKey) {
+    const replay = await idempotentReplay(idempotencyKey);
+    if (replay) {
+      console.log(`♻️ IDEM-KEY: ${idempotencyKey} already placed (betId=${replay.betId}) — replaying response`);
+      return res.json({ ...replay, idempotentReplay: true, idempotencyKey });
+    }
+    const settle = beginIdempotentBet(idempotencyKey);
+    const sendJson = res.json.bind(res);
+    res.json = (body) => {
+      settle(res.statusCode, body);
+      return sendJson(body);
+    };
+  }
+
   // ============================================================
   // IDEMPOTENCE GUARD — 3-layer dedup (P0 fix for double-bet race)
   // Layer 1: bettedConditions Set (persisted across restarts)
@@ -16,12 +16,16 @@
 
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, mpsc};
+use logger::{send_ntfy_alert, DailyDigestEvent, EventLogger, NearMissLog, OrderReceipt, OrderRequest, Venue};
+use esports_monitor::clock::{system_clock, Clock};
+use arb_detector::{append_paper_bet, broadcast_telegram, parse_telegram_chat_ids, PaperBetLine, TelegramFormat};
+use prediction_engine::{match_state_with_series, Prediction, PredictionEngine};
 use tracing::{info, warn, error, debug};
 use tracing_subscriber::{EnvFilter, fmt};
 use std::path::Path;
@@ -59,6 +63,9 @@ const CS2_SCORE_DISTRUST_LOCK_SECS: i64 = 45 * 60;
 const CS2_SCORE_DISTRUST_DECAY_SECS: i64 = 20 * 60;
 const CS2_SCORE_DISTRUST_LOCK_THRESHOLD: u8 = 3;
 const CS2_SCORE_DISTRUST_STABLE_RELEASE_EVENTS: u8 = 2;
+/// Dead-man's switch: live zápas bez změny skóre déle než tohle → zaseklý scraper, žádné score edge.
+/// Override přes env FROZEN_SCORE_WINDOW_SECS (0 = vypnuto).
+const FROZEN_SCORE_WINDOW_SECS: i64 = 15 * 60;
 /// === AUTO-BET CONFIG ===
 const AUTO_BET_ENABLED: bool = true;
 /// Base stake per auto-bet in USD
@@ -113,6 +120,10 @@ const RETRY_CONDITION_MAX_AGE_MS: u64 = 1200;
 const REMAP_RETRY_DELAY_MS: u64 = 120;
 /// Signal TTL — reject bet if decision is older than this (seconds)
 const SIGNAL_TTL_SECS: u64 = 3;
+/// Timeout jednoho POST /bet; po timeoutu se pošle znovu se stejným idempotency key
+const EXECUTOR_BET_TIMEOUT_SECS: u64 = 15;
+/// Kolikrát zopakovat POST /bet po transport timeoutu (executor dedupuje přes idempotency key)
+const BET_TRANSPORT_RETRY_MAX: usize = 1;
 /// Persistent dedup history lookback (hours) — older entries are ignored on startup.
 /// Prevents "blocked forever" behavior on recurring match keys.
 const DEDUP_HISTORY_LOOKBACK_HOURS: i64 = 8;
@@ -148,6 +159,8 @@ const MIN_ODDS_FACTOR_BASKETBALL: f64 = 0.83;
 const AUTO_BET_MIN_MARKET_SOURCES: usize = 2;
 /// Ignore stale odds snapshots older than this threshold
 const MAX_ODDS_AGE_SECS: i64 = 20;
+/// Minimum weight of an in-window odds source in the market average (see `odds_freshness_weight`)
+const ODDS_FRESHNESS_MIN_WEIGHT: f64 = 0.2;
 /// Maximum concurrent pending bets (inflight guard)
 const MAX_CONCURRENT_PENDING: usize = 8;
 /// Relaxed 2.25→2.80: with map lead + ≥72% prob the edge is real, 2.25 was
@@ -188,6 +201,10 @@ const PORTFOLIO_REPORT_SECS: u64 = 1800;
 const WATCHDOG_TIMEOUT_SECS: u64 = 120;
 /// === CASHOUT — DISABLED (no EV/fair_value calc yet, margin leak risk) ===
 const FF_CASHOUT_ENABLED: bool = false;
+/// === HEDGE — opačná strana, když se aktivní sázce otočí live skóre (false = jen Telegram nabídka) ===
+const FF_AUTO_HEDGE: bool = false;
+/// O kolik musí score-implied prob naší strany klesnout pod implied prob v době sázky
+const HEDGE_FAIR_DROP: f64 = 0.15;
 
 // ====================================================================
 // WS STATE GATE — real-time condition state from Azuro V3 streams
@@ -505,6 +522,10 @@ async fn run_ws_gate(
 const FF_CHANCE_ROUND_PARSER: bool = true;
 /// Cross-validate HLTV vs Chance scores (mismatch → skip + resync freeze)
 const FF_CROSS_VALIDATION: bool = true;
+/// CS2 score consensus: vážená většina live zdrojů přepíše skóre primárního feedu
+const FF_SCORE_CONSENSUS: bool = true;
+/// Intra-match arb (mapN_winner vs match_winner): automaticky vsadit obě nohy (jinak jen alert)
+const FF_INTRA_ARB_AUTO_BET: bool = false;
 /// Dynamic exposure caps (per-bet, per-condition, per-match, inflight)
 const FF_EXPOSURE_CAPS: bool = true;
 /// Allow re-bet on same condition when edge grows (tier upgrade / edge jump)
@@ -519,14 +540,19 @@ const FF_PER_SPORT_CAP: bool = true;
 const FF_RESYNC_FREEZE: bool = true;
 /// Phase 1: CS2 match_winner from round scores (maps 1-0 / 1-1 + round lead)
 const FF_CS2_MATCH_FROM_ROUNDS: bool = true;
-/// Phase 1: Football anomaly DISABLED — production data: 40% WR, PnL -$4.54 (n=10)
-const FF_FOOTBALL_ANOMALY_GOALDIFF2: bool = false;
 /// Phase 2: Tennis game-level model (paper trade only until 50+ bets)
 const FF_TENNIS_GAME_MODEL: bool = false;
 /// Phase 4: Basketball live bets (OFF until live_score kalibrace)
 const FF_BASKETBALL_LIVE: bool = false;
 /// Regime-based stake sizing (Kelly/3 for StrongEdge, $0.50 for FalseFavorite)
 const FF_REGIME_STAKE: bool = true;
+/// Stake škálovaný confidence sdíleného PredictionEngine (OFF dokud se neověří kalibrace)
+const FF_CONFIDENCE_STAKE: bool = false;
+/// Confidence, při které je multiplikátor 1.0 (pod ní se stake ořezává, nad ní roste)
+const CONFIDENCE_STAKE_PIVOT: f64 = 0.85;
+/// Rozsah confidence multiplikátoru — caps a trimmer se aplikují až potom
+const CONFIDENCE_STAKE_MIN_MULT: f64 = 0.50;
+const CONFIDENCE_STAKE_MAX_MULT: f64 = 1.25;
 /// Dota-2 score-edge dry-run (alert-only, no auto-bet)
 const FF_DOTA2_EDGE_DRY_RUN: bool = true;
 /// Dota-2 score-edge live rollout
@@ -535,6 +561,8 @@ const FF_DOTA2_EDGE_LIVE: bool = false;
 const FF_VALORANT_EDGE_DRY_RUN: bool = false;
 /// Valorant score-edge live rollout
 const FF_VALORANT_EDGE_LIVE: bool = false;
+/// Provozní ledger (bety, settlementy, audit eventy rozhodování)
+const LEDGER_PATH: &str = "data/ledger.jsonl";
 /// StrongEdge Kelly/3 stake floor
 const STRONG_EDGE_STAKE_MIN: f64 = 1.50;
 /// StrongEdge Kelly/3 stake cap
@@ -691,31 +719,64 @@ fn parse_football_minute_static(detailed: &str) -> Option<i64> {
     None
 }
 
-fn min_odds_factor_for_match(match_key: &str) -> f64 {
-    let sport = match_key.split("::").next().unwrap_or("");
-    match sport {
-        "tennis" => MIN_ODDS_FACTOR_TENNIS,
-        "basketball" => MIN_ODDS_FACTOR_BASKETBALL,
-        _ => MIN_ODDS_FACTOR_DEFAULT,
+/// Slippage guard (minOdds = displayed_odds * factor) per sport z prefixu match_key.
+#[derive(Debug, Clone)]
+struct SlippageGuard {
+    default_factor: f64,
+    /// sport prefix (lowercase, "cs2", "football", ...) → factor
+    sport_factors: HashMap<String, f64>,
+}
+
+impl Default for SlippageGuard {
+    fn default() -> Self {
+        Self {
+            default_factor: MIN_ODDS_FACTOR_DEFAULT,
+            sport_factors: HashMap::from([
+                ("tennis".to_string(), MIN_ODDS_FACTOR_TENNIS),
+                ("basketball".to_string(), MIN_ODDS_FACTOR_BASKETBALL),
+            ]),
+        }
     }
 }
 
-fn min_odds_factor_with_fallback(match_key: &str, fallback_applied: bool) -> f64 {
-    let base = min_odds_factor_for_match(match_key);
-    if fallback_applied {
-        (base - MIN_ODDS_FALLBACK_STEP).max(0.80)
-    } else {
-        base
+impl SlippageGuard {
+    /// MIN_ODDS_FACTORS="cs2=0.80,football=0.86,default=0.85" — přepisuje/doplňuje defaulty,
+    /// factor mimo (0, 1] se ignoruje.
+    fn from_env() -> Self {
+        let mut guard = Self::default();
+        for pair in std::env::var("MIN_ODDS_FACTORS").unwrap_or_default().split(',') {
+            let Some((sport, factor)) = pair.split_once('=') else { continue };
+            let Some(factor) = factor.trim().parse::<f64>().ok().filter(|f| *f > 0.0 && *f <= 1.0) else { continue };
+            match sport.trim().to_lowercase().as_str() {
+                "" => {}
+                "default" => guard.default_factor = factor,
+                sport => { guard.sport_factors.insert(sport.to_string(), factor); }
+            }
+        }
+        guard
+    }
+
+    fn factor_for_match(&self, match_key: &str) -> f64 {
+        let sport = match_key.split("::").next().unwrap_or("");
+        self.sport_factors.get(sport).copied().unwrap_or(self.default_factor)
+    }
+
+    fn factor_with_fallback(&self, match_key: &str, fallback_applied: bool) -> f64 {
+        let base = self.factor_for_match(match_key);
+        if fallback_applied {
+            // Floor 0.80, ale fallback nikdy nepovolí víc než bez něj (base pod floor zůstává)
+            (base - MIN_ODDS_FALLBACK_STEP).max(0.80).min(base)
+        } else {
+            base
+        }
     }
 }
 
-/// Compute on-chain minOdds (in 1e12 format) with a floor of 1.01.
+/// Compute minOdds as decimal odds with a floor of 1.01 (`OrderRequest` converts to the on-chain 1e12 format).
 /// Without this floor, low Azuro odds (e.g. 1.18 * 0.83 = 0.98) produce sub-1.0
 /// minOdds which provides ZERO slippage protection since odds can never be < 1.0.
-fn compute_min_odds_raw(azuro_odds: f64, factor: f64) -> (u64, f64) {
-    let display = (azuro_odds * factor).max(1.01);
-    let raw = (display * 1e12) as u64;
-    (raw, display)
+fn min_odds(azuro_odds: f64, factor: f64) -> f64 {
+    (azuro_odds * factor).max(1.01)
 }
 
 /// Additional sport-specific safety guard for auto-bet.
@@ -736,6 +797,80 @@ fn sport_auto_bet_guard(sport: &str, opp: &Opportunity) -> bool {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LeagueDecision {
+    AutoBet,
+    Denied,
+    /// Liga není na allowlistu (nebo ji feed neposílá) → jen alert
+    AlertOnly,
+}
+
+/// Ligový allow/deny filtr auto-betu, položky "sport:liga" (např. "football:j3 league").
+/// Liga se porovnává jako podřetězec po normalizaci (malá alfanumerika).
+/// Neznámá liga (mimo allowlist, nebo chybí) je jen alert; "sport:*" povolí celý sport. Deny platí vždy.
+#[derive(Debug, Clone, Default)]
+struct LeagueFilter {
+    allow: HashMap<String, Vec<String>>,
+    deny: HashMap<String, Vec<String>>,
+}
+
+impl LeagueFilter {
+    /// AUTO_BET_LEAGUE_ALLOW="football:premier league,cs2:*" / AUTO_BET_LEAGUE_DENY="football:npl"
+    fn from_env() -> Self {
+        let parse = |var: &str| -> HashMap<String, Vec<String>> {
+            let mut out: HashMap<String, Vec<String>> = HashMap::new();
+            for entry in std::env::var(var).unwrap_or_default().split(',') {
+                if let Some((sport, league)) = entry.split_once(':') {
+                    let league = if league.trim() == LEAGUE_ANY { LEAGUE_ANY.to_string() } else { normalize_league(league) };
+                    if !league.is_empty() {
+                        out.entry(sport.trim().to_lowercase()).or_default().push(league);
+                    }
+                }
+            }
+            out
+        };
+        Self { allow: parse("AUTO_BET_LEAGUE_ALLOW"), deny: parse("AUTO_BET_LEAGUE_DENY") }
+    }
+
+    fn decide(&self, sport: &str, league: Option<&str>) -> LeagueDecision {
+        let league = league.map(normalize_league).filter(|l| !l.is_empty());
+        let listed = |lists: &HashMap<String, Vec<String>>| {
+            league.as_deref().is_some_and(|l| {
+                lists.get(sport).is_some_and(|patterns| patterns.iter().any(|p| l.contains(p.as_str())))
+            })
+        };
+        let any_allowed = self.allow.get(sport).is_some_and(|patterns| patterns.iter().any(|p| p == LEAGUE_ANY));
+        if listed(&self.deny) {
+            LeagueDecision::Denied
+        } else if any_allowed || listed(&self.allow) {
+            LeagueDecision::AutoBet
+        } else {
+            LeagueDecision::AlertOnly
+        }
+    }
+}
+
+/// Allowlist položka "sport:*" — auto-bet pro libovolnou (i chybějící) ligu sportu.
+const LEAGUE_ANY: &str = "*";
+
+fn normalize_league(raw: &str) -> String {
+    raw.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Liga live zápasu z /state (match_key bez ::mapN_winner suffixu).
+fn live_league<'a>(state: &'a StateResponse, match_key: &str) -> Option<&'a str> {
+    let base = strip_map_winner_suffix(match_key);
+    state.live.iter()
+        .find(|l| l.match_key == base)
+        .and_then(|l| l.payload.league.as_deref())
+}
+
 /// Prematch odds anomaly auto-bet: RE-ENABLED with SCORE-CONFIRMED gate
 /// Only bets when live score supports the anomaly direction (leading team = value side)
 const AUTO_BET_ODDS_ANOMALY_ENABLED: bool = true;
@@ -950,6 +1085,62 @@ fn stake_below_executable_floor(raw_stake: f64, bankroll: f64) -> bool {
     floor > 0.0 && raw_stake > 0.0 && raw_stake < floor
 }
 
+/// Rekonstrukce start-of-day bankrollu po mid-day restartu bez `sod_bankroll` v daily_pnl.json:
+/// SOD ≈ aktuální balance + dnes vsazeno − dnes vráceno (claimy). Nikdy ne záporné.
+fn reconstruct_sod_bankroll(current_balance: f64, daily_wagered: f64, daily_returned: f64) -> f64 {
+    (current_balance + daily_wagered - daily_returned).max(0.0)
+}
+
+/// PAPER_BANKROLL (USD): pevný virtuální bankroll pro sizing experimenty nad reálnými feedy.
+fn paper_bankroll_from_env() -> Option<f64> {
+    std::env::var("PAPER_BANKROLL")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|b| b.is_finite() && *b > 0.0)
+}
+
+/// Paper bankroll platí jen bez živého executoru (offline nebo /health status "dry-run");
+/// s live walletem se sizing vždy řídí skutečným balance.
+fn effective_paper_bankroll(paper: Option<f64>, executor: Option<&ExecutorHealthResponse>) -> Option<f64> {
+    let executor_live = executor.is_some_and(|h| h.status.as_deref() != Some("dry-run"));
+    paper.filter(|_| !executor_live)
+}
+
+/// PAPER_BANKROLL za běhu: `effective_paper_bankroll` se přepočítá při každé odpovědi executoru
+/// (startup /health, balance refresh). Jakmile odpoví live executor s balance, paper režim končí natrvalo —
+/// pozdější výpadek executoru už zpět na virtuální bankroll nepřepne.
+struct PaperMode {
+    env: Option<f64>,
+    bankroll: Option<f64>,
+    /// SOD z daily_pnl.json přepsaný paper hodnotou; při přechodu na live se vrací
+    real_sod: Option<f64>,
+}
+
+impl PaperMode {
+    fn new(env: Option<f64>) -> Self {
+        Self { env, bankroll: effective_paper_bankroll(env, None), real_sod: None }
+    }
+
+    /// Přepočet podle /health. `Some((bankroll, sod))` = tahle odpověď paper režim ukončila:
+    /// sizing jede z live balance, SOD je původní z daily_pnl.json (bez něj live balance).
+    /// Live executor bez čitelného balance paper režim nechá — virtuální částka je bezpečnější než default.
+    fn observe(&mut self, executor: Option<&ExecutorHealthResponse>) -> Option<(f64, f64)> {
+        self.bankroll?;
+        if effective_paper_bankroll(self.env, executor).is_some() {
+            return None;
+        }
+        let balance = executor?.balance.as_deref()?.trim().parse::<f64>().ok().filter(|b| *b > 0.0)?;
+        self.bankroll = None;
+        Some((balance, self.real_sod.unwrap_or(balance)))
+    }
+}
+
+/// GET {executor}/health; None = offline nebo nevalidní JSON.
+async fn fetch_executor_health(client: &reqwest::Client, executor_url: &str) -> Option<ExecutorHealthResponse> {
+    let resp = client.get(format!("{}/health", executor_url)).send().await.ok()?;
+    resp.json::<ExecutorHealthResponse>().await.ok()
+}
+
 /// Stake Trimmer: min(calculated_stake, per_bet, cond_left, match_left, daily_left, inflight_left, sport_left)
 /// cross_val_multiplier: 1.25 if cross-validated, 1.0 neutral — applied to STAKE, not edge threshold
 /// Returns the final safe stake, or 0.0 if bet should be skipped
@@ -1077,27 +1268,218 @@ fn trim_stake(
     if final_stake < min_executable_stake { 0.0 } else { final_stake }
 }
 
-/// Cross-validation result for HLTV vs Chance score comparison.
+/// Výchozí počet desetinných míst stake (centy). Override přes env STAKE_ROUND_DECIMALS.
+const STAKE_ROUND_DECIMALS: u32 = 2;
+
+/// Zaokrouhlení stake + per-chain minimální stake, aplikuje se až po `trim_stake`.
+#[derive(Debug, Clone)]
+struct StakePolicy {
+    decimals: u32,
+    default_floor: f64,
+    /// chain (lowercase) → min stake USD
+    chain_floors: HashMap<String, f64>,
+}
+
+impl Default for StakePolicy {
+    fn default() -> Self {
+        Self {
+            decimals: STAKE_ROUND_DECIMALS,
+            default_floor: MIN_EXECUTABLE_STAKE_USD,
+            chain_floors: HashMap::new(),
+        }
+    }
+}
+
+impl StakePolicy {
+    /// STAKE_ROUND_DECIMALS=2, CHAIN_MIN_STAKE_USD="gnosis=1.0,polygon=0.5" (neznámý chain → $0.50).
+    fn from_env() -> Self {
+        let decimals = std::env::var("STAKE_ROUND_DECIMALS").ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|d| *d <= 6)
+            .unwrap_or(STAKE_ROUND_DECIMALS);
+        let chain_floors = std::env::var("CHAIN_MIN_STAKE_USD")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| {
+                let (chain, floor) = pair.split_once('=')?;
+                let floor = floor.trim().parse::<f64>().ok().filter(|f| *f >= 0.0)?;
+                Some((chain.trim().to_lowercase(), floor))
+            })
+            .collect();
+        Self { decimals, chain_floors, ..Self::default() }
+    }
+
+    fn min_stake(&self, chain: Option<&str>) -> f64 {
+        chain
+            .and_then(|c| self.chain_floors.get(&c.to_lowercase()))
+            .copied()
+            .unwrap_or(self.default_floor)
+    }
+
+    /// Zaokrouhlí dolů (nikdy nepřekročí cap z trimmeru); pod floorem chainu → 0.0 (skip).
+    fn finalize(&self, trimmed: f64, chain: Option<&str>) -> f64 {
+        if trimmed <= 0.0 {
+            return 0.0;
+        }
+        let factor = 10f64.powi(self.decimals as i32);
+        let rounded = (trimmed * factor + 1e-9).floor() / factor;
+        if rounded < self.min_stake(chain) { 0.0 } else { rounded }
+    }
+}
+
+/// Kam poslat DAILY_DIGEST při přechodu dne (JSONL event se zapisuje vždy).
+#[derive(Debug, Clone, Copy, Default)]
+struct DigestPush {
+    telegram: bool,
+    ntfy: bool,
+}
+
+impl DigestPush {
+    /// DAILY_DIGEST_PUSH="telegram,ntfy" (nenastaveno → jen data/daily_digest/).
+    fn from_env() -> Self {
+        let raw = std::env::var("DAILY_DIGEST_PUSH").unwrap_or_default().to_lowercase();
+        let targets: Vec<&str> = raw.split(',').map(str::trim).collect();
+        Self { telegram: targets.contains(&"telegram"), ntfy: targets.contains(&"ntfy") }
+    }
+}
+
+/// Azuro price impact: implied pravděpodobnost outcome roste lineárně s vyplacenou exposure
+/// vůči likviditě poolu, p(E) = p0 · (1 + E / L). Vrací průměrný realizovaný kurz pro `stake`
+/// (řešení x · (1 + stake·x / 2L) = odds). Bez likvidity / nulový stake → zobrazený kurz.
+fn azuro_price_impact(odds: f64, stake: f64, liquidity_usd: f64) -> f64 {
+    if odds <= 1.0 || stake <= 0.0 || liquidity_usd <= 0.0 {
+        return odds;
+    }
+    let k = stake / liquidity_usd;
+    ((1.0 + 2.0 * k * odds).sqrt() - 1.0) / k
+}
+
+/// Score edge (p.b.) po price impactu: score_implied − 100 / realizovaný kurz.
+fn post_impact_edge_pct(score_implied_pct: f64, odds: f64, stake: f64, liquidity_usd: f64) -> f64 {
+    score_implied_pct - 100.0 / azuro_price_impact(odds, stake, liquidity_usd)
+}
+
+/// Likvidita Azuro condition z /state (None = feed ji neposílá → bez simulace).
+fn condition_liquidity_usd(state: &StateResponse, condition_id: &str, venues: &ExecutionVenues) -> Option<f64> {
+    state.odds.iter()
+        .filter(|i| venues.is_venue(&i.payload.bookmaker))
+        .find(|i| i.payload.condition_id.as_deref() == Some(condition_id))
+        .and_then(|i| i.payload.liquidity_usd)
+        .filter(|l| *l > 0.0)
+}
+
+/// Live skóre jednoho zdroje pro konsensus (HLTV, Dust2, Chance, Tipsport, …)
+#[derive(Debug, Clone, PartialEq)]
+struct SourceScore {
+    source: String,
+    score: (i32, i32),
+    /// Důvěra ve zdroj (0..1) — viz score_source_weight
+    weight: f64,
+}
+
+/// Výsledek konsensu: vážená většina napříč zdroji.
+#[derive(Debug, Clone, PartialEq)]
+struct ConsensusScore {
+    score: (i32, i32),
+    /// Podíl váhy zdrojů, které souhlasí s `score` (0..1)
+    agreement: f64,
+    sources: usize,
+    agreeing: usize,
+    /// Aspoň jeden zdroj hlásí jiné skóre
+    conflict: bool,
+}
+
+impl ConsensusScore {
+    /// Vážená nadpoloviční většina (a aspoň 2 zdroje) — smí přepsat skóre primárního feedu
+    fn has_majority(&self) -> bool {
+        self.agreeing >= 2 && self.agreement > 0.5
+    }
+
+    /// Stake multiplikátor (zobecněná cross-validace HLTV vs Chance na N zdrojů):
+    ///   1.25 všechny zdroje (≥2) souhlasí, 1.0 jediný zdroj nebo jasná většina,
+    ///   0.5 konflikt bez většiny (hedged, NE hard skip)
+    /// IMPORTANT: multiplier is for STAKE/PRIORITY only, NOT for edge threshold!
+    fn stake_multiplier(&self) -> f64 {
+        if self.sources < 2 {
+            1.0
+        } else if !self.conflict {
+            1.25
+        } else if self.has_majority() {
+            1.0
+        } else {
+            0.5
+        }
+    }
+}
+
+/// Confidence-weighted sloučení live skóre jednoho zápasu z více zdrojů.
+#[derive(Debug, Clone, Default)]
+struct ScoreConsensus {
+    scores: Vec<SourceScore>,
+}
+
+impl ScoreConsensus {
+    /// Přidá zdroj; stejný zdroj podruhé přepíše předchozí hodnotu.
+    fn add(&mut self, source: &str, score: (i32, i32), weight: f64) {
+        self.scores.retain(|s| s.source != source);
+        self.scores.push(SourceScore { source: source.to_string(), score, weight: weight.max(0.0) });
+    }
+
+    fn resolve(&self) -> Option<ConsensusScore> {
+        if self.scores.is_empty() {
+            return None;
+        }
+        // Skupiny podle skóre: (skóre, součet váhy, počet zdrojů) v pořadí prvního výskytu
+        let mut groups: Vec<((i32, i32), f64, usize)> = Vec::new();
+        for s in &self.scores {
+            match groups.iter_mut().find(|g| g.0 == s.score) {
+                Some(g) => { g.1 += s.weight; g.2 += 1; }
+                None => groups.push((s.score, s.weight, 1)),
+            }
+        }
+        let total_weight: f64 = groups.iter().map(|g| g.1).sum();
+        // Nejvyšší váha, při shodě víc zdrojů, při shodě první zdroj (= priorita feedu)
+        let best = groups.iter().fold(None::<&((i32, i32), f64, usize)>, |best, g| match best {
+            Some(b) if b.1 > g.1 + 1e-9 || ((b.1 - g.1).abs() <= 1e-9 && b.2 >= g.2) => Some(b),
+            _ => Some(g),
+        })?;
+        Some(ConsensusScore {
+            score: best.0,
+            agreement: if total_weight > 0.0 { best.1 / total_weight } else { best.2 as f64 / self.scores.len() as f64 },
+            sources: self.scores.len(),
+            agreeing: best.2,
+            conflict: groups.len() > 1,
+        })
+    }
+}
+
+/// Důvěra ve zdroj live skóre (shodné pořadí jako live_item_priority).
+fn score_source_weight(source: &str) -> f64 {
+    match source {
+        "dust2" => 1.0,
+        s if s.starts_with("hltv") => 1.0,
+        "chance" | "chance_detailed" => 0.9,
+        "fortuna" => 0.8,
+        "tipsport" => 0.7,
+        _ => 0.6,
+    }
+}
+
+/// Cross-validation přes N zdrojů (dříve jen HLTV vs Chance).
 /// Returns (skip: bool, stake_multiplier: f64)
 ///   skip=false always — NEVER hard-skip, use reduced stake instead
-///   stake_multiplier: 1.25 (agree), 0.5 (mismatch=hedged), 1.0 (single source)
-/// IMPORTANT: multiplier is for STAKE/PRIORITY only, NOT for edge threshold!
-fn cross_validation_check(
-    hltv_score: Option<(i32, i32)>,
-    chance_score: Option<(i32, i32)>,
-) -> (bool, f64) {
-    match (hltv_score, chance_score) {
-        (Some(h), Some(c)) => {
-            if h.0 == c.0 && h.1 == c.1 {
-                (false, 1.25)  // Both agree → higher stake/priority
-            } else {
-                // Mismatch → HEDGED bet at 0.5x stake (not hard skip)
+fn cross_validation_check(consensus: &ScoreConsensus) -> (bool, f64) {
+    match consensus.resolve() {
+        Some(c) => {
+            if c.conflict {
                 // Scraper fluke is common; full skip leaves money on table
-                info!("CROSS-VAL mismatch: HLTV={:?} vs Chance={:?} → 0.5x stake (was: HARD SKIP)", h, c);
-                (false, 0.5)
+                info!("CROSS-VAL conflict: {:?} → consensus {:?} ({}/{} sources, agreement {:.2}) → stake×{:.2}",
+                    consensus.scores.iter().map(|s| (s.source.as_str(), s.score)).collect::<Vec<_>>(),
+                    c.score, c.agreeing, c.sources, c.agreement, c.stake_multiplier());
             }
+            (false, c.stake_multiplier())
         }
-        _ => (false, 1.0),  // Only one source → neutral
+        None => (false, 1.0),
     }
 }
 
@@ -1115,29 +1497,29 @@ struct ResyncState {
 }
 
 impl ResyncState {
-    fn new() -> Self {
+    fn new(clock: &dyn Clock) -> Self {
         Self {
-            frozen_at: Utc::now(),
+            frozen_at: clock.now_utc(),
             consecutive_agreements: 0,
         }
     }
 
     /// Check if match is still frozen (needs 60s + 2 consecutive agreements)
-    fn is_frozen(&self) -> bool {
-        let elapsed = (Utc::now() - self.frozen_at).num_seconds();
+    fn is_frozen(&self, clock: &dyn Clock) -> bool {
+        let elapsed = (clock.now_utc() - self.frozen_at).num_seconds();
         elapsed < 60 || self.consecutive_agreements < 2
     }
 
     /// Record an agreement; returns true if resync complete (unfrozen)
-    fn record_agreement(&mut self) -> bool {
+    fn record_agreement(&mut self, clock: &dyn Clock) -> bool {
         self.consecutive_agreements += 1;
-        let elapsed = (Utc::now() - self.frozen_at).num_seconds();
+        let elapsed = (clock.now_utc() - self.frozen_at).num_seconds();
         elapsed >= 60 && self.consecutive_agreements >= 2
     }
 
     /// Reset on new mismatch
-    fn record_mismatch(&mut self) {
-        self.frozen_at = Utc::now();
+    fn record_mismatch(&mut self, clock: &dyn Clock) {
+        self.frozen_at = clock.now_utc();
         self.consecutive_agreements = 0;
     }
 }
@@ -1255,12 +1637,12 @@ struct ReBetState {
 }
 
 impl ReBetState {
-    fn new(tier: &str, edge_pct: f64, stake: f64) -> Self {
+    fn new(tier: &str, edge_pct: f64, stake: f64, clock: &dyn Clock) -> Self {
         Self {
             bet_count: 1,
             highest_tier: tier.to_string(),
             last_edge_pct: edge_pct,
-            last_bet_at: Utc::now(),
+            last_bet_at: clock.now_utc(),
             total_wagered: stake,
         }
     }
@@ -1269,7 +1651,14 @@ impl ReBetState {
 /// Check if re-bet is allowed on this condition
 /// Returns true if: tier improved OR edge jumped ≥8%, cooldown ≥30s, count < 3,
 /// AND new edge_raw (after slippage) > last edge (not just "paper" edge)
-fn rebet_allowed(state: &ReBetState, new_tier: &str, new_edge_raw: f64, cond_cap_left: f64, match_cap_left: f64) -> bool {
+fn rebet_allowed(
+    state: &ReBetState,
+    clock: &dyn Clock,
+    new_tier: &str,
+    new_edge_raw: f64,
+    cond_cap_left: f64,
+    match_cap_left: f64,
+) -> bool {
     let tier_value = |t: &str| -> u8 {
         match t {
             "ULTRA" => 4,
@@ -1279,7 +1668,7 @@ fn rebet_allowed(state: &ReBetState, new_tier: &str, new_edge_raw: f64, cond_cap
             _ => 0,
         }
     };
-    let elapsed = (Utc::now() - state.last_bet_at).num_seconds();
+    let elapsed = (clock.now_utc() - state.last_bet_at).num_seconds();
     let tier_improved = tier_value(new_tier) > tier_value(&state.highest_tier);
     let edge_jumped = new_edge_raw - state.last_edge_pct >= 8.0;
     // Re-bet must have higher raw edge than last time (no "paper" inflation)
@@ -1359,6 +1748,12 @@ struct LivePayload {
     status: String,
     #[serde(default)]
     detailed_score: Option<String>,
+    /// "bo1" / "bo3" / "bo5" pokud ho feed-hub zná (Fortuna z názvu ligy), jinak None
+    #[serde(default)]
+    series_format: Option<String>,
+    /// Liga/turnaj, pokud ho scraper posílá (Fortuna) — vstup pro LeagueFilter
+    #[serde(default)]
+    league: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1378,6 +1773,9 @@ struct OddsPayload {
     team2: String,
     odds_team1: f64,
     odds_team2: f64,
+    /// Three-way trh (fotbal 1X2): kurz na remízu; None = two-way moneyline
+    #[serde(default)]
+    odds_draw: Option<f64>,
     liquidity_usd: Option<f64>,
     spread_pct: Option<f64>,
     url: Option<String>,
@@ -1386,6 +1784,8 @@ struct OddsPayload {
     condition_id: Option<String>,
     outcome1_id: Option<String>,
     outcome2_id: Option<String>,
+    #[serde(default)]
+    outcome_draw_id: Option<String>,
     chain: Option<String>,
 }
 
@@ -1404,6 +1804,7 @@ struct MapWinnerOdds {
     bookmaker: String,
     chain: Option<String>,
     url: Option<String>,
+    game_id: Option<String>,
 }
 
 // Telegram getUpdates response
@@ -1608,6 +2009,35 @@ async fn tg_send_message(client: &reqwest::Client, token: &str, chat_id: i64, te
     Ok(msg_id)
 }
 
+/// Alert do dalších chatů (TELEGRAM_CHAT_ID s víc ID) na pozadí — primární send na něj nečeká.
+/// Bez persistent menu (kanály ho neberou); chyby per chat loguje `broadcast_telegram`.
+fn tg_spawn_broadcast(client: &reqwest::Client, token: &str, chat_ids: &[String], text: &str) {
+    if token.trim().is_empty() || chat_ids.is_empty() {
+        return;
+    }
+    let (client, token, chat_ids, text) = (client.clone(), token.to_string(), chat_ids.to_vec(), text.to_string());
+    tokio::spawn(async move {
+        broadcast_telegram(&client, "https://api.telegram.org", &token, &chat_ids, &text, TelegramFormat::Html).await;
+    });
+}
+
+/// Alert (ne odpověď na příkaz): primární chat + broadcast do ostatních chatů / kanálů.
+async fn tg_send_alert(client: &reqwest::Client, token: &str, chat_id: i64, broadcast_chat_ids: &[String], text: &str) -> Result<i64> {
+    tg_spawn_broadcast(client, token, broadcast_chat_ids, text);
+    tg_send_message(client, token, chat_id, text).await
+}
+
+/// TELEGRAM_CHAT_ID → (interaktivní chat = první číselné ID, ostatní chaty vč. "@kanal" pro broadcast).
+fn split_alert_chat_ids(raw: &str) -> (Option<i64>, Vec<String>) {
+    let ids = parse_telegram_chat_ids(raw);
+    let primary = ids.iter().position(|id| id.parse::<i64>().is_ok_and(|n| n != 0));
+    let broadcast = ids.iter().enumerate()
+        .filter(|(i, _)| Some(*i) != primary)
+        .map(|(_, id)| id.clone())
+        .collect();
+    (primary.map(|i| ids[i].parse().unwrap_or(0)), broadcast)
+}
+
 async fn tg_get_updates(client: &reqwest::Client, token: &str, offset: i64) -> Result<TgUpdatesResponse> {
     if token.trim().is_empty() {
         return Ok(TgUpdatesResponse { ok: false, result: vec![] });
@@ -1668,6 +2098,372 @@ async fn tg_get_me(client: &reqwest::Client, token: &str) -> Result<i64> {
 // Score Edge — HLTV score vs stale Azuro odds
 // ====================================================================
 
+/// Sport-aware score sanity check: skóre nad stropem = garbage z FlashScore DOM concatenation
+/// (např. 714-0, 19-45 jako football).
+#[derive(Debug, Clone)]
+struct ScoreCeilings {
+    /// sport prefix match_key → max skóre jednoho týmu
+    per_sport: HashMap<String, i32>,
+    /// Sport bez stropu
+    default: i32,
+}
+
+impl Default for ScoreCeilings {
+    fn default() -> Self {
+        Self {
+            per_sport: HashMap::from([
+                ("football".to_string(), 8),     // max realistic football score per team (tightened from 15)
+                ("tennis".to_string(), 7),       // max sets in a match
+                ("hockey".to_string(), 10),      // max realistic hockey score (tightened from 15 — garbage scraper scores were 12+)
+                ("basketball".to_string(), 200), // max realistic basketball score per team
+                ("cs2".to_string(), 40),         // round scores (30 + OT rounds)
+                ("dota-2".to_string(), 100),     // kill scores
+                ("mma".to_string(), 5),          // round scores
+                ("boxing".to_string(), 5),
+                ("handball".to_string(), 45),    // max realistic handball score (tightened from 50)
+                ("volleyball".to_string(), 5),   // set scores
+                ("esports".to_string(), 50),     // generic esports limit
+            ]),
+            default: 999,
+        }
+    }
+}
+
+impl ScoreCeilings {
+    /// SCORE_SANITY_MAX="football=10,hockey=12" — přepisuje/doplňuje defaulty, záporné hodnoty se ignorují.
+    fn from_env() -> Self {
+        let mut ceilings = Self::default();
+        for pair in std::env::var("SCORE_SANITY_MAX").unwrap_or_default().split(',') {
+            let Some((sport, max)) = pair.split_once('=') else { continue };
+            let Some(max) = max.trim().parse::<i32>().ok().filter(|m| *m >= 0) else { continue };
+            let sport = sport.trim().to_lowercase();
+            if !sport.is_empty() {
+                ceilings.per_sport.insert(sport, max);
+            }
+        }
+        ceilings
+    }
+
+    fn max_for(&self, sport_prefix: &str) -> i32 {
+        self.per_sport.get(sport_prefix).copied().unwrap_or(self.default)
+    }
+}
+
+/// "Too late" filtr: vedoucí strana je `margin` bodů od konce mapy / zápasu (CS2 12-3, tenisový
+/// setball na zápas) → trh se zavře dřív, než sázka projde, edge se přeskočí.
+#[derive(Debug, Clone)]
+struct TooLateGuard {
+    /// sport prefix match_key → margin (roundy / gamy do rozhodnutí); chybějící / 0 = bez filtru
+    margins: HashMap<String, i32>,
+}
+
+impl Default for TooLateGuard {
+    fn default() -> Self {
+        Self {
+            margins: HashMap::from([
+                ("cs2".to_string(), 1),      // roundy do konce mapy (13, OT 16/19/...)
+                ("valorant".to_string(), 1), // Bo1 roundy (13, OT win-by-2)
+                ("tennis".to_string(), 1),   // gamy do konce setu, který rozhodne zápas
+            ]),
+        }
+    }
+}
+
+impl TooLateGuard {
+    /// TOO_LATE_MARGIN="cs2=2,tennis=0" — přepisuje/doplňuje defaulty, záporné hodnoty se ignorují.
+    fn from_env() -> Self {
+        let mut guard = Self::default();
+        for pair in std::env::var("TOO_LATE_MARGIN").unwrap_or_default().split(',') {
+            let Some((sport, margin)) = pair.split_once('=') else { continue };
+            let Some(margin) = margin.trim().parse::<i32>().ok().filter(|m| *m >= 0) else { continue };
+            let sport = sport.trim().to_lowercase();
+            if !sport.is_empty() {
+                guard.margins.insert(sport, margin);
+            }
+        }
+        guard
+    }
+
+    fn margin_for(&self, sport: &str) -> i32 {
+        self.margins.get(sport).copied().unwrap_or(0)
+    }
+
+    /// Some(zbývá) = vedoucí strana potřebuje k rozhodnutí nejvýš `margin` bodů.
+    fn too_late(&self, sport: &str, points_to_resolve: Option<i32>) -> Option<i32> {
+        let margin = self.margin_for(sport);
+        points_to_resolve.filter(|left| margin > 0 && *left <= margin)
+    }
+}
+
+/// Kolik roundů chybí vedoucímu týmu do konce CS2 mapy (MR12 do 13, MR3 OT do 16, 19, ...).
+fn cs2_rounds_to_map_win(leading: i32, losing: i32) -> i32 {
+    let target = if losing < 12 { 13 } else { 16 + 3 * ((losing - 12) / 3) };
+    target - leading
+}
+
+/// Valorant: do 13, v OT od 12:12 win-by-2.
+fn valorant_rounds_to_map_win(leading: i32, losing: i32) -> i32 {
+    let target = if losing < 12 { 13 } else { (losing + 2).max(leading + 1) };
+    target - leading
+}
+
+/// Gamy rozehraného setu z detailed_score ("2.set - 6:4, 5:3 (15:30*)" → (5, 3)), pořadí team1:team2.
+fn parse_tennis_current_set_games(detailed: &str) -> Option<(i32, i32)> {
+    let after_set = &detailed[detailed.find("set")?..];
+    let games_part = &after_set[after_set.find('-')? + 1..];
+    let before_paren = games_part.split('(').next().unwrap_or(games_part);
+    let (g1, g2) = before_paren.rsplit(',').next()?.trim().split_once(':')?;
+    Some((g1.trim().parse().ok()?, g2.trim().parse().ok()?))
+}
+
+/// Gamy, které vedoucímu hráči chybí do zisku setu rozhodujícího zápas; None = set zápas nerozhodne.
+fn tennis_games_to_match_win(leading_side: u8, leading_sets: i32, best_of: u8, detailed: Option<&str>) -> Option<i32> {
+    if leading_sets != (best_of / 2) as i32 {
+        return None;
+    }
+    let (g1, g2) = parse_tennis_current_set_games(detailed?)?;
+    let (lead, other) = if leading_side == 1 { (g1, g2) } else { (g2, g1) };
+    // 6 gamů, od 5:5 do 7 (6:6 = tiebreak, rozhoduje jeden "game")
+    let target = if other >= 5 { 7 } else { 6 };
+    Some((target - lead).max(1))
+}
+
+/// Per-sport kill switch: když score sanity check za krátké okno zamítá skóre příliš mnoha zápasů
+/// (typicky změněný markup scraperu), auto-bety sportu se vypnou a zůstanou vypnuté až do `/enable <sport>`.
+/// Vypnuté sporty se ukládají přes `StateStore`, restart je nezapne.
+#[derive(Debug, Clone)]
+struct SportKillSwitch {
+    /// Délka klouzavého okna pro reject rate
+    window_secs: i64,
+    /// Podíl sanity rejectů v okně, od kterého se sport vypne
+    max_reject_ratio: f64,
+    /// Minimum vzorků v okně — pár garbage skóre po startu sport nevypne
+    min_samples: usize,
+    /// sport → match_key → (poslední vyhodnocení, zamítnuto); zápas je jeden vzorek bez ohledu na počet pollů
+    samples: HashMap<String, HashMap<String, (chrono::DateTime<Utc>, bool)>>,
+    /// sport → důvod vypnutí
+    disabled: HashMap<String, String>,
+    /// Nově vypnuté sporty (sport, důvod), které ještě neodešly do Telegramu
+    pending_notices: Vec<(String, String)>,
+}
+
+impl Default for SportKillSwitch {
+    fn default() -> Self {
+        Self {
+            window_secs: 600,
+            max_reject_ratio: 0.5,
+            min_samples: 6,
+            samples: HashMap::new(),
+            disabled: HashMap::new(),
+            pending_notices: Vec::new(),
+        }
+    }
+}
+
+impl SportKillSwitch {
+    /// SPORT_KILL_WINDOW_SECS / SPORT_KILL_REJECT_RATIO / SPORT_KILL_MIN_SAMPLES;
+    /// AUTO_BET_DISABLED_SPORTS="football,hockey" = sporty vypnuté při prvním startu bez uloženého stavu
+    /// (chybějící = football, prázdné = žádný; zapnout jde jen přes `/enable`).
+    fn from_env() -> Self {
+        let mut switch = Self::default();
+        if let Some(secs) = std::env::var("SPORT_KILL_WINDOW_SECS").ok().and_then(|v| v.trim().parse::<i64>().ok()).filter(|v| *v > 0) {
+            switch.window_secs = secs;
+        }
+        if let Some(ratio) = std::env::var("SPORT_KILL_REJECT_RATIO").ok().and_then(|v| v.trim().parse::<f64>().ok()).filter(|v| *v > 0.0 && *v <= 1.0) {
+            switch.max_reject_ratio = ratio;
+        }
+        if let Some(min) = std::env::var("SPORT_KILL_MIN_SAMPLES").ok().and_then(|v| v.trim().parse::<usize>().ok()).filter(|v| *v > 0) {
+            switch.min_samples = min;
+        }
+        for sport in std::env::var("AUTO_BET_DISABLED_SPORTS").unwrap_or_else(|_| "football".to_string()).split(',') {
+            let sport = sport.trim().to_lowercase();
+            if !sport.is_empty() {
+                switch.disabled.insert(sport, "AUTO_BET_DISABLED_SPORTS".to_string());
+            }
+        }
+        switch
+    }
+
+    /// Zaznamená výsledek sanity checku zápasu (opakovaný poll přepíše jeho vzorek);
+    /// při překročení reject rate sport vypne a zařadí notifikaci.
+    fn record(&mut self, sport: &str, match_key: &str, rejected: bool, now: chrono::DateTime<Utc>) {
+        if self.disabled.contains_key(sport) {
+            return;
+        }
+        let window = self.samples.entry(sport.to_string()).or_default();
+        window.insert(match_key.to_string(), (now, rejected));
+        let cutoff = now - chrono::Duration::seconds(self.window_secs);
+        window.retain(|_, (ts, _)| *ts >= cutoff);
+        let rejects = window.values().filter(|(_, r)| *r).count();
+        let total = window.len();
+        if total < self.min_samples || (rejects as f64 / total as f64) < self.max_reject_ratio {
+            return;
+        }
+        let reason = format!("sanity reject {}/{} zápasů za {}s", rejects, total, self.window_secs);
+        warn!("🛑 SPORT KILL SWITCH: {} auto-bety vypnuty ({})", sport, reason);
+        self.samples.remove(sport);
+        self.disabled.insert(sport.to_string(), reason.clone());
+        self.pending_notices.push((sport.to_string(), reason));
+    }
+
+    fn is_disabled(&self, sport: &str) -> bool {
+        self.disabled.contains_key(sport)
+    }
+
+    /// Ruční potvrzení (`/enable <sport>`); staré vzorky se zahodí, aby sport hned znovu nespadl.
+    fn enable(&mut self, sport: &str) -> bool {
+        self.samples.remove(sport);
+        self.disabled.remove(sport).is_some()
+    }
+
+    fn take_notices(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.pending_notices)
+    }
+
+    /// Vypnuté sporty (sport, důvod) seřazené podle sportu — pro `StateStore`.
+    fn disabled_list(&self) -> Vec<(String, String)> {
+        let mut list: Vec<(String, String)> = self.disabled.iter().map(|(s, r)| (s.clone(), r.clone())).collect();
+        list.sort();
+        list
+    }
+
+    /// Uložený stav po restartu nahradí výchozí vypnuté sporty z env.
+    fn restore(&mut self, saved: Vec<(String, String)>) {
+        self.disabled = saved.into_iter().collect();
+    }
+}
+
+/// Hranice "sázkového dne" pro denní limity, bet count a digest.
+/// Default = půlnoc UTC; `BETTING_DAY_TZ` + `BETTING_DAY_CUTOFF_HOUR` ji posune do lokálního času
+/// (např. Europe/Prague + 6 → den končí v 06:00 pražského času, DST se řeší přes tz databázi).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BettingDay {
+    tz: chrono_tz::Tz,
+    /// Lokální hodina (0–23), ve které začíná nový sázkový den
+    cutoff_hour: u32,
+}
+
+impl Default for BettingDay {
+    fn default() -> Self {
+        Self { tz: chrono_tz::UTC, cutoff_hour: 0 }
+    }
+}
+
+impl BettingDay {
+    /// Neplatná timezone / hodina → UTC / 0 s varováním (radši známý default než pád při startu).
+    fn parse(tz: Option<&str>, cutoff_hour: Option<&str>) -> Self {
+        let mut day = Self::default();
+        if let Some(name) = tz.map(str::trim).filter(|v| !v.is_empty()) {
+            match name.parse::<chrono_tz::Tz>() {
+                Ok(tz) => day.tz = tz,
+                Err(_) => warn!("⚠️ BETTING_DAY_TZ='{}' není platná IANA timezone — používám UTC", name),
+            }
+        }
+        if let Some(raw) = cutoff_hour.map(str::trim).filter(|v| !v.is_empty()) {
+            match raw.parse::<u32>() {
+                Ok(hour) if hour < 24 => day.cutoff_hour = hour,
+                _ => warn!("⚠️ BETTING_DAY_CUTOFF_HOUR='{}' mimo 0–23 — používám 0", raw),
+            }
+        }
+        day
+    }
+
+    fn from_env() -> Self {
+        Self::parse(
+            std::env::var("BETTING_DAY_TZ").ok().as_deref(),
+            std::env::var("BETTING_DAY_CUTOFF_HOUR").ok().as_deref(),
+        )
+    }
+
+    /// Sázkový den (`YYYY-MM-DD`), do kterého patří okamžik `at`.
+    fn date_for(&self, at: chrono::DateTime<Utc>) -> String {
+        // Posun v lokálním (naive) čase → přechod DST nemění, ve kterou lokální hodinu den začíná
+        let local = at.with_timezone(&self.tz).naive_local() - chrono::Duration::hours(self.cutoff_hour as i64);
+        local.date().format("%Y-%m-%d").to_string()
+    }
+
+    fn today(&self) -> String {
+        self.date_for(Utc::now())
+    }
+
+    /// Patří RFC3339 timestamp z ledgeru do sázkového dne `date`?
+    /// Neparsovatelný ts → prefixové porovnání (staré záznamy bez offsetu).
+    fn ts_in_day(&self, ts: &str, date: &str) -> bool {
+        match chrono::DateTime::parse_from_rfc3339(ts) {
+            Ok(at) => self.date_for(at.with_timezone(&Utc)) == date,
+            Err(_) => ts.starts_with(date),
+        }
+    }
+}
+
+/// Proč `find_score_edges` zahodil kandidáta — tally per důvod ukazuje, kde funnel ztrácí příležitosti.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum SkipReason {
+    /// Skóre skočilo zpět (reset zdroje)
+    ScoreRewind,
+    /// Podezřelý skok skóre dopředu (CS2 / basketbal)
+    ScoreSpike,
+    /// Skóre nad stropem sportu / garbage parse
+    ScoreSanity,
+    /// CS2 karanténa po glitchi nebo terminální skóre mapy
+    Cs2Quarantine,
+    /// Mapa / zápas se rozhodne dřív, než sázka projde
+    TooLate,
+    /// Fotbal bez minuty v detailed_score
+    MissingMinute,
+    /// Skóre nedává směrový model (remíza, malý náskok, ...)
+    NotActionable,
+    /// Azuro kurz starší než okno čerstvosti
+    StaleOdds,
+    /// Map winner market na jinou než aktivní mapu
+    MapMismatch,
+    /// Týmy live vs Azuro nejdou jednoznačně spárovat
+    AmbiguousTeams,
+    /// Žádný Azuro match_winner kurz
+    NoOdds,
+    /// Edge pod MIN_SCORE_EDGE_PCT
+    BelowThreshold,
+    /// Model a Azuro se rozcházejí tak, že jde spíš o špatný market
+    MarketSanity,
+}
+
+impl SkipReason {
+    fn label(self) -> &'static str {
+        match self {
+            SkipReason::ScoreRewind    => "score_rewind",
+            SkipReason::ScoreSpike     => "score_spike",
+            SkipReason::ScoreSanity    => "score_sanity",
+            SkipReason::Cs2Quarantine  => "cs2_quarantine",
+            SkipReason::TooLate        => "too_late",
+            SkipReason::MissingMinute  => "missing_minute",
+            SkipReason::NotActionable  => "not_actionable",
+            SkipReason::StaleOdds      => "stale_odds",
+            SkipReason::MapMismatch    => "map_mismatch",
+            SkipReason::AmbiguousTeams => "ambiguous_teams",
+            SkipReason::NoOdds         => "no_odds",
+            SkipReason::BelowThreshold => "below_min_edge",
+            SkipReason::MarketSanity   => "market_sanity",
+        }
+    }
+}
+
+/// Kumulativní počty skipů per důvod od startu bota (`/status`, bot_status.json).
+#[derive(Debug, Clone, Default)]
+struct SkipTally {
+    counts: std::collections::BTreeMap<SkipReason, u64>,
+}
+
+impl SkipTally {
+    fn record(&mut self, reason: SkipReason) {
+        *self.counts.entry(reason).or_insert(0) += 1;
+    }
+
+    /// label → počet (BTreeMap kvůli stabilnímu pořadí v JSON).
+    fn by_label(&self) -> std::collections::BTreeMap<String, u64> {
+        self.counts.iter().map(|(reason, n)| (reason.label().to_string(), *n)).collect()
+    }
+}
+
 /// Track previous scores per match for score-change detection
 struct ScoreTracker {
     /// match_key → (score1, score2, timestamp) — last known scores
@@ -1680,23 +2476,77 @@ struct ScoreTracker {
     cs2_glitch_quarantine_until: HashMap<String, chrono::DateTime<Utc>>,
     /// match_key → distrust score / lock after repeated or severe CS2 score anomalies
     cs2_distrust_state: HashMap<String, Cs2DistrustState>,
+    /// match_key → (score1, score2, kdy se skóre naposledy změnilo) — dead-man's switch
+    score_changed_at: HashMap<String, (i32, i32, chrono::DateTime<Utc>)>,
+    /// Max stáří poslední změny skóre u live zápasu (0 = vypnuto)
+    frozen_window_secs: i64,
+    /// NEAR_MISS_LOG: edge těsně pod MIN_SCORE_EDGE_PCT → NEAR_MISS event
+    near_miss: Option<NearMissLog>,
+    /// Score sanity stropy per sport (garbage skóre ze scraperů)
+    score_ceilings: ScoreCeilings,
+    /// Vypnutí auto-betů sportu při vysokém podílu garbage skóre
+    sport_kill: SportKillSwitch,
+    /// Skip edge, když se mapa / zápas rozhodne dřív, než sázka projde
+    too_late: TooLateGuard,
+    /// Zdroj času pro cleanup a resync freeze (testy: MockClock)
+    clock: Arc<dyn Clock>,
+    /// Proč se kandidáti na edge zahodili (SkipReason → počet)
+    skip_tally: SkipTally,
+    /// Kam psát audit eventy rozhodování (produkčně LEDGER_PATH); None = nepsat (testy)
+    audit_ledger: Option<std::path::PathBuf>,
 }
 
 impl ScoreTracker {
     fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             prev_scores: HashMap::new(),
             edge_cooldown: HashMap::new(),
             backward_scores: HashMap::new(),
             cs2_glitch_quarantine_until: HashMap::new(),
             cs2_distrust_state: HashMap::new(),
+            score_changed_at: HashMap::new(),
+            frozen_window_secs: FROZEN_SCORE_WINDOW_SECS,
+            near_miss: None,
+            score_ceilings: ScoreCeilings::default(),
+            sport_kill: SportKillSwitch::default(),
+            too_late: TooLateGuard::default(),
+            clock,
+            skip_tally: SkipTally::default(),
+            audit_ledger: None,
         }
     }
 
+    /// Audit event do `audit_ledger` (no-op bez nastaveného ledgeru).
+    fn audit(&self, event: &str, data: &serde_json::Value) {
+        if let Some(path) = &self.audit_ledger {
+            append_ledger_audit_event(path, event, data);
+        }
+    }
+
+    /// Zaznamená aktuální skóre; Some(secs) = skóre stojí déle než frozen window.
+    fn frozen_score_secs(&mut self, match_key: &str, s1: i32, s2: i32, now: chrono::DateTime<Utc>) -> Option<i64> {
+        let entry = self.score_changed_at
+            .entry(match_key.to_string())
+            .or_insert((s1, s2, now));
+        if (entry.0, entry.1) != (s1, s2) {
+            *entry = (s1, s2, now);
+            return None;
+        }
+        let frozen_secs = (now - entry.2).num_seconds();
+        (self.frozen_window_secs > 0 && frozen_secs > self.frozen_window_secs).then_some(frozen_secs)
+    }
+
     /// Clean entries older than 30 min (match ended)
     fn cleanup(&mut self) {
-        let cutoff = Utc::now() - chrono::Duration::seconds(1800);
+        let cutoff = self.clock.now_utc() - chrono::Duration::seconds(1800);
         self.prev_scores.retain(|_, (_, _, ts)| *ts > cutoff);
+        // Změna skóre může být starší než cutoff (zamrzlý feed) → držet, dokud zápas vidíme
+        let seen = &self.prev_scores;
+        self.score_changed_at.retain(|key, _| seen.contains_key(key));
         self.edge_cooldown.retain(|_, ts| *ts > cutoff);
         self.backward_scores.retain(|_, state| state.first_seen_at > cutoff);
         self.cs2_glitch_quarantine_until.retain(|_, ts| *ts > cutoff);
@@ -1748,7 +2598,6 @@ struct ScoreEdge {
     condition_id: Option<String>,
     outcome1_id: Option<String>,
     outcome2_id: Option<String>,
-    outcome_id: Option<String>,
     chain: Option<String>,
     azuro_url: Option<String>,
     /// CS2 map confidence tier for dynamic odds cap ("ULTRA"/"HIGH"/"MEDIUM"/"LOW"/None)
@@ -1757,23 +2606,103 @@ struct ScoreEdge {
     cv_stake_mult: f64,
     /// Detailed score string from live feed (for esports anomaly guard)
     detailed_score: Option<String>,
+    /// Bo1 series — map winner and match winner are the same bet (dedup as siblings)
+    series_bo1: bool,
+    /// Azuro side (1/2) of the leading team after resolve_azuro_side_pair (may differ from leading_side)
+    azuro_side: u8,
+    /// Remíza u three-way trhu (match_winner payload; map winner nemá)
+    outcome_draw_id: Option<String>,
+    /// Pořadí outcome ID vůči týmům v surovém Azuro payloadu (před přerovnáním do live pořadí)
+    outcome_order: OutcomeOrder,
 }
 
-// ====================================================================
-// CS2 ROUND SCORE PARSER — extract current round from Chance detailed_score
-// ====================================================================
+/// Leading team of an edge — name, odds and outcome always taken from the same side.
+/// Alert formatter i bet request čtou jen odsud, takže zobrazení a exekuce se nemůžou rozejít.
+#[derive(Debug, Clone, PartialEq)]
+struct LeadingInfo<'a> {
+    team: &'a str,
+    odds: f64,
+    outcome_id: Option<&'a str>,
+    azuro_side: u8,
+}
 
-/// Parse CS2 round score from Dust2.us detailed_score string.
-/// Example: "R:9-3 M:0-0" → Some((9, 3)) for round score
-/// Example: "R:6-8 M:1-0" → Some((6, 8)) for round score
-fn parse_dust2_round_score(detailed: &str) -> Option<(i32, i32)> {
+impl ScoreEdge {
+    /// azuro_w1/w2 a outcome1/2_id jsou už přerovnané do pořadí live týmů → indexuje se leading_side.
+    fn leading(&self) -> LeadingInfo<'_> {
+        let (team, odds, outcome_id) = if self.leading_side == 1 {
+            (&self.team1, self.azuro_w1, &self.outcome1_id)
+        } else {
+            (&self.team2, self.azuro_w2, &self.outcome2_id)
+        };
+        LeadingInfo {
+            team,
+            odds,
+            outcome_id: outcome_id.as_deref(),
+            azuro_side: self.azuro_side,
+        }
+    }
+}
+
+/// Team name for a side (1 → team1, jinak team2).
+fn side_team<'a>(team1: &'a str, team2: &'a str, side: u8) -> &'a str {
+    if side == 1 { team1 } else { team2 }
+}
+
+/// Sedí outcome1_id/outcome2_id z feedu na team1/team2 payloadu?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutcomeOrder {
+    Verified,
+    Reversed,
+    Unverifiable,
+}
+
+/// Azuro slovníky číslují výsledky jedné podmínky vzestupně v pořadí P1 → (X) → P2
+/// (6995/6996, 7009/7010, 6977/6978, 1/2/3 …) — jediná metadata, ze kterých jde pořadí ověřit.
+/// Cokoliv jiného (nečíselné ID, mezera v číslování) je neověřitelné → auto-bet se blokuje.
+fn azuro_outcome_order(outcome1_id: Option<&str>, outcome2_id: Option<&str>, draw_id: Option<&str>) -> OutcomeOrder {
+    let parse = |id: Option<&str>| id.and_then(|s| s.trim().parse::<u64>().ok());
+    let (Some(o1), Some(o2)) = (parse(outcome1_id), parse(outcome2_id)) else {
+        return OutcomeOrder::Unverifiable;
+    };
+    match draw_id {
+        Some(raw) => match parse(Some(raw)) {
+            Some(x) if o1 + 1 == x && x + 1 == o2 => OutcomeOrder::Verified,
+            Some(x) if o2 + 1 == x && x + 1 == o1 => OutcomeOrder::Reversed,
+            _ => OutcomeOrder::Unverifiable,
+        },
+        None if o1 + 1 == o2 => OutcomeOrder::Verified,
+        None if o2 + 1 == o1 => OutcomeOrder::Reversed,
+        None => OutcomeOrder::Unverifiable,
+    }
+}
+
+// ====================================================================
+// CS2 ROUND SCORE PARSER — extract current round from Chance detailed_score
+// ====================================================================
+
+/// Horní mez kol jedné CS2 mapy vč. MR3 overtimů (12:12 → OT bloky po 6 kolech; 60 ≈ 7 OT).
+const CS2_MAX_MAP_ROUNDS: i32 = 60;
+
+/// Round skóre jedné mapy: regulace do 13, nad 13 jen jako OT (obě strany ≥ 12, rozdíl ≤ 4).
+fn is_plausible_cs2_map_rounds(a: i32, b: i32) -> bool {
+    let (hi, lo) = (a.max(b), a.min(b));
+    if lo < 0 || hi > CS2_MAX_MAP_ROUNDS {
+        return false;
+    }
+    hi <= 13 || (lo >= 12 && hi - lo <= 4)
+}
+
+/// Parse CS2 round score from Dust2.us detailed_score string.
+/// Example: "R:9-3 M:0-0" → Some((9, 3)) for round score
+/// Example: "R:6-8 M:1-0" → Some((6, 8)) for round score
+fn parse_dust2_round_score(detailed: &str) -> Option<(i32, i32)> {
     // Look for "R:X-Y" pattern
     if let Some(r_pos) = detailed.find("R:") {
         let after = &detailed[r_pos + 2..];
         let parts: Vec<&str> = after.split_whitespace().next()?.split('-').collect();
         if parts.len() == 2 {
             if let (Ok(a), Ok(b)) = (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
-                if a >= 0 && a <= 30 && b >= 0 && b <= 30 {
+                if is_plausible_cs2_map_rounds(a, b) {
                     return Some((a, b));
                 }
             }
@@ -1828,7 +2757,7 @@ fn parse_cs2_score_pair(segment: &str) -> Option<(i32, i32)> {
     let (Ok(a), Ok(b)) = (parts[0].trim().parse::<i32>(), parts[1].trim().parse::<i32>()) else {
         return None;
     };
-    if !(0..=30).contains(&a) || !(0..=30).contains(&b) {
+    if !is_plausible_cs2_map_rounds(a, b) {
         return None;
     }
     Some((a, b))
@@ -2089,6 +3018,10 @@ fn is_cs2_round_rewind_hold_state(
     has_cs2_detail && strong_rewind
 }
 
+/// Přechod na další mapu série (19-17 → nová mapa 1-0), ne backward glitch. Kde detailed_score nese
+/// číslo aktivní mapy, musí série prokazatelně postoupit (viz `cs2_series_advanced`); jinak stačí
+/// mapový kontext v detailu jako dřív.
+#[allow(clippy::too_many_arguments)]
 fn is_cs2_legit_map_rollover(
     prev_score1: i32,
     prev_score2: i32,
@@ -2097,6 +3030,7 @@ fn is_cs2_legit_map_rollover(
     raw_score1: i32,
     raw_score2: i32,
     detailed: Option<&str>,
+    series_format: Option<&str>,
     esports_family: Option<&str>,
 ) -> bool {
     if !is_cs2_like(esports_family, detailed) {
@@ -2129,7 +3063,40 @@ fn is_cs2_legit_map_rollover(
         && raw_round_like
         && previous_map_finished;
 
-    plausible_rollover_score && (has_map_rollover_detail || explicit_next_map || dust2_map_progress)
+    if !plausible_rollover_score {
+        return false;
+    }
+    match cs2_series_advanced(prev_score1, prev_score2, detail, series_format) {
+        Some(advanced) => advanced,
+        None => has_map_rollover_detail || explicit_next_map || dust2_map_progress,
+    }
+}
+
+/// Posunula se série o mapu, kterou ukončilo skóre `prev`? None = detail aktivní mapu nenese.
+/// Tipsport ("Lepší ze 3 | 2.mapa - 19:17, 0:0"): poslední dohraná mapa = `prev`, aktivní = dohrané + 1.
+/// Dust2 ("R:0-0 M:1-0") nese jen mapové skóre — bez známého BoN nejde odlišit konec Bo1 od další mapy.
+/// Neznámý formát série se nebere jako Bo3: horní mez mapy se kontroluje jen se známým BoN.
+fn cs2_series_advanced(prev_score1: i32, prev_score2: i32, detail: &str, series_format: Option<&str>) -> Option<bool> {
+    let best_of = parse_series_best_of(series_format, Some(detail));
+    let dust2_maps = parse_dust2_map_score(detail);
+    let (current_map, completed_ok) = match parse_cs2_current_map(detail) {
+        Some(map) => {
+            let completed = parse_cs2_completed_maps(detail);
+            let ok = !completed.is_empty()
+                && completed.len() + 1 == map as usize
+                && completed.last() == Some(&(prev_score1, prev_score2));
+            (map, ok || dust2_maps.is_some_and(|(m1, m2)| m1 + m2 + 1 == map as i32))
+        }
+        None => {
+            let (m1, m2) = dust2_maps?;
+            if best_of.is_none() {
+                return Some(false);
+            }
+            ((m1 + m2 + 1) as u8, m1 + m2 >= 1)
+        }
+    };
+    let within_series = best_of.is_none_or(|n| n > 1 && current_map <= n);
+    Some(current_map >= 2 && within_series && completed_ok && is_cs2_terminal_map_score(prev_score1, prev_score2))
 }
 
 fn is_cs2_backward_score_pending_state(
@@ -2218,6 +3185,7 @@ fn esports_anomaly_guard(score1: i32, score2: i32, detailed: Option<&str>) -> bo
 ///   "Lepší ze 3 | 3.mapa - 13:6, 9:13, 7:12" → Some((7, 12)) — current map round score
 ///   "Lepší ze 3 | 2.mapa - 13:6, 4:8"         → Some((4, 8))
 ///   "Lepší ze 3 | 1.mapa - 5:3"                → Some((5, 3))
+///   "Lepší ze 3 | 3.mapa - 13:6, 9:13, 19:17" → Some((19, 17)) — overtime
 /// Returns the LAST score in the comma-separated list (= current map being played).
 fn parse_cs2_round_score(detailed: &str) -> Option<(i32, i32)> {
     // Pattern: contains "mapa" and has scores like "X:Y"
@@ -2256,6 +3224,43 @@ fn parse_cs2_current_map(detailed: &str) -> Option<u8> {
     None
 }
 
+/// Series length (N z BoN) z `series_format` nebo detailed_score.
+/// "bo1" → Some(1), "Lepší ze 3 | 1.mapa - 5:3" → Some(3), "Best of 1" → Some(1)
+fn parse_series_best_of(series_format: Option<&str>, detailed: Option<&str>) -> Option<u8> {
+    for raw in [series_format, detailed].into_iter().flatten() {
+        let lower = raw.to_lowercase();
+        for marker in ["lepší ze", "best of", "bo"] {
+            for (pos, _) in lower.match_indices(marker) {
+                // "bo" jen na začátku slova (ne "bonus", "robot")
+                let word_start = lower[..pos]
+                    .chars()
+                    .next_back()
+                    .map(|c| !c.is_alphanumeric())
+                    .unwrap_or(true);
+                if !word_start {
+                    continue;
+                }
+                let digits: String = lower[pos + marker.len()..]
+                    .trim_start()
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                if let Ok(n) = digits.parse::<u8>() {
+                    if matches!(n, 1 | 3 | 5) {
+                        return Some(n);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Bo1: jediná mapa = celý zápas.
+fn is_bo1_series(series_format: Option<&str>, detailed: Option<&str>) -> bool {
+    parse_series_best_of(series_format, detailed) == Some(1)
+}
+
 /// Parse all completed map scores from detailed_score.
 /// "Lepší ze 3 | 3.mapa - 13:6, 9:13, 7:12" → [(13,6), (9,13)] (completed maps only, not current)
 fn parse_cs2_completed_maps(detailed: &str) -> Vec<(i32, i32)> {
@@ -2287,7 +3292,14 @@ fn is_cs2_incomplete_map_score_hold_state(
 
 #[cfg(test)]
 mod cs2_map_parser_tests {
-    use super::{has_cs2_incomplete_current_map_score, parse_cs2_current_map, parse_dust2_current_map};
+    use super::{
+        has_cs2_incomplete_current_map_score,
+        is_cs2_terminal_map_score,
+        parse_cs2_current_map,
+        parse_cs2_round_score,
+        parse_dust2_current_map,
+        parse_dust2_round_score,
+    };
 
     #[test]
     fn dust2_map_one_is_detected_from_round_and_map_score() {
@@ -2320,6 +3332,28 @@ mod cs2_map_parser_tests {
         assert!(!has_cs2_incomplete_current_map_score("Lepší ze 3 | 2.mapa - 13:8, 2:"));
         assert!(!has_cs2_incomplete_current_map_score("Lepší ze 3 | 3.mapa - 13:8, 9:13, 7:"));
     }
+
+    #[test]
+    fn overtime_round_scores_are_the_current_map() {
+        assert_eq!(parse_cs2_round_score("Lepší ze 3 | 3.mapa - 13:6, 9:13, 19:17"), Some((19, 17)));
+        assert_eq!(parse_cs2_round_score("Lepší ze 3 | 2.mapa - 16:14, 14:13"), Some((14, 13)));
+        assert_eq!(parse_cs2_round_score("Lepší ze 1 | 1.mapa - 34:31"), Some((34, 31)));
+        assert_eq!(parse_cs2_round_score("Lepší ze 3 | 1.mapa - 16:14"), Some((16, 14)));
+        assert_eq!(parse_dust2_round_score("R:17-15 M:1-1"), Some((17, 15)));
+        // Nad 13 bez OT kontextu (obě ≥ 12) = garbage, ne dřívější mapa
+        assert_eq!(parse_cs2_round_score("Lepší ze 3 | 2.mapa - 13:6, 21:4"), None);
+        assert_eq!(parse_dust2_round_score("R:45-3 M:0-0"), None);
+    }
+
+    #[test]
+    fn overtime_map_endings_are_terminal() {
+        assert!(is_cs2_terminal_map_score(16, 14));
+        assert!(is_cs2_terminal_map_score(12, 16));
+        assert!(is_cs2_terminal_map_score(19, 15));
+        assert!(!is_cs2_terminal_map_score(15, 13));
+        assert!(!is_cs2_terminal_map_score(14, 12));
+        assert!(!is_cs2_terminal_map_score(17, 15));
+    }
 }
 
 #[cfg(test)]
@@ -2477,6 +3511,138 @@ mod basketball_guard_tests {
     }
 }
 
+#[cfg(test)]
+mod alert_chat_ids_tests {
+    use super::split_alert_chat_ids;
+
+    #[test]
+    fn first_numeric_chat_is_interactive_and_channels_are_kept() {
+        let (primary, broadcast) = split_alert_chat_ids("123, @rustmisko_alerts, -100200, 123");
+        assert_eq!(primary, Some(123));
+        assert_eq!(broadcast, ["@rustmisko_alerts", "-100200"]);
+
+        // Kanál první: interaktivní je až číselný chat, kanál zůstává v broadcastu
+        let (primary, broadcast) = split_alert_chat_ids("@rustmisko_alerts,456");
+        assert_eq!(primary, Some(456));
+        assert_eq!(broadcast, ["@rustmisko_alerts"]);
+
+        let (primary, broadcast) = split_alert_chat_ids("@only_channel");
+        assert_eq!(primary, None);
+        assert_eq!(broadcast, ["@only_channel"]);
+        assert_eq!(split_alert_chat_ids(""), (None, vec![]));
+    }
+}
+
+#[cfg(test)]
+mod league_filter_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{live_league, LeagueDecision, LeagueFilter, LEAGUE_ANY};
+    use std::collections::HashMap;
+
+    fn football_filter() -> LeagueFilter {
+        LeagueFilter {
+            allow: HashMap::from([("football".to_string(), vec!["premier league".to_string(), "bundesliga".to_string()])]),
+            deny: HashMap::from([("football".to_string(), vec!["j3".to_string(), "npl".to_string()])]),
+        }
+    }
+
+    #[test]
+    fn denylisted_league_is_blocked_and_allowlisted_passes() {
+        let filter = football_filter();
+        assert_eq!(filter.decide("football", Some("Australia NPL Victoria")), LeagueDecision::Denied);
+        assert_eq!(filter.decide("football", Some("J3 League")), LeagueDecision::Denied);
+        assert_eq!(filter.decide("football", Some("England - Premier League")), LeagueDecision::AutoBet);
+        assert_eq!(filter.decide("football", Some("1. BUNDESLIGA")), LeagueDecision::AutoBet);
+    }
+
+    #[test]
+    fn unknown_league_is_alert_only() {
+        let filter = football_filter();
+        assert_eq!(filter.decide("football", Some("Peru Liga 2")), LeagueDecision::AlertOnly);
+        assert_eq!(filter.decide("football", None), LeagueDecision::AlertOnly);
+        // Sport bez allowlistu: žádná liga není prověřená → jen alert
+        assert_eq!(filter.decide("tennis", None), LeagueDecision::AlertOnly);
+        assert_eq!(filter.decide("tennis", Some("ATP Madrid")), LeagueDecision::AlertOnly);
+        assert_eq!(LeagueFilter::default().decide("football", None), LeagueDecision::AlertOnly);
+    }
+
+    #[test]
+    fn wildcard_allows_whole_sport_but_deny_still_wins() {
+        let filter = LeagueFilter {
+            allow: HashMap::from([("cs2".to_string(), vec![LEAGUE_ANY.to_string()])]),
+            deny: HashMap::from([("cs2".to_string(), vec!["cct".to_string()])]),
+        };
+        assert_eq!(filter.decide("cs2", None), LeagueDecision::AutoBet);
+        assert_eq!(filter.decide("cs2", Some("IEM Katowice")), LeagueDecision::AutoBet);
+        assert_eq!(filter.decide("cs2", Some("CCT Europe")), LeagueDecision::Denied);
+        assert_eq!(filter.decide("dota-2", None), LeagueDecision::AlertOnly);
+    }
+
+    #[test]
+    fn league_is_read_from_live_state_for_map_keys() {
+        let state = StateBuilder::default()
+            .live("football::sparta_vs_slavia", ("Sparta", "Slavia"), (1, 0))
+            .live_field("league", "Czech First League")
+            .build();
+        assert_eq!(live_league(&state, "football::sparta_vs_slavia"), Some("Czech First League"));
+        assert_eq!(live_league(&state, "football::other_vs_match"), None);
+    }
+}
+
+#[cfg(test)]
+mod price_impact_tests {
+    use super::{azuro_price_impact, post_impact_edge_pct, MIN_SCORE_EDGE_PCT};
+
+    #[test]
+    fn thin_pool_kills_edge_that_deep_pool_keeps() {
+        // Stejný zobrazený kurz 2.00, score model 60 % → zobrazený edge 10 p.b.
+        let deep = post_impact_edge_pct(60.0, 2.0, 10.0, 100_000.0);
+        let thin = post_impact_edge_pct(60.0, 2.0, 10.0, 50.0);
+
+        assert!(deep > 9.9, "deep={}", deep);
+        assert!(thin < MIN_SCORE_EDGE_PCT, "thin={}", thin);
+        assert!((azuro_price_impact(2.0, 10.0, 50.0) - 1.708).abs() < 0.001);
+    }
+
+    #[test]
+    fn price_impact_is_neutral_without_stake_or_liquidity() {
+        assert_eq!(azuro_price_impact(1.85, 0.0, 500.0), 1.85);
+        assert_eq!(azuro_price_impact(1.85, 5.0, 0.0), 1.85);
+        assert!(azuro_price_impact(1.85, 5.0, 500.0) < azuro_price_impact(1.85, 1.0, 500.0));
+    }
+}
+
+#[cfg(test)]
+mod tennis_model_tests {
+    use super::{tennis_best_of, tennis_score_to_win_prob};
+
+    #[test]
+    fn bo3_sets_keep_first_set_model() {
+        assert_eq!(tennis_score_to_win_prob(1, 0, 3), Some(0.65));
+        assert_eq!(tennis_score_to_win_prob(2, 0, 3), None);
+        assert_eq!(tennis_score_to_win_prob(2, 1, 3), None);
+        assert_eq!(tennis_score_to_win_prob(1, 1, 3), None);
+    }
+
+    #[test]
+    fn bo5_two_set_lead_is_not_yet_decided() {
+        assert_eq!(tennis_score_to_win_prob(1, 0, 5), Some(0.60));
+        assert_eq!(tennis_score_to_win_prob(2, 0, 5), Some(0.85));
+        assert_eq!(tennis_score_to_win_prob(2, 1, 5), Some(0.70));
+        assert_eq!(tennis_score_to_win_prob(3, 0, 5), None);
+        assert_eq!(tennis_score_to_win_prob(3, 2, 5), None);
+    }
+
+    #[test]
+    fn best_of_is_detected_from_hint_or_set_number() {
+        assert_eq!(tennis_best_of(Some("bo5"), None), 5);
+        assert_eq!(tennis_best_of(None, Some("Best of 5 | 3.set - 6:4, 6:3, 2:1")), 5);
+        assert_eq!(tennis_best_of(None, Some("4.set - 6:4, 3:6, 6:2, 1:0")), 5);
+        assert_eq!(tennis_best_of(None, Some("2.set - 6:4, 2:1")), 3);
+        assert_eq!(tennis_best_of(None, None), 3);
+    }
+}
+
 #[cfg(test)]
 mod threshold_relax_tests {
     use super::{
@@ -2557,24 +3723,35 @@ mod strategy_hotfix_tests {
         cross_market_base_dedup_block,
         dynamic_base_stake,
         effective_cs2_score_edge_min_edge,
+        effective_paper_bankroll,
         effective_score_edge_min_odds,
         executable_stake_floor,
+        game_id_dedup_block,
+        get_exposure_caps,
         get_sport_config,
         get_sport_exposure_cap,
         is_cs2_forward_spike_state,
         is_cs2_terminal_map_score,
         locked_exposure_total,
         mark_cs2_glitch_quarantine,
+        reconstruct_sod_bankroll,
         relax_cs2_distrust_on_stable_progress,
         record_cs2_distrust_event,
+        record_bet_game_id,
         refresh_active_bet_from_onchain_pending,
         score_edge_max_odds,
         stake_below_executable_floor,
         ActiveBet,
+        ExecutorHealthResponse,
+        PaperMode,
         ScoreTracker,
+        SlippageGuard,
+        StakePolicy,
         trim_stake,
         CS2_SCORE_DISTRUST_LOCK_SECS,
         CS2_SCORE_GLITCH_QUARANTINE_SECS,
+        MIN_ODDS_FACTOR_DEFAULT,
+        MIN_ODDS_FACTOR_TENNIS,
         STARTUP_UNVERIFIED_GRACE_SECS,
     };
     use chrono::{Duration, Utc};
@@ -2680,6 +3857,44 @@ mod strategy_hotfix_tests {
         assert!(!stake_below_executable_floor(0.0, bankroll));
     }
 
+    #[test]
+    fn stake_policy_rounds_down_to_configured_decimals() {
+        let cents = StakePolicy::default();
+        assert_eq!(cents.finalize(1.237, Some("polygon")), 1.23);
+        assert_eq!(cents.finalize(0.70, None), 0.70);
+        assert_eq!(cents.finalize(0.0, None), 0.0);
+
+        let whole = StakePolicy { decimals: 0, ..StakePolicy::default() };
+        assert_eq!(whole.finalize(2.99, None), 2.0);
+    }
+
+    #[test]
+    fn stake_below_chain_floor_after_rounding_is_skipped() {
+        let mut policy = StakePolicy::default();
+        policy.chain_floors.insert("gnosis".to_string(), 1.0);
+
+        assert_eq!(policy.finalize(0.999, Some("Gnosis")), 0.0);
+        assert_eq!(policy.finalize(1.004, Some("gnosis")), 1.0);
+        // Ostatní chainy drží výchozí $0.50
+        assert_eq!(policy.finalize(0.999, Some("polygon")), 0.99);
+        assert_eq!(policy.finalize(0.499, None), 0.0);
+    }
+
+    #[test]
+    fn slippage_guard_applies_custom_cs2_factor_and_default_elsewhere() {
+        let mut guard = SlippageGuard::default();
+        guard.sport_factors.insert("cs2".to_string(), 0.78);
+
+        assert_eq!(guard.factor_for_match("cs2::alpha_vs_beta::map2_winner"), 0.78);
+        assert_eq!(guard.factor_for_match("tennis::a_vs_b"), MIN_ODDS_FACTOR_TENNIS);
+        assert_eq!(guard.factor_for_match("football::x_vs_y"), MIN_ODDS_FACTOR_DEFAULT);
+        assert_eq!(guard.factor_for_match("dota-2::x_vs_y"), MIN_ODDS_FACTOR_DEFAULT);
+        // Fallback krok se odečítá od sportového factoru, floor 0.80
+        assert!((guard.factor_with_fallback("football::x_vs_y", true) - 0.80).abs() < 1e-9);
+        // Sportový factor pod floor: fallback ho nezvedne nad base
+        assert_eq!(guard.factor_with_fallback("cs2::alpha_vs_beta", true), 0.78);
+    }
+
     #[test]
     fn stale_loaded_unverified_bet_stops_blocking_pending_and_inflight() {
         let session_start = Utc::now() - Duration::seconds(STARTUP_UNVERIFIED_GRACE_SECS + 5);
@@ -2857,108 +4072,2114 @@ mod strategy_hotfix_tests {
     }
 
     #[test]
-    fn cs2_forward_spike_states_are_detected() {
-        assert!(is_cs2_forward_spike_state(
-            8,
-            7,
-            12,
-            1,
-            8,
-            Some("Lepší ze 3 | 1.mapa - 8:7"),
-            Some("cs2"),
-        ));
-        assert!(!is_cs2_forward_spike_state(
-            8,
-            7,
-            9,
-            8,
-            8,
-            Some("Lepší ze 3 | 1.mapa - 8:7"),
-            Some("cs2"),
-        ));
+    fn cs2_forward_spike_states_are_detected() {
+        assert!(is_cs2_forward_spike_state(
+            8,
+            7,
+            12,
+            1,
+            8,
+            Some("Lepší ze 3 | 1.mapa - 8:7"),
+            Some("cs2"),
+        ));
+        assert!(!is_cs2_forward_spike_state(
+            8,
+            7,
+            9,
+            8,
+            8,
+            Some("Lepší ze 3 | 1.mapa - 8:7"),
+            Some("cs2"),
+        ));
+    }
+
+    #[test]
+    fn cs2_distrust_events_lock_match_after_threshold() {
+        let now = Utc::now();
+        let mut tracker = ScoreTracker::new();
+
+        let (points_after_first, first_lock) =
+            record_cs2_distrust_event(&mut tracker, "esports::alliance_vs_nexus", now, 2);
+        assert_eq!(points_after_first, 2);
+        assert!(first_lock.is_none());
+
+        let (points_after_second, second_lock) = record_cs2_distrust_event(
+            &mut tracker,
+            "esports::alliance_vs_nexus",
+            now + Duration::seconds(5),
+            1,
+        );
+        assert_eq!(points_after_second, 3);
+        let until = second_lock.expect("lock set");
+        assert!(until >= now + Duration::seconds(CS2_SCORE_DISTRUST_LOCK_SECS - 1));
+    }
+
+    #[test]
+    fn cs2_distrust_lock_releases_after_two_stable_progress_updates() {
+        let now = Utc::now();
+        let mut tracker = ScoreTracker::new();
+
+        record_cs2_distrust_event(&mut tracker, "esports::alliance_vs_nexus", now, 2);
+        let (_, second_lock) = record_cs2_distrust_event(
+            &mut tracker,
+            "esports::alliance_vs_nexus",
+            now + Duration::seconds(5),
+            1,
+        );
+        assert!(second_lock.is_some());
+
+        assert!(!relax_cs2_distrust_on_stable_progress(
+            &mut tracker,
+            "esports::alliance_vs_nexus",
+            now + Duration::seconds(15),
+            true,
+            4,
+            2,
+            5,
+            2,
+        ));
+        assert!(relax_cs2_distrust_on_stable_progress(
+            &mut tracker,
+            "esports::alliance_vs_nexus",
+            now + Duration::seconds(25),
+            true,
+            5,
+            2,
+            6,
+            2,
+        ));
+
+        let active_lock = tracker
+            .cs2_distrust_state
+            .get("esports::alliance_vs_nexus")
+            .and_then(|state| state.active_lock_until(now + Duration::seconds(25)));
+        assert!(active_lock.is_none());
+    }
+
+    #[test]
+    fn cross_market_base_dedup_only_blocks_map_winner_siblings() {
+        use std::collections::HashSet;
+
+        let mut base_matches = HashSet::new();
+        let mut map_winners = HashSet::new();
+        let base = "esports::cybershoke_vs_ruby".to_string();
+
+        assert!(!cross_market_base_dedup_block(&base, true, false, &base_matches, &map_winners));
+        assert!(!cross_market_base_dedup_block(&base, false, false, &base_matches, &map_winners));
+
+        base_matches.insert(base.clone());
+        assert!(!cross_market_base_dedup_block(&base, true, false, &base_matches, &map_winners));
+        assert!(!cross_market_base_dedup_block(&base, false, false, &base_matches, &map_winners));
+
+        map_winners.insert(base.clone());
+        assert!(cross_market_base_dedup_block(&base, true, false, &base_matches, &map_winners));
+        assert!(!cross_market_base_dedup_block(&base, false, false, &base_matches, &map_winners));
+    }
+
+    #[test]
+    fn game_id_dedup_blocks_second_condition_on_same_game() {
+        use std::collections::HashMap;
+
+        let mut game_ids = HashMap::new();
+        assert!(!game_id_dedup_block(Some("game_42"), "cond_match_winner", &game_ids));
+
+        record_bet_game_id(Some("game_42"), "cond_match_winner", &mut game_ids);
+        // map2_winner na stejné game → double exposure
+        assert!(game_id_dedup_block(Some("game_42"), "cond_map2_winner", &game_ids));
+        // stejná condition (re-bet) a jiná game projdou
+        assert!(!game_id_dedup_block(Some("game_42"), "cond_match_winner", &game_ids));
+        assert!(!game_id_dedup_block(Some("game_43"), "cond_map2_winner", &game_ids));
+        assert!(!game_id_dedup_block(None, "cond_map2_winner", &game_ids));
+        assert!(!game_id_dedup_block(Some(""), "cond_map2_winner", &game_ids));
+    }
+
+    #[test]
+    fn sod_bankroll_reconstructed_from_balance_and_daily_pnl() {
+        // Ráno $120, dnes vsazeno $30, vráceno $12 → executor ukazuje $102
+        let sod = reconstruct_sod_bankroll(102.0, 30.0, 12.0);
+        assert!((sod - 120.0).abs() < 1e-9);
+        // Ziskový den: vráceno víc než vsazeno
+        assert!((reconstruct_sod_bankroll(140.0, 20.0, 40.0) - 120.0).abs() < 1e-9);
+        assert_eq!(reconstruct_sod_bankroll(0.0, 0.0, 5.0), 0.0);
+    }
+
+    #[test]
+    fn paper_bankroll_drives_sizing_when_executor_is_unavailable() {
+        let dry_run: ExecutorHealthResponse = serde_json::from_value(serde_json::json!({
+            "status": "dry-run", "balance": "0.00"
+        })).unwrap();
+        let live: ExecutorHealthResponse = serde_json::from_value(serde_json::json!({
+            "status": "ok", "balance": "65.00"
+        })).unwrap();
+
+        // Executor offline / dry-run → paper; live wallet → skutečný balance
+        assert_eq!(effective_paper_bankroll(Some(500.0), None), Some(500.0));
+        assert_eq!(effective_paper_bankroll(Some(500.0), Some(&dry_run)), Some(500.0));
+        assert_eq!(effective_paper_bankroll(Some(500.0), Some(&live)), None);
+        assert_eq!(effective_paper_bankroll(None, None), None);
+
+        let bankroll = effective_paper_bankroll(Some(500.0), Some(&dry_run)).unwrap_or(65.0);
+        assert!((dynamic_base_stake(bankroll, "cs2") - dynamic_base_stake(500.0, "cs2")).abs() < 1e-9);
+        assert!(dynamic_base_stake(bankroll, "cs2") > dynamic_base_stake(65.0, "cs2"));
+        // Exposure capy jedou z paper hodnoty
+        let (per_bet_frac, _, _, _, _) = get_exposure_caps(bankroll);
+        let stake = trim_stake(1_000.0, bankroll, 0.0, 0.0, 0.0, 0.0, 0.0, "cs2", 1.0, bankroll, "score_edge", 1.8, 30.0);
+        assert!(stake > 0.0 && stake <= bankroll * per_bet_frac + 1e-9);
+    }
+
+    #[test]
+    fn paper_mode_ends_once_live_executor_answers_and_restores_file_sod() {
+        let health = |status: &str, balance: &str| -> ExecutorHealthResponse {
+            serde_json::from_value(serde_json::json!({"status": status, "balance": balance})).unwrap()
+        };
+
+        // Executor offline při startu → paper; SOD z daily_pnl.json odložený
+        let mut paper = PaperMode::new(Some(500.0));
+        paper.real_sod = Some(80.0);
+        assert_eq!(paper.observe(None), None);
+        assert_eq!(paper.observe(Some(&health("dry-run", "0.00"))), None);
+        assert_eq!(paper.observe(Some(&health("ok", "?"))), None);
+        assert_eq!(paper.bankroll, Some(500.0));
+
+        // Live wallet → konec paper režimu, skutečný bankroll + původní SOD
+        assert_eq!(paper.observe(Some(&health("ok", "72.50"))), Some((72.5, 80.0)));
+        assert_eq!(paper.bankroll, None);
+        // Pozdější výpadek executoru zpět do paper nepřepne
+        assert_eq!(paper.observe(None), None);
+        assert_eq!(paper.bankroll, None);
+
+        // Bez SOD ze souboru je SOD live balance
+        let mut fresh = PaperMode::new(Some(500.0));
+        assert_eq!(fresh.observe(Some(&health("ok", "65.00"))), Some((65.0, 65.0)));
+    }
+
+    #[test]
+    fn cross_market_base_dedup_blocks_bo1_match_winner_after_map1_bet() {
+        use std::collections::HashSet;
+
+        let mut base_matches = HashSet::new();
+        let map_winners = HashSet::new();
+        let base = "cs2::alpha_vs_beta".to_string();
+
+        assert!(!cross_market_base_dedup_block(&base, false, true, &base_matches, &map_winners));
+        base_matches.insert(base.clone());
+        assert!(cross_market_base_dedup_block(&base, false, true, &base_matches, &map_winners));
+        assert!(cross_market_base_dedup_block(&base, true, true, &base_matches, &map_winners));
+    }
+}
+
+/// Sdílené test fixture: feed-hub `/state` odpověď a aktivní sázka.
+#[cfg(test)]
+mod test_fixtures {
+    use super::{ActiveBet, StateResponse};
+    use chrono::{DateTime, Utc};
+    use serde_json::{json, Value};
+
+    /// Builder `StateResponse`; sport řádku = prefix `match_key`, počty v hlavičce se dopočítají.
+    /// `live_field` / `odds_field` / `odds_seen_at` upravují naposledy přidaný řádek.
+    #[derive(Default)]
+    pub(super) struct StateBuilder {
+        live: Vec<Value>,
+        odds: Vec<Value>,
+    }
+
+    fn sport_of(match_key: &str) -> &str {
+        match_key.split("::").next().unwrap_or(match_key)
+    }
+
+    impl StateBuilder {
+        /// Live zápas se statusem "live".
+        pub(super) fn live(mut self, match_key: &str, teams: (&str, &str), score: (i32, i32)) -> Self {
+            self.live.push(json!({
+                "match_key": match_key,
+                "source": "chance",
+                "payload": {
+                    "sport": sport_of(match_key),
+                    "team1": teams.0,
+                    "team2": teams.1,
+                    "score1": score.0,
+                    "score2": score.1,
+                    "status": "live"
+                }
+            }));
+            self
+        }
+
+        /// Azuro trh s condition `cond_{market}` a outcome ID o1 / o2.
+        pub(super) fn azuro(self, match_key: &str, market: &str, teams: (&str, &str), odds: (f64, f64)) -> Self {
+            self.market(match_key, "azuro_polygon", market, teams, odds)
+                .odds_field("condition_id", format!("cond_{}", market))
+                .odds_field("outcome1_id", "o1")
+                .odds_field("outcome2_id", "o2")
+        }
+
+        /// Kurzy bookmakera `bookmaker` (bez execution dat), viděné teď.
+        pub(super) fn market(mut self, match_key: &str, bookmaker: &str, market: &str, teams: (&str, &str), odds: (f64, f64)) -> Self {
+            let source = if bookmaker.starts_with("azuro") { "azuro" } else { bookmaker };
+            self.odds.push(json!({
+                "match_key": match_key,
+                "source": source,
+                "seen_at": Utc::now().to_rfc3339(),
+                "payload": {
+                    "sport": sport_of(match_key),
+                    "bookmaker": bookmaker,
+                    "market": market,
+                    "team1": teams.0,
+                    "team2": teams.1,
+                    "odds_team1": odds.0,
+                    "odds_team2": odds.1
+                }
+            }));
+            self
+        }
+
+        pub(super) fn live_field(mut self, key: &str, value: impl Into<Value>) -> Self {
+            self.live.last_mut().expect("live row")["payload"][key] = value.into();
+            self
+        }
+
+        pub(super) fn odds_field(mut self, key: &str, value: impl Into<Value>) -> Self {
+            self.odds.last_mut().expect("odds row")["payload"][key] = value.into();
+            self
+        }
+
+        pub(super) fn odds_seen_at(mut self, seen_at: DateTime<Utc>) -> Self {
+            self.odds.last_mut().expect("odds row")["seen_at"] = json!(seen_at.to_rfc3339());
+            self
+        }
+
+        pub(super) fn build(self) -> StateResponse {
+            serde_json::from_value(json!({
+                "ts": Utc::now().to_rfc3339(),
+                "connections": 1,
+                "live_items": self.live.len(),
+                "odds_items": self.odds.len(),
+                "fused_ready": self.live.len().min(self.odds.len()),
+                "live": self.live,
+                "odds": self.odds
+            }))
+            .expect("valid state fixture")
+        }
+    }
+
+    /// CS2 Alpha vs Beta match_winner sázka na Alpha @ 1.70 (bez token_id); zbytek přes `..active_bet()`.
+    pub(super) fn active_bet(bet_id: &str, amount_usd: f64) -> ActiveBet {
+        ActiveBet {
+            alert_id: 1,
+            bet_id: bet_id.to_string(),
+            match_key: "cs2::alpha_vs_beta".to_string(),
+            market_key: "match_winner".to_string(),
+            original_sport: Some("cs2".to_string()),
+            resolved_sport: Some("cs2".to_string()),
+            esports_family: Some("cs2".to_string()),
+            team1: "Alpha".to_string(),
+            team2: "Beta".to_string(),
+            value_team: "Alpha".to_string(),
+            amount_usd,
+            odds: 1.70,
+            placed_at: "2026-03-01T10:00:00Z".to_string(),
+            condition_id: "cond-1".to_string(),
+            outcome_id: "out-alpha".to_string(),
+            graph_bet_id: None,
+            token_id: None,
+            path: "score_edge".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod bo1_series_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_score_edges, is_bo1_series, parse_series_best_of, ExecutionVenues, ScoreTracker, StateResponse};
+    use std::collections::HashMap;
+
+    fn cs2_state(detailed_score: &str) -> StateResponse {
+        StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (10, 4))
+            .live_field("detailed_score", detailed_score)
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Alpha", "Beta"), (1.50, 2.60))
+            .azuro("cs2::alpha_vs_beta", "map1_winner", ("Alpha", "Beta"), (1.30, 3.40))
+            .build()
+    }
+
+    #[test]
+    fn series_format_and_detailed_score_detect_bo1() {
+        assert_eq!(parse_series_best_of(Some("bo1"), None), Some(1));
+        assert_eq!(parse_series_best_of(None, Some("Lepší ze 3 | 1.mapa - 5:3")), Some(3));
+        assert_eq!(parse_series_best_of(None, Some("Best of 1")), Some(1));
+        assert_eq!(parse_series_best_of(None, Some("bonus 1")), None);
+        assert!(is_bo1_series(None, Some("Lepší ze 1 | 1.mapa - 10:4")));
+        assert!(!is_bo1_series(Some("BO3"), Some("Lepší ze 1 | 1.mapa - 10:4")));
+        assert!(!is_bo1_series(None, Some("R:10-4 M:0-0")));
+    }
+
+    #[test]
+    fn bo1_round_lead_bets_match_winner_directly() {
+        let state = cs2_state("Lepší ze 1 | 1.mapa - 10:4");
+        let edges = find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1);
+        let edge = &edges[0];
+        assert_eq!(edge.market_key, "match_winner");
+        assert!(edge.series_bo1);
+        assert_eq!(edge.leading_side, 1);
+        assert!(edge.cs2_map_confidence.is_some());
+    }
+
+    #[test]
+    fn bo3_round_lead_still_prefers_map_winner() {
+        let state = cs2_state("Lepší ze 3 | 1.mapa - 10:4");
+        let edges = find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].market_key, "map1_winner");
+        assert!(!edges[0].series_bo1);
+    }
+}
+
+#[cfg(test)]
+mod score_edge_ordering_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{
+        find_score_edges, format_score_edge_alert, ExecutionVenues, ScoreCeilings, ScoreTracker, StateResponse,
+    };
+    use std::collections::HashMap;
+
+    /// Bo1 CS2 zápasy (match_key, skóre) se stejnými kurzy — různé edge i shody.
+    fn multi_match_state(matches: &[(&str, i32, i32)]) -> StateResponse {
+        matches.iter().fold(StateBuilder::default(), |state, (key, s1, s2)| state
+            .live(key, ("Alpha", "Beta"), (*s1, *s2))
+            .live_field("detailed_score", format!("Lepší ze 1 | 1.mapa - {}:{}", s1, s2))
+            .azuro(key, "match_winner", ("Alpha", "Beta"), (1.50, 2.60))
+            .odds_field("condition_id", format!("cond_{}", key)))
+        .build()
+    }
+
+    #[test]
+    fn score_edges_are_ordered_deterministically_across_runs() {
+        let state = multi_match_state(&[
+            ("cs2::delta_vs_echo", 10, 4),
+            ("cs2::alpha_vs_beta", 10, 4),
+            ("cs2::golf_vs_hotel", 11, 3),
+            ("cs2::charlie_vs_foxtrot", 10, 4),
+        ]);
+
+        let run = || -> Vec<(String, String)> {
+            find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default())
+                .iter()
+                .map(|e| (e.match_key.clone(), format!("{}:{:.6}", e.confidence, e.edge_pct)))
+                .collect()
+        };
+        let first = run();
+        assert_eq!(first.len(), 4);
+        // Větší náskok = vyšší edge jde první, shody podle match_key
+        let keys: Vec<&str> = first.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["cs2::golf_vs_hotel", "cs2::alpha_vs_beta", "cs2::charlie_vs_foxtrot", "cs2::delta_vs_echo"]);
+        // Každý běh má vlastní HashMap seed → pořadí se nesmí lišit
+        for _ in 0..20 {
+            assert_eq!(run(), first);
+        }
+    }
+
+    #[test]
+    fn custom_score_ceiling_rejects_and_accepts_scores() {
+        let state = multi_match_state(&[("cs2::alpha_vs_beta", 10, 4), ("cs2::golf_vs_hotel", 11, 3)]);
+        let edges_with = |ceiling: Option<i32>| -> Vec<String> {
+            let mut tracker = ScoreTracker::new();
+            if let Some(max) = ceiling {
+                tracker.score_ceilings.per_sport.insert("cs2".to_string(), max);
+            }
+            find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default())
+                .into_iter()
+                .map(|e| e.match_key)
+                .collect()
+        };
+
+        // Default cs2 = 40 → oba zápasy projdou
+        assert_eq!(edges_with(None).len(), 2);
+        // Strop 10 → 11-3 je "garbage", 10-4 projde
+        assert_eq!(edges_with(Some(10)), ["cs2::alpha_vs_beta"]);
+        // Strop 9 → nic
+        assert!(edges_with(Some(9)).is_empty());
+
+        let defaults = ScoreCeilings::default();
+        assert_eq!(defaults.max_for("football"), 8);
+        assert_eq!(defaults.max_for("boxing"), 5);
+        assert_eq!(defaults.max_for("unknown"), 999);
+    }
+
+    #[test]
+    fn swapped_azuro_team_order_keeps_leading_name_odds_and_outcome_together() {
+        // Live: Alpha (team1) vede 10-4; Azuro má týmy v opačném pořadí
+        let state = StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (10, 4))
+            .live_field("detailed_score", "Lepší ze 1 | 1.mapa - 10:4")
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Beta", "Alpha"), (2.60, 1.50))
+            .odds_field("outcome1_id", "o_beta")
+            .odds_field("outcome2_id", "o_alpha")
+            .build();
+
+        let edges = find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1);
+        let edge = &edges[0];
+        let leading = edge.leading();
+        assert_eq!(leading.team, "Alpha");
+        assert_eq!(leading.odds, 1.50);
+        assert_eq!(leading.outcome_id, Some("o_alpha"));
+        assert_eq!(leading.azuro_side, 2);
+        assert_eq!(edge.outcome1_id.as_deref(), Some("o_alpha"));
+
+        let alert = format_score_edge_alert(edge, 1);
+        assert!(alert.contains("Pick: <b>Alpha</b> @ <b>1.50</b>"), "{}", alert);
+    }
+}
+
+#[cfg(test)]
+mod skip_reason_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_score_edges, ExecutionVenues, ScoreTracker, SkipReason, StateResponse};
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    /// CS2 Alpha vede 10-4; `odds` = (market, odds_team1, odds_team2, stáří v s).
+    fn cs2_state(detailed_score: &str, odds: &[(&str, f64, f64, i64)]) -> StateResponse {
+        let live = StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (10, 4))
+            .live_field("detailed_score", detailed_score);
+        odds.iter().fold(live, |state, (market, odds_team1, odds_team2, age_secs)| state
+            .azuro("cs2::alpha_vs_beta", market, ("Alpha", "Beta"), (*odds_team1, *odds_team2))
+            .odds_seen_at(Utc::now() - chrono::Duration::seconds(*age_secs)))
+        .build()
+    }
+
+    fn run(state: &StateResponse) -> ScoreTracker {
+        let mut tracker = ScoreTracker::new();
+        find_score_edges(state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        tracker
+    }
+
+    fn count(tracker: &ScoreTracker, reason: SkipReason) -> u64 {
+        tracker.skip_tally.counts.get(&reason).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn stale_map_winner_odds_are_tallied() {
+        let tracker = run(&cs2_state("Lepší ze 3 | 1.mapa - 10:4", &[
+            ("match_winner", 1.50, 2.60, 0),
+            ("map1_winner", 1.30, 3.40, 3600),
+        ]));
+        assert_eq!(count(&tracker, SkipReason::StaleOdds), 1);
+        assert_eq!(count(&tracker, SkipReason::BelowThreshold), 0);
+    }
+
+    #[test]
+    fn priced_in_lead_is_tallied_below_min_edge() {
+        // Azuro už lead započítal (implied ~99 %) → edge záporný
+        let tracker = run(&cs2_state("Lepší ze 1 | 1.mapa - 10:4", &[("match_winner", 1.01, 30.0, 0)]));
+        assert_eq!(count(&tracker, SkipReason::BelowThreshold), 1);
+        assert_eq!(tracker.skip_tally.by_label().get("below_min_edge"), Some(&1));
+    }
+
+    #[test]
+    fn garbage_score_is_tallied_as_score_sanity() {
+        // FlashScore DOM concatenation: 714-0 přes hokejový strop
+        let state = StateBuilder::default()
+            .live("hockey::home_vs_away", ("Home", "Away"), (714, 0))
+            .build();
+        let tracker = run(&state);
+        assert_eq!(count(&tracker, SkipReason::ScoreSanity), 1);
+        assert_eq!(count(&tracker, SkipReason::BelowThreshold), 0);
+    }
+}
+
+#[cfg(test)]
+mod cs2_map_rollover_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_score_edges, is_cs2_legit_map_rollover, ExecutionVenues, ScoreTracker, StateResponse};
+    use chrono::Utc;
+    use esports_monitor::clock::{Clock, MockClock};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const KEY: &str = "cs2::alpha_vs_beta";
+
+    fn live_state(score1: i32, score2: i32, detailed_score: &str) -> StateResponse {
+        StateBuilder::default()
+            .live(KEY, ("Alpha", "Beta"), (score1, score2))
+            .live_field("detailed_score", detailed_score)
+            .build()
+    }
+
+    /// Tracker, který naposledy viděl overtime konec 1. mapy 19-17.
+    fn after_overtime_map() -> ScoreTracker {
+        let mut tracker = ScoreTracker::new();
+        tracker.prev_scores.insert(KEY.to_string(), (19, 17, Utc::now() - chrono::Duration::seconds(30)));
+        tracker
+    }
+
+    /// (prev, nové skóre, detailed_score, series_format) → rollover?
+    fn rollover(prev: (i32, i32), score: (i32, i32), detail: &str, series_format: Option<&str>) -> bool {
+        is_cs2_legit_map_rollover(prev.0, prev.1, score.0, score.1, score.0, score.1, Some(detail), series_format, Some("cs2"))
+    }
+
+    #[test]
+    fn series_increment_with_round_reset_is_rollover() {
+        assert!(rollover((19, 17), (1, 0), "Lepší ze 3 | 2.mapa - 19:17, 0:0", None));
+        assert!(rollover((13, 7), (1, 0), "R:0-0 M:1-0", Some("bo3")));
+        assert!(rollover((9, 13), (1, 0), "Lepší ze 3 | 3.mapa - 13:6, 9:13, 1:0", None));
+        // Rollover zachycený až po pár kolech nové mapy
+        assert!(rollover((19, 17), (3, 2), "Lepší ze 3 | 2.mapa - 19:17, 3:2", None));
+        // Formát neznámý, ale detail mapu i dohranou mapu nese
+        assert!(rollover((19, 17), (1, 0), "2.mapa - 19:17, 0:0", None));
+
+        // Bez posunu série / jiná dohraná mapa / nedohraná mapa / Bo1 / mapa za koncem série
+        assert!(!rollover((19, 17), (1, 0), "Lepší ze 3 | 1.mapa - 1:0", None));
+        assert!(!rollover((19, 17), (1, 0), "Lepší ze 3 | 2.mapa - 13:5, 0:0", None));
+        assert!(!rollover((10, 4), (1, 0), "Lepší ze 3 | 2.mapa - 10:4, 0:0", None));
+        assert!(!rollover((19, 17), (1, 0), "Lepší ze 1 | 2.mapa - 19:17, 0:0", None));
+        assert!(!rollover((13, 7), (1, 0), "R:0-0 M:0-0", Some("bo3")));
+        assert!(!rollover((13, 7), (1, 0), "R:0-0 M:3-0", Some("bo3")));
+        // Neznámý formát ≠ Bo3: samotné mapové skóre 1-0 může být i konec Bo1
+        assert!(!rollover((13, 7), (1, 0), "R:0-0 M:1-0", None));
+    }
+
+    #[test]
+    fn clean_rollover_starts_tracking_new_map() {
+        let mut tracker = after_overtime_map();
+        let state = live_state(1, 0, "Lepší ze 3 | 2.mapa - 19:17, 0:0");
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+
+        let (s1, s2, _) = tracker.prev_scores[KEY];
+        assert_eq!((s1, s2), (1, 0), "nová mapa nahradí 19-17");
+        assert!(!tracker.edge_cooldown.contains_key(KEY));
+        assert!(!tracker.cs2_glitch_quarantine_until.contains_key(KEY));
+    }
+
+    #[test]
+    fn decision_audit_goes_to_injected_ledger_only() {
+        let dir = std::env::temp_dir().join(format!("alert_bot_audit_ledger_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let ledger = dir.join("ledger.jsonl");
+        let state = live_state(1, 0, "Lepší ze 3 | 2.mapa - 19:17, 0:0");
+
+        let mut tracker = after_overtime_map();
+        tracker.audit_ledger = Some(ledger.clone());
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        let audits: Vec<serde_json::Value> = std::fs::read_to_string(&ledger).unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert!(audits.iter().any(|a| a["event"] == "ESPORTS_SCORE_DECISION_AUDIT" && a["decision"] == "rollover_tracked"));
+
+        // Bez ledgeru (default) se nic nepíše
+        std::fs::remove_file(&ledger).unwrap();
+        find_score_edges(&state, &mut after_overtime_map(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert!(!ledger.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backward_glitch_without_series_increment_stays_held() {
+        let mut tracker = after_overtime_map();
+        let state = live_state(1, 0, "Lepší ze 3 | 1.mapa - 1:0");
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+
+        let (s1, s2, _) = tracker.prev_scores[KEY];
+        assert_eq!((s1, s2), (19, 17), "glitch nesmí přepsat sledované skóre");
+        assert!(tracker.edge_cooldown.contains_key(KEY));
+    }
+
+    #[test]
+    fn hold_audit_cooldown_follows_injected_clock() {
+        let dir = std::env::temp_dir().join(format!("alert_bot_hold_cooldown_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let ledger = dir.join("ledger.jsonl");
+        let clock = MockClock::new();
+        let mut tracker = ScoreTracker::with_clock(Arc::new(clock.clone()));
+        tracker.prev_scores.insert(KEY.to_string(), (19, 17, clock.now_utc() - chrono::Duration::seconds(30)));
+        tracker.audit_ledger = Some(ledger.clone());
+        let state = live_state(1, 0, "Lepší ze 3 | 1.mapa - 1:0");
+        let holds = || std::fs::read_to_string(&ledger).unwrap_or_default()
+            .lines()
+            .filter(|l| l.contains("hold_candidate"))
+            .count();
+
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(tracker.edge_cooldown[KEY], clock.now_utc());
+        assert_eq!(holds(), 1);
+
+        // 44s: stejný hold je duplicita, cooldown se posune na "teď" mock clocku
+        clock.advance_secs(44);
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(holds(), 1);
+        assert_eq!(tracker.edge_cooldown[KEY], clock.now_utc());
+
+        // 45s od posledního holdu → cooldown vypršel, audit znovu
+        clock.advance_secs(45);
+        find_score_edges(&state, &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(holds(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod sport_kill_switch_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_score_edges, ExecutionVenues, ScoreTracker, SportKillSwitch, StateResponse};
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    /// Live hokejové zápasy bez Azuro kurzů — stačí na průchod score sanity checkem.
+    fn hockey_state(scores: &[(i32, i32)]) -> StateResponse {
+        scores.iter().enumerate().fold(StateBuilder::default(), |state, (i, score)| state
+            .live(&format!("hockey::home{}_vs_away{}", i, i), (&format!("Home{}", i), &format!("Away{}", i)), *score))
+        .build()
+    }
+
+    fn run(scores: &[(i32, i32)]) -> ScoreTracker {
+        let mut tracker = ScoreTracker::new();
+        find_score_edges(&hockey_state(scores), &mut tracker, &mut HashMap::new(), &ExecutionVenues::default());
+        tracker
+    }
+
+    #[test]
+    fn high_garbage_rate_disables_sport_until_enable() {
+        // 6 z 8 skóre přes hokejový strop (10) → 75 % rejectů
+        let mut tracker = run(&[(714, 0), (19, 45), (3, 88), (52, 1), (41, 2), (2, 33), (2, 1), (3, 0)]);
+        assert!(tracker.sport_kill.is_disabled("hockey"));
+        assert!(!tracker.sport_kill.is_disabled("football"));
+
+        let notices = tracker.sport_kill.take_notices();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].0, "hockey");
+        assert!(tracker.sport_kill.take_notices().is_empty(), "notifikace jen jednou");
+
+        assert!(tracker.sport_kill.enable("hockey"));
+        assert!(!tracker.sport_kill.is_disabled("hockey"));
+        assert!(!tracker.sport_kill.enable("hockey"));
+    }
+
+    #[test]
+    fn occasional_garbage_or_too_few_samples_keep_sport_enabled() {
+        let tracker = run(&[(714, 0), (2, 1), (3, 0), (1, 0), (4, 2), (2, 0), (1, 3), (0, 2)]);
+        assert!(!tracker.sport_kill.is_disabled("hockey"));
+
+        // 100 % garbage, ale pod min_samples
+        let tracker = run(&[(714, 0), (19, 45)]);
+        assert!(!tracker.sport_kill.is_disabled("hockey"));
+    }
+
+    #[test]
+    fn samples_outside_window_are_forgotten() {
+        let mut switch = SportKillSwitch { min_samples: 4, ..SportKillSwitch::default() };
+        let t0 = Utc::now();
+        for i in 0..3 {
+            switch.record("tennis", &format!("tennis::a{}_vs_b{}", i, i), true, t0);
+        }
+        // Čtvrtý vzorek až po okně → staré rejecty vypadly, 1 vzorek < min_samples
+        switch.record("tennis", "tennis::a3_vs_b3", true, t0 + chrono::Duration::seconds(switch.window_secs + 1));
+        assert!(!switch.is_disabled("tennis"));
+    }
+
+    #[test]
+    fn repeated_polls_of_one_garbage_match_count_once() {
+        let mut switch = SportKillSwitch { min_samples: 4, ..SportKillSwitch::default() };
+        let t0 = Utc::now();
+        for i in 0..20 {
+            switch.record("hockey", "hockey::long_vs_match", true, t0 + chrono::Duration::seconds(i));
+        }
+        for i in 0..3 {
+            switch.record("hockey", &format!("hockey::ok{}_vs_fine{}", i, i), false, t0);
+        }
+        // 1 garbage zápas ze 4 → 25 % < 50 %, i když garbage skóre přišlo 20×
+        assert!(!switch.is_disabled("hockey"));
+    }
+}
+
+#[cfg(test)]
+mod betting_day_tests {
+    use super::BettingDay;
+    use chrono::{DateTime, Utc};
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn default_rolls_over_at_utc_midnight() {
+        let day = BettingDay::parse(None, None);
+        assert_eq!(day, BettingDay::default());
+        assert_eq!(day.date_for(at("2026-03-09T23:59:59Z")), "2026-03-09");
+        assert_eq!(day.date_for(at("2026-03-10T00:00:00Z")), "2026-03-10");
+    }
+
+    #[test]
+    fn prague_cutoff_six_follows_local_time_across_dst() {
+        let day = BettingDay::parse(Some("Europe/Prague"), Some("6"));
+        // zima: CET = UTC+1 → hranice 05:00Z
+        assert_eq!(day.date_for(at("2026-03-10T04:59:59Z")), "2026-03-09");
+        assert_eq!(day.date_for(at("2026-03-10T05:00:00Z")), "2026-03-10");
+        // léto: CEST = UTC+2 → hranice 04:00Z
+        assert_eq!(day.date_for(at("2026-07-10T03:59:59Z")), "2026-07-09");
+        assert_eq!(day.date_for(at("2026-07-10T04:00:00Z")), "2026-07-10");
+    }
+
+    #[test]
+    fn negative_offset_midnight_lags_utc_date() {
+        let day = BettingDay::parse(Some("America/New_York"), Some("0"));
+        // EDT = UTC-4: 03:59Z je ještě předchozí lokální den
+        assert_eq!(day.date_for(at("2026-03-10T03:59:59Z")), "2026-03-09");
+        assert_eq!(day.date_for(at("2026-03-10T04:30:00Z")), "2026-03-10");
+    }
+
+    #[test]
+    fn ledger_timestamps_are_bucketed_by_betting_day() {
+        let day = BettingDay::parse(Some("Europe/Prague"), Some("6"));
+        assert!(day.ts_in_day("2026-03-10T04:30:00Z", "2026-03-09"));
+        assert!(!day.ts_in_day("2026-03-10T04:30:00Z", "2026-03-10"));
+        assert!(day.ts_in_day("2026-03-10T06:30:00+01:00", "2026-03-10"));
+        // neparsovatelný ts → prefix
+        assert!(day.ts_in_day("2026-03-10 legacy", "2026-03-10"));
+    }
+
+    #[test]
+    fn invalid_config_falls_back_to_utc_midnight() {
+        assert_eq!(BettingDay::parse(Some("Mars/Olympus"), Some("25")), BettingDay::default());
+    }
+}
+
+#[cfg(test)]
+mod bot_status_tests {
+    use super::test_fixtures::active_bet;
+    use super::{ActiveBet, SkipReason, SkipTally, SportKillSwitch, StatusSources};
+    use std::collections::HashMap;
+
+    fn bet(id: &str, amount: f64) -> ActiveBet {
+        ActiveBet { market_key: "map_winner".to_string(), odds: 1.8, ..active_bet(id, amount) }
+    }
+
+    #[test]
+    fn snapshot_reflects_injected_loop_state() {
+        let bets = vec![bet("b1", 3.0), bet("b2", 2.0)];
+        let mut kill = SportKillSwitch::default();
+        kill.disabled.insert("hockey".to_string(), "test".to_string());
+        kill.disabled.insert("football".to_string(), "test".to_string());
+        let exposure = HashMap::from([
+            ("cs2".to_string(), 5.0),
+            ("tennis".to_string(), 0.0),
+        ]);
+        let mut skips = SkipTally::default();
+        skips.record(SkipReason::StaleOdds);
+        skips.record(SkipReason::StaleOdds);
+        skips.record(SkipReason::BelowThreshold);
+
+        let status = StatusSources {
+            bankroll: 120.0,
+            sod_bankroll: 100.0,
+            daily_wagered: 12.0,
+            daily_returned: 4.5,
+            daily_limit_override: Some(40.0),
+            auto_bet_count: 7,
+            active_bets: &bets,
+            inflight_wagered_total: 2.0,
+            safe_mode: true,
+            sport_kill: &kill,
+            sport_exposure: &exposure,
+            skip_tally: &skips,
+        }.snapshot();
+
+        assert_eq!(status.bankroll_usd, 120.0);
+        assert_eq!(status.sod_bankroll_usd, 100.0);
+        assert!((status.daily_net_usd + 7.5).abs() < 1e-9);
+        assert_eq!(status.daily_loss_limit_usd, 40.0);
+        assert!(status.daily_limit_override);
+        assert_eq!(status.active_bets, 2);
+        assert_eq!(status.inflight_total_usd, 2.0);
+        assert!(status.safe_mode);
+        assert_eq!(status.disabled_sports, vec!["football", "hockey"]);
+        assert_eq!(status.sport_exposure_usd.len(), 1, "nulová expozice se nevypisuje");
+
+        let text = status.summary_text();
+        assert!(text.contains("P/L: -7.50 USDT"), "{}", text);
+        assert!(text.contains("Loss: $7.50 / $40.00 ⚡"), "{}", text);
+        assert!(text.contains("Expozice: cs2 $5.00"), "{}", text);
+        assert!(text.contains("SAFE MODE"), "{}", text);
+        assert!(text.contains("Vypnuté sporty: football, hockey"), "{}", text);
+        assert!(text.contains("Skipy: below_min_edge 1 | stale_odds 2"), "{}", text);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["auto_bet_count"], 7);
+        assert_eq!(json["sport_exposure_usd"]["cs2"], 5.0);
+        assert_eq!(json["skip_reasons"]["stale_odds"], 2);
+    }
+}
+
+#[cfg(test)]
+mod executor_reject_class_tests {
+    use super::ExecutorRejectClass;
+
+    fn class(error: &str) -> ExecutorRejectClass {
+        ExecutorRejectClass::classify(Some(error), None)
+    }
+
+    #[test]
+    fn transient_condition_pause_is_retried() {
+        for err in [
+            "Condition is paused",
+            "ConditionNotActive: condition 100 is not active",
+            "Condition does not exist yet",
+            "market suspended",
+        ] {
+            assert_eq!(class(err), ExecutorRejectClass::ConditionPaused, "{}", err);
+            assert!(class(err).is_retryable(), "{}", err);
+        }
+    }
+
+    #[test]
+    fn permanent_failures_abort_immediately() {
+        for (err, expected) in [
+            ("Insufficient balance: have 1.2 USDT", ExecutorRejectClass::Fatal),
+            ("ERC20: insufficient allowance", ExecutorRejectClass::Fatal),
+            ("execution reverted: outcome settled", ExecutorRejectClass::Fatal),
+            ("nonce too low", ExecutorRejectClass::Fatal),
+            ("condition paused — insufficient balance", ExecutorRejectClass::Fatal),
+            ("Live is not available for this condition", ExecutorRejectClass::ConditionDead),
+            ("Duplicate bet (dedup)", ExecutorRejectClass::Dedup),
+            ("Odds too low: real odds 1.71 < min odds 1.80", ExecutorRejectClass::MinOdds),
+            ("socket hang up", ExecutorRejectClass::Unknown),
+        ] {
+            assert_eq!(class(err), expected, "{}", err);
+            assert!(!class(err).is_retryable(), "{}", err);
+        }
+    }
+
+    #[test]
+    fn state_field_contributes_and_reason_codes_stay_stable() {
+        assert_eq!(
+            ExecutorRejectClass::classify(Some("Bet failed"), Some("Resolved")),
+            ExecutorRejectClass::Fatal
+        );
+        assert_eq!(
+            ExecutorRejectClass::classify(Some("Bet failed"), Some("Paused")),
+            ExecutorRejectClass::ConditionPaused
+        );
+        assert_eq!(ExecutorRejectClass::ConditionPaused.reason_code(), "ConditionNotRunning");
+        assert_eq!(ExecutorRejectClass::ConditionDead.reason_code(), "ConditionNotRunning");
+        assert!(ExecutorRejectClass::ConditionDead.is_condition_state());
+        assert!(!ExecutorRejectClass::MinOdds.is_condition_state());
+    }
+}
+
+#[cfg(test)]
+mod frozen_score_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_score_edges, ExecutionVenues, ScoreTracker, StateResponse, FROZEN_SCORE_WINDOW_SECS};
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn bo1_state(score1: i32, score2: i32) -> StateResponse {
+        StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (score1, score2))
+            .live_field("detailed_score", format!("Lepší ze 1 | 1.mapa - {}:{}", score1, score2))
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Alpha", "Beta"), (1.50, 2.60))
+            .build()
+    }
+
+    #[test]
+    fn frozen_live_score_stops_producing_edges_until_it_moves() {
+        let key = "cs2::alpha_vs_beta";
+        let mut tracker = ScoreTracker::new();
+        let mut resync = HashMap::new();
+
+        let edges = find_score_edges(&bo1_state(10, 4), &mut tracker, &mut resync, &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1, "fresh 10-4 lead produces an edge");
+
+        // Skóre stojí déle než window; feed výpadek → zápas znovu viděn jako na startu
+        let stale = Utc::now() - chrono::Duration::seconds(FROZEN_SCORE_WINDOW_SECS + 60);
+        tracker.score_changed_at.insert(key.to_string(), (10, 4, stale));
+        tracker.prev_scores.remove(key);
+        tracker.edge_cooldown.remove(key);
+        assert!(find_score_edges(&bo1_state(10, 4), &mut tracker, &mut resync, &ExecutionVenues::default()).is_empty());
+        assert!(tracker.frozen_score_secs(key, 10, 4, Utc::now()).is_some());
+
+        // Skóre se pohnulo → edge znovu
+        let edges = find_score_edges(&bo1_state(11, 4), &mut tracker, &mut resync, &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1);
+        assert_eq!((edges[0].score1, edges[0].score2), (11, 4));
+        assert!(tracker.frozen_score_secs(key, 11, 4, Utc::now()).is_none());
+    }
+
+    #[test]
+    fn zero_window_disables_the_switch() {
+        let mut tracker = ScoreTracker::new();
+        tracker.frozen_window_secs = 0;
+        let stale = Utc::now() - chrono::Duration::seconds(24 * 3600);
+        tracker.score_changed_at.insert("cs2::alpha_vs_beta".to_string(), (10, 4, stale));
+        assert!(tracker.frozen_score_secs("cs2::alpha_vs_beta", 10, 4, Utc::now()).is_none());
+    }
+}
+
+#[cfg(test)]
+mod too_late_guard_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{
+        cs2_rounds_to_map_win, find_score_edges, tennis_games_to_match_win, ExecutionVenues, ScoreTracker,
+        StateResponse, TooLateGuard,
+    };
+    use std::collections::HashMap;
+
+    fn cs2_bo1_state(score1: i32, score2: i32) -> StateResponse {
+        StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (score1, score2))
+            .live_field("detailed_score", format!("Lepší ze 1 | 1.mapa - {}:{}", score1, score2))
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Alpha", "Beta"), (1.50, 2.60))
+            .build()
+    }
+
+    fn edges_at(score1: i32, score2: i32, guard: TooLateGuard) -> usize {
+        let mut tracker = ScoreTracker::new();
+        tracker.too_late = guard;
+        find_score_edges(&cs2_bo1_state(score1, score2), &mut tracker, &mut HashMap::new(), &ExecutionVenues::default()).len()
+    }
+
+    #[test]
+    fn cs2_map_point_is_skipped() {
+        assert_eq!(edges_at(12, 3, TooLateGuard::default()), 0);
+    }
+
+    #[test]
+    fn cs2_lead_short_of_map_point_proceeds() {
+        assert_eq!(edges_at(9, 3, TooLateGuard::default()), 1);
+    }
+
+    #[test]
+    fn zero_margin_disables_the_filter() {
+        let mut guard = TooLateGuard::default();
+        guard.margins.insert("cs2".to_string(), 0);
+        assert_eq!(edges_at(12, 3, guard), 1);
+    }
+
+    #[test]
+    fn rounds_to_resolution_follow_cs2_overtime() {
+        assert_eq!(cs2_rounds_to_map_win(12, 3), 1);
+        assert_eq!(cs2_rounds_to_map_win(9, 3), 4);
+        assert_eq!(cs2_rounds_to_map_win(13, 12), 3);
+        assert_eq!(cs2_rounds_to_map_win(15, 14), 1);
+        assert_eq!(cs2_rounds_to_map_win(16, 15), 3);
+    }
+
+    #[test]
+    fn tennis_set_point_only_counts_in_the_deciding_set() {
+        assert_eq!(tennis_games_to_match_win(1, 1, 3, Some("2.set - 6:4, 5:3 (15:30*)")), Some(1));
+        assert_eq!(tennis_games_to_match_win(2, 1, 3, Some("2.set - 4:6, 3:5")), Some(1));
+        assert_eq!(tennis_games_to_match_win(1, 1, 3, Some("2.set - 6:4, 5:5")), Some(2));
+        assert_eq!(tennis_games_to_match_win(1, 1, 3, Some("2.set - 6:4, 2:1")), Some(4));
+        // Bo5 1-0: ani vyhraný set zápas nerozhodne
+        assert_eq!(tennis_games_to_match_win(1, 1, 5, Some("2.set - 6:4, 5:3")), None);
+    }
+}
+
+#[cfg(test)]
+mod outcome_order_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{azuro_outcome_order, find_score_edges, ExecutionVenues, OutcomeOrder, ScoreTracker, StateResponse};
+    use std::collections::HashMap;
+
+    /// CS2 Bo1 9:3 pro Alpha, Azuro match_winner s danými outcome ID.
+    fn cs2_state(outcome1_id: &str, outcome2_id: &str) -> StateResponse {
+        StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (9, 3))
+            .live_field("detailed_score", "Lepší ze 1 | 1.mapa - 9:3")
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Alpha", "Beta"), (1.50, 2.60))
+            .odds_field("outcome1_id", outcome1_id)
+            .odds_field("outcome2_id", outcome2_id)
+            .build()
+    }
+
+    fn edge_order(outcome1_id: &str, outcome2_id: &str) -> OutcomeOrder {
+        let edges = find_score_edges(
+            &cs2_state(outcome1_id, outcome2_id),
+            &mut ScoreTracker::new(),
+            &mut HashMap::new(),
+            &ExecutionVenues::default(),
+        );
+        assert_eq!(edges.len(), 1);
+        edges[0].outcome_order
+    }
+
+    #[test]
+    fn dictionary_ordered_payload_is_verified() {
+        assert_eq!(edge_order("6995", "6996"), OutcomeOrder::Verified);
+    }
+
+    #[test]
+    fn reversed_outcome_ids_are_flagged() {
+        assert_eq!(edge_order("6996", "6995"), OutcomeOrder::Reversed);
+    }
+
+    #[test]
+    fn opaque_ids_cannot_be_verified() {
+        assert_eq!(edge_order("o1", "o2"), OutcomeOrder::Unverifiable);
+        assert_eq!(azuro_outcome_order(Some("7009"), Some("7012"), None), OutcomeOrder::Unverifiable);
+        assert_eq!(azuro_outcome_order(Some("7009"), None, None), OutcomeOrder::Unverifiable);
+    }
+
+    #[test]
+    fn reordered_edge_keeps_raw_payload_order_for_execution() {
+        // Azuro má týmy obráceně než live feed → edge přerovná ID do live pořadí (sestupně),
+        // ověření ale platí pro surový payload a anomálie ho přebírá z edge
+        let mut state = cs2_state("6995", "6996");
+        let payload = &mut state.odds[0].payload;
+        std::mem::swap(&mut payload.team1, &mut payload.team2);
+        std::mem::swap(&mut payload.odds_team1, &mut payload.odds_team2);
+        let edges = find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].outcome1_id.as_deref(), Some("6996"));
+        assert_eq!(edges[0].outcome2_id.as_deref(), Some("6995"));
+        assert_eq!(edges[0].outcome_draw_id, None);
+        assert_eq!(edges[0].outcome_order, OutcomeOrder::Verified);
+    }
+
+    #[test]
+    fn three_way_needs_draw_between_sides() {
+        assert_eq!(azuro_outcome_order(Some("1"), Some("3"), Some("2")), OutcomeOrder::Verified);
+        assert_eq!(azuro_outcome_order(Some("3"), Some("1"), Some("2")), OutcomeOrder::Reversed);
+        assert_eq!(azuro_outcome_order(Some("1"), Some("2"), Some("3")), OutcomeOrder::Unverifiable);
+    }
+}
+
+#[cfg(test)]
+mod mock_clock_tests {
+    use super::{rebet_allowed, ReBetState, ResyncState, ScoreTracker};
+    use esports_monitor::clock::{Clock, MockClock};
+    use std::sync::Arc;
+
+    #[test]
+    fn rebet_cooldown_opens_at_30s() {
+        let clock = MockClock::new();
+        let state = ReBetState::new("MEDIUM", 12.0, 1.0, &clock);
+
+        clock.advance_secs(29);
+        assert!(!rebet_allowed(&state, &clock, "HIGH", 15.0, 5.0, 5.0));
+        clock.advance_secs(1);
+        assert!(rebet_allowed(&state, &clock, "HIGH", 15.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn resync_freeze_needs_60s_and_two_agreements() {
+        let clock = MockClock::new();
+        let mut state = ResyncState::new(&clock);
+
+        clock.advance_secs(10);
+        assert!(!state.record_agreement(&clock));
+        assert!(!state.record_agreement(&clock));
+        assert!(state.is_frozen(&clock), "2 agreements, ale teprve 10s");
+
+        clock.advance_secs(49);
+        assert!(state.is_frozen(&clock));
+        clock.advance_secs(1);
+        assert!(!state.is_frozen(&clock));
+
+        state.record_mismatch(&clock);
+        assert!(state.is_frozen(&clock));
+    }
+
+    #[test]
+    fn tracker_cleanup_follows_injected_clock() {
+        let clock = MockClock::new();
+        let mut tracker = ScoreTracker::with_clock(Arc::new(clock.clone()));
+        tracker.prev_scores.insert("cs2::alpha_vs_beta".to_string(), (1, 0, clock.now_utc()));
+        tracker.edge_cooldown.insert("cs2::alpha_vs_beta".to_string(), clock.now_utc());
+
+        clock.advance_secs(1799);
+        tracker.cleanup();
+        assert!(tracker.prev_scores.contains_key("cs2::alpha_vs_beta"));
+
+        clock.advance_secs(2);
+        tracker.cleanup();
+        assert!(tracker.prev_scores.is_empty());
+        assert!(tracker.edge_cooldown.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod poll_cycle_tests {
+    use super::{
+        anomaly_alert_key, fetch_feed_state, record_placement_exposure, run_poll_cycle, score_edge_alert_key,
+        ExecutionVenues, ScoreTracker,
+    };
+    use chrono::Utc;
+    use std::collections::{HashMap, HashSet};
+
+    /// CS2 Bo1 10:4 (score edge) + tenis s rozjetými Azuro vs Tipsport odds (anomálie).
+    fn state_json() -> String {
+        let seen_at = Utc::now().to_rfc3339();
+        serde_json::json!({
+            "ts": seen_at,
+            "connections": 2,
+            "live_items": 2,
+            "odds_items": 3,
+            "fused_ready": 2,
+            "live": [
+                {
+                    "match_key": "cs2::alpha_vs_beta",
+                    "source": "chance",
+                    "payload": {
+                        "sport": "cs2",
+                        "team1": "Alpha",
+                        "team2": "Beta",
+                        "score1": 10,
+                        "score2": 4,
+                        "status": "live",
+                        "detailed_score": "Lepší ze 1 | 1.mapa - 10:4"
+                    }
+                },
+                {
+                    "match_key": "tennis::gamma_vs_delta",
+                    "source": "tipsport",
+                    "payload": {
+                        "sport": "tennis",
+                        "team1": "Gamma",
+                        "team2": "Delta",
+                        "score1": 0,
+                        "score2": 0,
+                        "status": "live"
+                    }
+                }
+            ],
+            "odds": [
+                {
+                    "match_key": "cs2::alpha_vs_beta",
+                    "source": "azuro",
+                    "seen_at": seen_at,
+                    "payload": {
+                        "sport": "cs2",
+                        "bookmaker": "azuro_polygon",
+                        "market": "match_winner",
+                        "team1": "Alpha",
+                        "team2": "Beta",
+                        "odds_team1": 1.50,
+                        "odds_team2": 2.60,
+                        "condition_id": "cond_cs2",
+                        "outcome1_id": "o1",
+                        "outcome2_id": "o2"
+                    }
+                },
+                {
+                    "match_key": "tennis::gamma_vs_delta",
+                    "source": "azuro",
+                    "seen_at": seen_at,
+                    "payload": {
+                        "sport": "tennis",
+                        "bookmaker": "azuro_polygon",
+                        "market": "match_winner",
+                        "team1": "Gamma",
+                        "team2": "Delta",
+                        "odds_team1": 1.6,
+                        "odds_team2": 2.3,
+                        "condition_id": "cond_tennis",
+                        "outcome1_id": "o1",
+                        "outcome2_id": "o2"
+                    }
+                },
+                {
+                    "match_key": "tennis::gamma_vs_delta",
+                    "source": "tipsport",
+                    "seen_at": seen_at,
+                    "payload": {
+                        "sport": "tennis",
+                        "bookmaker": "tipsport",
+                        "market": "match_winner",
+                        "team1": "Gamma",
+                        "team2": "Delta",
+                        "odds_team1": 1.4,
+                        "odds_team2": 2.9
+                    }
+                }
+            ]
+        })
+        .to_string()
+    }
+
+    /// Mock feed-hub: na každý request vrátí canned /state JSON.
+    async fn spawn_mock_feed_hub(body: String) -> String {
+        test_http::serve(move |_| test_http::json(&body)).await
+    }
+
+    #[tokio::test]
+    async fn one_cycle_against_mock_feed_hub_produces_edges_and_dedups() {
+        let url = spawn_mock_feed_hub(state_json()).await;
+        let client = reqwest::Client::new();
+        let venues = ExecutionVenues::default();
+        let mut tracker = ScoreTracker::new();
+        let mut resync = HashMap::new();
+        let mut condition_last_seen = HashMap::new();
+
+        let state = fetch_feed_state(&client, &url).await.expect("mock /state");
+        let signals = run_poll_cycle(&state, &mut tracker, &mut resync, &venues, &HashSet::new(), &mut condition_last_seen);
+
+        assert_eq!(signals.score_edges.len(), 1);
+        let edge = &signals.score_edges[0];
+        assert_eq!(edge.match_key, "cs2::alpha_vs_beta");
+        assert_eq!(edge.market_key, "match_winner");
+        assert_eq!(edge.leading_side, 1);
+
+        assert!(signals.anomalies.iter().any(|a| a.match_key == "tennis::gamma_vs_delta"
+            && a.azuro_bookmaker == "azuro_polygon"
+            && a.market_bookmaker == "tipsport"));
+        assert!(condition_last_seen.contains_key("cond_cs2"));
+        assert!(condition_last_seen.contains_key("cond_tennis"));
+
+        // Druhý cyklus: už alertované klíče se odfiltrují, počty před dedupem zůstávají
+        let already_alerted: HashSet<String> = signals.score_edges.iter().map(score_edge_alert_key)
+            .chain(signals.anomalies.iter().map(anomaly_alert_key))
+            .collect();
+        let state = fetch_feed_state(&client, &url).await.expect("mock /state");
+        let again = run_poll_cycle(&state, &mut tracker, &mut resync, &venues, &already_alerted, &mut condition_last_seen);
+        assert!(again.score_edges.is_empty());
+        assert!(again.anomalies.is_empty());
+        assert_eq!(again.total_anomalies, signals.total_anomalies);
+    }
+
+    #[test]
+    fn placement_exposure_accumulates_per_condition_match_and_sport() {
+        let (mut cond, mut by_match, mut by_sport) = (HashMap::new(), HashMap::new(), HashMap::new());
+        let cond_key = "cond_cs2".to_string();
+        record_placement_exposure(&mut cond, &mut by_match, &mut by_sport, Some(&cond_key), "cs2::alpha_vs_beta", "cs2", 1.5);
+        record_placement_exposure(&mut cond, &mut by_match, &mut by_sport, None, "cs2::alpha_vs_beta", "cs2", 0.5);
+
+        assert_eq!(cond.get("cond_cs2"), Some(&1.5));
+        assert_eq!(by_match.get("cs2::alpha_vs_beta"), Some(&2.0));
+        assert_eq!(by_sport.get("cs2"), Some(&2.0));
+    }
+}
+
+#[cfg(test)]
+mod bet_idempotency_tests {
+    use super::test_fixtures::active_bet;
+    use super::{read_executor_receipt, replay_already_booked, send_executor_bet, OrderReceipt, OrderRequest, Venue};
+    use chrono::{TimeZone, Utc};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn key_is_stable_within_minute_bucket_and_changes_with_inputs() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 1, 18, 30, 5).unwrap();
+        let t1 = Utc.with_ymd_and_hms(2026, 3, 1, 18, 30, 55).unwrap();
+        let t2 = Utc.with_ymd_and_hms(2026, 3, 1, 18, 31, 0).unwrap();
+        let key = OrderRequest::idempotency_key("100", "1", 3.0, t0.timestamp());
+        assert_eq!(key, OrderRequest::idempotency_key("100", "1", 3.0, t1.timestamp()));
+        assert_ne!(key, OrderRequest::idempotency_key("100", "1", 3.0, t2.timestamp()));
+        assert_ne!(key, OrderRequest::idempotency_key("100", "2", 3.0, t0.timestamp()));
+        assert_ne!(key, OrderRequest::idempotency_key("100", "1", 3.5, t0.timestamp()));
+    }
+
+    #[tokio::test]
+    async fn timed_out_post_that_succeeded_is_replayed_not_placed_twice() {
+        // Mock executor: první POST sázku umístí, ale odpověď pošle až po client timeoutu.
+        // Retry se stejným klíčem dostane původní odpověď s idempotentReplay.
+        let placed: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let seen_keys: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let (placed_srv, seen_srv) = (placed.clone(), seen_keys.clone());
+        let url = test_http::serve_async(move |req| {
+            let (placed_srv, seen_srv) = (placed_srv.clone(), seen_srv.clone());
+            async move {
+                let body: serde_json::Value = serde_json::from_str(req.body()).unwrap_or_default();
+                let key = body["idempotencyKey"].as_str().unwrap_or_default().to_string();
+                seen_srv.lock().unwrap().push(key.clone());
+                let replay = placed_srv.lock().unwrap().contains(&key);
+                let payload = if replay {
+                    serde_json::json!({"status": "ok", "betId": "bet-1", "state": "Accepted", "idempotentReplay": true})
+                } else {
+                    placed_srv.lock().unwrap().push(key);
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    serde_json::json!({"status": "ok", "betId": "bet-1", "state": "Accepted"})
+                };
+                test_http::json(payload.to_string())
+            }
+        }).await;
+
+        let order = OrderRequest::new(Venue::Azuro, None, "100", "1", 3.0, 1.01, Utc::now().timestamp());
+        let key = order.idempotency_key.clone();
+        let client = reqwest::Client::new();
+        let resp = send_executor_bet(&client, &url, &order, serde_json::json!({}), Duration::from_millis(150)).await.unwrap();
+        let br = read_executor_receipt(resp, &order).await.unwrap();
+
+        assert!(br.idempotent_replay);
+        assert!(br.error.is_none());
+        assert_eq!(br.bet_id.as_deref(), Some("bet-1"));
+        assert_eq!(placed.lock().unwrap().len(), 1);
+        assert_eq!(*seen_keys.lock().unwrap(), vec![key.clone(), key]);
+    }
+
+    #[test]
+    fn replay_is_booked_once() {
+        let replay = OrderReceipt {
+            bet_id: Some("bet-1".to_string()),
+            state: Some("Accepted".to_string()),
+            idempotent_replay: true,
+            ..Default::default()
+        };
+        // Timeout-retry: první odpověď nedorazila → sázka ještě není zaúčtovaná
+        assert!(!replay_already_booked(&replay, &[]));
+        // Opakované rozhodnutí ve stejném bucketu: sázka už v active_bets je
+        assert!(replay_already_booked(&replay, &[active_bet("bet-1", 3.0)]));
+        let fresh = OrderReceipt { idempotent_replay: false, ..replay };
+        assert!(!replay_already_booked(&fresh, &[active_bet("bet-1", 3.0)]));
+    }
+}
+
+#[cfg(test)]
+mod hedge_tests {
+    use super::test_fixtures::active_bet;
+    use super::{hedge_recommendation, ActiveBet, LivePayload, OddsPayload};
+
+    fn bet() -> ActiveBet {
+        ActiveBet { alert_id: 7, ..active_bet("bet-7", 3.0) }
+    }
+
+    fn live(score1: i32, score2: i32) -> LivePayload {
+        LivePayload {
+            sport: Some("cs2".to_string()),
+            team1: "Alpha".to_string(),
+            team2: "Beta".to_string(),
+            score1,
+            score2,
+            status: "live".to_string(),
+            detailed_score: None,
+            series_format: Some("bo3".to_string()),
+            league: None,
+        }
+    }
+
+    fn odds(odds_alpha: f64, odds_beta: f64) -> OddsPayload {
+        OddsPayload {
+            sport: Some("cs2".to_string()),
+            bookmaker: "azuro_polygon".to_string(),
+            market: Some("match_winner".to_string()),
+            team1: "Alpha".to_string(),
+            team2: "Beta".to_string(),
+            odds_team1: odds_alpha,
+            odds_team2: odds_beta,
+            odds_draw: None,
+            liquidity_usd: None,
+            spread_pct: None,
+            url: None,
+            game_id: None,
+            condition_id: Some("cond-1".to_string()),
+            outcome1_id: Some("out-alpha".to_string()),
+            outcome2_id: Some("out-beta".to_string()),
+            outcome_draw_id: None,
+            chain: Some("polygon".to_string()),
+        }
+    }
+
+    #[test]
+    fn score_reversal_after_bet_recommends_opposite_side_hedge() {
+        // Sázka na Alpha @1.70 (implied 59 %), Alpha pak prohrála mapu → 0:1, fair 42 %
+        let hedge = hedge_recommendation(&bet(), &live(0, 1), &odds(2.60, 1.50)).expect("hedge recommended");
+
+        assert_eq!(hedge.opposite_outcome_id, "out-beta");
+        assert_eq!(hedge.opposite_team, "Beta");
+        assert!((hedge.fair_prob_now - 0.42).abs() < 1e-9);
+        // Vyrovnávací stake 3 * 1.70 / 1.50 = 3.40 > původní $3 → cap na $3:
+        // Alpha vyhraje 5.10 - 3 - 3 = -0.90, Beta 3 * 1.50 - 6 = -1.50 → horší -1.50 (místo -3)
+        assert!((hedge.hedge_stake - 3.0).abs() < 1e-9);
+        assert!((hedge.locked_pnl + 1.50).abs() < 1e-9);
+
+        // Vyrovnávací stake pod původní sázkou zůstává → obě strany stejně
+        let full = hedge_recommendation(&bet(), &live(0, 1), &odds(1.60, 2.20)).expect("hedge recommended");
+        assert!((full.hedge_stake - 2.32).abs() < 1e-9);
+        assert!((full.locked_pnl - (5.10 - 3.0 - 2.32)).abs() < 1e-9);
+        assert!((full.with_stake(&bet(), 1.0).locked_pnl - (1.0 * 2.20 - 4.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn map_winner_fair_prob_uses_cs2_round_model_only() {
+        let map_bet = |match_key: &str| ActiveBet {
+            match_key: match_key.to_string(),
+            market_key: "map1_winner".to_string(),
+            ..bet()
+        };
+        let mut round_live = live(3, 11);
+        round_live.detailed_score = Some("1.mapa - 3:11".to_string());
+
+        assert!(hedge_recommendation(&map_bet("cs2::alpha_vs_beta"), &round_live, &odds(4.00, 1.20)).is_some());
+        assert!(hedge_recommendation(&map_bet("valorant::alpha_vs_beta"), &round_live, &odds(4.00, 1.20)).is_none());
+    }
+
+    #[test]
+    fn no_hedge_while_bet_side_holds_or_outcome_is_unknown() {
+        assert!(hedge_recommendation(&bet(), &live(0, 0), &odds(1.70, 2.10)).is_none());
+        assert!(hedge_recommendation(&bet(), &live(1, 0), &odds(1.30, 3.20)).is_none());
+
+        let mut foreign = odds(2.60, 1.50);
+        foreign.outcome1_id = Some("other".to_string());
+        assert!(hedge_recommendation(&bet(), &live(0, 1), &foreign).is_none());
+    }
+}
+
+#[cfg(test)]
+mod score_consensus_tests {
+    use super::{build_live_score_consensus, cross_validation_check, LiveItem, ScoreConsensus};
+
+    fn live(source: &str, team1: &str, team2: &str, score: (i32, i32)) -> LiveItem {
+        serde_json::from_value(serde_json::json!({
+            "match_key": "cs2::alpha_vs_beta",
+            "source": source,
+            "payload": {
+                "sport": "cs2",
+                "team1": team1,
+                "team2": team2,
+                "score1": score.0,
+                "score2": score.1,
+                "status": "live"
+            }
+        }))
+        .expect("valid live fixture")
+    }
+
+    #[test]
+    fn two_of_three_sources_agree() {
+        let mut consensus = ScoreConsensus::default();
+        consensus.add("tipsport", (9, 4), 0.7);
+        consensus.add("hltv", (10, 4), 1.0);
+        consensus.add("chance", (10, 4), 0.9);
+
+        let c = consensus.resolve().unwrap();
+        assert_eq!(c.score, (10, 4));
+        assert_eq!((c.agreeing, c.sources), (2, 3));
+        assert!(c.conflict);
+        assert!(c.has_majority());
+        assert!((c.agreement - 1.9 / 2.6).abs() < 1e-9);
+        // Jasná většina → neutrální stake, ne hedge
+        assert_eq!(cross_validation_check(&consensus), (false, 1.0));
+    }
+
+    #[test]
+    fn full_disagreement_is_flagged_and_hedged() {
+        let mut consensus = ScoreConsensus::default();
+        consensus.add("hltv", (10, 4), 1.0);
+        consensus.add("chance", (9, 5), 0.9);
+        consensus.add("tipsport", (8, 6), 0.7);
+
+        let c = consensus.resolve().unwrap();
+        assert!(c.conflict);
+        assert!(!c.has_majority());
+        assert_eq!(c.score, (10, 4), "highest-weight source wins the tie-break");
+        assert_eq!(c.agreeing, 1);
+        assert_eq!(cross_validation_check(&consensus), (false, 0.5));
+    }
+
+    #[test]
+    fn agreement_and_single_source_keep_legacy_multipliers() {
+        let mut consensus = ScoreConsensus::default();
+        assert_eq!(cross_validation_check(&consensus), (false, 1.0));
+        consensus.add("hltv", (10, 4), 1.0);
+        assert_eq!(cross_validation_check(&consensus), (false, 1.0));
+        consensus.add("chance_detailed", (10, 4), 0.9);
+        assert_eq!(cross_validation_check(&consensus), (false, 1.25));
+    }
+
+    #[test]
+    fn live_sources_are_aligned_to_primary_team_order() {
+        let primary = live("tipsport", "Alpha", "Beta", (9, 4));
+        let hltv = live("hltv", "Beta", "Alpha", (4, 10));
+        let chance = live("chance", "Alpha", "Beta", (10, 4));
+        // Mapové skóre se s roundy nemíchá
+        let fortuna = live("fortuna", "Alpha", "Beta", (1, 0));
+        let others = [&primary, &hltv, &chance, &fortuna];
+
+        let consensus = build_live_score_consensus(&primary, (9, 4), &others, None, Some("cs2"));
+        let c = consensus.resolve().unwrap();
+        assert_eq!(c.sources, 3);
+        assert_eq!(c.score, (10, 4));
+        assert!(c.has_majority());
+    }
+}
+
+#[cfg(test)]
+mod market_filter_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_odds_anomalies, find_score_edges, is_moneyline_market, ExecutionVenues, ScoreTracker, StateResponse};
+    use std::collections::HashMap;
+
+    fn state_with_market(market: &str) -> StateResponse {
+        state_with_venue(market, "azuro_polygon")
+    }
+
+    fn state_with_venue(market: &str, venue_bookmaker: &str) -> StateResponse {
+        StateBuilder::default()
+            .live("tennis::alpha_vs_beta", ("Alpha", "Beta"), (1, 0))
+            .azuro("tennis::alpha_vs_beta", market, ("Alpha", "Beta"), (1.6, 2.3))
+            .odds_field("bookmaker", venue_bookmaker)
+            .odds_field("condition_id", "c1")
+            .market("tennis::alpha_vs_beta", "tipsport", market, ("Alpha", "Beta"), (1.4, 2.9))
+            .build()
+    }
+
+    #[test]
+    fn moneyline_allowlist_accepts_only_winner_markets() {
+        assert!(is_moneyline_market(None));
+        assert!(is_moneyline_market(Some("match_winner")));
+        assert!(is_moneyline_market(Some(" Map2_Winner ")));
+        assert!(!is_moneyline_market(Some("total_maps")));
+        assert!(!is_moneyline_market(Some("handicap")));
+        assert!(!is_moneyline_market(Some("map_total_winner")));
+    }
+
+    #[test]
+    fn totals_market_is_ignored_by_edge_logic() {
+        let state = state_with_market("total_games_over_under");
+        assert!(find_odds_anomalies(&state, &ExecutionVenues::default()).is_empty());
+        let edges = find_score_edges(&state, &mut ScoreTracker::new(), &mut HashMap::new(), &ExecutionVenues::default());
+        assert!(edges.is_empty());
+    }
+
+    #[test]
+    fn match_winner_market_still_reaches_anomaly_logic() {
+        let state = state_with_market("match_winner");
+        assert!(!find_odds_anomalies(&state, &ExecutionVenues::default()).is_empty());
+    }
+
+    #[test]
+    fn custom_venue_prefix_splits_venue_from_reference_odds() {
+        let state = state_with_venue("match_winner", "onchain_book_v2");
+        // Default ("azuro_") nezná onchain_ → obě odds jsou jen reference, žádný venue
+        assert!(find_odds_anomalies(&state, &ExecutionVenues::default()).is_empty());
+
+        let venues = ExecutionVenues { prefixes: vec!["azuro_".to_string(), "onchain_".to_string()] };
+        assert!(venues.is_venue("azuro_polygon_v3"));
+        assert!(!venues.is_venue("tipsport"));
+        let anomalies = find_odds_anomalies(&state, &venues);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].azuro_bookmaker, "onchain_book_v2");
+        assert_eq!(anomalies[0].market_bookmaker, "tipsport");
+    }
+}
+
+#[cfg(test)]
+mod three_way_market_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{
+        anomaly_side_pick, devig_three_way, find_odds_anomalies, ExecutionVenues, StateResponse, VALUE_SIDE_DRAW,
+    };
+
+    /// Live fotbal: Azuro 1X2 proti Tipsportu (three-way nebo jen two-way).
+    fn football_state(market_draw: Option<f64>) -> StateResponse {
+        let teams = ("Sparta", "Slavia");
+        let state = StateBuilder::default()
+            .live("football::sparta_vs_slavia", teams, (0, 0))
+            .azuro("football::sparta_vs_slavia", "match_winner", teams, (2.10, 3.80))
+            .odds_field("odds_draw", 3.60)
+            .odds_field("condition_id", "c1")
+            .odds_field("outcome_draw_id", "ox")
+            .market("football::sparta_vs_slavia", "tipsport", "match_winner", teams, (2.20, 3.70));
+        match market_draw {
+            Some(draw) => state.odds_field("odds_draw", draw),
+            None => state,
+        }
+        .build()
+    }
+
+    #[test]
+    fn devig_normalizes_three_way_probabilities() {
+        let fair = devig_three_way(2.20, 3.00, 3.70).unwrap();
+        assert!((fair.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(fair[0] > fair[1] && fair[1] > fair[2]);
+        assert!(devig_three_way(1.0, 3.0, 3.0).is_none());
+    }
+
+    #[test]
+    fn three_way_azuro_line_picks_draw_against_three_way_market() {
+        let anomalies = find_odds_anomalies(&football_state(Some(3.00)), &ExecutionVenues::default());
+        assert_eq!(anomalies.len(), 1);
+        let a = &anomalies[0];
+        assert_eq!(a.value_side, VALUE_SIDE_DRAW);
+        assert_eq!(a.outcome_id.as_deref(), Some("ox"));
+        assert_eq!(a.market_draw, Some(3.00));
+        // 3.60 × fair 31.5 % ≈ +13.4 % EV
+        assert!((a.discrepancy_pct - 13.4).abs() < 0.2, "disc={}", a.discrepancy_pct);
+        assert_eq!(anomaly_side_pick(a, a.value_side), ("Remíza", 3.60, 3.00));
+    }
+
+    #[test]
+    fn three_way_azuro_line_is_not_compared_with_two_way_only_source() {
+        assert!(find_odds_anomalies(&football_state(None), &ExecutionVenues::default()).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod ledger_pnl_tests {
+    use super::{format_pnl_summary, summarize_ledger_pnl};
+    use chrono::{TimeZone, Utc};
+
+    /// 2× CS2 (výhra + prohra), tenis výhra, zrušená sázka a starý settlement mimo okno.
+    const LEDGER: &str = concat!(
+        r#"{"ts":"2026-03-01T10:00:00Z","event":"PLACED","bet_id":"b1","match_key":"cs2::navi_vs_faze","amount_usd":2.0,"odds":1.80,"edge_pct":20.0}"#, "\n",
+        r#"{"ts":"2026-03-01T11:00:00Z","event":"WON","bet_id":"b1","match_key":"cs2::navi_vs_faze","amount_usd":2.0,"odds":1.80,"payout_usd":3.6}"#, "\n",
+        r#"{"ts":"2026-03-01T10:05:00Z","event":"PLACED","bet_id":"b2","match_key":"cs2::g2_vs_mouz","amount_usd":3.0,"odds":1.50,"edge_pct":12.0}"#, "\n",
+        r#"{"ts":"2026-03-01T12:00:00Z","event":"LOST","bet_id":"b2","match_key":"cs2::g2_vs_mouz","amount_usd":3.0,"odds":1.50}"#, "\n",
+        r#"{"ts":"2026-03-01T10:10:00Z","event":"PLACED","bet_id":"b3","match_key":"tennis::a_vs_b","amount_usd":1.0,"odds":2.00,"anomaly_disc_pct":16.0}"#, "\n",
+        r#"{"ts":"2026-03-01T13:00:00Z","event":"WON","bet_id":"b3","match_key":"tennis::a_vs_b","amount_usd":1.0,"odds":2.00}"#, "\n",
+        r#"{"ts":"2026-03-01T13:00:00Z","event":"WON","bet_id":"b3","match_key":"tennis::a_vs_b","amount_usd":1.0,"odds":2.00}"#, "\n",
+        r#"{"ts":"2026-03-01T10:15:00Z","event":"PLACED","bet_id":"b4","match_key":"cs2::x_vs_y","amount_usd":2.0,"odds":1.70}"#, "\n",
+        r#"{"ts":"2026-03-01T14:00:00Z","event":"CANCELED","bet_id":"b4","match_key":"cs2::x_vs_y","amount_usd":2.0,"payout_usd":2.0}"#, "\n",
+        r#"{"ts":"2026-02-01T14:00:00Z","event":"LOST","bet_id":"b0","match_key":"cs2::old_vs_old","amount_usd":5.0,"odds":1.60}"#, "\n",
+        "not json\n",
+    );
+
+    #[test]
+    fn pairs_placements_with_settlements_into_metrics() {
+        let since = Utc.with_ymd_and_hms(2026, 2, 25, 0, 0, 0).unwrap();
+        let s = summarize_ledger_pnl(LEDGER, Some(since));
+
+        let t = &s.total;
+        assert_eq!((t.won, t.lost), (2, 1));
+        assert!((t.staked_usd - 6.0).abs() < 1e-9);
+        // +1.60 (payout) +1.00 (stake × odds) −3.00
+        assert!((t.pnl_usd - (-0.4)).abs() < 1e-9);
+        assert!((t.win_rate_pct() - 200.0 / 3.0).abs() < 1e-9);
+        assert!((t.roi_pct() - (-0.4 / 6.0 * 100.0)).abs() < 1e-9);
+        assert!((t.won_edge_sum / t.won_edge_n as f64 - 18.0).abs() < 1e-9);
+        assert!((t.lost_edge_sum / t.lost_edge_n as f64 - 12.0).abs() < 1e-9);
+
+        let cs2 = &s.per_sport["cs2"];
+        assert_eq!((cs2.won, cs2.lost), (1, 1));
+        assert!((cs2.pnl_usd - (-1.4)).abs() < 1e-9);
+        let tennis = &s.per_sport["tennis"];
+        assert_eq!((tennis.won, tennis.lost), (1, 0));
+        assert!((tennis.roi_pct() - 100.0).abs() < 1e-9);
+
+        let msg = format_pnl_summary(&s, Some(30));
+        assert!(msg.contains("WR 67%"), "{}", msg);
+        assert!(msg.contains("TENNIS — 1/1"), "{}", msg);
+    }
+
+    #[test]
+    fn all_time_includes_old_settlements() {
+        let s = summarize_ledger_pnl(LEDGER, None);
+        assert_eq!((s.total.won, s.total.lost), (2, 2));
+        assert!((s.total.pnl_usd - (-5.4)).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod odds_freshness_tests {
+    use super::test_fixtures::StateBuilder;
+    use super::{find_odds_anomalies, odds_freshness_weight, ExecutionVenues, StateResponse};
+    use chrono::{Duration, Utc};
+
+    /// Live CS2: Azuro 1.80/2.10, čerstvý Tipsport 1.55/2.50, Fortuna 18s stará 2.00/1.85.
+    fn state() -> StateResponse {
+        StateBuilder::default()
+            .live("cs2::alpha_vs_beta", ("Alpha", "Beta"), (1, 0))
+            .azuro("cs2::alpha_vs_beta", "match_winner", ("Alpha", "Beta"), (1.80, 2.10))
+            .odds_field("condition_id", "c1")
+            .odds_field("outcome1_id", "6995")
+            .odds_field("outcome2_id", "6996")
+            .market("cs2::alpha_vs_beta", "tipsport", "match_winner", ("Alpha", "Beta"), (1.55, 2.50))
+            .market("cs2::alpha_vs_beta", "fortuna", "match_winner", ("Alpha", "Beta"), (2.00, 1.85))
+            .odds_seen_at(Utc::now() - Duration::seconds(18))
+            .build()
+    }
+
+    #[test]
+    fn weighted_market_average_leans_to_fresh_source() {
+        let anomalies = find_odds_anomalies(&state(), &ExecutionVenues::default());
+        assert_eq!(anomalies.len(), 1);
+        let a = &anomalies[0];
+        // Prostý průměr 1.775 by dal jen ~1.4 % → žádná anomálie; Fortuna (18 s) má jen floor váhu 0.2
+        assert!((a.market_w1 - (1.55 + 0.2 * 2.00) / 1.2).abs() < 1e-3, "market_w1={}", a.market_w1);
+        assert!((a.market_w2 - (2.50 + 0.2 * 1.85) / 1.2).abs() < 1e-3, "market_w2={}", a.market_w2);
+        assert_eq!(a.value_side, 1);
+    }
+
+    #[test]
+    fn freshness_weight_decays_linearly_to_floor_and_treats_garbage_as_oldest() {
+        let now = Utc::now();
+        let weight = |age: i64| odds_freshness_weight(&(now - Duration::seconds(age)).to_rfc3339(), now);
+        assert!((weight(0) - 1.0).abs() < 1e-9);
+        // Lineárně přes MAX_ODDS_AGE_SECS (20 s): 5 s stará = 0.75, 10 s = 0.5
+        assert!((weight(5) - 0.75).abs() < 1e-3);
+        assert!((weight(10) - 0.5).abs() < 1e-3);
+        // Konec okna drží floor
+        assert!((weight(18) - 0.2).abs() < 1e-9);
+        assert!((odds_freshness_weight("not-a-timestamp", now) - 0.2).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod intra_arb_tests {
+    use super::{
+        find_intra_match_arb, intra_arb_close_out_stake, intra_arb_leg_stakes, intra_arb_map_is_decisive, ExecutionVenues,
+        StateOddsItem, INTRA_ARB_STAKE_USD, MIN_EXECUTABLE_STAKE_USD,
+    };
+
+    fn odds_item(market: &str, team1: &str, team2: &str, odds1: f64, odds2: f64, cond: &str) -> StateOddsItem {
+        let key = if market == "match_winner" {
+            "cs2::alpha_vs_beta".to_string()
+        } else {
+            format!("cs2::alpha_vs_beta::{}", market)
+        };
+        serde_json::from_value(serde_json::json!({
+            "match_key": key,
+            "source": "azuro",
+            "seen_at": chrono::Utc::now().to_rfc3339(),
+            "payload": {
+                "sport": "cs2",
+                "bookmaker": "azuro_polygon",
+                "market": market,
+                "team1": team1,
+                "team2": team2,
+                "odds_team1": odds1,
+                "odds_team2": odds2,
+                "condition_id": cond,
+                "outcome1_id": format!("{}-o1", cond),
+                "outcome2_id": format!("{}-o2", cond)
+            }
+        }))
+        .expect("valid odds fixture")
+    }
+
+    #[test]
+    fn mispriced_deciding_map_vs_match_is_arb() {
+        // Map item má týmy prohozené → strany se musí zarovnat na match_winner orientaci
+        let items = vec![
+            odds_item("match_winner", "Alpha", "Beta", 1.30, 4.20, "cm"),
+            odds_item("map3_winner", "Beta", "Alpha", 2.30, 1.60, "c3"),
+        ];
+        let arb = find_intra_match_arb(&items, &ExecutionVenues::default()).expect("1/1.60 + 1/4.20 < 1 → arb");
+
+        assert_eq!(arb.match_key, "cs2::alpha_vs_beta");
+        assert_eq!(arb.map_index, 3);
+        assert_eq!(arb.map_leg.team, "Alpha");
+        assert_eq!(arb.map_leg.side, 1);
+        assert_eq!(arb.map_leg.outcome_id.as_deref(), Some("c3-o2"));
+        assert_eq!(arb.match_leg.team, "Beta");
+        assert_eq!(arb.match_leg.outcome_id.as_deref(), Some("cm-o2"));
+        assert!(arb.implied_sum < 0.87);
+        assert!(arb.profit_pct > 10.0);
+        assert!((arb.map_leg.stake_frac + arb.match_leg.stake_frac - 1.0).abs() < 1e-9);
+        // Dutch: obě nohy vrací stejnou částku
+        let ret_map = arb.map_leg.stake_frac * arb.map_leg.odds;
+        let ret_match = arb.match_leg.stake_frac * arb.match_leg.odds;
+        assert!((ret_map - ret_match).abs() < 1e-9);
+    }
+
+    #[test]
+    fn consistent_prices_and_fee_eaten_margin_are_not_arb() {
+        let fair = vec![
+            odds_item("match_winner", "Alpha", "Beta", 1.60, 2.30, "cm"),
+            odds_item("map1_winner", "Alpha", "Beta", 1.65, 2.25, "c1"),
+        ];
+        assert!(find_intra_match_arb(&fair, &ExecutionVenues::default()).is_none());
+
+        // Σ implied = 0.995 → před fees arb, po INTRA_ARB_FEE_PCT už ne
+        let thin = vec![
+            odds_item("match_winner", "Alpha", "Beta", 1.98, 2.02, "cm"),
+            odds_item("map1_winner", "Alpha", "Beta", 2.00, 1.98, "c1"),
+        ];
+        assert!(find_intra_match_arb(&thin, &ExecutionVenues::default()).is_none());
+
+        // Bez match_winner trhu není s čím párovat
+        assert!(find_intra_match_arb(&fair[1..], &ExecutionVenues::default()).is_none());
+    }
+
+    #[test]
+    fn leg_stakes_keep_dutch_ratio_while_meeting_minimum() {
+        // 1.60 / 4.20: frac ~0.72 / ~0.28 → $2 dává menší nohu $0.55, minimum splněno bez navýšení
+        let arb = find_intra_match_arb(&[
+            odds_item("match_winner", "Alpha", "Beta", 1.30, 4.20, "cm"),
+            odds_item("map3_winner", "Beta", "Alpha", 2.30, 1.60, "c3"),
+        ], &ExecutionVenues::default()).unwrap();
+        let (map_stake, match_stake) = intra_arb_leg_stakes(&arb).unwrap();
+        assert!((map_stake + match_stake - INTRA_ARB_STAKE_USD).abs() < 0.02);
+        assert!((map_stake * arb.map_leg.odds - match_stake * arb.match_leg.odds).abs() < 0.05);
+
+        // 1.15 / 9.00: menší noha ~11 % → dutch se navýší jedním faktorem, poměr zůstává
+        let skewed = find_intra_match_arb(&[
+            odds_item("match_winner", "Alpha", "Beta", 1.06, 9.00, "cm"),
+            odds_item("map1_winner", "Alpha", "Beta", 1.15, 5.00, "c1"),
+        ], &ExecutionVenues::default()).unwrap();
+        let (map_stake, match_stake) = intra_arb_leg_stakes(&skewed).unwrap();
+        assert!(map_stake.min(match_stake) >= MIN_EXECUTABLE_STAKE_USD);
+        assert!(map_stake + match_stake > INTRA_ARB_STAKE_USD);
+        assert!((map_stake * skewed.map_leg.odds - match_stake * skewed.match_leg.odds).abs() < 0.05);
+
+        // 1.08 / 45: minimum menší nohy by chtělo dutch přes strop → skip
+        let extreme = find_intra_match_arb(&[
+            odds_item("match_winner", "Alpha", "Beta", 1.06, 45.0, "cm"),
+            odds_item("map1_winner", "Alpha", "Beta", 1.08, 20.0, "c1"),
+        ], &ExecutionVenues::default()).unwrap();
+        assert!(intra_arb_leg_stakes(&extreme).is_none());
+    }
+
+    #[test]
+    fn failed_second_leg_closes_first_on_opposite_outcome() {
+        let arb = find_intra_match_arb(&[
+            odds_item("match_winner", "Alpha", "Beta", 1.30, 4.20, "cm"),
+            odds_item("map3_winner", "Beta", "Alpha", 2.30, 1.60, "c3"),
+        ], &ExecutionVenues::default()).unwrap();
+        let close = arb.map_leg.close_out(&arb).expect("opposite outcome known");
+        assert_eq!(close.team, "Beta");
+        assert_eq!(close.outcome_id.as_deref(), Some("c3-o1"));
+        assert_eq!(close.condition_id.as_deref(), Some("c3"));
+        assert_eq!(close.odds, 2.30);
+
+        // $1.45 @ 1.60 → výplata 2.32; protisázka 2.32 / 2.30 = $1.01 vyrovná obě strany
+        assert_eq!(intra_arb_close_out_stake(1.45, 1.60, close.odds), Some(1.01));
+        assert_eq!(intra_arb_close_out_stake(0.50, 1.20, 4.00), None);
+    }
+
+    #[test]
+    fn only_deciding_map_locks_the_dutch() {
+        assert!(intra_arb_map_is_decisive(1, Some(1), None));
+        assert!(intra_arb_map_is_decisive(3, Some(3), Some(3)));
+        assert!(!intra_arb_map_is_decisive(3, Some(3), Some(2)));
+        assert!(!intra_arb_map_is_decisive(2, Some(3), Some(2)));
+        assert!(!intra_arb_map_is_decisive(1, None, None));
+    }
+}
+
+#[cfg(test)]
+mod pending_claims_tests {
+    use super::{load_pending_claims, FlatFileStateStore, LedgerBetMetadata};
+    use std::collections::HashMap;
+
+    #[test]
+    fn duplicate_pending_claims_collapse_to_one_active_bet() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_pending_claims_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pending_claims.txt");
+        let line = "cs2::alpha_vs_beta|Alpha|2|1.85|Alpha|Beta|match_winner|cs2|cs2|cs2";
+        std::fs::write(&path, [
+            format!("?|0xbet1|{}", line),
+            format!("?|0xbet1|{}", line),        // stejný betId
+            format!("?|?|{}", line),             // betId "?" → stejný (match, condition, amount)
+            format!("|?|{}", line),
+            "?|?|dota-2::gamma_vs_delta|Gamma|2|1.5|Gamma|Delta|match_winner|||".to_string(), // bez ID
+        ].join("\n")).unwrap();
+
+        let store = FlatFileStateStore::new(&dir);
+        let bets = load_pending_claims(&store, &HashMap::new(), &HashMap::new());
+        assert_eq!(bets.len(), 1);
+        assert_eq!(bets[0].bet_id, "0xbet1");
+        assert_eq!(bets[0].match_key, "cs2::alpha_vs_beta");
+
+        // Soubor je přepsaný vyčištěný a další load je idempotentní
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(rewritten.lines().count(), 1);
+        assert_eq!(load_pending_claims(&store, &HashMap::new(), &HashMap::new()).len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tuple_dedup_only_applies_to_rows_without_bet_id_and_with_condition() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_pending_claims_tuple_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let line = "cs2::alpha_vs_beta|Alpha|2|1.85|Alpha|Beta|match_winner|cs2|cs2|cs2";
+        std::fs::write(dir.join("pending_claims.txt"), [
+            format!("111111|0xbet1|{}", line),
+            format!("222222|0xbet2|{}", line),   // jiný betId, stejný zápas i částka → jiná sázka
+            format!("333333|?|{}", line),        // bez betId, condition z ledgeru
+            format!("444444|?|{}", line),        // bez betId, stejná condition → duplikát
+            format!("555555|?|{}", line),        // bez betId i condition → není podle čeho slučovat
+            format!("666666|?|{}", line),        // bez betId, condition sázky 0xbet1 → duplikát
+        ].join("\n")).unwrap();
+        let meta = |condition_id: &str| LedgerBetMetadata { condition_id: condition_id.to_string(), ..Default::default() };
+        let by_token: HashMap<String, LedgerBetMetadata> = [
+            ("111111".to_string(), meta("0xcond1")),
+            ("333333".to_string(), meta("0xcond2")),
+            ("444444".to_string(), meta("0xcond2")),
+            ("666666".to_string(), meta("0xcond1")),
+        ].into_iter().collect();
+
+        let store = FlatFileStateStore::new(&dir);
+        let bets = load_pending_claims(&store, &HashMap::new(), &by_token);
+        let tokens: Vec<_> = bets.iter().filter_map(|b| b.token_id.as_deref()).collect();
+        assert_eq!(tokens, vec!["111111", "222222", "333333", "555555"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(test)]
+mod claim_reconcile_tests {
+    use super::{reconcile_claimed_tokens, ClaimDetail};
+
+    fn detail(token_id: &str, amount_usd: f64, odds: f64, result: &str) -> ClaimDetail {
+        ClaimDetail {
+            token_id: token_id.to_string(),
+            alert_id: 1,
+            bet_id: format!("0xbet{}", token_id),
+            match_key: "cs2::alpha_vs_beta".to_string(),
+            market_key: "match_winner".to_string(),
+            team1: "Alpha".to_string(),
+            team2: "Beta".to_string(),
+            value_team: "Alpha".to_string(),
+            amount_usd,
+            odds,
+            payout_usd: 0.0,
+            result: result.to_string(),
+            path: "score_edge".to_string(),
+        }
+    }
+
+    #[test]
+    fn unknown_token_in_claim_response_is_not_credited() {
+        let details = vec![detail("101", 2.0, 1.8, "Won"), detail("102", 1.0, 2.0, "Canceled")];
+
+        // Čistý batch → věříme totalPayoutUsd executoru
+        let tokens = vec!["101".to_string(), "102".to_string()];
+        let (credited, matched, unexpected) = reconcile_claimed_tokens(&tokens, &details, 4.6);
+        assert!((credited - 4.6).abs() < 1e-9);
+        assert_eq!(matched.len(), 2);
+        assert!(unexpected.is_empty());
+
+        // Executor přibalil cizí token 999 (payout $10) → připíše se jen 3.60 + 1.00
+        let tokens = vec!["101".to_string(), "999".to_string(), "102".to_string()];
+        let (credited, matched, unexpected) = reconcile_claimed_tokens(&tokens, &details, 14.6);
+        assert!((credited - 4.6).abs() < 1e-9);
+        assert_eq!(matched.iter().map(|d| d.token_id.as_str()).collect::<Vec<_>>(), ["101", "102"]);
+        assert_eq!(unexpected, ["999"]);
+
+        // Jen cizí token → nic
+        let (credited, matched, unexpected) = reconcile_claimed_tokens(&["999".to_string()], &details, 10.0);
+        assert_eq!(credited, 0.0);
+        assert!(matched.is_empty());
+        assert_eq!(unexpected, ["999"]);
     }
+}
 
-    #[test]
-    fn cs2_distrust_events_lock_match_after_threshold() {
-        let now = Utc::now();
-        let mut tracker = ScoreTracker::new();
+#[cfg(test)]
+mod state_store_tests {
+    use super::test_fixtures::active_bet;
+    use super::{
+        ActiveBet, BetHistoryEntry, DailyPnl, FlatFileStateStore, SqliteStateStore, StateStore,
+    };
+    use rusqlite::Connection;
 
-        let (points_after_first, first_lock) =
-            record_cs2_distrust_event(&mut tracker, "esports::alliance_vs_nexus", now, 2);
-        assert_eq!(points_after_first, 2);
-        assert!(first_lock.is_none());
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("rustmisko_state_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
 
-        let (points_after_second, second_lock) = record_cs2_distrust_event(
-            &mut tracker,
-            "esports::alliance_vs_nexus",
-            now + Duration::seconds(5),
-            1,
-        );
-        assert_eq!(points_after_second, 3);
-        let until = second_lock.expect("lock set");
-        assert!(until >= now + Duration::seconds(CS2_SCORE_DISTRUST_LOCK_SECS - 1));
+    fn bet(bet_id: &str, amount_usd: f64) -> ActiveBet {
+        ActiveBet { token_id: Some("123456".to_string()), ..active_bet(bet_id, amount_usd) }
+    }
+
+    fn entry(match_key: &str) -> BetHistoryEntry {
+        BetHistoryEntry::new(match_key, "cond-1", "Alpha", 1.70, "match_winner", Some("game-1"))
     }
 
     #[test]
-    fn cs2_distrust_lock_releases_after_two_stable_progress_updates() {
-        let now = Utc::now();
-        let mut tracker = ScoreTracker::new();
+    fn failed_placement_leaves_count_pnl_and_history_untouched() {
+        let dir = temp_dir("atomic");
+        let store = SqliteStateStore::open(dir.join("state.sqlite")).unwrap();
+        let pnl = DailyPnl::new("2026-03-01", 3.0, 0.0, 100.0, None);
+        store.record_placement(1, &pnl, &entry("cs2::alpha_vs_beta")).unwrap();
 
-        record_cs2_distrust_event(&mut tracker, "esports::alliance_vs_nexus", now, 2);
-        let (_, second_lock) = record_cs2_distrust_event(
-            &mut tracker,
-            "esports::alliance_vs_nexus",
-            now + Duration::seconds(5),
-            1,
-        );
-        assert!(second_lock.is_some());
+        // Rozbitá tabulka → insert historie selže až po zápisu P&L a countu → celé se to vrátí
+        store.conn.execute_batch("DROP TABLE bet_history").unwrap();
+        let next = DailyPnl::new("2026-03-01", 6.0, 0.0, 100.0, Some(50.0));
+        assert!(store.record_placement(2, &next, &entry("cs2::gamma_vs_delta")).is_err());
 
-        assert!(!relax_cs2_distrust_on_stable_progress(
-            &mut tracker,
-            "esports::alliance_vs_nexus",
-            now + Duration::seconds(15),
-            true,
-            4,
-            2,
-            5,
-            2,
-        ));
-        assert!(relax_cs2_distrust_on_stable_progress(
-            &mut tracker,
-            "esports::alliance_vs_nexus",
-            now + Duration::seconds(25),
-            true,
-            5,
-            2,
-            6,
-            2,
-        ));
+        assert_eq!(store.load_bet_count().unwrap(), Some(("2026-03-01".to_string(), 1)));
+        assert_eq!(store.load_daily_pnl().unwrap(), Some(pnl));
 
-        let active_lock = tracker
-            .cs2_distrust_state
-            .get("esports::alliance_vs_nexus")
-            .and_then(|state| state.active_lock_until(now + Duration::seconds(25)));
-        assert!(active_lock.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn cross_market_base_dedup_only_blocks_map_winner_siblings() {
-        use std::collections::HashSet;
+    fn committed_state_survives_reopen_and_uncommitted_writes_do_not() {
+        let dir = temp_dir("reload");
+        let db = dir.join("state.sqlite");
+        {
+            let store = SqliteStateStore::open(&db).unwrap();
+            store.record_placement(1, &DailyPnl::new("2026-03-01", 3.0, 0.0, 100.0, None), &entry("cs2::alpha_vs_beta")).unwrap();
+            store.append_pending_claim(&bet("0xbet1", 3.0)).unwrap();
+            store.replace_pending_claims(&[bet("0xbet1", 3.0), bet("0xbet2", 2.0)]).unwrap();
+
+            // "Pád" uprostřed zápisu: transakce na druhém spojení se nikdy necommitne
+            let crashed = Connection::open(&db).unwrap();
+            crashed.execute_batch(
+                "BEGIN; INSERT INTO pending_claims(bet_id, amount_usd, line) VALUES ('0xlost', 9.0, 'lost');\
+                 UPDATE daily_state SET wagered = 99.0;",
+            ).unwrap();
+            drop(crashed);
+        }
 
-        let mut base_matches = HashSet::new();
-        let mut map_winners = HashSet::new();
-        let base = "esports::cybershoke_vs_ruby".to_string();
+        let reopened = SqliteStateStore::open(&db).unwrap();
+        assert_eq!(reopened.load_bet_count().unwrap(), Some(("2026-03-01".to_string(), 1)));
+        assert_eq!(reopened.load_daily_pnl().unwrap().unwrap().wagered, 3.0);
+        assert_eq!(reopened.load_bet_history().unwrap().len(), 1);
+        let lines = reopened.load_pending_claim_lines().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("123456|0xbet1|"));
+        assert_eq!(reopened.pending_exposure_usd().unwrap(), 5.0);
 
-        assert!(!cross_market_base_dedup_block(&base, true, &base_matches, &map_winners));
-        assert!(!cross_market_base_dedup_block(&base, false, &base_matches, &map_winners));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-        base_matches.insert(base.clone());
-        assert!(!cross_market_base_dedup_block(&base, true, &base_matches, &map_winners));
-        assert!(!cross_market_base_dedup_block(&base, false, &base_matches, &map_winners));
+    #[test]
+    fn legacy_flat_files_are_imported_once() {
+        let dir = temp_dir("migrate");
+        let placed_at = chrono::Utc::now().to_rfc3339();
+        std::fs::write(dir.join("bet_count_daily.txt"), "2026-03-01|4").unwrap();
+        std::fs::write(dir.join("daily_pnl.json"),
+            r#"{"date":"2026-03-01","wagered":12.5,"returned":4.0,"sod_bankroll":80.0,"limit_override":null}"#).unwrap();
+        std::fs::write(dir.join("bet_history.txt"), format!(
+            "cs2::old_vs_legacy|cond-0\ncs2::alpha_vs_beta|cond-1|Alpha|1.7|{}|map1_winner|game-1\n", placed_at)).unwrap();
+        std::fs::write(dir.join("pending_claims.txt"),
+            "?|0xbet1|cs2::alpha_vs_beta|Alpha|3|1.7|Alpha|Beta|match_winner|cs2|cs2|cs2\n").unwrap();
+        // Useknutý temp soubor z přerušeného atomic write se ignoruje
+        std::fs::write(dir.join("daily_pnl.json.tmp"), "{\"date\":\"2026-").unwrap();
+
+        let flat = FlatFileStateStore::new(&dir);
+        let store = SqliteStateStore::open(dir.join("state.sqlite")).unwrap();
+        assert!(store.import_flat_files(&flat).unwrap());
+        assert!(!store.import_flat_files(&flat).unwrap());
+
+        assert_eq!(store.load_bet_count().unwrap(), Some(("2026-03-01".to_string(), 4)));
+        assert_eq!(store.load_daily_pnl().unwrap(), Some(DailyPnl::new("2026-03-01", 12.5, 4.0, 80.0, None)));
+        let history = store.load_bet_history().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].placed_at, "");
+        assert_eq!(history[1].market_key, "map1_winner");
+        assert_eq!(history[1].game_id, "game-1");
+        assert_eq!(store.load_pending_claim_lines().unwrap().len(), 1);
+        assert_eq!(store.pending_exposure_usd().unwrap(), 3.0);
+
+        // Flat backend čte stejná data (výchozí BOT_STATE_BACKEND=files)
+        assert_eq!(flat.load_bet_history().unwrap(), history);
+        assert_eq!(flat.pending_exposure_usd().unwrap(), 3.0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-        map_winners.insert(base.clone());
-        assert!(cross_market_base_dedup_block(&base, true, &base_matches, &map_winners));
-        assert!(!cross_market_base_dedup_block(&base, false, &base_matches, &map_winners));
+    #[test]
+    fn disabled_sports_survive_reopen_on_both_backends() {
+        let dir = temp_dir("sport_kill");
+        let disabled = vec![("hockey".to_string(), "sanity reject 6/8 zápasů za 600s".to_string())];
+        let flat = FlatFileStateStore::new(&dir);
+        let sqlite = SqliteStateStore::open(dir.join("state.sqlite")).unwrap();
+        let stores: [&dyn StateStore; 2] = [&flat, &sqlite];
+        for store in stores {
+            assert_eq!(store.load_disabled_sports().unwrap(), None);
+            store.save_disabled_sports(&disabled).unwrap();
+            assert_eq!(store.load_disabled_sports().unwrap(), Some(disabled.clone()));
+            // `/enable` všech sportů je taky uložený stav, ne "nic uloženo"
+            store.save_disabled_sports(&[]).unwrap();
+            assert_eq!(store.load_disabled_sports().unwrap(), Some(Vec::new()));
+        }
+        drop(sqlite);
+        assert_eq!(SqliteStateStore::open(dir.join("state.sqlite")).unwrap().load_disabled_sports().unwrap(), Some(Vec::new()));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
 
@@ -3074,12 +6295,48 @@ fn scoped_condition_key(base_match_key: &str, condition_id: &str) -> String {
 fn cross_market_base_dedup_block(
     base_match_key: &str,
     is_candidate_map_winner: bool,
-    _already_bet_base_matches: &HashSet<String>,
+    is_bo1_sibling_candidate: bool,
+    already_bet_base_matches: &HashSet<String>,
     already_bet_map_winners: &HashSet<String>,
 ) -> bool {
+    // Bo1: map1_winner == match_winner → jakýkoli jiný bet na base match je double exposure
+    if is_bo1_sibling_candidate && already_bet_base_matches.contains(base_match_key) {
+        return true;
+    }
     is_candidate_map_winner && already_bet_map_winners.contains(base_match_key)
 }
 
+/// Azuro `game_id` je sdílený přes všechny condition jednoho zápasu (match_winner, mapN_winner...).
+/// Druhá *jiná* condition na stejné game = double exposure → blok (jen s FF_EXPOSURE_CAPS).
+/// Stejná condition projde (re-bet řeší vlastní logika).
+fn game_id_dedup_block(
+    game_id: Option<&str>,
+    condition_id: &str,
+    already_bet_game_ids: &HashMap<String, String>,
+) -> bool {
+    if !FF_EXPOSURE_CAPS {
+        return false;
+    }
+    let Some(game_id) = game_id.map(str::trim).filter(|g| !g.is_empty()) else {
+        return false;
+    };
+    already_bet_game_ids
+        .get(game_id)
+        .is_some_and(|bet_condition| bet_condition != condition_id)
+}
+
+fn record_bet_game_id(
+    game_id: Option<&str>,
+    condition_id: &str,
+    already_bet_game_ids: &mut HashMap<String, String>,
+) {
+    if let Some(game_id) = game_id.map(str::trim).filter(|g| !g.is_empty()) {
+        already_bet_game_ids
+            .entry(game_id.to_string())
+            .or_insert_with(|| condition_id.to_string());
+    }
+}
+
 fn market_dedup_key(match_key: &str, market_key: &str) -> String {
     let base_match_key = strip_map_winner_suffix(match_key);
     let inferred_market = if !market_key.is_empty() {
@@ -3441,6 +6698,18 @@ fn is_recent_seen_at(seen_at: &str, now: DateTime<Utc>) -> bool {
     }
 }
 
+/// Váha market zdroje v průměru kurzů: lineárně 1.0 → 0 přes okno MAX_ODDS_AGE_SECS, zdola
+/// ODDS_FRESHNESS_MIN_WEIGHT — čerstvé kurzy mají přednost, ale skoro-stale zdroj v průměru zůstane.
+/// Neparsovatelný seen_at = nejstarší v okně.
+fn odds_freshness_weight(seen_at: &str, now: DateTime<Utc>) -> f64 {
+    let max_age = MAX_ODDS_AGE_SECS as f64;
+    let age_secs = DateTime::parse_from_rfc3339(seen_at)
+        .map(|dt| (now - dt.with_timezone(&Utc)).num_milliseconds() as f64 / 1000.0)
+        .unwrap_or(max_age)
+        .clamp(0.0, max_age);
+    (1.0 - age_secs / max_age).max(ODDS_FRESHNESS_MIN_WEIGHT)
+}
+
 fn has_recent_azuro_market_for_live(
     match_key: &str,
     live: &LiveItem,
@@ -3737,17 +7006,26 @@ fn map_score_to_win_prob(leading: i32, losing: i32) -> Option<f64> {
 
 /// Tennis set score → estimated match win probability for the LEADING player
 ///
-/// Tennis is Bo3 sets (Grand Slams Bo5, but Azuro mainly has Bo3).
+/// Bo3 (default) nebo Bo5 (Grand Slam muži) podle `best_of`.
 /// SET lead is the strongest predictor:
-///   - 1-0 in sets → ~65% (won first set but opponent can come back)
-///   - 2-0 → match won (don't bet)
+///   - Bo3: 1-0 → ~65%, 2-x → match won (don't bet)
+///   - Bo5: 1-0 → ~60%, 2-0 → ~85%, 2-1 → ~70%, 3-x → match won
 ///   - Within a set: game lead matters less because service breaks/holds
 ///     are volatile — we only bet on SET leads for safety.
 ///
 /// `leading_score` and `losing_score` represent SET counts.
-fn tennis_score_to_win_prob(leading_sets: i32, losing_sets: i32) -> Option<f64> {
+fn tennis_score_to_win_prob(leading_sets: i32, losing_sets: i32, best_of: u8) -> Option<f64> {
     if leading_sets <= losing_sets { return None; }
 
+    if best_of == 5 {
+        return match (leading_sets, losing_sets) {
+            (1, 0) => Some(0.60),
+            (2, 0) => Some(0.85),  // 2 sety k dobru, soupeř potřebuje 3 v řadě
+            (2, 1) => Some(0.70),
+            _ => None,             // 3-x = rozhodnuto
+        };
+    }
+
     match (leading_sets, losing_sets) {
         (1, 0) => Some(0.65),  // Won first set → ~65% match win
         (2, 0) => None,        // Already won → too late
@@ -3756,6 +7034,19 @@ fn tennis_score_to_win_prob(leading_sets: i32, losing_sets: i32) -> Option<f64>
     }
 }
 
+/// Počet setů tenisového zápasu: explicitní BoN (series_format / detailed), jinak
+/// 4./5. set v detailed_score ⇒ Bo5. Default Bo3.
+fn tennis_best_of(series_format: Option<&str>, detailed: Option<&str>) -> u8 {
+    if let Some(n @ (3 | 5)) = parse_series_best_of(series_format, detailed) {
+        return n;
+    }
+    let lower = detailed.unwrap_or("").to_lowercase();
+    let bo5_set = ["4.set", "5.set", "4. set", "5. set", "set 4", "set 5"]
+        .iter()
+        .any(|marker| lower.contains(marker));
+    if bo5_set { 5 } else { 3 }
+}
+
 fn football_minute_from_context(status: Option<&str>, detailed_score: Option<&str>) -> Option<i32> {
     detailed_score
         .and_then(|detail| parse_football_minute_static(detail).map(|minute| minute as i32))
@@ -3826,6 +7117,14 @@ fn cs2_round_match_winner_guard(
     standard || first_map_domination
 }
 
+/// Bo1 match_winner je de facto map_winner → žádná map-lead podmínka,
+/// jen stejné minimum kol, pravděpodobnosti a odds cap jako round-level match_winner.
+fn cs2_bo1_match_winner_guard(expected_prob: f64, round_total: i32, azuro_odds: Option<f64>) -> bool {
+    azuro_odds.is_some_and(|odds| odds <= CS2_ROUND_MATCH_WINNER_MAX_ODDS)
+        && expected_prob >= 0.68
+        && round_total >= CS2_ROUND_MATCH_WINNER_MIN_ROUNDS
+}
+
 fn is_cs2_terminal_map_score(score1: i32, score2: i32) -> bool {
     let hi = score1.max(score2);
     let lo = score1.min(score2);
@@ -3837,7 +7136,8 @@ fn is_cs2_terminal_map_score(score1: i32, score2: i32) -> bool {
         return hi - lo >= 2;
     }
 
-    hi - lo >= 2
+    // MR3 overtime od 12:12: mapa končí na 16, 19, 22, ... s náskokem 2–4 kol (16:14, 19:15)
+    lo >= 12 && hi >= 16 && (hi - 16) % 3 == 0 && (2..=4).contains(&(hi - lo))
 }
 
 fn mark_cs2_glitch_quarantine(tracker: &mut ScoreTracker, match_key: &str, now: DateTime<Utc>) {
@@ -4018,7 +7318,7 @@ fn should_audit_esports_score_decision(
         )
 }
 
-fn append_ledger_audit_event(event: &str, data: &serde_json::Value) {
+fn append_ledger_audit_event(path: &Path, event: &str, data: &serde_json::Value) {
     let mut entry = data.clone();
     if let Some(obj) = entry.as_object_mut() {
         obj.insert("ts".to_string(), serde_json::json!(Utc::now().to_rfc3339()));
@@ -4027,7 +7327,7 @@ fn append_ledger_audit_event(event: &str, data: &serde_json::Value) {
     if let Ok(mut f) = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open("data/ledger.jsonl")
+        .open(path)
     {
         use std::io::Write;
         let _ = writeln!(f, "{}", entry);
@@ -4174,6 +7474,87 @@ fn score_edge_stake_multiplier(edge: &ScoreEdge, sport: &str, azuro_odds: f64) -
     mult
 }
 
+/// Multiplikátor stake z confidence predikce: lineárně confidence / pivot, ořezaný do [min, max].
+/// 0.95 vs 0.75 tak dá stake v poměru 0.95 : 0.75 (před caps).
+fn confidence_stake_multiplier(confidence: f32) -> f64 {
+    if !confidence.is_finite() || confidence <= 0.0 {
+        return 1.0;
+    }
+    (confidence as f64 / CONFIDENCE_STAKE_PIVOT).clamp(CONFIDENCE_STAKE_MIN_MULT, CONFIDENCE_STAKE_MAX_MULT)
+}
+
+/// Pre-cap stake po confidence multiplikátoru; bez confidence beze změny.
+fn confidence_scaled_stake(raw_stake: f64, confidence: Option<f32>) -> f64 {
+    confidence.map_or(raw_stake, |conf| raw_stake * confidence_stake_multiplier(conf))
+}
+
+/// Confidence sdíleného PredictionEngine pro score edge — z round skóre aktuální mapy.
+/// None, pokud engine nic neříká nebo predikuje opačnou stranu než `leading_side` edge.
+fn prediction_confidence_for_edge(engine: &PredictionEngine, edge: &ScoreEdge, sport: &str) -> Option<f32> {
+    let family = match edge.esports_family.unwrap_or(sport) {
+        "dota-2" => "dota2",
+        "league-of-legends" => "lol",
+        other => other,
+    };
+    let (r1, r2) = parse_esports_round_score(edge.detailed_score.as_deref()?)?;
+    let map_number = (edge.score1 + edge.score2 + 1).clamp(1, u8::MAX as i32) as u8;
+    let state = match_state_with_series(
+        family,
+        &edge.team1,
+        &edge.team2,
+        r1.clamp(0, u8::MAX as i32) as u8,
+        r2.clamp(0, u8::MAX as i32) as u8,
+        map_number,
+        None,
+        true,
+    );
+    let prediction = engine.predict(&state);
+    let predicted_side = match prediction {
+        Prediction::Team1Win(_) => 1,
+        Prediction::Team2Win(_) => 2,
+        Prediction::Uncertain => return None,
+    };
+    if predicted_side != edge.leading_side {
+        return None;
+    }
+    prediction.confidence()
+}
+
+#[cfg(test)]
+mod confidence_stake_tests {
+    use super::{confidence_scaled_stake, confidence_stake_multiplier, dynamic_base_stake,
+        CONFIDENCE_STAKE_MAX_MULT, CONFIDENCE_STAKE_MIN_MULT};
+    use prediction_engine::{match_state_with_series, PredictionEngine};
+
+    #[test]
+    fn higher_confidence_scales_pre_cap_stake_proportionally() {
+        let raw = dynamic_base_stake(200.0, "cs2") * 1.3;
+        let high = confidence_scaled_stake(raw, Some(0.95));
+        let low = confidence_scaled_stake(raw, Some(0.75));
+
+        assert!(high > raw && low < raw);
+        assert!((high / low - 0.95 / 0.75).abs() < 1e-6);
+        assert_eq!(confidence_scaled_stake(raw, None), raw);
+    }
+
+    #[test]
+    fn engine_confidence_feeds_the_multiplier() {
+        let engine = PredictionEngine::new();
+        let state = match_state_with_series("cs2", "Alpha", "Beta", 12, 8, 1, None, true);
+        let conf = engine.predict(&state).confidence().unwrap();
+
+        assert!((confidence_stake_multiplier(conf) - 0.95 / 0.85).abs() < 1e-6);
+    }
+
+    #[test]
+    fn multiplier_is_clamped_and_ignores_garbage() {
+        assert_eq!(confidence_stake_multiplier(1.0), CONFIDENCE_STAKE_MAX_MULT.min(1.0 / 0.85));
+        assert_eq!(confidence_stake_multiplier(0.2), CONFIDENCE_STAKE_MIN_MULT);
+        assert_eq!(confidence_stake_multiplier(f32::NAN), 1.0);
+        assert_eq!(confidence_stake_multiplier(0.0), 1.0);
+    }
+}
+
 /// Dota-2 kill score → estimated win probability.
 /// Kill leads in Dota-2 correlate with gold/XP advantage.
 /// Requires significant lead to be actionable (kills swing fast).
@@ -4274,25 +7655,99 @@ fn mma_score_to_win_prob(leading: i32, losing: i32) -> Option<f64> {
     }
 }
 
+/// Sestaví consensus pro jeden zápas: primární feed, ostatní live zdroje (týmy zarovnané
+/// na pořadí primárního feedu) a Chance detailed round score. Zdroje s jinou úrovní skóre
+/// (mapy vs roundy) se nemíchají — 1-0 na mapy není konflikt s 10-4 na roundy.
+fn build_live_score_consensus(
+    primary: &LiveItem,
+    primary_score: (i32, i32),
+    others: &[&LiveItem],
+    chance_round: Option<(i32, i32)>,
+    esports_family: Option<&str>,
+) -> ScoreConsensus {
+    let round_level = primary_score.0.max(primary_score.1) > 3;
+    let mut consensus = ScoreConsensus::default();
+    consensus.add(&primary.source, primary_score, score_source_weight(&primary.source));
+
+    for item in others {
+        if std::ptr::eq(*item, primary) || item.source == primary.source {
+            continue;
+        }
+        let (n1, n2) = normalize_cs2_live_score_for_edge(
+            item.payload.score1,
+            item.payload.score2,
+            item.payload.detailed_score.as_deref(),
+            esports_family,
+        );
+        let swapped = team_name_matches_single(&item.payload.team1, &primary.payload.team2)
+            && team_name_matches_single(&item.payload.team2, &primary.payload.team1);
+        let score = if swapped { (n2, n1) } else { (n1, n2) };
+        if (score.0.max(score.1) > 3) != round_level {
+            continue;
+        }
+        consensus.add(&item.source, score, score_source_weight(&item.source));
+    }
+
+    if let Some(round) = chance_round {
+        if (round.0.max(round.1) > 3) == round_level {
+            consensus.add("chance_detailed", round, score_source_weight("chance_detailed"));
+        }
+    }
+    consensus
+}
+
+/// Bookmaker prefixy execution venue (kde umíme vsadit); ostatní odds jsou tržní reference.
+#[derive(Debug, Clone)]
+struct ExecutionVenues {
+    prefixes: Vec<String>,
+}
+
+impl Default for ExecutionVenues {
+    fn default() -> Self {
+        Self { prefixes: vec!["azuro_".to_string()] }
+    }
+}
+
+impl ExecutionVenues {
+    /// EXECUTION_VENUE_PREFIXES="azuro_,azuro_polygon_v3" (čárkami oddělené), jinak default.
+    fn from_env() -> Self {
+        let prefixes: Vec<String> = std::env::var("EXECUTION_VENUE_PREFIXES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if prefixes.is_empty() { Self::default() } else { Self { prefixes } }
+    }
+
+    fn is_venue(&self, bookmaker: &str) -> bool {
+        self.prefixes.iter().any(|p| bookmaker.starts_with(p.as_str()))
+    }
+}
+
 /// Detect score-based edges: HLTV live score says one team leads,
 /// but Azuro odds haven't adjusted yet → BET on the leading team!
 fn find_score_edges(
     state: &StateResponse,
     tracker: &mut ScoreTracker,
     resync_freeze: &mut HashMap<String, ResyncState>,
+    venues: &ExecutionVenues,
 ) -> Vec<ScoreEdge> {
-    let now = Utc::now();
+    let now = tracker.clock.now_utc();
     let mut edges = Vec::new();
 
     // Build live score map
     let mut live_map: HashMap<&str, &LiveItem> = HashMap::new();
     let mut live_map_priority: HashMap<&str, i32> = HashMap::new();
+    // Všechny použitelné zdroje per zápas (pro score consensus)
+    let mut live_sources: HashMap<&str, Vec<&LiveItem>> = HashMap::new();
     for live in &state.live {
         let priority = live_item_priority(live);
         if priority < 0 {
             continue;
         }
         let key = live.match_key.as_str();
+        live_sources.entry(key).or_default().push(live);
         let should_replace = live_map_priority
             .get(key)
             .map(|current| priority > *current)
@@ -4303,12 +7758,17 @@ fn find_score_edges(
         }
     }
 
-    // Build Azuro odds map (only azuro_ bookmakers, match_winner)
+    // Build Azuro odds map (only execution-venue bookmakers, match_winner)
     let mut azuro_by_match: HashMap<&str, &StateOddsItem> = HashMap::new();
     // Build map winner odds map: match_key → Vec<MapWinnerOdds>
     let mut map_winners_by_match: HashMap<&str, Vec<MapWinnerOdds>> = HashMap::new();
     for item in &state.odds {
-        if !item.payload.bookmaker.starts_with("azuro_") {
+        if !venues.is_venue(&item.payload.bookmaker) {
+            continue;
+        }
+        if !is_moneyline_market(item.payload.market.as_deref()) {
+            debug!("MARKET_FILTER drop (score edge): match_key={} market={:?} bookmaker={}",
+                item.match_key, item.payload.market, item.payload.bookmaker);
             continue;
         }
         let market = item.payload.market.as_deref().unwrap_or("match_winner");
@@ -4332,6 +7792,7 @@ fn find_score_edges(
                     bookmaker: item.payload.bookmaker.clone(),
                     chain: item.payload.chain.clone(),
                     url: item.payload.url.clone(),
+                    game_id: item.payload.game_id.clone(),
                 });
         }
     }
@@ -4359,6 +7820,26 @@ fn find_score_edges(
             live_esports_class.family,
         );
 
+        // SCORE CONSENSUS: primární feed + ostatní live zdroje + Chance detailed round score
+        let is_cs2_like_match = match_key.starts_with("cs2::") || match_key.starts_with("esports::");
+        let score_consensus = if is_cs2_like_match {
+            let chance_round = if FF_CHANCE_ROUND_PARSER {
+                live.payload.detailed_score.as_deref().and_then(parse_cs2_round_score)
+            } else { None };
+            let others: &[&LiveItem] = live_sources.get(*match_key).map(|v| v.as_slice()).unwrap_or(&[]);
+            build_live_score_consensus(live, (s1, s2), others, chance_round, live_esports_class.family)
+        } else {
+            ScoreConsensus::default()
+        };
+        let (s1, s2) = match score_consensus.resolve() {
+            Some(c) if FF_SCORE_CONSENSUS && c.has_majority() && c.score != (s1, s2) => {
+                info!("  🗳️ {} SCORE CONSENSUS: {} {}-{} → {}-{} ({}/{} sources, agreement {:.2})",
+                    match_key, live.source, s1, s2, c.score.0, c.score.1, c.agreeing, c.sources, c.agreement);
+                c.score
+            }
+            _ => (s1, s2),
+        };
+
         // Check if score changed from previous poll
         let prev = tracker.prev_scores.get(*match_key).cloned();
 
@@ -4368,6 +7849,56 @@ fn find_score_edges(
             None => (s1, s2, now), // First time: use current score as "previous" for edge calc
         };
 
+        // MAP ROLLOVER: 19-17 -> nová mapa 1-0 se sérií +1 není backward glitch — sledovat novou mapu
+        // hned, dřív než hold / backward guard podrží staré skóre a cooldown do prvních kol.
+        let cs2_legit_map_rollover = !is_first_sight && is_cs2_legit_map_rollover(
+            prev_s1,
+            prev_s2,
+            s1,
+            s2,
+            raw_s1,
+            raw_s2,
+            live.payload.detailed_score.as_deref(),
+            live.payload.series_format.as_deref(),
+            live_esports_class.family,
+        );
+        if cs2_legit_map_rollover {
+            info!(
+                "  🔄 {} CS2 map rollover {}-{} -> {}-{} ({}), sleduji novou mapu",
+                match_key, prev_s1, prev_s2, s1, s2, live.payload.detailed_score.as_deref().unwrap_or("")
+            );
+            tracker.prev_scores.insert(match_key.to_string(), (s1, s2, now));
+            tracker.backward_scores.remove(*match_key);
+            if should_audit_esports_score_decision(
+                match_key,
+                live_esports_class.family,
+                live_esports_class.confidence,
+            ) {
+                tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                    "match_key": match_key,
+                    "path": "score_model",
+                    "decision": "rollover_tracked",
+                    "reason_code": "ScoreMapRollover",
+                    "reason_codes": ["ScoreMapRollover"],
+                    "resolved_sport": live_esports_class.family.or(live.payload.sport.as_deref()),
+                    "esports_family": live_esports_class.family,
+                    "sport_confidence": live_esports_class.confidence,
+                    "sport_reason": live_esports_class.reason,
+                    "team1": live.payload.team1,
+                    "team2": live.payload.team2,
+                    "raw_score1": raw_s1,
+                    "raw_score2": raw_s2,
+                    "prev_score1": prev_s1,
+                    "prev_score2": prev_s2,
+                    "score1": s1,
+                    "score2": s2,
+                    "live_status": live.payload.status,
+                    "detailed_score": live.payload.detailed_score,
+                }));
+            }
+            continue;
+        }
+
         let cs2_reset_hold_state = is_cs2_reset_hold_state(
             prev_s1,
             prev_s2,
@@ -4434,7 +7965,7 @@ fn find_score_edges(
                     live_esports_class.family,
                     live_esports_class.confidence,
                 ) {
-                    append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                    tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                         "match_key": match_key,
                         "path": "score_model",
                         "decision": "hold_candidate",
@@ -4461,17 +7992,6 @@ fn find_score_edges(
             continue;
         }
 
-        let cs2_legit_map_rollover = is_cs2_legit_map_rollover(
-            prev_s1,
-            prev_s2,
-            s1,
-            s2,
-            raw_s1,
-            raw_s2,
-            live.payload.detailed_score.as_deref(),
-            live_esports_class.family,
-        );
-
         let score_changed = s1 != prev_s1 || s2 != prev_s2;
         let elapsed_secs = (now - prev_seen_at).num_seconds().max(1);
         // Guard against score-mode switches / parser glitches:
@@ -4480,8 +8000,7 @@ fn find_score_edges(
             && s1 <= prev_s1
             && s2 <= prev_s2
             && (s1 < prev_s1 || s2 < prev_s2)
-            && !cs2_round_context_override
-            && !cs2_legit_map_rollover;
+            && !cs2_round_context_override;
 
         if !backward_score_jump {
             tracker.backward_scores.remove(*match_key);
@@ -4531,7 +8050,7 @@ fn find_score_edges(
                     live_esports_class.family,
                     live_esports_class.confidence,
                 ) {
-                    append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                    tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                         "match_key": match_key,
                         "path": "score_model",
                         "decision": "hold_candidate",
@@ -4557,6 +8076,7 @@ fn find_score_edges(
                 continue;
             }
 
+            tracker.skip_tally.record(SkipReason::ScoreRewind);
             info!(
                 "  ⏭️ {} score jump backward {}-{} -> {}-{} (source/reset), skipping edge eval",
                 match_key, prev_s1, prev_s2, s1, s2
@@ -4566,7 +8086,7 @@ fn find_score_edges(
                 live_esports_class.family,
                 live_esports_class.confidence,
             ) {
-                append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                     "match_key": match_key,
                     "path": "score_model",
                     "decision": "blocked_candidate",
@@ -4594,6 +8114,17 @@ fn find_score_edges(
 
         tracker.prev_scores.insert(match_key.to_string(), (s1, s2, now));
 
+        // DEAD-MAN'S SWITCH: "live" zápas se zamrzlým skóre = zaseklý scraper, stale lead nehrát.
+        // Fotbal/hokej mají přirozeně dlouhé úseky bez gólu → vyjmuté.
+        let freeze_exempt = matches!(match_key.split("::").next(), Some("football" | "hockey"));
+        if let Some(frozen_secs) = tracker.frozen_score_secs(match_key, s1, s2, now) {
+            if !freeze_exempt {
+                debug!("  🧊 {} {}-{}: score frozen for {}s (> {}s), skipping edge eval",
+                    match_key, s1, s2, frozen_secs, tracker.frozen_window_secs);
+                continue;
+            }
+        }
+
         if is_cs2_like(live_esports_class.family, live.payload.detailed_score.as_deref())
             && relax_cs2_distrust_on_stable_progress(tracker, match_key, now, score_changed, prev_s1, prev_s2, s1, s2)
         {
@@ -4623,7 +8154,7 @@ fn find_score_edges(
                     live_esports_class.family,
                     live_esports_class.confidence,
                 ) {
-                    append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                    tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                         "match_key": match_key,
                         "path": "score_model",
                         "decision": "blocked_candidate",
@@ -4650,6 +8181,7 @@ fn find_score_edges(
             }
             if let Some(quarantine_until) = tracker.cs2_glitch_quarantine_until.get(*match_key) {
                 if now < *quarantine_until {
+                    tracker.skip_tally.record(SkipReason::Cs2Quarantine);
                     info!(
                         "  ⏭️ {} {}-{}: CS2 glitch quarantine active until {} — skipping edge eval",
                         match_key,
@@ -4680,6 +8212,7 @@ fn find_score_edges(
             mark_cs2_glitch_quarantine(tracker, match_key, now);
             // Reduced 3→2: forward spike is suspicious but let it accumulate rather than instant-lock.
             let (_, distrust_lock_until) = record_cs2_distrust_event(tracker, match_key, now, 2);
+            tracker.skip_tally.record(SkipReason::ScoreSpike);
             info!(
                 "  ⏭️ {} CS2 forward spike {}-{} -> {}-{} ({}s) — skipping edge eval",
                 match_key,
@@ -4701,7 +8234,7 @@ fn find_score_edges(
                 live_esports_class.family,
                 live_esports_class.confidence,
             ) {
-                append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                     "match_key": match_key,
                     "path": "score_model",
                     "decision": "blocked_candidate",
@@ -4744,6 +8277,7 @@ fn find_score_edges(
                     || ((s1.max(s2) - s1.min(s2)) >= 60 && delta1.max(delta2) >= 15)
             );
         if forward_basket_spike {
+            tracker.skip_tally.record(SkipReason::ScoreSpike);
             info!(
                 "  ⏭️ {} basketball forward spike {}-{} -> {}-{} (Δ={} / {}, {}s), skipping edge eval",
                 match_key,
@@ -4791,20 +8325,11 @@ fn find_score_edges(
         // === SPORT-AWARE SCORE SANITY CHECK ===
         // Catches garbage scores from FlashScore DOM concatenation (e.g. 714-0, 19-45 labeled as football)
         let sport_prefix = match_key.split("::").next().unwrap_or("unknown");
-        let max_score_for_sport: i32 = match sport_prefix {
-            "football" => 8,       // max realistic football score per team (tightened from 15)
-            "tennis" => 7,         // max sets in a match
-            "hockey" => 10,        // max realistic hockey score (tightened from 15 — garbage scraper scores were 12+)
-            "basketball" => 200,   // max realistic basketball score per team
-            "cs2" => 40,           // round scores (30 + OT rounds)
-            "dota-2" => 100,       // kill scores
-            "mma" | "boxing" => 5, // round scores
-            "handball" => 45,      // max realistic handball score (tightened from 50)
-            "volleyball" => 5,     // set scores
-            "esports" => 50,       // generic esports limit
-            _ => 999,
-        };
-        if s1 > max_score_for_sport || s2 > max_score_for_sport {
+        let max_score_for_sport = tracker.score_ceilings.max_for(sport_prefix);
+        let sanity_failed = s1 > max_score_for_sport || s2 > max_score_for_sport;
+        tracker.sport_kill.record(sport_prefix, match_key, sanity_failed, now);
+        if sanity_failed {
+            tracker.skip_tally.record(SkipReason::ScoreSanity);
             info!("  ⏭️ {} {}-{}: {} score sanity FAIL (max={}), skipping",
                 match_key, s1, s2, sport_prefix, max_score_for_sport);
             continue;
@@ -4819,6 +8344,7 @@ fn find_score_edges(
             && is_cs2_terminal_map_score(s1, s2)
         {
             mark_cs2_glitch_quarantine(tracker, match_key, now);
+            tracker.skip_tally.record(SkipReason::Cs2Quarantine);
             info!(
                 "  ⏭️ {} {}-{}: terminal CS2 map score — likely settled map/rollover, skipping edge eval without distrust penalty",
                 match_key,
@@ -4830,7 +8356,7 @@ fn find_score_edges(
                 live_esports_class.family,
                 live_esports_class.confidence,
             ) {
-                append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                     "match_key": match_key,
                     "path": "score_model",
                     "decision": "hold_candidate",
@@ -4883,10 +8409,38 @@ fn find_score_edges(
         // Guard: REQUIRE parseable minute from detailed_score (= reliable data).
         let is_lol = match_key.starts_with("league-of-legends::");
         let is_valorant = match_key.starts_with("valorant::");
+        // Bo1 (CS2/Valorant): jediná mapa = zápas → map win prob z round score je rovnou match prob
+        // a match_winner je primární trh (map1_winner jen fallback, když match_winner odds chybí).
+        let is_bo1 = (is_cs2_like(live_esports_class.family, live.payload.detailed_score.as_deref())
+            || is_valorant
+            || is_promoted_valorant)
+            && is_bo1_series(live.payload.series_format.as_deref(), live.payload.detailed_score.as_deref());
+        let bo1_round_level = is_bo1 && leading_maps.max(losing_maps) > 3;
+
+        // TOO LATE: mapa / zápas se rozhodne v příštím roundu / gamu → trh se zavře před exekucí
+        let round_level = leading_maps.max(losing_maps) > 3;
+        let (too_late_sport, points_to_resolve) = if is_tennis {
+            let best_of = tennis_best_of(live.payload.series_format.as_deref(), live.payload.detailed_score.as_deref());
+            ("tennis", tennis_games_to_match_win(leading_side, leading_maps, best_of, live.payload.detailed_score.as_deref()))
+        } else if is_valorant || is_promoted_valorant {
+            ("valorant", bo1_round_level.then(|| valorant_rounds_to_map_win(leading_maps, losing_maps)))
+        } else if is_cs2_like(live_esports_class.family, live.payload.detailed_score.as_deref()) || is_promoted_cs2 {
+            ("cs2", round_level.then(|| cs2_rounds_to_map_win(leading_maps, losing_maps)))
+        } else {
+            (sport_prefix, None)
+        };
+        if let Some(left) = tracker.too_late.too_late(too_late_sport, points_to_resolve) {
+            tracker.skip_tally.record(SkipReason::TooLate);
+            info!("  ⏭️ {} {}-{}: TOO LATE — {} {} to resolution (margin {}), market closes before execution",
+                match_key, s1, s2, left, if too_late_sport == "tennis" { "game(s)" } else { "round(s)" },
+                tracker.too_late.margin_for(too_late_sport));
+            continue;
+        }
         if is_football {
             let ds = live.payload.detailed_score.as_deref().unwrap_or("");
             let has_minute = ds.contains(".min") || ds.contains("min.") || ds.contains("poločas") || ds.contains("pol.");
             if !has_minute {
+                tracker.skip_tally.record(SkipReason::MissingMinute);
                 info!("  ⏭️ {} {}-{}: football score-edge SKIPPED (no minute in detailed_score: '{}')", match_key, s1, s2, ds);
                 continue;
             }
@@ -4894,9 +8448,11 @@ fn find_score_edges(
 
         let expected_prob = if is_tennis {
             // Tennis: scores are SET counts (0-2)
-            match tennis_score_to_win_prob(leading_maps, losing_maps) {
+            let best_of = tennis_best_of(live.payload.series_format.as_deref(), live.payload.detailed_score.as_deref());
+            match tennis_score_to_win_prob(leading_maps, losing_maps, best_of) {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: tennis score not actionable",
                         match_key, s1, s2);
                     continue;
@@ -4908,6 +8464,7 @@ fn find_score_edges(
             match football_score_to_win_prob(leading_maps, losing_maps, minute) {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: football score not actionable",
                         match_key, s1, s2);
                     continue;
@@ -4927,6 +8484,7 @@ fn find_score_edges(
             match dota_prob {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: dota-2 score not actionable (diff={}, total={}, detailed='{}')",
                         match_key, s1, s2, leading_maps - losing_maps, s1 + s2,
                         live.payload.detailed_score.as_deref().unwrap_or(""));
@@ -4938,9 +8496,13 @@ fn find_score_edges(
             // LoL: map (game) scores 0-2 in Bo3, 0-3 in Bo5
             // Valorant: map scores 0-2 in Bo3
             // (1,0) = won 1 map → ~58% (map pick advantage, less than CS2)
-            match map_score_to_win_prob(leading_maps, losing_maps) {
+            // Valorant Bo1: round score (do 13 jako CS2) → map prob = match prob
+            let bo1_prob = (bo1_round_level && (is_valorant || is_promoted_valorant))
+                .then(|| cs2_map_win_prob(leading_maps - losing_maps, s1 + s2));
+            match bo1_prob.or_else(|| map_score_to_win_prob(leading_maps, losing_maps)) {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: {} map score not actionable",
                         match_key, s1, s2, if is_lol { "LoL" } else { "Valorant" });
                     continue;
@@ -4951,6 +8513,7 @@ fn find_score_edges(
             // Point lead model — we don't have quarter/time, use total points as proxy.
             // Guard: garbage parse values (score > 200 = Tipsport concatenation artifact)
             if s1.max(s2) > 200 || s1.max(s2) < 0 {
+                tracker.skip_tally.record(SkipReason::ScoreSanity);
                 info!("  ⏭️ {} {}-{}: basketball score looks like garbage (max>200), skipping",
                     match_key, s1, s2);
                 continue;
@@ -4958,6 +8521,7 @@ fn find_score_edges(
             match basketball_score_to_win_prob(leading_maps, losing_maps) {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: basketball score not actionable (diff={})",
                         match_key, s1, s2, leading_maps - losing_maps);
                     continue;
@@ -4968,6 +8532,7 @@ fn find_score_edges(
             match mma_score_to_win_prob(leading_maps, losing_maps) {
                 Some(p) => p,
                 None => {
+                    tracker.skip_tally.record(SkipReason::NotActionable);
                     info!("  ⏭️ {} {}-{}: MMA score not actionable", match_key, s1, s2);
                     continue;
                 }
@@ -4985,6 +8550,7 @@ fn find_score_edges(
             if match_key.starts_with("esports::") {
                 let max_s = s1.max(s2);
                 if max_s > 30 {
+                    tracker.skip_tally.record(SkipReason::ScoreSanity);
                     info!("  ⏭️ {} {}-{}: promoted CS2 but score > 30 (e-basketball or parse garbage), skipping",
                         match_key, s1, s2);
                     continue;
@@ -4992,10 +8558,16 @@ fn find_score_edges(
                 info!("  ✅ {} A2 PROMOTED: esports::→CS2 model (family={:?}, confidence={}, reason={})",
                     match_key, live_esports_class.family, live_esports_class.confidence, live_esports_class.reason);
             }
+            // Bo1: žádný map context — round score → map prob = match prob
+            if bo1_round_level {
+                let p = cs2_map_win_prob(leading_maps - losing_maps, s1 + s2);
+                info!("  🎯 {} CS2 Bo1: rounds {}-{} → match_prob={:.1}% (single map = match)",
+                    match_key, leading_maps, losing_maps, p * 100.0);
+                p
             // Phase 1: CS2 match_winner from round scores
             // When live_score is round-level (max > 3), try to compute
             // match win probability using round + map context.
-            if FF_CS2_MATCH_FROM_ROUNDS && leading_maps.max(losing_maps) > 3 {
+            } else if FF_CS2_MATCH_FROM_ROUNDS && leading_maps.max(losing_maps) > 3 {
                 // Parse map context from detailed_score
                 let ds = live.payload.detailed_score.as_deref().unwrap_or("");
                 let (ml, mm) = parse_esports_map_score(ds, s1, s2);
@@ -5032,6 +8604,7 @@ fn find_score_edges(
                 match score_to_win_prob(leading_maps, losing_maps) {
                     Some(p) => p,
                     None => {
+                        tracker.skip_tally.record(SkipReason::NotActionable);
                         info!("  ⏭️ {} {}-{}: score not actionable (diff={}, max={})",
                             match_key, s1, s2, leading_maps - losing_maps,
                             leading_maps.max(losing_maps));
@@ -5053,9 +8626,8 @@ fn find_score_edges(
         } else { None };
 
         // Only cross-validate for CS2/esports matches with round-level scores
-        let is_cs2_like_match = match_key.starts_with("cs2::") || match_key.starts_with("esports::");
         let (cv_skip, cv_stake_mult) = if FF_CROSS_VALIDATION && is_cs2_like_match && s1.max(s2) > 3 {
-            cross_validation_check(Some((s1, s2)), chance_round)
+            cross_validation_check(&score_consensus)
         } else {
             (false, 1.0) // non-CS2 or non-round-level → skip validation
         };
@@ -5064,8 +8636,9 @@ fn find_score_edges(
         // cv_skip is always false now — mismatch just reduces stake to 0.5x
         if FF_RESYNC_FREEZE && is_cs2_like_match && cv_stake_mult < 1.0 {
             // Record mismatch for tracking (no blocking)
-            let rs = resync_freeze.entry(match_key.to_string()).or_insert_with(ResyncState::new);
-            rs.record_mismatch();
+            let clock = tracker.clock.as_ref();
+            let rs = resync_freeze.entry(match_key.to_string()).or_insert_with(|| ResyncState::new(clock));
+            rs.record_mismatch(clock);
             info!("  ⚠️ {} CROSS-VAL MISMATCH (hedged 0.5x): HLTV={}-{} vs Chance={:?} detailed='{}'",
                 match_key, s1, s2, chance_round, detailed);
         } else if FF_RESYNC_FREEZE && is_cs2_like_match && cv_stake_mult > 1.0 {
@@ -5162,8 +8735,29 @@ fn find_score_edges(
             }
         }
 
+        let azuro_match_winner_opt: Option<&StateOddsItem> = azuro_by_match.get(odds_lookup_key).copied().or_else(|| {
+            azuro_by_match.values().find(|item| {
+                item.payload.market.as_deref().unwrap_or("match_winner") == "match_winner"
+                    && is_recent_seen_at(&item.seen_at, now)
+                    && teams_match_loose(
+                        &live.payload.team1,
+                        &live.payload.team2,
+                        &item.payload.team1,
+                        &item.payload.team2,
+                    )
+            }).copied()
+        });
+
+        // Bo1: match_winner je primární, pokud na něj máme čerstvé odds (map1_winner = stejný bet)
+        let bo1_match_winner_primary = bo1_round_level
+            && azuro_match_winner_opt.is_some_and(|a| is_recent_seen_at(&a.seen_at, now));
+        if bo1_match_winner_primary {
+            info!("  🎯 {} {}-{}: Bo1 — match_winner primary, skipping map_winner path",
+                match_key, s1, s2);
+        }
+
         // === STEP 1: Check MAP WINNER edges FIRST (highest priority) ===
-        if max_score > 3 && diff >= 3 {
+        if max_score > 3 && diff >= 3 && !bo1_match_winner_primary {
             // This is a round-level score within a CS2 map
             if let Some((_, map_odds_list)) = map_odds_list_opt {
                 // CS2 map win probability: based on (diff, total_rounds)
@@ -5183,6 +8777,7 @@ fn find_score_edges(
 
                 for mw in map_odds_list {
                     if !is_recent_seen_at(&mw.seen_at, now) {
+                        tracker.skip_tally.record(SkipReason::StaleOdds);
                         info!("  ⏭️ {} {}-{}: MW {} skipped (stale odds)",
                             match_key, s1, s2, mw.market);
                         continue;
@@ -5194,11 +8789,13 @@ fn find_score_edges(
                         .map(|c| c as u8 - b'0');
                     match (active_map_num, market_map_num) {
                         (Some(active), Some(mm)) if mm != active => {
+                            tracker.skip_tally.record(SkipReason::MapMismatch);
                             info!("  ⏭️ {} {}-{}: MW {} skipped (active map={}, market map={})",
                                 match_key, s1, s2, mw.market, active, mm);
                             continue;
                         }
                         (None, _) => {
+                            tracker.skip_tally.record(SkipReason::MapMismatch);
                             info!("  ⏭️ {} {}-{}: MW {} skipped (cannot determine active map)",
                                 match_key, s1, s2, mw.market);
                             continue;
@@ -5213,16 +8810,16 @@ fn find_score_edges(
                     ) {
                         Some(s) => s,
                         None => {
-                            let _leading_team = if leading_side == 1 { &live.payload.team1 } else { &live.payload.team2 };
+                            tracker.skip_tally.record(SkipReason::AmbiguousTeams);
                             info!("  🛑 {} MW {}: TEAM IDENTITY AMBIGUOUS! live={}+{} azuro={}+{} — BLOCKING bet",
                                 match_key, mw.market, live.payload.team1, live.payload.team2, mw.team1, mw.team2);
                             continue;
                         }
                     };
                     if azuro_side != leading_side {
-                        let leading_team = if leading_side == 1 { &live.payload.team1 } else { &live.payload.team2 };
                         info!("  🔀 {} MW {}: team order fix! live leading={} (side {}), matched azuro side {} ({})",
-                            match_key, mw.market, leading_team, leading_side, azuro_side, if azuro_side == 1 { &mw.team1 } else { &mw.team2 });
+                            match_key, mw.market, side_team(&live.payload.team1, &live.payload.team2, leading_side),
+                            leading_side, azuro_side, side_team(&mw.team1, &mw.team2, azuro_side));
                     }
 
                     let mw_implied = if azuro_side == 1 {
@@ -5234,19 +8831,18 @@ fn find_score_edges(
                     let mw_edge = (map_win_prob - mw_implied) * 100.0;
 
                     if mw_edge < MIN_SCORE_EDGE_PCT {
+                        tracker.skip_tally.record(SkipReason::BelowThreshold);
                         info!("  🗺️ {} {}-{}: MW {} edge={:.1}% < min {}%",
                             match_key, s1, s2, mw.market, mw_edge, MIN_SCORE_EDGE_PCT);
+                        if let Some(near_miss) = &tracker.near_miss {
+                            near_miss.record("score_edge", match_key, sport_prefix, mw_edge, MIN_SCORE_EDGE_PCT,
+                                &format!("{} below min edge at {}-{}", mw.market, s1, s2));
+                        }
                         continue;
                     }
 
                     let mw_confidence = if mw_edge >= 12.0 { "HIGH" } else { "MEDIUM" };
-                    let mw_outcome_id = if azuro_side == 1 {
-                        mw.outcome1_id.clone()
-                    } else {
-                        mw.outcome2_id.clone()
-                    };
-
-                    let leading_team_name = if leading_side == 1 { &live.payload.team1 } else { &live.payload.team2 };
+                    let leading_team_name = side_team(&live.payload.team1, &live.payload.team2, leading_side);
                     info!("🗺️ MAP WINNER EDGE [PRIORITY]: {} leads {}-{}, {} implied={:.1}%, map_prob={:.1}%, edge={:.1}% (azuro_side={}, tier={}, max_odds={:.2}) — BLOCKING match_winner",
                         leading_team_name, s1, s2, mw.market, mw_implied * 100.0, map_win_prob * 100.0, mw_edge, azuro_side,
                         map_confidence_tier, cs2_dynamic_max_odds(map_confidence_tier));
@@ -5291,16 +8887,19 @@ fn find_score_edges(
                         score_implied_pct: map_win_prob * 100.0,
                         edge_pct: mw_edge,
                         confidence: mw_confidence,
-                        game_id: None,
+                        game_id: mw.game_id.clone(),
                         condition_id: mw.condition_id.clone(),
                         outcome1_id: so1,
                         outcome2_id: so2,
-                        outcome_id: mw_outcome_id,
                         chain: mw.chain.clone(),
                         azuro_url: mw.url.clone(),
                         cs2_map_confidence: Some(map_confidence_tier),
                         cv_stake_mult,
                         detailed_score: live.payload.detailed_score.clone(),
+                        series_bo1: is_bo1,
+                        azuro_side,
+                        outcome_draw_id: None,
+                        outcome_order: azuro_outcome_order(mw.outcome1_id.as_deref(), mw.outcome2_id.as_deref(), None),
                     });
                 }
             }
@@ -5314,20 +8913,10 @@ fn find_score_edges(
         }
 
         // Get current Azuro odds for match winner
-        let azuro = match azuro_by_match.get(odds_lookup_key).copied().or_else(|| {
-            azuro_by_match.values().find(|item| {
-                item.payload.market.as_deref().unwrap_or("match_winner") == "match_winner"
-                    && is_recent_seen_at(&item.seen_at, now)
-                    && teams_match_loose(
-                        &live.payload.team1,
-                        &live.payload.team2,
-                        &item.payload.team1,
-                        &item.payload.team2,
-                    )
-            }).copied()
-        }) {
+        let azuro = match azuro_match_winner_opt {
             Some(a) => a,
             None => {
+                tracker.skip_tally.record(SkipReason::NoOdds);
                 info!("  ⏭️ {} {}-{}: NO AZURO ODDS (tried key={}, similar: {})",
                     match_key, s1, s2, odds_lookup_key,
                     azuro_by_match.keys().filter(|k| {
@@ -5342,6 +8931,7 @@ fn find_score_edges(
 
         // Azuro implied probability for the leading team
         if !is_recent_seen_at(&azuro.seen_at, now) {
+            tracker.skip_tally.record(SkipReason::StaleOdds);
             info!("  ⏭️ {} {}-{}: azuro match_winner stale, skipping", match_key, s1, s2);
             continue;
         }
@@ -5357,10 +8947,33 @@ fn find_score_edges(
             }
         }
 
+        // Bo1: map lead neexistuje — guard jen na kola, prob a odds cap
+        if bo1_round_level {
+            let bo1_azuro_odds = resolve_azuro_side_pair(
+                &live.payload.team1,
+                &live.payload.team2,
+                leading_side,
+                &azuro.payload.team1,
+                &azuro.payload.team2,
+            ).map(|side| if side == 1 { azuro.payload.odds_team1 } else { azuro.payload.odds_team2 });
+            if !cs2_bo1_match_winner_guard(expected_prob, s1 + s2, bo1_azuro_odds) {
+                info!(
+                    "  🛑 {} {}-{}: Bo1 match_winner blocked (prob={:.1}%, rounds={}, odds={:?}) — need ≥68% + ≥{}r + odds≤{:.2}",
+                    match_key,
+                    s1,
+                    s2,
+                    expected_prob * 100.0,
+                    s1 + s2,
+                    bo1_azuro_odds,
+                    CS2_ROUND_MATCH_WINNER_MIN_ROUNDS,
+                    CS2_ROUND_MATCH_WINNER_MAX_ODDS,
+                );
+                continue;
+            }
         // Variant 3: allow CS2 round-level match_winner slightly earlier, but only
         // with map lead, or explicit 1-1 Bo3 decider on map3, plus 72%+ match
         // probability, 10+ rounds played, and odds <= 2.25.
-        if is_cs2_like(live_esports_class.family, live.payload.detailed_score.as_deref())
+        } else if is_cs2_like(live_esports_class.family, live.payload.detailed_score.as_deref())
             && leading_maps.max(losing_maps) > 3
         {
             let ds_guard = live.payload.detailed_score.as_deref().unwrap_or("");
@@ -5408,7 +9021,7 @@ fn find_score_edges(
                     live_esports_class.family,
                     live_esports_class.confidence,
                 ) {
-                    append_ledger_audit_event("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
+                    tracker.audit("ESPORTS_SCORE_DECISION_AUDIT", &serde_json::json!({
                         "match_key": match_key,
                         "path": "score_model",
                         "decision": "blocked_candidate",
@@ -5455,16 +9068,16 @@ fn find_score_edges(
         ) {
             Some(s) => s,
             None => {
+                tracker.skip_tally.record(SkipReason::AmbiguousTeams);
                 info!("  🛑 {} match_winner: TEAM IDENTITY AMBIGUOUS! live={}+{} azuro={}+{} — BLOCKING bet",
                     match_key, live.payload.team1, live.payload.team2, azuro.payload.team1, azuro.payload.team2);
                 continue;
             }
         };
         if mw_azuro_side != leading_side {
-            let mw_leading_team = if leading_side == 1 { &live.payload.team1 } else { &live.payload.team2 };
             info!("  🔀 {} MW match_winner: team order fix! live leading={} (side {}), matched azuro side {} ({})",
-                match_key, mw_leading_team, leading_side, mw_azuro_side,
-                if mw_azuro_side == 1 { &azuro.payload.team1 } else { &azuro.payload.team2 });
+                match_key, side_team(&live.payload.team1, &live.payload.team2, leading_side), leading_side, mw_azuro_side,
+                side_team(&azuro.payload.team1, &azuro.payload.team2, mw_azuro_side));
         }
 
         let azuro_implied = if mw_azuro_side == 1 {
@@ -5482,16 +9095,23 @@ fn find_score_edges(
         }
 
         if edge < MIN_SCORE_EDGE_PCT {
+            tracker.skip_tally.record(SkipReason::BelowThreshold);
             info!("  ⏭️ {} {}-{}: edge={:.1}% < min {}% (prob={:.0}% az={:.0}%)",
                 match_key, s1, s2, edge, MIN_SCORE_EDGE_PCT, expected_prob*100.0, azuro_implied*100.0);
+            if let Some(near_miss) = &tracker.near_miss {
+                near_miss.record("score_edge", match_key, sport_prefix, edge, MIN_SCORE_EDGE_PCT,
+                    &format!("match_winner below min edge at {}-{}", s1, s2));
+            }
             continue;
         }
 
-        // SANITY CHECK: If expected prob is very high (>85%) but Azuro implied is
-        // suspiciously low (<40%), the Azuro condition is likely NOT match_winner
-        // (could be totals, handicap, or eFOOTBALL misclassification).
+        // SANITY CHECK (backstop): totals/handicap are already dropped by
+        // is_moneyline_market(). If expected prob is very high (>85%) but Azuro implied is
+        // suspiciously low (<40%), the condition is probably mislabeled upstream
+        // (wrong market tag or eFOOTBALL misclassification).
         // Real match_winner odds at 4-0 football lead should be >90% implied.
         if expected_prob > 0.85 && azuro_implied < 0.40 {
+            tracker.skip_tally.record(SkipReason::MarketSanity);
             info!("🛡️ SANITY REJECT: {} {}-{}: expected {:.0}% but Azuro only {:.0}% — likely wrong market or eFOOTBALL!",
                 match_key, s1, s2, expected_prob * 100.0, azuro_implied * 100.0);
             continue;
@@ -5500,18 +9120,12 @@ fn find_score_edges(
         // Confidence based on edge size (12% = aligned with sport_min_edge)
         let confidence = if edge >= 12.0 { "HIGH" } else { "MEDIUM" };
 
-        let leading_team = if leading_side == 1 { &live.payload.team1 } else { &live.payload.team2 };
+        let leading_team = side_team(&live.payload.team1, &live.payload.team2, leading_side);
         info!("⚡ MATCH WINNER EDGE [FALLBACK]: {} leads {}-{}, Azuro implied {:.1}%, expected {:.1}%, edge {:.1}% (azuro_side={}, no map_winner odds available)",
             leading_team, s1, s2, azuro_implied * 100.0, expected_prob * 100.0, edge, mw_azuro_side);
 
         tracker.edge_cooldown.insert(match_key.to_string(), now);
 
-        let outcome_id = if mw_azuro_side == 1 {
-            azuro.payload.outcome1_id.clone()
-        } else {
-            azuro.payload.outcome2_id.clone()
-        };
-
         // Reorder azuro odds to match live team ordering
         let (sw1, sw2, so1, so2) = if mw_azuro_side == leading_side {
             (azuro.payload.odds_team1, azuro.payload.odds_team2,
@@ -5555,24 +9169,49 @@ fn find_score_edges(
             condition_id: azuro.payload.condition_id.clone(),
             outcome1_id: so1,
             outcome2_id: so2,
-            outcome_id,
             chain: azuro.payload.chain.clone(),
             azuro_url: azuro.payload.url.clone(),
-            cs2_map_confidence: None, // match_winner, not map_winner
+            // Bo1 match_winner = map winner → map tier pro dynamic odds cap
+            cs2_map_confidence: bo1_round_level.then(|| cs2_confidence_tier(expected_prob, s1 + s2)),
             cv_stake_mult,
             detailed_score: live.payload.detailed_score.clone(),
+            series_bo1: is_bo1,
+            azuro_side: mw_azuro_side,
+            outcome_draw_id: azuro.payload.outcome_draw_id.clone(),
+            outcome_order: azuro_outcome_order(
+                azuro.payload.outcome1_id.as_deref(),
+                azuro.payload.outcome2_id.as_deref(),
+                azuro.payload.outcome_draw_id.as_deref(),
+            ),
         });
     }
 
     // Cleanup old entries
     tracker.cleanup();
 
+    // live_map je HashMap → pořadí iterace není stabilní; "first fit wins" dedup potřebuje deterministické pořadí
+    sort_score_edges(&mut edges);
     edges
 }
 
+/// Sort: HIGH first, then edge_pct desc, then match_key / market_key (stable tie-break).
+/// Nejhodnotnější edge každého zápasu jde tak do dedupu/cooldownu jako první.
+fn sort_score_edges(edges: &mut [ScoreEdge]) {
+    let tier = |confidence: &str| match confidence {
+        "HIGH" => 0,
+        "MEDIUM" => 1,
+        _ => 2,
+    };
+    edges.sort_by(|a, b| {
+        tier(a.confidence).cmp(&tier(b.confidence))
+            .then_with(|| b.edge_pct.total_cmp(&a.edge_pct))
+            .then_with(|| a.match_key.cmp(&b.match_key))
+            .then_with(|| a.market_key.cmp(&b.market_key))
+    });
+}
+
 fn format_score_edge_alert(e: &ScoreEdge, alert_id: u32) -> String {
-    let leading_team = if e.leading_side == 1 { &e.team1 } else { &e.team2 };
-    let azuro_odds = if e.leading_side == 1 { e.azuro_w1 } else { e.azuro_w2 };
+    let leading = e.leading();
     let market_label = e.market_key.replace('_', " ");
 
     let conf_emoji = if e.confidence == "HIGH" { "🟢" } else { "🟡" };
@@ -5581,7 +9220,7 @@ fn format_score_edge_alert(e: &ScoreEdge, alert_id: u32) -> String {
         .map(|u| format!("\n🔗 <a href=\"{}\">Azuro link</a>", u))
         .unwrap_or_default();
 
-    let exec_ready = if e.condition_id.is_some() && e.outcome_id.is_some() {
+    let exec_ready = if e.condition_id.is_some() && leading.outcome_id.is_some() {
         "✅ BET READY"
     } else {
         "⚠️ Manuální bet"
@@ -5611,8 +9250,8 @@ fn format_score_edge_alert(e: &ScoreEdge, alert_id: u32) -> String {
         e.score2,
         e.prev_score1,
         e.prev_score2,
-        leading_team,
-        azuro_odds,
+        leading.team,
+        leading.odds,
         e.team1,
         e.azuro_w1,
         e.team2,
@@ -5646,8 +9285,11 @@ struct OddsAnomaly {
     azuro_url: Option<String>,
     market_w1: f64,
     market_w2: f64,
+    /// Three-way trh: remíza (Azuro / průměr trhu)
+    azuro_draw: Option<f64>,
+    market_draw: Option<f64>,
     market_bookmaker: String,
-    /// Which side has value on Azuro: 1 or 2
+    /// Which side has value on Azuro: 1, 2 or VALUE_SIDE_DRAW
     value_side: u8,
     /// How much higher Azuro odds are vs market (%)
     discrepancy_pct: f64,
@@ -5668,6 +9310,11 @@ struct OddsAnomaly {
     condition_id: Option<String>,
     outcome1_id: Option<String>,
     outcome2_id: Option<String>,
+    /// Remíza u three-way trhu (pro ověření pořadí outcome ID)
+    outcome_draw_id: Option<String>,
+    /// Pořadí outcome1_id/outcome2_id vůči team1/team2, ověřené na surovém Azuro payloadu
+    /// (score-edge ID přerovnává do live pořadí, takže z nich samotných ho zpětně spočítat nejde)
+    outcome_order: OutcomeOrder,
     /// Outcome ID for the VALUE side
     outcome_id: Option<String>,
     chain: Option<String>,
@@ -5775,17 +9422,498 @@ fn refresh_active_bet_from_onchain_pending(
     changed
 }
 
-fn rewrite_pending_claims_file(active_bets: &[ActiveBet], pending_claims_path: &str) {
-    if let Ok(mut f) = std::fs::OpenOptions::new()
-        .create(true).write(true).truncate(true)
-        .open(pending_claims_path) {
+// ====================================================================
+// State persistence — StateStore (flat files default, sqlite opt-in)
+// ====================================================================
+
+/// Denní P&L snapshot (legacy: data/daily_pnl.json).
+#[derive(Debug, Clone, PartialEq)]
+struct DailyPnl {
+    date: String,
+    wagered: f64,
+    returned: f64,
+    sod_bankroll: f64,
+    limit_override: Option<f64>,
+}
+
+impl DailyPnl {
+    fn new(date: &str, wagered: f64, returned: f64, sod_bankroll: f64, limit_override: Option<f64>) -> Self {
+        Self { date: date.to_string(), wagered, returned, sod_bankroll, limit_override }
+    }
+}
+
+/// Jeden dedup záznam umístěného betu (legacy: řádek data/bet_history.txt).
+/// Format: match_key|condition_id|value_team|odds|placed_at|market_key|game_id
+#[derive(Debug, Clone, PartialEq)]
+struct BetHistoryEntry {
+    match_key: String,
+    condition_id: String,
+    value_team: String,
+    odds: f64,
+    placed_at: String,
+    market_key: String,
+    game_id: String,
+}
+
+impl BetHistoryEntry {
+    fn new(match_key: &str, condition_id: &str, value_team: &str, odds: f64, market_key: &str, game_id: Option<&str>) -> Self {
+        Self {
+            match_key: match_key.to_string(),
+            condition_id: condition_id.to_string(),
+            value_team: value_team.to_string(),
+            odds,
+            placed_at: Utc::now().to_rfc3339(),
+            market_key: market_key.to_string(),
+            game_id: game_id.unwrap_or("").to_string(),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!("{}|{}|{}|{}|{}|{}|{}",
+            self.match_key, self.condition_id, self.value_team, self.odds,
+            self.placed_at, self.market_key, self.game_id)
+    }
+
+    /// Starší řádky mají jen match_key|condition_id — chybějící pole zůstanou prázdná.
+    fn parse_line(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() < 2 {
+            return None;
+        }
+        let field = |i: usize| parts.get(i).copied().unwrap_or("").to_string();
+        Some(Self {
+            match_key: field(0),
+            condition_id: field(1),
+            value_team: field(2),
+            odds: parts.get(3).and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            placed_at: field(4),
+            market_key: field(5),
+            game_id: field(6),
+        })
+    }
+}
+
+/// Perzistentní stav bota: počet betů, denní P&L, dedup historie a pending claims (= zamčená expozice).
+/// Append-only ledger (data/ledger.jsonl) sem nepatří a zůstává beze změny.
+trait StateStore: Send {
+    /// Poslední uložený (date, auto_bet_count).
+    fn load_bet_count(&self) -> Result<Option<(String, u32)>>;
+    /// Poslední uložený denní P&L (volající si hlídá datum).
+    fn load_daily_pnl(&self) -> Result<Option<DailyPnl>>;
+    fn save_daily_pnl(&self, pnl: &DailyPnl) -> Result<()>;
+    /// Umístěný bet: počet betů, P&L i dedup záznam najednou (sqlite v jedné transakci).
+    fn record_placement(&self, bet_count: u32, pnl: &DailyPnl, entry: &BetHistoryEntry) -> Result<()>;
+    fn append_bet_history(&self, entry: &BetHistoryEntry) -> Result<()>;
+    fn load_bet_history(&self) -> Result<Vec<BetHistoryEntry>>;
+    /// Pending claims v pořadí zápisu (format viz `pending_claims_line`).
+    fn load_pending_claim_lines(&self) -> Result<Vec<String>>;
+    fn append_pending_claim(&self, bet: &ActiveBet) -> Result<()>;
+    fn replace_pending_claims(&self, bets: &[ActiveBet]) -> Result<()>;
+    /// Vypnuté sporty kill switche (sport, důvod); None = stav ještě nikdy nebyl uložen.
+    fn load_disabled_sports(&self) -> Result<Option<Vec<(String, String)>>>;
+    fn save_disabled_sports(&self, disabled: &[(String, String)]) -> Result<()>;
+    /// Součet amount_usd přes pending claims.
+    fn pending_exposure_usd(&self) -> Result<f64> {
+        Ok(self.load_pending_claim_lines()?
+            .iter()
+            .filter_map(|line| line.split('|').nth(4).and_then(|v| v.parse::<f64>().ok()))
+            .sum())
+    }
+}
+
+/// Výchozí backend (BOT_STATE_BACKEND=files) — soubory v data/ (čte je i dashboard a monitor skripty).
+struct FlatFileStateStore {
+    dir: std::path::PathBuf,
+}
+
+impl FlatFileStateStore {
+    fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, name: &str) -> std::path::PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Zápis přes temp soubor + rename → pád uprostřed zápisu nenechá useknutý soubor.
+    fn write_atomic(&self, name: &str, contents: &str) -> Result<()> {
+        let path = self.path(name);
+        let tmp = self.path(&format!("{}.tmp", name));
+        std::fs::write(&tmp, contents).with_context(|| format!("write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("rename {}", path.display()))?;
+        Ok(())
+    }
+
+    fn append_line(&self, name: &str, line: &str) -> Result<()> {
         use std::io::Write;
-        for bet in active_bets {
-            let _ = writeln!(f, "{}", pending_claims_line(bet));
+        let mut f = std::fs::OpenOptions::new()
+            .create(true).append(true)
+            .open(self.path(name))?;
+        writeln!(f, "{}", line)?;
+        Ok(())
+    }
+
+    fn read_lines(&self, name: &str) -> Result<Vec<String>> {
+        match std::fs::read_to_string(self.path(name)) {
+            Ok(contents) => Ok(contents.lines().map(|l| l.to_string()).collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl StateStore for FlatFileStateStore {
+    fn load_bet_count(&self) -> Result<Option<(String, u32)>> {
+        let Some(line) = self.read_lines("bet_count_daily.txt")?.into_iter().next() else {
+            return Ok(None);
+        };
+        let parts: Vec<&str> = line.trim().split('|').collect();
+        Ok((parts.len() >= 2).then(|| (parts[0].to_string(), parts[1].parse().unwrap_or(0))))
+    }
+
+    fn load_daily_pnl(&self) -> Result<Option<DailyPnl>> {
+        let contents = match std::fs::read_to_string(self.path("daily_pnl.json")) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let v: serde_json::Value = serde_json::from_str(&contents)?;
+        Ok(v["date"].as_str().map(|date| DailyPnl {
+            date: date.to_string(),
+            wagered: v["wagered"].as_f64().unwrap_or(0.0),
+            returned: v["returned"].as_f64().unwrap_or(0.0),
+            sod_bankroll: v["sod_bankroll"].as_f64().unwrap_or(0.0),
+            limit_override: v["limit_override"].as_f64(),
+        }))
+    }
+
+    fn save_daily_pnl(&self, pnl: &DailyPnl) -> Result<()> {
+        self.write_atomic("daily_pnl.json", &serde_json::json!({
+            "date": pnl.date, "wagered": pnl.wagered, "returned": pnl.returned,
+            "sod_bankroll": pnl.sod_bankroll, "limit_override": pnl.limit_override,
+        }).to_string())
+    }
+
+    fn record_placement(&self, bet_count: u32, pnl: &DailyPnl, entry: &BetHistoryEntry) -> Result<()> {
+        self.write_atomic("bet_count_daily.txt", &format!("{}|{}", pnl.date, bet_count))?;
+        self.save_daily_pnl(pnl)?;
+        self.append_bet_history(entry)
+    }
+
+    fn append_bet_history(&self, entry: &BetHistoryEntry) -> Result<()> {
+        self.append_line("bet_history.txt", &entry.to_line())
+    }
+
+    fn load_bet_history(&self) -> Result<Vec<BetHistoryEntry>> {
+        Ok(self.read_lines("bet_history.txt")?.iter().filter_map(|l| BetHistoryEntry::parse_line(l)).collect())
+    }
+
+    fn load_pending_claim_lines(&self) -> Result<Vec<String>> {
+        self.read_lines("pending_claims.txt")
+    }
+
+    fn append_pending_claim(&self, bet: &ActiveBet) -> Result<()> {
+        self.append_line("pending_claims.txt", &pending_claims_line(bet))
+    }
+
+    fn replace_pending_claims(&self, bets: &[ActiveBet]) -> Result<()> {
+        let contents: String = bets.iter().map(|b| format!("{}\n", pending_claims_line(b))).collect();
+        self.write_atomic("pending_claims.txt", &contents)
+    }
+
+    fn load_disabled_sports(&self) -> Result<Option<Vec<(String, String)>>> {
+        if !self.path("sport_kill.txt").exists() {
+            return Ok(None);
+        }
+        Ok(Some(self.read_lines("sport_kill.txt")?
+            .iter()
+            .filter_map(|l| l.split_once('|'))
+            .map(|(sport, reason)| (sport.to_string(), reason.to_string()))
+            .collect()))
+    }
+
+    fn save_disabled_sports(&self, disabled: &[(String, String)]) -> Result<()> {
+        let contents: String = disabled.iter().map(|(sport, reason)| format!("{}|{}\n", sport, reason)).collect();
+        self.write_atomic("sport_kill.txt", &contents)
+    }
+}
+
+/// Opt-in backend — jedna sqlite DB, každá změna je transakce (WAL + synchronous=FULL).
+struct SqliteStateStore {
+    conn: Connection,
+}
+
+impl SqliteStateStore {
+    fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path.as_ref())
+            .with_context(|| format!("open state db {}", path.as_ref().display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "FULL")?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS daily_state(\
+                date TEXT PRIMARY KEY,\
+                bet_count INTEGER NOT NULL DEFAULT 0,\
+                wagered REAL NOT NULL DEFAULT 0,\
+                returned REAL NOT NULL DEFAULT 0,\
+                sod_bankroll REAL NOT NULL DEFAULT 0,\
+                limit_override REAL\
+            );\
+            CREATE TABLE IF NOT EXISTS bet_history(\
+                id INTEGER PRIMARY KEY AUTOINCREMENT,\
+                match_key TEXT NOT NULL,\
+                condition_id TEXT NOT NULL,\
+                value_team TEXT NOT NULL,\
+                odds REAL NOT NULL,\
+                placed_at TEXT NOT NULL,\
+                market_key TEXT NOT NULL,\
+                game_id TEXT NOT NULL\
+            );\
+            CREATE TABLE IF NOT EXISTS pending_claims(\
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,\
+                bet_id TEXT,\
+                token_id TEXT,\
+                amount_usd REAL,\
+                line TEXT NOT NULL\
+            );\
+            CREATE TABLE IF NOT EXISTS state_meta(\
+                key TEXT PRIMARY KEY,\
+                value TEXT NOT NULL\
+            );\
+            CREATE TABLE IF NOT EXISTS sport_kill(\
+                sport TEXT PRIMARY KEY,\
+                reason TEXT NOT NULL\
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    fn upsert_daily_pnl(conn: &Connection, pnl: &DailyPnl) -> Result<()> {
+        conn.execute(
+            "INSERT INTO daily_state(date, wagered, returned, sod_bankroll, limit_override) VALUES (?1, ?2, ?3, ?4, ?5)\
+             ON CONFLICT(date) DO UPDATE SET wagered=excluded.wagered, returned=excluded.returned,\
+             sod_bankroll=excluded.sod_bankroll, limit_override=excluded.limit_override",
+            rusqlite::params![pnl.date, pnl.wagered, pnl.returned, pnl.sod_bankroll, pnl.limit_override],
+        )?;
+        Ok(())
+    }
+
+    fn upsert_bet_count(conn: &Connection, date: &str, bet_count: u32) -> Result<()> {
+        conn.execute(
+            "INSERT INTO daily_state(date, bet_count) VALUES (?1, ?2)\
+             ON CONFLICT(date) DO UPDATE SET bet_count=excluded.bet_count",
+            rusqlite::params![date, bet_count],
+        )?;
+        Ok(())
+    }
+
+    fn insert_bet_history(conn: &Connection, e: &BetHistoryEntry) -> Result<()> {
+        conn.execute(
+            "INSERT INTO bet_history(match_key, condition_id, value_team, odds, placed_at, market_key, game_id)\
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![e.match_key, e.condition_id, e.value_team, e.odds, e.placed_at, e.market_key, e.game_id],
+        )?;
+        Ok(())
+    }
+
+    fn insert_pending_line(conn: &Connection, line: &str) -> Result<()> {
+        let parts: Vec<&str> = line.split('|').collect();
+        conn.execute(
+            "INSERT INTO pending_claims(token_id, bet_id, amount_usd, line) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                parts.first().copied(),
+                parts.get(1).copied(),
+                parts.get(4).and_then(|v| v.parse::<f64>().ok()),
+                line,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Přepíše vypnuté sporty; značka v state_meta odliší prázdný uložený stav od neuloženého.
+    fn replace_disabled_sports(conn: &Connection, disabled: &[(String, String)]) -> Result<()> {
+        conn.execute("DELETE FROM sport_kill", [])?;
+        for (sport, reason) in disabled {
+            conn.execute("INSERT INTO sport_kill(sport, reason) VALUES (?1, ?2)", rusqlite::params![sport, reason])?;
+        }
+        conn.execute(
+            "INSERT INTO state_meta(key, value) VALUES ('sport_kill_saved', ?1)\
+             ON CONFLICT(key) DO UPDATE SET value=excluded.value",
+            rusqlite::params![Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Jednorázový import legacy souborů (značka v state_meta → další start nic nedělá).
+    /// Vrací true, pokud import proběhl.
+    fn import_flat_files(&self, flat: &FlatFileStateStore) -> Result<bool> {
+        let imported: Option<String> = self.conn
+            .query_row("SELECT value FROM state_meta WHERE key='flat_files_imported'", [], |r| r.get(0))
+            .optional()?;
+        if imported.is_some() {
+            return Ok(false);
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        if let Some(pnl) = flat.load_daily_pnl()? {
+            Self::upsert_daily_pnl(&tx, &pnl)?;
+        }
+        if let Some((date, count)) = flat.load_bet_count()? {
+            Self::upsert_bet_count(&tx, &date, count)?;
+        }
+        for entry in flat.load_bet_history()? {
+            Self::insert_bet_history(&tx, &entry)?;
+        }
+        for line in flat.load_pending_claim_lines()? {
+            Self::insert_pending_line(&tx, &line)?;
+        }
+        if let Some(disabled) = flat.load_disabled_sports()? {
+            Self::replace_disabled_sports(&tx, &disabled)?;
+        }
+        tx.execute(
+            "INSERT INTO state_meta(key, value) VALUES ('flat_files_imported', ?1)",
+            rusqlite::params![Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+}
+
+impl StateStore for SqliteStateStore {
+    fn load_bet_count(&self) -> Result<Option<(String, u32)>> {
+        Ok(self.conn
+            .query_row("SELECT date, bet_count FROM daily_state ORDER BY date DESC LIMIT 1", [], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, u32>(1)?))
+            })
+            .optional()?)
+    }
+
+    fn load_daily_pnl(&self) -> Result<Option<DailyPnl>> {
+        Ok(self.conn
+            .query_row(
+                "SELECT date, wagered, returned, sod_bankroll, limit_override FROM daily_state ORDER BY date DESC LIMIT 1",
+                [],
+                |r| Ok(DailyPnl {
+                    date: r.get(0)?,
+                    wagered: r.get(1)?,
+                    returned: r.get(2)?,
+                    sod_bankroll: r.get(3)?,
+                    limit_override: r.get(4)?,
+                }),
+            )
+            .optional()?)
+    }
+
+    fn save_daily_pnl(&self, pnl: &DailyPnl) -> Result<()> {
+        Self::upsert_daily_pnl(&self.conn, pnl)
+    }
+
+    fn record_placement(&self, bet_count: u32, pnl: &DailyPnl, entry: &BetHistoryEntry) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::upsert_daily_pnl(&tx, pnl)?;
+        Self::upsert_bet_count(&tx, &pnl.date, bet_count)?;
+        Self::insert_bet_history(&tx, entry)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn append_bet_history(&self, entry: &BetHistoryEntry) -> Result<()> {
+        Self::insert_bet_history(&self.conn, entry)
+    }
+
+    fn load_bet_history(&self) -> Result<Vec<BetHistoryEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT match_key, condition_id, value_team, odds, placed_at, market_key, game_id FROM bet_history ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |r| Ok(BetHistoryEntry {
+            match_key: r.get(0)?,
+            condition_id: r.get(1)?,
+            value_team: r.get(2)?,
+            odds: r.get(3)?,
+            placed_at: r.get(4)?,
+            market_key: r.get(5)?,
+            game_id: r.get(6)?,
+        }))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn load_pending_claim_lines(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT line FROM pending_claims ORDER BY seq")?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn append_pending_claim(&self, bet: &ActiveBet) -> Result<()> {
+        Self::insert_pending_line(&self.conn, &pending_claims_line(bet))
+    }
+
+    fn replace_pending_claims(&self, bets: &[ActiveBet]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM pending_claims", [])?;
+        for bet in bets {
+            Self::insert_pending_line(&tx, &pending_claims_line(bet))?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_disabled_sports(&self) -> Result<Option<Vec<(String, String)>>> {
+        let saved: Option<String> = self.conn
+            .query_row("SELECT value FROM state_meta WHERE key='sport_kill_saved'", [], |r| r.get(0))
+            .optional()?;
+        if saved.is_none() {
+            return Ok(None);
+        }
+        let mut stmt = self.conn.prepare("SELECT sport, reason FROM sport_kill ORDER BY sport")?;
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?;
+        Ok(Some(rows.collect::<rusqlite::Result<Vec<_>>>()?))
+    }
+
+    fn save_disabled_sports(&self, disabled: &[(String, String)]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::replace_disabled_sports(&tx, disabled)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn pending_exposure_usd(&self) -> Result<f64> {
+        Ok(self.conn.query_row("SELECT COALESCE(SUM(amount_usd), 0) FROM pending_claims", [], |r| r.get(0))?)
+    }
+}
+
+/// Backend podle env BOT_STATE_BACKEND ("files" default, "sqlite" opt-in).
+/// Soubory v data/ čte dashboard (dashboard/server.js) i ps1 skripty, se sqlite je přestanou dostávat.
+/// Sqlite při prvním startu naimportuje legacy soubory; když se DB nepodaří otevřít, jede se na souborech.
+fn open_state_store(dir: &str) -> Box<dyn StateStore> {
+    let flat = FlatFileStateStore::new(dir);
+    let backend = std::env::var("BOT_STATE_BACKEND").unwrap_or_else(|_| "files".to_string());
+    if !backend.eq_ignore_ascii_case("sqlite") {
+        info!("💾 State store: flat files in {}/", dir);
+        return Box::new(flat);
+    }
+    let db_path = std::env::var("BOT_STATE_PATH").unwrap_or_else(|_| format!("{}/bot_state.sqlite", dir));
+    let _ = std::fs::create_dir_all(dir);
+    match SqliteStateStore::open(&db_path) {
+        Ok(store) => {
+            match store.import_flat_files(&flat) {
+                Ok(true) => info!("💾 State store: imported legacy flat files from {}/ into {}", dir, db_path),
+                Ok(false) => {}
+                Err(e) => warn!("⚠️ State store: flat file import failed (continuing with empty tables): {}", e),
+            }
+            info!("💾 State store: sqlite {}", db_path);
+            Box::new(store)
+        }
+        Err(e) => {
+            warn!("⚠️ State store: sqlite {} unavailable ({}), falling back to flat files", db_path, e);
+            Box::new(flat)
         }
     }
 }
 
+fn rewrite_pending_claims_file(active_bets: &[ActiveBet], state_store: &dyn StateStore) {
+    if let Err(e) = state_store.replace_pending_claims(active_bets) {
+        warn!("⚠️ pending claims rewrite failed: {}", e);
+    }
+}
+
 fn pending_claims_line(bet: &ActiveBet) -> String {
     let tid = bet.token_id.as_deref().unwrap_or("?");
     format!(
@@ -5861,13 +9989,146 @@ fn persist_dashboard_runtime_config(
     }
 }
 
-fn append_pending_claim_entry(bet: &ActiveBet, pending_claims_path: &str) {
-    if let Ok(mut f) = std::fs::OpenOptions::new()
-        .create(true).append(true)
-        .open(pending_claims_path) {
-        use std::io::Write;
-        let _ = writeln!(f, "{}", pending_claims_line(bet));
+fn append_pending_claim_entry(bet: &ActiveBet, state_store: &dyn StateStore) {
+    if let Err(e) = state_store.append_pending_claim(bet) {
+        warn!("⚠️ pending claim append failed for bet {}: {}", bet.bet_id, e);
+    }
+}
+
+/// Načte pending claims ze state store jako ActiveBety pro auto-claim monitoring.
+/// Dedup podle bet_id; řádky bez bet_id (starší zápisy "?") podle (match_key, condition_id, amount_usd),
+/// jen když je condition_id známé. Řádky bez bet_id i tokenId se zahodí.
+/// Pokud se cokoli vyčistilo / doplnilo, pending claims se přepíšou.
+fn load_pending_claims(
+    state_store: &dyn StateStore,
+    ledger_meta_by_bet_id: &HashMap<String, LedgerBetMetadata>,
+    ledger_meta_by_token_id: &HashMap<String, LedgerBetMetadata>,
+) -> Vec<ActiveBet> {
+    let mut active_bets: Vec<ActiveBet> = Vec::new();
+    let lines = match state_store.load_pending_claim_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            warn!("⚠️ pending claims load failed: {}", e);
+            return active_bets;
+        }
+    };
+    let mut seen_bet_ids: HashSet<String> = HashSet::new();
+    // Starší zápisy mají betId "?" → druhý klíč (match_key, condition_id, amount)
+    let mut seen_bet_tuples: HashSet<(String, String, String)> = HashSet::new();
+    let mut rewrite_pending_claims_on_startup = false;
+    for line in &lines {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() >= 6 {
+            let token_id_raw = parts[0].to_string();
+            let bet_id = parts[1].to_string();
+            let has_bet_id = !is_missing_display_field(&bet_id);
+            // BUG #12 FIX: Skip duplicate bet_ids
+            if has_bet_id && !seen_bet_ids.insert(bet_id.clone()) {
+                info!("⏭️ Skipping duplicate pending claim: betId={}", bet_id);
+                rewrite_pending_claims_on_startup = true;
+                continue;
+            }
+            let match_key = parts[2].to_string();
+            let value_team = parts[3].to_string();
+            let amount_usd: f64 = parts[4].parse().unwrap_or(2.0);
+            let odds: f64 = parts[5].parse().unwrap_or(1.5);
+            // Extended fields (v2 format): team1|team2|market_key
+            let loaded_team1 = parts.get(6).unwrap_or(&"").to_string();
+            let loaded_team2 = parts.get(7).unwrap_or(&"").to_string();
+            let loaded_market = parts.get(8).unwrap_or(&"unknown").to_string();
+            let loaded_original_sport = parts.get(9).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
+            let loaded_resolved_sport = parts.get(10).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
+            let loaded_esports_family = parts.get(11).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
+            // Fallback: derive teams from match_key if not stored
+            let (fb_t1, fb_t2) = match_key_team_parts(&match_key)
+                .unwrap_or_else(|| (value_team.clone(), "?".to_string()));
+            let team1 = if !is_missing_display_field(&loaded_team1) { loaded_team1 } else { fb_t1 };
+            let team2 = if !is_missing_display_field(&loaded_team2) { loaded_team2 } else { fb_t2 };
+            let market_key = if !is_missing_display_field(&loaded_market) {
+                loaded_market
+            } else {
+                market_from_match_key(&match_key)
+            };
+            if is_missing_display_field(parts.get(6).copied().unwrap_or(""))
+                || is_missing_display_field(parts.get(7).copied().unwrap_or(""))
+                || is_missing_display_field(parts.get(8).copied().unwrap_or(""))
+                || parts.get(9).is_none()
+                || parts.get(10).is_none()
+                || parts.get(11).is_none()
+            {
+                rewrite_pending_claims_on_startup = true;
+            }
+            // "?" means tokenId not yet discovered — set to None so PATH B will discover it
+            // Also treat tokenId < 1000 as bogus (false positive from recursive extraction)
+            let token_id = if token_id_raw == "?" || token_id_raw.is_empty() {
+                None
+            } else if let Ok(tid_num) = token_id_raw.parse::<u64>() {
+                if tid_num < 1000 {
+                    info!("⚠️ Bogus tokenId {} for bet {} — treating as undiscovered", token_id_raw, bet_id);
+                    None
+                } else {
+                    Some(token_id_raw)
+                }
+            } else {
+                Some(token_id_raw)
+            };
+            if !has_bet_id && token_id.is_none() {
+                info!("⏭️ Skipping pending claim without betId/tokenId: {}", match_key);
+                rewrite_pending_claims_on_startup = true;
+                continue;
+            }
+            let mut restored_bet = ActiveBet {
+                alert_id: 0,
+                bet_id: bet_id.clone(),
+                match_key: match_key.clone(),
+                market_key,
+                original_sport: loaded_original_sport,
+                resolved_sport: loaded_resolved_sport.or_else(|| Some(match_key.split("::").next().unwrap_or("").to_string()).filter(|v| !v.is_empty())),
+                esports_family: loaded_esports_family,
+                team1,
+                team2,
+                value_team: value_team.clone(),
+                amount_usd,
+                odds,
+                placed_at: "loaded".to_string(),
+                condition_id: String::new(),
+                outcome_id: String::new(),
+                graph_bet_id: None,
+                token_id,
+                path: "loaded".to_string(),
+            };
+            if enrich_active_bet_from_ledger_metadata(
+                &mut restored_bet,
+                ledger_meta_by_bet_id,
+                ledger_meta_by_token_id,
+            ) {
+                rewrite_pending_claims_on_startup = true;
+            }
+            // Různé bet_id = různé sázky i se stejným zápasem a částkou; prázdné condition_id nic neidentifikuje
+            let tuple = (!restored_bet.condition_id.is_empty()).then(|| (
+                restored_bet.match_key.clone(),
+                restored_bet.condition_id.clone(),
+                format!("{:.2}", restored_bet.amount_usd),
+            ));
+            let duplicate_tuple = match tuple {
+                Some(tuple) => !seen_bet_tuples.insert(tuple) && !has_bet_id,
+                None => false,
+            };
+            if duplicate_tuple {
+                info!("⏭️ Skipping duplicate pending claim: {} ${:.2} (betId={})",
+                    restored_bet.match_key, restored_bet.amount_usd, restored_bet.bet_id);
+                rewrite_pending_claims_on_startup = true;
+                continue;
+            }
+            active_bets.push(restored_bet);
+        }
     }
+    if rewrite_pending_claims_on_startup {
+        rewrite_pending_claims_file(&active_bets, state_store);
+        info!("🧹 Rewrote pending claims on startup after dedup / normalizing stale team/market fields");
+    }
+    info!("📋 Loaded {} pending claims from state store", active_bets.len());
+    active_bets
 }
 
 /// Extract display-friendly team names: prefer real names, fallback to match_key parts
@@ -5921,7 +10182,7 @@ fn display_sport_verbose(bet: &ActiveBet) -> String {
 
 /// Format rich recent bets overview for /prehled command
 fn format_recent_bets_detail(limit: usize) -> String {
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     let Ok(contents) = std::fs::read_to_string(ledger_path) else {
         return "📭 Žádná data v ledgeru.".to_string();
     };
@@ -6021,7 +10282,7 @@ struct PlacedMeta {
 
 /// Look up original placement metadata from ledger.jsonl by bet_id
 fn lookup_placed_metadata(bet_id: &str) -> Option<PlacedMeta> {
-    let contents = std::fs::read_to_string("data/ledger.jsonl").ok()?;
+    let contents = std::fs::read_to_string(LEDGER_PATH).ok()?;
     for line in contents.lines().rev() {
         let entry: serde_json::Value = match serde_json::from_str(line) {
             Ok(v) => v,
@@ -6168,6 +10429,124 @@ fn format_status_bet_line(bet: &ActiveBet) -> String {
     )
 }
 
+/// Stav hlavní smyčky, ze kterého se staví `BotStatus` (jen výpůjčky, nic nemění).
+struct StatusSources<'a> {
+    bankroll: f64,
+    sod_bankroll: f64,
+    daily_wagered: f64,
+    daily_returned: f64,
+    daily_limit_override: Option<f64>,
+    auto_bet_count: u32,
+    active_bets: &'a [ActiveBet],
+    inflight_wagered_total: f64,
+    safe_mode: bool,
+    sport_kill: &'a SportKillSwitch,
+    sport_exposure: &'a HashMap<String, f64>,
+    skip_tally: &'a SkipTally,
+}
+
+impl StatusSources<'_> {
+    fn snapshot(&self) -> BotStatus {
+        let (_, _, _, dl_frac, _) = get_exposure_caps(self.sod_bankroll);
+        let mut disabled_sports: Vec<String> = self.sport_kill.disabled.keys().cloned().collect();
+        disabled_sports.sort();
+        BotStatus {
+            ts: Utc::now().to_rfc3339(),
+            bankroll_usd: self.bankroll,
+            sod_bankroll_usd: self.sod_bankroll,
+            daily_wagered_usd: self.daily_wagered,
+            daily_returned_usd: self.daily_returned,
+            daily_net_usd: self.daily_returned - self.daily_wagered,
+            daily_loss_limit_usd: self.daily_limit_override
+                .unwrap_or_else(|| DAILY_LOSS_LIMIT_USD.min(self.sod_bankroll * dl_frac)),
+            daily_limit_override: self.daily_limit_override.is_some(),
+            auto_bet_count: self.auto_bet_count,
+            active_bets: self.active_bets.len(),
+            inflight_total_usd: self.inflight_wagered_total,
+            safe_mode: self.safe_mode,
+            disabled_sports,
+            sport_exposure_usd: self.sport_exposure.iter()
+                .filter(|(_, v)| **v > 0.0)
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            skip_reasons: self.skip_tally.by_label(),
+        }
+    }
+}
+
+/// Read-only snapshot stavu bota — jeden zdroj pro `/status` i `data/bot_status.json` (dashboard, monitor skripty).
+/// alert_bot nemá vlastní HTTP server, takže metrics endpoint zastupuje `bot_status.json` přepisovaný každý poll.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BotStatus {
+    ts: String,
+    bankroll_usd: f64,
+    sod_bankroll_usd: f64,
+    daily_wagered_usd: f64,
+    daily_returned_usd: f64,
+    daily_net_usd: f64,
+    daily_loss_limit_usd: f64,
+    /// Limit je ručně přepsaný přes /limit
+    daily_limit_override: bool,
+    auto_bet_count: u32,
+    active_bets: usize,
+    inflight_total_usd: f64,
+    safe_mode: bool,
+    disabled_sports: Vec<String>,
+    /// sport → dnes vsazeno (BTreeMap kvůli stabilnímu pořadí v JSON i Telegramu)
+    sport_exposure_usd: std::collections::BTreeMap<String, f64>,
+    /// Důvod skipu score edge → počet od startu (kde funnel ztrácí příležitosti)
+    skip_reasons: std::collections::BTreeMap<String, u64>,
+}
+
+impl BotStatus {
+    /// P/L + limity + rizikové přepínače pro `/status`.
+    fn summary_text(&self) -> String {
+        let (pnl_sign, pnl_emoji) = if self.daily_net_usd >= 0.0 { ("+", "📈") } else { ("", "📉") };
+        let lim_tag = if self.daily_limit_override { " ⚡" } else { "" };
+        let mut text = format!(
+            "\n{} <b>P/L: {}{:.2} USDT</b>\n   Vsazeno ${:.2} | Vráceno ${:.2}\n   Loss: ${:.2} / ${:.2}{}\n   Auto-bets: {}\n",
+            pnl_emoji, pnl_sign, self.daily_net_usd,
+            self.daily_wagered_usd, self.daily_returned_usd,
+            (-self.daily_net_usd).max(0.0), self.daily_loss_limit_usd, lim_tag,
+            self.auto_bet_count,
+        );
+        text.push_str(&format!(
+            "   Bankroll ${:.2} (SOD ${:.2}) | Aktivní {} | In-flight ${:.2}\n",
+            self.bankroll_usd, self.sod_bankroll_usd, self.active_bets, self.inflight_total_usd,
+        ));
+        if !self.sport_exposure_usd.is_empty() {
+            let exposure = self.sport_exposure_usd.iter()
+                .map(|(sport, usd)| format!("{} ${:.2}", sport, usd))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            text.push_str(&format!("   Expozice: {}\n", exposure));
+        }
+        if !self.skip_reasons.is_empty() {
+            let skips = self.skip_reasons.iter()
+                .map(|(reason, n)| format!("{} {}", reason, n))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            text.push_str(&format!("   Skipy: {}\n", skips));
+        }
+        if self.safe_mode {
+            text.push_str("🛡️ SAFE MODE aktivní — auto-bety pozastaveny\n");
+        }
+        if !self.disabled_sports.is_empty() {
+            text.push_str(&format!("🛑 Vypnuté sporty: {}\n", self.disabled_sports.join(", ")));
+        }
+        text
+    }
+
+    /// Atomický zápis (temp + rename) — čtenář nikdy nevidí useknutý JSON.
+    fn write_to(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("rename {}", path.display()))?;
+        Ok(())
+    }
+}
+
 /// Format enriched detail block for WON/LOST notifications
 fn format_placed_detail(meta: &PlacedMeta) -> String {
     let mut parts = Vec::new();
@@ -6222,7 +10601,7 @@ fn record_live_loss_for_streak(
 fn reconcile_active_bets_with_executor_snapshot(
     active_bets: &mut Vec<ActiveBet>,
     bets_arr: &[serde_json::Value],
-    pending_claims_path: &str,
+    state_store: &dyn StateStore,
     session_start: DateTime<Utc>,
     inflight_ttl_secs: i64,
 ) -> f64 {
@@ -6458,30 +10837,60 @@ fn reconcile_active_bets_with_executor_snapshot(
     }
 
     if needs_rewrite {
-        rewrite_pending_claims_file(active_bets, pending_claims_path);
-        info!("💾 RECONCILE: pending_claims.txt rewritten ({} entries)", active_bets.len());
+        rewrite_pending_claims_file(active_bets, state_store);
+        info!("💾 RECONCILE: pending claims rewritten ({} entries)", active_bets.len());
     }
 
     locked_exposure_total(active_bets, session_start)
 }
 
-#[derive(Debug, Deserialize)]
-struct ExecutorBetResponse {
-    status: Option<String>,
-    #[serde(rename = "betId")]
-    bet_id: Option<String>,
-    #[serde(rename = "tokenId")]
-    token_id: Option<String>,
-    #[serde(rename = "graphBetId")]
-    graph_bet_id: Option<String>,
-    #[serde(rename = "acceptedOdds")]
-    accepted_odds: Option<f64>,
-    #[serde(rename = "requestedOdds")]
-    requested_odds: Option<f64>,
-    #[serde(rename = "minOdds")]
-    min_odds: Option<f64>,
-    state: Option<String>,
-    error: Option<String>,
+/// POST {executor}/bet; timeout se zopakuje se stejným tělem (a tedy stejným idempotency key).
+/// Pokud první request ve skutečnosti prošel, executor vrátí původní výsledek s `idempotentReplay`.
+/// `context` (matchKey, týmy, sport, requestedOdds…) jde do těla vedle polí `OrderRequest`.
+async fn send_executor_bet(
+    client: &reqwest::Client,
+    executor_url: &str,
+    order: &OrderRequest,
+    context: serde_json::Value,
+    timeout: Duration,
+) -> reqwest::Result<reqwest::Response> {
+    let bet_body = order.to_azuro_executor_body(context);
+    let mut transport_retries = 0;
+    loop {
+        match client.post(format!("{}/bet", executor_url))
+            .timeout(timeout)
+            .json(&bet_body)
+            .send()
+            .await
+        {
+            Err(e) if e.is_timeout() && transport_retries < BET_TRANSPORT_RETRY_MAX => {
+                transport_retries += 1;
+                warn!("⏳ POST /bet timeout — retry {}/{} se stejným idempotency key {}",
+                    transport_retries, BET_TRANSPORT_RETRY_MAX, order.idempotency_key);
+            }
+            other => return other,
+        }
+    }
+}
+
+/// Parsuje odpověď `POST /bet` — společné pro všechny cesty sázek (edge, anomaly, arb, hedge, /bet).
+/// Idempotentní replay se loguje tady: executor sázku umístil už dřív, znovu se nepokládá.
+/// Zda ho volající ještě zaúčtuje, rozhoduje `replay_already_booked`.
+async fn read_executor_receipt(resp: reqwest::Response, order: &OrderRequest) -> reqwest::Result<OrderReceipt> {
+    let receipt = resp.json::<OrderReceipt>().await?;
+    if receipt.idempotent_replay {
+        info!("♻️ BET idempotent replay — executor už sázku umístil (bet_id={}, key={}), nepokládám znovu",
+            receipt.bet_id.as_deref().unwrap_or("?"), order.idempotency_key);
+    }
+    Ok(receipt)
+}
+
+/// Replay sázky, která už je v `active_bets` (stejné rozhodnutí ve stejném minutovém bucketu) →
+/// znovu ji neúčtovat (ActiveBet, daily_wagered, pending claim). Replay po timeoutu POSTu, jehož
+/// odpověď nikdy nedorazila, v `active_bets` není a zaúčtuje se normálně.
+fn replay_already_booked(receipt: &OrderReceipt, active_bets: &[ActiveBet]) -> bool {
+    receipt.idempotent_replay
+        && receipt.bet_id.as_deref().is_some_and(|bet_id| active_bets.iter().any(|b| b.bet_id == bet_id))
 }
 
 fn json_f64_field(value: &serde_json::Value, key: &str) -> Option<f64> {
@@ -6670,6 +11079,20 @@ fn normalized_market_key(market: Option<&str>) -> String {
         .unwrap_or_else(|| "match_winner".to_string())
 }
 
+/// Allowlist trhů pro edge logiku: jen moneyline (`match_winner`, `mapN_winner`).
+/// Totals/handicap mají jiné kurzy a nesmí se porovnávat se score-based pravděpodobností.
+fn is_moneyline_market(market: Option<&str>) -> bool {
+    let market = normalized_market_key(market);
+    if market == "match_winner" {
+        return true;
+    }
+    market
+        .strip_prefix("map")
+        .and_then(|rest| rest.strip_suffix("_winner"))
+        .map(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
 fn match_prefix_from_match_key(match_key: &str) -> String {
     match_key.split("::").next().unwrap_or("unknown").to_string()
 }
@@ -6747,6 +11170,48 @@ struct ClaimDetail {
     path: String,
 }
 
+/// Očekávaný payout jednoho claimnutého tokenu: známý payout, jinak Won = amount × odds, Canceled = refund.
+fn claim_detail_expected_payout(detail: &ClaimDetail) -> f64 {
+    if detail.payout_usd > 0.0 {
+        detail.payout_usd
+    } else if detail.result == "Won" {
+        detail.amount_usd * detail.odds
+    } else {
+        detail.amount_usd
+    }
+}
+
+/// Spáruje tokenIds z /claim odpovědi s tokeny aktivních sázek poslanými v batchi.
+/// Vrací (payout k připsání, spárované detaily, neočekávané tokeny). Pokud executor přibalil
+/// cizí token, `totalPayoutUsd` nejde rozdělit — připíše se jen součet očekávaných payoutů
+/// spárovaných sázek (nejvýš total).
+fn reconcile_claimed_tokens(
+    claimed_tokens: &[String],
+    details: &[ClaimDetail],
+    total_payout: f64,
+) -> (f64, Vec<ClaimDetail>, Vec<String>) {
+    let claimed_set: HashSet<&str> = claimed_tokens.iter().map(|tid| tid.as_str()).collect();
+    let matched: Vec<ClaimDetail> = details.iter()
+        .filter(|detail| claimed_set.contains(detail.token_id.as_str()))
+        .cloned()
+        .collect();
+    let known: HashSet<&str> = details.iter().map(|detail| detail.token_id.as_str()).collect();
+    let mut unexpected: Vec<String> = Vec::new();
+    for tid in claimed_tokens {
+        if !known.contains(tid.as_str()) && !unexpected.contains(tid) {
+            unexpected.push(tid.clone());
+        }
+    }
+
+    let credited = if unexpected.is_empty() {
+        total_payout
+    } else {
+        let expected: f64 = matched.iter().map(claim_detail_expected_payout).sum();
+        expected.min(total_payout.max(0.0))
+    };
+    (credited, matched, unexpected)
+}
+
 fn prefer_non_missing_field(current: &str, candidate: &str) -> String {
     if !is_missing_display_field(candidate) {
         candidate.to_string()
@@ -6936,7 +11401,7 @@ impl RuntimeAuditSummary {
 }
 
 fn recent_portfolio_event_lines(limit: usize, lookback_hours: i64) -> Vec<String> {
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     if !Path::new(ledger_path).exists() {
         return Vec::new();
     }
@@ -7029,7 +11494,7 @@ fn recent_portfolio_event_lines(limit: usize, lookback_hours: i64) -> Vec<String
 fn load_ledger_bet_metadata() -> (HashMap<String, LedgerBetMetadata>, HashMap<String, LedgerBetMetadata>) {
     let mut by_bet_id = HashMap::new();
     let mut by_token_id = HashMap::new();
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     if !Path::new(ledger_path).exists() {
         return (by_bet_id, by_token_id);
     }
@@ -7317,7 +11782,7 @@ fn build_pending_telegram_entry(
 
 fn summarize_recent_runtime(hours: i64) -> RuntimeAuditSummary {
     let mut summary = RuntimeAuditSummary::default();
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     if !Path::new(ledger_path).exists() {
         return summary;
     }
@@ -7366,7 +11831,7 @@ fn summarize_recent_runtime(hours: i64) -> RuntimeAuditSummary {
 
 fn summarize_recent_odds_drift(hours: i64) -> OddsDriftAuditSummary {
     let mut summary = OddsDriftAuditSummary::default();
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     if !Path::new(ledger_path).exists() {
         return summary;
     }
@@ -7415,12 +11880,164 @@ fn summarize_recent_odds_drift(hours: i64) -> OddsDriftAuditSummary {
     summary
 }
 
+/// Realizovaný výkon jedné skupiny vyřízených sázek (WON / LOST) z ledgeru.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct PnlBucket {
+    won: usize,
+    lost: usize,
+    staked_usd: f64,
+    pnl_usd: f64,
+    /// Součet edge_pct z PLACED záznamu (jen sázky, kde edge známe)
+    won_edge_sum: f64,
+    won_edge_n: usize,
+    lost_edge_sum: f64,
+    lost_edge_n: usize,
+}
+
+impl PnlBucket {
+    fn settled(&self) -> usize {
+        self.won + self.lost
+    }
+
+    fn win_rate_pct(&self) -> f64 {
+        if self.settled() == 0 { 0.0 } else { self.won as f64 / self.settled() as f64 * 100.0 }
+    }
+
+    fn roi_pct(&self) -> f64 {
+        if self.staked_usd <= 0.0 { 0.0 } else { self.pnl_usd / self.staked_usd * 100.0 }
+    }
+
+    fn avg_edge(sum: f64, n: usize) -> Option<f64> {
+        (n > 0).then(|| sum / n as f64)
+    }
+
+    fn add(&mut self, won: bool, stake: f64, pnl: f64, edge_pct: Option<f64>) {
+        self.staked_usd += stake;
+        self.pnl_usd += pnl;
+        if won {
+            self.won += 1;
+            if let Some(edge) = edge_pct {
+                self.won_edge_sum += edge;
+                self.won_edge_n += 1;
+            }
+        } else {
+            self.lost += 1;
+            if let Some(edge) = edge_pct {
+                self.lost_edge_sum += edge;
+                self.lost_edge_n += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct PnlSummary {
+    total: PnlBucket,
+    per_sport: std::collections::BTreeMap<String, PnlBucket>,
+}
+
+/// Spáruje PLACED → WON/LOST podle bet_id a sečte realizované P&L.
+/// `since` filtruje podle času settlementu; CANCELED (vrácený vklad) se do výkonu nepočítá.
+fn summarize_ledger_pnl(contents: &str, since: Option<DateTime<Utc>>) -> PnlSummary {
+    let mut placed: HashMap<String, serde_json::Value> = HashMap::new();
+    let mut summary = PnlSummary::default();
+    let mut settled_ids: HashSet<String> = HashSet::new();
+
+    for line in contents.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let event = entry.get("event").and_then(|v| v.as_str()).unwrap_or("");
+        let bet_id = entry.get("bet_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        if event == "PLACED" {
+            if !bet_id.is_empty() {
+                placed.insert(bet_id, entry);
+            }
+            continue;
+        }
+        if event != "WON" && event != "LOST" {
+            continue;
+        }
+        if !bet_id.is_empty() && !settled_ids.insert(bet_id.clone()) {
+            continue; // duplicitní settlement po restartu
+        }
+        let in_window = match (since, entry.get("ts").and_then(|v| v.as_str())) {
+            (None, _) => true,
+            (Some(cutoff), Some(ts)) => DateTime::parse_from_rfc3339(ts)
+                .is_ok_and(|t| t.with_timezone(&Utc) >= cutoff),
+            (Some(_), None) => false,
+        };
+        if !in_window {
+            continue;
+        }
+
+        let placement = placed.get(&bet_id);
+        let field = |key: &str| entry.get(key).and_then(|v| v.as_f64())
+            .or_else(|| placement.and_then(|p| p.get(key)).and_then(|v| v.as_f64()));
+        let stake = field("amount_usd").or_else(|| field("stake_usd")).unwrap_or(0.0);
+        let won = event == "WON";
+        let pnl = if won {
+            let payout = entry.get("payout_usd").and_then(|v| v.as_f64())
+                .unwrap_or_else(|| stake * field("odds").unwrap_or(0.0));
+            payout - stake
+        } else {
+            -stake
+        };
+        let edge_pct = placement.and_then(|p| {
+            p.get("edge_pct").and_then(|v| v.as_f64())
+                .or_else(|| p.get("anomaly_disc_pct").and_then(|v| v.as_f64()))
+        });
+        let match_key = entry.get("match_key").and_then(|v| v.as_str())
+            .or_else(|| placement.and_then(|p| p.get("match_key")).and_then(|v| v.as_str()))
+            .unwrap_or("");
+        let sport = match match_key.split_once("::") {
+            Some((sport, _)) if !sport.is_empty() => sport.to_string(),
+            _ => "?".to_string(),
+        };
+
+        summary.total.add(won, stake, pnl, edge_pct);
+        summary.per_sport.entry(sport).or_default().add(won, stake, pnl, edge_pct);
+    }
+    summary
+}
+
+/// Telegram text pro /pnl.
+fn format_pnl_summary(summary: &PnlSummary, days: Option<i64>) -> String {
+    let period = match days {
+        Some(d) => format!("posledních {} dní", d),
+        None => "celá historie".to_string(),
+    };
+    if summary.total.settled() == 0 {
+        return format!("📭 Žádné vyřízené sázky ({}).", period);
+    }
+    let edge = |v: Option<f64>| v.map(|e| format!("{:.1}%", e)).unwrap_or_else(|| "?".to_string());
+    let t = &summary.total;
+    let mut msg = format!(
+        "💰 <b>Realizované P&amp;L</b> ({})\n\n\
+         Sázek: {} (✅ {} / ❌ {}) | WR {:.0}%\n\
+         Vsazeno: ${:.2} | P&amp;L: <b>{:+.2}$</b> | ROI {:+.1}%\n\
+         Ø edge výher: {} | Ø edge proher: {}\n\n\
+         <b>Per sport:</b>\n",
+        period, t.settled(), t.won, t.lost, t.win_rate_pct(),
+        t.staked_usd, t.pnl_usd, t.roi_pct(),
+        edge(PnlBucket::avg_edge(t.won_edge_sum, t.won_edge_n)),
+        edge(PnlBucket::avg_edge(t.lost_edge_sum, t.lost_edge_n)),
+    );
+    for (sport, b) in &summary.per_sport {
+        msg.push_str(&format!(
+            "• {} — {}/{} WR {:.0}% | {:+.2}$ | ROI {:+.1}%\n",
+            sport.to_uppercase(), b.won, b.settled(), b.win_rate_pct(), b.pnl_usd, b.roi_pct(),
+        ));
+    }
+    msg
+}
+
 fn recover_unresolved_accepts_from_ledger(
     active_bets: &mut Vec<ActiveBet>,
     ledger_settled_ids: &HashSet<String>,
 ) -> LedgerRecoveryStats {
     let mut stats = LedgerRecoveryStats::default();
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     if !Path::new(ledger_path).exists() {
         return stats;
     }
@@ -7581,6 +12198,7 @@ fn remap_execution_ids_from_state(
     team1: &str,
     team2: &str,
     value_side: u8,
+    venues: &ExecutionVenues,
 ) -> Option<(String, String)> {
     let desired_market = market_from_match_key(match_key);
 
@@ -7588,7 +12206,7 @@ fn remap_execution_ids_from_state(
         if item.match_key != match_key {
             continue;
         }
-        if !item.payload.bookmaker.starts_with("azuro_") {
+        if !venues.is_venue(&item.payload.bookmaker) {
             continue;
         }
         let item_market = normalized_market_key(item.payload.market.as_deref());
@@ -7616,6 +12234,14 @@ fn remap_execution_ids_from_state(
             continue;
         }
 
+        // Remíza nezávisí na pořadí týmů
+        if value_side == VALUE_SIDE_DRAW {
+            match item.payload.outcome_draw_id.as_ref() {
+                Some(o) if !o.is_empty() => return Some((cond.clone(), o.clone())),
+                _ => continue,
+            }
+        }
+
         let mapped_side = if direct {
             value_side
         } else if value_side == 1 {
@@ -7631,7 +12257,195 @@ fn remap_execution_ids_from_state(
     None
 }
 
-fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
+// ====================================================================
+// INTRA-MATCH ARB — mapN_winner vs match_winner na stejném zápase
+// ====================================================================
+
+/// Rezerva na gas + slippage mezi dvěma nohama (v % z vrácené částky)
+const INTRA_ARB_FEE_PCT: f64 = 1.0;
+/// Celkový stake dutche (rozdělený mezi obě nohy)
+const INTRA_ARB_STAKE_USD: f64 = 2.0;
+/// Strop celkového stake, když se dutch musí navýšit, aby menší noha splnila MIN_EXECUTABLE_STAKE_USD
+const INTRA_ARB_MAX_STAKE_USD: f64 = 5.0;
+
+/// Jedna noha dutche. `side` je v orientaci match_winner itemu (1 = team1).
+#[derive(Debug, Clone)]
+struct IntraArbLeg {
+    market: String,
+    side: u8,
+    team: String,
+    odds: f64,
+    /// Podíl celkového stake (obě nohy dohromady = 1.0)
+    stake_frac: f64,
+    condition_id: Option<String>,
+    outcome_id: Option<String>,
+    /// Druhý outcome stejné condition — uzavření nohy, když druhá noha selže
+    opposite_outcome_id: Option<String>,
+    opposite_odds: f64,
+}
+
+impl IntraArbLeg {
+    /// Protisázka na opačný outcome stejného trhu (uzavře otevřenou nohu).
+    fn close_out(&self, arb: &IntraArb) -> Option<IntraArbLeg> {
+        let side = 3 - self.side;
+        Some(IntraArbLeg {
+            market: self.market.clone(),
+            side,
+            team: if side == 1 { arb.team1.clone() } else { arb.team2.clone() },
+            odds: self.opposite_odds,
+            stake_frac: 0.0,
+            condition_id: self.condition_id.clone(),
+            outcome_id: Some(self.opposite_outcome_id.clone()?).filter(|o| !o.is_empty()),
+            opposite_outcome_id: self.outcome_id.clone(),
+            opposite_odds: self.odds,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IntraArb {
+    match_key: String,
+    team1: String,
+    team2: String,
+    map_index: u8,
+    map_leg: IntraArbLeg,
+    match_leg: IntraArbLeg,
+    /// 1/odds_map + 1/odds_match (< 1.0 = arb před fees)
+    implied_sum: f64,
+    /// Garantovaný zisk po INTRA_ARB_FEE_PCT, v % ze stake
+    profit_pct: f64,
+}
+
+/// "map3_winner" → Some(3)
+fn map_market_index(market: &str) -> Option<u8> {
+    market.strip_prefix("map")?
+        .strip_suffix("_winner")?
+        .parse::<u8>()
+        .ok()
+        .filter(|n| *n > 0)
+}
+
+/// Dutch: map vítěz X + match vítěz Y (Y != X). Kryje všechny výsledky JEN když je mapa
+/// rozhodující (Bo1 map1, Bo3 map3 za 1:1, ...) — jinak "X vyhraje mapu, Y zápas" prohraje obě nohy.
+/// Funkce řeší jen ceny, rozhodující mapu gatuje volající přes `intra_arb_map_is_decisive`.
+fn find_intra_match_arb(match_items: &[StateOddsItem], venues: &ExecutionVenues) -> Option<IntraArb> {
+    let priced = |item: &&StateOddsItem| {
+        venues.is_venue(&item.payload.bookmaker)
+            && [item.payload.odds_team1, item.payload.odds_team2]
+                .iter()
+                .all(|o| *o > SUSPENDED_MARKET_MIN_ODDS && *o < SUSPENDED_MARKET_MAX_ODDS)
+    };
+    let match_item = match_items.iter()
+        .filter(priced)
+        .find(|item| normalized_market_key(item.payload.market.as_deref()) == "match_winner")?;
+
+    let mut best: Option<IntraArb> = None;
+    for map_item in match_items.iter().filter(priced) {
+        let market = normalized_market_key(map_item.payload.market.as_deref());
+        let Some(map_index) = map_market_index(&market) else {
+            continue;
+        };
+        let m = &match_item.payload;
+        let p = &map_item.payload;
+        let direct = teams_match(&p.team1, &m.team1) && teams_match(&p.team2, &m.team2);
+        let swapped = teams_match(&p.team1, &m.team2) && teams_match(&p.team2, &m.team1);
+        if !direct && !swapped {
+            continue;
+        }
+
+        for map_side in [1u8, 2] {
+            // map_side v orientaci map itemu → side v orientaci match itemu
+            let side = if direct { map_side } else { 3 - map_side };
+            let match_side = 3 - side;
+            let ((map_odds, map_outcome), map_opposite) = if map_side == 1 {
+                ((p.odds_team1, p.outcome1_id.clone()), (p.odds_team2, p.outcome2_id.clone()))
+            } else {
+                ((p.odds_team2, p.outcome2_id.clone()), (p.odds_team1, p.outcome1_id.clone()))
+            };
+            let ((match_odds, match_outcome), match_opposite) = if match_side == 1 {
+                ((m.odds_team1, m.outcome1_id.clone()), (m.odds_team2, m.outcome2_id.clone()))
+            } else {
+                ((m.odds_team2, m.outcome2_id.clone()), (m.odds_team1, m.outcome1_id.clone()))
+            };
+
+            let implied_sum = 1.0 / map_odds + 1.0 / match_odds;
+            let profit_pct = ((1.0 / implied_sum) * (1.0 - INTRA_ARB_FEE_PCT / 100.0) - 1.0) * 100.0;
+            if profit_pct <= 0.0 || best.as_ref().is_some_and(|b| b.profit_pct >= profit_pct) {
+                continue;
+            }
+
+            let team = |s: u8| if s == 1 { m.team1.clone() } else { m.team2.clone() };
+            best = Some(IntraArb {
+                match_key: strip_map_winner_suffix(&match_item.match_key),
+                team1: m.team1.clone(),
+                team2: m.team2.clone(),
+                map_index,
+                map_leg: IntraArbLeg {
+                    market: market.clone(),
+                    side,
+                    team: team(side),
+                    odds: map_odds,
+                    stake_frac: (1.0 / map_odds) / implied_sum,
+                    condition_id: p.condition_id.clone(),
+                    outcome_id: map_outcome,
+                    opposite_outcome_id: map_opposite.1,
+                    opposite_odds: map_opposite.0,
+                },
+                match_leg: IntraArbLeg {
+                    market: "match_winner".to_string(),
+                    side: match_side,
+                    team: team(match_side),
+                    odds: match_odds,
+                    stake_frac: (1.0 / match_odds) / implied_sum,
+                    condition_id: m.condition_id.clone(),
+                    outcome_id: match_outcome,
+                    opposite_outcome_id: match_opposite.1,
+                    opposite_odds: match_opposite.0,
+                },
+                implied_sum,
+                profit_pct,
+            });
+        }
+    }
+    best
+}
+
+/// Stake obou nohou (map, match) ve stejném poměru jako `stake_frac`. Menší noha pod
+/// MIN_EXECUTABLE_STAKE_USD → celý dutch se navýší jedním faktorem; nad INTRA_ARB_MAX_STAKE_USD → None.
+fn intra_arb_leg_stakes(arb: &IntraArb) -> Option<(f64, f64)> {
+    let min_frac = arb.map_leg.stake_frac.min(arb.match_leg.stake_frac);
+    if min_frac <= 0.0 {
+        return None;
+    }
+    let total = INTRA_ARB_STAKE_USD.max(MIN_EXECUTABLE_STAKE_USD / min_frac);
+    if total > INTRA_ARB_MAX_STAKE_USD + 1e-9 {
+        return None;
+    }
+    // Zaokrouhlit nahoru na centy → menší noha nespadne pod minimum
+    let cents = |frac: f64| (total * frac * 100.0 - 1e-6).ceil() / 100.0;
+    Some((cents(arb.map_leg.stake_frac), cents(arb.match_leg.stake_frac)))
+}
+
+/// Stake protisázky, která vyrovná výplatu otevřené nohy (`stake` @ `odds`); None = pod minimem.
+fn intra_arb_close_out_stake(stake: f64, odds: f64, opposite_odds: f64) -> Option<f64> {
+    if opposite_odds <= 1.0 {
+        return None;
+    }
+    let close_stake = (stake * odds / opposite_odds * 100.0).round() / 100.0;
+    (close_stake >= MIN_EXECUTABLE_STAKE_USD).then_some(close_stake)
+}
+
+/// Rozhoduje mapa N o celém zápase? Bo1 → map1 vždy; BoN → N-tá mapa, která se právě hraje
+/// (na poslední mapu se série dostane jen za nerozhodného stavu).
+fn intra_arb_map_is_decisive(map_index: u8, best_of: Option<u8>, current_map: Option<u8>) -> bool {
+    match best_of {
+        Some(1) => map_index == 1,
+        Some(bo) => map_index == bo && current_map == Some(bo),
+        None => false,
+    }
+}
+
+fn find_odds_anomalies(state: &StateResponse, venues: &ExecutionVenues) -> Vec<OddsAnomaly> {
     let now = Utc::now();
     // Build set of currently live match_keys
     let live_keys: std::collections::HashMap<String, &LiveItem> = state.live.iter()
@@ -7639,8 +12453,14 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
         .collect();
 
     // Group odds by match_key
+    // Jen moneyline (match_winner / mapN_winner) — totals/handicap zahodit hned tady
     let mut by_match: std::collections::HashMap<String, Vec<&StateOddsItem>> = std::collections::HashMap::new();
     for item in &state.odds {
+        if !is_moneyline_market(item.payload.market.as_deref()) {
+            debug!("MARKET_FILTER drop (odds anomaly): match_key={} market={:?} bookmaker={}",
+                item.match_key, item.payload.market, item.payload.bookmaker);
+            continue;
+        }
         by_match.entry(item.match_key.clone()).or_default().push(item);
     }
 
@@ -7648,11 +12468,11 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
 
     for (match_key, items) in &by_match {
         let azuro_items: Vec<&&StateOddsItem> = items.iter()
-            .filter(|i| i.payload.bookmaker.starts_with("azuro_") && is_recent_seen_at(&i.seen_at, now))
+            .filter(|i| venues.is_venue(&i.payload.bookmaker) && is_recent_seen_at(&i.seen_at, now))
             .collect();
         // Include hltv-featured (20bet, ggbet, etc.) as market reference!
         let market_items_all: Vec<&&StateOddsItem> = items.iter()
-            .filter(|i| !i.payload.bookmaker.starts_with("azuro_") && is_recent_seen_at(&i.seen_at, now))
+            .filter(|i| !venues.is_venue(&i.payload.bookmaker) && is_recent_seen_at(&i.seen_at, now))
             .collect();
 
         if azuro_items.is_empty() || market_items_all.is_empty() {
@@ -7684,7 +12504,8 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                 .copied()
                 .filter(|mi| {
                     let market_key = normalized_market_key(mi.payload.market.as_deref());
-                    market_key == azuro_market
+                    // 1X2 jen proti 1X2, two-way jen proti two-way (kurzy s remízou nejsou srovnatelné)
+                    market_key == azuro_market && mi.payload.odds_draw.is_some() == azuro.odds_draw.is_some()
                 })
                 .collect();
 
@@ -7698,17 +12519,33 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                 continue;
             }
 
-            // For each market source, align teams and compute discrepancy
+            if azuro.odds_draw.is_some() {
+                anomalies.extend(find_three_way_anomaly(
+                    match_key,
+                    azuro,
+                    &market_items,
+                    is_live,
+                    live_score.clone(),
+                    detailed_score.clone(),
+                    now,
+                ));
+                continue;
+            }
+
+            // For each market source, align teams and compute discrepancy (průměr vážený čerstvostí)
             let mut total_m_w1 = 0.0_f64;
             let mut total_m_w2 = 0.0_f64;
+            let mut total_weight = 0.0_f64;
             let mut any_swapped = false;
             let mut any_ambiguous = false;
             let mut market_count = 0;
 
             for mi in &market_items {
                 let (mw1, mw2, swapped, ambiguous) = align_teams(azuro, &mi.payload);
-                total_m_w1 += mw1;
-                total_m_w2 += mw2;
+                let weight = odds_freshness_weight(&mi.seen_at, now);
+                total_m_w1 += mw1 * weight;
+                total_m_w2 += mw2 * weight;
+                total_weight += weight;
                 if swapped { any_swapped = true; }
                 if ambiguous { any_ambiguous = true; }
                 market_count += 1;
@@ -7721,8 +12558,8 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                 continue;
             }
 
-            let avg_w1 = total_m_w1 / market_count as f64;
-            let avg_w2 = total_m_w2 / market_count as f64;
+            let avg_w1 = total_m_w1 / total_weight;
+            let avg_w2 = total_m_w2 / total_weight;
 
             let market_bookie = market_items.iter().map(|i| i.payload.bookmaker.as_str()).collect::<Vec<_>>().join("+");
 
@@ -7919,6 +12756,8 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                     azuro_url: azuro.url.clone(),
                     market_w1: avg_w1,
                     market_w2: avg_w2,
+                    azuro_draw: None,
+                    market_draw: None,
                     market_bookmaker: market_bookie,
                     value_side: 1,
                     discrepancy_pct: disc_w1,
@@ -7932,6 +12771,12 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                     condition_id: azuro.condition_id.clone(),
                     outcome1_id: azuro.outcome1_id.clone(),
                     outcome2_id: azuro.outcome2_id.clone(),
+                    outcome_draw_id: azuro.outcome_draw_id.clone(),
+                    outcome_order: azuro_outcome_order(
+                        azuro.outcome1_id.as_deref(),
+                        azuro.outcome2_id.as_deref(),
+                        azuro.outcome_draw_id.as_deref(),
+                    ),
                     outcome_id: azuro.outcome1_id.clone(),
                     chain: azuro.chain.clone(),
                 });
@@ -7948,6 +12793,8 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                     azuro_url: azuro.url.clone(),
                     market_w1: avg_w1,
                     market_w2: avg_w2,
+                    azuro_draw: None,
+                    market_draw: None,
                     market_bookmaker: market_bookie,
                     value_side: 2,
                     discrepancy_pct: disc_w2,
@@ -7961,6 +12808,12 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
                     condition_id: azuro.condition_id.clone(),
                     outcome1_id: azuro.outcome1_id.clone(),
                     outcome2_id: azuro.outcome2_id.clone(),
+                    outcome_draw_id: azuro.outcome_draw_id.clone(),
+                    outcome_order: azuro_outcome_order(
+                        azuro.outcome1_id.as_deref(),
+                        azuro.outcome2_id.as_deref(),
+                        azuro.outcome_draw_id.as_deref(),
+                    ),
                     outcome_id: azuro.outcome2_id.clone(),
                     chain: azuro.chain.clone(),
                 });
@@ -7981,10 +12834,346 @@ fn find_odds_anomalies(state: &StateResponse) -> Vec<OddsAnomaly> {
     anomalies
 }
 
+/// Remíza ve three-way trhu (value_side 1 = team1, 2 = team2).
+const VALUE_SIDE_DRAW: u8 = 3;
+
+/// De-vig 1X2 kurzů: implied pravděpodobnosti normalizované na součet 1 (pořadí 1, X, 2).
+fn devig_three_way(odds1: f64, odds_draw: f64, odds2: f64) -> Option<[f64; 3]> {
+    if odds1 <= 1.0 || odds_draw <= 1.0 || odds2 <= 1.0 {
+        return None;
+    }
+    let raw = [1.0 / odds1, 1.0 / odds_draw, 1.0 / odds2];
+    let total: f64 = raw.iter().sum();
+    Some(raw.map(|p| p / total))
+}
+
+/// Three-way (1X2) anomálie: Azuro kurz vs. de-vigovaná fair pravděpodobnost trhu, včetně remízy.
+/// Discrepancy = EV proti fair trhu (azuro_odds × fair_p − 1); bere se nejlepší ze tří výsledků.
+fn find_three_way_anomaly(
+    match_key: &str,
+    azuro: &OddsPayload,
+    market_items: &[&&StateOddsItem],
+    is_live: bool,
+    live_score: Option<String>,
+    detailed_score: Option<String>,
+    now: DateTime<Utc>,
+) -> Option<OddsAnomaly> {
+    let azuro_draw = azuro.odds_draw?;
+    let mut totals = [0.0_f64; 3];
+    let mut total_weight = 0.0_f64;
+    let mut any_swapped = false;
+    for mi in market_items {
+        let (mw1, mw2, swapped, ambiguous) = align_teams(azuro, &mi.payload);
+        if ambiguous {
+            info!("🚫 ODDS ANOMALY TEAM AMBIGUOUS (1X2): {} — azuro({} vs {}) cannot reliably match market teams, skipping",
+                match_key, azuro.team1, azuro.team2);
+            return None;
+        }
+        let weight = odds_freshness_weight(&mi.seen_at, now);
+        totals[0] += mw1 * weight;
+        totals[1] += mi.payload.odds_draw? * weight;
+        totals[2] += mw2 * weight;
+        total_weight += weight;
+        any_swapped |= swapped;
+    }
+    if market_items.is_empty() {
+        return None;
+    }
+    let market = totals.map(|t| t / total_weight);
+    if market.iter().any(|o| *o <= SUSPENDED_MARKET_MIN_ODDS || *o >= SUSPENDED_MARKET_MAX_ODDS) {
+        debug!("ODDS_ANOMALY 1X2 suspended market: {} odds={:.2}/{:.2}/{:.2}", match_key, market[0], market[1], market[2]);
+        return None;
+    }
+    let fair = devig_three_way(market[0], market[1], market[2])?;
+    let azuro_odds = [azuro.odds_team1, azuro_draw, azuro.odds_team2];
+    let disc = [0, 1, 2].map(|i| (azuro_odds[i] * fair[i] - 1.0) * 100.0);
+    let (best_idx, best_disc) = disc.iter().copied().enumerate().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if best_disc <= MIN_EDGE_PCT {
+        return None;
+    }
+
+    let mut reasons = vec![format!("1X2 de-vig trhu: {:.0}% / {:.0}% / {:.0}%",
+        fair[0] * 100.0, fair[1] * 100.0, fair[2] * 100.0)];
+    let mut penalty = 0;
+    if any_swapped {
+        reasons.push(format!("Týmy v jiném pořadí ✅ zarovnáno (azuro: {} vs {}, trh: {} vs {})",
+            azuro.team1, azuro.team2, market_items[0].payload.team1, market_items[0].payload.team2));
+        penalty += 1;
+    }
+    if azuro_odds[best_idx] > 8.0 {
+        reasons.push(format!("Extrémní odds ({:.2}) — pravděpodobně rozhodnutý zápas", azuro_odds[best_idx]));
+        penalty += 2;
+    }
+    if best_disc > 40.0 {
+        reasons.push(format!("{:.0}% discrepancy je podezřele vysoká — stale data?", best_disc));
+        penalty += 2;
+    }
+    if market_items.len() >= 2 {
+        reasons.push(format!("{} market zdrojů se shoduje", market_items.len()));
+        penalty -= 1;
+    }
+    let confidence = if penalty <= 0 {
+        "HIGH"
+    } else if penalty <= 2 {
+        "MEDIUM"
+    } else {
+        return None;
+    };
+
+    let (value_side, outcome_id) = match best_idx {
+        0 => (1, azuro.outcome1_id.clone()),
+        1 => (VALUE_SIDE_DRAW, azuro.outcome_draw_id.clone()),
+        _ => (2, azuro.outcome2_id.clone()),
+    };
+    Some(OddsAnomaly {
+        detected_at: now,
+        match_key: match_key.to_string(),
+        market_key: azuro.market.clone().unwrap_or_else(|| "match_winner".to_string()),
+        team1: azuro.team1.clone(),
+        team2: azuro.team2.clone(),
+        azuro_w1: azuro.odds_team1,
+        azuro_w2: azuro.odds_team2,
+        azuro_bookmaker: azuro.bookmaker.clone(),
+        azuro_url: azuro.url.clone(),
+        market_w1: market[0],
+        market_w2: market[2],
+        azuro_draw: Some(azuro_draw),
+        market_draw: Some(market[1]),
+        market_bookmaker: market_items.iter().map(|i| i.payload.bookmaker.as_str()).collect::<Vec<_>>().join("+"),
+        value_side,
+        discrepancy_pct: best_disc,
+        confidence,
+        confidence_reasons: reasons,
+        teams_swapped: any_swapped,
+        is_live,
+        live_score,
+        detailed_score,
+        game_id: azuro.game_id.clone(),
+        condition_id: azuro.condition_id.clone(),
+        outcome1_id: azuro.outcome1_id.clone(),
+        outcome2_id: azuro.outcome2_id.clone(),
+        outcome_draw_id: azuro.outcome_draw_id.clone(),
+        outcome_order: azuro_outcome_order(
+            azuro.outcome1_id.as_deref(),
+            azuro.outcome2_id.as_deref(),
+            azuro.outcome_draw_id.as_deref(),
+        ),
+        outcome_id,
+        chain: azuro.chain.clone(),
+    })
+}
+
+/// GET {feed_hub}/state → StateResponse (testy proti mock feed-hubu; hlavní smyčka loguje fetch a parse chybu zvlášť).
+#[cfg(test)]
+async fn fetch_feed_state(client: &reqwest::Client, feed_hub_url: &str) -> Result<StateResponse> {
+    let resp = client.get(format!("{}/state", feed_hub_url)).send().await.context("fetch /state")?;
+    resp.json::<StateResponse>().await.context("parse /state")
+}
+
+/// Condition freshness: conditions neviděné v /state déle než tohle se zahodí.
+const CONDITION_LAST_SEEN_TTL_SECS: u64 = 600;
+
+/// Detekční výstup jednoho poll cyklu nad /state (po cooldown dedupu).
+struct PollCycleSignals {
+    score_edges: Vec<ScoreEdge>,
+    anomalies: Vec<OddsAnomaly>,
+    /// Počty před dedupem (Poll log)
+    total_score_edges: usize,
+    total_anomalies: usize,
+}
+
+fn score_edge_alert_key(edge: &ScoreEdge) -> String {
+    format!("score:{}:{}:{}-{}", edge.match_key, edge.leading_side, edge.score1, edge.score2)
+}
+
+fn anomaly_alert_key(anomaly: &OddsAnomaly) -> String {
+    format!("{}:{}:{}", anomaly.match_key, anomaly.value_side, anomaly.azuro_bookmaker)
+}
+
+/// Jeden poll cyklus bez Telegramu/executoru: freshness conditions, score edges (primární)
+/// a cross-book anomálie (sekundární), bez signálů s klíčem v `already_alerted`.
+fn run_poll_cycle(
+    state: &StateResponse,
+    tracker: &mut ScoreTracker,
+    resync_freeze: &mut HashMap<String, ResyncState>,
+    venues: &ExecutionVenues,
+    already_alerted: &HashSet<String>,
+    condition_last_seen: &mut HashMap<String, std::time::Instant>,
+) -> PollCycleSignals {
+    let poll_instant = std::time::Instant::now();
+    for item in &state.odds {
+        if let Some(cid) = &item.payload.condition_id {
+            if !cid.is_empty() {
+                condition_last_seen.insert(cid.clone(), poll_instant);
+            }
+        }
+    }
+    condition_last_seen.retain(|_, ts| poll_instant.duration_since(*ts).as_secs() < CONDITION_LAST_SEEN_TTL_SECS);
+
+    let score_edges = find_score_edges(state, tracker, resync_freeze, venues);
+    let anomalies = find_odds_anomalies(state, venues);
+    let total_score_edges = score_edges.len();
+    let total_anomalies = anomalies.len();
+    PollCycleSignals {
+        score_edges: score_edges.into_iter()
+            .filter(|e| !already_alerted.contains(&score_edge_alert_key(e)))
+            .collect(),
+        anomalies: anomalies.into_iter()
+            .filter(|a| !already_alerted.contains(&anomaly_alert_key(a)))
+            .collect(),
+        total_score_edges,
+        total_anomalies,
+    }
+}
+
+/// Exposure po úspěšném umístění: condition (pokud je známá) + base match + sport.
+fn record_placement_exposure(
+    condition_exposure: &mut HashMap<String, f64>,
+    match_exposure: &mut HashMap<String, f64>,
+    sport_exposure: &mut HashMap<String, f64>,
+    scoped_cond_key: Option<&String>,
+    base_match_key: &str,
+    sport: &str,
+    stake: f64,
+) {
+    if let Some(key) = scoped_cond_key {
+        *condition_exposure.entry(key.clone()).or_insert(0.0) += stake;
+    }
+    *match_exposure.entry(base_match_key.to_string()).or_insert(0.0) += stake;
+    *sport_exposure.entry(sport.to_string()).or_insert(0.0) += stake;
+}
+
+/// Doporučený hedge aktivní sázky: opačný outcome stejné condition, stake vyrovná výsledek obou stran.
+#[derive(Debug, Clone, PartialEq)]
+struct HedgeRecommendation {
+    bet_id: String,
+    match_key: String,
+    condition_id: String,
+    opposite_outcome_id: String,
+    opposite_team: String,
+    opposite_odds: f64,
+    /// 1 / kurz naší sázky
+    implied_prob_at_bet: f64,
+    /// Score-implied prob naší strany teď
+    fair_prob_now: f64,
+    /// Max. stake původní sázky — hedge nikdy neriskuje víc, než co jistí
+    hedge_stake: f64,
+    /// PnL po hedgi v horším z obou výsledků (plný hedge = stejné pro oba; záporné = menší ztráta)
+    locked_pnl: f64,
+}
+
+impl HedgeRecommendation {
+    /// Hedge s jiným stake (cap / trimmer); locked_pnl se přepočte.
+    fn with_stake(self, bet: &ActiveBet, hedge_stake: f64) -> Self {
+        let locked_pnl = hedge_locked_pnl(bet, self.opposite_odds, hedge_stake);
+        Self { hedge_stake, locked_pnl, ..self }
+    }
+}
+
+/// Horší z obou výsledků po hedgi `hedge_stake` @ `opposite_odds` proti sázce `bet`.
+fn hedge_locked_pnl(bet: &ActiveBet, opposite_odds: f64, hedge_stake: f64) -> f64 {
+    let ours_wins = bet.amount_usd * bet.odds - bet.amount_usd - hedge_stake;
+    let theirs_wins = hedge_stake * opposite_odds - bet.amount_usd - hedge_stake;
+    ours_wins.min(theirs_wins)
+}
+
+/// Score-implied prob strany se skóre `ours` proti `theirs`.
+/// Map-winner trh = roundy na mapě (CS2 model — jiné sporty None), jinak mapy v sérii; None = série už rozhodnutá.
+fn live_side_fair_prob(sport: &str, map_market: bool, ours: i32, theirs: i32) -> Option<f64> {
+    if map_market && sport != "cs2" {
+        return None;
+    }
+    if ours == theirs {
+        return Some(0.5);
+    }
+    let (lead, trail) = (ours.max(theirs), ours.min(theirs));
+    let leading_prob = if map_market {
+        cs2_map_win_prob(lead - trail, lead + trail)
+    } else {
+        map_score_to_win_prob(lead, trail)?
+    };
+    Some(if ours > theirs { leading_prob } else { 1.0 - leading_prob })
+}
+
+/// Hedge doporučení pro aktivní sázku z aktuálního live skóre a odds její condition.
+fn hedge_recommendation(bet: &ActiveBet, live: &LivePayload, odds: &OddsPayload) -> Option<HedgeRecommendation> {
+    if odds.condition_id.as_deref() != Some(bet.condition_id.as_str()) || bet.odds <= 1.0 {
+        return None;
+    }
+    let (opposite_outcome_id, opposite_odds, opposite_team) = if odds.outcome1_id.as_deref() == Some(bet.outcome_id.as_str()) {
+        (odds.outcome2_id.clone()?, odds.odds_team2, odds.team2.clone())
+    } else if odds.outcome2_id.as_deref() == Some(bet.outcome_id.as_str()) {
+        (odds.outcome1_id.clone()?, odds.odds_team1, odds.team1.clone())
+    } else {
+        return None;
+    };
+    if opposite_odds <= 1.0 {
+        return None;
+    }
+
+    let map_market = is_map_winner_market(&bet.match_key, &bet.market_key);
+    let detailed = live.detailed_score.as_deref().unwrap_or("");
+    let (s1, s2) = if map_market {
+        parse_esports_round_score(detailed).unwrap_or((live.score1, live.score2))
+    } else {
+        parse_esports_map_score(detailed, live.score1, live.score2)
+    };
+    let (ours, theirs) = match resolve_azuro_side(&bet.value_team, &live.team1, &live.team2, 0)? {
+        1 => (s1, s2),
+        _ => (s2, s1),
+    };
+
+    let sport = bet.match_key.split("::").next().unwrap_or("");
+    let fair_prob_now = live_side_fair_prob(sport, map_market, ours, theirs)?;
+    let implied_prob_at_bet = 1.0 / bet.odds;
+    if implied_prob_at_bet - fair_prob_now < HEDGE_FAIR_DROP {
+        return None;
+    }
+
+    let payout = bet.amount_usd * bet.odds;
+    let hedge_stake = ((payout / opposite_odds * 100.0).round() / 100.0).min(bet.amount_usd);
+    Some(HedgeRecommendation {
+        bet_id: bet.bet_id.clone(),
+        match_key: bet.match_key.clone(),
+        condition_id: bet.condition_id.clone(),
+        opposite_outcome_id,
+        opposite_team,
+        opposite_odds,
+        implied_prob_at_bet,
+        fair_prob_now,
+        hedge_stake,
+        locked_pnl: hedge_locked_pnl(bet, opposite_odds, hedge_stake),
+    })
+}
+
+fn format_hedge_message(bet: &ActiveBet, hedge: &HedgeRecommendation, auto: bool) -> String {
+    format!(
+        "🛡️ <b>HEDGE{}</b> #{}\n\n\
+         {}\n\
+         Sázka: {} ${:.2} @ {:.2} (implied {:.0}%)\n\
+         Live fair teď: {:.0}%\n\
+         Opačná strana: {} @ {:.2} → stake ${:.2}\n\
+         Zamčený výsledek: {:+.2} USD (bez hedge až -{:.2})",
+        if auto { " PLACING" } else { " DOPORUČEN" },
+        bet.alert_id, hedge.match_key,
+        bet.value_team, bet.amount_usd, bet.odds, hedge.implied_prob_at_bet * 100.0,
+        hedge.fair_prob_now * 100.0,
+        hedge.opposite_team, hedge.opposite_odds, hedge.hedge_stake,
+        hedge.locked_pnl, bet.amount_usd,
+    )
+}
+
+/// (tým nebo "Remíza", Azuro odds, market odds) pro danou stranu anomálie.
+fn anomaly_side_pick(a: &OddsAnomaly, side: u8) -> (&str, f64, f64) {
+    match side {
+        1 => (&a.team1, a.azuro_w1, a.market_w1),
+        VALUE_SIDE_DRAW => ("Remíza", a.azuro_draw.unwrap_or(0.0), a.market_draw.unwrap_or(0.0)),
+        _ => (&a.team2, a.azuro_w2, a.market_w2),
+    }
+}
+
 fn format_anomaly_alert(a: &OddsAnomaly, alert_id: u32) -> String {
-    let value_team = if a.value_side == 1 { &a.team1 } else { &a.team2 };
-    let azuro_odds = if a.value_side == 1 { a.azuro_w1 } else { a.azuro_w2 };
-    let market_odds = if a.value_side == 1 { a.market_w1 } else { a.market_w2 };
+    let (value_team, azuro_odds, market_odds) = anomaly_side_pick(a, a.value_side);
     let market_label = a.market_key.replace('_', " ");
 
     let conf_emoji = match a.confidence {
@@ -8172,6 +13361,63 @@ fn format_odds_drift_message(requested_odds: f64, accepted_odds: f64) -> Option<
     })
 }
 
+/// Třída chyby z executoru (`error` + `state`) — rozhoduje, jestli má retry smysl.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExecutorRejectClass {
+    /// Live kurzy na chvíli zmrazené (condition paused / not active) — retry má smysl
+    ConditionPaused,
+    /// Condition už neběží (mapa dohraná, live nedostupné) — žádný retry, blacklist
+    ConditionDead,
+    /// Kurz se mezitím pohnul pod minOdds — řeší jednorázový min-odds fallback
+    MinOdds,
+    /// Executor už stejnou sázku má (409)
+    Dedup,
+    /// Trvalá chyba (balance, allowance, revert, settled outcome…) — retry jen pálí budget
+    Fatal,
+    Unknown,
+}
+
+impl ExecutorRejectClass {
+    fn classify(error: Option<&str>, state: Option<&str>) -> Self {
+        let text = format!("{} {}", error.unwrap_or(""), state.unwrap_or("")).to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| text.contains(n));
+        // Pořadí je důležité: "insufficient balance" uvnitř paused hlášky pořád nesmí retryovat
+        if has(&["insufficient", "allowance", "revert", "nonce", "settled", "resolved", "canceled", "cancelled"]) {
+            Self::Fatal
+        } else if has(&["not available", "live is not"]) {
+            Self::ConditionDead
+        } else if has(&["dedup", "already bet"]) {
+            Self::Dedup
+        } else if has(&["min odds", "minodds", "real odds"]) {
+            Self::MinOdds
+        } else if has(&["paused", "not active", "not exist", "suspended"]) {
+            Self::ConditionPaused
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Jediná třída, která se retryuje s `AUTO_BET_RETRY_DELAYS_MS` backoffem.
+    fn is_retryable(self) -> bool {
+        self == Self::ConditionPaused
+    }
+
+    fn is_condition_state(self) -> bool {
+        matches!(self, Self::ConditionPaused | Self::ConditionDead)
+    }
+
+    /// `reason_code` v ledgeru (BET_FAILED) a v Telegram hlášce.
+    fn reason_code(self) -> &'static str {
+        match self {
+            Self::ConditionPaused | Self::ConditionDead => "ConditionNotRunning",
+            Self::MinOdds => "MinOddsReject",
+            Self::Dedup => "Dedup",
+            Self::Fatal => "Fatal",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
 fn format_auto_bet_failed_message(
     aid: u32,
     path: &str,
@@ -8343,9 +13589,11 @@ async fn main() -> Result<()> {
     }
 
     // Discover chat_id: either from env or from first message
-    let mut chat_id: Option<i64> = std::env::var("TELEGRAM_CHAT_ID")
-        .ok()
-        .and_then(|s| s.parse().ok());
+    // TELEGRAM_CHAT_ID="a,@channel,...": first numeric chat is interactive (commands, replies), alerts go to all of them
+    let (mut chat_id, broadcast_chat_ids) = split_alert_chat_ids(&std::env::var("TELEGRAM_CHAT_ID").unwrap_or_default());
+    if !broadcast_chat_ids.is_empty() {
+        info!("📣 Alert broadcast to {} extra chat(s): {:?}", broadcast_chat_ids.len(), broadcast_chat_ids);
+    }
 
     let mut update_offset: i64 = 0;
     let mut sent_alerts: Vec<SentAlert> = Vec::new();
@@ -8360,9 +13608,35 @@ async fn main() -> Result<()> {
     // These should NOT block new bets via MAX_CONCURRENT_PENDING (no longer risk exposure).
     let mut deferred_claim_tokens: HashSet<String> = HashSet::new();
     let mut score_tracker = ScoreTracker::new();
+    score_tracker.frozen_window_secs = std::env::var("FROZEN_SCORE_WINDOW_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .unwrap_or(FROZEN_SCORE_WINDOW_SECS);
+    info!("🧊 Frozen-score dead-man's switch: {}s", score_tracker.frozen_window_secs);
+    score_tracker.score_ceilings = ScoreCeilings::from_env();
+    score_tracker.sport_kill = SportKillSwitch::from_env();
+    score_tracker.too_late = TooLateGuard::from_env();
+    score_tracker.audit_ledger = Some(LEDGER_PATH.into());
+    let execution_venues = ExecutionVenues::from_env();
+    let stake_policy = StakePolicy::from_env();
+    // Sdílená kalibrace s live-observerem (PREDICTION_STATS_PATH), jinak surové heuristiky
+    let prediction_engine = std::env::var("PREDICTION_STATS_PATH")
+        .map(PredictionEngine::with_stats_path)
+        .unwrap_or_else(|_| PredictionEngine::new());
+    let slippage_guard = SlippageGuard::from_env();
+    let league_filter = LeagueFilter::from_env();
+    let digest_push = DigestPush::from_env();
+    info!("🏦 Execution venue prefixes: {:?}", execution_venues.prefixes);
+    score_tracker.near_miss = NearMissLog::from_env("data/near_miss");
+    if score_tracker.near_miss.is_some() {
+        info!("📉 Near-miss log enabled → data/near_miss/");
+    }
     // In-flight dedup: condition IDs currently being sent to executor (prevents race condition
     // where two score edges for same match arrive in same poll tick before executor responds)
     let mut inflight_conditions: HashSet<String> = HashSet::new();
+    // bet_id sázek, ke kterým už odešel hedge (nabídka nebo auto-hedge) — jeden hedge na sázku;
+    // po restartu se doplní z HEDGE_RECOMMENDED záznamů v ledgeru
+    let mut hedged_bets: HashSet<String> = HashSet::new();
 
     // === RE-BET STATE: track bets per condition for re-bet logic ===
     let mut rebet_tracker: HashMap<String, ReBetState> = HashMap::new();
@@ -8390,29 +13664,40 @@ async fn main() -> Result<()> {
     // Prevents "shrinking box" where losing bets reduce bankroll → reduce limit → stop earlier
     let mut start_of_day_bankroll: f64 = 65.0;
     let mut sod_loaded_from_file = false; // guard: don't overwrite SOD from executor if file had valid value
+    let mut sod_needs_reconstruction = false; // mid-day restart bez sod_bankroll → dopočítat z executor balance
+
+    // === STATE STORE: bet count, daily P&L, dedup history, pending claims ===
+    let state_store = open_state_store("data");
+    match state_store.load_disabled_sports() {
+        Ok(Some(saved)) => score_tracker.sport_kill.restore(saved),
+        Ok(None) => {}
+        Err(e) => warn!("⚠️ Sport kill switch: uložený stav nelze načíst ({}), platí AUTO_BET_DISABLED_SPORTS", e),
+    }
+    if !score_tracker.sport_kill.disabled.is_empty() {
+        info!("🛑 Auto-bet vypnutý pro sporty: {:?}", score_tracker.sport_kill.disabled_list());
+    }
 
-    // BUG #6 FIX: Persist auto_bet_count across restarts (daily file)
-    let bet_count_path = "data/bet_count_daily.txt";
+    // BUG #6 FIX: Persist auto_bet_count across restarts (daily)
     let mut auto_bet_count: u32 = 0;
+    let betting_day = BettingDay::from_env();
+    info!("📅 Sázkový den: {} od {:02}:00", betting_day.tz, betting_day.cutoff_hour);
     {
-        let today = Utc::now().format("%Y-%m-%d").to_string();
-        if Path::new(bet_count_path).exists() {
-            if let Ok(contents) = std::fs::read_to_string(bet_count_path) {
-                let parts: Vec<&str> = contents.trim().split('|').collect();
-                if parts.len() >= 2 && parts[0] == today {
-                    auto_bet_count = parts[1].parse().unwrap_or(0);
-                    info!("📋 Loaded auto_bet_count={} for today ({})", auto_bet_count, today);
-                } else {
-                    info!("📋 bet_count_daily.txt is from a different day, resetting to 0");
-                }
+        let today = betting_day.today();
+        match state_store.load_bet_count() {
+            Ok(Some((date, count))) if date == today => {
+                auto_bet_count = count;
+                info!("📋 Loaded auto_bet_count={} for today ({})", auto_bet_count, today);
             }
+            Ok(Some(_)) => info!("📋 Stored bet count is from a different day, resetting to 0"),
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ bet count load failed: {}", e),
         }
     }
 
     // === DAILY P&L TRACKING (NET loss limit) ===
     let mut daily_wagered: f64 = 0.0;
     let mut daily_returned: f64 = 0.0;
-    let mut daily_date = Utc::now().format("%Y-%m-%d").to_string();
+    let mut daily_date = betting_day.today();
     let mut daily_loss_alert_sent = false;
     let mut daily_loss_last_reminder: Option<DateTime<Utc>> = None;
     /// Runtime override pro daily limit — nastaven přes /limit +X, reset na None každý nový den
@@ -8435,59 +13720,52 @@ async fn main() -> Result<()> {
         );
         info!("📱 Dashboard config loaded: max_stake={:?}, sport_focus={:?}, autobet={}, no_bet_mode={}",
             dashboard_max_stake, dashboard_sport_focus, dashboard_autobet_enabled, !dashboard_autobet_enabled);
-    }
-    // Load from daily_pnl.json if exists (includes SOD bankroll persistence)
-    {
-        let pnl_path = "data/daily_pnl.json";
-        if Path::new(pnl_path).exists() {
-            if let Ok(contents) = std::fs::read_to_string(pnl_path) {
-                if let Ok(v) = serde_json::from_str::<serde_json::Value>(&contents) {
-                    if v["date"].as_str() == Some(&daily_date) {
-                        daily_wagered = v["wagered"].as_f64().unwrap_or(0.0);
-                        daily_returned = v["returned"].as_f64().unwrap_or(0.0);
-                        // Restore SOD bankroll from file (survives mid-day restarts)
-                        if let Some(sod) = v["sod_bankroll"].as_f64() {
-                            if sod > 0.0 {
-                                start_of_day_bankroll = sod;
-                                sod_loaded_from_file = true;
-                                info!("📋 Restored SOD bankroll from file: ${:.2}", sod);
-                            }
-                        }
-                        // Fallback: if no sod_bankroll in file but we have daily P&L data,
-                        // mark as loaded to prevent executor overwrite (SOD = default, which is
-                        // better than using current depleted balance)
-                        if !sod_loaded_from_file && daily_wagered > 0.0 {
-                            sod_loaded_from_file = true;
-                            info!("📋 SOD bankroll not in file, but mid-day restart detected (wagered > 0). Keeping default SOD=${:.2} to prevent shrinking-box", start_of_day_bankroll);
-                        }
-                        // Restore limit override if saved
-                        if let Some(ov) = v["limit_override"].as_f64() {
-                            if ov > DAILY_LOSS_LIMIT_USD {
-                                daily_limit_override = Some(ov);
-                                info!("📋 Restored limit override: ${:.0}", ov);
-                            }
-                        }
-                        info!("📋 Loaded daily P&L: wagered={:.2} returned={:.2} net={:.2} sod_br=${:.2}",
-                            daily_wagered, daily_returned, daily_returned - daily_wagered, start_of_day_bankroll);
-                    } else {
-                        info!("📋 daily_pnl.json is from different day, resetting");
-                    }
+    }
+    // Load daily P&L from state store (includes SOD bankroll persistence)
+    match state_store.load_daily_pnl() {
+        Ok(Some(pnl)) if pnl.date == daily_date => {
+            daily_wagered = pnl.wagered;
+            daily_returned = pnl.returned;
+            // Restore SOD bankroll (survives mid-day restarts)
+            if pnl.sod_bankroll > 0.0 {
+                start_of_day_bankroll = pnl.sod_bankroll;
+                sod_loaded_from_file = true;
+                info!("📋 Restored SOD bankroll from state store: ${:.2}", pnl.sod_bankroll);
+            }
+            // Fallback: if no sod_bankroll stored but we have daily P&L data,
+            // mark as loaded to prevent executor overwrite with the depleted balance;
+            // the real SOD is reconstructed from executor balance + today's P&L below.
+            if !sod_loaded_from_file && daily_wagered > 0.0 {
+                sod_loaded_from_file = true;
+                sod_needs_reconstruction = true;
+                info!("📋 SOD bankroll not stored, but mid-day restart detected (wagered > 0). SOD will be reconstructed from executor balance (default ${:.2} until then)", start_of_day_bankroll);
+            }
+            // Restore limit override if saved
+            if let Some(ov) = pnl.limit_override {
+                if ov > DAILY_LOSS_LIMIT_USD {
+                    daily_limit_override = Some(ov);
+                    info!("📋 Restored limit override: ${:.0}", ov);
                 }
             }
+            info!("📋 Loaded daily P&L: wagered={:.2} returned={:.2} net={:.2} sod_br=${:.2}",
+                daily_wagered, daily_returned, daily_returned - daily_wagered, start_of_day_bankroll);
         }
+        Ok(Some(_)) => info!("📋 Stored daily P&L is from different day, resetting"),
+        Ok(None) => {}
+        Err(e) => warn!("⚠️ daily P&L load failed: {}", e),
     }
 
     // Reconcile daily P&L from today's ledger so restarts and duplicate claim paths
     // cannot leave daily_pnl.json out of sync with authoritative events.
-    if Path::new("data/ledger.jsonl").exists() {
-        if let Ok(contents) = std::fs::read_to_string("data/ledger.jsonl") {
+    if Path::new(LEDGER_PATH).exists() {
+        if let Ok(contents) = std::fs::read_to_string(LEDGER_PATH) {
             let mut ledger_daily_wagered = 0.0;
             let mut ledger_daily_returned = 0.0;
             let mut today_claimed_tokens: HashSet<String> = HashSet::new();
             let mut today_claimed_txs: HashSet<String> = HashSet::new();
             for line in contents.lines() {
                 if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) {
-                    if !entry.get("ts").and_then(|v| v.as_str()).map(|s| s.starts_with(&daily_date)).unwrap_or(false) {
+                    if !entry.get("ts").and_then(|v| v.as_str()).map(|s| betting_day.ts_in_day(s, &daily_date)).unwrap_or(false) {
                         continue;
                     }
                     match entry.get("event").and_then(|v| v.as_str()).unwrap_or("") {
@@ -8524,8 +13802,7 @@ async fn main() -> Result<()> {
             if (ledger_daily_wagered - daily_wagered).abs() > 0.009 || (ledger_daily_returned - daily_returned).abs() > 0.009 {
                 daily_wagered = ledger_daily_wagered;
                 daily_returned = ledger_daily_returned;
-                let _ = std::fs::write("data/daily_pnl.json",
-                    serde_json::json!({"date": daily_date, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                let _ = state_store.save_daily_pnl(&DailyPnl::new(&daily_date, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                 info!("📋 Reconciled daily_pnl from ledger: wagered={:.2} returned={:.2}", daily_wagered, daily_returned);
             }
         }
@@ -8538,8 +13815,31 @@ async fn main() -> Result<()> {
 
     // === WATCHDOG: SAFE MODE ===
     let mut safe_mode = false;
+    let mut safe_mode_activations_today: usize = 0;
     let mut last_good_data: Option<std::time::Instant> = None;
 
+    // === STATUS SNAPSHOT ===
+    // Jediné místo, kde se `StatusSources` plní ze stavu smyčky (poll → bot_status.json, /status).
+    // Makro, ne funkce: stav jsou lokální `mut` proměnné main, výpůjčky musí vzniknout až v místě použití.
+    macro_rules! status_snapshot {
+        () => {
+            StatusSources {
+                bankroll: current_bankroll,
+                sod_bankroll: start_of_day_bankroll,
+                daily_wagered,
+                daily_returned,
+                daily_limit_override,
+                auto_bet_count,
+                active_bets: &active_bets,
+                inflight_wagered_total,
+                safe_mode,
+                sport_kill: &score_tracker.sport_kill,
+                sport_exposure: &sport_exposure,
+                skip_tally: &score_tracker.skip_tally,
+            }.snapshot()
+        };
+    }
+
     // === EVENT LOG HELPER ===
     let events_path = "data/events.jsonl";
     let log_event = |event_type: &str, data: &serde_json::Value| {
@@ -8567,6 +13867,10 @@ async fn main() -> Result<()> {
         }
     }
 
+    let paper_settlement_enabled = env_bool("PAPER_SETTLEMENT", false);
+    // Log dir live-observeru — dry-run score-edge sázky jdou do jeho paper inboxu,
+    // vyhodnotí je MATCH_RESOLVED (alert-bot vítěze nezná)
+    let paper_bets_dir = std::path::PathBuf::from(std::env::var("PAPER_BETS_DIR").unwrap_or_else(|_| "logs".to_string()));
     let sqlite_ledger_tx: Option<mpsc::UnboundedSender<serde_json::Value>> = if env_bool("BET_SQLITE", true) {
         let db_path = std::env::var("BET_SQLITE_PATH").unwrap_or_else(|_| "data/bets.sqlite".to_string());
         let db_path_for_thread = db_path.clone();
@@ -8741,7 +14045,7 @@ async fn main() -> Result<()> {
     };
 
     // === PERMANENT BET LEDGER (append-only, NEVER deleted) ===
-    let ledger_path = "data/ledger.jsonl";
+    let ledger_path = LEDGER_PATH;
     let ledger_write = |event: &str, data: &serde_json::Value| {
         let mut entry = data.clone();
         if let Some(obj) = entry.as_object_mut() {
@@ -8771,7 +14075,6 @@ async fn main() -> Result<()> {
     const MATCH_BLACKLIST_TTL_SECS: u64 = 600; // 10 min — match-level cooldown (raised from 300s: 3x CondNR on same match within 16min)
 
     // === DEDUP: track already-bet market scopes + condition IDs (persisted across restarts) ===
-    let bet_history_path = "data/bet_history.txt";
     let mut already_bet_matches: HashSet<String> = HashSet::new();
     let mut already_bet_conditions: HashSet<String> = HashSet::new();
     // Track base match keys (without ::mapN_winner suffix) for match-level exposure
@@ -8780,144 +14083,50 @@ async fn main() -> Result<()> {
     // Subset of already_bet_base_matches containing MAP_WINNER placements.
     // Used to block only sibling map-winner bets on the same series.
     let mut already_bet_map_winners: HashSet<String> = HashSet::new();
-    // Load from file on startup
-    if Path::new(bet_history_path).exists() {
-        if let Ok(contents) = std::fs::read_to_string(bet_history_path) {
+    // Azuro game_id → condition_id prvního betu; blokuje druhou condition na stejné game.
+    let mut already_bet_game_ids: HashMap<String, String> = HashMap::new();
+    // Load from state store on startup
+    match state_store.load_bet_history() {
+        Ok(history) => {
             let now_utc = Utc::now();
-            let mut loaded_total: usize = 0;
+            let loaded_total = history.len();
             let mut loaded_fresh: usize = 0;
-            for line in contents.lines() {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() >= 2 {
-                    loaded_total += 1;
-                    let is_fresh = if parts.len() >= 5 {
-                        chrono::DateTime::parse_from_rfc3339(parts[4])
-                            .map(|ts| (now_utc - ts.with_timezone(&Utc)).num_hours() <= DEDUP_HISTORY_LOOKBACK_HOURS)
-                            .unwrap_or(false)
-                    } else {
-                        false
-                    };
-                    if !is_fresh {
-                        continue;
-                    }
-                    let history_market_key = parts.get(5).copied().unwrap_or("");
-                    already_bet_matches.insert(market_dedup_key(parts[0], history_market_key));
-                    // Extract base match key (strip ::mapN_winner suffix)
-                    let base_key = strip_map_winner_suffix(parts[0]);
-                    let is_map_winner_entry = is_map_winner_market(parts[0], history_market_key);
-                    already_bet_conditions.insert(scoped_condition_key(&base_key, parts[1]));
-                    if is_map_winner_entry {
-                        already_bet_map_winners.insert(base_key.clone());
-                    }
-                    already_bet_base_matches.insert(base_key);
-                    loaded_fresh += 1;
+            for entry in &history {
+                let is_fresh = chrono::DateTime::parse_from_rfc3339(&entry.placed_at)
+                    .map(|ts| (now_utc - ts.with_timezone(&Utc)).num_hours() <= DEDUP_HISTORY_LOOKBACK_HOURS)
+                    .unwrap_or(false);
+                if !is_fresh {
+                    continue;
+                }
+                already_bet_matches.insert(market_dedup_key(&entry.match_key, &entry.market_key));
+                // Extract base match key (strip ::mapN_winner suffix)
+                let base_key = strip_map_winner_suffix(&entry.match_key);
+                let is_map_winner_entry = is_map_winner_market(&entry.match_key, &entry.market_key);
+                already_bet_conditions.insert(scoped_condition_key(&base_key, &entry.condition_id));
+                if is_map_winner_entry {
+                    already_bet_map_winners.insert(base_key.clone());
                 }
+                record_bet_game_id(Some(entry.game_id.as_str()), &entry.condition_id, &mut already_bet_game_ids);
+                already_bet_base_matches.insert(base_key);
+                loaded_fresh += 1;
             }
             info!("📋 Loaded {} fresh dedup entries from history ({} total scanned, lookback={}h, {} base matches)",
                 loaded_fresh, loaded_total, DEDUP_HISTORY_LOOKBACK_HOURS, already_bet_base_matches.len());
         }
+        Err(e) => warn!("⚠️ dedup history load failed: {}", e),
     }
 
     // === PENDING CLAIMS: persist token IDs for bets waiting to be claimed ===
-    let pending_claims_path = "data/pending_claims.txt";
     // Format per line: tokenId|betId|matchKey|valueTeam|amountUsd|odds|team1|team2|marketKey|originalSport|resolvedSport|esportsFamily
     // Load on startup → add to active_bets for auto-claim monitoring
     let (ledger_meta_by_bet_id, ledger_meta_by_token_id) = load_ledger_bet_metadata();
-    if Path::new(pending_claims_path).exists() {
-        if let Ok(contents) = std::fs::read_to_string(pending_claims_path) {
-            let mut seen_bet_ids: HashSet<String> = HashSet::new();
-            let mut rewrite_pending_claims_on_startup = false;
-            for line in contents.lines() {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() >= 6 {
-                    let token_id_raw = parts[0].to_string();
-                    let bet_id = parts[1].to_string();
-                    // BUG #12 FIX: Skip duplicate bet_ids
-                    if seen_bet_ids.contains(&bet_id) {
-                        info!("⏭️ Skipping duplicate pending claim: betId={}", bet_id);
-                        continue;
-                    }
-                    seen_bet_ids.insert(bet_id.clone());
-                    let match_key = parts[2].to_string();
-                    let value_team = parts[3].to_string();
-                    let amount_usd: f64 = parts[4].parse().unwrap_or(2.0);
-                    let odds: f64 = parts[5].parse().unwrap_or(1.5);
-                    // Extended fields (v2 format): team1|team2|market_key
-                    let loaded_team1 = parts.get(6).unwrap_or(&"").to_string();
-                    let loaded_team2 = parts.get(7).unwrap_or(&"").to_string();
-                    let loaded_market = parts.get(8).unwrap_or(&"unknown").to_string();
-                    let loaded_original_sport = parts.get(9).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
-                    let loaded_resolved_sport = parts.get(10).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
-                    let loaded_esports_family = parts.get(11).filter(|v| !v.is_empty()).map(|v| (*v).to_string());
-                    // Fallback: derive teams from match_key if not stored
-                    let (fb_t1, fb_t2) = match_key_team_parts(&match_key)
-                        .unwrap_or_else(|| (value_team.clone(), "?".to_string()));
-                    let team1 = if !is_missing_display_field(&loaded_team1) { loaded_team1 } else { fb_t1 };
-                    let team2 = if !is_missing_display_field(&loaded_team2) { loaded_team2 } else { fb_t2 };
-                    let market_key = if !is_missing_display_field(&loaded_market) {
-                        loaded_market
-                    } else {
-                        market_from_match_key(&match_key)
-                    };
-                    if is_missing_display_field(parts.get(6).copied().unwrap_or(""))
-                        || is_missing_display_field(parts.get(7).copied().unwrap_or(""))
-                        || is_missing_display_field(parts.get(8).copied().unwrap_or(""))
-                        || parts.get(9).is_none()
-                        || parts.get(10).is_none()
-                        || parts.get(11).is_none()
-                    {
-                        rewrite_pending_claims_on_startup = true;
-                    }
-                    // "?" means tokenId not yet discovered — set to None so PATH B will discover it
-                    // Also treat tokenId < 1000 as bogus (false positive from recursive extraction)
-                    let token_id = if token_id_raw == "?" || token_id_raw.is_empty() {
-                        None
-                    } else if let Ok(tid_num) = token_id_raw.parse::<u64>() {
-                        if tid_num < 1000 {
-                            info!("⚠️ Bogus tokenId {} for bet {} — treating as undiscovered", token_id_raw, bet_id);
-                            None
-                        } else {
-                            Some(token_id_raw)
-                        }
-                    } else {
-                        Some(token_id_raw)
-                    };
-                    let mut restored_bet = ActiveBet {
-                        alert_id: 0,
-                        bet_id: bet_id.clone(),
-                        match_key: match_key.clone(),
-                        market_key,
-                        original_sport: loaded_original_sport,
-                        resolved_sport: loaded_resolved_sport.or_else(|| Some(match_key.split("::").next().unwrap_or("").to_string()).filter(|v| !v.is_empty())),
-                        esports_family: loaded_esports_family,
-                        team1,
-                        team2,
-                        value_team: value_team.clone(),
-                        amount_usd,
-                        odds,
-                        placed_at: "loaded".to_string(),
-                        condition_id: String::new(),
-                        outcome_id: String::new(),
-                        graph_bet_id: None,
-                        token_id,
-                        path: "loaded".to_string(),
-                    };
-                    if enrich_active_bet_from_ledger_metadata(
-                        &mut restored_bet,
-                        &ledger_meta_by_bet_id,
-                        &ledger_meta_by_token_id,
-                    ) {
-                        rewrite_pending_claims_on_startup = true;
-                    }
-                    active_bets.push(restored_bet);
-                }
-            }
-            if rewrite_pending_claims_on_startup {
-                rewrite_pending_claims_file(&active_bets, pending_claims_path);
-                info!("🧹 Rewrote pending_claims.txt on startup after normalizing stale team/market fields");
-            }
-            info!("📋 Loaded {} pending claims from file", active_bets.len());
-        }
+    active_bets.extend(load_pending_claims(
+        &*state_store,
+        &ledger_meta_by_bet_id,
+        &ledger_meta_by_token_id,
+    ));
+    if let Ok(exposure) = state_store.pending_exposure_usd() {
+        info!("📋 Pending claims exposure: ${:.2}", exposure);
     }
 
     // If no chat_id, wait for user to send /start (timeboxed; never block auto-bets forever)
@@ -8971,22 +14180,32 @@ async fn main() -> Result<()> {
         info!("Alert bot running WITHOUT Telegram. feed_hub={}, executor={}", feed_hub_url, executor_url);
     }
 
+    // PAPER_BANKROLL: bez živého executoru se sizing i exposure capy počítají z pevné virtuální částky
+    let mut paper_mode = PaperMode::new(paper_bankroll_from_env());
+
     // Check executor health at startup
     let executor_status = match client.get(format!("{}/health", executor_url)).send().await {
         Ok(resp) => {
             match resp.json::<ExecutorHealthResponse>().await {
                 Ok(h) => {
+                    paper_mode.observe(Some(&h));
                     let wallet = h.wallet.as_deref().unwrap_or("?");
                     let balance = h.balance.as_deref().unwrap_or("?");
                     let allowance = h.relayer_allowance.as_deref().unwrap_or("?");
-                    // Update bankroll from executor balance
-                    if let Ok(bal) = balance.parse::<f64>() {
+                    // Update bankroll from executor balance (paper režim balance ignoruje)
+                    if let (None, Ok(bal)) = (paper_mode.bankroll, balance.parse::<f64>()) {
                         current_bankroll = bal;
                         // Only set SOD from executor if NOT already loaded from daily_pnl.json
                         // (mid-day restart: file has the real SOD, executor has current depleted balance)
                         if !sod_loaded_from_file {
                             start_of_day_bankroll = bal;
                             info!("💰 Bankroll set from executor: ${:.2} (SOD locked)", current_bankroll);
+                        } else if sod_needs_reconstruction {
+                            // daily_wagered/returned jsou už zrekonciliované z ledgeru
+                            start_of_day_bankroll = reconstruct_sod_bankroll(bal, daily_wagered, daily_returned);
+                            let _ = state_store.save_daily_pnl(&DailyPnl::new(&daily_date, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
+                            info!("💰 SOD reconstructed: balance ${:.2} + wagered ${:.2} - returned ${:.2} = ${:.2} (persisted)",
+                                bal, daily_wagered, daily_returned, start_of_day_bankroll);
                         } else {
                             info!("💰 Bankroll from executor: ${:.2} (SOD kept from file: ${:.2})", bal, start_of_day_bankroll);
                         }
@@ -8998,6 +14217,29 @@ async fn main() -> Result<()> {
         }
         Err(_) => "❌ Executor OFFLINE — sázky nebudou fungovat!\n   Spusť: cd executor && node index.js".to_string(),
     };
+    if let Some(paper) = paper_mode.bankroll {
+        // Rekonstruovaný SOD potřebuje live balance — při přechodu na live se vezme balance
+        paper_mode.real_sod = (sod_loaded_from_file && !sod_needs_reconstruction).then_some(start_of_day_bankroll);
+        current_bankroll = paper;
+        start_of_day_bankroll = paper;
+        info!("📝 PAPER BANKROLL: ${:.2} (executor balance ignored)", paper);
+    }
+
+    // === PAPER → LIVE: executor naběhl (nebo přepnul z dry-run) až po startu ===
+    // Volá se před každým balance refreshem; bez něj by paper bankroll řídil reálné sázky celý běh.
+    macro_rules! paper_live_check {
+        () => {
+            if paper_mode.bankroll.is_some() {
+                let health = fetch_executor_health(&client, &executor_url).await;
+                if let Some((bal, sod)) = paper_mode.observe(health.as_ref()) {
+                    current_bankroll = bal;
+                    start_of_day_bankroll = sod;
+                    let _ = state_store.save_daily_pnl(&DailyPnl::new(&daily_date, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
+                    info!("📝 PAPER → LIVE: executor live, bankroll ${:.2}, SOD ${:.2} (persisted)", bal, sod);
+                }
+            }
+        };
+    }
 
     // Startup message
     let session_limit_str = "∞ (UNLIMITED)".to_string();
@@ -9071,8 +14313,8 @@ async fn main() -> Result<()> {
     let mut claimed_token_ids: HashSet<String> = HashSet::new();
     let mut claimed_tx_hashes: HashSet<String> = HashSet::new();
     // BUG FIX: Load ledger_settled_ids from ledger.jsonl on startup to prevent duplicate writes after restart
-    if Path::new("data/ledger.jsonl").exists() {
-        if let Ok(contents) = std::fs::read_to_string("data/ledger.jsonl") {
+    if Path::new(LEDGER_PATH).exists() {
+        if let Ok(contents) = std::fs::read_to_string(LEDGER_PATH) {
             for line in contents.lines() {
                 if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) {
                     let event = entry.get("event").and_then(|v| v.as_str()).unwrap_or("");
@@ -9081,6 +14323,11 @@ async fn main() -> Result<()> {
                             ledger_settled_ids.insert(bid.to_string());
                         }
                     }
+                    if event == "HEDGE_RECOMMENDED" {
+                        if let Some(bid) = entry.get("bet_id").and_then(|v| v.as_str()) {
+                            hedged_bets.insert(bid.to_string());
+                        }
+                    }
                     if event == "EXECUTOR_CLAIM" {
                         if let Some(tx) = entry.get("txHash").and_then(|v| v.as_str()) {
                             claimed_tx_hashes.insert(tx.to_string());
@@ -9099,6 +14346,7 @@ async fn main() -> Result<()> {
                 ledger_settled_ids.len());
             info!("📋 Loaded {} claimed token IDs and {} claim tx hashes from ledger",
                 claimed_token_ids.len(), claimed_tx_hashes.len());
+            info!("📋 Loaded {} already-hedged bet IDs from ledger (HEDGE_RECOMMENDED)", hedged_bets.len());
         }
 
         let recovery_stats = recover_unresolved_accepts_from_ledger(&mut active_bets, &ledger_settled_ids);
@@ -9209,7 +14457,7 @@ async fn main() -> Result<()> {
                         watchdog_refreshed_from_health = true;
                         if safe_mode {
                             safe_mode = false;
-                            let _ = tg_send_message(&client, &token, chat_id,
+                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                 "✅ Feed-hub telemetry čerstvá. Auto-bety obnoveny.").await;
                             log_event("SAFE_MODE_OFF", &serde_json::json!({
                                 "reason": "telemetry_fresh",
@@ -9223,6 +14471,7 @@ async fn main() -> Result<()> {
                         if let Some(last_good) = last_good_data {
                             if last_good.elapsed().as_secs() > WATCHDOG_TIMEOUT_SECS && !safe_mode {
                                 safe_mode = true;
+                                safe_mode_activations_today += 1;
                                 let elapsed = last_good.elapsed().as_secs();
                                 warn!(
                                     "⚠️ SAFE MODE: all feed signals stale for {}s > {}s threshold (gql={}ms shadow_ok={}ms shadow_event={}ms legacy_ws={}ms)",
@@ -9233,7 +14482,7 @@ async fn main() -> Result<()> {
                                     shadow_event_age_ms,
                                     legacy_ws_age_ms,
                                 );
-                                let _ = tg_send_message(&client, &token, chat_id,
+                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                     &format!(
                                         "⚠️ <b>SAFE MODE ACTIVATED</b>\n\nVšechny feed signály jsou staré {}s.\nGQL={} ms\nShadow OK={} ms\nShadow event={} ms\nLegacy WS={} ms\n\nAuto-bety POZASTAVENY. Alerty stále fungují.",
                                         elapsed,
@@ -9257,9 +14506,10 @@ async fn main() -> Result<()> {
                 } else if let Some(last_good) = last_good_data {
                     if last_good.elapsed().as_secs() > WATCHDOG_TIMEOUT_SECS && !safe_mode {
                         safe_mode = true;
+                        safe_mode_activations_today += 1;
                         let elapsed = last_good.elapsed().as_secs();
                         warn!("⚠️ SAFE MODE: feed-hub telemetry unavailable for {}s > {}s threshold", elapsed, WATCHDOG_TIMEOUT_SECS);
-                        let _ = tg_send_message(&client, &token, chat_id,
+                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                             &format!("⚠️ <b>SAFE MODE ACTIVATED</b>\n\nFeed-hub telemetry neodpovídá {}s.\nAuto-bety POZASTAVENY.\nAlerty stále fungují.\n\nZkontroluj feed-hub + Chrome tab + Tampermonkey.", elapsed)
                         ).await;
                         log_event("SAFE_MODE_ON", &serde_json::json!({"elapsed_secs": elapsed, "reason": "telemetry_unavailable"}));
@@ -9276,13 +14526,13 @@ async fn main() -> Result<()> {
                                 }
                                 if safe_mode && !watchdog_refreshed_from_health {
                                     safe_mode = false;
-                                    let _ = tg_send_message(&client, &token, chat_id,
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                         "✅ Feed-hub ONLINE. Auto-bety obnoveny.").await;
                                     log_event("SAFE_MODE_OFF", &serde_json::json!({"reason": "state_fallback"}));
                                 }
 
-                                // === DAILY DATE RESET (midnight UTC) ===
-                                let today_now = Utc::now().format("%Y-%m-%d").to_string();
+                                // === DAILY DATE RESET (hranice sázkového dne, default půlnoc UTC) ===
+                                let today_now = betting_day.today();
                                 if today_now != daily_date {
                                     log_event("DAILY_RESET", &serde_json::json!({
                                         "date": daily_date,
@@ -9292,6 +14542,24 @@ async fn main() -> Result<()> {
                                     }));
                                     info!("📅 New day {} — resetting daily P&L (yesterday net={:.2})",
                                         today_now, daily_returned - daily_wagered);
+                                    // === DAILY DIGEST (z ledgeru za končící den) ===
+                                    let digest = DailyDigestEvent::from_ledger_file_with(
+                                        ledger_path, &daily_date, safe_mode_activations_today,
+                                        |ts| betting_day.ts_in_day(ts, &daily_date),
+                                    );
+                                    if let Err(e) = EventLogger::new("data/daily_digest").log(&digest) {
+                                        warn!("📊 DAILY_DIGEST write failed: {}", e);
+                                    }
+                                    info!("📊 DAILY_DIGEST {}: placed={} W/L={}/{} net={:+.2} safe_mode={}",
+                                        digest.date, digest.bets_placed, digest.settled_won, digest.settled_lost,
+                                        digest.net_pnl_usd, digest.safe_mode_activations);
+                                    if digest_push.telegram {
+                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &digest.summary_text()).await;
+                                    }
+                                    if digest_push.ntfy {
+                                        send_ntfy_alert(&digest.summary_text(), &format!("Daily digest {}", digest.date)).await;
+                                    }
+                                    safe_mode_activations_today = 0;
                                     daily_wagered = 0.0;
                                     daily_returned = 0.0;
                                     daily_date = today_now;
@@ -9303,9 +14571,8 @@ async fn main() -> Result<()> {
                                     info!("📅 SOD bankroll locked: ${:.2}", start_of_day_bankroll);
                                     // Persist SOD bankroll for day-rollover
                                     {
-                                        let today = Utc::now().format("%Y-%m-%d").to_string();
-                                        let _ = std::fs::write("data/daily_pnl.json",
-                                            serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                        let today = betting_day.today();
+                                        let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                     }
                                     // === RESET EXPOSURE + REBET TRACKERS ===
                                     condition_exposure.clear();
@@ -9338,7 +14605,7 @@ async fn main() -> Result<()> {
                                                             "trigger": "dashboard"
                                                         }));
                                                         if chat_id > 0 {
-                                                            let _ = tg_send_message(&client, &token, chat_id,
+                                                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                                 &format!("⚡ <b>LIMIT via Dashboard</b>\n${:.0} → <b>${:.0}</b> | Room: ${:.2}",
                                                                     old, new_lim, room)).await;
                                                         }
@@ -9394,24 +14661,12 @@ async fn main() -> Result<()> {
                                             get_exposure_caps(start_of_day_bankroll).3 * 100.0,
                                             start_of_day_bankroll,
                                         );
-                                        let _ = tg_send_message(&client, &token, chat_id, &msg).await;
+                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await;
                                         daily_loss_alert_sent = true;
                                         daily_loss_last_reminder = Some(now_utc);
                                     }
                                 }
 
-                                // === CONDITION FRESHNESS: update last-seen for all active conditions ===
-                                let poll_instant = std::time::Instant::now();
-                                for item in &state.odds {
-                                    if let Some(cid) = &item.payload.condition_id {
-                                        if !cid.is_empty() {
-                                            condition_last_seen.insert(cid.clone(), poll_instant);
-                                        }
-                                    }
-                                }
-                                // GC: remove conditions not seen in 10 minutes
-                                condition_last_seen.retain(|_, ts| poll_instant.duration_since(*ts).as_secs() < 600);
-
                                 // === WS GATE: subscribe new condition IDs to WS stream ===
                                 if ws_state_gate_enabled {
                                     let new_cids: Vec<String> = state.odds.iter()
@@ -9424,18 +14679,55 @@ async fn main() -> Result<()> {
                                     }
                                 }
 
+                                // === DETECTION: condition freshness + score edges + cross-book anomalies (deduped) ===
+                                let signals = run_poll_cycle(
+                                    &state,
+                                    &mut score_tracker,
+                                    &mut resync_freeze,
+                                    &execution_venues,
+                                    &already_alerted,
+                                    &mut condition_last_seen,
+                                );
+
+                                // === SPORT KILL SWITCH: garbage skóre → auto-bety sportu vypnuté do /enable ===
+                                let kill_notices = score_tracker.sport_kill.take_notices();
+                                if !kill_notices.is_empty() {
+                                    if let Err(e) = state_store.save_disabled_sports(&score_tracker.sport_kill.disabled_list()) {
+                                        warn!("⚠️ Sport kill switch save failed: {}", e);
+                                    }
+                                }
+                                for (kill_sport, reason) in kill_notices {
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
+                                        &format!(
+                                            "🛑 <b>SPORT KILL SWITCH</b>\n\n\
+                                             Auto-bety pro <b>{}</b> vypnuty: {}\n\
+                                             Scraper nejspíš posílá garbage skóre (změna markupu?).\n\n\
+                                             Po kontrole zapni: <code>/enable {}</code>",
+                                            kill_sport, reason, kill_sport
+                                        )
+                                    ).await;
+                                    ledger_write("SPORT_KILL_SWITCH", &serde_json::json!({
+                                        "sport": kill_sport, "reason": reason, "action": "disabled"
+                                    }));
+                                }
+
+                                // === STATUS SNAPSHOT: read-only stav pro dashboard / monitor skripty ===
+                                let status = status_snapshot!();
+                                if let Err(e) = status.write_to(Path::new("data/bot_status.json")) {
+                                    debug!("bot_status snapshot write failed: {}", e);
+                                }
+
                                 // === 1. SCORE EDGE detection (primary strategy!) ===
-                                let score_edges = find_score_edges(&state, &mut score_tracker, &mut resync_freeze);
                                 let mut sent_score_edges = 0usize;
-                                for edge in &score_edges {
-                                    let alert_key = format!("score:{}:{}:{}-{}", edge.match_key, edge.leading_side, edge.score1, edge.score2);
-                                    if already_alerted.contains(&alert_key) {
-                                        continue;
-                                    }
+                                for edge in &signals.score_edges {
+                                    let alert_key = score_edge_alert_key(edge);
 
                                     alert_counter += 1;
                                     let aid = alert_counter;
 
+                                    // Jméno, kurz i outcome vedoucího týmu z jednoho místa — alert a bet se shodují
+                                    let leading = edge.leading();
+
                                     // Store as OddsAnomaly for YES/BET compatibility
                                     let anomaly = OddsAnomaly {
                                         detected_at: Utc::now(),
@@ -9449,6 +14741,8 @@ async fn main() -> Result<()> {
                                         azuro_url: edge.azuro_url.clone(),
                                         market_w1: 0.0,
                                         market_w2: 0.0,
+                                        azuro_draw: None,
+                                        market_draw: None,
                                         market_bookmaker: "score-edge".to_string(),
                                         value_side: edge.leading_side,
                                         discrepancy_pct: edge.edge_pct,
@@ -9470,12 +14764,14 @@ async fn main() -> Result<()> {
                                         condition_id: edge.condition_id.clone(),
                                         outcome1_id: edge.outcome1_id.clone(),
                                         outcome2_id: edge.outcome2_id.clone(),
-                                        outcome_id: edge.outcome_id.clone(),
+                                        outcome_draw_id: edge.outcome_draw_id.clone(),
+                                        outcome_order: edge.outcome_order,
+                                        outcome_id: leading.outcome_id.map(str::to_string),
                                         chain: edge.chain.clone(),
                                     };
 
-                                    let azuro_odds = if edge.leading_side == 1 { edge.azuro_w1 } else { edge.azuro_w2 };
-                                    let leading_team = if edge.leading_side == 1 { &edge.team1 } else { &edge.team2 };
+                                    let azuro_odds = leading.odds;
+                                    let leading_team = leading.team;
 
                                     // === AUTO-BET: place bet automatically on high-confidence edges ===
                                     let mut cond_id_str = anomaly.condition_id.as_deref().unwrap_or("").to_string();
@@ -9489,14 +14785,23 @@ async fn main() -> Result<()> {
 
                                     // Cross-market dedup only blocks sibling map-winner bets.
                                     // Exact market dedup and match exposure caps handle the rest.
+                                    // Bo1: match_winner a map1_winner jsou sourozenci — blokuj druhý trh.
                                     let is_candidate_map_winner = is_map_winner_market(&match_key_for_bet, &edge.market_key);
+                                    let is_bo1_sibling = edge.series_bo1 && !already_bet_matches.contains(&bet_market_dedup_key);
                                     let base_already_bet = cross_market_base_dedup_block(
                                         &base_match_key,
                                         is_candidate_map_winner,
+                                        is_bo1_sibling,
                                         &already_bet_base_matches,
                                         &already_bet_map_winners,
                                     );
 
+                                    let game_already_bet = game_id_dedup_block(
+                                        anomaly.game_id.as_deref(),
+                                        &cond_id_str,
+                                        &already_bet_game_ids,
+                                    );
+
                                     let mut scoped_cond_key = (!cond_id_str.is_empty())
                                         .then(|| scoped_condition_key(&base_match_key, &cond_id_str));
 
@@ -9506,6 +14811,10 @@ async fn main() -> Result<()> {
                                         info!("🛡️ MAP-WINNER DEDUP: {} blocked (base {} already has a map-winner bet)",
                                             match_key_for_bet, base_match_key);
                                         (true, false)
+                                    } else if game_already_bet {
+                                        info!("🛡️ GAME-ID DEDUP: {} blocked (game {} already has a bet on another condition)",
+                                            match_key_for_bet, anomaly.game_id.as_deref().unwrap_or("?"));
+                                        (true, false)
                                     } else if scoped_cond_key.as_ref().is_some_and(|key| already_bet_conditions.contains(key))
                                         || already_bet_matches.contains(&bet_market_dedup_key) {
                                         // Already bet → check if re-bet is allowed (only when FF enabled)
@@ -9519,7 +14828,7 @@ async fn main() -> Result<()> {
                                             let cond_cap_left = (current_bankroll * cond_frac - cond_exp_rb).max(0.0);
                                             let match_cap_left = (current_bankroll * match_frac - match_exp_rb).max(0.0);
                                             if let Some(rb_state) = scoped_cond_key.as_ref().and_then(|key| rebet_tracker.get(key)) {
-                                                rebet_allowed(rb_state, edge.confidence, edge.edge_pct, cond_cap_left, match_cap_left)
+                                                rebet_allowed(rb_state, score_tracker.clock.as_ref(), edge.confidence, edge.edge_pct, cond_cap_left, match_cap_left)
                                             } else { false }
                                         };
                                         if can_rebet {
@@ -9543,7 +14852,14 @@ async fn main() -> Result<()> {
                                         edge.resolved_sport.as_deref(),
                                         edge.esports_family,
                                     );
-                                    let (sport_auto_allowed, mut sport_min_edge, sport_multiplier, preferred_market) = get_sport_config(sport);
+                                    let (mut sport_auto_allowed, mut sport_min_edge, sport_multiplier, preferred_market) = get_sport_config(sport);
+                                    if sport_auto_allowed
+                                        && (score_tracker.sport_kill.is_disabled(sport) || score_tracker.sport_kill.is_disabled(sport_raw))
+                                    {
+                                        info!("🛑 SPORT KILL SWITCH: {} auto-bet blokován (sport {} vypnutý, /enable {})",
+                                            edge.match_key, sport, sport_raw);
+                                        sport_auto_allowed = false;
+                                    }
                                     let sport_live_enabled = sport_score_edge_live_enabled(sport);
                                     let sport_dry_run_enabled = sport_score_edge_dry_run_enabled(sport);
                                     // Football: dynamic edge threshold by minute
@@ -9599,6 +14915,17 @@ async fn main() -> Result<()> {
                                     } else {
                                         base_stake * sport_multiplier * score_stake_mult
                                     };
+                                    // Confidence sdíleného PredictionEngine škáluje stake před caps / trimmerem
+                                    let prediction_conf = if FF_CONFIDENCE_STAKE {
+                                        prediction_confidence_for_edge(&prediction_engine, edge, sport)
+                                    } else {
+                                        None
+                                    };
+                                    if let Some(conf) = prediction_conf {
+                                        info!("🎯 CONFIDENCE STAKE: {} conf={:.2} mult={:.2} raw=${:.2}",
+                                            edge.match_key, conf, confidence_stake_multiplier(conf), raw_stake);
+                                    }
+                                    let raw_stake = confidence_scaled_stake(raw_stake, prediction_conf);
                                     info!("📈 SCORE STAKE: {} edge={:.1}% sport={} odds={:.2} raw=${:.2}",
                                         edge.match_key, edge.edge_pct, sport, azuro_odds, raw_stake);
 
@@ -9622,9 +14949,16 @@ async fn main() -> Result<()> {
                                         );
                                         0.0
                                     } else {
-                                        trim_stake(raw_stake, current_bankroll, cond_exp, match_exp, daily_net_loss_for_cap,
+                                        let trimmed = trim_stake(raw_stake, current_bankroll, cond_exp, match_exp, daily_net_loss_for_cap,
                                             inflight_wagered_total, sport_exp, sport, cv_sm, start_of_day_bankroll, "score_edge", azuro_odds,
-                                            daily_limit_override.unwrap_or(DAILY_LOSS_LIMIT_USD))
+                                            daily_limit_override.unwrap_or(DAILY_LOSS_LIMIT_USD));
+                                        let finalized = stake_policy.finalize(trimmed, edge.chain.as_deref());
+                                        if trimmed > 0.0 && finalized == 0.0 {
+                                            info!("🪫 STAKE_BELOW_CHAIN_MIN: {} trimmed=${:.4} chain={} min=${:.2} | path=score_edge",
+                                                match_key_for_bet, trimmed, edge.chain.as_deref().unwrap_or("?"),
+                                                stake_policy.min_stake(edge.chain.as_deref()));
+                                        }
+                                        finalized
                                     };
                                     if stake < 0.50 && raw_stake >= 0.50 {
                                         info!("🛡️ EXPOSURE CAP: {} stake trimmed from ${:.2} to $0 (bank=${:.0} cond_exp=${:.2} match_exp=${:.2} daily_loss=${:.2})",
@@ -9645,7 +14979,7 @@ async fn main() -> Result<()> {
                                         } else {
                                             "blocked"
                                         };
-                                        append_ledger_audit_event("ESPORTS_PROMOTION_GATE_AUDIT", &serde_json::json!({
+                                        score_tracker.audit("ESPORTS_PROMOTION_GATE_AUDIT", &serde_json::json!({
                                             "match_key": edge.match_key,
                                             "market_key": edge.market_key,
                                             "decision": gate_decision,
@@ -9822,11 +15156,42 @@ async fn main() -> Result<()> {
                                         }
                                     };
 
-                                    let should_auto_bet = AUTO_BET_ENABLED
+                                    let league_decision = league_filter.decide(sport, live_league(&state, &edge.match_key));
+                                    let league_ok = league_decision == LeagueDecision::AutoBet;
+
+                                    // === FILL SIMULATION: edge po price impactu našeho stake proti likviditě poolu ===
+                                    let impact_ok = match anomaly.condition_id.as_deref()
+                                        .and_then(|cid| condition_liquidity_usd(&state, cid, &execution_venues))
+                                    {
+                                        Some(liquidity) if stake > 0.0 => {
+                                            let realized_odds = azuro_price_impact(azuro_odds, stake, liquidity);
+                                            let realized_edge = post_impact_edge_pct(edge.score_implied_pct, azuro_odds, stake, liquidity);
+                                            if realized_edge < MIN_SCORE_EDGE_PCT {
+                                                info!("💧 PRICE IMPACT: {} stake=${:.2} liquidity=${:.0} odds {:.3}→{:.3} edge {:.1}%→{:.1}% < {:.1}% — skipping auto-bet",
+                                                    edge.match_key, stake, liquidity, azuro_odds, realized_odds, edge.edge_pct, realized_edge, MIN_SCORE_EDGE_PCT);
+                                                false
+                                            } else {
+                                                true
+                                            }
+                                        }
+                                        _ => true,
+                                    };
+
+                                    // OUTCOME ORDER: outcome1/2_id musí prokazatelně patřit team1/team2, jinak sázíme naslepo
+                                    let outcome_order_ok = edge.outcome_order == OutcomeOrder::Verified;
+                                    if !outcome_order_ok && edge.confidence == "HIGH" {
+                                        info!("🛡️ OUTCOME ORDER: {} outcome ids {:?}/{:?} → {:?} — auto-bet blocked",
+                                            edge.match_key, edge.outcome1_id, edge.outcome2_id, edge.outcome_order);
+                                    }
+
+                                    // Všechny gates kromě live rollout flagu sportu — dry-run sport tu sázku "vsadí" jen papírově
+                                    let auto_bet_gates_ok = AUTO_BET_ENABLED
+                                        && league_ok
+                                        && impact_ok
+                                        && outcome_order_ok
                                         && dashboard_autobet_enabled
                                         && (dashboard_sport_focus.contains(&"all".to_string()) || dashboard_sport_focus.iter().any(|s| s == sport))
                                         && sport_auto_allowed
-                                        && sport_live_enabled
                                         && is_preferred_market
                                         && sport_guard_ok
                                         && within_daily_limit
@@ -9846,6 +15211,26 @@ async fn main() -> Result<()> {
                                         && bankroll_ok   // MIN_BANKROLL guard
                                         && pending_ok    // MAX_CONCURRENT_PENDING guard
                                         && streak_ok;    // LOSS_STREAK pause guard
+                                    let should_auto_bet = auto_bet_gates_ok && sport_live_enabled;
+
+                                    if paper_settlement_enabled && auto_bet_gates_ok && sport_dry_run_enabled && !sport_live_enabled {
+                                        let paper_line = PaperBetLine {
+                                            ts: Utc::now().to_rfc3339(),
+                                            source: "alert_bot_score_edge".to_string(),
+                                            sport: sport.to_string(),
+                                            home: edge.team1.clone(),
+                                            away: edge.team2.clone(),
+                                            side: leading_team.to_string(),
+                                            market_id: anomaly.condition_id.clone().unwrap_or_default(),
+                                            stake_usd: stake,
+                                            price: 1.0 / azuro_odds,
+                                        };
+                                        match append_paper_bet(&paper_bets_dir, &paper_line) {
+                                            Ok(()) => info!("📝 PAPER BET (dry-run {}): {} @ {:.2} ${:.2} ({})",
+                                                sport, leading_team, azuro_odds, stake, edge.match_key),
+                                            Err(e) => warn!("⚠️ paper bet append failed for {}: {}", edge.match_key, e),
+                                        }
+                                    }
 
                                     if !bankroll_ok && edge.confidence == "HIGH" {
                                         info!("🛑 MIN BANKROLL: ${:.2} < ${:.2} — skipping auto-bet", current_bankroll, MIN_BANKROLL_USD);
@@ -9865,6 +15250,10 @@ async fn main() -> Result<()> {
                                     if !sport_auto_allowed && edge.confidence == "HIGH" {
                                         info!("📢 {} ALERT ONLY (auto-bet disabled for {})", edge.match_key, sport);
                                     }
+                                    if !league_ok && edge.confidence == "HIGH" {
+                                        info!("🏟️ LEAGUE FILTER: {} ({}) league={:?} → {:?} — alert only",
+                                            edge.match_key, sport, live_league(&state, &edge.match_key), league_decision);
+                                    }
                                     if sport_dry_run_enabled && !sport_live_enabled && edge.confidence == "HIGH" {
                                         info!("🧪 SPORT DRY-RUN: {} ({}) passed candidate stage but live auto-bet flag is OFF", edge.match_key, sport);
                                     }
@@ -9896,7 +15285,7 @@ async fn main() -> Result<()> {
                                             edge.esports_reason,
                                         );
                                     }
-                                    let block_reason_codes = blocked_score_edge_reason_codes(
+                                    let mut block_reason_codes = blocked_score_edge_reason_codes(
                                         AUTO_BET_ENABLED,
                                         sport_auto_allowed,
                                         sport_live_enabled,
@@ -9922,6 +15311,17 @@ async fn main() -> Result<()> {
                                         pending_ok,
                                         streak_ok,
                                     );
+                                    match league_decision {
+                                        LeagueDecision::Denied => block_reason_codes.push("LeagueDenied"),
+                                        LeagueDecision::AlertOnly => block_reason_codes.push("LeagueNotAllowlisted"),
+                                        LeagueDecision::AutoBet => {}
+                                    }
+                                    if !impact_ok {
+                                        block_reason_codes.push("PriceImpact");
+                                    }
+                                    if !outcome_order_ok {
+                                        block_reason_codes.push("OutcomeOrderUnverified");
+                                    }
                                     let auditable_esports = should_audit_esports_score_decision(
                                         &edge.match_key,
                                         edge.esports_family,
@@ -10183,7 +15583,6 @@ async fn main() -> Result<()> {
                                         // Place the bet — with retry on "condition not active"
                                         let decision_instant = std::time::Instant::now();
                                         let decision_ts = Utc::now();
-                                        let amount_raw = (stake * 1e6) as u64;
 
                                         // Retry loop: Azuro pauses conditions during score events
                                         // (set/game point in tennis, goal in football). We retry twice
@@ -10193,7 +15592,7 @@ async fn main() -> Result<()> {
                                         let mut minodds_fallback_applied = false;
                                         let mut bet_success = false;
                                         loop {
-                                        let mut min_odds_factor = min_odds_factor_with_fallback(&match_key_for_bet, minodds_fallback_applied);
+                                        let mut min_odds_factor = slippage_guard.factor_with_fallback(&match_key_for_bet, minodds_fallback_applied);
                                         // HIGH EDGE OVERRIDE: when score-edge is >40% and GQL odds are stale,
                                         // accept any odds >= AUTO_BET_MIN_ODDS (1.70). The edge is so large that
                                         // even with massive slippage the bet remains +EV.
@@ -10206,12 +15605,9 @@ async fn main() -> Result<()> {
                                                 min_odds_factor = ev_floor;
                                             }
                                         }
-                                        let (min_odds, min_odds_display) = compute_min_odds_raw(azuro_odds, min_odds_factor);
-                                        let bet_body = serde_json::json!({
-                                            "conditionId": condition_id,
-                                            "outcomeId": outcome_id,
-                                            "amount": amount_raw.to_string(),
-                                            "minOdds": min_odds.to_string(),
+                                        let min_odds_display = min_odds(azuro_odds, min_odds_factor);
+                                        let order = OrderRequest::new(Venue::Azuro, None, &condition_id, &outcome_id, stake, min_odds_display, decision_ts.timestamp());
+                                        let bet_context = serde_json::json!({
                                             "requestedOdds": azuro_odds,
                                             "matchKey": match_key_for_bet,
                                             "originalSport": sport_raw,
@@ -10229,7 +15625,7 @@ async fn main() -> Result<()> {
                                                 inflight_conditions.remove(key);
                                             }
                                             inflight_conditions.remove(&bet_market_dedup_key);
-                                            let _ = tg_send_message(&client, &token, chat_id,
+                                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                 &format!(
                                                     "⏰ <b>AUTO-BET #{} TTL EXPIRED</b>\n\
                                                      🏷️ <b>{}</b> | path: <b>edge</b>\n\
@@ -10277,38 +15673,27 @@ async fn main() -> Result<()> {
                                         }
                                         let send_ts = Utc::now();
                                         let send_instant = std::time::Instant::now();
-                                        match client.post(format!("{}/bet", executor_url))
-                                            .json(&bet_body).send().await {
+                                        match send_executor_bet(&client, &executor_url, &order, bet_context, Duration::from_secs(EXECUTOR_BET_TIMEOUT_SECS)).await {
                                             Ok(resp) => {
                                                 let response_ts = Utc::now();
                                                 let rtt_ms = send_instant.elapsed().as_millis();
                                                 let pipeline_ms = decision_instant.elapsed().as_millis();
-                                                match resp.json::<ExecutorBetResponse>().await {
+                                                match read_executor_receipt(resp, &order).await {
                                                     Ok(br) => {
                                                         let is_rejected = br.state.as_deref()
                                                             .map(|s| s == "Rejected" || s == "Failed" || s == "Cancelled")
                                                             .unwrap_or(false);
                                                         if let Some(err) = &br.error {
-                                                            // Check if retryable (condition paused/not active)
-                                                            let err_lower = err.to_lowercase();
-                                                            let is_condition_paused = err_lower.contains("not active")
-                                                                || err_lower.contains("paused")
-                                                                || err_lower.contains("not exist");
-                                                            // Permanent dead condition (map3 finished, live unavailable, etc.)
-                                                            // These should NEVER be retried but MUST be blacklisted immediately.
-                                                            let is_condition_dead = err_lower.contains("not available")
-                                                                || err_lower.contains("live is not");
-                                                            let is_fatal = err_lower.contains("insufficient")
-                                                                || err_lower.contains("allowance")
-                                                                || err_lower.contains("revert")
-                                                                || err_lower.contains("nonce");
-                                                            if is_condition_paused && !is_condition_dead && !is_fatal && attempt < max_retries {
+                                                            // Retry jen u přechodných stavů (condition paused); dead/fatal končí hned
+                                                            let reject_class = ExecutorRejectClass::classify(Some(err), br.state.as_deref());
+                                                            if reject_class.is_retryable() && attempt < max_retries {
                                                                 if let Some((new_condition_id, new_outcome_id)) = remap_execution_ids_from_state(
                                                                     &state,
                                                                     &match_key_for_bet,
                                                                     &edge.team1,
                                                                     &edge.team2,
                                                                     edge.leading_side,
+                                                                    &execution_venues,
                                                                 ) {
                                                                     if new_condition_id != condition_id || new_outcome_id != outcome_id {
                                                                         info!("🔁 AUTO-BET #{} remap retry: cond {}→{} out {}→{}",
@@ -10348,9 +15733,7 @@ async fn main() -> Result<()> {
                                                                     continue; // retry the loop
                                                                 }
                                                             }
-                                                            // Classify error for noise reduction
-                                                            let is_minodds_reject = err_lower.contains("min odds") || err_lower.contains("minodds")
-                                                                || err_lower.contains("real odds");
+                                                            let is_minodds_reject = reject_class == ExecutorRejectClass::MinOdds;
                                                             if is_minodds_reject && !minodds_fallback_applied && attempt < max_retries {
                                                                 attempt += 1;
                                                                 minodds_fallback_applied = true;
@@ -10362,13 +15745,9 @@ async fn main() -> Result<()> {
                                                                 tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
                                                                 continue;
                                                             }
-                                                            let is_dedup = err_lower.contains("dedup") || err_lower.contains("already bet");
-                                                            let is_condition_state_reject = is_condition_paused || is_condition_dead;
-                                                            let reason_code = if is_condition_state_reject { "ConditionNotRunning" }
-                                                                else if is_minodds_reject { "MinOddsReject" }
-                                                                else if is_dedup { "Dedup" }
-                                                                else if is_fatal { "Fatal" }
-                                                                else { "Unknown" };
+                                                            let is_dedup = reject_class == ExecutorRejectClass::Dedup;
+                                                            let is_condition_state_reject = reject_class.is_condition_state();
+                                                            let reason_code = reject_class.reason_code();
                                                             // CONDITION BLACKLIST: add failed condition to blacklist
                                                             if is_condition_state_reject || is_minodds_reject {
                                                                 blacklisted_conditions.insert(
@@ -10423,7 +15802,7 @@ async fn main() -> Result<()> {
                                                             }
                                                             // Don't spam TG for dedup (409) — it's normal operational behavior
                                                             if !is_dedup {
-                                                                let _ = tg_send_message(&client, &token, chat_id,
+                                                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                                     &format_auto_bet_failed_message(
                                                                         aid,
                                                                         "edge",
@@ -10452,7 +15831,7 @@ async fn main() -> Result<()> {
                                                                 aid, br.state.as_deref().unwrap_or("?"),
                                                                 &condition_id,
                                                                 match_key_for_bet);
-                                                            let _ = tg_send_message(&client, &token, chat_id,
+                                                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                                 &format_auto_bet_rejected_message(
                                                                     aid,
                                                                     "edge",
@@ -10473,22 +15852,22 @@ async fn main() -> Result<()> {
                                                             }
                                                             inflight_conditions.remove(&bet_market_dedup_key);
                                                             break; // exit retry loop
+                                                        } else if replay_already_booked(&br, &active_bets) {
+                                                            info!("♻️ AUTO-BET #{} replay of booked bet {} — not recording again", aid, br.bet_id.as_deref().unwrap_or("?"));
+                                                            if let Some(key) = scoped_cond_key.as_ref() {
+                                                                inflight_conditions.remove(key);
+                                                            }
+                                                            inflight_conditions.remove(&bet_market_dedup_key);
+                                                            break;
                                                         } else {
                                                             auto_bet_count += 1;
                                                             daily_wagered += stake;
-                                                            // Persist daily P&L
-                                                            {
-                                                                let today = Utc::now().format("%Y-%m-%d").to_string();
-                                                                let _ = std::fs::write(bet_count_path, format!("{}|{}", today, auto_bet_count));
-                                                                let _ = std::fs::write("data/daily_pnl.json",
-                                                                    serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
-                                                            }
                                                             let bet_id = br.bet_id.as_deref().unwrap_or("?");
                                                             let bet_state = br.state.as_deref().unwrap_or("?");
                                                             let token_id_opt = sanitize_token_id(br.token_id.clone());
                                                             let graph_bet_id_opt = br.graph_bet_id.clone();
                                                             let is_dry_run = bet_state == "DRY-RUN" || bet_id.starts_with("dry-");
-                                                            let mut accepted_odds = br.accepted_odds.unwrap_or(azuro_odds);
+                                                            let mut accepted_odds = br.placed_odds.unwrap_or(azuro_odds);
                                                             if bet_state == "Accepted" && !is_dry_run {
                                                                 if let Ok(status_resp) = client
                                                                     .get(format!("{}/bet/{}", executor_url, bet_id))
@@ -10539,17 +15918,15 @@ async fn main() -> Result<()> {
                                                             }
                                                             // BUG #1 FIX: Also record base match key
                                                             already_bet_base_matches.insert(base_match_key.clone());
+                                                            record_bet_game_id(anomaly.game_id.as_deref(), &cond_id_str, &mut already_bet_game_ids);
                                                             // Track map_winner placements for sibling-map dedup logic
                                                                 if is_map_winner_market(&match_key_for_bet, &edge.market_key) {
                                                                 already_bet_map_winners.insert(base_match_key.clone());
                                                             }
 
                                                             // === EXPOSURE TRACKING: update condition + match + sport + inflight ===
-                                                            if let Some(key) = scoped_cond_key.as_ref() {
-                                                                *condition_exposure.entry(key.clone()).or_insert(0.0) += stake;
-                                                            }
-                                                            *match_exposure.entry(base_match_key.clone()).or_insert(0.0) += stake;
-                                                            *sport_exposure.entry(sport.to_string()).or_insert(0.0) += stake;
+                                                            record_placement_exposure(&mut condition_exposure, &mut match_exposure, &mut sport_exposure,
+                                                                scoped_cond_key.as_ref(), &base_match_key, sport, stake);
                                                             inflight_wagered_total += stake;
 
                                                             // === RE-BET TRACKING: update or create state ===
@@ -10557,13 +15934,13 @@ async fn main() -> Result<()> {
                                                                 rb.bet_count += 1;
                                                                 rb.highest_tier = edge.confidence.to_string();
                                                                 rb.last_edge_pct = edge.edge_pct;
-                                                                rb.last_bet_at = Utc::now();
+                                                                rb.last_bet_at = score_tracker.clock.now_utc();
                                                                 rb.total_wagered += stake;
                                                                 info!("🔄 RE-BET #{}: {} total bets on cond={}, total wagered=${:.2}",
                                                                     rb.bet_count, match_key_for_bet, cond_id_str, rb.total_wagered);
                                                             } else {
                                                                 rebet_tracker.insert(scoped_condition_key(&base_match_key, &cond_id_str),
-                                                                    ReBetState::new(edge.confidence, edge.edge_pct, stake));
+                                                                    ReBetState::new(edge.confidence, edge.edge_pct, stake, score_tracker.clock.as_ref()));
                                                             }
 
                                                             // Remove from inflight (bet is now in persistent dedup)
@@ -10571,14 +15948,13 @@ async fn main() -> Result<()> {
                                                                 inflight_conditions.remove(key);
                                                             }
                                                             inflight_conditions.remove(&bet_market_dedup_key);
-                                                            // Persist to file
-                                                            if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                .create(true).append(true)
-                                                                .open(bet_history_path) {
-                                                                use std::io::Write;
-                                                                let _ = writeln!(f, "{}|{}|{}|{}|{}|{}",
-                                                                    match_key_for_bet, cond_id_str,
-                                                                    leading_team, accepted_odds, Utc::now().to_rfc3339(), edge.market_key);
+                                                            // Persist bet count + daily P&L + dedup entry in one transaction
+                                                            if let Err(e) = state_store.record_placement(
+                                                                auto_bet_count,
+                                                                &DailyPnl::new(&betting_day.today(), daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override),
+                                                                &BetHistoryEntry::new(&match_key_for_bet, &cond_id_str, leading_team, accepted_odds, &edge.market_key, anomaly.game_id.as_deref()),
+                                                            ) {
+                                                                warn!("⚠️ BET #{} state persist failed: {}", aid, e);
                                                             }
 
                                                             if !is_dry_run {
@@ -10603,7 +15979,7 @@ async fn main() -> Result<()> {
                                                                     path: "score_edge".to_string(),
                                                                 };
                                                                 active_bets.push(active_bet.clone());
-                                                                append_pending_claim_entry(&active_bet, pending_claims_path);
+                                                                append_pending_claim_entry(&active_bet, &*state_store);
                                                                 // === LEDGER: BET PLACED ===
                                                                 ledger_write("PLACED", &serde_json::json!({
                                                                     "alert_id": aid, "bet_id": bet_id,
@@ -10695,7 +16071,7 @@ async fn main() -> Result<()> {
                                                                 auto_bet_count,
                                                                 is_dry_run,
                                                             );
-                                                            if let Err(e) = tg_send_message(&client, &token, chat_id, &result_msg).await {
+                                                            if let Err(e) = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &result_msg).await {
                                                                 error!("Failed to send auto-bet result alert: {}", e);
                                                             } else {
                                                                 score_alert_sent = true;
@@ -10710,6 +16086,7 @@ async fn main() -> Result<()> {
                                                                 let follow_aid = aid;
                                                                 let follow_team = leading_team.to_string();
                                                                 let follow_chat = chat_id;
+                                                                let follow_broadcast = broadcast_chat_ids.clone();
                                                                 let follow_match_key = match_key_for_bet.clone();
                                                                 let follow_market_key = edge.market_key.clone();
                                                                 let follow_condition_id = condition_id.clone();
@@ -10734,9 +16111,9 @@ async fn main() -> Result<()> {
                                                                                      🧠 on-chain state: <b>{}</b>\n\
                                                                                      📝 {}",
                                                                                     follow_aid, follow_team, final_state, err_msg);
-                                                                                let _ = tg_send_message(
+                                                                                let _ = tg_send_alert(
                                                                                     &follow_client, &follow_token,
-                                                                                    follow_chat, &alert).await;
+                                                                                    follow_chat, &follow_broadcast, &alert).await;
                                                                                 warn!("❌ AUTO-BET #{} FOLLOW-UP REJECTED: {} err={}",
                                                                                     follow_aid, follow_bet_id, err_msg);
                                                                                 // LEDGER: write on-chain REJECTED event
@@ -10757,7 +16134,7 @@ async fn main() -> Result<()> {
                                                                                         "stake": follow_stake,
                                                                                     });
                                                                                     if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                                        .create(true).append(true).open("data/ledger.jsonl") {
+                                                                                        .create(true).append(true).open(LEDGER_PATH) {
                                                                                         use std::io::Write;
                                                                                         let _ = writeln!(f, "{}", entry);
                                                                                     }
@@ -10784,10 +16161,10 @@ async fn main() -> Result<()> {
                                                                                     token_id,
                                                                                     drift_line,
                                                                                 );
-                                                                                let _ = tg_send_message(
+                                                                                let _ = tg_send_alert(
                                                                                     &follow_client,
                                                                                     &follow_token,
-                                                                                    follow_chat,
+                                                                                    follow_chat, &follow_broadcast,
                                                                                     &alert,
                                                                                 ).await;
                                                                                 info!("✅ AUTO-BET #{} FOLLOW-UP CONFIRMED: state={} tokenId={}",
@@ -10812,7 +16189,7 @@ async fn main() -> Result<()> {
                                                                                         "path": "edge",
                                                                                     });
                                                                                     if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                                        .create(true).append(true).open("data/ledger.jsonl") {
+                                                                                        .create(true).append(true).open(LEDGER_PATH) {
                                                                                         use std::io::Write;
                                                                                         let _ = writeln!(f, "{}", entry);
                                                                                         if let Some((delta_abs, delta_pct)) = significant_odds_drift(follow_odds, accepted_odds) {
@@ -10852,7 +16229,7 @@ async fn main() -> Result<()> {
                                                         // Remove from inflight on parse error too
                                                         inflight_conditions.remove(&cond_id_str);
                                                         inflight_conditions.remove(&bet_market_dedup_key);
-                                                        let _ = tg_send_message(&client, &token, chat_id,
+                                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                             &format!(
                                                                 "❌ <b>AUTO-BET #{} RESPONSE ERROR</b>\n\
                                                                  path: <b>edge</b>\n\
@@ -10870,7 +16247,7 @@ async fn main() -> Result<()> {
                                                 // Remove from inflight on executor error
                                                 inflight_conditions.remove(&cond_id_str);
                                                 inflight_conditions.remove(&bet_market_dedup_key);
-                                                let _ = tg_send_message(&client, &token, chat_id,
+                                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                     &format!(
                                                         "❌ <b>AUTO-BET #{} EXECUTOR OFFLINE</b>\n\
                                                          path: <b>edge</b>\n\
@@ -10911,7 +16288,7 @@ async fn main() -> Result<()> {
                                         } else {
                                             let msg = format_score_edge_alert(edge, aid);
                                             telegram_attempted = true;
-                                            match tg_send_message(&client, &token, chat_id, &msg).await {
+                                            match tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await {
                                                 Ok(msg_id) => {
                                                     score_alert_sent = true;
                                                     if msg_id > 0 {
@@ -10952,29 +16329,214 @@ async fn main() -> Result<()> {
                                     }
                                 }
 
-                                // === 2. Cross-book odds anomaly (secondary strategy) ===
-                                let anomalies = find_odds_anomalies(&state);
-                                let mut actually_sent = sent_score_edges;
-                                let total_anomalies = anomalies.len();
-                                for anomaly in anomalies {
-                                    let alert_key = format!("{}:{}:{}", anomaly.match_key, anomaly.value_side, anomaly.azuro_bookmaker);
+                                // === 1b. INTRA-MATCH ARB (mapN_winner vs match_winner na rozhodující mapě) ===
+                                let mut intra_groups: HashMap<String, Vec<StateOddsItem>> = HashMap::new();
+                                for item in &state.odds {
+                                    if execution_venues.is_venue(&item.payload.bookmaker)
+                                        && is_moneyline_market(item.payload.market.as_deref())
+                                    {
+                                        intra_groups.entry(strip_map_winner_suffix(&item.match_key))
+                                            .or_default()
+                                            .push(item.clone());
+                                    }
+                                }
+                                for (base_key, items) in &intra_groups {
+                                    let Some(arb) = find_intra_match_arb(items, &execution_venues) else {
+                                        continue;
+                                    };
+                                    let live = state.live.iter().find(|l| {
+                                        l.match_key == *base_key
+                                            || teams_match_loose(&l.payload.team1, &l.payload.team2, &arb.team1, &arb.team2)
+                                    });
+                                    let decisive = live.is_some_and(|l| {
+                                        let detailed = l.payload.detailed_score.as_deref();
+                                        intra_arb_map_is_decisive(
+                                            arb.map_index,
+                                            parse_series_best_of(l.payload.series_format.as_deref(), detailed),
+                                            detailed.and_then(parse_cs2_current_map),
+                                        )
+                                    });
+                                    if !decisive {
+                                        debug!("INTRA-ARB skip (map{} not decisive): {} sum={:.4}",
+                                            arb.map_index, base_key, arb.implied_sum);
+                                        continue;
+                                    }
+                                    let alert_key = format!("intra:{}:{}:{}", base_key, arb.map_leg.market, arb.map_leg.side);
                                     if already_alerted.contains(&alert_key) {
                                         continue;
                                     }
 
                                     alert_counter += 1;
                                     let aid = alert_counter;
+                                    info!("🔀 INTRA-ARB #{}: {} {} {} @ {:.2} + match {} @ {:.2} sum={:.4} profit={:.2}%",
+                                        aid, arb.match_key, arb.map_leg.market, arb.map_leg.team, arb.map_leg.odds,
+                                        arb.match_leg.team, arb.match_leg.odds, arb.implied_sum, arb.profit_pct);
+                                    let msg = format!(
+                                        "🔀 <b>#{} INTRA-MATCH ARB</b>\n\
+                                         🧩 <b>{}</b>\n\
+                                         🗺️ {}: <b>{}</b> @ {:.2} ({:.0}% stake)\n\
+                                         🏆 match_winner: <b>{}</b> @ {:.2} ({:.0}% stake)\n\
+                                         Σ implied: {:.2}% | profit po fees: <b>{:.2}%</b>",
+                                        aid, arb.match_key,
+                                        arb.map_leg.market, arb.map_leg.team, arb.map_leg.odds, arb.map_leg.stake_frac * 100.0,
+                                        arb.match_leg.team, arb.match_leg.odds, arb.match_leg.stake_frac * 100.0,
+                                        arb.implied_sum * 100.0, arb.profit_pct,
+                                    );
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await;
+                                    sent_alerts.push(SentAlert {
+                                        match_key: alert_key,
+                                        sent_at: Utc::now(),
+                                    });
 
-                                    let value_team = if anomaly.value_side == 1 {
-                                        anomaly.team1.clone()
-                                    } else {
-                                        anomaly.team2.clone()
+                                    let legs_executable = [&arb.map_leg, &arb.match_leg].iter().all(|leg| {
+                                        leg.condition_id.as_deref().is_some_and(|c| !c.is_empty())
+                                            && leg.outcome_id.as_deref().is_some_and(|o| !o.is_empty())
+                                    });
+                                    let should_auto_bet_intra = AUTO_BET_ENABLED
+                                        && FF_INTRA_ARB_AUTO_BET
+                                        && dashboard_autobet_enabled
+                                        && !safe_mode
+                                        && legs_executable
+                                        && daily_net_loss < effective_daily_limit
+                                        && current_bankroll >= MIN_BANKROLL_USD
+                                        && !already_bet_base_matches.contains(base_key);
+                                    if !should_auto_bet_intra {
+                                        continue;
+                                    }
+                                    let Some((map_stake, match_stake)) = intra_arb_leg_stakes(&arb) else {
+                                        info!("⏭️ INTRA-ARB #{} skipped: menší noha by na ${:.2} potřebovala dutch nad ${:.2}",
+                                            aid, MIN_EXECUTABLE_STAKE_USD, INTRA_ARB_MAX_STAKE_USD);
+                                        continue;
                                     };
-                                    let azuro_odds = if anomaly.value_side == 1 {
-                                        anomaly.azuro_w1
-                                    } else {
-                                        anomaly.azuro_w2
+
+                                    // Obě nohy za přesné kurzy (minOdds factor 1.0) — slippage by arb sežral.
+                                    // Selže druhá noha → do plánu přibude protisázka, která první nohu uzavře.
+                                    let mut plan = vec![(arb.map_leg.clone(), map_stake), (arb.match_leg.clone(), match_stake)];
+                                    let mut placed_legs = 0usize;
+                                    let mut open_leg: Option<(IntraArbLeg, f64, f64)> = None;
+                                    let mut closed_out: Option<bool> = None;
+                                    let mut next_leg = 0usize;
+                                    while let Some((leg, stake)) = plan.get(next_leg).cloned() {
+                                        let is_close_out = next_leg >= 2;
+                                        next_leg += 1;
+                                        let path = if is_close_out { "intra_arb_close" } else { "intra_arb" };
+                                        let condition_id = leg.condition_id.clone().unwrap_or_default();
+                                        let outcome_id = leg.outcome_id.clone().unwrap_or_default();
+                                        let leg_match_key = if leg.market == "match_winner" {
+                                            base_key.clone()
+                                        } else {
+                                            format!("{}::{}", base_key, leg.market)
+                                        };
+                                        // Uzavření má přednost před cenou → běžná tolerance slippage
+                                        let min_odds = min_odds(leg.odds, if is_close_out { MIN_ODDS_FACTOR_DEFAULT } else { 1.0 });
+                                        let order = OrderRequest::new(Venue::Azuro, None, &condition_id, &outcome_id, stake, min_odds, Utc::now().timestamp());
+                                        let bet_context = serde_json::json!({
+                                            "requestedOdds": leg.odds,
+                                            "matchKey": leg_match_key,
+                                            "team1": arb.team1,
+                                            "team2": arb.team2,
+                                            "valueTeam": leg.team,
+                                        });
+                                        let result = match send_executor_bet(&client, &executor_url, &order, bet_context, Duration::from_secs(EXECUTOR_BET_TIMEOUT_SECS)).await {
+                                            Ok(resp) => read_executor_receipt(resp, &order).await.map_err(|e| e.to_string()),
+                                            Err(e) => Err(e.to_string()),
+                                        };
+                                        let br = match result {
+                                            Ok(br) if br.error.is_none()
+                                                && !matches!(br.state.as_deref(), Some("Rejected" | "Failed" | "Cancelled")) => br,
+                                            failed => {
+                                                let err = match failed {
+                                                    Ok(br) => br.error.unwrap_or_else(|| br.state.unwrap_or_default()),
+                                                    Err(e) => e,
+                                                };
+                                                warn!("❌ INTRA-ARB #{} leg {} ({}) failed: {}", aid, leg.market, path, err);
+                                                if is_close_out {
+                                                    closed_out = Some(false);
+                                                    break;
+                                                }
+                                                // Selhala první noha → nic nevisí. Druhá → protisázka na opačný outcome trhu první nohy
+                                                let Some((open, open_stake, open_odds)) = open_leg.take() else { break };
+                                                match open.close_out(&arb).zip(intra_arb_close_out_stake(open_stake, open_odds, open.opposite_odds)) {
+                                                    Some((close_leg, close_stake)) => plan.push((close_leg, close_stake)),
+                                                    None => closed_out = Some(false),
+                                                }
+                                                continue;
+                                            }
+                                        };
+
+                                        if is_close_out {
+                                            closed_out = Some(true);
+                                        } else {
+                                            placed_legs += 1;
+                                        }
+                                        let accepted_odds = br.placed_odds.unwrap_or(leg.odds);
+                                        if !is_close_out && placed_legs == 1 {
+                                            open_leg = Some((leg.clone(), stake, accepted_odds));
+                                        }
+                                        if replay_already_booked(&br, &active_bets) {
+                                            info!("♻️ INTRA-ARB #{} leg {} replay of booked bet — not recording again", aid, leg.market);
+                                            continue;
+                                        }
+                                        daily_wagered += stake;
+                                        let bet_id = br.bet_id.clone().unwrap_or_else(|| "?".to_string());
+                                        let active_bet = ActiveBet {
+                                            alert_id: aid,
+                                            bet_id: bet_id.clone(),
+                                            match_key: leg_match_key.clone(),
+                                            market_key: leg.market.clone(),
+                                            original_sport: None,
+                                            resolved_sport: None,
+                                            esports_family: None,
+                                            team1: arb.team1.clone(),
+                                            team2: arb.team2.clone(),
+                                            value_team: leg.team.clone(),
+                                            amount_usd: stake,
+                                            odds: accepted_odds,
+                                            placed_at: Utc::now().to_rfc3339(),
+                                            condition_id: condition_id.clone(),
+                                            outcome_id: outcome_id.clone(),
+                                            graph_bet_id: br.graph_bet_id.clone(),
+                                            token_id: sanitize_token_id(br.token_id.clone()),
+                                            path: path.to_string(),
+                                        };
+                                        active_bets.push(active_bet.clone());
+                                        append_pending_claim_entry(&active_bet, &*state_store);
+                                        ledger_write("PLACED", &serde_json::json!({
+                                            "alert_id": aid, "bet_id": bet_id,
+                                            "match_key": leg_match_key,
+                                            "match_prefix": match_prefix_from_match_key(base_key),
+                                            "market_key": leg.market,
+                                            "value_team": leg.team,
+                                            "amount_usd": stake, "odds": accepted_odds,
+                                            "requested_odds": leg.odds,
+                                            "condition_id": condition_id,
+                                            "outcome_id": outcome_id,
+                                            "path": path,
+                                            "implied_sum": arb.implied_sum,
+                                            "profit_pct": arb.profit_pct,
+                                        }));
+                                    }
+                                    already_bet_base_matches.insert(base_key.clone());
+                                    let _ = state_store.save_daily_pnl(&DailyPnl::new(&betting_day.today(), daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
+                                    let leg_msg = match (placed_legs, closed_out) {
+                                        (2, _) => format!("🤖 <b>INTRA-ARB #{}</b>: obě nohy vsazeny", aid),
+                                        (0, _) => format!("❌ <b>INTRA-ARB #{}</b>: první noha neprošla, nic nevsazeno", aid),
+                                        (_, Some(true)) => format!("🛡️ <b>INTRA-ARB #{}</b>: druhá noha selhala — první uzavřena protisázkou", aid),
+                                        _ => format!("⚠️ <b>INTRA-ARB #{}</b>: vsazeno {}/2 nohou, uzavření selhalo — otevřená pozice, zkontroluj ručně", aid, placed_legs),
                                     };
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &leg_msg).await;
+                                }
+
+                                // === 2. Cross-book odds anomaly (secondary strategy) ===
+                                let mut actually_sent = sent_score_edges;
+                                for anomaly in signals.anomalies {
+                                    let alert_key = anomaly_alert_key(&anomaly);
+
+                                    alert_counter += 1;
+                                    let aid = alert_counter;
+
+                                    let (value_team, azuro_odds, _) = anomaly_side_pick(&anomaly, anomaly.value_side);
+                                    let value_team = value_team.to_string();
 
                                     let market_source_count = anomaly.market_bookmaker
                                         .split('+')
@@ -10991,20 +16553,33 @@ async fn main() -> Result<()> {
                                         || inflight_conditions.contains(&bet_market_dedup_key);
                                     // Cross-market dedup only blocks sibling map-winner bets.
                                     let is_candidate_map_winner_anom = is_map_winner_market(&match_key_for_bet, &anomaly.market_key);
+                                    let is_bo1_sibling_anom = is_bo1_series(None, anomaly.detailed_score.as_deref())
+                                        && !already_bet_matches.contains(&bet_market_dedup_key);
                                     let base_already_bet_anom = cross_market_base_dedup_block(
                                         &base_match_key,
                                         is_candidate_map_winner_anom,
+                                        is_bo1_sibling_anom,
                                         &already_bet_base_matches,
                                         &already_bet_map_winners,
                                     );
+                                    let game_already_bet_anom = game_id_dedup_block(
+                                        anomaly.game_id.as_deref(),
+                                        &cond_id_str,
+                                        &already_bet_game_ids,
+                                    );
                                     let already_bet_this = is_inflight_anom
                                         || base_already_bet_anom
+                                        || game_already_bet_anom
                                         || scoped_cond_key.as_ref().is_some_and(|key| already_bet_conditions.contains(key))
                                         || already_bet_matches.contains(&bet_market_dedup_key);
                                     if base_already_bet_anom {
                                         info!("🛡️ MAP-WINNER DEDUP (anomaly): {} blocked (base {} already has a map-winner bet)",
                                             match_key_for_bet, base_match_key);
                                     }
+                                    if game_already_bet_anom {
+                                        info!("🛡️ GAME-ID DEDUP (anomaly): {} blocked (game {} already has a bet on another condition)",
+                                            match_key_for_bet, anomaly.game_id.as_deref().unwrap_or("?"));
+                                    }
 
                                     // ENABLED: Odds anomaly auto-bet (ONLY for LIVE matches)
                                     // Prefer confirmation from multiple market sources.
@@ -11057,7 +16632,7 @@ async fn main() -> Result<()> {
                                                     let minute = football_minute_from_context(None, anomaly.detailed_score.as_deref());
                                                     football_score_to_win_prob(a_leading, a_losing, minute)
                                                 }
-                                                "tennis" => tennis_score_to_win_prob(a_leading, a_losing),
+                                                "tennis" => tennis_score_to_win_prob(a_leading, a_losing, tennis_best_of(None, Some(a_detail))),
                                                 "basketball" => basketball_score_to_win_prob(a_leading, a_losing),
                                                 _ => None,
                                             }
@@ -11107,9 +16682,16 @@ async fn main() -> Result<()> {
                                         );
                                         0.0
                                     } else {
-                                        trim_stake(anomaly_raw_stake, current_bankroll, anomaly_cond_exp, anomaly_match_exp, anomaly_daily_loss,
+                                        let trimmed = trim_stake(anomaly_raw_stake, current_bankroll, anomaly_cond_exp, anomaly_match_exp, anomaly_daily_loss,
                                             inflight_wagered_total, anomaly_sport_exp, anomaly_sport, 1.0, start_of_day_bankroll, "anomaly", azuro_odds,
-                                            daily_limit_override.unwrap_or(DAILY_LOSS_LIMIT_USD))
+                                            daily_limit_override.unwrap_or(DAILY_LOSS_LIMIT_USD));
+                                        let finalized = stake_policy.finalize(trimmed, anomaly.chain.as_deref());
+                                        if trimmed > 0.0 && finalized == 0.0 {
+                                            info!("🪫 STAKE_BELOW_CHAIN_MIN: {} trimmed=${:.4} chain={} min=${:.2} | path=anomaly",
+                                                anomaly.match_key, trimmed, anomaly.chain.as_deref().unwrap_or("?"),
+                                                stake_policy.min_stake(anomaly.chain.as_deref()));
+                                        }
+                                        finalized
                                     };
 
                                     // SAFETY: block anomaly auto-bet when Azuro has identical odds (oracle bug)
@@ -11122,13 +16704,18 @@ async fn main() -> Result<()> {
                                     // === SPORT-SPECIFIC ANOMALY GUARD ===
                                     // Score-edge path has sport_auto_bet_guard + model validation;
                                     // anomaly path is purely odds-comparison → needs stricter sport rules.
-                                    let anomaly_sport_allowed = match anomaly_sport {
-                                        // Football: ENABLED for anomaly path ONLY with goal_diff ≥ 2 (Phase 1, 2026-03-04)
-                                        // Rationale: 0/2 football anomaly lost → but those were game-start bets.
+                                    let anomaly_sport_killed = score_tracker.sport_kill.is_disabled(anomaly_sport);
+                                    if anomaly_sport_killed {
+                                        info!("🛑 SPORT KILL SWITCH (anomaly): {} auto-bet blokován (/enable {})",
+                                            anomaly.match_key, anomaly_sport);
+                                    }
+                                    let anomaly_sport_allowed = !anomaly_sport_killed && match anomaly_sport {
+                                        // Football: anomaly path ONLY with goal_diff ≥ 2 (Phase 1, 2026-03-04).
                                         // With goal_diff ≥ 2, true_p is 0.85+ (very strong position).
+                                        // Disabled by default through the sport kill switch (production data: 40% WR,
+                                        // PnL -$4.54, n=10) — AUTO_BET_DISABLED_SPORTS / `/enable football`.
                                         "football" => {
-                                            if !FF_FOOTBALL_ANOMALY_GOALDIFF2 { false }
-                                            else if let Some(ref score) = anomaly.live_score {
+                                            if let Some(ref score) = anomaly.live_score {
                                                 let parts: Vec<&str> = score.split('-').collect();
                                                 if parts.len() == 2 {
                                                     let (fs1, fs2) = (
@@ -11265,7 +16852,21 @@ async fn main() -> Result<()> {
                                             anomaly.match_key, anomaly.discrepancy_pct, anomaly_disc_min);
                                     }
 
+                                    let anomaly_league_decision = league_filter.decide(anomaly_sport, live_league(&state, &anomaly.match_key));
+                                    if anomaly_league_decision != LeagueDecision::AutoBet && anomaly.confidence == "HIGH" {
+                                        info!("🏟️ LEAGUE FILTER (anomaly): {} ({}) league={:?} → {:?} — alert only",
+                                            anomaly.match_key, anomaly_sport, live_league(&state, &anomaly.match_key), anomaly_league_decision);
+                                    }
+
+                                    let anomaly_outcome_order = anomaly.outcome_order;
+                                    if anomaly_outcome_order != OutcomeOrder::Verified && anomaly.confidence == "HIGH" {
+                                        info!("🛡️ OUTCOME ORDER (anomaly): {} outcome ids {:?}/{:?} → {:?} — auto-bet blocked",
+                                            anomaly.match_key, anomaly.outcome1_id, anomaly.outcome2_id, anomaly_outcome_order);
+                                    }
+
                                     let should_auto_bet_anomaly = AUTO_BET_ENABLED
+                                        && anomaly_league_decision == LeagueDecision::AutoBet
+                                        && anomaly_outcome_order == OutcomeOrder::Verified
                                         && dashboard_autobet_enabled
                                         && (dashboard_sport_focus.contains(&"all".to_string()) || dashboard_sport_focus.iter().any(|s| s == anomaly_sport))
                                         && AUTO_BET_ODDS_ANOMALY_ENABLED
@@ -11462,19 +17063,15 @@ async fn main() -> Result<()> {
 
                                         let decision_instant = std::time::Instant::now();
                                         let decision_ts_b = Utc::now();
-                                        let amount_raw = (stake * 1e6) as u64;
 
                                         let max_retries = AUTO_BET_RETRY_MAX;
                                         let mut attempt = 0;
                                         let mut minodds_fallback_applied = false;
                                         loop {
-                                        let min_odds_factor = min_odds_factor_with_fallback(&match_key_for_bet, minodds_fallback_applied);
-                                        let (min_odds, min_odds_display_b) = compute_min_odds_raw(azuro_odds, min_odds_factor);
-                                        let bet_body = serde_json::json!({
-                                            "conditionId": condition_id,
-                                            "outcomeId": outcome_id,
-                                            "amount": amount_raw.to_string(),
-                                            "minOdds": min_odds.to_string(),
+                                        let min_odds_factor = slippage_guard.factor_with_fallback(&match_key_for_bet, minodds_fallback_applied);
+                                        let min_odds_display_b = min_odds(azuro_odds, min_odds_factor);
+                                        let order = OrderRequest::new(Venue::Azuro, None, &condition_id, &outcome_id, stake, min_odds_display_b, decision_ts_b.timestamp());
+                                        let bet_context = serde_json::json!({
                                             "requestedOdds": azuro_odds,
                                             "matchKey": match_key_for_bet,
                                             "originalSport": anomaly.match_key.split("::").next().unwrap_or(""),
@@ -11492,7 +17089,7 @@ async fn main() -> Result<()> {
                                                 inflight_conditions.remove(key);
                                             }
                                             inflight_conditions.remove(&bet_market_dedup_key);
-                                            let _ = tg_send_message(&client, &token, chat_id,
+                                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                 &format!(
                                                     "⏰ <b>AUTO-BET #{} TTL EXPIRED</b>\n\
                                                      🏷️ <b>{}</b> | path: <b>anomaly_odds</b>\n\
@@ -11531,36 +17128,26 @@ async fn main() -> Result<()> {
                                         }
                                         let send_ts_b = Utc::now();
                                         let send_instant_b = std::time::Instant::now();
-                                        match client.post(format!("{}/bet", executor_url))
-                                            .json(&bet_body).send().await {
+                                        match send_executor_bet(&client, &executor_url, &order, bet_context, Duration::from_secs(EXECUTOR_BET_TIMEOUT_SECS)).await {
                                             Ok(resp) => {
                                                 let response_ts_b = Utc::now();
                                                 let rtt_ms_b = send_instant_b.elapsed().as_millis();
                                                 let pipeline_ms_b = decision_instant.elapsed().as_millis();
-                                                match resp.json::<ExecutorBetResponse>().await {
+                                                match read_executor_receipt(resp, &order).await {
                                                     Ok(br) => {
                                                         let is_rejected = br.state.as_deref()
                                                             .map(|s| s == "Rejected" || s == "Failed" || s == "Cancelled")
                                                             .unwrap_or(false);
                                                         if let Some(err) = &br.error {
-                                                            let err_lower = err.to_lowercase();
-                                                            let is_condition_paused = err_lower.contains("not active")
-                                                                || err_lower.contains("paused")
-                                                                || err_lower.contains("not exist");
-                                                            // Permanent dead condition — no retry, but blacklist immediately
-                                                            let is_condition_dead = err_lower.contains("not available")
-                                                                || err_lower.contains("live is not");
-                                                            let is_fatal = err_lower.contains("insufficient")
-                                                                || err_lower.contains("allowance")
-                                                                || err_lower.contains("revert")
-                                                                || err_lower.contains("nonce");
-                                                            if is_condition_paused && !is_condition_dead && !is_fatal && attempt < max_retries {
+                                                            let reject_class = ExecutorRejectClass::classify(Some(err), br.state.as_deref());
+                                                            if reject_class.is_retryable() && attempt < max_retries {
                                                                 if let Some((new_condition_id, new_outcome_id)) = remap_execution_ids_from_state(
                                                                     &state,
                                                                     &match_key_for_bet,
                                                                     &anomaly.team1,
                                                                     &anomaly.team2,
                                                                     anomaly.value_side,
+                                                                    &execution_venues,
                                                                 ) {
                                                                     if new_condition_id != condition_id || new_outcome_id != outcome_id {
                                                                         info!("🔁 AUTO-BET ODDS #{} remap retry: cond {}→{} out {}→{}",
@@ -11600,8 +17187,7 @@ async fn main() -> Result<()> {
                                                                     continue;
                                                                 }
                                                             }
-                                                            let is_minodds_b = err_lower.contains("min odds") || err_lower.contains("minodds")
-                                                                || err_lower.contains("real odds");
+                                                            let is_minodds_b = reject_class == ExecutorRejectClass::MinOdds;
                                                             if is_minodds_b && !minodds_fallback_applied && attempt < max_retries {
                                                                 attempt += 1;
                                                                 minodds_fallback_applied = true;
@@ -11613,13 +17199,9 @@ async fn main() -> Result<()> {
                                                                 tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
                                                                 continue;
                                                             }
-                                                            let is_dedup_b = err_lower.contains("dedup") || err_lower.contains("already bet");
-                                                            let is_condition_state_reject_b = is_condition_paused || is_condition_dead;
-                                                            let reason_code_b = if is_condition_state_reject_b { "ConditionNotRunning" }
-                                                                else if is_minodds_b { "MinOddsReject" }
-                                                                else if is_dedup_b { "Dedup" }
-                                                                else if is_fatal { "Fatal" }
-                                                                else { "Unknown" };
+                                                            let is_dedup_b = reject_class == ExecutorRejectClass::Dedup;
+                                                            let is_condition_state_reject_b = reject_class.is_condition_state();
+                                                            let reason_code_b = reject_class.reason_code();
                                                             // CONDITION BLACKLIST: add failed condition to blacklist
                                                             if is_condition_state_reject_b || is_minodds_b {
                                                                 blacklisted_conditions.insert(
@@ -11663,7 +17245,7 @@ async fn main() -> Result<()> {
                                                             }));
                                                             }
                                                             if !is_dedup_b {
-                                                                let _ = tg_send_message(&client, &token, chat_id,
+                                                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                                     &format_auto_bet_failed_message(
                                                                         aid,
                                                                         "anomaly_odds",
@@ -11691,7 +17273,7 @@ async fn main() -> Result<()> {
                                                                 aid, br.state.as_deref().unwrap_or("?"),
                                                                 &condition_id,
                                                                 match_key_for_bet);
-                                                            let _ = tg_send_message(&client, &token, chat_id,
+                                                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                                 &format_auto_bet_rejected_message(
                                                                     aid,
                                                                     "anomaly_odds",
@@ -11705,22 +17287,22 @@ async fn main() -> Result<()> {
                                                             }
                                                             inflight_conditions.remove(&bet_market_dedup_key);
                                                             break;
+                                                        } else if replay_already_booked(&br, &active_bets) {
+                                                            info!("♻️ AUTO-BET ODDS #{} replay of booked bet {} — not recording again", aid, br.bet_id.as_deref().unwrap_or("?"));
+                                                            if let Some(key) = scoped_cond_key.as_ref() {
+                                                                inflight_conditions.remove(key);
+                                                            }
+                                                            inflight_conditions.remove(&bet_market_dedup_key);
+                                                            break;
                                                         } else {
                                                             auto_bet_count += 1;
                                                             daily_wagered += stake;
-                                                            // Persist daily P&L
-                                                            {
-                                                                let today = Utc::now().format("%Y-%m-%d").to_string();
-                                                                let _ = std::fs::write(bet_count_path, format!("{}|{}", today, auto_bet_count));
-                                                                let _ = std::fs::write("data/daily_pnl.json",
-                                                                    serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
-                                                            }
                                                             let bet_id = br.bet_id.as_deref().unwrap_or("?");
                                                             let bet_state = br.state.as_deref().unwrap_or("?");
                                                             let token_id_opt = sanitize_token_id(br.token_id.clone());
                                                             let graph_bet_id_opt = br.graph_bet_id.clone();
                                                             let is_dry_run = bet_state == "DRY-RUN" || bet_id.starts_with("dry-");
-                                                            let mut accepted_odds = br.accepted_odds.unwrap_or(azuro_odds);
+                                                            let mut accepted_odds = br.placed_odds.unwrap_or(azuro_odds);
                                                             if bet_state == "Accepted" && !is_dry_run {
                                                                 if let Ok(status_resp) = client
                                                                     .get(format!("{}/bet/{}", executor_url, bet_id))
@@ -11768,26 +17350,24 @@ async fn main() -> Result<()> {
                                                                 already_bet_conditions.insert(key.clone());
                                                             }
                                                             already_bet_base_matches.insert(base_match_key.clone());
+                                                            record_bet_game_id(anomaly.game_id.as_deref(), &cond_id_str, &mut already_bet_game_ids);
                                                             // Track map_winner placements for sibling-map dedup logic
                                                             if is_map_winner_market(&match_key_for_bet, &anomaly.market_key) {
                                                                 already_bet_map_winners.insert(base_match_key.clone());
                                                             }
 
                                                             // === EXPOSURE TRACKING (odds anomaly path) ===
-                                                            if let Some(key) = scoped_cond_key.as_ref() {
-                                                                *condition_exposure.entry(key.clone()).or_insert(0.0) += stake;
-                                                            }
-                                                            *match_exposure.entry(base_match_key.clone()).or_insert(0.0) += stake;
-                                                            *sport_exposure.entry(anomaly_sport.to_string()).or_insert(0.0) += stake;
+                                                            record_placement_exposure(&mut condition_exposure, &mut match_exposure, &mut sport_exposure,
+                                                                scoped_cond_key.as_ref(), &base_match_key, anomaly_sport, stake);
                                                             inflight_wagered_total += stake;
 
-                                                            if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                .create(true).append(true)
-                                                                .open(bet_history_path) {
-                                                                use std::io::Write;
-                                                                let _ = writeln!(f, "{}|{}|{}|{}|{}|{}",
-                                                                    match_key_for_bet, cond_id_str,
-                                                                    value_team, accepted_odds, Utc::now().to_rfc3339(), anomaly.market_key);
+                                                            // Persist bet count + daily P&L + dedup entry in one transaction
+                                                            if let Err(e) = state_store.record_placement(
+                                                                auto_bet_count,
+                                                                &DailyPnl::new(&betting_day.today(), daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override),
+                                                                &BetHistoryEntry::new(&match_key_for_bet, &cond_id_str, &value_team, accepted_odds, &anomaly.market_key, anomaly.game_id.as_deref()),
+                                                            ) {
+                                                                warn!("⚠️ BET #{} state persist failed: {}", aid, e);
                                                             }
 
                                                             if !is_dry_run {
@@ -11812,7 +17392,7 @@ async fn main() -> Result<()> {
                                                                     path: "anomaly_odds".to_string(),
                                                                 };
                                                                 active_bets.push(active_bet.clone());
-                                                                append_pending_claim_entry(&active_bet, pending_claims_path);
+                                                                append_pending_claim_entry(&active_bet, &*state_store);
                                                                 // === LEDGER: BET PLACED ===
                                                                 ledger_write("PLACED", &serde_json::json!({
                                                                     "alert_id": aid, "bet_id": bet_id,
@@ -11885,7 +17465,7 @@ async fn main() -> Result<()> {
                                                                 auto_bet_count,
                                                                 is_dry_run,
                                                             );
-                                                            if let Err(e) = tg_send_message(&client, &token, chat_id, &result_msg).await {
+                                                            if let Err(e) = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &result_msg).await {
                                                                 error!("Failed to send auto-bet anomaly result alert: {}", e);
                                                             } else {
                                                                 anomaly_alert_sent = true;
@@ -11900,6 +17480,7 @@ async fn main() -> Result<()> {
                                                                 let follow_aid = aid;
                                                                 let follow_team = value_team.clone();
                                                                 let follow_chat = chat_id;
+                                                                let follow_broadcast = broadcast_chat_ids.clone();
                                                                 let follow_match_key = anomaly.match_key.clone();
                                                                 let follow_market_key = anomaly.market_key.clone();
                                                                 let follow_condition_id = condition_id.clone();
@@ -11924,9 +17505,9 @@ async fn main() -> Result<()> {
                                                                                      🧠 on-chain state: <b>{}</b>\n\
                                                                                      📝 {}",
                                                                                     follow_aid, follow_team, final_state, err_msg);
-                                                                                let _ = tg_send_message(
+                                                                                let _ = tg_send_alert(
                                                                                     &follow_client, &follow_token,
-                                                                                    follow_chat, &alert).await;
+                                                                                    follow_chat, &follow_broadcast, &alert).await;
                                                                                 warn!("❌ AUTO-BET ODDS #{} FOLLOW-UP REJECTED: {} err={}",
                                                                                     follow_aid, follow_bet_id, err_msg);
                                                                                 // LEDGER: write on-chain REJECTED event
@@ -11947,7 +17528,7 @@ async fn main() -> Result<()> {
                                                                                         "stake": follow_stake,
                                                                                     });
                                                                                     if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                                        .create(true).append(true).open("data/ledger.jsonl") {
+                                                                                        .create(true).append(true).open(LEDGER_PATH) {
                                                                                         use std::io::Write;
                                                                                         let _ = writeln!(f, "{}", entry);
                                                                                     }
@@ -11974,10 +17555,10 @@ async fn main() -> Result<()> {
                                                                                     token_id,
                                                                                     drift_line,
                                                                                 );
-                                                                                let _ = tg_send_message(
+                                                                                let _ = tg_send_alert(
                                                                                     &follow_client,
                                                                                     &follow_token,
-                                                                                    follow_chat,
+                                                                                    follow_chat, &follow_broadcast,
                                                                                     &alert,
                                                                                 ).await;
                                                                                 info!("✅ AUTO-BET ODDS #{} FOLLOW-UP CONFIRMED: state={} tokenId={}",
@@ -12001,7 +17582,7 @@ async fn main() -> Result<()> {
                                                                                         "stake": follow_stake,
                                                                                     });
                                                                                     if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                                        .create(true).append(true).open("data/ledger.jsonl") {
+                                                                                        .create(true).append(true).open(LEDGER_PATH) {
                                                                                         use std::io::Write;
                                                                                         let _ = writeln!(f, "{}", entry);
                                                                                         if let Some((delta_abs, delta_pct)) = significant_odds_drift(follow_odds, accepted_odds) {
@@ -12038,7 +17619,7 @@ async fn main() -> Result<()> {
                                                         }
                                                     }
                                                     Err(e) => {
-                                                        let _ = tg_send_message(&client, &token, chat_id,
+                                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                             &format!(
                                                                 "❌ <b>AUTO-BET #{} RESPONSE ERROR</b>\n\
                                                                  path: <b>anomaly_odds</b>\n\
@@ -12054,7 +17635,7 @@ async fn main() -> Result<()> {
                                                 }
                                             }
                                             Err(e) => {
-                                                let _ = tg_send_message(&client, &token, chat_id,
+                                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                     &format!(
                                                         "❌ <b>AUTO-BET #{} EXECUTOR OFFLINE</b>\n\
                                                          path: <b>anomaly_odds</b>\n\
@@ -12090,7 +17671,7 @@ async fn main() -> Result<()> {
                                                 anomaly.match_key, MANUAL_MATCH_COOLDOWN_SECS);
                                         } else {
                                             let msg = format_anomaly_alert(&anomaly, aid);
-                                            match tg_send_message(&client, &token, chat_id, &msg).await {
+                                            match tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await {
                                                 Ok(msg_id) => {
                                                     anomaly_alert_sent = true;
                                                     msg_id_to_alert_id.insert(msg_id, aid);
@@ -12126,7 +17707,138 @@ async fn main() -> Result<()> {
                                 }
 
                                 info!("Poll: {} score edges, {} odds anomalies, {} sent (cooldown={})",
-                                    score_edges.len(), total_anomalies, actually_sent, sent_alerts.len());
+                                    signals.total_score_edges, signals.total_anomalies, actually_sent, sent_alerts.len());
+
+                                // === HEDGE: aktivní sázka, které se po sázce otočilo live skóre → opačná strana ===
+                                let hedges: Vec<(ActiveBet, HedgeRecommendation)> = active_bets.iter()
+                                    .filter(|bet| bet.path != "hedge" && !hedged_bets.contains(&bet.bet_id))
+                                    .filter_map(|bet| {
+                                        let base_key = strip_map_winner_suffix(&bet.match_key);
+                                        let live = state.live.iter().find(|l| l.match_key == base_key)?;
+                                        let odds = state.odds.iter()
+                                            .find(|o| o.payload.condition_id.as_deref() == Some(bet.condition_id.as_str()))?;
+                                        hedge_recommendation(bet, &live.payload, &odds.payload).map(|h| (bet.clone(), h))
+                                    })
+                                    .collect();
+                                for (bet, mut hedge) in hedges {
+                                    hedged_bets.insert(bet.bet_id.clone());
+                                    // Auto-hedge je reálná sázka → stejné gates a trimmer jako score-edge / anomaly auto-bet
+                                    let hedge_sport = bet.match_key.split("::").next().unwrap_or("").to_string();
+                                    let hedge_base_key = strip_map_winner_suffix(&bet.match_key);
+                                    let hedge_cond_key = scoped_condition_key(&hedge_base_key, &hedge.condition_id);
+                                    let hedge_daily_loss = (daily_wagered - daily_returned).max(0.0);
+                                    let hedge_gates_ok = FF_AUTO_HEDGE
+                                        && AUTO_BET_ENABLED
+                                        && dashboard_autobet_enabled
+                                        && !safe_mode
+                                        && current_bankroll >= MIN_BANKROLL_USD
+                                        && hedge_daily_loss < effective_daily_limit;
+                                    let auto_stake = if hedge_gates_ok {
+                                        let trimmed = trim_stake(hedge.hedge_stake, current_bankroll,
+                                            condition_exposure.get(&hedge_cond_key).copied().unwrap_or(0.0),
+                                            match_exposure.get(&hedge_base_key).copied().unwrap_or(0.0),
+                                            hedge_daily_loss, inflight_wagered_total,
+                                            sport_exposure.get(&hedge_sport).copied().unwrap_or(0.0),
+                                            &hedge_sport, 1.0, start_of_day_bankroll, "hedge", hedge.opposite_odds,
+                                            daily_limit_override.unwrap_or(DAILY_LOSS_LIMIT_USD));
+                                        stake_policy.finalize(trimmed, None)
+                                    } else {
+                                        0.0
+                                    };
+                                    let auto = auto_stake > 0.0;
+                                    if auto && auto_stake < hedge.hedge_stake {
+                                        info!("🛡️ HEDGE bet={} stake trimmed ${:.2} → ${:.2} (exposure caps)",
+                                            bet.bet_id, hedge.hedge_stake, auto_stake);
+                                        hedge = hedge.with_stake(&bet, auto_stake);
+                                    }
+                                    warn!("🛡️ HEDGE {} bet={} fair {:.2} vs implied {:.2} → {} @ {:.2} ${:.2} (locked {:+.2})",
+                                        if auto { "AUTO" } else { "OFFER" }, bet.bet_id, hedge.fair_prob_now, hedge.implied_prob_at_bet,
+                                        hedge.opposite_team, hedge.opposite_odds, hedge.hedge_stake, hedge.locked_pnl);
+                                    ledger_write("HEDGE_RECOMMENDED", &serde_json::json!({
+                                        "alert_id": bet.alert_id, "bet_id": bet.bet_id,
+                                        "match_key": hedge.match_key,
+                                        "condition_id": hedge.condition_id,
+                                        "outcome_id": hedge.opposite_outcome_id,
+                                        "value_team": hedge.opposite_team,
+                                        "odds": hedge.opposite_odds,
+                                        "amount_usd": hedge.hedge_stake,
+                                        "implied_prob_at_bet": hedge.implied_prob_at_bet,
+                                        "fair_prob_now": hedge.fair_prob_now,
+                                        "locked_pnl": hedge.locked_pnl,
+                                        "auto": auto,
+                                    }));
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &format_hedge_message(&bet, &hedge, auto)).await;
+                                    if !auto {
+                                        continue;
+                                    }
+
+                                    let min_odds = min_odds(hedge.opposite_odds, slippage_guard.factor_for_match(&bet.match_key));
+                                    let order = OrderRequest::new(Venue::Azuro, None, &hedge.condition_id, &hedge.opposite_outcome_id, hedge.hedge_stake, min_odds, Utc::now().timestamp());
+                                    let bet_context = serde_json::json!({
+                                        "requestedOdds": hedge.opposite_odds,
+                                        "matchKey": bet.match_key,
+                                        "originalSport": bet.original_sport,
+                                        "resolvedSport": bet.resolved_sport,
+                                        "esportsFamily": bet.esports_family,
+                                        "team1": bet.team1,
+                                        "team2": bet.team2,
+                                        "valueTeam": hedge.opposite_team,
+                                    });
+                                    let placed = match send_executor_bet(&client, &executor_url, &order, bet_context, Duration::from_secs(EXECUTOR_BET_TIMEOUT_SECS)).await {
+                                        Ok(resp) => read_executor_receipt(resp, &order).await.ok(),
+                                        Err(e) => {
+                                            warn!("🛡️ HEDGE bet={} request failed: {}", bet.bet_id, e);
+                                            None
+                                        }
+                                    };
+                                    let Some(br) = placed else { continue; };
+                                    let state_str = br.state.as_deref().unwrap_or("?");
+                                    let hedge_bet_id = br.bet_id.clone().unwrap_or_default();
+                                    if br.error.is_some() || matches!(state_str, "Rejected" | "Failed" | "Cancelled") || hedge_bet_id.is_empty() {
+                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
+                                            &format!("❌ <b>HEDGE FAILED #{}</b>\n\nState: {}\nError: {}",
+                                                bet.alert_id, state_str, br.error.as_deref().unwrap_or("-"))
+                                        ).await;
+                                        continue;
+                                    }
+                                    if state_str == "DRY-RUN" || hedge_bet_id.starts_with("dry-") || replay_already_booked(&br, &active_bets) {
+                                        continue;
+                                    }
+                                    let accepted_odds = br.placed_odds.unwrap_or(hedge.opposite_odds);
+                                    let hedge_bet = ActiveBet {
+                                        bet_id: hedge_bet_id.clone(),
+                                        value_team: hedge.opposite_team.clone(),
+                                        amount_usd: hedge.hedge_stake,
+                                        odds: accepted_odds,
+                                        placed_at: Utc::now().to_rfc3339(),
+                                        outcome_id: hedge.opposite_outcome_id.clone(),
+                                        graph_bet_id: br.graph_bet_id.clone(),
+                                        token_id: sanitize_token_id(br.token_id.clone()),
+                                        path: "hedge".to_string(),
+                                        ..bet.clone()
+                                    };
+                                    daily_wagered += hedge.hedge_stake;
+                                    record_placement_exposure(&mut condition_exposure, &mut match_exposure, &mut sport_exposure,
+                                        Some(&hedge_cond_key), &hedge_base_key, &hedge_sport, hedge.hedge_stake);
+                                    inflight_wagered_total += hedge.hedge_stake;
+                                    active_bets.push(hedge_bet.clone());
+                                    append_pending_claim_entry(&hedge_bet, &*state_store);
+                                    ledger_write("PLACED", &serde_json::json!({
+                                        "alert_id": bet.alert_id, "bet_id": hedge_bet_id,
+                                        "match_key": bet.match_key,
+                                        "market_key": bet.market_key,
+                                        "team1": bet.team1, "team2": bet.team2,
+                                        "value_team": hedge.opposite_team,
+                                        "amount_usd": hedge.hedge_stake, "odds": accepted_odds,
+                                        "requested_odds": hedge.opposite_odds,
+                                        "condition_id": hedge.condition_id,
+                                        "outcome_id": hedge.opposite_outcome_id,
+                                        "token_id": hedge_bet.token_id,
+                                        "graph_bet_id": hedge_bet.graph_bet_id,
+                                        "hedged_bet_id": bet.bet_id,
+                                        "path": "hedge",
+                                    }));
+                                }
                             }
                             Err(e) => warn!("Failed to parse /state: {}", e),
                         }
@@ -12226,7 +17938,7 @@ async fn main() -> Result<()> {
                                             match resp.json::<ExecutorCashoutResponse>().await {
                                                 Ok(cr) => {
                                                     let state = cr.state.as_deref().unwrap_or("?");
-                                                    let _ = tg_send_message(&client, &token, chat_id,
+                                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                                         &format!(
                                                             "💰 <b>AUTO-CASHOUT #{}</b>\n\n\
                                                              {} vs {}\n\
@@ -12363,11 +18075,10 @@ async fn main() -> Result<()> {
                                         total_returned += payout;
                                         daily_returned += payout;
                                         {
-                                            let today = Utc::now().format("%Y-%m-%d").to_string();
-                                            let _ = std::fs::write("data/daily_pnl.json",
-                                                serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                            let today = betting_day.today();
+                                            let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                         }
-                                        let _ = tg_send_message(&client, &token, chat_id,
+                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                             &format!("💰 <b>AUTO-CLAIM (safety net)</b>\n\nVyplaceno {} sázek, ${:.2}\n💰 Nový zůstatek: {} USDT",
                                                 claimed, payout, new_bal)
                                         ).await;
@@ -12510,11 +18221,8 @@ async fn main() -> Result<()> {
                                         &mut loss_streak_pause_until,
                                     );
                                     {
-                                        let today = Utc::now().format("%Y-%m-%d").to_string();
-                                        let _ = std::fs::write(
-                                            "data/daily_pnl.json",
-                                            serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string(),
-                                        );
+                                        let today = betting_day.today();
+                                        let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                     }
                                     let (dt1, dt2) = display_team_pair(&bet_view.match_key, &bet_view.team1, &bet_view.team2);
                                     let placed_detail = lookup_placed_metadata(&bet.bet_id)
@@ -12535,7 +18243,7 @@ async fn main() -> Result<()> {
                                         bet_view.amount_usd,
                                         placed_detail
                                     );
-                                    let _ = tg_send_message(&client, &token, chat_id, &loss_msg).await;
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &loss_msg).await;
                                     // === LEDGER: LOST (check_payout) ===
                                     if !ledger_settled_ids.contains(&bet.bet_id) {
                                         ledger_write("LOST", &serde_json::json!({
@@ -12695,7 +18403,7 @@ async fn main() -> Result<()> {
                                     placed_detail,
                                     total_wagered, total_returned
                                 );
-                                let _ = tg_send_message(&client, &token, chat_id, &loss_msg).await;
+                                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &loss_msg).await;
                             }
                             _ => {}
                         }
@@ -12705,7 +18413,7 @@ async fn main() -> Result<()> {
                             "🏁 <b>Bet #{} settled</b>: {} (no tokenId pro claim)\n{} vs {}",
                             bet.alert_id, effective_result, bet.team1, bet.team2
                         );
-                        let _ = tg_send_message(&client, &token, chat_id, &msg).await;
+                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await;
                     }
 
                     settled_bet_ids.insert(bet.bet_id.clone());
@@ -12793,11 +18501,12 @@ async fn main() -> Result<()> {
                                     let total_payout = cr.total_payout_usd.unwrap_or(0.0);
                                     let new_balance = cr.new_balance_usd.as_deref().unwrap_or("?");
                                     let claimed_tokens = cr.token_ids.clone().unwrap_or_else(|| verified_tokens.clone());
-                                    let claimed_token_set: HashSet<&str> = claimed_tokens.iter().map(|tid| tid.as_str()).collect();
-                                    let claimed_details: Vec<ClaimDetail> = verified_details.iter()
-                                        .filter(|detail| claimed_token_set.contains(detail.token_id.as_str()))
-                                        .cloned()
-                                        .collect();
+                                    let (credited_payout, claimed_details, unexpected_tokens) =
+                                        reconcile_claimed_tokens(&claimed_tokens, &verified_details, total_payout);
+                                    if !unexpected_tokens.is_empty() {
+                                        warn!("⚠️ Claim response contains {} unexpected token(s) {:?} — crediting only matched bets ${:.2} of ${:.2}",
+                                            unexpected_tokens.len(), unexpected_tokens, credited_payout, total_payout);
+                                    }
                                     let new_claim_tokens: Vec<String> = claimed_tokens.iter()
                                         .filter(|tid| !claimed_token_ids.contains(*tid))
                                         .cloned()
@@ -12808,17 +18517,18 @@ async fn main() -> Result<()> {
                                         claimed_token_ids.insert(tid.clone());
                                     }
                                     if should_count_claim {
-                                        total_returned += total_payout;
-                                        daily_returned += total_payout;
+                                        total_returned += credited_payout;
+                                        daily_returned += credited_payout;
                                         {
-                                            let today = Utc::now().format("%Y-%m-%d").to_string();
-                                            let _ = std::fs::write("data/daily_pnl.json",
-                                                serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                            let today = betting_day.today();
+                                            let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                         }
                                         ledger_write("EXECUTOR_CLAIM", &serde_json::json!({
                                             "claimed": claimed_tokens.len(),
                                             "tokenIds": claimed_tokens,
                                             "totalPayoutUsd": total_payout,
+                                            "creditedPayoutUsd": credited_payout,
+                                            "unexpectedTokenIds": unexpected_tokens,
                                             "newBalanceUsd": new_balance,
                                             "txHash": tx,
                                             "context": "batch_claim"
@@ -12864,12 +18574,12 @@ async fn main() -> Result<()> {
                                          💰 <b>Nový zůstatek: {} USDT</b>\n\n\
                                          📊 Daily P/L: <b>{}{:.2} USDT</b>\n\
                                          (vsazeno: ${:.2}, vráceno: ${:.2})",
-                                        total_payout, tx, new_balance,
+                                        credited_payout, tx, new_balance,
                                         pnl_sign, daily_pnl_claim,
                                         daily_wagered, daily_returned
                                     ));
 
-                                    let _ = tg_send_message(&client, &token, chat_id, &msg).await;
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await;
                                     if should_count_claim {
                                         for detail in &claimed_details {
                                             ledger_write("CLAIMED", &serde_json::json!({
@@ -12895,7 +18605,7 @@ async fn main() -> Result<()> {
                                 }
                                 Err(e) => {
                                     warn!("Claim response parse error: {}", e);
-                                    let _ = tg_send_message(&client, &token, chat_id,
+                                    let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                         &format!("⚠️ Claim error: {}", e)
                                     ).await;
                                 }
@@ -12903,7 +18613,7 @@ async fn main() -> Result<()> {
                         }
                         Err(e) => {
                             warn!("Claim request failed: {}", e);
-                            let _ = tg_send_message(&client, &token, chat_id,
+                            let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                 &format!("⚠️ Claim request failed: {}", e)
                             ).await;
                         }
@@ -12920,18 +18630,7 @@ async fn main() -> Result<()> {
 
                 // Rewrite pending_claims file when bets removed OR tokenIds discovered
                 if !bets_to_remove.is_empty() || needs_pending_rewrite {
-                    if let Ok(mut f) = std::fs::OpenOptions::new()
-                        .create(true).write(true).truncate(true)
-                        .open(pending_claims_path) {
-                        use std::io::Write;
-                        for bet in &active_bets {
-                            let tid = bet.token_id.as_deref().unwrap_or("?");
-                            let _ = writeln!(f, "{}|{}|{}|{}|{}|{}|{}|{}|{}",
-                                tid, bet.bet_id, bet.match_key,
-                                bet.value_team, bet.amount_usd, bet.odds,
-                                bet.team1, bet.team2, bet.market_key);
-                        }
-                    }
+                    rewrite_pending_claims_file(&active_bets, &*state_store);
                 }
 
                 // === SAFETY NET: Call /auto-claim every 5th tick to catch any missed bets ===
@@ -12964,12 +18663,11 @@ async fn main() -> Result<()> {
                                         total_returned += payout;
                                         daily_returned += payout;
                                         {
-                                            let today = Utc::now().format("%Y-%m-%d").to_string();
-                                            let _ = std::fs::write("data/daily_pnl.json",
-                                                serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                            let today = betting_day.today();
+                                            let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                         }
                                         info!("💰 Safety-net auto-claim: {} bets, ${:.2} (daily_returned now ${:.2})", claimed, payout, daily_returned);
-                                        let _ = tg_send_message(&client, &token, chat_id,
+                                        let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids,
                                             &format!("💰 <b>AUTO-CLAIM (safety net)</b>\n\nVyplaceno {} sázek, ${:.2}\n💰 Nový zůstatek: {} USDT",
                                                 claimed, payout, new_bal)
                                         ).await;
@@ -13004,7 +18702,7 @@ async fn main() -> Result<()> {
                                 inflight_wagered_total = reconcile_active_bets_with_executor_snapshot(
                                     &mut active_bets,
                                     bets_arr,
-                                    pending_claims_path,
+                                    &*state_store,
                                     session_start,
                                     INFLIGHT_TTL_SECS,
                                 );
@@ -13021,6 +18719,7 @@ async fn main() -> Result<()> {
                 let uptime_mins = (Utc::now() - session_start).num_minutes();
                 msg.push_str(&format!("⏱️ Uptime: {}h {}min\n\n", uptime_mins / 60, uptime_mins % 60));
 
+                paper_live_check!();
                 // Get wallet balance from executor (try /balance for live on-chain data)
                 let executor_ok = match client.get(format!("{}/balance", executor_url)).send().await {
                     Ok(resp) => {
@@ -13030,7 +18729,7 @@ async fn main() -> Result<()> {
                                 let nat = b.get("native").and_then(|v| v.as_str()).unwrap_or("?");
                                 msg.push_str(&format!("💰 <b>Wallet: {} USDT</b> ({} MATIC)\n", bal, &nat[..nat.len().min(6)]));
                                 // === BANKROLL REFRESH for exposure caps ===
-                                if let Ok(parsed_bal) = bal.parse::<f64>() {
+                                if let (None, Ok(parsed_bal)) = (paper_mode.bankroll, bal.parse::<f64>()) {
                                     if parsed_bal > 0.0 {
                                         let old_br = current_bankroll;
                                         current_bankroll = parsed_bal;
@@ -13126,7 +18825,7 @@ async fn main() -> Result<()> {
                         inflight_wagered_total = reconcile_active_bets_with_executor_snapshot(
                             &mut active_bets,
                             bets_arr,
-                            pending_claims_path,
+                            &*state_store,
                             session_start,
                             INFLIGHT_TTL_SECS,
                         );
@@ -13297,8 +18996,8 @@ async fn main() -> Result<()> {
                 match client.get(format!("{}/state", feed_hub_url)).send().await {
                     Ok(resp) => {
                         if let Ok(state) = resp.json::<StateResponse>().await {
-                            let azuro_count = state.odds.iter().filter(|o| o.payload.bookmaker.starts_with("azuro_")).count();
-                            let market_count = state.odds.iter().filter(|o| !o.payload.bookmaker.starts_with("azuro_")).count();
+                            let azuro_count = state.odds.iter().filter(|o| execution_venues.is_venue(&o.payload.bookmaker)).count();
+                            let market_count = state.odds.iter().filter(|o| !execution_venues.is_venue(&o.payload.bookmaker)).count();
                             let map_winner_count = state.odds.iter().filter(|o| {
                                 o.payload.market.as_deref().map(|m| m.starts_with("map")).unwrap_or(false)
                             }).count();
@@ -13314,7 +19013,7 @@ async fn main() -> Result<()> {
                     Err(_) => {}
                 }
 
-                let _ = tg_send_message(&client, &token, chat_id, &msg).await;
+                let _ = tg_send_alert(&client, &token, chat_id, &broadcast_chat_ids, &msg).await;
                 info!("📊 Portfolio report sent");
             }
 
@@ -13425,6 +19124,7 @@ async fn main() -> Result<()> {
                                     msg.push_str(&format!("⏱️ Session: {}h {}min\n", uptime_mins / 60, uptime_mins % 60));
 
                                     // === WALLET ===
+                                    paper_live_check!();
                                     match client.get(format!("{}/balance", executor_url)).send().await {
                                         Ok(resp) => {
                                             match resp.json::<serde_json::Value>().await {
@@ -13432,7 +19132,7 @@ async fn main() -> Result<()> {
                                                     let bal = b.get("betToken").and_then(|v| v.as_str()).unwrap_or("?");
                                                     let nat = b.get("native").and_then(|v| v.as_str()).unwrap_or("?");
                                                     msg.push_str(&format!("💰 <b>{} USDT</b> ({} MATIC)\n", bal, &nat[..nat.len().min(6)]));
-                                                    if let Ok(parsed_bal) = bal.parse::<f64>() {
+                                                    if let (None, Ok(parsed_bal)) = (paper_mode.bankroll, bal.parse::<f64>()) {
                                                         if parsed_bal > 0.0 { current_bankroll = parsed_bal; }
                                                     }
                                                 }
@@ -13467,7 +19167,7 @@ async fn main() -> Result<()> {
                                             ));
                                             if let Some(bets_arr) = mb.get("bets").and_then(|v| v.as_array()) {
                                                 inflight_wagered_total = reconcile_active_bets_with_executor_snapshot(
-                                                    &mut active_bets, bets_arr, pending_claims_path,
+                                                    &mut active_bets, bets_arr, &*state_store,
                                                     session_start, INFLIGHT_TTL_SECS,
                                                 );
                                             }
@@ -13516,18 +19216,8 @@ async fn main() -> Result<()> {
                                         }
                                     }
 
-                                    let daily_pnl = daily_returned - daily_wagered;
-                                    let (pnl_sign, pnl_emoji) = if daily_pnl >= 0.0 { ("+", "📈") } else { ("", "📉") };
-                                    msg.push_str(&format!("\n{} <b>P/L: {}{:.2} USDT</b>\n", pnl_emoji, pnl_sign, daily_pnl));
-                                    msg.push_str(&format!("   Vsazeno ${:.2} | Vráceno ${:.2}\n", daily_wagered, daily_returned));
-                                    let daily_net_loss = (daily_wagered - daily_returned).max(0.0);
-                                    let effective_daily_limit = {
-                                        let (_, _, _, dl_frac, _) = get_exposure_caps(start_of_day_bankroll);
-                                        daily_limit_override.unwrap_or_else(|| DAILY_LOSS_LIMIT_USD.min(start_of_day_bankroll * dl_frac))
-                                    };
-                                    let lim_tag = if daily_limit_override.is_some() { " ⚡" } else { "" };
-                                    msg.push_str(&format!("   Loss: ${:.2} / ${:.2}{}\n", daily_net_loss, effective_daily_limit, lim_tag));
-                                    msg.push_str(&format!("   Auto-bets: {}\n", auto_bet_count));
+                                    let status = status_snapshot!();
+                                    msg.push_str(&status.summary_text());
 
                                     let runtime_audit = summarize_recent_runtime(24);
                                     if runtime_audit.has_data() {
@@ -13576,8 +19266,8 @@ async fn main() -> Result<()> {
                                     match client.get(format!("{}/state", feed_hub_url)).send().await {
                                         Ok(resp) => {
                                             if let Ok(state) = resp.json::<StateResponse>().await {
-                                                let azuro_count = state.odds.iter().filter(|o| o.payload.bookmaker.starts_with("azuro_")).count();
-                                                let market_count = state.odds.iter().filter(|o| !o.payload.bookmaker.starts_with("azuro_")).count();
+                                                let azuro_count = state.odds.iter().filter(|o| execution_venues.is_venue(&o.payload.bookmaker)).count();
+                                                let market_count = state.odds.iter().filter(|o| !execution_venues.is_venue(&o.payload.bookmaker)).count();
                                                 msg.push_str(&format!("📡 Feed: {} live | Azuro {} | Market {}\n",
                                                     state.live_items, azuro_count, market_count));
                                             }
@@ -13600,7 +19290,7 @@ async fn main() -> Result<()> {
                                         Ok(resp) => {
                                             match resp.json::<StateResponse>().await {
                                                 Ok(state) => {
-                                                    let anomalies = find_odds_anomalies(&state);
+                                                    let anomalies = find_odds_anomalies(&state, &execution_venues);
                                                     if anomalies.is_empty() {
                                                         let _ = tg_send_message(&client, &token, chat_id,
                                                             "📭 Žádné odds anomálie právě teď.\nAzuro a trh se shodují."
@@ -13608,7 +19298,7 @@ async fn main() -> Result<()> {
                                                     } else {
                                                         let summary = anomalies.iter().take(5)
                                                             .map(|a| {
-                                                                let team = if a.value_side == 1 { &a.team1 } else { &a.team2 };
+                                                                let (team, _, _) = anomaly_side_pick(a, a.value_side);
                                                                 format!("• {} <b>+{:.1}%</b> ({})", team, a.discrepancy_pct, a.match_key)
                                                             })
                                                             .collect::<Vec<_>>()
@@ -13761,9 +19451,8 @@ async fn main() -> Result<()> {
                                                         total_returned += payout;
                                                         daily_returned += payout;
                                                         {
-                                                            let today = Utc::now().format("%Y-%m-%d").to_string();
-                                                            let _ = std::fs::write("data/daily_pnl.json",
-                                                                serde_json::json!({"date": today, "wagered": daily_wagered, "returned": daily_returned, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                                            let today = betting_day.today();
+                                                            let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, daily_wagered, daily_returned, start_of_day_bankroll, daily_limit_override));
                                                         }
                                                         ledger_write("EXECUTOR_CLAIM", &serde_json::json!({
                                                             "claimed": claimed,
@@ -13853,6 +19542,14 @@ async fn main() -> Result<()> {
                                         let _ = tg_send_message_with_keyboard(&client, &token, chat_id, &detail, keyboard).await;
                                     }
 
+                                } else if text == "/pnl" || text.starts_with("/pnl ") {
+                                    // /pnl [N] — realizovaný výkon z ledgeru, volitelně za posledních N dní
+                                    let days = text.trim_start_matches("/pnl").trim().parse::<i64>().ok().filter(|d| *d > 0);
+                                    let since = days.map(|d| Utc::now() - chrono::Duration::days(d));
+                                    let contents = std::fs::read_to_string(LEDGER_PATH).unwrap_or_default();
+                                    let msg = format_pnl_summary(&summarize_ledger_pnl(&contents, since), days);
+                                    let _ = tg_send_message(&client, &token, chat_id, &msg).await;
+
                                 } else if text == "/nabidka" {
                                     mute_manual_alerts = true;
                                     let _ = tg_send_message(&client, &token, chat_id,
@@ -13916,6 +19613,33 @@ async fn main() -> Result<()> {
                                         ).await;
                                     }
 
+                                } else if text.starts_with("/enable") {
+                                    let enable_sport = text.trim_start_matches("/enable").trim().to_lowercase();
+                                    if enable_sport.is_empty() {
+                                        let disabled: Vec<String> = score_tracker.sport_kill.disabled.iter()
+                                            .map(|(sport, reason)| format!("• {} — {}", sport, reason))
+                                            .collect();
+                                        let _ = tg_send_message(&client, &token, chat_id,
+                                            &format!("❌ Syntax: /enable &lt;sport&gt;\nVypnuté sporty:\n{}",
+                                                if disabled.is_empty() { "(žádné)".to_string() } else { disabled.join("\n") })
+                                        ).await;
+                                    } else if score_tracker.sport_kill.enable(&enable_sport) {
+                                        if let Err(e) = state_store.save_disabled_sports(&score_tracker.sport_kill.disabled_list()) {
+                                            warn!("⚠️ Sport kill switch save failed: {}", e);
+                                        }
+                                        let _ = tg_send_message(&client, &token, chat_id,
+                                            &format!("✅ <b>{}</b> auto-bety znovu zapnuty.", enable_sport)
+                                        ).await;
+                                        info!("✅ /enable {} — sport kill switch uvolněn", enable_sport);
+                                        ledger_write("SPORT_KILL_SWITCH", &serde_json::json!({
+                                            "sport": enable_sport, "action": "enabled", "trigger": "manual_command"
+                                        }));
+                                    } else {
+                                        let _ = tg_send_message(&client, &token, chat_id,
+                                            &format!("ℹ️ {} není vypnutý.", enable_sport)
+                                        ).await;
+                                    }
+
                                 } else if text == "/reset_daily" || text == "/resetdaily" {
                                     let old_w = daily_wagered;
                                     let old_r = daily_returned;
@@ -13926,9 +19650,8 @@ async fn main() -> Result<()> {
                                     daily_loss_last_reminder = None;
                                     daily_limit_override = None; // reset override on full daily reset
                                     {
-                                        let today = Utc::now().format("%Y-%m-%d").to_string();
-                                        let _ = std::fs::write("data/daily_pnl.json",
-                                            serde_json::json!({"date": today, "wagered": 0.0, "returned": 0.0, "sod_bankroll": start_of_day_bankroll, "limit_override": daily_limit_override}).to_string());
+                                        let today = betting_day.today();
+                                        let _ = state_store.save_daily_pnl(&DailyPnl::new(&today, 0.0, 0.0, start_of_day_bankroll, daily_limit_override));
                                     }
                                     let _ = tg_send_message(&client, &token, chat_id,
                                         &format!(
@@ -13954,12 +19677,14 @@ async fn main() -> Result<()> {
                                          <b>Commands:</b>\n\
                                          /menu — 🎛 interaktivní ovládací panel\n\
                                          /prehled — 📋 posledních 10 settled betů\n\
+                                         /pnl [N] — 💰 realizované P&amp;L, WR a ROI per sport (N dní)\n\
                                          /status — kompletní přehled systému + portfolio\n\
                                          /bets — sázky ze subgraphu (live) + lokální\n\
                                          /odds — aktuální odds anomálie\n\
                                          /nabidka — vypnout manuální alerty\n\
                                          /nabidkaup — zapnout manuální alerty\n\
                                          /reset_daily — reset daily loss limitu\n\
+                                         /enable &lt;sport&gt; — zapnout sport po kill switchi\n\
                                          /claim — manuální auto-claim výher\n\
                                          /help — tato zpráva\n\n\
                                          <b>Na alert odpověz:</b>\n\
@@ -14034,16 +19759,29 @@ async fn main() -> Result<()> {
                                                 continue;
                                             }
                                         };
+                                        // OUTCOME ORDER: stejná pojistka jako u auto-betu — neověřené pořadí = sázka naslepo
+                                        if anomaly.outcome_order != OutcomeOrder::Verified {
+                                            let _ = tg_send_message(&client, &token, chat_id,
+                                                &format!(
+                                                    "🛑 <b>MANUAL BET BLOCKED</b>\n\nAlert #{}\n{}\nOutcome ID {:?}/{:?} nejdou ověřit proti týmům ({:?}).",
+                                                    aid, anomaly.match_key, anomaly.outcome1_id, anomaly.outcome2_id, anomaly.outcome_order
+                                                )
+                                            ).await;
+                                            continue;
+                                        }
+
                                         let selected_side = if opposite_side {
-                                            if anomaly.value_side == 1 { 2 } else { 1 }
+                                            // Remíza nemá jednu opačnou stranu → bez outcome_id
+                                            match anomaly.value_side { 1 => 2, 2 => 1, _ => 0 }
                                         } else {
                                             anomaly.value_side
                                         };
 
-                                        let selected_outcome_id = if selected_side == 1 {
-                                            anomaly.outcome1_id.clone()
-                                        } else {
-                                            anomaly.outcome2_id.clone()
+                                        let selected_outcome_id = match selected_side {
+                                            1 => anomaly.outcome1_id.clone(),
+                                            2 => anomaly.outcome2_id.clone(),
+                                            VALUE_SIDE_DRAW => anomaly.outcome_draw_id.clone(),
+                                            _ => None,
                                         };
 
                                         let outcome_id = match selected_outcome_id {
@@ -14059,17 +19797,20 @@ async fn main() -> Result<()> {
                                         // Manual dedup guard (same as auto-bet protection):
                                         // don't allow re-betting same condition/base match from stale alert messages.
                                         let manual_base_match_key = strip_map_winner_suffix(&anomaly.match_key);
+                                        let manual_market_dedup_key = market_dedup_key(&anomaly.match_key, &anomaly.market_key);
                                         let manual_dedup_base = cross_market_base_dedup_block(
                                             &manual_base_match_key,
                                             is_map_winner_market(&anomaly.match_key, &anomaly.market_key),
+                                            is_bo1_series(None, anomaly.detailed_score.as_deref())
+                                                && !already_bet_matches.contains(&manual_market_dedup_key),
                                             &already_bet_base_matches,
                                             &already_bet_map_winners,
                                         );
                                         let manual_scoped_condition = scoped_condition_key(&manual_base_match_key, &condition_id);
-                                        let manual_market_dedup_key = market_dedup_key(&anomaly.match_key, &anomaly.market_key);
                                         let manual_dedup = already_bet_conditions.contains(&manual_scoped_condition)
                                             || already_bet_matches.contains(&manual_market_dedup_key)
                                             || manual_dedup_base
+                                            || game_id_dedup_block(anomaly.game_id.as_deref(), &condition_id, &already_bet_game_ids)
                                             || inflight_conditions.contains(&manual_scoped_condition)
                                             || inflight_conditions.contains(&manual_market_dedup_key);
                                         if manual_dedup {
@@ -14082,8 +19823,7 @@ async fn main() -> Result<()> {
                                             continue;
                                         }
 
-                                        let azuro_odds = if selected_side == 1 { anomaly.azuro_w1 } else { anomaly.azuro_w2 };
-                                        let value_team = if selected_side == 1 { &anomaly.team1 } else { &anomaly.team2 };
+                                        let (value_team, azuro_odds, _) = anomaly_side_pick(anomaly, selected_side);
 
                                         if azuro_odds > MANUAL_BET_MAX_ODDS {
                                             let _ = tg_send_message(&client, &token, chat_id,
@@ -14109,14 +19849,10 @@ async fn main() -> Result<()> {
                                         ).await;
 
                                         // POST to executor
-                                        let (min_odds, _min_odds_display_cmd) = compute_min_odds_raw(azuro_odds, min_odds_factor_for_match(&anomaly.match_key));
-                                        let amount_raw = (amount * 1e6) as u64; // USDT 6 decimals
-
-                                        let bet_body = serde_json::json!({
-                                            "conditionId": condition_id,
-                                            "outcomeId": outcome_id,
-                                            "amount": amount_raw.to_string(),
-                                            "minOdds": min_odds.to_string(),
+                                        let min_odds = min_odds(azuro_odds, slippage_guard.factor_for_match(&anomaly.match_key));
+
+                                        let order = OrderRequest::new(Venue::Azuro, None, &condition_id, &outcome_id, amount, min_odds, Utc::now().timestamp());
+                                        let bet_context = serde_json::json!({
                                             "requestedOdds": azuro_odds,
                                             "matchKey": anomaly.match_key,
                                             "originalSport": anomaly.match_key.split("::").next().unwrap_or(""),
@@ -14127,10 +19863,9 @@ async fn main() -> Result<()> {
                                             "valueTeam": value_team,
                                         });
 
-                                        match client.post(format!("{}/bet", executor_url))
-                                            .json(&bet_body).send().await {
+                                        match send_executor_bet(&client, &executor_url, &order, bet_context, Duration::from_secs(EXECUTOR_BET_TIMEOUT_SECS)).await {
                                             Ok(resp) => {
-                                                match resp.json::<ExecutorBetResponse>().await {
+                                                match read_executor_receipt(resp, &order).await {
                                                     Ok(br) => {
                                                         let is_rejected = br.state.as_deref()
                                                             .map(|s| s == "Rejected" || s == "Failed" || s == "Cancelled")
@@ -14152,13 +19887,17 @@ async fn main() -> Result<()> {
                                                                 "state": br.state,
                                                                 "path": "bet_command"
                                                             }));
+                                                        } else if replay_already_booked(&br, &active_bets) {
+                                                            let _ = tg_send_message(&client, &token, chat_id,
+                                                                &format!("♻️ <b>BET #{} ALREADY PLACED</b>\n\nIdempotent replay, bet ID: <code>{}</code>", aid, br.bet_id.as_deref().unwrap_or("?"))
+                                                            ).await;
                                                         } else {
                                                             let bet_id = br.bet_id.as_deref().unwrap_or("?");
                                                             let state = br.state.as_deref().unwrap_or("?");
                                                             let token_id_opt = sanitize_token_id(br.token_id.clone());
                                                             let graph_bet_id_opt = br.graph_bet_id.clone();
                                                             let is_dry_run = state == "DRY-RUN" || bet_id.starts_with("dry-");
-                                                            let mut accepted_odds = br.accepted_odds.unwrap_or(azuro_odds);
+                                                            let mut accepted_odds = br.placed_odds.unwrap_or(azuro_odds);
                                                             if state == "Accepted" && !is_dry_run {
                                                                 if let Ok(status_resp) = client
                                                                     .get(format!("{}/bet/{}", executor_url, bet_id))
@@ -14209,6 +19948,7 @@ async fn main() -> Result<()> {
                                                                 already_bet_matches.insert(manual_market_dedup_key.clone());
                                                                 already_bet_conditions.insert(manual_scoped_condition.clone());
                                                                 already_bet_base_matches.insert(manual_base_match_key.clone());
+                                                                record_bet_game_id(anomaly.game_id.as_deref(), &condition_id, &mut already_bet_game_ids);
                                                                 if is_map_winner_market(&anomaly.match_key, &anomaly.market_key) {
                                                                     already_bet_map_winners.insert(manual_base_match_key.clone());
                                                                 }
@@ -14237,15 +19977,13 @@ async fn main() -> Result<()> {
                                                                     path: "bet_command".to_string(),
                                                                 };
                                                                 active_bets.push(active_bet.clone());
-                                                                append_pending_claim_entry(&active_bet, pending_claims_path);
-                                                                    if let Ok(mut f) = std::fs::OpenOptions::new()
-                                                                        .create(true).append(true)
-                                                                        .open(bet_history_path) {
-                                                                        use std::io::Write;
-                                                                        let _ = writeln!(f, "{}|{}|{}|{}|{}|{}",
-                                                                        anomaly.match_key, condition_id,
-                                                                        value_team, accepted_odds, Utc::now().to_rfc3339(), anomaly.market_key);
-                                                                    }
+                                                                append_pending_claim_entry(&active_bet, &*state_store);
+                                                                if let Err(e) = state_store.append_bet_history(&BetHistoryEntry::new(
+                                                                    &anomaly.match_key, &condition_id, value_team, accepted_odds,
+                                                                    &anomaly.market_key, anomaly.game_id.as_deref(),
+                                                                )) {
+                                                                    warn!("⚠️ BET #{} dedup persist failed: {}", aid, e);
+                                                                }
                                                                 // === LEDGER: BET PLACED (bet-command) ===
                                                                 ledger_write("PLACED", &serde_json::json!({
                                                                     "alert_id": aid, "bet_id": bet_id,
@@ -40,7 +40,7 @@ async fn main() -> Result<()> {
     for (t1, t2) in test_teams {
         // Zkusime evaluate. Pokud to neni v SX Betu, hodi to "No cached market" a projde to hned.
         // Pokud to je, spoji se to s Orderbookem a vypise to mozny edge.
-        let _ = arb.evaluate_esports_match(t1, t2, "test_sport", t1).await;
+        let _ = arb.evaluate_esports_match(t1, t2, "test_sport", t1, None).await;
     }
     
     tracing::info!("Dumping all ACTIVE SX Bet markets found in cache:");
@@ -14,6 +14,8 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
+use hltv_scraper::{EndpointAvailabilityTracker, EndpointProber};
+use esports_core::match_id::team_names_equivalent;
 use logger::EventLogger;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -71,6 +73,12 @@ pub struct LiveMatchPayload {
     pub detailed_score: Option<String>,
     pub status: Option<String>,
     pub url: Option<String>,
+    /// "bo1" / "bo3" / "bo5" — posílá scraper, který formát zná; Fortuna ho nese jen v názvu ligy
+    #[serde(default)]
+    pub series_format: Option<String>,
+    /// Název ligy/turnaje, pokud ho scraper zná (ligový filtr auto-betu v alert_bot)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub league: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -217,6 +225,8 @@ struct FeedHubState {
     ws_last_ok_ms: Arc<AtomicI64>,
     /// Epoch ms of last inbound SHADOW-WS text frame received
     ws_last_event_ms: Arc<AtomicI64>,
+    /// Rolling availability of HLTV/GosuGamers endpoints (written by EndpointProber, read by HTTP)
+    endpoint_availability: Arc<EndpointAvailabilityTracker>,
 }
 
 impl FeedHubState {
@@ -230,6 +240,7 @@ impl FeedHubState {
             gql_last_ok_ms: Arc::new(AtomicI64::new(0)),
             ws_last_ok_ms: Arc::new(AtomicI64::new(0)),
             ws_last_event_ms: Arc::new(AtomicI64::new(0)),
+            endpoint_availability: Arc::new(EndpointAvailabilityTracker::default()),
         }
     }
 }
@@ -970,6 +981,22 @@ fn sanitize_detailed_score(ds: &str) -> String {
     rest.to_string()
 }
 
+/// Series format ("bo3") z labelu ligy / turnaje: "IEM Cologne - Bo3", "Playoffs (Best of 5)".
+fn series_format_from_label(label: &str) -> Option<String> {
+    let words: Vec<String> = label.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+    words.iter().enumerate().find_map(|(i, w)| {
+        let n = match w.strip_prefix("bo") {
+            Some(n) => n,
+            None if w == "best" && words.get(i + 1).map(String::as_str) == Some("of") => words.get(i + 2)?,
+            None => return None,
+        };
+        matches!(n, "1" | "3" | "5").then(|| format!("bo{}", n))
+    })
+}
+
 /// Enrich detailed_score: if the new entry has None but the existing entry for the
 /// same key has Some(ds) that is fresh (< 120s), carry it forward.
 /// This prevents data loss when a source without detailed_score (e.g. Fortuna)
@@ -1628,10 +1655,10 @@ fn fuzzy_find_key<'a>(needle: &str, candidates: &'a [&str]) -> Option<&'a str> {
         // Check strong team equivalence in both orderings.
         // This handles truncated forms like "virginiacavalie" vs "virginiacavaliers"
         // and minor source-specific naming differences.
-        let a_match = team_name_equivalent(c_a, n_a);
-        let b_match = team_name_equivalent(c_b, n_b);
-        let a_rev_match = team_name_equivalent(c_a, n_b);
-        let b_rev_match = team_name_equivalent(c_b, n_a);
+        let a_match = team_names_equivalent(c_a, n_a);
+        let b_match = team_names_equivalent(c_b, n_b);
+        let a_rev_match = team_names_equivalent(c_a, n_b);
+        let b_rev_match = team_names_equivalent(c_b, n_a);
 
         let is_esports = matches!(sport, "cs2" | "dota-2" | "league-of-legends" | "valorant" | "esports");
 
@@ -1655,113 +1682,6 @@ fn fuzzy_find_key<'a>(needle: &str, candidates: &'a [&str]) -> Option<&'a str> {
     None
 }
 
-fn team_name_equivalent(a: &str, b: &str) -> bool {
-    if a == b {
-        return true;
-    }
-    if a.is_empty() || b.is_empty() {
-        return false;
-    }
-
-    // Fast-path: substring/suffix equivalence (existing behavior, expanded)
-    if a.contains(b) || b.contains(a) || a.ends_with(b) || b.ends_with(a) {
-        return true;
-    }
-
-    if one_edit_apart(a, b) {
-        return true;
-    }
-
-    let sim = bigram_jaccard_similarity(a, b);
-    let common_prefix = common_prefix_len(a, b);
-
-    // Strict threshold, with fallback when strong common prefix exists.
-    sim >= 0.70 || (sim >= 0.55 && common_prefix >= 4)
-}
-
-fn one_edit_apart(a: &str, b: &str) -> bool {
-    let ab = a.as_bytes();
-    let bb = b.as_bytes();
-    let al = ab.len();
-    let bl = bb.len();
-
-    if al == 0 || bl == 0 {
-        return false;
-    }
-    if al.abs_diff(bl) > 1 {
-        return false;
-    }
-
-    if al == bl {
-        let mut diffs = 0usize;
-        for i in 0..al {
-            if ab[i] != bb[i] {
-                diffs += 1;
-                if diffs > 1 {
-                    return false;
-                }
-            }
-        }
-        return diffs == 1;
-    }
-
-    let (shorter, longer) = if al < bl { (ab, bb) } else { (bb, ab) };
-    let mut i = 0usize;
-    let mut j = 0usize;
-    let mut edits = 0usize;
-    while i < shorter.len() && j < longer.len() {
-        if shorter[i] == longer[j] {
-            i += 1;
-            j += 1;
-        } else {
-            edits += 1;
-            if edits > 1 {
-                return false;
-            }
-            j += 1;
-        }
-    }
-    true
-}
-
-fn common_prefix_len(a: &str, b: &str) -> usize {
-    a.chars()
-        .zip(b.chars())
-        .take_while(|(x, y)| x == y)
-        .count()
-}
-
-fn bigram_jaccard_similarity(a: &str, b: &str) -> f64 {
-    if a == b {
-        return 1.0;
-    }
-
-    let ab = a.as_bytes();
-    let bb = b.as_bytes();
-
-    if ab.len() < 2 || bb.len() < 2 {
-        return 0.0;
-    }
-
-    let mut a_set: HashSet<Vec<u8>> = HashSet::new();
-    let mut b_set: HashSet<Vec<u8>> = HashSet::new();
-
-    for w in ab.windows(2) {
-        a_set.insert(w.to_vec());
-    }
-    for w in bb.windows(2) {
-        b_set.insert(w.to_vec());
-    }
-
-    if a_set.is_empty() || b_set.is_empty() {
-        return 0.0;
-    }
-
-    let intersection = a_set.intersection(&b_set).count() as f64;
-    let union = a_set.union(&b_set).count() as f64;
-    if union == 0.0 { 0.0 } else { intersection / union }
-}
-
 async fn canonicalize_match_key_ingest(state: &FeedHubState, candidate_key: &str) -> String {
     let live_r = state.live.read().await;
     let odds_r = state.odds.read().await;
@@ -2260,6 +2180,9 @@ async fn handle_fortuna_post(state: &FeedHubState, body_str: &str) -> (bool, Str
             let mut live_w = state.live.write().await;
             let existing = live_w.get(&key);
             let enriched_ds = enrich_detailed_score(&None, existing, now);
+            // Formát z ligy; jinak ponechat ten od zdroje, který ho zná (Fortuna ho jinak neposílá)
+            let series_format = m.league.as_deref().and_then(series_format_from_label)
+                .or_else(|| existing.and_then(|e| e.payload.series_format.clone()));
             let live_entry = LiveMatchState {
                 source: source.clone(),
                 seen_at: now,
@@ -2272,6 +2195,8 @@ async fn handle_fortuna_post(state: &FeedHubState, body_str: &str) -> (bool, Str
                     detailed_score: enriched_ds,
                     status: m.status.clone(),
                     url: None,
+                    series_format,
+                    league: m.league.clone(),
                 },
             };
             live_w.insert(key.clone(), live_entry);
@@ -2435,6 +2360,15 @@ async fn handle_http_connection(mut stream: TcpStream, state: FeedHubState) -> R
             let json = serde_json::to_string_pretty(&snap).unwrap_or_else(|_| "{}".to_string());
             ("HTTP/1.1 200 OK", "application/json; charset=utf-8", json)
         }
+        ("GET", "/endpoint-availability") => {
+            let snap = state.endpoint_availability.snapshot();
+            let json = serde_json::to_string_pretty(&snap).unwrap_or_else(|_| "{}".to_string());
+            ("HTTP/1.1 200 OK", "application/json; charset=utf-8", json)
+        }
+        ("GET", "/metrics") => {
+            let body = state.endpoint_availability.snapshot().render_prometheus();
+            ("HTTP/1.1 200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+        }
         ("GET", "/opportunities") => {
             let opps = build_opportunities(&state).await;
             let json = serde_json::to_string_pretty(&opps).unwrap_or_else(|_| "{}".to_string());
@@ -2467,7 +2401,7 @@ async fn handle_http_connection(mut stream: TcpStream, state: FeedHubState) -> R
 
 async fn start_http_server(state: FeedHubState, bind: SocketAddr) -> Result<()> {
     let listener = TcpListener::bind(bind).await.context("http bind")?;
-    info!("feed-hub http listening on http://{} (GET /health, /state, /opportunities; POST /fortuna)", bind);
+    info!("feed-hub http listening on http://{} (GET /health, /state, /opportunities, /endpoint-availability, /metrics; POST /fortuna)", bind);
 
     loop {
         let (stream, peer) = listener.accept().await.context("http accept")?;
@@ -2757,6 +2691,22 @@ async fn main() -> Result<()> {
     info!("feed-hub DB: {}", db_path);
     let db_tx = spawn_db_writer(DbConfig { path: db_path });
 
+    // HLTV/GosuGamers endpoint availability prober (ENDPOINT_PROBE_SECS=0 → off)
+    {
+        let probe_secs = std::env::var("ENDPOINT_PROBE_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(300);
+        if probe_secs > 0 {
+            let prober = EndpointProber::with_default_endpoints()
+                .with_tracker(Arc::clone(&state.endpoint_availability));
+            info!("endpoint prober every {}s", probe_secs);
+            tokio::spawn(prober.run(Duration::from_secs(probe_secs)));
+        } else {
+            info!("endpoint prober disabled (ENDPOINT_PROBE_SECS=0)");
+        }
+    }
+
     // Minimal HTTP read-only state endpoint
     {
         let http_bind = std::env::var("FEED_HTTP_BIND").unwrap_or_else(|_| "127.0.0.1:8081".to_string());
@@ -2866,7 +2816,17 @@ async fn main() -> Result<()> {
         });
     }
 
-    while let Ok((stream, peer)) = listener.accept().await {
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(_) => break,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                info!("Ctrl+C → feed-hub stopping");
+                break;
+            }
+        };
         let state = state.clone();
         let logger = Arc::clone(&logger);
         let db_tx = db_tx.clone();
@@ -2878,5 +2838,8 @@ async fn main() -> Result<()> {
         });
     }
 
+    if let Err(e) = logger.flush() {
+        warn!("log flush failed: {e}");
+    }
     Ok(())
 }
@@ -2,7 +2,7 @@
 //! Spustit: cargo run --bin hltv-test
 
 use anyhow::Result;
-use hltv_scraper::{HltvScraper, HltvLiveMatch};
+use hltv_scraper::{probe_chrome_available, HltvScraper, HltvLiveMatch};
 use tokio::sync::mpsc;
 use tokio::time::{Duration, Instant};
 use tracing::{info, warn, Level};
@@ -18,7 +18,8 @@ async fn main() -> Result<()> {
     
     info!("🚀 HLTV Scraper Test - Target latency <15s");
     
-    let mut scraper = HltvScraper::new();
+    let chrome_available = probe_chrome_available().await;
+    let mut scraper = HltvScraper::new().with_browser_available(chrome_available);
 
     info!("🩺 Probing HLTV endpoints...");
     for probe_url in ["https://www.hltv.org/live", "https://www.hltv.org/results"] {
@@ -124,7 +125,7 @@ async fn main() -> Result<()> {
     
     // Spusť monitoring v background task
     let monitor_handle = tokio::spawn(async move {
-        let mut scraper = HltvScraper::new();
+        let mut scraper = HltvScraper::new().with_browser_available(chrome_available);
         
         if let Err(e) = scraper.monitor_live_matches(move |match_data| {
             if let Err(_) = tx.blocking_send(match_data) {
@@ -10,17 +10,25 @@
 ///
 /// Spuštění:
 ///   cargo run --bin live-observer
+///   cargo run --bin live-observer -- --selftest   (probe všech zdrojů, exit 1 = kritický zdroj down)
 
 use anyhow::Result;
 use dotenv::dotenv;
 use esports_monitor::EsportsMonitor;
 use arb_detector::ArbDetector;
+use logger::{EventLogger, RecentEvents};
+use observer::{run_live_value, run_observer, run_schedule_prewarm, ObserverConfig};
+use price_monitor::{oddsapi_sports_from_env, PriceMonitor};
 use std::env;
 use std::fs::File;
 use tokio::time::{sleep, Duration};
 use tracing::{info, warn};
+use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{EnvFilter, fmt};
 
+mod observer;
+mod selftest;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
@@ -32,31 +40,48 @@ async fn main() -> Result<()> {
         )
         .init();
 
+    // Selftest běží bez single-instance locku (jde spustit vedle běžícího observeru)
+    if env::args().any(|a| a == "--selftest") {
+        let monitor = EsportsMonitor::new("logs", 15)
+            .with_chrome_available(hltv_scraper::probe_chrome_available().await);
+        let arb = ArbDetector::new("logs", true);
+        let code = selftest::run_observer_selftest(&monitor, &arb, &EventLogger::new("logs")).await;
+        std::process::exit(code);
+    }
+
     info!("=== RustMiskoLive Observer — LIVE SCORING ACTIVE ===");
     info!("Mode: OBSERVE ONLY (no trades)");
     info!("Strategy: Live match state machine → SX Bet oracle lag detection");
     info!("Logs: ./logs/");
 
-    // Single instance lock
-    let lock_file_path = env::temp_dir().join("rustmiskolive_esports.lock");
-    let lock_file = match File::create(&lock_file_path) {
-        Ok(f) => f,
-        Err(e) => {
-            warn!("Failed to create lock file at {:?}: {}", lock_file_path, e);
-            return Ok(());
+    // Single instance lock (SINGLE_INSTANCE_LOCK=0 vypne — kontejnery / sdílený tmpfs v testech)
+    let single_instance_lock = env::var("SINGLE_INSTANCE_LOCK")
+        .map(|v| v.trim() != "0")
+        .unwrap_or(true);
+    let mut lock = if single_instance_lock {
+        let lock_file_path = env::temp_dir().join("rustmiskolive_esports.lock");
+        match File::create(&lock_file_path) {
+            Ok(f) => Some(fd_lock::RwLock::new(f)),
+            Err(e) => {
+                warn!("Failed to create lock file at {:?}: {}", lock_file_path, e);
+                return Ok(());
+            }
         }
+    } else {
+        info!("Single-instance lock disabled (SINGLE_INSTANCE_LOCK=0).");
+        None
     };
 
-    let mut lock = fd_lock::RwLock::new(lock_file);
-    let _write_guard = match lock.try_write() {
-        Ok(guard) => {
+    let _write_guard = match lock.as_mut().map(|l| l.try_write()) {
+        Some(Ok(guard)) => {
             info!("Acquired single-instance lock.");
-            guard
+            Some(guard)
         }
-        Err(_) => {
+        Some(Err(_)) => {
             warn!("Another instance of live-observer is already running! Exiting.");
             return Ok(());
         }
+        None => None,
     };
 
     let poll_interval_secs = env::var("ESPORTS_POLL_INTERVAL_SECS")
@@ -64,9 +89,16 @@ async fn main() -> Result<()> {
         .and_then(|v| v.parse::<u64>().ok())
         .unwrap_or(15);
 
-    info!("Live poll interval: {}s", poll_interval_secs);
+    // Sniper mode (3s) jen když je nějaký live zápas blízko konce — ne při každém live zápasu
+    let sniper_min_confidence = env::var("SNIPER_MIN_CONFIDENCE")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(0.85);
+
+    info!("Live poll interval: {}s (sniper min confidence {:.2})", poll_interval_secs, sniper_min_confidence);
 
-    let monitor = EsportsMonitor::new("logs", poll_interval_secs);
+    let monitor = EsportsMonitor::new("logs", poll_interval_secs)
+        .with_chrome_available(hltv_scraper::probe_chrome_available().await);
     let arb = ArbDetector::new("logs", true);
 
     // Spustit STRATZ WebSocket na dotu 2
@@ -77,39 +109,72 @@ async fn main() -> Result<()> {
     arb.debug_print_cache().await;
     info!("🚀 READY: Spouštím live scoring loop.");
 
-    let mut fallback_counter: u32 = 0;
-
-    loop {
-        info!("--- Live poll cycle ---");
-
-        // PRIMÁRNÍ: live match tracking → detekuje právě dokončené zápasy
-        let live_finished = monitor.poll_live_all().await;
-        for m in &live_finished {
-            if let Err(e) = arb.evaluate_esports_match(&m.home, &m.away, &m.sport, &m.winner).await {
-                warn!("SX Bet eval failed pro {}: {}", m.match_name, e);
-            }
-        }
+    let config = ObserverConfig {
+        poll_interval:         Duration::from_secs(poll_interval_secs),
+        sniper_interval:       Duration::from_secs(3),
+        sniper_min_confidence,
+        fallback_every:        20, // 20 × 15s = 5 minut
+        log_dir:               "logs".into(),
+        stats_path:            env::var("PREDICTION_STATS_PATH")
+            .unwrap_or_else(|_| "logs/prediction_accuracy.json".to_string())
+            .into(),
+    };
 
-        // FALLBACK: results scraping jednou za ~5 minut (audit)
-        // Chytá zápasy co mohly proběhnout bez live detekce (restart bota atd.)
-        fallback_counter += 1;
-        if fallback_counter >= 20 {  // 20 × 15s = 5 minut
-            fallback_counter = 0;
-            info!("--- Fallback results audit ---");
-            let fallback = monitor.poll_all().await;
-            for m in fallback {
-                if let Err(e) = arb.evaluate_esports_match(&m.home, &m.away, &m.sport, &m.winner).await {
-                    warn!("Fallback SX Bet eval failed pro {}: {}", m.match_name, e);
-                }
+    // Ctrl+C → doběhne aktuální iterace a loop skončí
+    let cancel = CancellationToken::new();
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                info!("Ctrl+C → stopping observer loop");
+                cancel.cancel();
             }
         }
+    });
 
-        let current_interval = if monitor.is_any_match_live() {
-            3 // 🚀 Sniper mode!
-        } else {
-            poll_interval_secs // Běžný audit timing
-        };
+    // LoL getSchedule → SX markety nadcházejících zápasů v cache ještě před resolved
+    let prewarm_every = env::var("LOL_SCHEDULE_PREWARM_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(600);
 
-        sleep(Duration::from_secs(current_interval)).await;
-    }
+    // Live value betting (opt-in): Pinnacle fair value z PriceMonitoru proti SX cache i před koncem zápasu
+    let live_value = env::var("LIVE_VALUE").is_ok_and(|v| matches!(v.trim(), "1" | "true"));
+    let live_value_every = env::var("LIVE_VALUE_POLL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    let pinnacle_lines = RecentEvents::new(512);
+    let prices = live_value.then(|| {
+        info!("Live value: Pinnacle vs SX každých {}s", live_value_every);
+        PriceMonitor::new(
+            "logs",
+            env::var("PINNACLE_API_KEY").ok(),
+            env::var("ODDS_API_KEY").ok(),
+            1.0,
+            live_value_every,
+        )
+        .with_oddsapi_sports(oddsapi_sports_from_env())
+        .with_recent_events(pinnacle_lines.clone())
+    });
+
+    // LoL getLive ve vlastním tasku — Riot limit nezávisí na cadence Sniper mode
+    let lol_cancel = cancel.clone();
+    let live_value_cancel = cancel.clone();
+    tokio::join!(
+        run_observer(&monitor, &arb, &config, cancel.clone()),
+        run_schedule_prewarm(&monitor, &arb, Duration::from_secs(prewarm_every), cancel),
+        async {
+            if let Some(prices) = &prices {
+                run_live_value(prices, &arb, &pinnacle_lines, Duration::from_secs(live_value_every), live_value_cancel).await;
+            }
+        },
+        async {
+            tokio::select! {
+                _ = monitor.run_lol_poller() => {}
+                _ = lol_cancel.cancelled() => {}
+            }
+        },
+    );
+    Ok(())
 }
@@ -0,0 +1,461 @@
+//! Live scoring loop observeru, oddělený od `main` (lock, warmup) — testy ho volají přímo
+//! s fake zdroji a ukončí ho přes CancellationToken.
+
+use anyhow::Result;
+use arb_detector::ArbDetector;
+use esports_core::match_id::team_names_equivalent;
+use esports_core::MatchId;
+use esports_monitor::EsportsMonitor;
+use logger::{EventLogger, MatchResolvedEvent, PredictionCalibrationEvent, PredictionEvent, PredictionInputs, RecentEvents, now_iso};
+use prediction_engine::{Prediction, PredictionEngine, Sport};
+use price_monitor::PriceMonitor;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Zdroj live/fallback výsledků (produkčně `EsportsMonitor`).
+pub trait LiveSource {
+    async fn poll_live(&self) -> Vec<MatchResolvedEvent>;
+    async fn poll_fallback(&self) -> Vec<MatchResolvedEvent>;
+    /// Sniper mode: aspoň jeden live zápas má predikci >= threshold
+    fn sniper_ready(&self, threshold: f32) -> bool;
+    /// Aktuální predikce live zápasů (PREDICTION eventy)
+    fn live_predictions(&self) -> Vec<PredictionEvent> {
+        Vec::new()
+    }
+    /// Dopíše bufferované logy při ukončení loopu
+    fn flush_logs(&self) {}
+}
+
+/// Vyhodnocení resolved zápasu proti trhu (produkčně `ArbDetector` → SX Bet).
+pub trait MatchEvaluator {
+    async fn evaluate(&self, m: &MatchResolvedEvent) -> Result<()>;
+    fn flush_logs(&self) {}
+}
+
+impl LiveSource for EsportsMonitor {
+    async fn poll_live(&self) -> Vec<MatchResolvedEvent> {
+        self.poll_live_all().await
+    }
+
+    async fn poll_fallback(&self) -> Vec<MatchResolvedEvent> {
+        self.poll_all().await
+    }
+
+    fn sniper_ready(&self, threshold: f32) -> bool {
+        self.has_conclusive_or_near_match(threshold)
+    }
+
+    fn live_predictions(&self) -> Vec<PredictionEvent> {
+        EsportsMonitor::live_predictions(self)
+    }
+
+    fn flush_logs(&self) {
+        EsportsMonitor::flush_logs(self)
+    }
+}
+
+impl MatchEvaluator for ArbDetector {
+    async fn evaluate(&self, m: &MatchResolvedEvent) -> Result<()> {
+        let result = self.evaluate_esports_match(&m.home, &m.away, &m.sport, &m.winner, m.source_match_id.as_deref()).await;
+        // PAPER_SETTLEMENT: až po evalu, ať se vyhodnotí i dry-run sázka z oracle lag na tentýž zápas
+        self.settle_paper_bets(m);
+        result
+    }
+
+    fn flush_logs(&self) {
+        ArbDetector::flush_logs(self)
+    }
+}
+
+pub struct ObserverConfig {
+    pub poll_interval:         Duration,
+    /// Interval v Sniper mode (live zápas blízko konce)
+    pub sniper_interval:       Duration,
+    pub sniper_min_confidence: f32,
+    /// Fallback results audit každých N ticků
+    pub fallback_every:        u32,
+    pub log_dir:               PathBuf,
+    /// Persistovaná accuracy predikcí
+    pub stats_path:            PathBuf,
+}
+
+/// Live scoring loop. Běží dokud není `cancel` zrušen, vrací počet odjetých iterací.
+pub async fn run_observer<S: LiveSource, E: MatchEvaluator>(
+    monitor: &S,
+    arb: &E,
+    config: &ObserverConfig,
+    cancel: CancellationToken,
+) -> u64 {
+    // Kalibrace predikcí: MATCH_RESOLVED = ground truth → record_outcome, accuracy přežije restart
+    let mut calibrator = PredictionEngine::with_stats_path(&config.stats_path);
+    let calibration_logger = EventLogger::new(&config.log_dir);
+    info!("Prediction calibrator: {} evaluated, stats={}", calibrator.accuracy().evaluated, config.stats_path.display());
+
+    let mut fallback_counter: u32 = 0;
+    let mut iterations: u64 = 0;
+    // match_id → naposledy zalogovaná (predikce, vstupy); PREDICTION jen při změně
+    let mut last_predictions: HashMap<String, (Prediction, PredictionInputs)> = HashMap::new();
+
+    while !cancel.is_cancelled() {
+        info!("--- Live poll cycle ---");
+
+        // PRIMÁRNÍ: live match tracking → detekuje právě dokončené zápasy
+        let live_finished = monitor.poll_live().await;
+        for m in &live_finished {
+            if let Err(e) = arb.evaluate(m).await {
+                warn!("SX Bet eval failed pro {}: {}", m.match_name, e);
+            }
+            feed_outcome(&mut calibrator, m);
+        }
+
+        record_predictions(&mut calibrator, &calibration_logger, &mut last_predictions, monitor.live_predictions());
+
+        // FALLBACK: results scraping jednou za ~5 minut (audit)
+        // Chytá zápasy co mohly proběhnout bez live detekce (restart bota atd.)
+        fallback_counter += 1;
+        if fallback_counter >= config.fallback_every {
+            fallback_counter = 0;
+            info!("--- Fallback results audit ---");
+            let fallback = monitor.poll_fallback().await;
+            for m in fallback {
+                if let Err(e) = arb.evaluate(&m).await {
+                    warn!("Fallback SX Bet eval failed pro {}: {}", m.match_name, e);
+                }
+                feed_outcome(&mut calibrator, &m);
+            }
+
+            let stats = calibrator.accuracy();
+            let _ = calibration_logger.log(&PredictionCalibrationEvent {
+                ts:              now_iso(),
+                event:           "PREDICTION_CALIBRATION",
+                evaluated:       stats.evaluated,
+                correct:         stats.correct,
+                accuracy:        stats.accuracy(),
+                avg_confidence:  stats.avg_confidence(),
+                pending_matches: calibrator.pending_matches(),
+            });
+        }
+
+        iterations += 1;
+
+        let current_interval = if monitor.sniper_ready(config.sniper_min_confidence) {
+            config.sniper_interval // 🚀 Sniper mode!
+        } else {
+            config.poll_interval // Běžný audit timing
+        };
+
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = sleep(current_interval) => {}
+        }
+    }
+
+    // Graceful shutdown: bufferované JSONL řádky na disk dřív, než proces skončí
+    if let Err(e) = calibration_logger.flush() {
+        warn!("Flush calibration logu selhal: {}", e);
+    }
+    monitor.flush_logs();
+    arb.flush_logs();
+    info!("Observer loop stopped after {} iterations", iterations);
+    iterations
+}
+
+/// LoL rozpis → předehřátí SX cache každých `every`, dokud není `cancel` zrušen.
+/// Chyba getSchedule jen warnuje; background sync cache plní dál obecně.
+pub async fn run_schedule_prewarm(monitor: &EsportsMonitor, arb: &ArbDetector, every: Duration, cancel: CancellationToken) {
+    while !cancel.is_cancelled() {
+        match monitor.poll_lol_schedule().await {
+            Ok(upcoming) if !upcoming.is_empty() => {
+                arb.prewarm_markets(&upcoming).await;
+            }
+            Ok(_) => {}
+            Err(e) => warn!("LoL schedule prewarm failed: {}", e),
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = sleep(every) => {}
+        }
+    }
+}
+
+/// Live value betting (LIVE_VALUE=1): PriceMonitor poll → PINNACLE_LINE do `lines` → Pinnacle fair value
+/// proti nacachovaným SX marketům, každých `every`, dokud není `cancel` zrušen.
+pub async fn run_live_value(prices: &PriceMonitor, arb: &ArbDetector, lines: &RecentEvents, every: Duration, cancel: CancellationToken) {
+    while !cancel.is_cancelled() {
+        prices.poll_all().await;
+        let evaluated = arb.evaluate_live_value(lines).await;
+        if evaluated > 0 {
+            info!("Live value: {} SX marketů proti Pinnacle fair value", evaluated);
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = sleep(every) => {}
+        }
+    }
+}
+
+/// Nové / změněné live predikce → PREDICTION event + kalibrátor (vyhodnotí je pozdější MATCH_RESOLVED).
+fn record_predictions(
+    calibrator: &mut PredictionEngine,
+    logger: &EventLogger,
+    last: &mut HashMap<String, (Prediction, PredictionInputs)>,
+    predictions: Vec<PredictionEvent>,
+) {
+    last.retain(|id, _| predictions.iter().any(|p| &p.match_id == id));
+    for mut p in predictions {
+        let unchanged = last.get(&p.match_id)
+            .is_some_and(|(prediction, inputs)| *prediction == p.prediction && *inputs == p.inputs);
+        if unchanged {
+            continue;
+        }
+        calibrator.log_team_prediction(&p.match_id, &p.home, &p.away, p.prediction.clone());
+        // Do accuracy jde surová heuristika, PREDICTION nese kalibrovanou confidence
+        if let Some(sport) = Sport::from_label(&p.sport) {
+            p.confidence = calibrator.calibrate(&sport, p.prediction.clone()).confidence();
+        }
+        if let Err(e) = logger.log(&p) {
+            warn!("PREDICTION log pro {} selhal: {}", p.match_id, e);
+        }
+        last.insert(p.match_id, (p.prediction, p.inputs));
+    }
+}
+
+/// Klíč výsledku pro kalibraci = `PredictionEvent.match_id` (`MatchId::key`, "cs2::a_vs_b");
+/// `match_name` zdroje výsledku ho nenese, chybí-li `match_id`, dopočte se z týmů.
+fn calibration_key(m: &MatchResolvedEvent) -> Option<String> {
+    m.match_id.clone().or_else(|| MatchId::from_labels(&m.sport, &m.home, &m.away).map(|id| id.key()))
+}
+
+/// Strana vítěze ("team1" / "team2") vůči týmům predikce, ne vůči home/away zdroje výsledku —
+/// ten může mít pořadí prohozené (např. GosuGamers fallback). None pro neznámého / nejednoznačného vítěze.
+fn predicted_winner_side(calibrator: &PredictionEngine, key: &str, winner: &str) -> Option<&'static str> {
+    let (team1, team2) = calibrator.prediction_teams(key)?;
+    match (team_names_equivalent(team1, winner), team_names_equivalent(team2, winner)) {
+        (true, false) => Some("team1"),
+        (false, true) => Some("team2"),
+        _ => None,
+    }
+}
+
+/// Resolved zápas → vyhodnoť zalogované predikce a ulož accuracy.
+fn feed_outcome(calibrator: &mut PredictionEngine, m: &MatchResolvedEvent) {
+    let (Some(key), Some(sport)) = (calibration_key(m), Sport::from_label(&m.sport)) else {
+        return;
+    };
+    let Some(side) = predicted_winner_side(calibrator, &key, &m.winner) else {
+        return;
+    };
+    if let Some(hit) = calibrator.record_sport_outcome(&sport, &key, side) {
+        let (correct, evaluated) = calibrator.get_accuracy_stats();
+        info!("🎯 Prediction {} pro {}: accuracy {}/{}",
+            if hit { "HIT" } else { "MISS" }, m.match_name, correct, evaluated);
+        if let Err(e) = calibrator.save_stats() {
+            warn!("Prediction stats save failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod observer_loop_tests {
+    use super::{feed_outcome, record_predictions, run_observer, LiveSource, MatchEvaluator, ObserverConfig};
+    use logger::{EventLogger, EventReader, MatchResolvedEvent, PredictionEvent, PredictionInputs, now_iso};
+    use prediction_engine::{Prediction, PredictionEngine, Sport};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    struct FakeSource {
+        polls:  AtomicU32,
+        cancel: CancellationToken,
+    }
+
+    impl LiveSource for FakeSource {
+        async fn poll_live(&self) -> Vec<MatchResolvedEvent> {
+            let n = self.polls.fetch_add(1, Ordering::SeqCst) + 1;
+            if n >= 2 {
+                self.cancel.cancel();
+            }
+            if n == 1 {
+                vec![MatchResolvedEvent {
+                    ts:         now_iso(),
+                    event:      "MATCH_RESOLVED",
+                    sport:      "valorant".to_string(),
+                    match_name: "Alpha vs Beta".to_string(),
+                    home:       "Alpha".to_string(),
+                    away:       "Beta".to_string(),
+                    winner:     "Alpha".to_string(),
+                    ended_at:   now_iso(),
+                    source_match_id: None,
+                    match_id:        None,
+                }]
+            } else {
+                vec![]
+            }
+        }
+
+        async fn poll_fallback(&self) -> Vec<MatchResolvedEvent> {
+            vec![]
+        }
+
+        fn sniper_ready(&self, _threshold: f32) -> bool {
+            false
+        }
+
+        fn live_predictions(&self) -> Vec<PredictionEvent> {
+            vec![PredictionEvent {
+                ts:         now_iso(),
+                event:      "PREDICTION",
+                match_id:   "valorant::delta_vs_gamma".to_string(),
+                sport:      "valorant".to_string(),
+                home:       "Gamma".to_string(),
+                away:       "Delta".to_string(),
+                prediction: Prediction::Team1Win(0.9),
+                confidence: Some(0.9),
+                inputs:     PredictionInputs {
+                    map_score:       Some((12, 3)),
+                    series_score:    None,
+                    map_number:      1,
+                    best_of:         None,
+                    score_history:   vec![(12, 3)],
+                },
+            }]
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeEvaluator {
+        evaluated: AtomicU32,
+    }
+
+    impl MatchEvaluator for FakeEvaluator {
+        async fn evaluate(&self, _m: &MatchResolvedEvent) -> anyhow::Result<()> {
+            self.evaluated.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("no SX Bet market"))
+        }
+    }
+
+    #[tokio::test]
+    async fn loop_runs_two_iterations_and_stops_on_token() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_observer_loop_{}", std::process::id()));
+        let cancel = CancellationToken::new();
+        let source = FakeSource { polls: AtomicU32::new(0), cancel: cancel.clone() };
+        let evaluator = FakeEvaluator::default();
+        let config = ObserverConfig {
+            poll_interval:         Duration::from_millis(10),
+            sniper_interval:       Duration::from_millis(5),
+            sniper_min_confidence: 0.85,
+            fallback_every:        20,
+            log_dir:               dir.clone(),
+            stats_path:            dir.join("prediction_accuracy.json"),
+        };
+
+        let iterations = tokio::time::timeout(
+            Duration::from_secs(5),
+            run_observer(&source, &evaluator, &config, cancel),
+        ).await.expect("loop must exit via cancellation token");
+
+        assert_eq!(iterations, 2);
+        assert_eq!(source.polls.load(Ordering::SeqCst), 2);
+        assert_eq!(evaluator.evaluated.load(Ordering::SeqCst), 1);
+
+        // Stejná predikce ve dvou cyklech → jediný PREDICTION řádek
+        let predictions: Vec<serde_json::Value> = EventReader::new(&dir).read_all("PREDICTION").unwrap();
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions[0]["inputs"]["map_score"], serde_json::json!([12, 3]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resolved_match_closes_calibration_loop_for_logged_prediction() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_observer_calibration_{}", std::process::id()));
+        let mut calibrator = PredictionEngine::with_stats_path(dir.join("prediction_accuracy.json"));
+        let logger = EventLogger::new(&dir);
+        let mut last = HashMap::new();
+
+        let source = FakeSource { polls: AtomicU32::new(0), cancel: CancellationToken::new() };
+        record_predictions(&mut calibrator, &logger, &mut last, source.live_predictions());
+        assert_eq!(calibrator.pending_matches(), 1);
+
+        // Zdroj výsledku nese vlastní match_name, ne klíč predikce
+        feed_outcome(&mut calibrator, &MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "valorant".to_string(),
+            match_name: "12345-gamma-vs-delta".to_string(),
+            home:       "Gamma".to_string(),
+            away:       "Delta".to_string(),
+            winner:     "Gamma".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        });
+        assert_eq!(calibrator.accuracy().evaluated, 1);
+        assert_eq!(calibrator.accuracy().correct, 1);
+        assert_eq!(calibrator.pending_matches(), 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn result_source_with_swapped_team_order_is_scored_by_team_name() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_observer_swapped_{}", std::process::id()));
+        let mut calibrator = PredictionEngine::new();
+        let logger = EventLogger::new(&dir);
+        let mut last = HashMap::new();
+
+        // Predikce: team1 = Gamma vyhraje
+        let source = FakeSource { polls: AtomicU32::new(0), cancel: CancellationToken::new() };
+        record_predictions(&mut calibrator, &logger, &mut last, source.live_predictions());
+        assert_eq!(calibrator.prediction_teams("valorant::delta_vs_gamma"), Some(("Gamma", "Delta")));
+
+        // Zdroj výsledku má týmy obráceně (home = Delta), vyhrála Gamma → trefa, ne miss
+        feed_outcome(&mut calibrator, &MatchResolvedEvent {
+            ts:         now_iso(),
+            event:      "MATCH_RESOLVED",
+            sport:      "valorant".to_string(),
+            match_name: "delta_vs_gamma".to_string(),
+            home:       "Delta".to_string(),
+            away:       "gamma".to_string(),
+            winner:     "gamma".to_string(),
+            ended_at:   now_iso(),
+            source_match_id: None,
+            match_id:        None,
+        });
+        assert_eq!(calibrator.accuracy().evaluated, 1);
+        assert_eq!(calibrator.accuracy().correct, 1);
+        assert_eq!(calibrator.prediction_teams("valorant::delta_vs_gamma"), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn logged_prediction_carries_calibrated_confidence() {
+        let dir = std::env::temp_dir().join(format!("rustmisko_observer_calibrated_{}", std::process::id()));
+        let mut calibrator = PredictionEngine::new().with_min_calibration_samples(2);
+        // Valorant vzorek: 1 trefa ze 2 při confidence 0.8 → faktor 0.625
+        for (match_id, winner) in [("a_vs_b", "team1"), ("c_vs_d", "team2")] {
+            calibrator.log_prediction(match_id, Prediction::Team1Win(0.8));
+            calibrator.record_sport_outcome(&Sport::Valorant, match_id, winner);
+        }
+        let logger = EventLogger::new(&dir);
+        let mut last = HashMap::new();
+
+        let source = FakeSource { polls: AtomicU32::new(0), cancel: CancellationToken::new() };
+        record_predictions(&mut calibrator, &logger, &mut last, source.live_predictions());
+        logger.flush().expect("PREDICTION log flushed");
+
+        let predictions: Vec<serde_json::Value> = EventReader::new(&dir).read_all("PREDICTION").unwrap();
+        assert_eq!(predictions.len(), 1);
+        let conf = predictions[0]["confidence"].as_f64().unwrap();
+        assert!((conf - 0.9 * 0.625).abs() < 1e-4, "calibrated confidence {}", conf);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,174 @@
+//! Selftest pro `live-observer --selftest`
+//!
+//! Každý externí zdroj se zkusí jednou (status + latence), výsledek se zaloguje
+//! jako ApiStatusEvent (scope "selftest"). Exit code != 0, pokud padl kritický zdroj.
+
+use anyhow::Result;
+use arb_detector::ArbDetector;
+use esports_monitor::EsportsMonitor;
+use hltv_scraper::HltvScraper;
+use logger::{ApiStatusEvent, EventLogger, now_iso};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+use tokio::time::{timeout, Duration};
+use tracing::{info, warn};
+
+/// Max doba jednoho probe (Chrome pro GosuGamers potřebuje ~10s).
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub type ProbeFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + 'a>>;
+
+/// Jeden zdroj k otestování — fetcher je injektovaný (v testech fake).
+pub struct SourceProbe<'a> {
+    pub source:   &'static str,
+    pub critical: bool,
+    pub fetch:    ProbeFuture<'a>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbeReport {
+    pub source:     &'static str,
+    pub critical:   bool,
+    pub ok:         bool,
+    pub latency_ms: u64,
+    pub detail:     String,
+}
+
+/// Spustí probe sekvenčně (ať se zdroje navzájem neovlivňují latencí).
+pub async fn run_probes(probes: Vec<SourceProbe<'_>>, probe_timeout: Duration) -> Vec<ProbeReport> {
+    let mut reports = Vec::with_capacity(probes.len());
+    for probe in probes {
+        let started = Instant::now();
+        let result = match timeout(probe_timeout, probe.fetch).await {
+            Ok(r) => r,
+            Err(_) => Err(anyhow::anyhow!("timeout after {}s", probe_timeout.as_secs())),
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let (ok, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, format!("{:#}", e)),
+        };
+        reports.push(ProbeReport { source: probe.source, critical: probe.critical, ok, latency_ms, detail });
+    }
+    reports
+}
+
+/// 0 = všechny kritické zdroje OK, 1 = aspoň jeden kritický zdroj down.
+pub fn exit_code(reports: &[ProbeReport]) -> i32 {
+    if reports.iter().any(|r| r.critical && !r.ok) { 1 } else { 0 }
+}
+
+fn log_reports(logger: &EventLogger, reports: &[ProbeReport]) {
+    for r in reports {
+        let mark = if r.ok { "✅" } else if r.critical { "❌" } else { "⚠️" };
+        let tier = if r.critical { "critical" } else { "optional" };
+        if r.ok {
+            info!("{} {:<16} {:>6}ms  [{}] {}", mark, r.source, r.latency_ms, tier, r.detail);
+        } else {
+            warn!("{} {:<16} {:>6}ms  [{}] {}", mark, r.source, r.latency_ms, tier, r.detail);
+        }
+        let _ = logger.log(&ApiStatusEvent {
+            ts:           now_iso(),
+            event:        "API_STATUS",
+            source:       r.source.to_string(),
+            scope:        "selftest".to_string(),
+            ok:           r.ok,
+            status_code:  None,
+            message:      format!("{}ms {}", r.latency_ms, r.detail),
+            items_logged: 0,
+            parser_suspect: false,
+        });
+    }
+}
+
+/// Probe všech zdrojů observeru, vrací exit code.
+pub async fn run_observer_selftest(monitor: &EsportsMonitor, arb: &ArbDetector, logger: &EventLogger) -> i32 {
+    let mut hltv = HltvScraper::new().with_browser_available(monitor.chrome_available());
+    let hltv = &mut hltv;
+
+    let probes = vec![
+        SourceProbe { source: "lolesports",     critical: true,  fetch: Box::pin(monitor.probe_source("lolesports")) },
+        SourceProbe { source: "vlr.gg",         critical: true,  fetch: Box::pin(monitor.probe_source("vlr")) },
+        SourceProbe { source: "gosugamers",     critical: true,  fetch: Box::pin(monitor.probe_source("gosugamers")) },
+        SourceProbe { source: "sx_bet",         critical: true,  fetch: Box::pin(arb.probe_source("sx_bet")) },
+        SourceProbe { source: "azuro_thegraph", critical: true,  fetch: Box::pin(arb.probe_source("azuro_thegraph")) },
+        SourceProbe { source: "arbitrum_rpc",   critical: false, fetch: Box::pin(arb.probe_source("arbitrum_rpc")) },
+        SourceProbe { source: "polygon_rpc",    critical: false, fetch: Box::pin(arb.probe_source("polygon_rpc")) },
+        SourceProbe {
+            source:   "hltv",
+            critical: false,
+            fetch:    Box::pin(async move {
+                let probe = hltv.probe_endpoint("https://www.hltv.org/matches").await?;
+                if probe.looks_like_challenge_page {
+                    anyhow::bail!("challenge page ({} bytes)", probe.html_len);
+                }
+                Ok(format!("{} bytes, {} match ids", probe.html_len, probe.match_id_count))
+            }),
+        },
+    ];
+
+    info!("🩺 SELFTEST: probing {} sources (timeout {}s each)...", probes.len(), PROBE_TIMEOUT.as_secs());
+    let reports = run_probes(probes, PROBE_TIMEOUT).await;
+    log_reports(logger, &reports);
+
+    let code = exit_code(&reports);
+    let failed: Vec<&str> = reports.iter().filter(|r| !r.ok).map(|r| r.source).collect();
+    if code == 0 {
+        info!("🩺 SELFTEST PASSED ({} / {} sources OK)", reports.len() - failed.len(), reports.len());
+    } else {
+        warn!("🩺 SELFTEST FAILED — down: {}", failed.join(", "));
+    }
+    code
+}
+
+#[cfg(test)]
+mod selftest_tests {
+    use super::{exit_code, run_probes, SourceProbe};
+    use tokio::time::Duration;
+
+    fn fake(source: &'static str, critical: bool, result: Result<&'static str, &'static str>) -> SourceProbe<'static> {
+        SourceProbe {
+            source,
+            critical,
+            fetch: Box::pin(async move {
+                result.map(|d| d.to_string()).map_err(|e| anyhow::anyhow!(e))
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_critical_source_yields_nonzero_exit() {
+        let reports = run_probes(vec![
+            fake("lolesports", true, Ok("HTTP 200")),
+            fake("sx_bet", true, Err("connection refused")),
+            fake("hltv", false, Ok("ok")),
+        ], Duration::from_secs(1)).await;
+
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].ok);
+        assert!(!reports[1].ok);
+        assert_eq!(reports[1].detail, "connection refused");
+        assert_eq!(exit_code(&reports), 1);
+    }
+
+    #[tokio::test]
+    async fn optional_source_down_and_timeout_handling() {
+        let hanging = SourceProbe {
+            source:   "polygon_rpc",
+            critical: false,
+            fetch:    Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok("late".to_string())
+            }),
+        };
+        let reports = run_probes(vec![
+            fake("vlr.gg", true, Ok("HTTP 200")),
+            hanging,
+        ], Duration::from_millis(50)).await;
+
+        assert!(!reports[1].ok);
+        assert!(reports[1].detail.contains("timeout"));
+        assert_eq!(exit_code(&reports), 0);
+    }
+}
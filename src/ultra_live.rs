@@ -5,8 +5,8 @@
 
 use anyhow::Result;
 use dotenv::dotenv;
-use hltv_scraper::{HltvScraper, HltvLiveMatch};
-use prediction_engine::{PredictionEngine, MatchState, Prediction, match_state_from_hltv};
+use hltv_scraper::{probe_chrome_available, HltvScraper, HltvLiveMatch};
+use prediction_engine::{PredictionEngine, MatchState, Prediction, match_state_with_series};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::sync::Arc;
@@ -74,9 +74,9 @@ struct UltraLiveMonitor {
 }
 
 impl UltraLiveMonitor {
-    fn new() -> Self {
+    fn new(chrome_available: bool) -> Self {
         Self {
-            hltv_scraper: Arc::new(Mutex::new(HltvScraper::new())),
+            hltv_scraper: Arc::new(Mutex::new(HltvScraper::new().with_browser_available(chrome_available))),
             prediction_engine: Arc::new(Mutex::new(PredictionEngine::new())),
             sniper_sessions: Arc::new(Mutex::new(HashMap::new())),
             previous_live_ids: Arc::new(Mutex::new(HashSet::new())),
@@ -136,14 +136,14 @@ impl UltraLiveMonitor {
         for &match_id in &current_live_ids {
             if let Ok(Some(match_data)) = scraper.fetch_match_details(match_id).await {
                 // Vytvoř match state pro predikci
-                let state = match_state_from_hltv(
+                let state = match_state_with_series(
                     "cs2",
                     &match_data.team1,
                     &match_data.team2,
                     match_data.score1,
                     match_data.score2,
                     1, // map_number (prozatím 1)
-                    3, // total_maps (prozatím Bo3)
+                    None, // total_maps — HLTV detail formát nenese
                     match_data.is_live,
                 );
                 
@@ -262,7 +262,7 @@ async fn main() -> Result<()> {
     info!("Target latency: <15s (vs. 60-120s original)");
     info!("Sniper mode: 2s interval při high confidence");
     
-    let monitor = UltraLiveMonitor::new();
+    let monitor = UltraLiveMonitor::new(probe_chrome_available().await);
     monitor.run().await?;
     
     Ok(())
@@ -272,7 +272,7 @@ async fn main() -> Result<()> {
 async fn test_hltv_fetch() -> Result<()> {
     info!("🧪 Testing HLTV scraper...");
     
-    let mut scraper = HltvScraper::new();
+    let mut scraper = HltvScraper::new().with_browser_available(probe_chrome_available().await);
     
     match scraper.fetch_live_matches().await {
         Ok(ids) => {
@@ -0,0 +1,88 @@
+use prediction_engine::{match_state_from_hltv, Prediction, PredictionEngine, Sport};
+use std::path::PathBuf;
+
+fn temp_stats_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustmisko_calibration_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("prediction_accuracy.json")
+}
+
+#[test]
+fn resolved_match_updates_and_persists_accuracy() {
+    let path = temp_stats_path("feedback");
+    let mut engine = PredictionEngine::with_stats_path(&path);
+    assert_eq!(engine.get_accuracy_stats(), (0, 0));
+
+    // 12:4 na CS2 mapě → Team1Win(0.95)
+    let state = match_state_from_hltv("cs2", "Alpha", "Beta", 12, 4, 1, 1, true);
+    let prediction = engine.predict(&state);
+    assert_eq!(prediction, Prediction::Team1Win(0.95));
+    engine.log_team_prediction("alpha_vs_beta", "Alpha", "Beta", prediction);
+    engine.log_prediction("gamma_vs_delta", Prediction::Team2Win(0.9));
+    assert_eq!(engine.pending_matches(), 2);
+
+    assert_eq!(engine.prediction_teams("alpha_vs_beta"), Some(("Alpha", "Beta")));
+    assert_eq!(engine.record_outcome("alpha_vs_beta", "team1"), Some(true));
+    assert_eq!(engine.record_outcome("gamma_vs_delta", "team1"), Some(false));
+    assert_eq!(engine.record_outcome("unknown_match", "team1"), None);
+
+    assert_eq!(engine.get_accuracy_stats(), (1, 2));
+    assert_eq!(engine.pending_matches(), 0);
+    assert_eq!(engine.accuracy().accuracy(), Some(0.5));
+    engine.save_stats().expect("stats saved");
+
+    // Restart: accuracy se načte ze souboru
+    let restarted = PredictionEngine::with_stats_path(&path);
+    assert_eq!(restarted.get_accuracy_stats(), (1, 2));
+    assert_eq!(restarted.accuracy(), engine.accuracy());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn prediction_teams_are_kept_until_outcome() {
+    let mut engine = PredictionEngine::new();
+    engine.log_prediction("a_vs_b", Prediction::Team1Win(0.8));
+    assert_eq!(engine.prediction_teams("a_vs_b"), None);
+
+    engine.log_team_prediction("c_vs_d", "Gamma", "Delta", Prediction::Team2Win(0.8));
+    assert_eq!(engine.prediction_teams("c_vs_d"), Some(("Gamma", "Delta")));
+    assert_eq!(engine.record_outcome("c_vs_d", "team2"), Some(true));
+    assert_eq!(engine.prediction_teams("c_vs_d"), None);
+}
+
+#[test]
+fn calibration_is_ignored_below_min_sample_count() {
+    let mut engine = PredictionEngine::new().with_min_calibration_samples(3);
+    engine.log_prediction("a_vs_b", Prediction::Team1Win(0.8));
+    engine.log_prediction("c_vs_d", Prediction::Team1Win(0.8));
+    assert_eq!(engine.record_sport_outcome(&Sport::Cs2, "a_vs_b", "team1"), Some(true));
+    assert_eq!(engine.record_sport_outcome(&Sport::Cs2, "c_vs_d", "team1"), Some(true));
+    assert_eq!(engine.calibration_sample_count(&Sport::Cs2), 2);
+    assert_eq!(engine.calibration_sample_count(&Sport::Valorant), 0);
+
+    // 2 trefy ze 2 by confidence nafoukly — pod prahem platí surová heuristika
+    let state = match_state_from_hltv("cs2", "Alpha", "Beta", 12, 4, 1, 1, true);
+    assert_eq!(engine.predict(&state), Prediction::Team1Win(0.95));
+}
+
+#[test]
+fn calibration_applies_once_sport_reaches_min_sample_count() {
+    let mut engine = PredictionEngine::new().with_min_calibration_samples(3);
+    for (match_id, winner) in [("a_vs_b", "team1"), ("c_vs_d", "team1"), ("e_vs_f", "team2")] {
+        engine.log_prediction(match_id, Prediction::Team1Win(0.8));
+        engine.record_sport_outcome(&Sport::Cs2, match_id, winner);
+    }
+    assert_eq!(engine.calibration_sample_count(&Sport::Cs2), 3);
+
+    // accuracy 2/3 vs avg confidence 0.8 → faktor 0.8333
+    let state = match_state_from_hltv("cs2", "Alpha", "Beta", 12, 4, 1, 1, true);
+    let conf = engine.predict(&state).confidence().unwrap();
+    assert!((conf - 0.95 * (2.0 / 2.4)).abs() < 1e-4, "calibrated confidence {}", conf);
+
+    // Jistý výsledek a jiný sport bez vzorku zůstávají beze změny
+    let won = match_state_from_hltv("cs2", "Alpha", "Beta", 13, 4, 1, 1, true);
+    assert_eq!(engine.predict(&won), Prediction::Team1Win(1.0));
+    let valorant = match_state_from_hltv("valorant", "Alpha", "Beta", 12, 4, 1, 1, true);
+    assert_eq!(engine.predict(&valorant), Prediction::Team1Win(0.98));
+}